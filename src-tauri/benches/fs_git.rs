@@ -0,0 +1,75 @@
+//! Benchmarks for tree reads, status parsing, search and diff parsing.
+//!
+//! By default they run on a generated project. Set `LEO_BENCH_ROOT` to a
+//! checkout to measure the tree and search steps on a real repository:
+//!
+//!     LEO_BENCH_ROOT=~/src/big-repo cargo bench --bench fs_git
+
+use app_lib::modules::profile;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::fmt::Write;
+use std::path::PathBuf;
+
+fn generated_project() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    for d in 0..20 {
+        let sub = dir.path().join(format!("src/mod_{d}"));
+        std::fs::create_dir_all(&sub).unwrap();
+        for f in 0..50 {
+            let mut text = String::new();
+            for line in 0..200 {
+                let _ = writeln!(text, "fn f_{line}() {{ let x = {line}; }} // {}", if line % 50 == 0 { "TODO" } else { "ok" });
+            }
+            std::fs::write(sub.join(format!("file_{f}.rs")), text).unwrap();
+        }
+    }
+    dir
+}
+
+fn porcelain(entries: usize) -> Vec<u8> {
+    let mut out = Vec::new();
+    for i in 0..entries {
+        let status: &[u8] = match i % 4 {
+            0 => b" M",
+            1 => b"??",
+            2 => b"A ",
+            _ => b"R ",
+        };
+        out.extend_from_slice(status);
+        out.extend_from_slice(format!(" src/dir_{}/file_{i}.rs\0", i % 37).as_bytes());
+        if i % 4 == 3 {
+            out.extend_from_slice(format!("src/old/file_{i}.rs\0").as_bytes());
+        }
+    }
+    out
+}
+
+fn unified_diff(files: usize, hunks: usize) -> String {
+    let mut out = String::new();
+    for f in 0..files {
+        let _ = writeln!(out, "diff --git a/src/f{f}.rs b/src/f{f}.rs\n--- a/src/f{f}.rs\n+++ b/src/f{f}.rs");
+        for h in 0..hunks {
+            let start = h * 20 + 1;
+            let _ = writeln!(out, "@@ -{start},7 +{start},8 @@ fn f{h}()");
+            out.push_str(" context\n context\n-old line\n+new line\n+added line\n context\n context\n context\n");
+        }
+    }
+    out
+}
+
+fn benches(c: &mut Criterion) {
+    let generated = generated_project();
+    let root = std::env::var_os("LEO_BENCH_ROOT").map(PathBuf::from).unwrap_or_else(|| generated.path().to_path_buf());
+
+    c.bench_function("tree: read", |b| b.iter(|| profile::tree(black_box(&root), 50).unwrap()));
+    c.bench_function("search: TODO", |b| b.iter(|| profile::search(black_box(&root), "TODO").unwrap()));
+
+    let status = porcelain(10_000);
+    c.bench_function("status: parse 10k entries", |b| b.iter(|| profile::parse_status(black_box(&status))));
+
+    let diff = unified_diff(50, 40);
+    c.bench_function("diff: parse 50 files", |b| b.iter(|| profile::parse_diff(black_box(&diff))));
+}
+
+criterion_group!(fs_git, benches);
+criterion_main!(fs_git);
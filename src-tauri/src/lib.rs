@@ -1,6 +1,6 @@
 pub mod modules;
 
-use modules::{ai, fs, git, graph, knowledge, log as app_log, menu, session, shell, symbols, window_mgr};
+use modules::{a11y, ai, code_image, color, config_tools, data, diagram, emmet, events, fs, git, graph, i18n, issues, knowledge, log as app_log, menu, net, operations, policy, preview_server, profile, release, scaffold, search, session, share_server, shell, stats, symbols, sync, watchdog, window_mgr};
 use std::sync::Arc;
 use tauri::Manager;
 
@@ -14,10 +14,15 @@ pub fn run() {
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_drag::init())
+        .register_asynchronous_uri_scheme_protocol(fs::media::SCHEME, fs::media::handle)
         .manage(terminal_state)
         .manage(project_root_state)
         .manage(Arc::new(ai::AiState::new()))
         .manage(Arc::new(knowledge::KnowledgeState::new()))
+        .manage(preview_server::PreviewServerState::new())
+        .manage(share_server::ShareServerState::new())
+        .manage(fs::index::FileIndexState::new())
+        .manage(git::ignored::IgnoredWatchState::new())
         .manage(app_log::LogState::new())
         .manage(session::AppStateHandle(std::sync::Mutex::new(
             session::AppState::default(),
@@ -25,33 +30,82 @@ pub fn run() {
         .manage(window_mgr::InitialProjectState(std::sync::Mutex::new(
             std::collections::HashMap::new(),
         )))
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(watchdog::wrap(policy::wrap(tauri::generate_handler![
             // Logging
             app_log::log_record,
+            watchdog::get_slow_command_report,
             // File system
             fs::set_project_root,
             fs::read_dir_tree,
+            fs::read_dir_page,
             fs::read_file_content,
             fs::write_file_content,
+            fs::write_files_batch,
+            fs::history::list_file_history,
+            fs::history::read_file_version,
+            fs::history::restore_file_version,
+            fs::get_file_version,
             fs::read_file_binary,
+            fs::read_file_hex,
+            fs::read_file_range,
+            fs::is_binary_file,
+            fs::preview::preview_file,
+            fs::filetype::detect_file_type,
+            fs::routing::get_editor_routing,
+            fs::routing::set_editor_routing,
+            fs::checksum::hash_file,
+            fs::encoding::audit_encodings,
+            fs::encoding::fix_encodings,
+            fs::thumbnail::get_thumbnail,
             fs::get_home_dir,
             fs::create_project_dir,
             fs::create_file,
             fs::create_folder,
             fs::delete_entries,
+            fs::delete::delete_entries_permanent,
+            fs::delete::cancel_delete_entries,
             fs::rename_entry,
             fs::move_entries,
             fs::import_external_files,
+            fs::export_file,
             fs::paste_entries,
             fs::duplicate_entry,
+            fs::archive::compress_entries,
+            fs::backup::get_backup_settings,
+            fs::backup::configure_backups,
+            fs::backup::run_backup_now,
+            fs::backup::list_backups,
+            fs::backup::restore_backup,
+            fs::journal::undo_last_fs_operation,
+            fs::workspace_edit::apply_workspace_edit,
             fs::reveal_in_file_manager,
+            fs::open_externally,
             fs::list_all_files,
+            fs::recently_modified_files,
+            fs::fuzzy::fuzzy_find_files,
+            fs::exclude::get_exclude_patterns,
+            fs::exclude::set_exclude_patterns,
+            fs::permissions::get_file_permissions,
+            fs::permissions::set_file_permissions,
+            fs::complete::complete_path,
+            fs::chunked::read_file_chunked,
+            fs::chunked::cancel_read_file_chunked,
+            fs::chunked::read_file_window,
+            fs::tail::tail_file,
+            fs::tail::pause_tail,
+            fs::tail::resume_tail,
+            fs::tail::stop_tail,
+            fs::generated::is_generated_file,
+            fs::generated::get_generated_dirs,
+            fs::generated::set_generated_dirs,
             // Git
             git::get_git_status,
             git::get_git_remote_status,
             git::get_git_ignored,
+            git::ignored::watch_git_ignored,
             git::get_git_branch,
             git::git_diff,
+            git::diff::diff_strings,
             git::git_stage,
             git::git_unstage,
             git::git_discard,
@@ -66,18 +120,45 @@ pub fn run() {
             git::git_log,
             git::git_list_branches,
             git::git_checkout_branch,
+            git::merge::git_merge,
+            git::rebase::git_rebase_plan,
+            git::rebase::git_rebase_start,
+            git::rebase::git_rebase_continue,
+            git::rebase::git_rebase_skip,
+            git::rebase::git_rebase_abort,
             git::git_resolve_conflict,
+            git::git_conflict_versions,
             git::git_create_checkpoint,
             git::git_restore_checkpoint,
             git::git_list_checkpoints,
             git::git_clone,
             git::find_git_repos,
+            git::branch_policy::get_branch_policy,
+            git::branch_policy::set_branch_policy,
+            git::branch_policy::git_create_branch_from_template,
+            git::owners::resolve_code_owners,
+            git::changelog::generate_changelog,
+            git::share::export_shareable_diff,
+            git::share::get_diff_share_settings,
+            git::share::set_diff_share_settings,
+            git::stats::git_repo_stats,
+            git::stats::git_gc,
+            git::bisect::git_bisect_start,
+            git::bisect::git_bisect_mark,
+            git::bisect::git_bisect_status,
+            git::bisect::git_bisect_reset,
             // Shell
             shell::spawn_terminal,
             shell::write_terminal,
             shell::kill_terminal,
             shell::resize_terminal,
             shell::run_command_capture,
+            shell::get_terminal_activity_config,
+            shell::set_terminal_activity_config,
+            shell::get_terminal_scrollback_config,
+            shell::set_terminal_scrollback_config,
+            shell::read_terminal_scrollback,
+            shell::get_terminal_memory_usage,
             // AI
             ai::set_api_key,
             ai::set_provider_key,
@@ -85,12 +166,106 @@ pub fn run() {
             ai::ai_chat,
             ai::ai_chat_stream,
             ai::ai_chat_cancel,
+            ai::ai_clear_cache,
+            ai::manager::list_active_ai_requests,
+            ai::manager::cancel_ai_request,
+            ai::assist::ai_document_symbol,
+            ai::assist::ai_resolve_conflict,
+            ai::assist::ai_review_changes,
+            ai::assist::ai_quick_action,
+            ai::agent::ai_run_agent,
+            ai::agent::ai_agent_respond,
+            // Colors
+            color::parse_colors,
+            color::convert_color,
+            // Diagrams
+            diagram::render_diagram,
+            diagram::get_diagram_settings,
+            diagram::set_diagram_settings,
+            // Code images
+            code_image::render_code_image,
+            // Emmet
+            emmet::expand_abbreviation,
+            // Config files
+            config_tools::validate_config,
+            config_tools::format_config,
+            config_tools::tooling::discover_tooling,
+            // Data previews
+            data::preview_table,
+            data::preview_parquet,
+            data::notebook::read_notebook,
+            data::notebook::write_notebook,
+            data::proto::decode_with_schema,
+            // Preview server
+            preview_server::start_preview,
+            preview_server::stop_preview,
+            preview_server::get_preview,
+            // Read-only sharing
+            share_server::share_readonly,
+            share_server::stop_share_readonly,
+            share_server::get_share_readonly,
+            // Issues
+            issues::get_issue_tracker,
+            issues::set_issue_tracker,
+            issues::list_assigned_issues,
+            issues::get_issue,
+            issues::issue_work_refs,
+            issues::todos::scan_todos,
+            issues::todos::create_issue_from_todo,
+            // Profiling
+            profile::profile_operation,
+            // Release
+            release::bump_version,
+            // Scaffolding
+            scaffold::list_project_templates,
+            scaffold::create_project,
+            scaffold::generators::list_code_generators,
+            scaffold::generators::generate_code,
+            scaffold::gitignore::suggest_gitignore,
+            scaffold::gitignore::write_gitignore,
+            // Search
+            search::search_in_project,
+            search::replace_in_project,
+            search::playground::test_regex,
+            search::structural::structural_search,
+            search::structural::structural_replace,
+            search::duplicates::find_duplicates,
+            // Project stats
+            stats::project_stats,
+            // Network
+            net::get_network_settings,
+            net::set_network_settings,
+            net::test_network,
+            net::set_offline,
+            net::get_offline_status,
+            net::detect_connectivity,
+            // Command policy
+            policy::get_command_policy,
             // Session
             session::get_recent_projects,
             session::save_session,
             session::remove_recent_project,
+            session::get_project_groups,
+            session::create_project_group,
+            session::delete_project_group,
+            session::assign_project_to_group,
+            session::set_pinned,
+            session::handoff::export_session_bundle,
+            session::handoff::import_session_bundle,
+            session::storage::get_state_storage_report,
+            session::storage::set_session_policy,
+            // Accessibility
+            a11y::summarize_diff,
+            a11y::describe_git_graph,
+            a11y::describe_tree_changes,
             // Graph
             graph::analyze_file_graph,
+            graph::deps::build_import_graph,
+            graph::deps::export_import_graph,
+            graph::unreferenced::find_unreferenced_files,
+            // Localization
+            i18n::set_locale,
+            i18n::get_locale,
             // Knowledge
             knowledge::knowledge_init,
             knowledge::knowledge_index,
@@ -108,15 +283,32 @@ pub fn run() {
             knowledge::knowledge_admin_load_conversation,
             knowledge::knowledge_admin_delete_conversation,
             knowledge::knowledge_admin_delete_conversations,
+            knowledge::semantic::semantic_status,
+            knowledge::semantic::semantic_set_enabled,
+            knowledge::semantic::semantic_index,
+            knowledge::semantic::semantic_reindex_paths,
+            knowledge::semantic::semantic_search,
+            knowledge::semantic::preview_retrieved_context,
             // Symbols
             symbols::symbols_extract,
             symbols::symbols_get_body,
+            symbols::imports::suggest_import,
+            symbols::organize::organize_imports,
+            // Settings sync
+            sync::get_sync_target,
+            sync::set_sync_target,
+            sync::sync_now,
+            // Background operations
+            operations::list_operations,
+            operations::cancel_operation,
+            // IPC contract
+            events::ipc_version,
             // Window management
             window_mgr::open_new_window,
             window_mgr::open_folder_in_new_window,
             window_mgr::close_focused_window,
             window_mgr::get_initial_project,
-        ])
+        ])))
         .setup(|app| {
             if cfg!(debug_assertions) {
                 app.handle().plugin(
@@ -129,6 +321,7 @@ pub fn run() {
             // into the encrypted store. No-op when there's nothing to
             // migrate. Set LEO_DISABLE_KEY_MIGRATION=1 to skip.
             ai::migrate_plaintext_keys();
+            operations::init(app.handle().clone());
             let loaded = session::load_state_from_disk(app.handle()).unwrap_or_default();
             let handle = app.state::<session::AppStateHandle>();
             let mut guard = handle
@@ -161,6 +354,7 @@ pub fn run() {
                     let mut map = state.blocking_write();
                     map.remove(&label);
                 }
+                fs::journal::clear(&label);
                 // Kill terminals for this window
                 if let Some(state) = window.try_state::<shell::TerminalState>() {
                     if let Ok(mut managers) = state.lock() {
@@ -173,6 +367,22 @@ pub fn run() {
                 if let Some(state) = window.try_state::<Arc<knowledge::KnowledgeState>>() {
                     state.remove_window(&label);
                 }
+                // Stop this window's preview server
+                if let Some(state) = window.try_state::<preview_server::PreviewServerState>() {
+                    state.remove_window(&label);
+                }
+                // Stop sharing this window's project
+                if let Some(state) = window.try_state::<share_server::ShareServerState>() {
+                    state.remove_window(&label);
+                }
+                // Stop watching this window's project for the file index
+                if let Some(state) = window.try_state::<fs::index::FileIndexState>() {
+                    state.remove_window(&label);
+                }
+                // Stop watching this window's ignore files
+                if let Some(state) = window.try_state::<git::ignored::IgnoredWatchState>() {
+                    state.remove_window(&label);
+                }
                 // Remove any unclaimed initial project entry
                 if let Some(state) = window.try_state::<window_mgr::InitialProjectState>() {
                     if let Ok(mut map) = state.0.lock() {
@@ -0,0 +1,343 @@
+//! Plain-text descriptions for screen readers.
+//!
+//! The diff, history and file tree views are visual: colored gutters, a
+//! commit graph drawn in ASCII, rows that appear and vanish. The functions
+//! here turn the data behind those views into short sentences the
+//! frontend can put in an `aria-live` region or an `aria-label`. They take
+//! what the views already fetched, so no git command runs twice.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use crate::modules::git::{DiffLine, GitGraphRow};
+
+/// How many names a sentence lists before "and N more".
+const MAX_NAMES: usize = 3;
+
+fn count(n: usize, one: &str, many: &str) -> String {
+    format!("{} {}", n, if n == 1 { one } else { many })
+}
+
+/// "a", "a and b", "a, b and c", "a, b, c and 2 more".
+fn list(names: &[String]) -> String {
+    let shown = &names[..names.len().min(MAX_NAMES)];
+    let rest = names.len() - shown.len();
+    match (shown, rest) {
+        ([], _) => String::new(),
+        ([only], 0) => only.clone(),
+        (_, 0) => format!("{} and {}", shown[..shown.len() - 1].join(", "), shown[shown.len() - 1]),
+        (_, rest) => format!("{} and {} more", shown.join(", "), rest),
+    }
+}
+
+fn span(first: u32, last: u32) -> String {
+    if first == last {
+        format!("line {}", first)
+    } else {
+        format!("lines {} to {}", first, last)
+    }
+}
+
+// ── Diffs ──
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct DiffSummary {
+    /// One sentence for the whole file.
+    pub summary: String,
+    /// One sentence per hunk, in order.
+    pub hunks: Vec<String>,
+    pub added: usize,
+    pub removed: usize,
+}
+
+#[derive(Default)]
+struct HunkCount {
+    added: usize,
+    removed: usize,
+    new_lines: Option<(u32, u32)>,
+    old_lines: Option<(u32, u32)>,
+}
+
+fn widen(range: &mut Option<(u32, u32)>, n: Option<u32>) {
+    if let Some(n) = n {
+        *range = Some(match *range {
+            Some((a, b)) => (a.min(n), b.max(n)),
+            None => (n, n),
+        });
+    }
+}
+
+impl HunkCount {
+    fn describe(&self) -> String {
+        match (self.added, self.removed, self.new_lines, self.old_lines) {
+            (a, r, Some((first, last)), _) if a > 0 && r > 0 => {
+                format!("{} changed: {} added, {} removed.", capitalize(&span(first, last)), a, r)
+            }
+            (a, _, Some((first, last)), _) if a > 0 => {
+                format!("{} added at {}.", count(a, "line", "lines"), span(first, last))
+            }
+            (_, r, _, Some((first, last))) => {
+                format!("{} removed from original {}.", count(r, "line", "lines"), span(first, last))
+            }
+            _ => "No line changes.".to_string(),
+        }
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Describe the lines `git_diff` returned for `file`.
+pub fn describe_diff(file: &str, lines: &[DiffLine]) -> DiffSummary {
+    let mut hunks: Vec<HunkCount> = Vec::new();
+    for line in lines {
+        let is_header = line.kind == "ctx" && line.old_num.is_none() && line.new_num.is_none() && line.text.starts_with("@@");
+        if is_header || hunks.is_empty() {
+            hunks.push(HunkCount::default());
+            if is_header {
+                continue;
+            }
+        }
+        let hunk = hunks.last_mut().expect("pushed above");
+        match line.kind.as_str() {
+            "add" => {
+                hunk.added += 1;
+                widen(&mut hunk.new_lines, line.new_num);
+            }
+            "del" => {
+                hunk.removed += 1;
+                widen(&mut hunk.old_lines, line.old_num);
+            }
+            _ => {}
+        }
+    }
+    hunks.retain(|h| h.added + h.removed > 0);
+
+    let added: usize = hunks.iter().map(|h| h.added).sum();
+    let removed: usize = hunks.iter().map(|h| h.removed).sum();
+    let summary = match (added, removed) {
+        (0, 0) => format!("No changes in {}.", file),
+        (a, 0) => format!("{}: {} added in {}.", file, count(a, "line", "lines"), count(hunks.len(), "place", "places")),
+        (0, r) => format!("{}: {} removed in {}.", file, count(r, "line", "lines"), count(hunks.len(), "place", "places")),
+        (a, r) => format!(
+            "{}: {} added and {} removed in {}.",
+            file,
+            count(a, "line", "lines"),
+            r,
+            count(hunks.len(), "place", "places")
+        ),
+    };
+    DiffSummary { summary, hunks: hunks.iter().map(HunkCount::describe).collect(), added, removed }
+}
+
+// ── Commit graph ──
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct GraphSummary {
+    pub summary: String,
+    /// One sentence per commit, newest first.
+    pub commits: Vec<String>,
+}
+
+/// Describe the rows `git_log` returned. `git log --graph` draws each
+/// branch line two columns wide, so a commit's lane is the column of its
+/// `*` halved; a merge is a commit whose next row opens a `\` beside it.
+pub fn describe_graph(rows: &[GitGraphRow]) -> GraphSummary {
+    let mut commits = Vec::new();
+    let mut authors = HashSet::new();
+    let mut merges = 0;
+    let mut lanes = 1;
+    for (i, row) in rows.iter().enumerate() {
+        lanes = lanes.max(row.graph.trim_end().len().div_ceil(2));
+        let Some(commit) = &row.commit else { continue };
+        let star = row.graph.find('*').unwrap_or(0);
+        let is_merge = rows
+            .get(i + 1)
+            .filter(|next| next.commit.is_none())
+            .is_some_and(|next| next.graph.as_bytes().get(star + 1) == Some(&b'\\'));
+        authors.insert(commit.author.as_str());
+
+        let mut sentence = format!("{}: {}, by {}, {}.", commit.short_hash, commit.message, commit.author, commit.date);
+        if is_merge {
+            merges += 1;
+            sentence.push_str(" Merge commit.");
+        }
+        if star >= 2 {
+            sentence.push_str(&format!(" On side branch {}.", star / 2 + 1));
+        }
+        commits.push(sentence);
+    }
+
+    let summary = if commits.is_empty() {
+        "No commits.".to_string()
+    } else {
+        let mut s = format!(
+            "{} by {}",
+            count(commits.len(), "commit", "commits"),
+            count(authors.len(), "author", "authors")
+        );
+        if merges > 0 {
+            s.push_str(&format!(", including {}", count(merges, "merge", "merges")));
+        }
+        s.push('.');
+        if lanes > 1 {
+            s.push_str(&format!(" Up to {} branches run side by side.", lanes));
+        }
+        s
+    };
+    GraphSummary { summary, commits }
+}
+
+// ── File tree ──
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct TreeChange {
+    /// `created`, `removed` or `modified`.
+    pub kind: String,
+    pub path: String,
+    #[serde(default)]
+    pub is_dir: bool,
+}
+
+/// Narrate a batch of file tree changes, e.g. "Created 2 files in src:
+/// a.rs and b.rs. Removed 1 folder: dist." Paths are shown relative to
+/// `root` when they are inside it.
+pub fn narrate_tree_changes(root: Option<&str>, changes: &[TreeChange]) -> String {
+    // kind -> (is_dir -> parent -> names), in a fixed sentence order.
+    let mut groups: BTreeMap<(usize, bool), BTreeMap<String, Vec<String>>> = BTreeMap::new();
+    let mut seen = HashSet::new();
+    for change in changes {
+        let order = match change.kind.as_str() {
+            "created" => 0,
+            "removed" => 1,
+            "modified" => 2,
+            _ => continue,
+        };
+        if !seen.insert((order, change.path.as_str())) {
+            continue;
+        }
+        let path = Path::new(&change.path);
+        let rel = root.and_then(|r| path.strip_prefix(r).ok()).unwrap_or(path);
+        let name = rel.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_else(|| rel.display().to_string());
+        let parent = rel.parent().map(|p| p.to_string_lossy().into_owned()).unwrap_or_default();
+        groups.entry((order, change.is_dir)).or_default().entry(parent).or_default().push(name);
+    }
+
+    let mut sentences = Vec::new();
+    for ((order, is_dir), by_parent) in groups {
+        let verb = ["Created", "Removed", "Modified"][order];
+        let (one, many) = if is_dir { ("folder", "folders") } else { ("file", "files") };
+        let names: Vec<String> = by_parent.values().flatten().cloned().collect();
+        let place = match by_parent.keys().collect::<Vec<_>>().as_slice() {
+            [parent] if !parent.is_empty() => format!(" in {}", parent),
+            [_] => String::new(),
+            parents => format!(" in {}", count(parents.len(), "folder", "folders")),
+        };
+        sentences.push(format!("{} {}{}: {}.", verb, count(names.len(), one, many), place, list(&names)));
+    }
+    if sentences.is_empty() {
+        "No changes.".to_string()
+    } else {
+        sentences.join(" ")
+    }
+}
+
+// ── Commands ──
+
+#[tauri::command]
+pub fn summarize_diff(file_path: String, lines: Vec<DiffLine>) -> DiffSummary {
+    describe_diff(&file_path, &lines)
+}
+
+#[tauri::command]
+pub fn describe_git_graph(rows: Vec<GitGraphRow>) -> GraphSummary {
+    describe_graph(&rows)
+}
+
+#[tauri::command]
+pub fn describe_tree_changes(root: Option<String>, changes: Vec<TreeChange>) -> String {
+    narrate_tree_changes(root.as_deref(), &changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::git::{parse_unified_diff, GitLogCommit};
+
+    #[test]
+    fn describes_diffs() {
+        let diff = "\
+diff --git a/main.rs b/main.rs
+--- a/main.rs
++++ b/main.rs
+@@ -1,3 +1,4 @@
+ fn main() {
+-    println!(\"hi\");
++    let name = \"world\";
++    println!(\"hello {name}\");
+ }
+@@ -20,2 +21,1 @@
+ // end
+-// old
+";
+        let summary = describe_diff("main.rs", &parse_unified_diff(diff));
+        assert_eq!(summary.summary, "main.rs: 2 lines added and 2 removed in 2 places.");
+        assert_eq!(summary.hunks, ["Lines 2 to 3 changed: 2 added, 1 removed.", "1 line removed from original line 21."]);
+        assert_eq!(describe_diff("x", &[]).summary, "No changes in x.");
+    }
+
+    fn row(graph: &str, commit: Option<(&str, &str, &str)>) -> GitGraphRow {
+        GitGraphRow {
+            graph: graph.to_string(),
+            commit: commit.map(|(hash, author, message)| GitLogCommit {
+                hash: hash.repeat(8),
+                short_hash: hash.to_string(),
+                author: author.to_string(),
+                date: "2 days ago".to_string(),
+                message: message.to_string(),
+            }),
+        }
+    }
+
+    #[test]
+    fn describes_the_commit_graph() {
+        let rows = vec![
+            row("*", Some(("a1", "Ana", "Merge branch 'fix'"))),
+            row("|\\", None),
+            row("| *", Some(("b2", "Bo", "Fix crash"))),
+            row("|/", None),
+            row("*", Some(("c3", "Ana", "Initial commit"))),
+        ];
+        let summary = describe_graph(&rows);
+        assert_eq!(summary.summary, "3 commits by 2 authors, including 1 merge. Up to 2 branches run side by side.");
+        assert_eq!(summary.commits[0], "a1: Merge branch 'fix', by Ana, 2 days ago. Merge commit.");
+        assert_eq!(summary.commits[1], "b2: Fix crash, by Bo, 2 days ago. On side branch 2.");
+        assert_eq!(describe_graph(&[]).summary, "No commits.");
+    }
+
+    #[test]
+    fn narrates_tree_changes() {
+        let change = |kind: &str, path: &str, is_dir| TreeChange { kind: kind.into(), path: path.into(), is_dir };
+        let changes = [
+            change("created", "/p/src/a.rs", false),
+            change("created", "/p/src/b.rs", false),
+            change("created", "/p/src/b.rs", false),
+            change("removed", "/p/dist", true),
+            change("modified", "/p/src/c.rs", false),
+            change("modified", "/p/lib/d.rs", false),
+            change("accessed", "/p/e.rs", false),
+        ];
+        assert_eq!(
+            narrate_tree_changes(Some("/p"), &changes),
+            "Created 2 files in src: a.rs and b.rs. Removed 1 folder: dist. Modified 2 files in 2 folders: d.rs and c.rs."
+        );
+        assert_eq!(list(&["a", "b", "c", "d", "e"].map(String::from)), "a, b, c and 2 more");
+        assert_eq!(narrate_tree_changes(None, &[]), "No changes.");
+    }
+}
@@ -0,0 +1,657 @@
+//! Workspace agent: reach a goal by editing, building and testing.
+//!
+//! `ai_run_agent` hands the model a goal and lets it act one step at a
+//! time. Each reply is a single tagged action: read a file, rewrite one,
+//! run the project's check command (its build or tests), or finish.
+//! Rewrites go through `WorkspaceEdit`: the diff goes out in an
+//! `agent-step` event and nothing is written until `ai_agent_respond`
+//! approves it, after which the edit is in the undo journal like any
+//! other. A failing check is parsed into file/line failures and fed back
+//! with the end of its output, so the model can read what broke and try
+//! again.
+//!
+//! A run ends when the model says it's done, after `max_steps` actions or
+//! after `max_seconds`, whichever comes first. It runs as an operation,
+//! so it can be cancelled from the background tasks popover; a cancelled
+//! or timed-out run stops at once, killing a check that is still running
+//! along with everything it started.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::AppHandle;
+use tokio::sync::oneshot;
+use tokio::time::Instant;
+use ts_rs::TS;
+
+use super::assist::extract_tag;
+use crate::modules::events;
+use crate::modules::fs::workspace_edit::{apply_edit, FileOperation, WorkspaceEdit};
+use crate::modules::fs::generated::GeneratedDirs;
+use crate::modules::fs::{project_root_async, resolve_within_root, ProjectRootState};
+use crate::modules::operations::{self, Operation, OperationKind};
+use crate::modules::search;
+
+const DEFAULT_STEPS: u32 = 15;
+const MAX_STEPS: u32 = 50;
+const DEFAULT_SECONDS: u64 = 600;
+const MAX_SECONDS: u64 = 3600;
+/// File text shown to the model per read.
+const MAX_FILE_CHARS: usize = 12_000;
+/// Check output shown to the model, from the end.
+const OUTPUT_TAIL_LINES: usize = 60;
+const MAX_FAILURES: usize = 20;
+/// Earlier steps are dropped from the prompt past this size.
+const MAX_TRANSCRIPT_CHARS: usize = 60_000;
+const MAX_LISTED_FILES: usize = 300;
+
+const SYSTEM_PROMPT: &str = "You are a coding agent working in a software project to reach a goal. \
+    Reply with exactly one action per message, in tags:\n\
+    <action>read</action><path>relative/path</path> to see a file;\n\
+    <action>edit</action><path>relative/path</path><content>the complete new file</content><summary>why</summary> \
+    to create or rewrite a file (the user approves each edit);\n\
+    <action>check</action> to build and test the project with its check command;\n\
+    <action>done</action><summary>what changed and whether the check passes</summary> to finish.\n\
+    Read the files you need before editing them. After editing, run the check and fix what it reports. \
+    Finish as soon as the check passes and the goal is met.";
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentStepKind {
+    Read,
+    Edit,
+    Check,
+    Done,
+    /// A reply that wasn't a usable action.
+    Invalid,
+}
+
+/// A file/line problem picked out of check output.
+#[derive(Serialize, Clone, Debug, PartialEq, TS)]
+pub struct CheckFailure {
+    pub path: String,
+    pub line: u32,
+    pub column: Option<u32>,
+    pub message: String,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, TS)]
+pub struct CheckResult {
+    pub command: String,
+    /// `None` when killed by a signal.
+    pub exit_code: Option<i32>,
+    pub passed: bool,
+    pub failures: Vec<CheckFailure>,
+    /// The last lines of stdout and stderr.
+    pub output_tail: String,
+}
+
+/// Payload of `agent-step`. Sent when a step starts and again when it
+/// ends; an edit is sent in between with `awaiting_approval` set.
+#[derive(Serialize, Clone, Debug, TS)]
+pub struct AgentStep {
+    #[ts(type = "number")]
+    pub run_id: u64,
+    /// 1-based.
+    pub step: u32,
+    pub kind: AgentStepKind,
+    /// The model's reason for the step, or what went wrong with it.
+    pub summary: String,
+    pub path: Option<String>,
+    /// Unified diff of an edit.
+    pub diff: Option<String>,
+    /// The edit waits for `ai_agent_respond`.
+    pub awaiting_approval: bool,
+    /// Whether the edit was approved, once decided.
+    pub approved: Option<bool>,
+    pub check: Option<CheckResult>,
+    /// The step has finished.
+    pub finished: bool,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentOutcome {
+    /// The model finished.
+    Done,
+    StepBudget,
+    TimeBudget,
+    Cancelled,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct AgentRun {
+    pub run_id: u64,
+    pub outcome: AgentOutcome,
+    /// The model's closing summary, when it finished.
+    pub summary: String,
+    pub steps: u32,
+    pub edits_applied: u32,
+    pub last_check: Option<CheckResult>,
+}
+
+/// One parsed model reply.
+#[derive(Debug, PartialEq)]
+enum Action {
+    Read { path: String },
+    Edit { path: String, content: String, summary: String },
+    Check,
+    Done { summary: String },
+}
+
+fn parse_action(reply: &str) -> Result<Action, String> {
+    let tag = |name| extract_tag(reply, name).map(str::trim);
+    let path = || tag("path").filter(|p| !p.is_empty()).map(str::to_string).ok_or("The action has no <path>");
+    match tag("action") {
+        Some("read") => Ok(Action::Read { path: path()? }),
+        Some("edit") => Ok(Action::Edit {
+            path: path()?,
+            content: extract_tag(reply, "content").ok_or("The edit has no <content>")?.to_string(),
+            summary: tag("summary").unwrap_or("").to_string(),
+        }),
+        Some("check") => Ok(Action::Check),
+        Some("done") => Ok(Action::Done { summary: tag("summary").unwrap_or("").to_string() }),
+        Some(other) => Err(format!("Unknown action `{}`", other)),
+        None => Err("The reply has no <action>".to_string()),
+    }
+}
+
+// ── Checks ──
+
+/// The usual build-and-test command for the project at `root`.
+fn default_check(root: &Path) -> Option<&'static str> {
+    if root.join("Cargo.toml").is_file() {
+        Some("cargo test")
+    } else if root.join("package.json").is_file() {
+        Some("npm test")
+    } else if root.join("go.mod").is_file() {
+        Some("go test ./...")
+    } else if ["pyproject.toml", "pytest.ini", "setup.py"].iter().any(|f| root.join(f).is_file()) {
+        Some("python -m pytest")
+    } else {
+        None
+    }
+}
+
+fn number(s: &str) -> Option<u32> {
+    s.trim().parse().ok()
+}
+
+/// Compiler and test-runner problems in `output`: rustc's `--> path:l:c`
+/// under an `error` line, `path(l,c): message` (tsc), and the common
+/// `path:l:c: message` / `path:l: message` (gcc, clang, go, eslint, mypy).
+fn parse_failures(output: &str) -> Vec<CheckFailure> {
+    let mut failures: Vec<CheckFailure> = Vec::new();
+    let mut last_error: Option<&str> = None;
+    for line in output.lines() {
+        let trimmed = line.trim();
+        let failure = if let Some(message) = trimmed.strip_prefix("error").filter(|r| r.starts_with([':', '['])) {
+            last_error = Some(message.split_once(": ").map_or(message, |(_, m)| m));
+            None
+        } else if trimmed.starts_with("warning") {
+            last_error = None;
+            None
+        } else if let Some(location) = trimmed.strip_prefix("--> ") {
+            let mut parts = location.rsplitn(3, ':');
+            let (column, line_no, path) = (parts.next().and_then(number), parts.next().and_then(number), parts.next());
+            match (last_error.take(), path, line_no) {
+                (Some(message), Some(path), Some(line)) => {
+                    Some(CheckFailure { path: path.to_string(), line, column, message: message.to_string() })
+                }
+                _ => None,
+            }
+        } else if let Some((path, rest)) = trimmed.split_once('(').filter(|(p, _)| is_path(p)) {
+            rest.split_once("): ").and_then(|(pos, message)| {
+                let (l, c) = pos.split_once(',')?;
+                Some(CheckFailure { path: path.to_string(), line: number(l)?, column: number(c), message: message.to_string() })
+            })
+        } else {
+            let mut parts = trimmed.splitn(4, ':');
+            match (parts.next(), parts.next().and_then(number), parts.next(), parts.next()) {
+                (Some(path), Some(line), Some(col), Some(message)) if is_path(path) && number(col).is_some() => {
+                    Some(CheckFailure { path: path.to_string(), line, column: number(col), message: message.trim().to_string() })
+                }
+                (Some(path), Some(line), Some(message), None) if is_path(path) => {
+                    Some(CheckFailure { path: path.to_string(), line, column: None, message: message.trim().to_string() })
+                }
+                _ => None,
+            }
+        };
+        if let Some(f) = failure.filter(|f| !f.message.is_empty() && !failures.contains(f)) {
+            failures.push(f);
+            if failures.len() == MAX_FAILURES {
+                break;
+            }
+        }
+    }
+    failures
+}
+
+/// Looks like a relative or absolute source path: no spaces, has an
+/// extension.
+fn is_path(s: &str) -> bool {
+    !s.is_empty() && !s.contains(char::is_whitespace) && Path::new(s).extension().is_some()
+}
+
+fn output_tail(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    lines[lines.len().saturating_sub(OUTPUT_TAIL_LINES)..].join("\n")
+}
+
+/// `command` run by the platform's shell.
+fn shell(command: &str) -> tokio::process::Command {
+    #[cfg(windows)]
+    let mut cmd = {
+        let mut cmd = tokio::process::Command::new("cmd");
+        // Passed as typed: cmd does its own parsing of the quotes.
+        cmd.arg("/C").raw_arg(command);
+        cmd
+    };
+    #[cfg(not(windows))]
+    let mut cmd = {
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.args(["-c", command]);
+        // Its own group, so the test runners and servers it starts can
+        // be killed with it.
+        cmd.process_group(0);
+        cmd
+    };
+    cmd.stdin(std::process::Stdio::null()).stdout(std::process::Stdio::piped()).stderr(std::process::Stdio::piped());
+    cmd
+}
+
+/// Kills a running check and every process it started when dropped
+/// before `finish`, i.e. when the run is cancelled or times out.
+struct CheckProcess(Option<u32>);
+
+impl CheckProcess {
+    fn finish(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for CheckProcess {
+    fn drop(&mut self) {
+        let Some(pid) = self.0 else { return };
+        // SAFETY: `kill` has no memory-safety preconditions; a negative
+        // pid signals the group `shell` put the check in.
+        #[cfg(unix)]
+        unsafe {
+            libc::kill(-(pid as i32), libc::SIGKILL);
+        }
+        #[cfg(windows)]
+        let _ = std::process::Command::new("taskkill").args(["/T", "/F", "/PID", &pid.to_string()]).output();
+    }
+}
+
+async fn run_check(root: &Path, command: &str) -> Result<CheckResult, String> {
+    let child = shell(command)
+        .current_dir(root)
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to run `{}`: {}", command, e))?;
+    let process = CheckProcess(child.id());
+    let output = child.wait_with_output().await.map_err(|e| format!("Failed to run `{}`: {}", command, e))?;
+    process.finish();
+    let text = format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+    Ok(CheckResult {
+        command: command.to_string(),
+        exit_code: output.status.code(),
+        passed: output.status.success(),
+        failures: if output.status.success() { Vec::new() } else { parse_failures(&text) },
+        output_tail: output_tail(&text),
+    })
+}
+
+/// How a check reads in the transcript.
+fn describe_check(check: &CheckResult) -> String {
+    if check.passed {
+        return format!("`{}` passed.", check.command);
+    }
+    let mut text = format!(
+        "`{}` failed (exit {}).",
+        check.command,
+        check.exit_code.map_or("by signal".to_string(), |c| c.to_string())
+    );
+    if !check.failures.is_empty() {
+        text.push_str("\nFailures:");
+        for f in &check.failures {
+            let column = f.column.map(|c| format!(":{}", c)).unwrap_or_default();
+            text.push_str(&format!("\n- {}:{}{}: {}", f.path, f.line, column, f.message));
+        }
+    }
+    text.push_str(&format!("\nEnd of output:\n{}", check.output_tail));
+    text
+}
+
+// ── Approvals ──
+
+fn pending() -> &'static Mutex<HashMap<u64, oneshot::Sender<bool>>> {
+    static PENDING: OnceLock<Mutex<HashMap<u64, oneshot::Sender<bool>>>> = OnceLock::new();
+    PENDING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+// ── The loop ──
+
+/// Why a run stopped early.
+enum Stop {
+    Cancelled,
+    Time,
+}
+
+/// `fut`, unless the run is cancelled or out of time first.
+async fn bounded<T>(op: &Operation, deadline: Instant, fut: impl Future<Output = T>) -> Result<T, Stop> {
+    tokio::select! {
+        out = fut => Ok(out),
+        _ = op.cancelled() => Err(Stop::Cancelled),
+        _ = tokio::time::sleep_until(deadline) => Err(Stop::Time),
+    }
+}
+
+/// The prompt for the next step: goal, files and as many of the latest
+/// steps as fit.
+fn prompt(goal: &str, files: &str, check: &str, transcript: &[String]) -> String {
+    let mut kept = Vec::new();
+    let mut size = 0;
+    for entry in transcript.iter().rev() {
+        size += entry.len();
+        if size > MAX_TRANSCRIPT_CHARS && !kept.is_empty() {
+            kept.push("(earlier steps omitted)");
+            break;
+        }
+        kept.push(entry);
+    }
+    kept.reverse();
+    format!(
+        "Goal: {}\n\nCheck command: {}\n\nProject files:\n{}\n\nSteps so far:\n{}\n\nYour next action:",
+        goal,
+        check,
+        files,
+        if kept.is_empty() { "(none)".to_string() } else { kept.join("\n\n") }
+    )
+}
+
+/// The files listed to the model: gitignored files, the project's exclude
+/// patterns and its generated directories are left out.
+fn project_files(root: &Path) -> String {
+    let generated = GeneratedDirs::for_project(root);
+    let mut files: Vec<PathBuf> = search::project_files(root, root).filter(|f| !generated.contains(f)).collect();
+    files.sort();
+    let mut list: Vec<String> = files
+        .iter()
+        .take(MAX_LISTED_FILES)
+        .filter_map(|f| f.strip_prefix(root).ok())
+        .map(|f| f.to_string_lossy().replace('\\', "/"))
+        .collect();
+    if files.len() > MAX_LISTED_FILES {
+        list.push(format!("… and {} more", files.len() - MAX_LISTED_FILES));
+    }
+    list.join("\n")
+}
+
+fn resolve(root: &Path, rel: &str) -> Result<PathBuf, String> {
+    Ok(resolve_within_root(&root.join(rel).to_string_lossy(), root)?)
+}
+
+struct Run<'a> {
+    app: &'a AppHandle,
+    window: &'a str,
+    root: &'a Path,
+    op: &'a Operation,
+    deadline: Instant,
+    provider: Option<&'a str>,
+    model: Option<&'a str>,
+}
+
+impl Run<'_> {
+    fn emit(&self, step: AgentStep) {
+        events::AGENT_STEP.emit_to(self.app, self.window, step);
+    }
+
+    fn step(&self, step: u32, kind: AgentStepKind, summary: &str) -> AgentStep {
+        AgentStep {
+            run_id: self.op.id(),
+            step,
+            kind,
+            summary: summary.to_string(),
+            path: None,
+            diff: None,
+            awaiting_approval: false,
+            approved: None,
+            check: None,
+            finished: false,
+        }
+    }
+
+    /// Show the edit, wait for a decision, and apply it if approved.
+    async fn edit(&self, mut event: AgentStep, path: &str, content: String) -> Result<(AgentStep, String), Stop> {
+        let edit = WorkspaceEdit { operations: vec![FileOperation::Create { path: path.to_string(), content, overwrite: true }] };
+        let diff = match apply_edit(self.root, self.window, &edit, true) {
+            Ok(result) => result.changes.into_iter().find_map(|c| c.diff).unwrap_or_default(),
+            Err(e) => return Ok((event, format!("The edit to {} is invalid: {}", path, e))),
+        };
+        let (tx, rx) = oneshot::channel();
+        pending().lock().unwrap_or_else(|e| e.into_inner()).insert(self.op.id(), tx);
+        event.diff = Some(diff);
+        event.awaiting_approval = true;
+        self.emit(event.clone());
+        let decision = bounded(self.op, self.deadline, rx).await;
+        pending().lock().unwrap_or_else(|e| e.into_inner()).remove(&self.op.id());
+        let approved = decision?.unwrap_or(false);
+        event.awaiting_approval = false;
+        event.approved = Some(approved);
+        if !approved {
+            return Ok((event, format!("The user rejected the edit to {}.", path)));
+        }
+        let outcome = match apply_edit(self.root, self.window, &edit, false) {
+            Ok(_) => format!("Wrote {}.", path),
+            Err(e) => {
+                event.approved = Some(false);
+                format!("Writing {} failed: {}", path, e)
+            }
+        };
+        Ok((event, outcome))
+    }
+
+    async fn run(&self, goal: &str, check_command: &str, max_steps: u32) -> Result<AgentRun, String> {
+        let root = self.root.to_path_buf();
+        let files = tokio::task::spawn_blocking(move || project_files(&root)).await.map_err(|e| e.to_string())?;
+        let mut transcript: Vec<String> = Vec::new();
+        let mut run = AgentRun {
+            run_id: self.op.id(),
+            outcome: AgentOutcome::StepBudget,
+            summary: String::new(),
+            steps: 0,
+            edits_applied: 0,
+            last_check: None,
+        };
+        for n in 1..=max_steps {
+            run.steps = n;
+            self.op.progress(u64::from(n - 1), Some(u64::from(max_steps)), None);
+            let user = prompt(goal, &files, check_command, &transcript);
+            let reply = match bounded(self.op, self.deadline, super::complete("agent", self.provider, self.model, SYSTEM_PROMPT, user)).await {
+                Ok(reply) => reply?,
+                Err(stop) => return Ok(run.stopped(stop)),
+            };
+            let action = match parse_action(&reply) {
+                Ok(action) => action,
+                Err(e) => {
+                    let mut event = self.step(n, AgentStepKind::Invalid, &e);
+                    event.finished = true;
+                    self.emit(event);
+                    transcript.push(format!("Step {}: your reply was not a valid action ({}). Reply with one action in tags.", n, e));
+                    continue;
+                }
+            };
+            match action {
+                Action::Read { path } => {
+                    let mut event = self.step(n, AgentStepKind::Read, "");
+                    event.path = Some(path.clone());
+                    self.emit(event.clone());
+                    let text = resolve(self.root, &path).and_then(|p| std::fs::read_to_string(p).map_err(|e| e.to_string()));
+                    transcript.push(match text {
+                        Ok(text) => {
+                            let shown: String = text.chars().take(MAX_FILE_CHARS).collect();
+                            let cut = if shown.len() < text.len() { "\n(truncated)" } else { "" };
+                            format!("Step {}: read {}:\n```\n{}\n```{}", n, path, shown, cut)
+                        }
+                        Err(e) => format!("Step {}: couldn't read {}: {}", n, path, e),
+                    });
+                    event.finished = true;
+                    self.emit(event);
+                }
+                Action::Edit { path, content, summary } => {
+                    let mut event = self.step(n, AgentStepKind::Edit, &summary);
+                    event.path = Some(path.clone());
+                    let (mut event, outcome) = match self.edit(event, &path, content).await {
+                        Ok(done) => done,
+                        Err(stop) => return Ok(run.stopped(stop)),
+                    };
+                    if event.approved == Some(true) {
+                        run.edits_applied += 1;
+                    }
+                    transcript.push(format!("Step {}: edit {} ({}). {}", n, path, summary, outcome));
+                    event.finished = true;
+                    self.emit(event);
+                }
+                Action::Check => {
+                    let mut event = self.step(n, AgentStepKind::Check, check_command);
+                    self.emit(event.clone());
+                    let check = match bounded(self.op, self.deadline, run_check(self.root, check_command)).await {
+                        Ok(check) => check?,
+                        Err(stop) => return Ok(run.stopped(stop)),
+                    };
+                    transcript.push(format!("Step {}: check. {}", n, describe_check(&check)));
+                    event.check = Some(check.clone());
+                    event.finished = true;
+                    self.emit(event);
+                    run.last_check = Some(check);
+                }
+                Action::Done { summary } => {
+                    let mut event = self.step(n, AgentStepKind::Done, &summary);
+                    event.finished = true;
+                    self.emit(event);
+                    run.outcome = AgentOutcome::Done;
+                    run.summary = summary;
+                    return Ok(run);
+                }
+            }
+        }
+        Ok(run)
+    }
+}
+
+impl AgentRun {
+    fn stopped(mut self, stop: Stop) -> Self {
+        self.outcome = match stop {
+            Stop::Cancelled => AgentOutcome::Cancelled,
+            Stop::Time => AgentOutcome::TimeBudget,
+        };
+        self
+    }
+}
+
+// ── Commands ──
+
+/// Work toward `goal` in the window's project, editing files (each edit
+/// approved through `ai_agent_respond`), running `check_command` (or the
+/// project's usual test command) and reading its failures, for at most
+/// `max_steps` actions and `max_seconds`. Progress arrives as
+/// `agent-step` events.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn ai_run_agent(
+    app: AppHandle,
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    goal: String,
+    max_steps: Option<u32>,
+    max_seconds: Option<u64>,
+    check_command: Option<String>,
+    provider: Option<String>,
+    model: Option<String>,
+) -> Result<AgentRun, String> {
+    let root = project_root_async(window.label(), &state).await?;
+    let check_command = match check_command.map(|c| c.trim().to_string()).filter(|c| !c.is_empty()) {
+        Some(command) => command,
+        None => default_check(&root)
+            .ok_or("No check command was given and none could be detected for this project")?
+            .to_string(),
+    };
+    let max_steps = max_steps.unwrap_or(DEFAULT_STEPS).clamp(1, MAX_STEPS);
+    let seconds = max_seconds.unwrap_or(DEFAULT_SECONDS).clamp(10, MAX_SECONDS);
+    let title: String = goal.chars().take(60).collect();
+    let op = operations::manager().start(OperationKind::Ai, &format!("Agent: {}", title), Some(window.label()), true);
+    let run = Run {
+        app: &app,
+        window: window.label(),
+        root: &root,
+        op: &op,
+        deadline: Instant::now() + Duration::from_secs(seconds),
+        provider: provider.as_deref(),
+        model: model.as_deref(),
+    };
+    op.track(run.run(&goal, &check_command, max_steps).await)
+}
+
+/// Approve or reject the edit agent run `run_id` is waiting on. Returns
+/// false if it isn't waiting.
+#[tauri::command]
+pub fn ai_agent_respond(run_id: u64, approve: bool) -> bool {
+    let sender = pending().lock().unwrap_or_else(|e| e.into_inner()).remove(&run_id);
+    sender.is_some_and(|tx| tx.send(approve).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_actions_and_check_failures() {
+        assert_eq!(parse_action("Let me look.\n<action>read</action><path> src/lib.rs </path>"), Ok(Action::Read { path: "src/lib.rs".into() }));
+        assert_eq!(
+            parse_action("<action>edit</action>\n<path>a.rs</path>\n<content>\nfn a() {}\n</content>\n<summary>Add a</summary>"),
+            Ok(Action::Edit { path: "a.rs".into(), content: "fn a() {}".into(), summary: "Add a".into() })
+        );
+        assert_eq!(parse_action("<action>check</action>"), Ok(Action::Check));
+        assert!(parse_action("<action>edit</action><path>a.rs</path>").unwrap_err().contains("<content>"));
+        assert!(parse_action("I think we're done").is_err());
+
+        let output = "\
+   Compiling demo v0.1.0
+error[E0308]: mismatched types
+ --> src/main.rs:4:18
+  |
+src/app.ts(12,5): error TS2322: Type 'string' is not assignable to type 'number'.
+main.go:7:2: undefined: fmt
+tests/test_x.py:30: AssertionError
+note: this is not a path: really
+test result: FAILED. 1 passed; 1 failed
+";
+        let failures = parse_failures(output);
+        assert_eq!(
+            failures.iter().map(|f| (f.path.as_str(), f.line, f.column)).collect::<Vec<_>>(),
+            [("src/main.rs", 4, Some(18)), ("src/app.ts", 12, Some(5)), ("main.go", 7, Some(2)), ("tests/test_x.py", 30, None)]
+        );
+        assert_eq!(failures[0].message, "mismatched types");
+        assert_eq!(failures[1].message, "error TS2322: Type 'string' is not assignable to type 'number'.");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn a_dropped_check_kills_what_it_started() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = dir.path().join("marker");
+        let command = format!("(sleep 1; touch '{}') & sleep 5", marker.display());
+        let check = tokio::time::timeout(Duration::from_millis(200), run_check(dir.path(), &command)).await;
+        assert!(check.is_err());
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(!marker.exists());
+
+        let check = run_check(dir.path(), "echo ok; exit 3").await.unwrap();
+        assert_eq!((check.exit_code, check.passed, check.output_tail.as_str()), (Some(3), false, "ok"));
+    }
+}
@@ -0,0 +1,780 @@
+//! Backend-driven AI actions on project code.
+//!
+//! Each command gathers the relevant context itself (via tree-sitter,
+//! git, …), builds a task-specific prompt, and post-processes the model
+//! reply into something the editor can apply directly — an insert edit,
+//! a proposed file body, a list of diagnostics — instead of free-form
+//! chat text.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::modules::fs::{generated, project_root_async, resolve_within_root, ProjectRootState};
+use crate::modules::git::{read_conflict_versions, read_working_diff, ConflictVersions};
+use crate::modules::symbols::{extract_symbols, Symbol};
+
+/// Upper bound on source text sent with a single request. Symbols larger
+/// than this are truncated — the signature and opening lines carry most
+/// of what a doc comment needs.
+const MAX_SOURCE_CHARS: usize = 12_000;
+
+// ── Types ──
+
+/// 1-based inclusive line range, matching `Symbol::start_line/end_line`.
+#[derive(Deserialize, Clone, Copy)]
+pub struct LineRange {
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// Insert `text` before `line` (1-based) at `character` (0-based).
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct TextInsert {
+    pub line: usize,
+    pub character: usize,
+    pub text: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct DocumentSymbolResult {
+    pub symbol: String,
+    pub kind: String,
+    pub edit: TextInsert,
+}
+
+/// Replace the text between two 1-based line / 0-based character positions.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct TextReplace {
+    pub start_line: usize,
+    pub start_character: usize,
+    pub end_line: usize,
+    pub end_character: usize,
+    pub text: String,
+}
+
+/// The editor selection an action applies to, with its current text so
+/// unsaved buffer contents are used rather than what's on disk.
+#[derive(Deserialize, Clone)]
+pub struct Selection {
+    pub start_line: usize,
+    pub start_character: usize,
+    pub end_line: usize,
+    pub end_character: usize,
+    pub text: String,
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum QuickAction {
+    Explain,
+    Optimize,
+    AddTypes,
+    ConvertLanguage { target: String },
+    FixError { message: String },
+}
+
+/// Prose for explanations, a replacement edit for in-place rewrites, and
+/// standalone code for conversions into another language.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum QuickActionResult {
+    Prose { text: String },
+    Edit { edit: TextReplace, explanation: String },
+    Code { language: String, code: String },
+}
+
+/// A proposed merge result. `resolution` is the full file body, ready to
+/// be passed to `git_resolve_conflict` once the user has reviewed it.
+#[derive(Serialize, Clone)]
+pub struct ConflictResolution {
+    pub file_path: String,
+    pub resolution: String,
+    pub rationale: String,
+}
+
+/// One review finding, anchored to a line in the new version of a file.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ReviewDiagnostic {
+    pub file_path: String,
+    pub line: usize,
+    /// `error`, `warning`, or `info`.
+    pub severity: String,
+    pub message: String,
+}
+
+// ── Doc comment conventions ──
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum DocStyle {
+    /// `/// line` above the item (Rust).
+    TripleSlash,
+    /// `/** … */` block above the declaration (JS/TS).
+    JsDoc,
+    /// `"""…"""` as the first statement of the body (Python).
+    Docstring,
+}
+
+fn doc_style_for_extension(ext: &str) -> Option<DocStyle> {
+    match ext {
+        "rs" => Some(DocStyle::TripleSlash),
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" => Some(DocStyle::JsDoc),
+        "py" | "pyi" => Some(DocStyle::Docstring),
+        _ => None,
+    }
+}
+
+fn language_label(style: DocStyle) -> &'static str {
+    match style {
+        DocStyle::TripleSlash => "Rust (rustdoc `///` comments)",
+        DocStyle::JsDoc => "JavaScript/TypeScript (JSDoc with @param/@returns where useful)",
+        DocStyle::Docstring => "Python (PEP 257 docstring)",
+    }
+}
+
+/// Pick the smallest symbol that fully contains the requested range.
+fn innermost_symbol(symbols: &[Symbol], range: LineRange) -> Option<&Symbol> {
+    symbols
+        .iter()
+        .filter(|s| s.start_line <= range.start_line && s.end_line >= range.end_line)
+        .min_by_key(|s| s.end_line - s.start_line)
+}
+
+/// Strip whatever wrapping the model added (fences, comment markers,
+/// quotes) and return the bare doc text lines.
+fn clean_doc_text(raw: &str) -> Vec<String> {
+    let mut lines: Vec<String> = raw
+        .lines()
+        .filter(|l| !l.trim_start().starts_with("```"))
+        .map(|l| {
+            let t = l.trim();
+            let t = t
+                .strip_prefix("///")
+                .or_else(|| t.strip_prefix("/**"))
+                .or_else(|| t.strip_prefix("//"))
+                .unwrap_or(t);
+            let t = t.strip_suffix("*/").unwrap_or(t);
+            let t = t.trim();
+            let t = if t == "*" { "" } else { t.strip_prefix("* ").unwrap_or(t) };
+            t.trim_matches('"').trim_end().to_string()
+        })
+        .collect();
+    while lines.first().is_some_and(|l| l.is_empty()) {
+        lines.remove(0);
+    }
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+/// Render cleaned doc lines in the language's comment syntax, one output
+/// line per input line, each terminated with `\n`.
+fn format_doc_comment(lines: &[String], style: DocStyle, indent: &str) -> String {
+    let mut out = String::new();
+    match style {
+        DocStyle::TripleSlash => {
+            for l in lines {
+                if l.is_empty() {
+                    out.push_str(&format!("{indent}///\n"));
+                } else {
+                    out.push_str(&format!("{indent}/// {l}\n"));
+                }
+            }
+        }
+        DocStyle::JsDoc => {
+            out.push_str(&format!("{indent}/**\n"));
+            for l in lines {
+                if l.is_empty() {
+                    out.push_str(&format!("{indent} *\n"));
+                } else {
+                    out.push_str(&format!("{indent} * {l}\n"));
+                }
+            }
+            out.push_str(&format!("{indent} */\n"));
+        }
+        DocStyle::Docstring => {
+            if lines.len() <= 1 {
+                let text = lines.first().map(String::as_str).unwrap_or("");
+                out.push_str(&format!("{indent}\"\"\"{text}\"\"\"\n"));
+            } else {
+                out.push_str(&format!("{indent}\"\"\"{}\n", lines[0]));
+                for l in &lines[1..] {
+                    if l.is_empty() {
+                        out.push('\n');
+                    } else {
+                        out.push_str(&format!("{indent}{l}\n"));
+                    }
+                }
+                out.push_str(&format!("{indent}\"\"\"\n"));
+            }
+        }
+    }
+    out
+}
+
+fn leading_whitespace(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+/// Where the doc comment goes and how it's indented. Rust/JS docs sit
+/// directly above the symbol; Python docstrings go on the first line of
+/// the body, after a (possibly multi-line) signature ending in `:`.
+fn insertion_point(source: &str, symbol: &Symbol, style: DocStyle) -> (usize, String) {
+    let lines: Vec<&str> = source.lines().collect();
+    let decl_line = lines.get(symbol.start_line.saturating_sub(1)).copied().unwrap_or("");
+    let decl_indent = leading_whitespace(decl_line).to_string();
+    if style != DocStyle::Docstring {
+        return (symbol.start_line, decl_indent);
+    }
+
+    let mut header_end = symbol.start_line;
+    for (idx, line) in lines.iter().enumerate().skip(symbol.start_line.saturating_sub(1)) {
+        let code = line.split('#').next().unwrap_or("").trim_end();
+        if code.ends_with(':') {
+            header_end = idx + 1;
+            break;
+        }
+    }
+    let body_indent = lines
+        .iter()
+        .skip(header_end)
+        .find(|l| !l.trim().is_empty())
+        .map(|l| leading_whitespace(l).to_string())
+        .filter(|ind| ind.len() > decl_indent.len())
+        .unwrap_or_else(|| format!("{decl_indent}    "));
+    (header_end + 1, body_indent)
+}
+
+// ── Conflict resolution ──
+
+/// Contents of the first `<tag>…</tag>` block, with one leading and one
+/// trailing newline trimmed so file bodies round-trip unchanged.
+pub(super) fn extract_tag<'a>(reply: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let start = reply.find(&open)? + open.len();
+    let end = start + reply[start..].find(&close)?;
+    let body = &reply[start..end];
+    let body = body.strip_prefix('\n').unwrap_or(body);
+    Some(body.strip_suffix('\n').unwrap_or(body))
+}
+
+fn has_conflict_markers(text: &str) -> bool {
+    text.lines().any(|l| {
+        l.starts_with("<<<<<<<") || l.starts_with(">>>>>>>") || l == "=======" || l.starts_with("======= ")
+    })
+}
+
+fn conflict_prompt(file_path: &str, versions: &ConflictVersions, working: &str) -> String {
+    let side = |v: &Option<String>| match v {
+        Some(text) => truncate_chars(text, MAX_SOURCE_CHARS).to_string(),
+        None => "(file does not exist on this side)".to_string(),
+    };
+    format!(
+        "File: {}\n\n<base>\n{}</base>\n\n<ours>\n{}</ours>\n\n<theirs>\n{}</theirs>\n\n\
+         <working_copy>\n{}</working_copy>",
+        file_path,
+        side(&versions.base),
+        side(&versions.ours),
+        side(&versions.theirs),
+        truncate_chars(working, MAX_SOURCE_CHARS),
+    )
+}
+
+// ── Quick actions ──
+
+fn file_language(path: &str) -> &'static str {
+    match Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or("") {
+        "rs" => "Rust",
+        "js" | "jsx" | "mjs" | "cjs" => "JavaScript",
+        "ts" | "tsx" | "mts" | "cts" => "TypeScript",
+        "py" | "pyi" => "Python",
+        "go" => "Go",
+        "java" => "Java",
+        "svelte" => "Svelte",
+        "vue" => "Vue",
+        "c" | "h" => "C",
+        "cpp" | "cc" | "hpp" => "C++",
+        "rb" => "Ruby",
+        "sh" => "shell",
+        _ => "the file's language",
+    }
+}
+
+/// Task-specific instructions. Edit-style actions ask for a single fenced
+/// block so the reply can be split into replacement code and explanation.
+fn quick_action_instructions(action: &QuickAction, language: &str) -> String {
+    const EDIT_FORMAT: &str = "Reply with the complete replacement for the selection in a single \
+        fenced code block, preserving its indentation, followed by at most a short paragraph \
+        explaining the change.";
+    match action {
+        QuickAction::Explain => format!(
+            "Explain what the selected {language} code does, how it works, and anything surprising \
+             about it. Be concise and use markdown."
+        ),
+        QuickAction::Optimize => format!(
+            "Rewrite the selected {language} code to be faster or use less memory without changing \
+             its behavior or public interface. {EDIT_FORMAT}"
+        ),
+        QuickAction::AddTypes => format!(
+            "Add precise type annotations to the selected {language} code. Do not change behavior. \
+             {EDIT_FORMAT}"
+        ),
+        QuickAction::FixError { message } => format!(
+            "Fix the selected {language} code so this error no longer occurs:\n{message}\n{EDIT_FORMAT}"
+        ),
+        QuickAction::ConvertLanguage { target } => format!(
+            "Translate the selected {language} code into idiomatic {target}, keeping the same \
+             behavior. Reply with the translated code only, in a single fenced code block."
+        ),
+    }
+}
+
+/// Split a reply into the first fenced code block and the prose around it.
+/// A reply without fences is treated as all code.
+fn split_code_reply(reply: &str) -> (String, String) {
+    let Some(open) = reply.find("```") else {
+        return (reply.trim_matches('\n').to_string(), String::new());
+    };
+    let after_fence = &reply[open + 3..];
+    let body_start = after_fence.find('\n').map(|i| i + 1).unwrap_or(after_fence.len());
+    let body = &after_fence[body_start..];
+    let (code, rest) = match body.find("```") {
+        Some(close) => (&body[..close], &body[close + 3..]),
+        None => (body, ""),
+    };
+    let prose = format!("{} {}", reply[..open].trim(), rest.trim()).trim().to_string();
+    (code.strip_suffix('\n').unwrap_or(code).to_string(), prose)
+}
+
+// ── Change review ──
+
+/// Split a multi-file diff at `diff --git` headers, returning the
+/// destination path of each file alongside its patch text.
+fn split_diff_by_file(diff: &str) -> Vec<(String, &str)> {
+    let mut starts: Vec<usize> = diff
+        .match_indices("diff --git ")
+        .map(|(i, _)| i)
+        .filter(|&i| i == 0 || diff.as_bytes()[i - 1] == b'\n')
+        .collect();
+    starts.push(diff.len());
+    starts
+        .windows(2)
+        .map(|w| {
+            let section = &diff[w[0]..w[1]];
+            let path = section
+                .lines()
+                .find_map(|l| l.strip_prefix("+++ b/"))
+                .or_else(|| section.lines().next().and_then(|h| h.rsplit(" b/").next()))
+                .unwrap_or("")
+                .trim()
+                .to_string();
+            (path, section)
+        })
+        .collect()
+}
+
+/// Group per-file patches into prompts of at most `max` characters. A
+/// single file larger than `max` is truncated into its own chunk.
+fn chunk_diff<'a>(files: &[(String, &'a str)], max: usize) -> Vec<Vec<(String, &'a str)>> {
+    let mut chunks = Vec::new();
+    let mut current: Vec<(String, &'a str)> = Vec::new();
+    let mut size = 0;
+    for (path, patch) in files {
+        let patch = truncate_chars(patch, max);
+        if !current.is_empty() && size + patch.len() > max {
+            chunks.push(std::mem::take(&mut current));
+            size = 0;
+        }
+        size += patch.len();
+        current.push((path.clone(), patch));
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Parse `severity|path|line|message` lines. Findings for files outside
+/// the chunk or with unknown severities are dropped rather than guessed at.
+fn parse_review_reply(reply: &str, files: &[(String, &str)]) -> Vec<ReviewDiagnostic> {
+    reply
+        .lines()
+        .filter_map(|l| {
+            let mut parts = l.trim().trim_start_matches("- ").splitn(4, '|');
+            let severity = parts.next()?.trim().to_ascii_lowercase();
+            let file_path = parts.next()?.trim().to_string();
+            let line = parts.next()?.trim().parse::<usize>().ok()?;
+            let message = parts.next()?.trim().to_string();
+            let severity = match severity.as_str() {
+                "error" | "critical" | "high" => "error",
+                "warning" | "medium" => "warning",
+                "info" | "low" | "nit" => "info",
+                _ => return None,
+            };
+            if message.is_empty() || !files.iter().any(|(p, _)| *p == file_path) {
+                return None;
+            }
+            Some(ReviewDiagnostic {
+                file_path,
+                line: line.max(1),
+                severity: severity.to_string(),
+                message,
+            })
+        })
+        .collect()
+}
+
+fn truncate_chars(s: &str, max: usize) -> &str {
+    match s.char_indices().nth(max) {
+        Some((idx, _)) => &s[..idx],
+        None => s,
+    }
+}
+
+// ── Commands ──
+
+/// Generate a doc comment for the symbol enclosing `range` and return it
+/// as an insert edit. Nothing is written to disk; the frontend applies
+/// the edit to the open buffer so it participates in normal undo.
+#[tauri::command]
+pub async fn ai_document_symbol(
+    window: tauri::WebviewWindow,
+    path: String,
+    range: LineRange,
+    provider: Option<String>,
+    model: Option<String>,
+    bypass_cache: Option<bool>,
+    state: tauri::State<'_, ProjectRootState>,
+) -> Result<DocumentSymbolResult, String> {
+    let root = project_root_async(window.label(), &state).await?;
+    let file_path = resolve_within_root(&path, &root)?;
+    let source = std::fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read file: {}", e.kind()))?;
+
+    let ext = Path::new(&path).extension().and_then(|e| e.to_str()).unwrap_or("");
+    let style = doc_style_for_extension(ext)
+        .ok_or_else(|| format!("Doc generation is not supported for .{} files", ext))?;
+
+    let symbols = extract_symbols(&source, ext);
+    let symbol = innermost_symbol(&symbols, range)
+        .ok_or_else(|| "No function, class, or type found at the selection".to_string())?;
+
+    let system = "You write documentation comments for source code. \
+        Reply with only the documentation text: no code, no comment delimiters, \
+        no markdown fences. Describe what the symbol does and why a caller would use it; \
+        document parameters, return values, and errors only when they are not obvious.";
+    let user = format!(
+        "Language: {}\nSymbol: {} ({})\n\n{}",
+        language_label(style),
+        symbol.name,
+        symbol.kind,
+        truncate_chars(&symbol.body, MAX_SOURCE_CHARS),
+    );
+    let reply = super::complete_cached(
+        "document",
+        provider.as_deref(),
+        model.as_deref(),
+        system,
+        user,
+        bypass_cache.unwrap_or(false),
+    )
+    .await?;
+
+    let doc_lines = clean_doc_text(&reply);
+    if doc_lines.is_empty() {
+        return Err("The model returned an empty doc comment".to_string());
+    }
+    let (line, indent) = insertion_point(&source, symbol, style);
+    Ok(DocumentSymbolResult {
+        symbol: symbol.name.clone(),
+        kind: symbol.kind.clone(),
+        edit: TextInsert {
+            line,
+            character: 0,
+            text: format_doc_comment(&doc_lines, style, &indent),
+        },
+    })
+}
+
+/// Run a built-in action on the editor selection. `path` supplies the
+/// language and surrounding file for context; the selection text itself
+/// comes from the buffer.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn ai_quick_action(
+    window: tauri::WebviewWindow,
+    action: QuickAction,
+    selection: Selection,
+    path: String,
+    provider: Option<String>,
+    model: Option<String>,
+    bypass_cache: Option<bool>,
+    state: tauri::State<'_, ProjectRootState>,
+) -> Result<QuickActionResult, String> {
+    if selection.text.trim().is_empty() {
+        return Err("Select some code first".to_string());
+    }
+    let root = project_root_async(window.label(), &state).await?;
+    let file_path = resolve_within_root(&path, &root)?;
+    let file_text = std::fs::read_to_string(&file_path).unwrap_or_default();
+    let language = file_language(&path);
+
+    let system = "You are a coding assistant acting on a selection in the user's editor. \
+        Follow the task instructions exactly and do not add unrelated changes.";
+    let mut user = format!("Task: {}\n\n", quick_action_instructions(&action, language));
+    if !file_text.is_empty() {
+        user.push_str(&format!(
+            "Surrounding file ({}):\n```\n{}\n```\n\n",
+            path,
+            truncate_chars(&file_text, MAX_SOURCE_CHARS)
+        ));
+    }
+    user.push_str(&format!(
+        "Selection (lines {}-{}):\n```\n{}\n```",
+        selection.start_line,
+        selection.end_line,
+        truncate_chars(&selection.text, MAX_SOURCE_CHARS)
+    ));
+    let reply = super::complete_cached(
+        "quick_action",
+        provider.as_deref(),
+        model.as_deref(),
+        system,
+        user,
+        bypass_cache.unwrap_or(false),
+    )
+    .await?;
+
+    Ok(match action {
+        QuickAction::Explain => QuickActionResult::Prose { text: reply.trim().to_string() },
+        QuickAction::ConvertLanguage { target } => QuickActionResult::Code {
+            language: target,
+            code: split_code_reply(&reply).0,
+        },
+        QuickAction::Optimize | QuickAction::AddTypes | QuickAction::FixError { .. } => {
+            let (code, explanation) = split_code_reply(&reply);
+            if code.trim().is_empty() {
+                return Err("The model did not return any code".to_string());
+            }
+            QuickActionResult::Edit {
+                edit: TextReplace {
+                    start_line: selection.start_line,
+                    start_character: selection.start_character,
+                    end_line: selection.end_line,
+                    end_character: selection.end_character,
+                    text: code,
+                },
+                explanation,
+            }
+        }
+    })
+}
+
+/// Propose a resolution for a conflicted file from its base/ours/theirs
+/// stages. Nothing is written; the frontend shows the proposal alongside
+/// the rationale and applies it through `git_resolve_conflict`.
+#[tauri::command]
+pub async fn ai_resolve_conflict(
+    window: tauri::WebviewWindow,
+    repo_path: String,
+    file_path: String,
+    provider: Option<String>,
+    model: Option<String>,
+    state: tauri::State<'_, ProjectRootState>,
+) -> Result<ConflictResolution, String> {
+    let root = project_root_async(window.label(), &state).await?;
+    let repo = resolve_within_root(&repo_path, &root)?;
+    let versions = read_conflict_versions(&repo, &file_path)?;
+    let working = std::fs::read_to_string(repo.join(&file_path)).unwrap_or_default();
+
+    let system = "You resolve git merge conflicts. Combine the intent of both sides \
+        relative to the common base; when they genuinely contradict, prefer the version \
+        that keeps the code compiling and explain the choice. Reply with the complete \
+        resolved file inside <resolution></resolution> tags, with no conflict markers, \
+        followed by a short explanation inside <rationale></rationale> tags.";
+    let user = conflict_prompt(&file_path, &versions, &working);
+    let reply = super::complete("resolve_conflict", provider.as_deref(), model.as_deref(), system, user).await?;
+
+    let resolution = extract_tag(&reply, "resolution")
+        .ok_or_else(|| "The model did not return a resolution".to_string())?;
+    if has_conflict_markers(resolution) {
+        return Err("The proposed resolution still contains conflict markers".to_string());
+    }
+    let rationale = extract_tag(&reply, "rationale").unwrap_or("").trim().to_string();
+    Ok(ConflictResolution {
+        file_path,
+        resolution: resolution.to_string(),
+        rationale,
+    })
+}
+
+/// Review the working changes and return findings as diagnostics that the
+/// editor can show inline. Large diffs are reviewed in chunks of whole
+/// files so each request stays within `MAX_SOURCE_CHARS`. Generated files
+/// are left out.
+#[tauri::command]
+pub async fn ai_review_changes(
+    window: tauri::WebviewWindow,
+    repo_path: String,
+    staged_only: bool,
+    provider: Option<String>,
+    model: Option<String>,
+    state: tauri::State<'_, ProjectRootState>,
+) -> Result<Vec<ReviewDiagnostic>, String> {
+    let root = project_root_async(window.label(), &state).await?;
+    let repo = resolve_within_root(&repo_path, &root)?;
+    let diff = read_working_diff(&repo, staged_only)?;
+    // Lockfiles, bundles and generated directories make for long, useless prompts.
+    let generated_dirs = generated::GeneratedDirs::for_project(&root);
+    let files: Vec<_> = split_diff_by_file(&diff)
+        .into_iter()
+        .filter(|(path, _)| {
+            let file = repo.join(path);
+            !generated_dirs.contains(&file) && generated::check_file(&file).is_none()
+        })
+        .collect();
+    if files.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let system = "You review code changes before they are pushed. Report only real \
+        problems in the added or modified lines: bugs, security issues, missing error \
+        handling, and clear maintainability problems. One finding per line, formatted \
+        exactly as `severity|path|line|message`, where severity is error, warning, or \
+        info, path is the file path as shown after `+++ b/`, and line is the line \
+        number in the new file. Reply with nothing else; reply with an empty message \
+        if there are no findings.";
+    let mut diagnostics = Vec::new();
+    for chunk in chunk_diff(&files, MAX_SOURCE_CHARS) {
+        let user: String = chunk.iter().map(|(_, patch)| *patch).collect();
+        let reply = super::complete("review", provider.as_deref(), model.as_deref(), system, user).await?;
+        diagnostics.extend(parse_review_reply(&reply, &chunk));
+    }
+    Ok(diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(v: &[&str]) -> Vec<String> {
+        v.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn clean_doc_text_strips_fences_and_markers() {
+        let raw = "```rust\n/// Adds two numbers.\n///\n/// Returns the sum.\n```\n";
+        assert_eq!(clean_doc_text(raw), lines(&["Adds two numbers.", "", "Returns the sum."]));
+
+        let jsdoc = "/**\n * Fetch a user.\n * @param id user id\n */";
+        assert_eq!(clean_doc_text(jsdoc), lines(&["Fetch a user.", "@param id user id"]));
+    }
+
+    #[test]
+    fn format_doc_comment_uses_language_conventions() {
+        let doc = lines(&["Adds numbers.", "", "Never overflows."]);
+        assert_eq!(
+            format_doc_comment(&doc, DocStyle::TripleSlash, "    "),
+            "    /// Adds numbers.\n    ///\n    /// Never overflows.\n"
+        );
+        assert_eq!(
+            format_doc_comment(&lines(&["Adds numbers."]), DocStyle::JsDoc, ""),
+            "/**\n * Adds numbers.\n */\n"
+        );
+        assert_eq!(
+            format_doc_comment(&lines(&["Adds numbers."]), DocStyle::Docstring, "    "),
+            "    \"\"\"Adds numbers.\"\"\"\n"
+        );
+    }
+
+    #[test]
+    fn innermost_symbol_prefers_the_tightest_match() {
+        let source = "class Calc:\n    def add(self, a, b):\n        return a + b\n";
+        let symbols = extract_symbols(source, "py");
+        let hit = innermost_symbol(&symbols, LineRange { start_line: 3, end_line: 3 }).unwrap();
+        assert_eq!(hit.name, "add");
+    }
+
+    #[test]
+    fn python_docstring_goes_after_multiline_signature() {
+        let source = "def add(\n    a,\n    b,\n):\n    return a + b\n";
+        let symbols = extract_symbols(source, "py");
+        let sym = innermost_symbol(&symbols, LineRange { start_line: 1, end_line: 1 }).unwrap();
+        let (line, indent) = insertion_point(source, sym, DocStyle::Docstring);
+        assert_eq!(line, 5);
+        assert_eq!(indent, "    ");
+    }
+
+    #[test]
+    fn rust_doc_goes_above_indented_item() {
+        let source = "impl Foo {\n    pub fn bar(&self) -> u32 {\n        42\n    }\n}\n";
+        let symbols = extract_symbols(source, "rs");
+        let sym = innermost_symbol(&symbols, LineRange { start_line: 3, end_line: 3 }).unwrap();
+        assert_eq!(sym.name, "bar");
+        let (line, indent) = insertion_point(source, sym, DocStyle::TripleSlash);
+        assert_eq!((line, indent.as_str()), (2, "    "));
+    }
+
+    #[test]
+    fn extract_tag_preserves_file_body() {
+        let reply = "Sure.\n<resolution>\nfn a() {}\n\nfn b() {}\n</resolution>\n<rationale>Kept both.</rationale>";
+        assert_eq!(extract_tag(reply, "resolution"), Some("fn a() {}\n\nfn b() {}"));
+        assert_eq!(extract_tag(reply, "rationale"), Some("Kept both."));
+        assert_eq!(extract_tag(reply, "missing"), None);
+    }
+
+    #[test]
+    fn detects_leftover_conflict_markers() {
+        assert!(has_conflict_markers("a\n<<<<<<< HEAD\nb\n=======\nc\n>>>>>>> main\n"));
+        assert!(!has_conflict_markers("let sep = \"=======\";\n// ===== heading =====\n"));
+    }
+
+    #[test]
+    fn review_reply_is_parsed_and_filtered() {
+        let diff = "diff --git a/src/a.rs b/src/a.rs\n--- a/src/a.rs\n+++ b/src/a.rs\n@@ -1 +1 @@\n-x\n+y\n\
+                    diff --git a/b.py b/b.py\n--- a/b.py\n+++ b/b.py\n@@ -1 +1 @@\n-x\n+y\n";
+        let files = split_diff_by_file(diff);
+        assert_eq!(files.iter().map(|(p, _)| p.as_str()).collect::<Vec<_>>(), ["src/a.rs", "b.py"]);
+
+        let reply = "error|src/a.rs|1|unwrap on user input\n\
+                     - Nit | b.py | 1 | prefer f-strings\n\
+                     warning|other.rs|3|not in this diff\n\
+                     Looks good otherwise.";
+        let diags = parse_review_reply(reply, &files);
+        assert_eq!(diags.len(), 2);
+        assert_eq!(diags[0].severity, "error");
+        assert_eq!(diags[1], ReviewDiagnostic {
+            file_path: "b.py".into(),
+            line: 1,
+            severity: "info".into(),
+            message: "prefer f-strings".into(),
+        });
+    }
+
+    #[test]
+    fn chunk_diff_keeps_files_whole() {
+        let files = vec![("a".to_string(), "aaaa"), ("b".to_string(), "bbbb"), ("c".to_string(), "cc")];
+        let chunks = chunk_diff(&files, 6);
+        let names: Vec<Vec<&str>> = chunks.iter().map(|c| c.iter().map(|(p, _)| p.as_str()).collect()).collect();
+        assert_eq!(names, vec![vec!["a"], vec!["b", "c"]]);
+    }
+
+    #[test]
+    fn quick_actions_deserialize_from_tagged_json() {
+        let a: QuickAction = serde_json::from_str(r#"{"kind":"convert_language","target":"Go"}"#).unwrap();
+        assert_eq!(a, QuickAction::ConvertLanguage { target: "Go".into() });
+        let a: QuickAction = serde_json::from_str(r#"{"kind":"add_types"}"#).unwrap();
+        assert_eq!(a, QuickAction::AddTypes);
+    }
+
+    #[test]
+    fn split_code_reply_separates_code_and_prose() {
+        let (code, prose) = split_code_reply("Here you go:\n```ts\nconst x: number = 1;\n```\nAdded a type.");
+        assert_eq!(code, "const x: number = 1;");
+        assert_eq!(prose, "Here you go: Added a type.");
+
+        let (code, prose) = split_code_reply("let y = 2;\n");
+        assert_eq!((code.as_str(), prose.as_str()), ("let y = 2;", ""));
+    }
+}
@@ -0,0 +1,120 @@
+//! In-memory response cache for deterministic AI actions.
+//!
+//! Entries are keyed by a SHA-256 of provider, model, system prompt, and
+//! user content, so any change to the code being acted on is a miss.
+//! Entries expire after `TTL` and the cache is bounded at `MAX_ENTRIES`,
+//! evicting the oldest insert first. Nothing is persisted — responses can
+//! contain source code and shouldn't outlive the process.
+
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+const TTL: Duration = Duration::from_secs(60 * 60);
+const MAX_ENTRIES: usize = 256;
+
+struct Entry {
+    value: String,
+    inserted: Instant,
+}
+
+pub(crate) struct ResponseCache {
+    entries: HashMap<String, Entry>,
+    ttl: Duration,
+    capacity: usize,
+}
+
+impl ResponseCache {
+    fn new(ttl: Duration, capacity: usize) -> Self {
+        Self { entries: HashMap::new(), ttl, capacity }
+    }
+
+    fn get(&mut self, key: &str) -> Option<String> {
+        let fresh = self.entries.get(key).map(|e| e.inserted.elapsed() < self.ttl)?;
+        if fresh {
+            self.entries.get(key).map(|e| e.value.clone())
+        } else {
+            self.entries.remove(key);
+            None
+        }
+    }
+
+    fn insert(&mut self, key: String, value: String) {
+        let ttl = self.ttl;
+        self.entries.retain(|_, e| e.inserted.elapsed() < ttl);
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&key) {
+            if let Some(oldest) = self.entries.iter().min_by_key(|(_, e)| e.inserted).map(|(k, _)| k.clone()) {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(key, Entry { value, inserted: Instant::now() });
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+fn cache() -> &'static Mutex<ResponseCache> {
+    static CACHE: OnceLock<Mutex<ResponseCache>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(ResponseCache::new(TTL, MAX_ENTRIES)))
+}
+
+pub(crate) fn key(provider: &str, model: &str, system: &str, user: &str) -> String {
+    let mut hasher = Sha256::new();
+    for part in [provider, model, system, user] {
+        // Length-prefix each part so ("ab", "c") and ("a", "bc") differ.
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part.as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+pub(crate) fn get(key: &str) -> Option<String> {
+    cache().lock().ok()?.get(key)
+}
+
+pub(crate) fn insert(key: String, value: String) {
+    if let Ok(mut c) = cache().lock() {
+        c.insert(key, value);
+    }
+}
+
+pub(crate) fn clear() {
+    if let Ok(mut c) = cache().lock() {
+        c.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expired_entries_miss() {
+        let mut c = ResponseCache::new(Duration::from_millis(0), 4);
+        c.insert("k".into(), "v".into());
+        assert_eq!(c.get("k"), None);
+
+        let mut c = ResponseCache::new(TTL, 4);
+        c.insert("k".into(), "v".into());
+        assert_eq!(c.get("k").as_deref(), Some("v"));
+    }
+
+    #[test]
+    fn full_cache_evicts_oldest() {
+        let mut c = ResponseCache::new(TTL, 2);
+        c.insert("a".into(), "1".into());
+        c.insert("b".into(), "2".into());
+        c.insert("c".into(), "3".into());
+        assert_eq!(c.get("a"), None);
+        assert_eq!(c.get("c").as_deref(), Some("3"));
+    }
+
+    #[test]
+    fn key_separates_fields() {
+        assert_ne!(key("p", "m", "ab", "c"), key("p", "m", "a", "bc"));
+        assert_eq!(key("p", "m", "s", "u"), key("p", "m", "s", "u"));
+    }
+}
@@ -0,0 +1,415 @@
+//! Coordination for concurrent AI requests.
+//!
+//! Every outbound model call goes through `manager()`. Requests wait in a
+//! per-provider priority queue until a slot is free (local models get one
+//! slot, hosted providers a few), identical in-flight completions share a
+//! single upstream call, and each request gets an id that the frontend can
+//! list and cancel. Each request is also listed as a background operation.
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use tokio::sync::{broadcast, oneshot, watch};
+
+use crate::modules::operations::{self, Operation, OperationKind};
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    Low,
+    Normal,
+    High,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct AiRequestInfo {
+    pub id: u64,
+    pub provider: String,
+    pub kind: String,
+    pub priority: Priority,
+    /// `queued` or `running`.
+    pub state: String,
+    /// Unix milliseconds when the request was submitted.
+    pub submitted_at: u64,
+}
+
+fn concurrency_limit(provider: &str) -> usize {
+    match provider {
+        // A local server usually runs one model at a time; parallel calls
+        // just thrash it.
+        "local" => 1,
+        _ => 4,
+    }
+}
+
+// ── Queue ──
+
+struct Waiter {
+    priority: Priority,
+    seq: u64,
+    wake: oneshot::Sender<()>,
+}
+
+impl PartialEq for Waiter {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority && self.seq == other.seq
+    }
+}
+impl Eq for Waiter {}
+impl PartialOrd for Waiter {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Waiter {
+    /// Higher priority first, then FIFO within a priority.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority.cmp(&other.priority).then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+#[derive(Default)]
+struct ProviderQueue {
+    running: usize,
+    waiting: BinaryHeap<Waiter>,
+}
+
+struct Active {
+    info: AiRequestInfo,
+    cancel: watch::Sender<bool>,
+}
+
+type SharedResult = Result<String, String>;
+
+#[derive(Default)]
+struct Inner {
+    next_id: u64,
+    next_seq: u64,
+    providers: HashMap<String, ProviderQueue>,
+    active: HashMap<u64, Active>,
+    inflight: HashMap<String, broadcast::Sender<SharedResult>>,
+}
+
+pub(crate) struct RequestManager {
+    inner: Mutex<Inner>,
+}
+
+pub(crate) fn manager() -> &'static RequestManager {
+    static MANAGER: OnceLock<RequestManager> = OnceLock::new();
+    MANAGER.get_or_init(|| RequestManager { inner: Mutex::new(Inner::default()) })
+}
+
+/// Holds a request's registration and, once running, its provider slot.
+/// Dropping it frees both, so a request whose future is dropped mid-flight
+/// can't leak a slot.
+struct Ticket<'a> {
+    manager: &'a RequestManager,
+    id: u64,
+    provider: String,
+    holds_slot: bool,
+}
+
+impl Drop for Ticket<'_> {
+    fn drop(&mut self) {
+        let mut inner = self.manager.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.active.remove(&self.id);
+        if self.holds_slot {
+            release_slot(&mut inner, &self.provider);
+        }
+    }
+}
+
+/// Marks the caller leading a shared request. Dropping it mid-flight
+/// removes the request's entry, closing the channel so its followers get
+/// an error instead of waiting forever. Once the leader has handed out its
+/// result it is disarmed, since the entry under `key` may by then belong
+/// to the next leader.
+struct Leader<'a> {
+    manager: &'a RequestManager,
+    key: &'a str,
+    armed: bool,
+}
+
+impl Drop for Leader<'_> {
+    fn drop(&mut self) {
+        if self.armed {
+            self.manager.lock().inflight.remove(self.key);
+        }
+    }
+}
+
+/// Resolves once the request is cancelled from either side, marking the
+/// operation cancelled too.
+async fn cancelled(cancel_rx: &mut watch::Receiver<bool>, op: &Operation) {
+    tokio::select! {
+        Ok(_) = cancel_rx.wait_for(|c| *c) => op.cancel(),
+        _ = op.cancelled() => {}
+    }
+}
+
+/// Hand the slot to the best waiter that is still listening, or free it.
+fn release_slot(inner: &mut Inner, provider: &str) {
+    let Some(queue) = inner.providers.get_mut(provider) else { return };
+    while let Some(waiter) = queue.waiting.pop() {
+        if waiter.wake.send(()).is_ok() {
+            return;
+        }
+    }
+    queue.running = queue.running.saturating_sub(1);
+}
+
+impl RequestManager {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Register a request and wait for a provider slot. Returns the ticket
+    /// and a receiver that flips to `true` on cancellation.
+    async fn acquire(
+        &self,
+        provider: &str,
+        kind: &str,
+        priority: Priority,
+        op: &Operation,
+    ) -> Result<(Ticket<'_>, watch::Receiver<bool>), String> {
+        let (cancel_tx, mut cancel_rx) = watch::channel(false);
+        let (mut ticket, wait) = {
+            let mut inner = self.lock();
+            inner.next_id += 1;
+            let id = inner.next_id;
+            let submitted_at = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64;
+            inner.active.insert(id, Active {
+                info: AiRequestInfo {
+                    id,
+                    provider: provider.to_string(),
+                    kind: kind.to_string(),
+                    priority,
+                    state: "queued".to_string(),
+                    submitted_at,
+                },
+                cancel: cancel_tx,
+            });
+            inner.next_seq += 1;
+            let seq = inner.next_seq;
+            let limit = concurrency_limit(provider);
+            let queue = inner.providers.entry(provider.to_string()).or_default();
+            let wait = if queue.running < limit {
+                queue.running += 1;
+                None
+            } else {
+                let (wake, rx) = oneshot::channel();
+                queue.waiting.push(Waiter { priority, seq, wake });
+                Some(rx)
+            };
+            let ticket = Ticket { manager: self, id, provider: provider.to_string(), holds_slot: wait.is_none() };
+            (ticket, wait)
+        };
+
+        if let Some(mut rx) = wait {
+            tokio::select! {
+                _ = &mut rx => {}
+                _ = cancelled(&mut cancel_rx, op) => {
+                    // The slot may have been handed over just as we gave up.
+                    ticket.holds_slot = rx.try_recv().is_ok();
+                    return Err("Request cancelled".to_string());
+                }
+            }
+            ticket.holds_slot = true;
+        }
+        if let Some(active) = self.lock().active.get_mut(&ticket.id) {
+            active.info.state = "running".to_string();
+        }
+        Ok((ticket, cancel_rx))
+    }
+
+    /// Run `fut` once a slot for `provider` is free, aborting it if the
+    /// request is cancelled through `cancel_ai_request` or, as a background
+    /// operation, through `cancel_operation`.
+    pub(crate) async fn run<T, F>(&self, provider: &str, kind: &str, priority: Priority, fut: F) -> Result<T, String>
+    where
+        F: Future<Output = Result<T, String>>,
+    {
+        let op = operations::manager().start(OperationKind::Ai, &format!("{} ({})", kind, provider), None, true);
+        let result = async {
+            let (_ticket, mut cancel_rx) = self.acquire(provider, kind, priority, &op).await?;
+            tokio::select! {
+                result = fut => result,
+                _ = cancelled(&mut cancel_rx, &op) => Err("Request cancelled".to_string()),
+            }
+        }
+        .await;
+        op.track(result)
+    }
+
+    /// Like `run`, but callers passing the same `key` while a request is in
+    /// flight wait for that request's result instead of issuing another.
+    pub(crate) async fn run_shared<F>(
+        &self,
+        provider: &str,
+        kind: &str,
+        priority: Priority,
+        key: String,
+        fut: F,
+    ) -> SharedResult
+    where
+        F: Future<Output = SharedResult>,
+    {
+        let existing = {
+            let mut inner = self.lock();
+            match inner.inflight.get(&key) {
+                Some(tx) => Some(tx.subscribe()),
+                None => {
+                    inner.inflight.insert(key.clone(), broadcast::channel(1).0);
+                    None
+                }
+            }
+        };
+        if let Some(mut rx) = existing {
+            return rx.recv().await.unwrap_or_else(|_| Err("Request cancelled".to_string()));
+        }
+
+        let mut leader = Leader { manager: self, key: &key, armed: true };
+        let result = self.run(provider, kind, priority, fut).await;
+        // Removing and sending under one lock, so a caller either subscribes
+        // in time to get the result or becomes the next leader.
+        if let Some(tx) = self.lock().inflight.remove(&key) {
+            let _ = tx.send(result.clone());
+        }
+        leader.armed = false;
+        result
+    }
+
+    pub(crate) fn list(&self) -> Vec<AiRequestInfo> {
+        let mut list: Vec<AiRequestInfo> = self.lock().active.values().map(|a| a.info.clone()).collect();
+        list.sort_by_key(|r| r.id);
+        list
+    }
+
+    pub(crate) fn cancel(&self, id: u64) -> bool {
+        match self.lock().active.get(&id) {
+            Some(active) => active.cancel.send(true).is_ok(),
+            None => false,
+        }
+    }
+}
+
+// ── Commands ──
+
+#[tauri::command]
+pub fn list_active_ai_requests() -> Vec<AiRequestInfo> {
+    manager().list()
+}
+
+/// Cancel a queued or running request. Returns false if it already finished.
+#[tauri::command]
+pub fn cancel_ai_request(id: u64) -> bool {
+    manager().cancel(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn fresh() -> &'static RequestManager {
+        Box::leak(Box::new(RequestManager { inner: Mutex::new(Inner::default()) }))
+    }
+
+    #[tokio::test]
+    async fn local_provider_runs_one_at_a_time() {
+        let m = fresh();
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let task = |running: Arc<AtomicUsize>, peak: Arc<AtomicUsize>| async move {
+            let now = running.fetch_add(1, AtomicOrdering::SeqCst) + 1;
+            peak.fetch_max(now, AtomicOrdering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            running.fetch_sub(1, AtomicOrdering::SeqCst);
+            Ok::<_, String>(())
+        };
+        let (a, b, c) = tokio::join!(
+            m.run("local", "t", Priority::Normal, task(running.clone(), peak.clone())),
+            m.run("local", "t", Priority::Normal, task(running.clone(), peak.clone())),
+            m.run("local", "t", Priority::Normal, task(running.clone(), peak.clone())),
+        );
+        assert!(a.is_ok() && b.is_ok() && c.is_ok());
+        assert_eq!(peak.load(AtomicOrdering::SeqCst), 1);
+        assert!(m.list().is_empty());
+    }
+
+    #[tokio::test]
+    async fn identical_requests_share_one_call() {
+        let m = fresh();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let call = |calls: Arc<AtomicUsize>| async move {
+            calls.fetch_add(1, AtomicOrdering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Ok("answer".to_string())
+        };
+        let (a, b) = tokio::join!(
+            m.run_shared("openai", "t", Priority::Normal, "k".into(), call(calls.clone())),
+            m.run_shared("openai", "t", Priority::Normal, "k".into(), call(calls.clone())),
+        );
+        assert_eq!(a.unwrap(), "answer");
+        assert_eq!(b.unwrap(), "answer");
+        assert_eq!(calls.load(AtomicOrdering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn followers_of_a_dropped_leader_get_an_error() {
+        let m = fresh();
+        let leader = m.run_shared("openai", "t", Priority::Normal, "k".into(), async {
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            Ok("never".to_string())
+        });
+        let follower = m.run_shared("openai", "t", Priority::Normal, "k".into(), async { Ok("again".to_string()) });
+        let (_, result) = tokio::join!(tokio::time::timeout(Duration::from_millis(20), leader), follower);
+        assert_eq!(result, Err("Request cancelled".to_string()));
+        assert!(m.lock().inflight.is_empty());
+        let next = m.run_shared("openai", "t", Priority::Normal, "k".into(), async { Ok("again".to_string()) });
+        assert_eq!(next.await.unwrap(), "again");
+    }
+
+    #[test]
+    fn a_finished_leader_leaves_the_next_leaders_entry() {
+        let m = fresh();
+        let leader = Leader { manager: m, key: "k", armed: false };
+        m.lock().inflight.insert("k".into(), broadcast::channel(1).0);
+        drop(leader);
+        assert!(m.lock().inflight.contains_key("k"));
+    }
+
+    #[tokio::test]
+    async fn cancel_aborts_a_running_request() {
+        let m = fresh();
+        let pending = m.run("openai", "t", Priority::Normal, async {
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            Ok::<_, String>(())
+        });
+        let canceller = async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            let id = m.list()[0].id;
+            assert!(m.cancel(id));
+        };
+        let (result, _) = tokio::join!(pending, canceller);
+        assert_eq!(result, Err("Request cancelled".to_string()));
+        assert!(m.list().is_empty());
+    }
+
+    #[test]
+    fn waiters_pop_by_priority_then_fifo() {
+        let mut heap = BinaryHeap::new();
+        for (priority, seq) in [(Priority::Low, 1), (Priority::High, 2), (Priority::Normal, 3), (Priority::High, 4)] {
+            heap.push(Waiter { priority, seq, wake: oneshot::channel().0 });
+        }
+        let order: Vec<u64> = std::iter::from_fn(|| heap.pop().map(|w| w.seq)).collect();
+        assert_eq!(order, vec![2, 4, 3, 1]);
+    }
+}
@@ -2,40 +2,44 @@ use keyring::Entry;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, OnceLock};
-use std::time::Duration;
-use tauri::{AppHandle, Emitter};
+use std::sync::Arc;
+use tauri::AppHandle;
 use tokio::sync::Mutex;
-
-mod key_store;
-
-/// Shared HTTP client for non-streaming requests (with timeout).
-fn http_client() -> &'static reqwest::Client {
-    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
-    CLIENT.get_or_init(|| {
-        reqwest::Client::builder()
-            .pool_idle_timeout(Some(Duration::from_secs(90)))
-            .pool_max_idle_per_host(4)
-            .tcp_keepalive(Some(Duration::from_secs(30)))
-            .connect_timeout(Duration::from_secs(15))
-            .timeout(Duration::from_secs(120))
-            .build()
-            .expect("reqwest client build")
-    })
-}
-
-/// Shared HTTP client for streaming requests (no overall timeout).
-fn http_client_streaming() -> &'static reqwest::Client {
-    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
-    CLIENT.get_or_init(|| {
-        reqwest::Client::builder()
-            .pool_idle_timeout(Some(Duration::from_secs(90)))
-            .pool_max_idle_per_host(4)
-            .tcp_keepalive(Some(Duration::from_secs(30)))
-            .connect_timeout(Duration::from_secs(15))
-            .build()
-            .expect("reqwest streaming client build")
-    })
+use ts_rs::TS;
+
+use crate::modules::events;
+use crate::modules::fs::ProjectRootState;
+use crate::modules::net;
+use crate::modules::policy::Category;
+use crate::modules::knowledge::semantic::{self, Citation};
+use manager::{manager, Priority};
+
+pub mod agent;
+pub mod assist;
+mod cache;
+pub(crate) mod key_store;
+pub mod manager;
+
+/// Policy categories of the AI commands, here and in the submodules.
+pub(crate) const COMMAND_CATEGORIES: &[(&str, &[Category])] = &[
+    ("ai_chat", &[Category::Ai]),
+    ("ai_chat_stream", &[Category::Ai]),
+    ("ai_document_symbol", &[Category::Ai]),
+    ("ai_resolve_conflict", &[Category::Ai]),
+    ("ai_review_changes", &[Category::Ai]),
+    ("ai_quick_action", &[Category::Ai]),
+    ("ai_run_agent", &[Category::Ai, Category::Terminal]),
+];
+
+/// HTTP client for non-streaming requests (with timeout), honoring the
+/// user's proxy and CA settings.
+fn http_client() -> reqwest::Client {
+    net::client()
+}
+
+/// HTTP client for streaming requests (no overall timeout).
+fn http_client_streaming() -> reqwest::Client {
+    net::streaming_client()
 }
 
 const SERVICE_NAME: &str = "leo-ide";
@@ -104,7 +108,7 @@ fn derive_machine_file_key() -> [u8; key_store::KEY_SIZE] {
     k
 }
 
-fn get_key(provider: &str) -> Result<Option<String>, String> {
+pub(crate) fn get_key(provider: &str) -> Result<Option<String>, String> {
     if let Ok(entry) = Entry::new(SERVICE_NAME, provider) {
         if let Ok(pw) = entry.get_password() {
             if !pw.is_empty() {
@@ -125,7 +129,7 @@ fn get_key(provider: &str) -> Result<Option<String>, String> {
     }
 }
 
-fn set_key(provider: &str, key: &str) -> Result<(), String> {
+pub(crate) fn set_key(provider: &str, key: &str) -> Result<(), String> {
     let file_key = get_or_create_file_key()?;
     let dir = keys_dir();
 
@@ -249,15 +253,29 @@ pub struct AiStreamRequest {
     pub provider: Option<String>,
     pub session_id: String,
     pub tools: Option<Value>,
+    /// Attach semantically retrieved project code to this turn.
+    #[serde(default)]
+    pub retrieval: Option<RetrievalOptions>,
+}
+
+#[derive(Deserialize)]
+pub struct RetrievalOptions {
+    pub project_root: String,
+    pub top_k: Option<usize>,
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Clone, TS)]
 pub struct StreamChunk {
     pub session_id: String,
     pub delta: String,
     pub done: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub tool_calls: Option<Value>,
+    /// Retrieved chunks used for this turn; sent once, on the final chunk.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub citations: Option<Vec<Citation>>,
 }
 
 const SYSTEM_PROMPT: &str = "You are an AI coding assistant embedded in a lightweight IDE called leo. \
@@ -278,16 +296,96 @@ impl AiState {
     }
 }
 
+/// Resolve the credential for `provider`. For the local provider the key
+/// slot holds the server base URL, defaulting to Ollama's port.
+fn resolve_api_key(provider: &str) -> Result<String, String> {
+    if provider == "local" {
+        Ok(get_key(provider)?.unwrap_or_else(|| "http://localhost:11434".to_string()))
+    } else {
+        get_key(provider)?.ok_or_else(|| format!("No API key configured for {}.", display_provider(provider)))
+    }
+}
+
+/// Hosted providers need the network; a local model server doesn't.
+fn ensure_provider_reachable(provider: &str) -> Result<(), String> {
+    if provider == "local" {
+        Ok(())
+    } else {
+        net::ensure_online(&format!("{} requests", display_provider(provider)))
+    }
+}
+
+/// Interactive actions jump the queue ahead of background work.
+fn priority_for(kind: &str) -> Priority {
+    match kind {
+        "chat" | "quick_action" | "document" => Priority::High,
+        "review" | "resolve_conflict" => Priority::Normal,
+        _ => Priority::Low,
+    }
+}
+
+/// One-shot, non-streaming completion for backend-driven AI actions
+/// (doc generation, conflict resolution, review, …). Provider and model
+/// fall back to the same defaults as `ai_chat`. `kind` labels the request
+/// in the request manager and decides its queue priority; identical
+/// concurrent calls share one upstream request.
+pub(crate) async fn complete(
+    kind: &str,
+    provider: Option<&str>,
+    model: Option<&str>,
+    system: &str,
+    user: String,
+) -> Result<String, String> {
+    let provider = provider.unwrap_or("openrouter").to_lowercase();
+    ensure_provider_reachable(&provider)?;
+    let api_key = resolve_api_key(&provider)?;
+    let model = model.map(str::to_string).unwrap_or_else(|| default_model(&provider));
+    let key = cache::key(&provider, &model, system, &user);
+    let messages = vec![
+        ChatMessageInput { role: "system".into(), content: system.into(), tool_call_id: None },
+        ChatMessageInput { role: "user".into(), content: user, tool_call_id: None },
+    ];
+    manager()
+        .run_shared(&provider, kind, priority_for(kind), key, call_blocking(&provider, &api_key, &model, &messages))
+        .await
+}
+
+/// `complete` with the response cache in front of it, for actions whose
+/// output should be identical for identical input (explain, document, …).
+/// `bypass` skips the lookup but still refreshes the cached entry.
+pub(crate) async fn complete_cached(
+    kind: &str,
+    provider: Option<&str>,
+    model: Option<&str>,
+    system: &str,
+    user: String,
+    bypass: bool,
+) -> Result<String, String> {
+    let provider = provider.unwrap_or("openrouter").to_lowercase();
+    let model = model.map(str::to_string).unwrap_or_else(|| default_model(&provider));
+    let key = cache::key(&provider, &model, system, &user);
+    if !bypass {
+        if let Some(hit) = cache::get(&key) {
+            return Ok(hit);
+        }
+    }
+    let reply = complete(kind, Some(&provider), Some(&model), system, user).await?;
+    cache::insert(key, reply.clone());
+    Ok(reply)
+}
+
+#[tauri::command]
+pub fn ai_clear_cache() {
+    cache::clear();
+}
+
 // ── Legacy blocking command (kept for backward compat) ──
 
 #[tauri::command]
 pub async fn ai_chat(request: AiRequest) -> Result<String, String> {
     let provider = request.provider.clone().unwrap_or_else(|| "openrouter".to_string()).to_lowercase();
-    let api_key = if provider == "local" {
-        get_key(&provider)?.unwrap_or_else(|| "http://localhost:11434".to_string())
-    } else {
-        get_key(&provider)?.ok_or_else(|| format!("No API key configured for {}.", display_provider(&provider)))?
-    };
+    ensure_provider_reachable(&provider)?;
+    let api_key = resolve_api_key(&provider)?;
 
     let user_content = match &request.context {
         Some(ctx) if !ctx.is_empty() => format!("Code context:\n```\n{}\n```\n\n{}", ctx, request.prompt),
@@ -300,27 +398,45 @@ pub async fn ai_chat(request: AiRequest) -> Result<String, String> {
     ];
 
     let model = request.model.unwrap_or_else(|| default_model(&provider));
-    call_blocking(&provider, &api_key, &model, &messages).await
+    manager()
+        .run(&provider, "chat", Priority::High, call_blocking(&provider, &api_key, &model, &messages))
+        .await
 }
 
 // ── Streaming command ──
 
 #[tauri::command]
 pub async fn ai_chat_stream(
-    request: AiStreamRequest,
+    window: tauri::WebviewWindow,
+    mut request: AiStreamRequest,
     app: AppHandle,
     state: tauri::State<'_, Arc<AiState>>,
+    root_state: tauri::State<'_, ProjectRootState>,
 ) -> Result<(), String> {
     let provider = request.provider.clone().unwrap_or_else(|| "openrouter".to_string()).to_lowercase();
-    let api_key = if provider == "local" {
-        get_key(&provider)?.unwrap_or_else(|| "http://localhost:11434".to_string())
-    } else {
-        get_key(&provider)?.ok_or_else(|| format!("No API key configured for {}.", display_provider(&provider)))?
-    };
+    ensure_provider_reachable(&provider)?;
+    let api_key = resolve_api_key(&provider)?;
     let model = request.model.unwrap_or_else(|| default_model(&provider));
     let session_id = request.session_id.clone();
     let tools = request.tools.clone();
 
+    // Retrieval is best-effort: a disabled or empty index just means the
+    // turn goes out without extra context.
+    let mut citations = None;
+    if let Some(opts) = &request.retrieval {
+        let prompt = request.messages.iter().rev().find(|m| m.role == "user").map(|m| m.content.clone());
+        if let Some(prompt) = prompt {
+            match semantic::retrieve_context(window.label(), &opts.project_root, &prompt, opts.top_k, &root_state).await {
+                Ok(retrieved) if !retrieved.citations.is_empty() => {
+                    attach_context(&mut request.messages, &retrieved.context);
+                    citations = Some(retrieved.citations);
+                }
+                Ok(_) => {}
+                Err(e) => log::debug!("Skipping retrieval: {}", e),
+            }
+        }
+    }
+
     // Set up cancellation
     let (cancel_tx, cancel_rx) = tokio::sync::watch::channel(false);
     {
@@ -332,7 +448,14 @@ pub async fn ai_chat_stream(
     let sid = session_id.clone();
 
     tokio::spawn(async move {
-        let result = stream_response(&provider, &api_key, &model, &request.messages, &tools, &app, &sid, cancel_rx).await;
+        let result = manager()
+            .run(
+                &provider,
+                "chat",
+                Priority::High,
+                stream_response(&provider, &api_key, &model, &request.messages, &tools, &app, &sid, cancel_rx),
+            )
+            .await;
 
         // Clean up cancel token
         {
@@ -343,11 +466,11 @@ pub async fn ai_chat_stream(
         // Emit done or error
         match result {
             Ok(()) => {
-                let _ = app.emit("ai-stream-chunk", StreamChunk { session_id: sid, delta: String::new(), done: true, tool_calls: None });
+                events::AI_STREAM_CHUNK.emit(&app, StreamChunk { session_id: sid, delta: String::new(), done: true, tool_calls: None, citations });
             }
             Err(e) => {
-                let _ = app.emit("ai-stream-chunk", StreamChunk { session_id: sid.clone(), delta: format!("Error: {}", e), done: false, tool_calls: None });
-                let _ = app.emit("ai-stream-chunk", StreamChunk { session_id: sid, delta: String::new(), done: true, tool_calls: None });
+                events::AI_STREAM_CHUNK.emit(&app, StreamChunk { session_id: sid.clone(), delta: format!("Error: {}", e), done: false, tool_calls: None, citations: None });
+                events::AI_STREAM_CHUNK.emit(&app, StreamChunk { session_id: sid, delta: String::new(), done: true, tool_calls: None, citations });
             }
         }
     });
@@ -367,6 +490,18 @@ pub async fn ai_chat_cancel(
     Ok(())
 }
 
+/// Add retrieved context to the system prompt, or as a leading system
+/// message when the conversation has none.
+fn attach_context(messages: &mut Vec<ChatMessageInput>, context: &str) {
+    match messages.iter_mut().find(|m| m.role == "system") {
+        Some(system) => {
+            system.content.push_str("\n\n");
+            system.content.push_str(context);
+        }
+        None => messages.insert(0, ChatMessageInput { role: "system".into(), content: context.into(), tool_call_id: None }),
+    }
+}
+
 // ── Streaming implementation ──
 
 async fn stream_response(
@@ -432,11 +567,12 @@ async fn stream_response(
                                     let tool_calls = extract_tool_calls(&parsed, provider);
 
                                     if !delta.is_empty() || tool_calls.is_some() {
-                                        let _ = app.emit("ai-stream-chunk", StreamChunk {
+                                        events::AI_STREAM_CHUNK.emit(app, StreamChunk {
                                             session_id: session_id.to_string(),
                                             delta,
                                             done: false,
                                             tool_calls,
+                                            citations: None,
                                         });
                                     }
 
@@ -0,0 +1,426 @@
+//! Code screenshots: a snippet drawn as a styled SVG.
+//!
+//! `render_code_image` lays a file, or text passed in, out in a window
+//! frame with a title bar, line numbers and a drop shadow in the colours
+//! of one of the editor themes, like silicon or carbon. Rust, JavaScript,
+//! TypeScript and Python are highlighted from their tree-sitter parse;
+//! other languages are drawn in the theme's text colour. Layout assumes a
+//! monospace font with the usual 0.6em advance, so no font metrics are
+//! needed here. The frontend draws the SVG onto a canvas when it wants a
+//! PNG, since the backend has no font rasterizer.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::path::Path;
+use tree_sitter::{Node, Parser};
+
+use crate::modules::fs::{validate_path, ProjectRootState};
+use crate::modules::search::read_text;
+use crate::modules::symbols::language_for_extension;
+
+const MAX_LINES: usize = 400;
+/// Longer lines are cut and end in `…`.
+const MAX_COLUMNS: usize = 160;
+const TAB_WIDTH: usize = 4;
+const FONT_SIZE: f32 = 14.0;
+const CHAR_WIDTH: f32 = FONT_SIZE * 0.6;
+const LINE_HEIGHT: f32 = 21.0;
+/// Room around the window for its shadow.
+const MARGIN: f32 = 32.0;
+const PADDING: f32 = 20.0;
+const TITLE_BAR: f32 = 36.0;
+const MIN_WINDOW_WIDTH: f32 = 320.0;
+const FONT_FAMILY: &str = "'JetBrains Mono', 'Fira Code', Menlo, Consolas, 'DejaVu Sans Mono', monospace";
+
+/// 1-based, inclusive.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct LineRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct CodeImageOptions {
+    /// Defaults to true.
+    pub line_numbers: Option<bool>,
+    /// Title bar text; defaults to the file name.
+    pub title: Option<String>,
+    /// File extension picking the highlighter for `content` without a
+    /// `path`, e.g. `rs` or `ts`.
+    pub language: Option<String>,
+    /// Remove indentation shared by all lines. Defaults to true.
+    pub dedent: Option<bool>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CodeImage {
+    pub svg: String,
+    pub width: u32,
+    pub height: u32,
+    /// True when only the first `MAX_LINES` lines were drawn.
+    pub truncated: bool,
+}
+
+// ── Themes ──
+
+/// The colours of an editor theme, as the frontend's editor uses them.
+struct Theme {
+    background: &'static str,
+    foreground: &'static str,
+    gutter: &'static str,
+    keyword: &'static str,
+    string: &'static str,
+    comment: &'static str,
+    function: &'static str,
+    number: &'static str,
+    type_name: &'static str,
+    operator: &'static str,
+}
+
+fn theme(id: &str) -> Result<Theme, String> {
+    let t = |c: [&'static str; 10]| Theme {
+        background: c[0],
+        foreground: c[1],
+        gutter: c[2],
+        keyword: c[3],
+        string: c[4],
+        comment: c[5],
+        function: c[6],
+        number: c[7],
+        type_name: c[8],
+        operator: c[9],
+    };
+    Ok(match id {
+        "one-dark" => t(["#282c34", "#abb2bf", "#636d83", "#c678dd", "#98c379", "#7f848e", "#61afef", "#d19a66", "#e5c07b", "#56b6c2"]),
+        "dracula" => t(["#282a36", "#f8f8f2", "#6272a4", "#ff79c6", "#f1fa8c", "#6272a4", "#50fa7b", "#bd93f9", "#8be9fd", "#ff79c6"]),
+        "github-dark" => t(["#0d1117", "#e6edf3", "#6e7681", "#ff7b72", "#a5d6ff", "#8b949e", "#d2a8ff", "#79c0ff", "#79c0ff", "#ff7b72"]),
+        "tokyo-night" => t(["#1a1b26", "#c0caf5", "#565f89", "#bb9af7", "#9ece6a", "#565f89", "#7aa2f7", "#ff9e64", "#2ac3de", "#89ddff"]),
+        "nord" => t(["#2e3440", "#d8dee9", "#616e88", "#81a1c1", "#a3be8c", "#616e88", "#88c0d0", "#b48ead", "#8fbcbb", "#81a1c1"]),
+        "catppuccin-mocha" => t(["#1e1e2e", "#cdd6f4", "#6c7086", "#cba6f7", "#a6e3a1", "#6c7086", "#89b4fa", "#fab387", "#94e2d5", "#89dceb"]),
+        "rose-pine" => t(["#191724", "#e0def4", "#6e6a86", "#c4a7e7", "#f6c177", "#6e6a86", "#9ccfd8", "#ebbcba", "#9ccfd8", "#31748f"]),
+        "plum-dark" => t(["#15121A", "#E8E2D5", "#6A6080", "#C79BBF", "#8EA88A", "#6A6080", "#D4C697", "#C9956A", "#C79B78", "#A8A09C"]),
+        "github-light" => t(["#ffffff", "#24292e", "#8b949e", "#cf222e", "#0a3069", "#6e7781", "#8250df", "#0550ae", "#0550ae", "#cf222e"]),
+        "catppuccin-latte" => t(["#eff1f5", "#4c4f69", "#8c8fa1", "#8839ef", "#40a02b", "#8c8fa1", "#1e66f5", "#fe640b", "#179299", "#04a5e5"]),
+        "solarized-light" => t(["#fdf6e3", "#657b83", "#93a1a1", "#859900", "#2aa198", "#93a1a1", "#268bd2", "#d33682", "#b58900", "#859900"]),
+        "plum-light" => t(["#F5EFE2", "#2A2018", "#B0A48A", "#7A3A6A", "#4A6B3E", "#8A7E6A", "#7A5A14", "#8A4A1E", "#6E3E1A", "#5E5346"]),
+        other => return Err(format!("Unknown theme: {}", other)),
+    })
+}
+
+// ── Highlighting ──
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Role {
+    Keyword,
+    String,
+    Comment,
+    Function,
+    Number,
+    Type,
+    Operator,
+}
+
+impl Role {
+    fn color(self, theme: &Theme) -> &'static str {
+        match self {
+            Role::Keyword => theme.keyword,
+            Role::String => theme.string,
+            Role::Comment => theme.comment,
+            Role::Function => theme.function,
+            Role::Number => theme.number,
+            Role::Type => theme.type_name,
+            Role::Operator => theme.operator,
+        }
+    }
+}
+
+/// Whether `node` is the `field` child of `parent`.
+fn is_field(parent: Node, field: &str, node: Node) -> bool {
+    parent.child_by_field_name(field) == Some(node)
+}
+
+/// The callee of a call: `f` in `f()`, `Vec::new`, `a.f` and `m!`.
+fn is_callee(node: Node) -> bool {
+    node.parent().is_some_and(|p| {
+        (matches!(p.kind(), "call_expression" | "call" | "new_expression") && (is_field(p, "function", node) || is_field(p, "constructor", node)))
+            || (p.kind() == "macro_invocation" && is_field(p, "macro", node))
+    })
+}
+
+fn classify(node: Node) -> Option<Role> {
+    let kind = node.kind();
+    if !node.is_named() {
+        if kind.len() > 1 && kind.chars().all(|c| c.is_ascii_lowercase() || c == '_') {
+            return Some(Role::Keyword);
+        }
+        if kind.chars().all(|c| "+-*/%=!<>&|^~?".contains(c)) {
+            return Some(Role::Operator);
+        }
+        return None;
+    }
+    if kind.contains("comment") {
+        return Some(Role::Comment);
+    }
+    match kind {
+        "string" | "string_literal" | "raw_string_literal" | "char_literal" | "template_string" | "concatenated_string" => {
+            return Some(Role::String)
+        }
+        "number" | "integer" | "float" | "integer_literal" | "float_literal" | "boolean_literal" | "true" | "false" | "null"
+        | "undefined" | "none" => return Some(Role::Number),
+        "this" | "self" | "super" | "crate" => return Some(Role::Keyword),
+        "type_identifier" | "primitive_type" | "predefined_type" => return Some(Role::Type),
+        _ => {}
+    }
+    if !kind.ends_with("identifier") {
+        return None;
+    }
+    let parent = node.parent()?;
+    let definition = matches!(
+        parent.kind(),
+        "function_item" | "function_signature_item" | "function_declaration" | "generator_function_declaration" | "function_definition"
+            | "method_definition"
+    );
+    if definition && is_field(parent, "name", node) {
+        return Some(Role::Function);
+    }
+    if matches!(parent.kind(), "class_declaration" | "class_definition" | "class") && is_field(parent, "name", node) {
+        return Some(Role::Type);
+    }
+    // `a.f()`, `a::f()`: the last part of a path that is called.
+    let member = ["property", "field", "attribute", "name"].iter().any(|f| is_field(parent, f, node))
+        && matches!(parent.kind(), "member_expression" | "field_expression" | "attribute" | "scoped_identifier");
+    (is_callee(node) || member && is_callee(parent)).then_some(Role::Function)
+}
+
+/// The role of every byte of `source`, from a tree-sitter parse when the
+/// language has a grammar.
+fn highlight(source: &str, ext: &str) -> Vec<Option<Role>> {
+    let mut roles = vec![None; source.len()];
+    let Some(language) = language_for_extension(ext) else { return roles };
+    let mut parser = Parser::new();
+    if parser.set_language(&language).is_err() {
+        return roles;
+    }
+    let Some(tree) = parser.parse(source, None) else { return roles };
+    let mut cursor = tree.walk();
+    loop {
+        let node = cursor.node();
+        let role = classify(node);
+        if let Some(role) = role {
+            roles[node.byte_range()].fill(Some(role));
+        }
+        // Strings, comments, literals and types are coloured as a whole.
+        let leaf = matches!(role, Some(Role::String | Role::Comment | Role::Number | Role::Type));
+        if !leaf && cursor.goto_first_child() {
+            continue;
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return roles;
+            }
+        }
+    }
+}
+
+// ── Layout ──
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// The lines to draw, after `range`, the line cap and dedenting.
+struct Selection {
+    /// Number of the first line.
+    first: usize,
+    /// Byte range of each line, without its line break.
+    lines: Vec<(usize, usize)>,
+    truncated: bool,
+}
+
+fn select_lines(source: &str, range: Option<LineRange>, dedent: bool) -> Result<Selection, String> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    for line in source.split_inclusive('\n') {
+        let text = line.trim_end_matches('\n').trim_end_matches('\r');
+        lines.push((start, start + text.len()));
+        start += line.len();
+    }
+    if lines.is_empty() {
+        lines.push((0, 0));
+    }
+    let (first, last) = match range {
+        Some(r) if r.start == 0 || r.start > r.end || r.start > lines.len() => return Err("Invalid line range".to_string()),
+        Some(r) => (r.start, r.end.min(lines.len())),
+        None => (1, lines.len()),
+    };
+    let truncated = last - first + 1 > MAX_LINES;
+    let mut lines = lines[first - 1..last.min(first - 1 + MAX_LINES)].to_vec();
+    if dedent {
+        let text = |&(s, e): &(usize, usize)| &source[s..e];
+        let code = || lines.iter().map(text).filter(|l| !l.trim().is_empty());
+        let prefix = code().map(|l| &l[..l.len() - l.trim_start_matches([' ', '\t']).len()]).min_by_key(|p| p.len()).unwrap_or("");
+        // Mixed tabs and spaces are left alone.
+        if code().all(|l| l.starts_with(prefix)) {
+            let cut = prefix.len();
+            for (s, e) in lines.iter_mut() {
+                *s += cut.min(*e - *s);
+            }
+        }
+    }
+    Ok(Selection { first, lines, truncated })
+}
+
+/// A line as runs of text and colour, tabs expanded and cut to
+/// `MAX_COLUMNS`. Returns the runs and the line's width in columns.
+fn runs<'a>(source: &str, (start, end): (usize, usize), roles: &[Option<Role>], theme: &'a Theme) -> (Vec<(String, &'a str)>, usize) {
+    let mut runs: Vec<(String, &str)> = Vec::new();
+    let mut columns = 0;
+    for (i, c) in source[start..end].char_indices() {
+        if columns >= MAX_COLUMNS {
+            runs.push(("…".to_string(), theme.gutter));
+            columns += 1;
+            break;
+        }
+        let color = roles[start + i].map_or(theme.foreground, |r| r.color(theme));
+        let text = if c == '\t' { " ".repeat(TAB_WIDTH) } else { c.to_string() };
+        columns += text.chars().count();
+        match runs.last_mut() {
+            Some((run, run_color)) if *run_color == color => run.push_str(&text),
+            _ => runs.push((text, color)),
+        }
+    }
+    (runs, columns)
+}
+
+fn render(source: &str, ext: &str, range: Option<LineRange>, theme: &Theme, title: Option<&str>, options: &CodeImageOptions) -> Result<CodeImage, String> {
+    let roles = highlight(source, ext);
+    let Selection { first, lines, truncated } = select_lines(source, range, options.dedent.unwrap_or(true))?;
+    let drawn: Vec<_> = lines.iter().map(|&line| runs(source, line, &roles, theme)).collect();
+
+    let numbers = options.line_numbers.unwrap_or(true);
+    let digits = (first + lines.len() - 1).to_string().len();
+    let gutter = if numbers { (digits + 2) as f32 * CHAR_WIDTH } else { 0.0 };
+    let columns = drawn.iter().map(|(_, w)| *w).max().unwrap_or(0);
+    let window_width = (2.0 * PADDING + gutter + columns as f32 * CHAR_WIDTH).max(MIN_WINDOW_WIDTH);
+    let window_height = TITLE_BAR + 2.0 * PADDING + lines.len() as f32 * LINE_HEIGHT - (LINE_HEIGHT - FONT_SIZE);
+    let (width, height) = ((window_width + 2.0 * MARGIN).ceil(), (window_height + 2.0 * MARGIN).ceil());
+
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}"><defs><filter id="shadow" x="-20%" y="-20%" width="140%" height="140%"><feDropShadow dx="0" dy="10" stdDeviation="14" flood-color="#000" flood-opacity="0.45"/></filter></defs><rect x="{m}" y="{m}" width="{ww:.1}" height="{wh:.1}" rx="10" fill="{bg}" filter="url(#shadow)"/>"##,
+        w = width,
+        h = height,
+        m = MARGIN,
+        ww = window_width,
+        wh = window_height,
+        bg = theme.background,
+    );
+    let dot_y = MARGIN + TITLE_BAR / 2.0 + 2.0;
+    for (i, color) in ["#ff5f56", "#ffbd2e", "#27c93f"].iter().enumerate() {
+        let _ = write!(svg, r#"<circle cx="{:.1}" cy="{:.1}" r="6" fill="{}"/>"#, MARGIN + PADDING + 6.0 + i as f32 * 20.0, dot_y, color);
+    }
+    if let Some(title) = title.filter(|t| !t.is_empty()) {
+        let _ = write!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" fill="{}" font-family="{}" font-size="13" text-anchor="middle">{}</text>"#,
+            MARGIN + window_width / 2.0,
+            dot_y + 4.5,
+            theme.gutter,
+            FONT_FAMILY,
+            escape(title),
+        );
+    }
+    let _ = write!(svg, r#"<g font-family="{}" font-size="{}" xml:space="preserve" style="white-space:pre">"#, FONT_FAMILY, FONT_SIZE);
+    let text_x = MARGIN + PADDING + gutter;
+    for (i, (line_runs, _)) in drawn.iter().enumerate() {
+        let y = MARGIN + TITLE_BAR + PADDING + FONT_SIZE + i as f32 * LINE_HEIGHT;
+        if numbers {
+            let _ = write!(svg, r#"<text x="{:.1}" y="{:.1}" fill="{}" text-anchor="end">{}</text>"#, text_x - 2.0 * CHAR_WIDTH, y, theme.gutter, first + i);
+        }
+        let _ = write!(svg, r#"<text x="{:.1}" y="{:.1}" fill="{}">"#, text_x, y, theme.foreground);
+        for (text, color) in line_runs {
+            if *color == theme.foreground {
+                svg.push_str(&escape(text));
+            } else {
+                let _ = write!(svg, r#"<tspan fill="{}">{}</tspan>"#, color, escape(text));
+            }
+        }
+        svg.push_str("</text>");
+    }
+    svg.push_str("</g></svg>");
+    Ok(CodeImage { svg, width: width as u32, height: height as u32, truncated })
+}
+
+// ── Commands ──
+
+/// Draw `content`, or the file at `path`, as an SVG in the colours of the
+/// editor theme `theme` (`one-dark` by default). With both, `content` is
+/// drawn and `path` names it, e.g. for an editor buffer with unsaved
+/// changes. `range` limits the lines drawn.
+#[tauri::command]
+pub fn render_code_image(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: Option<String>,
+    content: Option<String>,
+    range: Option<LineRange>,
+    theme: Option<String>,
+    options: Option<CodeImageOptions>,
+) -> Result<CodeImage, String> {
+    let options = options.unwrap_or_default();
+    let theme = self::theme(theme.as_deref().unwrap_or("one-dark"))?;
+    let file = path.as_deref().map(|p| validate_path(p, window.label(), &state)).transpose()?;
+    let source = match (content, &file) {
+        (Some(content), _) => content,
+        (None, Some(file)) => read_text(file).ok_or("This file can't be drawn as an image")?,
+        (None, None) => return Err("Nothing to render".to_string()),
+    };
+    let file_ext = file.as_deref().and_then(Path::extension).map(|e| e.to_string_lossy().into_owned());
+    let ext = options.language.clone().or(file_ext).unwrap_or_default();
+    let file_name = file.as_deref().and_then(Path::file_name).map(|n| n.to_string_lossy().into_owned());
+    let title = options.title.clone().or(file_name);
+    render(&source, &ext, range, &theme, title.as_deref(), &options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draws_highlighted_snippets() {
+        let source = "struct Point;\n\nimpl Point {\n    fn dist(&self) -> f64 {\n        // a & b\n        self.len() * 2.0\n    }\n}\n";
+        let roles = highlight(source, "rs");
+        let role_of = |needle: &str| roles[source.find(needle).unwrap()];
+        assert_eq!(role_of("fn"), Some(Role::Keyword));
+        assert_eq!(role_of("dist"), Some(Role::Function));
+        assert_eq!(role_of("f64"), Some(Role::Type));
+        assert_eq!(role_of("// a"), Some(Role::Comment));
+        assert_eq!(role_of("len"), Some(Role::Function));
+        assert_eq!(role_of("2.0"), Some(Role::Number));
+        assert_eq!(role_of("*"), Some(Role::Operator));
+        assert!(highlight("a = 1", "txt").iter().all(Option::is_none));
+
+        let theme = theme("github-light").unwrap();
+        let image = render(source, "rs", Some(LineRange { start: 4, end: 7 }), &theme, Some("geo.rs"), &CodeImageOptions::default()).unwrap();
+        assert!(!image.truncated);
+        assert!(image.svg.starts_with("<svg") && image.svg.ends_with("</svg>"));
+        // Dedented by the shared four spaces, numbered from the range start.
+        assert!(image.svg.contains(r##"text-anchor="end">4</text><text x="##));
+        assert!(image.svg.contains(r##"<tspan fill="#cf222e">fn</tspan> <tspan fill="#8250df">dist</tspan>"##));
+        assert!(image.svg.contains("// a &amp; b"));
+        assert!(image.svg.contains(">geo.rs</text>"));
+        assert!(!image.svg.contains(">3</text>") && !image.svg.contains(">8</text>"));
+        assert_eq!(image.height as f32, (2.0 * MARGIN + TITLE_BAR + 2.0 * PADDING + 4.0 * LINE_HEIGHT - (LINE_HEIGHT - FONT_SIZE)).ceil());
+
+        let long = "x".repeat(500) + "\n";
+        let image = render(&long.repeat(MAX_LINES + 1), "", None, &theme, None, &CodeImageOptions::default()).unwrap();
+        assert!(image.truncated);
+        assert!(image.svg.contains("…"));
+        assert_eq!(image.svg.matches("<text").count(), 2 * MAX_LINES);
+
+        assert!(render("a\n", "", Some(LineRange { start: 3, end: 4 }), &theme, None, &CodeImageOptions::default()).is_err());
+        assert!(self::theme("neon").is_err());
+    }
+}
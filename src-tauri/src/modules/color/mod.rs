@@ -0,0 +1,351 @@
+//! Color literals for editor swatches and the color picker.
+//!
+//! `parse_colors` finds hex, `rgb()`/`rgba()`, `hsl()`/`hsla()` and named
+//! colors in a document and normalizes each to RGBA; `convert_color`
+//! rewrites a color in another notation, keeping its alpha. In stylesheets
+//! and markup colors are found anywhere in declaration values; in other
+//! languages only inside string literals, where `#add` is more likely a
+//! color than a private field.
+
+use regex::Regex;
+use serde::Serialize;
+use std::sync::OnceLock;
+
+// ── Types ──
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct Rgba {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    /// 0.0–1.0.
+    pub a: f32,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorFormat {
+    Hex,
+    Rgb,
+    Hsl,
+    Named,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ColorLiteral {
+    /// 1-based line of the literal.
+    pub line: usize,
+    /// 0-based character column.
+    pub column: usize,
+    /// Length in characters.
+    pub length: usize,
+    pub text: String,
+    pub format: ColorFormat,
+    pub rgba: Rgba,
+}
+
+// ── Parsing ──
+
+const NAMED_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0xf0f8ff), ("antiquewhite", 0xfaebd7), ("aqua", 0x00ffff), ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff), ("beige", 0xf5f5dc), ("bisque", 0xffe4c4), ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd), ("blue", 0x0000ff), ("blueviolet", 0x8a2be2), ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887), ("cadetblue", 0x5f9ea0), ("chartreuse", 0x7fff00), ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50), ("cornflowerblue", 0x6495ed), ("cornsilk", 0xfff8dc), ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff), ("darkblue", 0x00008b), ("darkcyan", 0x008b8b), ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9), ("darkgreen", 0x006400), ("darkgrey", 0xa9a9a9), ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b), ("darkolivegreen", 0x556b2f), ("darkorange", 0xff8c00), ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000), ("darksalmon", 0xe9967a), ("darkseagreen", 0x8fbc8f), ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f), ("darkslategrey", 0x2f4f4f), ("darkturquoise", 0x00ced1), ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493), ("deepskyblue", 0x00bfff), ("dimgray", 0x696969), ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff), ("firebrick", 0xb22222), ("floralwhite", 0xfffaf0), ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff), ("gainsboro", 0xdcdcdc), ("ghostwhite", 0xf8f8ff), ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520), ("gray", 0x808080), ("green", 0x008000), ("greenyellow", 0xadff2f),
+    ("grey", 0x808080), ("honeydew", 0xf0fff0), ("hotpink", 0xff69b4), ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082), ("ivory", 0xfffff0), ("khaki", 0xf0e68c), ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5), ("lawngreen", 0x7cfc00), ("lemonchiffon", 0xfffacd), ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080), ("lightcyan", 0xe0ffff), ("lightgoldenrodyellow", 0xfafad2), ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90), ("lightgrey", 0xd3d3d3), ("lightpink", 0xffb6c1), ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa), ("lightskyblue", 0x87cefa), ("lightslategray", 0x778899), ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de), ("lightyellow", 0xffffe0), ("lime", 0x00ff00), ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6), ("magenta", 0xff00ff), ("maroon", 0x800000), ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd), ("mediumorchid", 0xba55d3), ("mediumpurple", 0x9370db), ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee), ("mediumspringgreen", 0x00fa9a), ("mediumturquoise", 0x48d1cc), ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970), ("mintcream", 0xf5fffa), ("mistyrose", 0xffe4e1), ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead), ("navy", 0x000080), ("oldlace", 0xfdf5e6), ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23), ("orange", 0xffa500), ("orangered", 0xff4500), ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa), ("palegreen", 0x98fb98), ("paleturquoise", 0xafeeee), ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5), ("peachpuff", 0xffdab9), ("peru", 0xcd853f), ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd), ("powderblue", 0xb0e0e6), ("purple", 0x800080), ("rebeccapurple", 0x663399),
+    ("red", 0xff0000), ("rosybrown", 0xbc8f8f), ("royalblue", 0x4169e1), ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072), ("sandybrown", 0xf4a460), ("seagreen", 0x2e8b57), ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d), ("silver", 0xc0c0c0), ("skyblue", 0x87ceeb), ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090), ("slategrey", 0x708090), ("snow", 0xfffafa), ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4), ("tan", 0xd2b48c), ("teal", 0x008080), ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347), ("turquoise", 0x40e0d0), ("violet", 0xee82ee), ("wheat", 0xf5deb3),
+    ("white", 0xffffff), ("whitesmoke", 0xf5f5f5), ("yellow", 0xffff00), ("yellowgreen", 0x9acd32),
+];
+
+fn color_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?i)#(?:[0-9a-f]{8}|[0-9a-f]{6}|[0-9a-f]{3,4})\b|\b(?:rgba?|hsla?)\(\s*[-+0-9.%deg\s,/]+\)|\b[a-z]{3,20}\b",
+        )
+        .unwrap()
+    })
+}
+
+/// Languages where colors appear bare in declarations rather than in
+/// strings.
+fn is_style_language(language: &str) -> bool {
+    matches!(
+        language.to_ascii_lowercase().as_str(),
+        "css" | "scss" | "sass" | "less" | "styl" | "stylus" | "postcss" | "html" | "htm" | "svelte" | "vue" | "xml" | "svg"
+    )
+}
+
+fn named(name: &str) -> Option<Rgba> {
+    let lower = name.to_ascii_lowercase();
+    if lower == "transparent" {
+        return Some(Rgba { r: 0, g: 0, b: 0, a: 0.0 });
+    }
+    NAMED_COLORS.iter().find(|(n, _)| *n == lower).map(|(_, rgb)| Rgba {
+        r: (rgb >> 16) as u8,
+        g: (rgb >> 8) as u8,
+        b: *rgb as u8,
+        a: 1.0,
+    })
+}
+
+fn parse_hex(hex: &str) -> Option<Rgba> {
+    let digits: Vec<u8> = hex.chars().map(|c| c.to_digit(16).map(|d| d as u8)).collect::<Option<_>>()?;
+    let (r, g, b, a) = match digits.len() {
+        3 | 4 => {
+            let d = |i: usize| digits.get(i).map(|v| v * 17);
+            (d(0)?, d(1)?, d(2)?, d(3).unwrap_or(255))
+        }
+        6 | 8 => {
+            let d = |i: usize| digits.get(i * 2).zip(digits.get(i * 2 + 1)).map(|(h, l)| h * 16 + l);
+            (d(0)?, d(1)?, d(2)?, d(3).unwrap_or(255))
+        }
+        _ => return None,
+    };
+    Some(Rgba { r, g, b, a: a as f32 / 255.0 })
+}
+
+/// A number, percentage (of `full`) or, for hues, an angle in degrees.
+fn component(token: &str, full: f32) -> Option<f32> {
+    if let Some(p) = token.strip_suffix('%') {
+        return p.parse::<f32>().ok().map(|v| v / 100.0 * full);
+    }
+    token.trim_end_matches("deg").parse::<f32>().ok()
+}
+
+/// `rgb(…)`, `rgba(…)`, `hsl(…)` or `hsla(…)` in comma or space syntax.
+fn parse_function(text: &str) -> Option<Rgba> {
+    let open = text.find('(')?;
+    let name = text[..open].to_ascii_lowercase();
+    let args: Vec<&str> = text[open + 1..text.len() - 1]
+        .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+        .collect();
+    if args.len() != 3 && args.len() != 4 {
+        return None;
+    }
+    let alpha = match args.get(3) {
+        Some(a) => component(a, 1.0)?.clamp(0.0, 1.0),
+        None => 1.0,
+    };
+    let byte = |v: f32| v.round().clamp(0.0, 255.0) as u8;
+    if name.starts_with("rgb") {
+        Some(Rgba { r: byte(component(args[0], 255.0)?), g: byte(component(args[1], 255.0)?), b: byte(component(args[2], 255.0)?), a: alpha })
+    } else {
+        let (r, g, b) = hsl_to_rgb(component(args[0], 360.0)?, component(args[1], 1.0)?.clamp(0.0, 1.0), component(args[2], 1.0)?.clamp(0.0, 1.0));
+        Some(Rgba { r: byte(r), g: byte(g), b: byte(b), a: alpha })
+    }
+}
+
+fn hsl_to_rgb(h: f32, s: f32, l: f32) -> (f32, f32, f32) {
+    let h = h.rem_euclid(360.0) / 360.0;
+    let q = if l < 0.5 { l * (1.0 + s) } else { l + s - l * s };
+    let p = 2.0 * l - q;
+    let channel = |mut t: f32| {
+        t = t.rem_euclid(1.0);
+        let v = if t < 1.0 / 6.0 {
+            p + (q - p) * 6.0 * t
+        } else if t < 0.5 {
+            q
+        } else if t < 2.0 / 3.0 {
+            p + (q - p) * (2.0 / 3.0 - t) * 6.0
+        } else {
+            p
+        };
+        v * 255.0
+    };
+    (channel(h + 1.0 / 3.0), channel(h), channel(h - 1.0 / 3.0))
+}
+
+fn rgb_to_hsl(c: Rgba) -> (f32, f32, f32) {
+    let (r, g, b) = (c.r as f32 / 255.0, c.g as f32 / 255.0, c.b as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    let d = max - min;
+    if d == 0.0 {
+        return (0.0, 0.0, l);
+    }
+    let s = if l > 0.5 { d / (2.0 - max - min) } else { d / (max + min) };
+    let h = if max == r {
+        (g - b) / d + if g < b { 6.0 } else { 0.0 }
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    };
+    (h * 60.0, s, l)
+}
+
+/// Parse one color value in any supported notation.
+fn parse_value(text: &str) -> Option<(ColorFormat, Rgba)> {
+    let text = text.trim();
+    if let Some(hex) = text.strip_prefix('#') {
+        return parse_hex(hex).map(|c| (ColorFormat::Hex, c));
+    }
+    if text.ends_with(')') {
+        let format = if text.to_ascii_lowercase().starts_with("hsl") { ColorFormat::Hsl } else { ColorFormat::Rgb };
+        return parse_function(text).map(|c| (format, c));
+    }
+    named(text).map(|c| (ColorFormat::Named, c))
+}
+
+/// Whether byte `at` of `line` is inside a string literal, judged by the
+/// quotes before it on the same line.
+fn in_string(line: &str, at: usize) -> bool {
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for c in line[..at].chars() {
+        match (quote, c) {
+            (_, _) if escaped => escaped = false,
+            (Some(_), '\\') => escaped = true,
+            (Some(q), c) if c == q => quote = None,
+            (None, '"' | '\'' | '`') => quote = Some(c),
+            _ => {}
+        }
+    }
+    quote.is_some()
+}
+
+fn find_colors(content: &str, language: &str) -> Vec<ColorLiteral> {
+    let style = is_style_language(language);
+    let mut found = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        for m in color_regex().find_iter(line) {
+            let text = m.as_str();
+            let quoted = in_string(line, m.start());
+            let is_word = text.chars().all(|c| c.is_ascii_alphabetic());
+            let accept = if style {
+                // Declaration values only: skip selectors (`#main {`) and
+                // property names (`color:`).
+                let before = &line[..m.start()];
+                let after = &line[m.end()..];
+                (quoted || before.contains(':') || before.contains('=')) && !after.contains('{') && !after.trim_start().starts_with(':')
+            } else if is_word {
+                // A bare word is only a color when it's the whole string.
+                quoted && matches!((line[..m.start()].chars().last(), line[m.end()..].chars().next()), (Some(a), Some(b)) if a == b && "'\"`".contains(a))
+            } else {
+                quoted
+            };
+            if !accept {
+                continue;
+            }
+            if let Some((format, rgba)) = parse_value(text) {
+                found.push(ColorLiteral {
+                    line: index + 1,
+                    column: line[..m.start()].chars().count(),
+                    length: text.chars().count(),
+                    text: text.to_string(),
+                    format,
+                    rgba,
+                });
+            }
+        }
+    }
+    found
+}
+
+// ── Formatting ──
+
+fn trim_float(v: f32) -> String {
+    let s = format!("{:.3}", v);
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+fn format_color(c: Rgba, format: &str) -> Result<String, String> {
+    let opaque = c.a >= 1.0;
+    Ok(match format {
+        "hex" if opaque => format!("#{:02x}{:02x}{:02x}", c.r, c.g, c.b),
+        "hex" => format!("#{:02x}{:02x}{:02x}{:02x}", c.r, c.g, c.b, (c.a * 255.0).round() as u8),
+        "rgb" if opaque => format!("rgb({}, {}, {})", c.r, c.g, c.b),
+        "rgb" => format!("rgba({}, {}, {}, {})", c.r, c.g, c.b, trim_float(c.a)),
+        "hsl" => {
+            let (h, s, l) = rgb_to_hsl(c);
+            let (h, s, l) = (h.round(), (s * 100.0).round(), (l * 100.0).round());
+            if opaque {
+                format!("hsl({}, {}%, {}%)", h, s, l)
+            } else {
+                format!("hsla({}, {}%, {}%, {})", h, s, l, trim_float(c.a))
+            }
+        }
+        other => return Err(format!("Unknown color format: {} (expected hex, rgb or hsl)", other)),
+    })
+}
+
+// ── Commands ──
+
+/// Color literals in `content` with their position and RGBA value.
+/// `language` is a language id or file extension, e.g. `css` or `tsx`.
+#[tauri::command]
+pub fn parse_colors(content: String, language: String) -> Vec<ColorLiteral> {
+    find_colors(&content, &language)
+}
+
+/// Rewrite `value` as `hex`, `rgb` or `hsl`, keeping its alpha.
+#[tauri::command]
+pub fn convert_color(value: String, target_format: String) -> Result<String, String> {
+    let (_, rgba) = parse_value(&value).ok_or_else(|| format!("Not a color: {}", value))?;
+    format_color(rgba, &target_format.to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_colors_in_stylesheets() {
+        let css = "#main { color: #f00; }\n.a:hover {\n  background: rgba(0, 128, 255, 0.5);\n  border: 1px solid rebeccapurple;\n  fill: hsl(120 100% 25% / 50%);\n}\n";
+        let found = find_colors(css, "css");
+        let texts: Vec<&str> = found.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(texts, vec!["#f00", "rgba(0, 128, 255, 0.5)", "rebeccapurple", "hsl(120 100% 25% / 50%)"]);
+        assert_eq!((found[0].line, found[0].column, found[0].length), (1, 15, 4));
+        assert_eq!(found[1].rgba, Rgba { r: 0, g: 128, b: 255, a: 0.5 });
+        assert_eq!(found[3].rgba, Rgba { r: 0, g: 128, b: 0, a: 0.5 });
+    }
+
+    #[test]
+    fn only_strings_count_in_scripts() {
+        let js = "class A { #add() {} }\nconst theme = { bg: '#1e1e1e', fg: \"white\", label: 'red alert' };\n";
+        let texts: Vec<String> = find_colors(js, "ts").into_iter().map(|c| c.text).collect();
+        assert_eq!(texts, vec!["#1e1e1e", "white"]);
+    }
+
+    #[test]
+    fn converts_between_formats() {
+        let convert = |v: &str, f: &str| format_color(parse_value(v).unwrap().1, f).unwrap();
+        assert_eq!(convert("#ff000080", "rgb"), "rgba(255, 0, 0, 0.502)");
+        assert_eq!(convert("rgb(0, 128, 0)", "hsl"), "hsl(120, 100%, 25%)");
+        assert_eq!(convert("hsl(120, 100%, 25%)", "hex"), "#008000");
+        assert_eq!(convert("teal", "hex"), "#008080");
+        assert!(format_color(Rgba { r: 0, g: 0, b: 0, a: 1.0 }, "cmyk").is_err());
+    }
+}
@@ -0,0 +1,511 @@
+//! Validation and formatting for JSON, YAML and TOML config files.
+//!
+//! `validate_config` reports parse errors and JSON Schema violations as
+//! line/column diagnostics. The schema is, in order: the one passed in, a
+//! local file named by the document's `$schema` key, or a bundled schema
+//! picked by file name (`package.json`, `tauri.conf.json`, `Cargo.toml`).
+//! Remote `$schema` URLs are never fetched.
+//!
+//! `format_config` keeps key order and comments. JSON is re-indented;
+//! YAML and TOML only get whitespace normalization. The result is parsed
+//! again and discarded if it doesn't mean the same thing as the input.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+use crate::modules::fs::{validate_path, ProjectRootState};
+
+pub mod tooling;
+
+const BUNDLED_SCHEMAS: &[(&str, &str)] = &[
+    ("package.json", include_str!("schemas/package.json.schema.json")),
+    ("tauri.conf.json", include_str!("schemas/tauri.conf.json.schema.json")),
+    ("Cargo.toml", include_str!("schemas/cargo.toml.schema.json")),
+];
+
+// ── Types ──
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigFormat {
+    Json,
+    /// JSON with comments and trailing commas (tsconfig, VS Code settings).
+    Jsonc,
+    Yaml,
+    Toml,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ConfigDiagnostic {
+    /// 1-based position of the problem.
+    pub line: usize,
+    pub column: usize,
+    pub severity: String,
+    pub message: String,
+    /// JSON pointer to the offending value, for schema errors.
+    pub pointer: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ConfigValidation {
+    pub format: ConfigFormat,
+    /// Which schema was applied: a path, or `bundled:<name>`.
+    pub schema: Option<String>,
+    pub diagnostics: Vec<ConfigDiagnostic>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct FormattedConfig {
+    pub format: ConfigFormat,
+    pub text: String,
+    pub changed: bool,
+    pub diagnostics: Vec<ConfigDiagnostic>,
+}
+
+fn error_at(line: usize, column: usize, message: impl Into<String>) -> ConfigDiagnostic {
+    ConfigDiagnostic { line, column, severity: "error".to_string(), message: message.into(), pointer: None }
+}
+
+// ── Format detection and parsing ──
+
+fn detect_format(path: &Path) -> Option<ConfigFormat> {
+    let name = path.file_name()?.to_str()?;
+    let in_vscode = path.parent().and_then(|p| p.file_name()).is_some_and(|n| n == ".vscode");
+    match path.extension()?.to_str()? {
+        "jsonc" => Some(ConfigFormat::Jsonc),
+        "json" if in_vscode || name.starts_with("tsconfig") || name.starts_with("jsconfig") => Some(ConfigFormat::Jsonc),
+        "json" => Some(ConfigFormat::Json),
+        "yaml" | "yml" => Some(ConfigFormat::Yaml),
+        "toml" => Some(ConfigFormat::Toml),
+        _ => None,
+    }
+}
+
+/// 1-based line and column of a byte offset.
+fn position_of(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rfind('\n').map_or(before.len(), |i| before.len() - i - 1) + 1;
+    (line, column)
+}
+
+fn parse(source: &str, format: ConfigFormat) -> Result<Value, ConfigDiagnostic> {
+    match format {
+        ConfigFormat::Json => serde_json::from_str(source).map_err(|e| error_at(e.line(), e.column(), e.to_string())),
+        ConfigFormat::Jsonc => {
+            let stripped = strip_jsonc(source);
+            serde_json::from_str(&stripped).map_err(|e| error_at(e.line(), e.column(), e.to_string()))
+        }
+        ConfigFormat::Yaml => serde_yaml::from_str(source).map_err(|e| {
+            let (line, column) = e.location().map_or((1, 1), |l| (l.line(), l.column()));
+            error_at(line, column, e.to_string())
+        }),
+        ConfigFormat::Toml => toml::from_str(source).map_err(|e| {
+            let (line, column) = e.span().map_or((1, 1), |s| position_of(source, s.start));
+            error_at(line, column, e.message().to_string())
+        }),
+    }
+}
+
+// ── JSON tokens ──
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Punct(char),
+    /// String, number or keyword, verbatim.
+    Atom(&'a str),
+    LineComment(&'a str),
+    BlockComment(&'a str),
+}
+
+/// `(token, newlines since the previous token)`.
+fn tokenize(source: &str) -> Vec<(Token<'_>, usize)> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let mut newlines = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        let start = i;
+        match c {
+            b'\n' => {
+                newlines += 1;
+                i += 1;
+                continue;
+            }
+            b' ' | b'\t' | b'\r' => {
+                i += 1;
+                continue;
+            }
+            b'{' | b'}' | b'[' | b']' | b',' | b':' => {
+                tokens.push((Token::Punct(c as char), newlines));
+                i += 1;
+            }
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                i = (i + 1).min(bytes.len());
+                tokens.push((Token::Atom(&source[start..i]), newlines));
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+                tokens.push((Token::LineComment(source[start..i].trim_end()), newlines));
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = source[i + 2..].find("*/").map_or(bytes.len(), |j| i + 2 + j + 2);
+                tokens.push((Token::BlockComment(&source[start..i]), newlines));
+            }
+            _ => {
+                while i < bytes.len() && !b" \t\r\n{}[],:\"/".contains(&bytes[i]) {
+                    i += 1;
+                }
+                if i == start {
+                    i += 1;
+                }
+                tokens.push((Token::Atom(&source[start..i]), newlines));
+            }
+        }
+        newlines = 0;
+    }
+    tokens
+}
+
+/// Drop comments and trailing commas so serde_json can parse JSONC.
+/// Newlines are kept so error positions still line up.
+fn strip_jsonc(source: &str) -> String {
+    let tokens = tokenize(source);
+    let mut out = String::with_capacity(source.len());
+    for (i, (token, newlines)) in tokens.iter().enumerate() {
+        out.push_str(&"\n".repeat(*newlines));
+        match token {
+            Token::Punct(',') => {
+                let next = tokens[i + 1..].iter().find(|(t, _)| !matches!(t, Token::LineComment(_) | Token::BlockComment(_)));
+                if !matches!(next, Some((Token::Punct('}' | ']'), _))) {
+                    out.push(',');
+                }
+            }
+            Token::Punct(c) => out.push(*c),
+            Token::Atom(s) => {
+                out.push(' ');
+                out.push_str(s);
+            }
+            Token::BlockComment(s) => out.push_str(&"\n".repeat(s.matches('\n').count())),
+            Token::LineComment(_) => {}
+        }
+    }
+    out
+}
+
+fn detect_indent(source: &str) -> String {
+    source
+        .lines()
+        .map(|l| &l[..l.len() - l.trim_start().len()])
+        .find(|ws| !ws.is_empty())
+        .map(|ws| if ws.starts_with('\t') { "\t".to_string() } else { " ".repeat(ws.len().min(8)) })
+        .unwrap_or_else(|| "  ".to_string())
+}
+
+/// Re-indent JSON/JSONC, keeping key order, comments and single blank
+/// lines between members.
+fn format_json(source: &str) -> String {
+    let unit = detect_indent(source);
+    let tokens = tokenize(source);
+    let mut out = String::with_capacity(source.len());
+    let mut depth = 0usize;
+    let mut pending_newline = false;
+
+    let newline = |out: &mut String, depth: usize, blank: bool| {
+        out.push('\n');
+        if blank {
+            out.push('\n');
+        }
+        out.push_str(&unit.repeat(depth));
+    };
+
+    for (i, (token, newlines)) in tokens.iter().enumerate() {
+        let is_comment = matches!(token, Token::LineComment(_) | Token::BlockComment(_));
+        let closing = matches!(token, Token::Punct('}' | ']'))
+            && !matches!(i.checked_sub(1).map(|j| &tokens[j].0), Some(Token::Punct('{' | '[')));
+        if closing {
+            depth = depth.saturating_sub(1);
+            pending_newline = true;
+        }
+        // A comment on the same source line as the previous token stays there.
+        if pending_newline && !(is_comment && *newlines == 0) {
+            newline(&mut out, depth, *newlines > 1 && !closing);
+            pending_newline = false;
+        }
+        match token {
+            Token::Punct(c @ ('{' | '[')) => {
+                let close = if *c == '{' { '}' } else { ']' };
+                out.push(*c);
+                if matches!(tokens.get(i + 1), Some((Token::Punct(n), _)) if *n == close) {
+                    continue;
+                }
+                depth += 1;
+                pending_newline = true;
+            }
+            Token::Punct(',') => {
+                out.push(',');
+                pending_newline = true;
+            }
+            Token::Punct(':') => out.push_str(": "),
+            Token::Punct(c) => out.push(*c),
+            Token::Atom(s) => out.push_str(s),
+            Token::LineComment(s) | Token::BlockComment(s) => {
+                if !out.is_empty() && !out.ends_with(&*unit) && !out.ends_with('\n') {
+                    out.push(' ');
+                }
+                out.push_str(s);
+                if matches!(token, Token::LineComment(_)) {
+                    pending_newline = true;
+                }
+            }
+        }
+    }
+    out.push('\n');
+    out
+}
+
+/// Whitespace-only cleanup for YAML and TOML: trailing spaces, runs of
+/// blank lines, and the final newline.
+fn normalize_whitespace(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut blank_run = 0;
+    for line in source.lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            blank_run += 1;
+            continue;
+        }
+        if blank_run > 0 && !out.is_empty() {
+            out.push('\n');
+        }
+        blank_run = 0;
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+// ── Schemas ──
+
+fn bundled_schema(path: &Path) -> Option<(&'static str, &'static str)> {
+    let name = path.file_name()?.to_str()?;
+    BUNDLED_SCHEMAS.iter().find(|(n, _)| *n == name).copied()
+}
+
+/// Resolve the schema to use: `(label, schema)`.
+fn resolve_schema(
+    file: &Path,
+    document: &Value,
+    explicit: Option<PathBuf>,
+) -> Result<Option<(String, Value)>, String> {
+    let load = |p: &Path| -> Result<Value, String> {
+        let text = std::fs::read_to_string(p).map_err(|e| format!("Failed to read schema: {}", e.kind()))?;
+        serde_json::from_str(&text).map_err(|e| format!("Invalid schema {}: {}", p.display(), e))
+    };
+    if let Some(p) = explicit {
+        return Ok(Some((p.to_string_lossy().to_string(), load(&p)?)));
+    }
+    if let Some(reference) = document.get("$schema").and_then(Value::as_str) {
+        if !reference.contains("://") {
+            let p = file.parent().unwrap_or(file).join(reference);
+            if p.is_file() {
+                return Ok(Some((p.to_string_lossy().to_string(), load(&p)?)));
+            }
+        }
+    }
+    match bundled_schema(file) {
+        Some((name, text)) => {
+            let schema = serde_json::from_str(text).map_err(|e| format!("Invalid bundled schema {}: {}", name, e))?;
+            Ok(Some((format!("bundled:{}", name), schema)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Best-effort source position for a JSON pointer: find each key segment
+/// in turn after the previous one.
+fn locate_pointer(source: &str, pointer: &str) -> (usize, usize) {
+    let mut pos = 0;
+    let mut found = None;
+    for segment in pointer.split('/').skip(1) {
+        let segment = segment.replace("~1", "/").replace("~0", "~");
+        if segment.is_empty() || segment.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        let mut search_from = pos;
+        while let Some(i) = source[search_from..].find(segment.as_str()) {
+            let start = search_from + i;
+            let end = start + segment.len();
+            let is_word = |c: Option<char>| c.map_or(true, |c| !(c.is_alphanumeric() || c == '_' || c == '-'));
+            if is_word(source[..start].chars().next_back()) && is_word(source[end..].chars().next()) {
+                pos = end;
+                found = Some(start);
+                break;
+            }
+            search_from = end;
+        }
+    }
+    found.map_or((1, 1), |i| position_of(source, i))
+}
+
+fn schema_diagnostics(source: &str, document: &Value, schema: &Value) -> Result<Vec<ConfigDiagnostic>, String> {
+    let validator = jsonschema::validator_for(schema).map_err(|e| format!("Invalid schema: {}", e))?;
+    let mut diagnostics: Vec<ConfigDiagnostic> = validator
+        .iter_errors(document)
+        .map(|e| {
+            let pointer = e.instance_path.to_string();
+            let (line, column) = locate_pointer(source, &pointer);
+            ConfigDiagnostic { line, column, severity: "error".to_string(), message: e.to_string(), pointer: Some(pointer) }
+        })
+        .collect();
+    diagnostics.sort_by_key(|d| (d.line, d.column));
+    Ok(diagnostics)
+}
+
+// ── Commands ──
+
+fn read_source(path: &Path, content: Option<String>) -> Result<String, String> {
+    match content {
+        Some(c) => Ok(c),
+        None => std::fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e.kind())),
+    }
+}
+
+/// Check `path` for syntax and schema errors. Pass `content` to check an
+/// unsaved buffer instead of the file on disk, and `schema` to override
+/// the schema lookup.
+#[tauri::command]
+pub fn validate_config(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: String,
+    schema: Option<String>,
+    content: Option<String>,
+) -> Result<ConfigValidation, String> {
+    let file = validate_path(&path, window.label(), &state)?;
+    let format = detect_format(&file).ok_or_else(|| "Not a JSON, YAML or TOML file".to_string())?;
+    let explicit = schema.map(|s| validate_path(&s, window.label(), &state)).transpose()?;
+    let source = read_source(&file, content)?;
+
+    let document = match parse(&source, format) {
+        Ok(doc) => doc,
+        Err(diagnostic) => return Ok(ConfigValidation { format, schema: None, diagnostics: vec![diagnostic] }),
+    };
+    let Some((label, schema)) = resolve_schema(&file, &document, explicit)? else {
+        return Ok(ConfigValidation { format, schema: None, diagnostics: Vec::new() });
+    };
+    let diagnostics = schema_diagnostics(&source, &document, &schema)?;
+    Ok(ConfigValidation { format, schema: Some(label), diagnostics })
+}
+
+/// Pretty-print `path` (or `content`, if given). Nothing is written; the
+/// editor replaces its buffer with `text`. Files that don't parse come
+/// back unchanged with the parse error in `diagnostics`.
+#[tauri::command]
+pub fn format_config(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: String,
+    content: Option<String>,
+) -> Result<FormattedConfig, String> {
+    let file = validate_path(&path, window.label(), &state)?;
+    let format = detect_format(&file).ok_or_else(|| "Not a JSON, YAML or TOML file".to_string())?;
+    let source = read_source(&file, content)?;
+    Ok(format_source(&source, format))
+}
+
+fn format_source(source: &str, format: ConfigFormat) -> FormattedConfig {
+    let unchanged = |diagnostics| FormattedConfig { format, text: source.to_string(), changed: false, diagnostics };
+    let before = match parse(source, format) {
+        Ok(v) => v,
+        Err(diagnostic) => return unchanged(vec![diagnostic]),
+    };
+    let text = match format {
+        ConfigFormat::Json | ConfigFormat::Jsonc => format_json(source),
+        ConfigFormat::Yaml | ConfigFormat::Toml => normalize_whitespace(source),
+    };
+    if parse(&text, format).ok().as_ref() != Some(&before) {
+        return unchanged(vec![ConfigDiagnostic {
+            severity: "warning".to_string(),
+            ..error_at(1, 1, "Formatting would change the document's meaning; left as is")
+        }]);
+    }
+    FormattedConfig { format, changed: text != source, text, diagnostics: Vec::new() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundled(name: &str) -> Value {
+        let (_, text) = bundled_schema(Path::new(name)).unwrap();
+        serde_json::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn parse_errors_carry_positions() {
+        let err = parse("{\n  \"a\": 1,\n  \"b\" 2\n}", ConfigFormat::Json).unwrap_err();
+        assert_eq!(err.line, 3);
+        let err = parse("[package]\nname = \n", ConfigFormat::Toml).unwrap_err();
+        assert_eq!(err.line, 2);
+        let err = parse("a: 1\n b: 2\n", ConfigFormat::Yaml).unwrap_err();
+        assert_eq!(err.line, 2);
+    }
+
+    #[test]
+    fn jsonc_comments_and_trailing_commas_parse() {
+        let src = "{\n  // compiler\n  \"strict\": true, /* yes */\n  \"lib\": [\"es2020\",],\n}\n";
+        assert!(parse(src, ConfigFormat::Jsonc).is_ok());
+        assert!(parse(src, ConfigFormat::Json).is_err());
+    }
+
+    #[test]
+    fn valid_configs_match_bundled_schemas() {
+        let cases = [
+            ("tauri.conf.json", include_str!("../../../tauri.conf.json"), ConfigFormat::Json),
+            ("Cargo.toml", include_str!("../../../Cargo.toml"), ConfigFormat::Toml),
+            ("package.json", r#"{"name": "@leo/ide", "private": true, "type": "module", "scripts": {"dev": "vite"}}"#, ConfigFormat::Json),
+        ];
+        for (name, source, format) in cases {
+            let doc = parse(source, format).unwrap();
+            let diagnostics = schema_diagnostics(source, &doc, &bundled(name)).unwrap();
+            assert!(diagnostics.is_empty(), "{}: {:?}", name, diagnostics);
+        }
+    }
+
+    #[test]
+    fn schema_errors_point_at_the_offending_key() {
+        let src = "[package]\nname = \"demo\"\nedition = \"2020\"\n\n[dependencies]\nserde = 1\n";
+        let doc = parse(src, ConfigFormat::Toml).unwrap();
+        let diagnostics = schema_diagnostics(src, &doc, &bundled("Cargo.toml")).unwrap();
+        let lines: Vec<usize> = diagnostics.iter().map(|d| d.line).collect();
+        assert_eq!(lines, vec![3, 6]);
+        assert_eq!(diagnostics[0].pointer.as_deref(), Some("/package/edition"));
+    }
+
+    #[test]
+    fn json_formatting_keeps_order_and_comments() {
+        let src = "{\"z\":1,\"a\":[1,2],   // trailing\n\n\"e\":{}}";
+        let out = format_source(src, ConfigFormat::Jsonc);
+        assert!(out.changed);
+        assert_eq!(out.text, "{\n  \"z\": 1,\n  \"a\": [\n    1,\n    2\n  ], // trailing\n\n  \"e\": {}\n}\n");
+        let again = format_source(&out.text, ConfigFormat::Jsonc);
+        assert!(!again.changed);
+    }
+
+    #[test]
+    fn yaml_and_toml_get_whitespace_cleanup_only() {
+        let out = format_source("a: 1   \n\n\n\nb:\n  - x\n", ConfigFormat::Yaml);
+        assert_eq!(out.text, "a: 1\n\nb:\n  - x\n");
+        let out = format_source("[a]\nx = 1", ConfigFormat::Toml);
+        assert_eq!(out.text, "[a]\nx = 1\n");
+    }
+}
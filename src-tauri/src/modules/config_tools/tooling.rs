@@ -0,0 +1,474 @@
+//! Formatter and linter config discovery.
+//!
+//! `discover_tooling` looks at the top of a project (or a package inside
+//! it) for Prettier, ESLint, Biome, dprint, Stylelint, rustfmt, Black,
+//! Ruff and clang-format configs plus `.editorconfig`, and reports which
+//! tool formats and lints each language. Two formatters, or two linters,
+//! configured for the same language are reported as conflicts, as are
+//! indentation, line width and line ending settings that `.editorconfig`
+//! and Prettier disagree on. Only the directory itself is scanned;
+//! nested configs in a monorepo are found by asking for each package.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::modules::fs::{validate_path, ProjectRootState};
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum ToolKind {
+    Formatter,
+    Linter,
+}
+
+struct KnownTool {
+    name: &'static str,
+    language: &'static str,
+    kinds: &'static [ToolKind],
+    files: &'static [&'static str],
+    /// Key in `package.json` that can hold the config instead.
+    package_key: Option<&'static str>,
+    /// Table in `pyproject.toml` that can hold the config instead.
+    pyproject_table: Option<&'static str>,
+}
+
+const FORMATTER: &[ToolKind] = &[ToolKind::Formatter];
+const LINTER: &[ToolKind] = &[ToolKind::Linter];
+const BOTH: &[ToolKind] = &[ToolKind::Formatter, ToolKind::Linter];
+
+/// Tools in order of preference: when two format the same language, the
+/// earlier one is picked.
+const KNOWN_TOOLS: &[KnownTool] = &[
+    KnownTool {
+        name: "biome",
+        language: "javascript",
+        kinds: BOTH,
+        files: &["biome.json", "biome.jsonc"],
+        package_key: None,
+        pyproject_table: None,
+    },
+    KnownTool {
+        name: "prettier",
+        language: "javascript",
+        kinds: FORMATTER,
+        files: &[
+            ".prettierrc",
+            ".prettierrc.json",
+            ".prettierrc.json5",
+            ".prettierrc.yaml",
+            ".prettierrc.yml",
+            ".prettierrc.toml",
+            ".prettierrc.js",
+            ".prettierrc.cjs",
+            ".prettierrc.mjs",
+            "prettier.config.js",
+            "prettier.config.cjs",
+            "prettier.config.mjs",
+            "prettier.config.ts",
+        ],
+        package_key: Some("prettier"),
+        pyproject_table: None,
+    },
+    KnownTool {
+        name: "dprint",
+        language: "javascript",
+        kinds: FORMATTER,
+        files: &["dprint.json", ".dprint.json", "dprint.jsonc", ".dprint.jsonc"],
+        package_key: None,
+        pyproject_table: None,
+    },
+    KnownTool {
+        name: "eslint",
+        language: "javascript",
+        kinds: LINTER,
+        files: &[
+            "eslint.config.js",
+            "eslint.config.mjs",
+            "eslint.config.cjs",
+            "eslint.config.ts",
+            ".eslintrc",
+            ".eslintrc.js",
+            ".eslintrc.cjs",
+            ".eslintrc.json",
+            ".eslintrc.yaml",
+            ".eslintrc.yml",
+        ],
+        package_key: Some("eslintConfig"),
+        pyproject_table: None,
+    },
+    KnownTool {
+        name: "stylelint",
+        language: "css",
+        kinds: LINTER,
+        files: &[
+            ".stylelintrc",
+            ".stylelintrc.json",
+            ".stylelintrc.yaml",
+            ".stylelintrc.yml",
+            ".stylelintrc.js",
+            "stylelint.config.js",
+            "stylelint.config.mjs",
+            "stylelint.config.cjs",
+        ],
+        package_key: Some("stylelint"),
+        pyproject_table: None,
+    },
+    KnownTool {
+        name: "rustfmt",
+        language: "rust",
+        kinds: FORMATTER,
+        files: &["rustfmt.toml", ".rustfmt.toml"],
+        package_key: None,
+        pyproject_table: None,
+    },
+    KnownTool {
+        name: "black",
+        language: "python",
+        kinds: FORMATTER,
+        files: &[],
+        package_key: None,
+        pyproject_table: Some("black"),
+    },
+    KnownTool {
+        name: "ruff",
+        language: "python",
+        kinds: LINTER,
+        files: &["ruff.toml", ".ruff.toml"],
+        package_key: None,
+        pyproject_table: Some("ruff"),
+    },
+    KnownTool {
+        name: "clang-format",
+        language: "c",
+        kinds: FORMATTER,
+        files: &[".clang-format", "_clang-format"],
+        package_key: None,
+        pyproject_table: None,
+    },
+];
+
+// ── Types ──
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct DetectedTool {
+    pub name: String,
+    pub language: String,
+    pub kinds: Vec<ToolKind>,
+    /// Where the config was found, relative to the scanned directory:
+    /// a file name, or `package.json#prettier` / `pyproject.toml#tool.ruff`.
+    pub config: String,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictKind {
+    Formatter,
+    Linter,
+    Setting,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ToolingConflict {
+    pub kind: ConflictKind,
+    /// The language affected, or the setting name for `Setting`.
+    pub subject: String,
+    pub tools: Vec<String>,
+    pub message: String,
+}
+
+/// Editor settings the project asks for, from `.editorconfig`'s `[*]`
+/// section with gaps filled in from Prettier.
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct EditorSettings {
+    pub indent_style: Option<String>,
+    pub indent_size: Option<u32>,
+    pub max_line_length: Option<u32>,
+    pub end_of_line: Option<String>,
+    pub insert_final_newline: Option<bool>,
+    pub trim_trailing_whitespace: Option<bool>,
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct ToolingReport {
+    pub tools: Vec<DetectedTool>,
+    /// The formatter to run on save, by language.
+    pub formatters: BTreeMap<String, String>,
+    /// Linters configured, by language.
+    pub linters: BTreeMap<String, Vec<String>>,
+    pub editor: EditorSettings,
+    pub conflicts: Vec<ToolingConflict>,
+}
+
+// ── Discovery ──
+
+fn read_json(path: &Path) -> Option<Value> {
+    let text = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+fn read_toml(path: &Path) -> Option<toml::Value> {
+    let text = std::fs::read_to_string(path).ok()?;
+    toml::from_str(&text).ok()
+}
+
+fn find_config(dir: &Path, tool: &KnownTool, package: Option<&Value>, pyproject: Option<&toml::Value>) -> Option<String> {
+    if let Some(file) = tool.files.iter().find(|f| dir.join(f).is_file()) {
+        return Some(file.to_string());
+    }
+    if let Some(key) = tool.package_key {
+        if package.is_some_and(|p| p.get(key).is_some()) {
+            return Some(format!("package.json#{}", key));
+        }
+    }
+    if let Some(table) = tool.pyproject_table {
+        if pyproject.and_then(|p| p.get("tool")).and_then(|t| t.get(table)).is_some() {
+            return Some(format!("pyproject.toml#tool.{}", table));
+        }
+    }
+    None
+}
+
+/// The `[*]` section (and any keys before the first section) of an
+/// `.editorconfig`.
+fn parse_editorconfig(text: &str) -> EditorSettings {
+    let mut settings = EditorSettings::default();
+    let mut in_all = true;
+    let mut tab_width = None;
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_all = section == "*";
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else { continue };
+        if !in_all {
+            continue;
+        }
+        let value = value.trim().to_ascii_lowercase();
+        let flag = || match value.as_str() {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        };
+        match key.trim().to_ascii_lowercase().as_str() {
+            "indent_style" if value == "space" || value == "tab" => settings.indent_style = Some(value.clone()),
+            "indent_size" => settings.indent_size = value.parse().ok(),
+            "tab_width" => tab_width = value.parse().ok(),
+            "max_line_length" => settings.max_line_length = value.parse().ok(),
+            "end_of_line" if matches!(value.as_str(), "lf" | "crlf" | "cr") => settings.end_of_line = Some(value.clone()),
+            "insert_final_newline" => settings.insert_final_newline = flag(),
+            "trim_trailing_whitespace" => settings.trim_trailing_whitespace = flag(),
+            _ => {}
+        }
+    }
+    // `indent_size = tab` means "use tab_width".
+    settings.indent_size = settings.indent_size.or(tab_width);
+    settings
+}
+
+/// Prettier's layout options, when its config is data rather than code.
+fn prettier_settings(dir: &Path, config: &str, package: Option<&Value>) -> Option<EditorSettings> {
+    let options = match config {
+        "package.json#prettier" => package?.get("prettier")?.clone(),
+        ".prettierrc" | ".prettierrc.json" | ".prettierrc.yaml" | ".prettierrc.yml" => {
+            let text = std::fs::read_to_string(dir.join(config)).ok()?;
+            serde_yaml::from_str(&text).ok()?
+        }
+        ".prettierrc.toml" => serde_json::to_value(read_toml(&dir.join(config))?).ok()?,
+        _ => return None,
+    };
+    let options = options.as_object()?;
+    let number = |key: &str| options.get(key).and_then(Value::as_u64).map(|n| n as u32);
+    Some(EditorSettings {
+        indent_style: options
+            .get("useTabs")
+            .and_then(Value::as_bool)
+            .map(|tabs| if tabs { "tab" } else { "space" }.to_string()),
+        indent_size: number("tabWidth"),
+        max_line_length: number("printWidth"),
+        end_of_line: options.get("endOfLine").and_then(Value::as_str).filter(|e| *e != "auto").map(str::to_string),
+        insert_final_newline: None,
+        trim_trailing_whitespace: None,
+    })
+}
+
+/// `a` from `.editorconfig` unless unset, recording a conflict when
+/// Prettier's `b` says otherwise.
+fn pick<T: PartialEq + Clone + std::fmt::Display>(
+    setting: &str,
+    a: &Option<T>,
+    b: &Option<T>,
+    conflicts: &mut Vec<ToolingConflict>,
+) -> Option<T> {
+    if let (Some(a), Some(b)) = (a, b) {
+        if a != b {
+            conflicts.push(ToolingConflict {
+                kind: ConflictKind::Setting,
+                subject: setting.to_string(),
+                tools: vec!["editorconfig".to_string(), "prettier".to_string()],
+                message: format!(".editorconfig sets {} to {} but Prettier uses {}", setting, a, b),
+            });
+        }
+    }
+    a.clone().or_else(|| b.clone())
+}
+
+fn merge_settings(
+    editorconfig: Option<EditorSettings>,
+    prettier: Option<EditorSettings>,
+    conflicts: &mut Vec<ToolingConflict>,
+) -> EditorSettings {
+    let ec = editorconfig.unwrap_or_default();
+    let pr = prettier.unwrap_or_default();
+    EditorSettings {
+        indent_style: pick("indent_style", &ec.indent_style, &pr.indent_style, conflicts),
+        indent_size: pick("indent_size", &ec.indent_size, &pr.indent_size, conflicts),
+        max_line_length: pick("max_line_length", &ec.max_line_length, &pr.max_line_length, conflicts),
+        end_of_line: pick("end_of_line", &ec.end_of_line, &pr.end_of_line, conflicts),
+        insert_final_newline: ec.insert_final_newline,
+        trim_trailing_whitespace: ec.trim_trailing_whitespace,
+    }
+}
+
+pub(crate) fn discover(dir: &Path) -> ToolingReport {
+    let package = read_json(&dir.join("package.json"));
+    let pyproject = read_toml(&dir.join("pyproject.toml"));
+    let mut report = ToolingReport::default();
+
+    for tool in KNOWN_TOOLS {
+        let Some(config) = find_config(dir, tool, package.as_ref(), pyproject.as_ref()) else { continue };
+        report.tools.push(DetectedTool {
+            name: tool.name.to_string(),
+            language: tool.language.to_string(),
+            kinds: tool.kinds.to_vec(),
+            config,
+        });
+    }
+
+    for kind in [ToolKind::Formatter, ToolKind::Linter] {
+        let mut by_language: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for tool in report.tools.iter().filter(|t| t.kinds.contains(&kind)) {
+            by_language.entry(&tool.language).or_default().push(&tool.name);
+        }
+        for (language, names) in by_language {
+            if names.len() > 1 {
+                let (conflict_kind, role) = match kind {
+                    ToolKind::Formatter => (ConflictKind::Formatter, "format"),
+                    ToolKind::Linter => (ConflictKind::Linter, "lint"),
+                };
+                report.conflicts.push(ToolingConflict {
+                    kind: conflict_kind,
+                    subject: language.to_string(),
+                    tools: names.iter().map(|n| n.to_string()).collect(),
+                    message: format!("{} are all configured to {} {}", names.join(", "), role, language),
+                });
+            }
+            match kind {
+                ToolKind::Formatter => {
+                    report.formatters.insert(language.to_string(), names[0].to_string());
+                }
+                ToolKind::Linter => {
+                    report.linters.insert(language.to_string(), names.iter().map(|n| n.to_string()).collect());
+                }
+            }
+        }
+    }
+
+    let editorconfig = std::fs::read_to_string(dir.join(".editorconfig")).ok().map(|t| parse_editorconfig(&t));
+    if editorconfig.is_some() {
+        report.tools.push(DetectedTool {
+            name: "editorconfig".to_string(),
+            language: "*".to_string(),
+            kinds: Vec::new(),
+            config: ".editorconfig".to_string(),
+        });
+    }
+    let prettier = report
+        .tools
+        .iter()
+        .find(|t| t.name == "prettier")
+        .and_then(|t| prettier_settings(dir, &t.config, package.as_ref()));
+    report.editor = merge_settings(editorconfig, prettier, &mut report.conflicts);
+    report
+}
+
+/// Formatter, linter and editor settings for the open project, or for
+/// `root` (a directory inside it) when given.
+#[tauri::command]
+pub fn discover_tooling(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    root: Option<String>,
+) -> Result<ToolingReport, String> {
+    let dir = match root {
+        Some(root) => validate_path(&root, window.label(), &state)?,
+        None => state.blocking_read().get(window.label()).and_then(|o| o.clone()).ok_or("No project is open")?,
+    };
+    if !dir.is_dir() {
+        return Err("Not a directory".to_string());
+    }
+    Ok(discover(&dir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_tools_and_conflicts() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, text: &str| std::fs::write(dir.path().join(name), text).unwrap();
+        write("package.json", r#"{ "name": "app", "prettier": { "useTabs": true, "printWidth": 100 } }"#);
+        write("biome.json", "{}");
+        write("eslint.config.js", "export default [];");
+        write("pyproject.toml", "[tool.ruff]\nline-length = 88\n");
+        write(".editorconfig", "root = true\n\n[*]\nindent_style = space\nindent_size = 2\ninsert_final_newline = true\n\n[*.md]\nindent_size = 4\n");
+
+        let report = discover(dir.path());
+        let names: Vec<_> = report.tools.iter().map(|t| (t.name.as_str(), t.config.as_str())).collect();
+        assert_eq!(
+            names,
+            [
+                ("biome", "biome.json"),
+                ("prettier", "package.json#prettier"),
+                ("eslint", "eslint.config.js"),
+                ("ruff", "pyproject.toml#tool.ruff"),
+                ("editorconfig", ".editorconfig")
+            ]
+        );
+        assert_eq!(report.formatters.get("javascript").map(String::as_str), Some("biome"));
+        assert_eq!(report.linters["javascript"], ["biome", "eslint"]);
+        assert_eq!(report.linters["python"], ["ruff"]);
+
+        let conflicts: Vec<_> = report.conflicts.iter().map(|c| (c.kind, c.subject.as_str())).collect();
+        assert_eq!(
+            conflicts,
+            [
+                (ConflictKind::Formatter, "javascript"),
+                (ConflictKind::Linter, "javascript"),
+                (ConflictKind::Setting, "indent_style")
+            ]
+        );
+        assert_eq!(
+            report.editor,
+            EditorSettings {
+                indent_style: Some("space".into()),
+                indent_size: Some(2),
+                max_line_length: Some(100),
+                end_of_line: None,
+                insert_final_newline: Some(true),
+                trim_trailing_whitespace: None,
+            }
+        );
+    }
+
+    #[test]
+    fn empty_project_has_no_tooling() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(discover(dir.path()), ToolingReport::default());
+    }
+}
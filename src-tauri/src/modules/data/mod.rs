@@ -0,0 +1,460 @@
+//! Previews for data files.
+//!
+//! Tables are parsed in Rust and paged, so the data grid only ever holds
+//! one page of rows no matter how large the file is. Column types are
+//! inferred from the rows in the page. Parquet files carry their own
+//! schema, so only a sample of rows is decoded.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::Arc;
+
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::schema::types::Type as SchemaType;
+
+pub mod notebook;
+pub mod proto;
+
+use crate::modules::fs::{project_root_async, resolve_within_root, ProjectRootState};
+
+const DEFAULT_PAGE_ROWS: usize = 500;
+const MAX_PAGE_ROWS: usize = 10_000;
+const SNIFF_CANDIDATES: &[u8] = b",\t;|";
+
+// ── Types ──
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct TableOptions {
+    /// Single-character delimiter; detected from the extension and first
+    /// line when omitted.
+    pub delimiter: Option<String>,
+    pub limit: Option<usize>,
+    /// Number of data rows to skip.
+    pub offset: Option<usize>,
+    /// Resume from a `next_byte` of an earlier page instead of skipping
+    /// `offset` rows from the top; `offset` then only labels the first row.
+    pub start_byte: Option<u64>,
+    /// Whether the first row holds column names (default true).
+    pub has_header: Option<bool>,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnKind {
+    Integer,
+    Float,
+    Boolean,
+    Date,
+    DateTime,
+    String,
+    /// Every value in the page is empty.
+    Empty,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TableColumn {
+    pub name: String,
+    pub kind: ColumnKind,
+    /// Empty cells in this page.
+    pub null_count: usize,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TablePreview {
+    pub delimiter: String,
+    pub columns: Vec<TableColumn>,
+    /// Cells converted to their column's kind; empty cells are null.
+    pub rows: Vec<Vec<Value>>,
+    /// Index of the first returned row among the data rows.
+    pub offset: usize,
+    /// Byte position after the last returned row, for the next page.
+    pub next_byte: u64,
+    pub has_more: bool,
+    pub file_size: u64,
+}
+
+// ── Parsing helpers ──
+
+fn sniff_delimiter(path: &Path, first_line: &str) -> u8 {
+    match path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).as_deref() {
+        Some("tsv" | "tab") => return b'\t',
+        Some("psv") => return b'|',
+        _ => {}
+    }
+    let mut best = (b',', 0);
+    for &c in SNIFF_CANDIDATES {
+        let count = first_line.bytes().filter(|&b| b == c).count();
+        if count > best.1 {
+            best = (c, count);
+        }
+    }
+    best.0
+}
+
+fn parse_delimiter(value: &str) -> Result<u8, String> {
+    match value {
+        "\\t" | "tab" => Ok(b'\t'),
+        v if v.len() == 1 && v.is_ascii() => Ok(v.as_bytes()[0]),
+        _ => Err("Delimiter must be a single ASCII character".to_string()),
+    }
+}
+
+fn is_date(s: &str) -> bool {
+    let b = s.as_bytes();
+    b.len() == 10
+        && b[4] == b'-'
+        && b[7] == b'-'
+        && b.iter().enumerate().all(|(i, c)| i == 4 || i == 7 || c.is_ascii_digit())
+}
+
+fn is_datetime(s: &str) -> bool {
+    s.len() > 11 && is_date(&s[..10]) && matches!(s.as_bytes()[10], b'T' | b' ') && s[11..].starts_with(|c: char| c.is_ascii_digit())
+}
+
+fn parse_bool(s: &str) -> Option<bool> {
+    match s.to_ascii_lowercase().as_str() {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// The narrowest kind `value` fits.
+fn kind_of(value: &str) -> ColumnKind {
+    if value.parse::<i64>().is_ok() {
+        ColumnKind::Integer
+    } else if value.parse::<f64>().is_ok_and(|f| f.is_finite()) {
+        ColumnKind::Float
+    } else if parse_bool(value).is_some() {
+        ColumnKind::Boolean
+    } else if is_date(value) {
+        ColumnKind::Date
+    } else if is_datetime(value) {
+        ColumnKind::DateTime
+    } else {
+        ColumnKind::String
+    }
+}
+
+/// Smallest kind covering both.
+fn widen(a: ColumnKind, b: ColumnKind) -> ColumnKind {
+    use ColumnKind::*;
+    match (a, b) {
+        (Empty, k) | (k, Empty) => k,
+        (x, y) if x == y => x,
+        (Integer, Float) | (Float, Integer) => Float,
+        (Date, DateTime) | (DateTime, Date) => DateTime,
+        _ => String,
+    }
+}
+
+fn convert(value: &str, kind: ColumnKind) -> Value {
+    if value.is_empty() {
+        return Value::Null;
+    }
+    match kind {
+        ColumnKind::Integer => value.parse::<i64>().map(Value::from).unwrap_or_else(|_| value.into()),
+        ColumnKind::Float => value.parse::<f64>().map(Value::from).unwrap_or_else(|_| value.into()),
+        ColumnKind::Boolean => parse_bool(value).map(Value::Bool).unwrap_or_else(|| value.into()),
+        _ => Value::String(value.to_string()),
+    }
+}
+
+fn record_strings(record: &csv::ByteRecord) -> Vec<String> {
+    record.iter().map(|f| String::from_utf8_lossy(f).trim().to_string()).collect()
+}
+
+fn reader_at(file: File, delimiter: u8) -> csv::Reader<BufReader<File>> {
+    csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(BufReader::new(file))
+}
+
+// ── Preview ──
+
+fn preview(path: &Path, options: &TableOptions) -> Result<TablePreview, String> {
+    let io_err = |e: std::io::Error| format!("Failed to read file: {}", e.kind());
+    let csv_err = |e: csv::Error| format!("Failed to parse table: {}", e);
+    let file = File::open(path).map_err(io_err)?;
+    let file_size = file.metadata().map_err(io_err)?.len();
+    let limit = options.limit.unwrap_or(DEFAULT_PAGE_ROWS).clamp(1, MAX_PAGE_ROWS);
+    let offset = options.offset.unwrap_or(0);
+    let has_header = options.has_header.unwrap_or(true);
+
+    let delimiter = match &options.delimiter {
+        Some(d) => parse_delimiter(d)?,
+        None => {
+            let mut first_line = Vec::new();
+            BufReader::new(&file).read_until(b'\n', &mut first_line).map_err(io_err)?;
+            sniff_delimiter(path, &String::from_utf8_lossy(&first_line))
+        }
+    };
+
+    // The header always comes from the top of the file.
+    let mut reader = reader_at(File::open(path).map_err(io_err)?, delimiter);
+    let mut record = csv::ByteRecord::new();
+    let header = if has_header && reader.read_byte_record(&mut record).map_err(csv_err)? {
+        Some(record_strings(&record))
+    } else {
+        None
+    };
+
+    let base = match options.start_byte {
+        Some(start) => {
+            let mut file = File::open(path).map_err(io_err)?;
+            file.seek(SeekFrom::Start(start)).map_err(io_err)?;
+            reader = reader_at(file, delimiter);
+            start
+        }
+        None => {
+            for _ in 0..offset {
+                if !reader.read_byte_record(&mut record).map_err(csv_err)? {
+                    break;
+                }
+            }
+            0
+        }
+    };
+
+    let mut raw: Vec<Vec<String>> = Vec::new();
+    while raw.len() < limit && reader.read_byte_record(&mut record).map_err(csv_err)? {
+        raw.push(record_strings(&record));
+    }
+    let next_byte = base + reader.position().byte();
+    let has_more = reader.read_byte_record(&mut record).map_err(csv_err)?;
+
+    let width = raw.iter().map(Vec::len).chain(header.iter().map(Vec::len)).max().unwrap_or(0);
+    let mut columns: Vec<TableColumn> = (0..width)
+        .map(|i| TableColumn {
+            name: header.as_ref().and_then(|h| h.get(i)).cloned().unwrap_or_else(|| format!("Column {}", i + 1)),
+            kind: ColumnKind::Empty,
+            null_count: 0,
+        })
+        .collect();
+    for row in &raw {
+        for (i, column) in columns.iter_mut().enumerate() {
+            match row.get(i).map(String::as_str) {
+                None | Some("") => column.null_count += 1,
+                Some(v) => column.kind = widen(column.kind, kind_of(v)),
+            }
+        }
+    }
+    let rows = raw
+        .iter()
+        .map(|row| columns.iter().enumerate().map(|(i, c)| convert(row.get(i).map_or("", String::as_str), c.kind)).collect())
+        .collect();
+
+    Ok(TablePreview {
+        delimiter: (delimiter as char).to_string(),
+        columns,
+        rows,
+        offset,
+        next_byte,
+        has_more,
+        file_size,
+    })
+}
+
+/// Parse one page of a CSV/TSV file. Page forward with `start_byte` set
+/// to the previous page's `next_byte` to avoid re-reading the file from
+/// the top.
+#[tauri::command]
+pub async fn preview_table(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: String,
+    options: Option<TableOptions>,
+) -> Result<TablePreview, String> {
+    let root = project_root_async(window.label(), &state).await?;
+    let file = resolve_within_root(&path, &root)?;
+    let options = options.unwrap_or_default();
+    tokio::task::spawn_blocking(move || preview(&file, &options))
+        .await
+        .map_err(|e| format!("Preview failed: {}", e))?
+}
+
+// ── Parquet ──
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ParquetOptions {
+    /// Top-level columns to read, in order; all columns when omitted.
+    pub columns: Option<Vec<String>>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ParquetColumn {
+    pub name: String,
+    /// Physical type, or `GROUP` for nested columns.
+    pub physical_type: String,
+    /// Logical annotation such as `String` or `Timestamp { .. }`.
+    pub logical_type: Option<String>,
+    pub nullable: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ParquetPreview {
+    /// Full schema of the file.
+    pub columns: Vec<ParquetColumn>,
+    /// Names of the columns in each row, in order.
+    pub selected: Vec<String>,
+    /// Cells as JSON; nested groups become objects, binary becomes base64.
+    pub rows: Vec<Vec<Value>>,
+    pub total_rows: i64,
+    pub row_groups: usize,
+    pub created_by: Option<String>,
+}
+
+fn parquet_column(field: &SchemaType) -> ParquetColumn {
+    let info = field.get_basic_info();
+    ParquetColumn {
+        name: field.name().to_string(),
+        physical_type: if field.is_primitive() { field.get_physical_type().to_string() } else { "GROUP".to_string() },
+        logical_type: info.logical_type().map(|t| format!("{:?}", t)),
+        nullable: !info.has_repetition() || info.repetition() != parquet::basic::Repetition::REQUIRED,
+    }
+}
+
+fn preview_parquet_file(path: &Path, options: &ParquetOptions) -> Result<ParquetPreview, String> {
+    let parquet_err = |e: parquet::errors::ParquetError| format!("Failed to read parquet file: {}", e);
+    let file = File::open(path).map_err(|e| format!("Failed to read file: {}", e.kind()))?;
+    let reader = SerializedFileReader::new(file).map_err(parquet_err)?;
+    let metadata = reader.metadata();
+    let schema = metadata.file_metadata().schema();
+    let limit = options.limit.unwrap_or(DEFAULT_PAGE_ROWS).clamp(1, MAX_PAGE_ROWS);
+
+    let fields: Vec<Arc<SchemaType>> = match &options.columns {
+        None => schema.get_fields().to_vec(),
+        Some(names) => names
+            .iter()
+            .map(|name| {
+                schema.get_fields().iter().find(|f| f.name() == name).cloned().ok_or_else(|| format!("Unknown column: {}", name))
+            })
+            .collect::<Result<_, _>>()?,
+    };
+    let projection = SchemaType::group_type_builder(schema.name()).with_fields(fields.clone()).build().map_err(parquet_err)?;
+
+    let mut rows = Vec::new();
+    for row in reader.get_row_iter(Some(projection)).map_err(parquet_err)?.take(limit) {
+        let row = row.map_err(parquet_err)?;
+        rows.push(row.get_column_iter().map(|(_, field)| field.to_json_value()).collect());
+    }
+
+    Ok(ParquetPreview {
+        columns: schema.get_fields().iter().map(|f| parquet_column(f)).collect(),
+        selected: fields.iter().map(|f| f.name().to_string()).collect(),
+        rows,
+        total_rows: metadata.file_metadata().num_rows(),
+        row_groups: metadata.num_row_groups(),
+        created_by: metadata.file_metadata().created_by().map(str::to_string),
+    })
+}
+
+/// Schema and the first rows of a Parquet file.
+#[tauri::command]
+pub async fn preview_parquet(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: String,
+    options: Option<ParquetOptions>,
+) -> Result<ParquetPreview, String> {
+    let root = project_root_async(window.label(), &state).await?;
+    let file = resolve_within_root(&path, &root)?;
+    let options = options.unwrap_or_default();
+    tokio::task::spawn_blocking(move || preview_parquet_file(&file, &options))
+        .await
+        .map_err(|e| format!("Preview failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &tempfile::TempDir, name: &str, content: &str) -> std::path::PathBuf {
+        let path = dir.path().join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn quoted_fields_and_types() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write(&dir, "t.csv", "id,name,score,active,joined\n1,\"Doe, Jane\",9.5,true,2024-01-02\n2,\"multi\nline\",7,false,\n");
+        let p = preview(&path, &TableOptions::default()).unwrap();
+        let kinds: Vec<ColumnKind> = p.columns.iter().map(|c| c.kind).collect();
+        use ColumnKind::*;
+        assert_eq!(kinds, vec![Integer, String, Float, Boolean, Date]);
+        assert_eq!(p.rows[0][1], Value::from("Doe, Jane"));
+        assert_eq!(p.rows[1][1], Value::from("multi\nline"));
+        assert_eq!(p.rows[1][2], Value::from(7.0));
+        assert_eq!(p.rows[1][4], Value::Null);
+        assert_eq!(p.columns[4].null_count, 1);
+        assert!(!p.has_more);
+    }
+
+    #[test]
+    fn delimiter_is_sniffed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write(&dir, "t.txt", "a;b;c\n1;2;3\n");
+        assert_eq!(preview(&path, &TableOptions::default()).unwrap().delimiter, ";");
+        let path = write(&dir, "t.tsv", "a,b\tc\n1,2\t3\n");
+        let p = preview(&path, &TableOptions::default()).unwrap();
+        assert_eq!((p.delimiter.as_str(), p.columns.len()), ("\t", 2));
+    }
+
+    #[test]
+    fn pages_by_offset_and_by_byte() {
+        let dir = tempfile::tempdir().unwrap();
+        let body: String = (0..10).map(|i| format!("{}\n", i)).collect();
+        let path = write(&dir, "t.csv", &format!("n\n{}", body));
+
+        let first = preview(&path, &TableOptions { limit: Some(4), ..Default::default() }).unwrap();
+        assert!(first.has_more);
+        let next = preview(&path, &TableOptions { limit: Some(4), offset: Some(4), start_byte: Some(first.next_byte), ..Default::default() }).unwrap();
+        let by_offset = preview(&path, &TableOptions { limit: Some(4), offset: Some(4), ..Default::default() }).unwrap();
+        assert_eq!(next.rows, by_offset.rows);
+        assert_eq!(next.rows[0][0], Value::from(4));
+        assert_eq!(next.columns[0].name, "n");
+    }
+
+    #[test]
+    fn parquet_schema_and_projected_rows() {
+        use parquet::data_type::{ByteArray, ByteArrayType, Int64Type};
+        use parquet::file::writer::SerializedFileWriter;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("t.parquet");
+        let schema = Arc::new(
+            parquet::schema::parser::parse_message_type("message schema { REQUIRED INT64 id; OPTIONAL BYTE_ARRAY name (UTF8); }").unwrap(),
+        );
+        let mut writer = SerializedFileWriter::new(File::create(&path).unwrap(), schema, Default::default()).unwrap();
+        let mut group = writer.next_row_group().unwrap();
+        let mut col = group.next_column().unwrap().unwrap();
+        col.typed::<Int64Type>().write_batch(&[1, 2, 3], None, None).unwrap();
+        col.close().unwrap();
+        let mut col = group.next_column().unwrap().unwrap();
+        col.typed::<ByteArrayType>().write_batch(&[ByteArray::from("a"), ByteArray::from("c")], Some(&[1, 0, 1]), None).unwrap();
+        col.close().unwrap();
+        group.close().unwrap();
+        writer.close().unwrap();
+
+        let all = preview_parquet_file(&path, &ParquetOptions::default()).unwrap();
+        assert_eq!(all.total_rows, 3);
+        assert_eq!((all.columns[0].physical_type.as_str(), all.columns[0].nullable), ("INT64", false));
+        assert!(all.columns[1].nullable);
+        assert_eq!(all.rows[1], vec![Value::from(2), Value::Null]);
+
+        let options = ParquetOptions { columns: Some(vec!["name".into()]), limit: Some(2) };
+        let some = preview_parquet_file(&path, &options).unwrap();
+        assert_eq!(some.selected, vec!["name"]);
+        assert_eq!(some.rows, vec![vec![Value::from("a")], vec![Value::Null]]);
+        assert!(preview_parquet_file(&path, &ParquetOptions { columns: Some(vec!["x".into()]), limit: None }).is_err());
+    }
+}
@@ -0,0 +1,239 @@
+//! Jupyter notebooks (nbformat 4).
+//!
+//! Notebooks are handed to the frontend as a flat cell list with `source`
+//! joined into one string. Everything the editor doesn't touch — notebook
+//! and cell metadata, outputs, attachments, unknown fields — round-trips
+//! unchanged, so saving a notebook only rewrites the cells that changed.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::Path;
+
+use crate::modules::fs::{history, validate_path, write_atomic, ProjectRootState, MAX_TEXT_FILE_BYTES};
+
+const NBFORMAT: u64 = 4;
+const NBFORMAT_MINOR: u64 = 5;
+
+// ── Types ──
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct NotebookCell {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub cell_type: String,
+    /// Cell text; stored on disk as a list of lines.
+    #[serde(default, deserialize_with = "multiline")]
+    pub source: String,
+    #[serde(default)]
+    pub metadata: Value,
+    /// Code cells only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outputs: Option<Vec<Value>>,
+    /// Code cells only; null until the cell has run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_count: Option<Value>,
+    /// Fields this editor doesn't know about, e.g. `attachments`.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Notebook {
+    pub nbformat: u64,
+    pub nbformat_minor: u64,
+    pub metadata: Value,
+    /// Kernel language from `metadata.language_info` or the kernelspec.
+    pub language: Option<String>,
+    pub cells: Vec<NotebookCell>,
+}
+
+// ── nbformat helpers ──
+
+/// nbformat allows multiline strings either as one string or a list of
+/// lines.
+fn multiline<'de, D: serde::Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+    Ok(match Value::deserialize(d)? {
+        Value::String(s) => s,
+        Value::Array(lines) => lines.iter().filter_map(Value::as_str).collect(),
+        _ => String::new(),
+    })
+}
+
+/// Split into lines that keep their `\n`, as Jupyter writes them.
+fn to_lines(s: &str) -> Value {
+    Value::Array(s.split_inclusive('\n').map(|l| Value::String(l.to_string())).collect())
+}
+
+fn language_of(metadata: &Value) -> Option<String> {
+    metadata
+        .pointer("/language_info/name")
+        .or_else(|| metadata.pointer("/kernelspec/language"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+fn parse(text: &str) -> Result<(Map<String, Value>, Notebook), String> {
+    let mut doc: Map<String, Value> =
+        serde_json::from_str(text).map_err(|e| format!("Invalid notebook JSON: {}", e))?;
+    let nbformat = doc.get("nbformat").and_then(Value::as_u64).unwrap_or(0);
+    if nbformat != NBFORMAT {
+        return Err(format!("Unsupported notebook format: nbformat {}", nbformat));
+    }
+    let cells: Vec<NotebookCell> = match doc.remove("cells") {
+        Some(cells) => serde_json::from_value(cells).map_err(|e| format!("Invalid notebook cell: {}", e))?,
+        None => Vec::new(),
+    };
+    let metadata = doc.get("metadata").cloned().unwrap_or_else(|| Value::Object(Map::new()));
+    let notebook = Notebook {
+        nbformat,
+        nbformat_minor: doc.get("nbformat_minor").and_then(Value::as_u64).unwrap_or(0),
+        language: language_of(&metadata),
+        metadata,
+        cells,
+    };
+    Ok((doc, notebook))
+}
+
+/// Serialize `cells` into `doc` (the existing notebook minus its cells)
+/// the way Jupyter does: sorted keys, one-space indent, trailing newline.
+fn serialize(mut doc: Map<String, Value>, cells: Vec<NotebookCell>, metadata: Option<Value>) -> Result<String, String> {
+    let with_ids = doc.get("nbformat_minor").and_then(Value::as_u64).unwrap_or(0) >= 5;
+    let cells = cells
+        .into_iter()
+        .map(|cell| {
+            let is_code = cell.cell_type == "code";
+            let mut value = serde_json::to_value(&cell).map_err(|e| e.to_string())?;
+            let obj = value.as_object_mut().ok_or("Invalid notebook cell")?;
+            obj.insert("source".into(), to_lines(&cell.source));
+            if cell.metadata.is_null() {
+                obj.insert("metadata".into(), Value::Object(Map::new()));
+            }
+            if is_code {
+                // Both are required on code cells and forbidden elsewhere.
+                obj.entry("outputs").or_insert_with(|| Value::Array(Vec::new()));
+                obj.entry("execution_count").or_insert(Value::Null);
+            } else {
+                obj.remove("outputs");
+                obj.remove("execution_count");
+            }
+            if !with_ids {
+                obj.remove("id");
+            }
+            Ok(value)
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    doc.insert("cells".into(), Value::Array(cells));
+    if let Some(metadata) = metadata {
+        doc.insert("metadata".into(), metadata);
+    }
+    doc.entry("metadata").or_insert_with(|| Value::Object(Map::new()));
+    doc.entry("nbformat").or_insert(NBFORMAT.into());
+    doc.entry("nbformat_minor").or_insert(NBFORMAT_MINOR.into());
+
+    let sorted: std::collections::BTreeMap<_, _> = doc.into_iter().collect();
+    let mut out = Vec::new();
+    let mut ser = serde_json::Serializer::with_formatter(&mut out, serde_json::ser::PrettyFormatter::with_indent(b" "));
+    sorted.serialize(&mut ser).map_err(|e| e.to_string())?;
+    out.push(b'\n');
+    String::from_utf8(out).map_err(|e| e.to_string())
+}
+
+fn read_text(path: &Path) -> Result<String, String> {
+    let meta = std::fs::metadata(path).map_err(|e| format!("Failed to read file: {}", e.kind()))?;
+    if meta.len() > MAX_TEXT_FILE_BYTES {
+        return Err(format!("FILE_TOO_LARGE: {} bytes; limit {}", meta.len(), MAX_TEXT_FILE_BYTES));
+    }
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e.kind()))
+}
+
+// ── Commands ──
+
+#[tauri::command]
+pub fn read_notebook(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: String,
+) -> Result<Notebook, String> {
+    let file = validate_path(&path, window.label(), &state)?;
+    parse(&read_text(&file)?).map(|(_, notebook)| notebook)
+}
+
+/// Save `cells` into the notebook at `path`. Top-level fields of the
+/// existing file are kept; `metadata` replaces the notebook metadata when
+/// given. A missing file is created as an empty nbformat 4 notebook.
+#[tauri::command]
+pub fn write_notebook(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: String,
+    cells: Vec<NotebookCell>,
+    metadata: Option<Value>,
+) -> Result<(), String> {
+    let file = validate_path(&path, window.label(), &state)?;
+    let root = state.blocking_read().get(window.label()).and_then(|o| o.clone()).ok_or("No project is open")?;
+    let doc = if file.exists() { parse(&read_text(&file)?)?.0 } else { Map::new() };
+    let content = serialize(doc, cells, metadata)?;
+    history::keep(&root, &file, content.as_bytes());
+    write_atomic(&file, content.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r##"{
+ "cells": [
+  {
+   "cell_type": "markdown",
+   "id": "a1",
+   "metadata": {},
+   "source": ["# Title\n", "Intro"]
+  },
+  {
+   "cell_type": "code",
+   "execution_count": 3,
+   "id": "b2",
+   "metadata": {"tags": ["setup"]},
+   "outputs": [{"name": "stdout", "output_type": "stream", "text": ["hi\n"]}],
+   "source": "print('hi')"
+  }
+ ],
+ "metadata": {"kernelspec": {"language": "python", "name": "python3"}},
+ "nbformat": 4,
+ "nbformat_minor": 5
+}"##;
+
+    #[test]
+    fn reads_cells_and_language() {
+        let (_, nb) = parse(SAMPLE).unwrap();
+        assert_eq!(nb.language.as_deref(), Some("python"));
+        assert_eq!(nb.cells.len(), 2);
+        assert_eq!(nb.cells[0].source, "# Title\nIntro");
+        assert_eq!(nb.cells[1].execution_count, Some(Value::from(3)));
+        assert_eq!(nb.cells[1].outputs.as_ref().map(Vec::len), Some(1));
+    }
+
+    #[test]
+    fn round_trip_preserves_outputs_and_metadata() {
+        let (doc, nb) = parse(SAMPLE).unwrap();
+        let mut cells = nb.cells;
+        cells[0].source = "# New title\n".into();
+        cells.push(NotebookCell { cell_type: "raw".into(), source: "x".into(), ..Default::default() });
+        let text = serialize(doc, cells, None).unwrap();
+
+        let saved: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(saved["metadata"]["kernelspec"]["name"], "python3");
+        assert_eq!(saved["cells"][0]["source"], serde_json::json!(["# New title\n"]));
+        assert_eq!(saved["cells"][1]["outputs"][0]["text"], serde_json::json!(["hi\n"]));
+        assert_eq!(saved["cells"][1]["metadata"]["tags"], serde_json::json!(["setup"]));
+        assert!(saved["cells"][2].get("outputs").is_none());
+        assert_eq!(saved["cells"][2]["metadata"], serde_json::json!({}));
+        assert!(text.starts_with("{\n \"cells\": ["));
+        assert!(text.ends_with("}\n"));
+    }
+
+    #[test]
+    fn rejects_old_formats() {
+        assert!(parse(r#"{"nbformat": 3, "worksheets": []}"#).unwrap_err().contains("nbformat 3"));
+    }
+}
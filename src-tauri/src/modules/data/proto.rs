@@ -0,0 +1,119 @@
+//! Schema-aware decoding of binary Protocol Buffers payloads.
+//!
+//! The `.proto` file is compiled on the fly, so there's no codegen step:
+//! point at a payload, its schema and a message name and get the message
+//! back as proto3 JSON.
+
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor, SerializeOptions};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+
+use crate::modules::fs::{validate_path, ProjectRootState, MAX_BINARY_FILE_BYTES};
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DecodedMessage {
+    /// Fully qualified name of the decoded message type.
+    pub message_type: String,
+    pub value: Value,
+}
+
+/// Compile `schema` with its own directory and the project root as import
+/// paths.
+fn compile(schema: &Path, root: &Path) -> Result<DescriptorPool, String> {
+    let dir = schema.parent().ok_or("Invalid schema path")?;
+    let name = schema.file_name().ok_or("Invalid schema path")?;
+    let mut includes = vec![dir.to_path_buf()];
+    if dir != root {
+        includes.push(root.to_path_buf());
+    }
+    let mut compiler = protox::Compiler::new(includes).map_err(|e| format!("Failed to compile schema: {}", e))?;
+    compiler.include_imports(true);
+    compiler.open_file(name).map_err(|e| format!("Failed to compile schema: {}", e))?;
+    Ok(compiler.descriptor_pool())
+}
+
+/// Look `name` up by full name, or by short name when that's unambiguous.
+fn find_message(pool: &DescriptorPool, name: &str) -> Result<MessageDescriptor, String> {
+    if let Some(message) = pool.get_message_by_name(name.trim_start_matches('.')) {
+        return Ok(message);
+    }
+    let mut candidates: Vec<MessageDescriptor> = pool.all_messages().filter(|m| m.name() == name).collect();
+    match candidates.len() {
+        1 => Ok(candidates.remove(0)),
+        0 => {
+            let known: Vec<String> = pool
+                .all_messages()
+                .filter(|m| !m.full_name().starts_with("google.protobuf."))
+                .map(|m| m.full_name().to_string())
+                .collect();
+            Err(format!("Unknown message type: {} (schema defines {})", name, known.join(", ")))
+        }
+        _ => Err(format!("Ambiguous message type: {}; use the fully qualified name", name)),
+    }
+}
+
+fn decode(bytes: &[u8], message: MessageDescriptor) -> Result<DecodedMessage, String> {
+    let message_type = message.full_name().to_string();
+    let decoded = DynamicMessage::decode(message, bytes).map_err(|e| format!("Failed to decode {}: {}", message_type, e))?;
+    // Show every field, including ones at their default value, since this
+    // is for inspecting payloads rather than exchanging them.
+    let options = SerializeOptions::new().skip_default_fields(false);
+    let value = decoded
+        .serialize_with_options(serde_json::value::Serializer, &options)
+        .map_err(|e| format!("Failed to convert {}: {}", message_type, e))?;
+    Ok(DecodedMessage { message_type, value })
+}
+
+/// Decode the binary protobuf message in `data_path` as `message_type`
+/// from the `.proto` file at `schema_path`.
+#[tauri::command]
+pub fn decode_with_schema(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    data_path: String,
+    schema_path: String,
+    message_type: String,
+) -> Result<DecodedMessage, String> {
+    let data = validate_path(&data_path, window.label(), &state)?;
+    let schema = validate_path(&schema_path, window.label(), &state)?;
+    let root = state.blocking_read().get(window.label()).and_then(|o| o.clone()).ok_or("No project is open")?;
+
+    let meta = std::fs::metadata(&data).map_err(|e| format!("Failed to read file: {}", e.kind()))?;
+    if meta.len() > MAX_BINARY_FILE_BYTES {
+        return Err(format!("FILE_TOO_LARGE: {} bytes; limit {}", meta.len(), MAX_BINARY_FILE_BYTES));
+    }
+    let pool = compile(&schema, &root)?;
+    let message = find_message(&pool, &message_type)?;
+    let bytes = std::fs::read(&data).map_err(|e| format!("Failed to read file: {}", e.kind()))?;
+    decode(&bytes, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_payload_against_compiled_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = std::fs::canonicalize(dir.path()).unwrap();
+        std::fs::create_dir(root.join("proto")).unwrap();
+        std::fs::write(root.join("proto/common.proto"), "syntax = \"proto3\";\npackage demo;\nmessage Tag { string name = 1; }\n").unwrap();
+        std::fs::write(
+            root.join("proto/point.proto"),
+            "syntax = \"proto3\";\npackage demo;\nimport \"common.proto\";\nmessage Point { int32 x = 1; string label = 2; Tag tag = 3; bool hidden = 4; }\n",
+        )
+        .unwrap();
+
+        let pool = compile(&root.join("proto/point.proto"), &root).unwrap();
+        let point = find_message(&pool, "Point").unwrap();
+        // x = 150, label = "hi", tag = { name: "t" }
+        let bytes = [0x08, 0x96, 0x01, 0x12, 0x02, b'h', b'i', 0x1a, 0x03, 0x0a, 0x01, b't'];
+        let decoded = decode(&bytes, point).unwrap();
+        assert_eq!(decoded.message_type, "demo.Point");
+        assert_eq!(decoded.value, serde_json::json!({"x": 150, "label": "hi", "tag": {"name": "t"}, "hidden": false}));
+
+        assert!(find_message(&pool, "Missing").unwrap_err().contains("demo.Point"));
+        assert!(decode(&[0x08], find_message(&pool, "demo.Point").unwrap()).is_err());
+    }
+}
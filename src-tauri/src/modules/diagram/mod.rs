@@ -0,0 +1,289 @@
+//! Diagram rendering for markdown previews and `.mmd` / `.puml` files.
+//!
+//! Nothing is rendered in-process; Mermaid goes through mermaid-cli
+//! (`mmdc`) and PlantUML through a local `plantuml` launcher or
+//! `java -jar $PLANTUML_JAR`. When neither is installed, PlantUML source
+//! goes to the public PlantUML server only if `plantuml_server` is turned
+//! on in `~/.leo-ide/diagrams.json`; it's off by default, since the source
+//! leaves the machine. Rendered SVGs are cached on disk keyed by a hash of
+//! the kind and source, so a diagram that rendered once keeps showing
+//! offline.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+use crate::modules::net;
+use crate::modules::policy::Category;
+
+/// Policy categories of this module's commands.
+pub(crate) const COMMAND_CATEGORIES: &[(&str, &[Category])] = &[("render_diagram", &[Category::Network])];
+
+const RENDER_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_SOURCE_BYTES: usize = 256 * 1024;
+const MAX_CACHE_ENTRIES: usize = 500;
+const PLANTUML_SERVER: &str = "https://www.plantuml.com/plantuml/svg/";
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct DiagramSettings {
+    /// Send PlantUML source to plantuml.com when no local renderer is
+    /// installed.
+    pub plantuml_server: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum DiagramKind {
+    Mermaid,
+    PlantUml,
+}
+
+impl DiagramKind {
+    fn parse(kind: &str) -> Result<Self, String> {
+        match kind.to_ascii_lowercase().as_str() {
+            "mermaid" | "mmd" => Ok(Self::Mermaid),
+            "plantuml" | "puml" | "uml" => Ok(Self::PlantUml),
+            other => Err(format!("Unsupported diagram kind: {}", other)),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Mermaid => "mermaid",
+            Self::PlantUml => "plantuml",
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RenderedDiagram {
+    pub svg: String,
+    /// Which renderer produced it: "mmdc", "plantuml", "java" or "server".
+    pub renderer: String,
+    pub cached: bool,
+}
+
+// ── Settings ──
+
+fn settings_path() -> PathBuf {
+    dirs::home_dir().unwrap_or_default().join(".leo-ide").join("diagrams.json")
+}
+
+fn load_settings() -> DiagramSettings {
+    std::fs::read_to_string(settings_path()).ok().and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default()
+}
+
+// ── Cache ──
+
+fn cache_dir() -> PathBuf {
+    dirs::home_dir().unwrap_or_default().join(".leo-ide").join("diagrams")
+}
+
+fn cache_key(kind: DiagramKind, source: &str) -> String {
+    let mut h = Sha256::new();
+    h.update(kind.name().as_bytes());
+    h.update([0]);
+    h.update(source.as_bytes());
+    h.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn cache_get(dir: &Path, key: &str) -> Option<(String, String)> {
+    let text = std::fs::read_to_string(dir.join(format!("{}.svg", key))).ok()?;
+    // First line records the renderer so `renderer` stays accurate on hits.
+    let (renderer, svg) = text.split_once('\n')?;
+    Some((renderer.to_string(), svg.to_string()))
+}
+
+/// Store a rendered SVG, dropping the least recently written entries once
+/// the cache is full. Failures are ignored; the cache is best-effort.
+fn cache_put(dir: &Path, key: &str, renderer: &str, svg: &str) {
+    if std::fs::create_dir_all(dir).is_err() {
+        return;
+    }
+    let _ = crate::modules::fs::write_atomic(&dir.join(format!("{}.svg", key)), format!("{}\n{}", renderer, svg).as_bytes());
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    let mut files: Vec<_> = entries
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|x| x == "svg"))
+        .map(|e| (e.metadata().and_then(|m| m.modified()).ok(), e.path()))
+        .collect();
+    if files.len() > MAX_CACHE_ENTRIES {
+        files.sort();
+        for (_, path) in &files[..files.len() - MAX_CACHE_ENTRIES] {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+// ── Renderers ──
+
+fn find_executable(name: &str) -> Option<PathBuf> {
+    let exts: &[&str] = if cfg!(windows) { &[".exe", ".cmd", ".bat"] } else { &[""] };
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .flat_map(|dir| exts.iter().map(move |ext| dir.join(format!("{}{}", name, ext))))
+        .find(|p| p.is_file())
+}
+
+/// Run a renderer with `input` on stdin and return its stdout.
+async fn run(program: &Path, args: &[String], input: &[u8]) -> Result<Vec<u8>, String> {
+    let name = program.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    let mut child = tokio::process::Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", name, e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input).await.map_err(|e| format!("Failed to write to {}: {}", name, e))?;
+    }
+    let output = tokio::time::timeout(RENDER_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| format!("{} timed out after {}s", name, RENDER_TIMEOUT.as_secs()))?
+        .map_err(|e| format!("{} failed: {}", name, e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("{} failed: {}", name, stderr.trim()));
+    }
+    Ok(output.stdout)
+}
+
+async fn render_mermaid(source: &str) -> Result<(String, String), String> {
+    let mmdc = find_executable("mmdc")
+        .ok_or("RENDERER_MISSING: Mermaid rendering needs mermaid-cli (npm install -g @mermaid-js/mermaid-cli)")?;
+    // mmdc picks the output format from the file extension, so go through
+    // a temp file rather than stdout.
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let out = std::env::temp_dir().join(format!("leo-mermaid-{}-{}.svg", std::process::id(), COUNTER.fetch_add(1, Ordering::Relaxed)));
+    let args = ["--quiet", "-i", "-", "-o", &out.to_string_lossy(), "-b", "transparent"].map(String::from);
+    let result = run(&mmdc, &args, source.as_bytes()).await;
+    let svg = result.and_then(|_| std::fs::read_to_string(&out).map_err(|e| format!("mmdc produced no output: {}", e)));
+    let _ = std::fs::remove_file(&out);
+    Ok(("mmdc".to_string(), svg?))
+}
+
+/// Hex form of the PlantUML text encoding (`~h` prefix), which the server
+/// accepts in place of its deflate-based one.
+fn plantuml_server_url(source: &str) -> String {
+    let hex: String = source.bytes().map(|b| format!("{:02x}", b)).collect();
+    format!("{}~h{}", PLANTUML_SERVER, hex)
+}
+
+async fn render_plantuml(source: &str, settings: &DiagramSettings) -> Result<(String, String), String> {
+    let utf8 = ["-tsvg", "-pipe", "-charset", "UTF-8"].map(String::from);
+    let local = match (find_executable("plantuml"), std::env::var_os("PLANTUML_JAR"), find_executable("java")) {
+        (Some(plantuml), _, _) => Some(("plantuml", plantuml, utf8.to_vec())),
+        (None, Some(jar), Some(java)) => {
+            Some(("java", java, [vec!["-jar".to_string(), jar.to_string_lossy().into_owned()], utf8.to_vec()].concat()))
+        }
+        _ => None,
+    };
+    if let Some((name, program, args)) = local {
+        let svg = run(&program, &args, source.as_bytes()).await?;
+        return Ok((name.to_string(), String::from_utf8_lossy(&svg).into_owned()));
+    }
+    if !settings.plantuml_server {
+        return Err("RENDERER_MISSING: PlantUML rendering needs a local `plantuml` launcher, or Java with PLANTUML_JAR set. \
+            Turn on the PlantUML server in the diagram settings to render on plantuml.com instead"
+            .to_string());
+    }
+
+    net::ensure_online("PlantUML rendering")?;
+    let resp = net::client()
+        .get(plantuml_server_url(source))
+        .send()
+        .await
+        .map_err(|e| format!("PlantUML server request failed: {}", e))?;
+    // The server answers syntax errors with 400 and an SVG describing the
+    // error, which is still worth showing.
+    let status = resp.status();
+    let svg = resp.text().await.map_err(|e| format!("PlantUML server request failed: {}", e))?;
+    if !svg.contains("<svg") {
+        return Err(format!("PlantUML server returned {}", status));
+    }
+    Ok(("server".to_string(), svg))
+}
+
+// ── Commands ──
+
+#[tauri::command]
+pub fn get_diagram_settings() -> DiagramSettings {
+    load_settings()
+}
+
+#[tauri::command]
+pub fn set_diagram_settings(settings: DiagramSettings) -> Result<(), String> {
+    let path = settings_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to save diagram settings: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+    crate::modules::fs::write_atomic(&path, json.as_bytes()).map_err(|e| format!("Failed to save diagram settings: {}", e))
+}
+
+/// Render a Mermaid or PlantUML diagram to SVG. `kind` is "mermaid" or
+/// "plantuml" (file extensions `mmd`/`puml` work too). Errors starting
+/// with `RENDERER_MISSING:` mean no renderer is installed, or for
+/// PlantUML that none is and the server fallback is off.
+#[tauri::command]
+pub async fn render_diagram(source: String, kind: String) -> Result<RenderedDiagram, String> {
+    let kind = DiagramKind::parse(&kind)?;
+    if source.len() > MAX_SOURCE_BYTES {
+        return Err(format!("CONTENT_TOO_LARGE: {} bytes; limit {}", source.len(), MAX_SOURCE_BYTES));
+    }
+    let dir = cache_dir();
+    let key = cache_key(kind, &source);
+    if let Some((renderer, svg)) = cache_get(&dir, &key) {
+        return Ok(RenderedDiagram { svg, renderer, cached: true });
+    }
+    let (renderer, svg) = match kind {
+        DiagramKind::Mermaid => render_mermaid(&source).await?,
+        DiagramKind::PlantUml => render_plantuml(&source, &load_settings()).await?,
+    };
+    let (dir, key, to_cache) = (dir, key, (renderer.clone(), svg.clone()));
+    let _ = tokio::task::spawn_blocking(move || cache_put(&dir, &key, &to_cache.0, &to_cache.1)).await;
+    Ok(RenderedDiagram { svg, renderer, cached: false })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_round_trips_and_stays_bounded() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = cache_key(DiagramKind::Mermaid, "graph TD; A-->B");
+        assert_ne!(key, cache_key(DiagramKind::PlantUml, "graph TD; A-->B"));
+        assert!(cache_get(dir.path(), &key).is_none());
+        cache_put(dir.path(), &key, "mmdc", "<svg>\n</svg>");
+        assert_eq!(cache_get(dir.path(), &key), Some(("mmdc".to_string(), "<svg>\n</svg>".to_string())));
+
+        for i in 0..MAX_CACHE_ENTRIES + 5 {
+            cache_put(dir.path(), &format!("k{}", i), "server", "<svg/>");
+        }
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), MAX_CACHE_ENTRIES);
+    }
+
+    #[test]
+    fn parses_kinds_and_encodes_server_urls() {
+        assert_eq!(DiagramKind::parse("Mermaid").unwrap(), DiagramKind::Mermaid);
+        assert_eq!(DiagramKind::parse("puml").unwrap(), DiagramKind::PlantUml);
+        assert!(DiagramKind::parse("graphviz").is_err());
+        assert_eq!(plantuml_server_url("A->B"), format!("{}~h412d3e42", PLANTUML_SERVER));
+    }
+
+    #[tokio::test]
+    async fn plantuml_server_is_opt_in() {
+        if find_executable("plantuml").is_some() || (std::env::var_os("PLANTUML_JAR").is_some() && find_executable("java").is_some()) {
+            return;
+        }
+        let err = render_plantuml("@startuml\nA -> B\n@enduml", &DiagramSettings::default()).await.unwrap_err();
+        assert!(err.starts_with("RENDERER_MISSING:") && err.contains("PlantUML server"));
+        assert_eq!(serde_json::from_str::<DiagramSettings>("{}").unwrap(), DiagramSettings { plantuml_server: false });
+    }
+}
@@ -0,0 +1,655 @@
+//! Emmet abbreviation expansion for HTML, JSX and CSS.
+//!
+//! Markup abbreviations support the usual syntax: `>` child, `+` sibling,
+//! `^` climb up, `(…)` groups, `*N` repetition with `$` numbering
+//! (`$$`, `$@-`, `$@3`), `#id`, `.class`, `[attr=value]` and `{text}`.
+//! Tags are inferred from the parent when omitted (`ul>.item` gives
+//! `li`). CSS abbreviations follow Emmet's property shorthands: `m10-20`,
+//! `w100p`, `dib`, `pos:a`, `c#f`, chained with `+`.
+
+use serde::Deserialize;
+
+const MAX_REPEAT: usize = 1000;
+const MAX_NODES: usize = 10_000;
+
+const SELF_CLOSING: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source", "track", "wbr",
+];
+const INLINE: &[&str] = &[
+    "a", "abbr", "b", "bdo", "button", "cite", "code", "dfn", "em", "i", "kbd", "label", "q", "s", "samp", "small",
+    "span", "strong", "sub", "sup", "time", "u", "var",
+];
+/// Attributes added when an element doesn't set them itself.
+const DEFAULT_ATTRS: &[(&str, &[&str])] = &[
+    ("a", &["href"]),
+    ("img", &["src", "alt"]),
+    ("input", &["type=text"]),
+    ("link", &["rel=stylesheet", "href"]),
+    ("script", &["src"]),
+    ("form", &["action"]),
+    ("label", &["for"]),
+    ("iframe", &["src"]),
+    ("source", &["src"]),
+    ("option", &["value"]),
+];
+
+const HTML5_DOC: &str = "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n{i}<meta charset=\"UTF-8\">\n{i}<meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">\n{i}<title>{t1}</title>\n</head>\n<body>\n{i}{t2}\n</body>\n</html>";
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct EmmetOptions {
+    /// Indentation unit (default two spaces).
+    pub indent: Option<String>,
+    /// Emit `${1}`-style tab stops in empty attributes and elements.
+    pub tabstops: Option<bool>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Syntax {
+    Html,
+    Jsx,
+    Css,
+}
+
+fn syntax_of(language: &str) -> Result<Syntax, String> {
+    match language.to_ascii_lowercase().as_str() {
+        "html" | "htm" | "xhtml" | "svelte" | "vue" | "xml" | "php" => Ok(Syntax::Html),
+        "jsx" | "tsx" | "javascriptreact" | "typescriptreact" => Ok(Syntax::Jsx),
+        "css" | "scss" | "less" | "sass" | "styl" | "stylus" | "postcss" => Ok(Syntax::Css),
+        other => Err(format!("Emmet is not available for {}", other)),
+    }
+}
+
+// ── Markup parsing ──
+
+#[derive(Clone, Debug, Default)]
+struct Node {
+    name: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+    attrs: Vec<(String, Option<String>)>,
+    text: Option<String>,
+    repeat: Option<usize>,
+    children: Vec<Node>,
+    /// Parenthesized group; renders its children in place.
+    group: bool,
+}
+
+struct Parser<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    src: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn new(src: &'a str) -> Self {
+        Self { chars: src.chars().collect(), pos: 0, src }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn error(&self, what: &str) -> String {
+        format!("Invalid abbreviation \"{}\": {} at {}", self.src, what, self.pos)
+    }
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(&f) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    /// Contents up to the matching `close`, honoring nesting.
+    fn delimited(&mut self, close: char) -> Result<String, String> {
+        let open = self.chars[self.pos];
+        self.pos += 1;
+        let start = self.pos;
+        let mut depth = 0;
+        while let Some(c) = self.peek() {
+            if c == open && open != close {
+                depth += 1;
+            } else if c == close {
+                if depth == 0 {
+                    let inner = self.chars[start..self.pos].iter().collect();
+                    self.pos += 1;
+                    return Ok(inner);
+                }
+                depth -= 1;
+            }
+            self.pos += 1;
+        }
+        Err(self.error(&format!("missing '{}'", close)))
+    }
+
+    fn name_char(c: char) -> bool {
+        c.is_alphanumeric() || matches!(c, '-' | '_' | ':' | '$' | '@' | '!')
+    }
+
+    /// Sibling list, stopping at `)` or the end.
+    fn parse_list(&mut self) -> Result<Vec<Node>, String> {
+        // levels[k] holds the siblings being built at depth k; each level
+        // past the first belongs to the last node of the level before it.
+        let mut levels: Vec<Vec<Node>> = vec![Vec::new()];
+        loop {
+            let item = self.parse_item()?;
+            levels.last_mut().unwrap().push(item);
+            match self.peek() {
+                Some('>') => {
+                    self.pos += 1;
+                    levels.push(Vec::new());
+                }
+                Some('+') => self.pos += 1,
+                Some('^') => {
+                    while self.peek() == Some('^') {
+                        self.pos += 1;
+                        if levels.len() > 1 {
+                            fold(&mut levels);
+                        }
+                    }
+                }
+                Some(')') | None => break,
+                Some(_) => return Err(self.error("unexpected character")),
+            }
+        }
+        while levels.len() > 1 {
+            fold(&mut levels);
+        }
+        Ok(levels.pop().unwrap())
+    }
+
+    fn parse_item(&mut self) -> Result<Node, String> {
+        let mut node = if self.peek() == Some('(') {
+            self.pos += 1;
+            let children = self.parse_list()?;
+            if self.peek() != Some(')') {
+                return Err(self.error("missing ')'"));
+            }
+            self.pos += 1;
+            Node { children, group: true, ..Default::default() }
+        } else {
+            self.parse_element()?
+        };
+        if self.peek() == Some('*') {
+            self.pos += 1;
+            let count = self.take_while(|c| c.is_ascii_digit());
+            let count: usize = count.parse().map_err(|_| self.error("expected a number after '*'"))?;
+            if count > MAX_REPEAT {
+                return Err(self.error("repeat count too large"));
+            }
+            node.repeat = Some(count);
+        }
+        Ok(node)
+    }
+
+    fn parse_element(&mut self) -> Result<Node, String> {
+        let mut node = Node::default();
+        let name = self.take_while(Self::name_char);
+        if !name.is_empty() {
+            node.name = Some(name);
+        }
+        loop {
+            match self.peek() {
+                Some('#') => {
+                    self.pos += 1;
+                    node.id = Some(self.take_while(Self::name_char));
+                }
+                Some('.') => {
+                    self.pos += 1;
+                    let class = self.take_while(Self::name_char);
+                    if class.is_empty() {
+                        return Err(self.error("empty class name"));
+                    }
+                    node.classes.push(class);
+                }
+                Some('[') => {
+                    let inner = self.delimited(']')?;
+                    node.attrs.extend(parse_attrs(&inner));
+                }
+                Some('{') => {
+                    let text = self.delimited('}')?;
+                    node.text = Some(node.text.take().unwrap_or_default() + &text);
+                }
+                _ => break,
+            }
+        }
+        if node.name.is_none() && node.id.is_none() && node.classes.is_empty() && node.attrs.is_empty() && node.text.is_none() {
+            return Err(self.error("expected an element"));
+        }
+        Ok(node)
+    }
+}
+
+/// Attach the deepest level to the last node of the level above.
+fn fold(levels: &mut Vec<Vec<Node>>) {
+    let children = levels.pop().unwrap_or_default();
+    if let Some(parent) = levels.last_mut().and_then(|l| l.last_mut()) {
+        parent.children.extend(children);
+    }
+}
+
+/// `a=1 b="two words" c` → pairs; a bare name has no value.
+fn parse_attrs(inner: &str) -> Vec<(String, Option<String>)> {
+    let mut attrs = Vec::new();
+    let mut chars = inner.chars().peekable();
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+        let name: String = std::iter::from_fn(|| chars.next_if(|c| !c.is_whitespace() && *c != '=')).collect();
+        if name.is_empty() {
+            break;
+        }
+        let value = if chars.next_if_eq(&'=').is_some() {
+            Some(match chars.peek().copied() {
+                Some(q @ ('"' | '\'')) => {
+                    chars.next();
+                    let v: String = std::iter::from_fn(|| chars.next_if(|c| *c != q)).collect();
+                    chars.next();
+                    v
+                }
+                _ => std::iter::from_fn(|| chars.next_if(|c| !c.is_whitespace())).collect(),
+            })
+        } else {
+            None
+        };
+        attrs.push((name, value));
+    }
+    attrs
+}
+
+// ── Markup rendering ──
+
+/// Replace `$` runs with the 1-based repeat index, zero-padded to the run
+/// length. `@-` counts down, `@N` starts at N.
+fn number(text: &str, index: usize, count: usize) -> String {
+    if !text.contains('$') {
+        return text.to_string();
+    }
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] != '$' {
+            out.push(chars[i]);
+            i += 1;
+            continue;
+        }
+        let width = chars[i..].iter().take_while(|&&c| c == '$').count();
+        i += width;
+        let (mut reverse, mut base) = (false, 1usize);
+        if chars.get(i) == Some(&'@') {
+            i += 1;
+            if chars.get(i) == Some(&'-') {
+                reverse = true;
+                i += 1;
+            }
+            let digits: String = chars[i..].iter().take_while(|c| c.is_ascii_digit()).collect();
+            i += digits.len();
+            base = digits.parse().unwrap_or(1);
+        }
+        let n = if reverse { base + count - 1 - index } else { base + index };
+        out.push_str(&format!("{:0width$}", n, width = width));
+    }
+    out
+}
+
+fn implicit_tag(parent: Option<&str>) -> &'static str {
+    match parent {
+        Some("ul" | "ol") => "li",
+        Some("table" | "tbody" | "thead" | "tfoot") => "tr",
+        Some("tr") => "td",
+        Some("select" | "optgroup" | "datalist") => "option",
+        Some("dl") => "dt",
+        Some(p) if INLINE.contains(&p) => "span",
+        _ => "div",
+    }
+}
+
+struct Renderer {
+    syntax: Syntax,
+    indent: String,
+    tabstops: bool,
+    next_tabstop: usize,
+    nodes: usize,
+    out: Vec<String>,
+}
+
+impl Renderer {
+    fn placeholder(&mut self) -> String {
+        if !self.tabstops {
+            return String::new();
+        }
+        self.next_tabstop += 1;
+        format!("${{{}}}", self.next_tabstop)
+    }
+
+    fn attr_name(&self, name: &str) -> String {
+        match (self.syntax, name) {
+            (Syntax::Jsx, "class") => "className".into(),
+            (Syntax::Jsx, "for") => "htmlFor".into(),
+            _ => name.into(),
+        }
+    }
+
+    /// Render `nodes` at `depth`. `num` is the nearest repeat's (index,
+    /// count) for `$` numbering.
+    fn render(&mut self, nodes: &[Node], parent: Option<&str>, depth: usize, num: (usize, usize)) -> Result<(), String> {
+        for node in nodes {
+            let count = node.repeat.unwrap_or(1);
+            for i in 0..count {
+                let num = if node.repeat.is_some() { (i, count) } else { num };
+                self.nodes += 1;
+                if self.nodes > MAX_NODES {
+                    return Err("Abbreviation expands to too many elements".to_string());
+                }
+                if node.group {
+                    self.render(&node.children, parent, depth, num)?;
+                } else {
+                    self.render_element(node, parent, depth, num)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn render_element(&mut self, node: &Node, parent: Option<&str>, depth: usize, num: (usize, usize)) -> Result<(), String> {
+        let pad = self.indent.repeat(depth);
+        let text = node.text.as_deref().map(|t| number(t, num.0, num.1));
+        let is_text_only = node.name.is_none() && node.id.is_none() && node.classes.is_empty() && node.attrs.is_empty();
+        if is_text_only {
+            self.out.push(format!("{}{}", pad, text.unwrap_or_default()));
+            return self.render(&node.children, parent, depth, num);
+        }
+
+        let name = match &node.name {
+            Some(n) => number(n, num.0, num.1),
+            None => implicit_tag(parent).to_string(),
+        };
+        let mut attrs: Vec<(String, Option<String>)> = Vec::new();
+        if let Some(id) = &node.id {
+            attrs.push(("id".into(), Some(number(id, num.0, num.1))));
+        }
+        if !node.classes.is_empty() {
+            let classes: Vec<String> = node.classes.iter().map(|c| number(c, num.0, num.1)).collect();
+            attrs.push(("class".into(), Some(classes.join(" "))));
+        }
+        for (k, v) in &node.attrs {
+            attrs.push((k.clone(), v.as_deref().map(|v| number(v, num.0, num.1))));
+        }
+        if let Some((_, defaults)) = DEFAULT_ATTRS.iter().find(|(tag, _)| *tag == name) {
+            for default in *defaults {
+                let (k, v) = default.split_once('=').map_or((*default, None), |(k, v)| (k, Some(v.to_string())));
+                if !attrs.iter().any(|(name, _)| name == k) {
+                    attrs.push((k.to_string(), v));
+                }
+            }
+        }
+
+        let mut open = format!("<{}", name);
+        for (k, v) in attrs {
+            let value = v.filter(|v| !v.is_empty()).unwrap_or_else(|| self.placeholder());
+            open.push_str(&format!(" {}=\"{}\"", self.attr_name(&k), value));
+        }
+
+        if SELF_CLOSING.contains(&name.as_str()) && node.children.is_empty() && text.is_none() {
+            let close = if self.syntax == Syntax::Jsx { " />" } else { ">" };
+            self.out.push(format!("{}{}{}", pad, open, close));
+        } else if node.children.is_empty() {
+            let content = text.unwrap_or_else(|| self.placeholder());
+            self.out.push(format!("{}{}>{}</{}>", pad, open, content, name));
+        } else {
+            self.out.push(format!("{}{}>", pad, open));
+            if let Some(text) = text {
+                self.out.push(format!("{}{}{}", pad, self.indent, text));
+            }
+            self.render(&node.children, Some(&name), depth + 1, num)?;
+            self.out.push(format!("{}</{}>", pad, name));
+        }
+        Ok(())
+    }
+}
+
+fn expand_markup(abbr: &str, syntax: Syntax, indent: &str, tabstops: bool) -> Result<String, String> {
+    let mut renderer = Renderer { syntax, indent: indent.to_string(), tabstops, next_tabstop: 0, nodes: 0, out: Vec::new() };
+    if matches!(abbr, "!" | "html:5" | "doc") {
+        let doc = HTML5_DOC
+            .replace("{i}", indent)
+            .replace("{t1}", &if tabstops { "${1:Document}".to_string() } else { "Document".to_string() })
+            .replace("{t2}", &renderer.placeholder().replace("${1}", "${2}"));
+        return Ok(doc);
+    }
+    let mut parser = Parser::new(abbr);
+    let nodes = parser.parse_list()?;
+    if parser.peek().is_some() {
+        return Err(parser.error("unmatched ')'"));
+    }
+    renderer.render(&nodes, None, 0, (0, 1))?;
+    Ok(renderer.out.join("\n"))
+}
+
+// ── CSS ──
+
+const CSS_PROPERTIES: &[(&str, &str)] = &[
+    ("ac", "align-content"), ("ai", "align-items"), ("as", "align-self"), ("b", "bottom"), ("bd", "border"),
+    ("bdb", "border-bottom"), ("bdc", "border-color"), ("bdl", "border-left"), ("bdr", "border-right"),
+    ("bdrs", "border-radius"), ("bds", "border-style"), ("bdt", "border-top"), ("bdw", "border-width"),
+    ("bg", "background"), ("bgc", "background-color"), ("bgi", "background-image"), ("bgp", "background-position"),
+    ("bgr", "background-repeat"), ("bgs", "background-size"), ("bxsh", "box-shadow"), ("bxz", "box-sizing"),
+    ("c", "color"), ("cl", "clear"), ("cnt", "content"), ("cur", "cursor"), ("d", "display"), ("fb", "flex-basis"),
+    ("fd", "flex-direction"), ("ff", "font-family"), ("fg", "flex-grow"), ("fl", "float"), ("fs", "font-style"),
+    ("fsh", "flex-shrink"), ("fw", "font-weight"), ("fx", "flex"), ("fxw", "flex-wrap"), ("fz", "font-size"),
+    ("g", "gap"), ("gtc", "grid-template-columns"), ("gtr", "grid-template-rows"), ("h", "height"),
+    ("jc", "justify-content"), ("l", "left"), ("lh", "line-height"), ("lis", "list-style"), ("ls", "letter-spacing"),
+    ("m", "margin"), ("mah", "max-height"), ("maw", "max-width"), ("mb", "margin-bottom"), ("mih", "min-height"),
+    ("miw", "min-width"), ("ml", "margin-left"), ("mr", "margin-right"), ("mt", "margin-top"), ("o", "outline"),
+    ("op", "opacity"), ("ord", "order"), ("ov", "overflow"), ("ovx", "overflow-x"), ("ovy", "overflow-y"),
+    ("p", "padding"), ("pb", "padding-bottom"), ("pe", "pointer-events"), ("pl", "padding-left"),
+    ("pos", "position"), ("pr", "padding-right"), ("pt", "padding-top"), ("r", "right"), ("t", "top"),
+    ("ta", "text-align"), ("td", "text-decoration"), ("ti", "text-indent"), ("trf", "transform"),
+    ("trs", "transition"), ("tt", "text-transform"), ("us", "user-select"), ("v", "visibility"),
+    ("va", "vertical-align"), ("w", "width"), ("ws", "white-space"), ("z", "z-index"),
+];
+
+const CSS_KEYWORDS: &[(&str, &[(&str, &str)])] = &[
+    ("display", &[("n", "none"), ("b", "block"), ("i", "inline"), ("ib", "inline-block"), ("f", "flex"), ("if", "inline-flex"), ("g", "grid"), ("ig", "inline-grid"), ("t", "table"), ("c", "contents")]),
+    ("position", &[("s", "static"), ("r", "relative"), ("a", "absolute"), ("f", "fixed"), ("st", "sticky")]),
+    ("float", &[("l", "left"), ("r", "right"), ("n", "none")]),
+    ("clear", &[("b", "both"), ("l", "left"), ("r", "right"), ("n", "none")]),
+    ("text-align", &[("l", "left"), ("c", "center"), ("r", "right"), ("j", "justify")]),
+    ("text-decoration", &[("n", "none"), ("u", "underline"), ("o", "overline"), ("l", "line-through")]),
+    ("text-transform", &[("u", "uppercase"), ("l", "lowercase"), ("c", "capitalize"), ("n", "none")]),
+    ("font-weight", &[("n", "normal"), ("b", "bold"), ("br", "bolder"), ("lr", "lighter")]),
+    ("font-style", &[("n", "normal"), ("i", "italic"), ("o", "oblique")]),
+    ("overflow", &[("h", "hidden"), ("v", "visible"), ("s", "scroll"), ("a", "auto")]),
+    ("overflow-x", &[("h", "hidden"), ("v", "visible"), ("s", "scroll"), ("a", "auto")]),
+    ("overflow-y", &[("h", "hidden"), ("v", "visible"), ("s", "scroll"), ("a", "auto")]),
+    ("cursor", &[("p", "pointer"), ("d", "default"), ("t", "text"), ("m", "move"), ("a", "auto"), ("na", "not-allowed")]),
+    ("visibility", &[("v", "visible"), ("h", "hidden"), ("c", "collapse")]),
+    ("justify-content", &[("s", "flex-start"), ("c", "center"), ("e", "flex-end"), ("sb", "space-between"), ("sa", "space-around"), ("se", "space-evenly")]),
+    ("align-items", &[("s", "flex-start"), ("c", "center"), ("e", "flex-end"), ("st", "stretch"), ("b", "baseline")]),
+    ("align-content", &[("s", "flex-start"), ("c", "center"), ("e", "flex-end"), ("st", "stretch"), ("sb", "space-between"), ("sa", "space-around")]),
+    ("align-self", &[("a", "auto"), ("s", "flex-start"), ("c", "center"), ("e", "flex-end"), ("st", "stretch"), ("b", "baseline")]),
+    ("flex-direction", &[("r", "row"), ("rr", "row-reverse"), ("c", "column"), ("cr", "column-reverse")]),
+    ("flex-wrap", &[("w", "wrap"), ("nw", "nowrap"), ("wr", "wrap-reverse")]),
+    ("white-space", &[("n", "normal"), ("nw", "nowrap"), ("p", "pre"), ("pw", "pre-wrap"), ("pl", "pre-line")]),
+    ("vertical-align", &[("t", "top"), ("m", "middle"), ("b", "bottom"), ("bl", "baseline"), ("s", "sub"), ("sup", "super")]),
+    ("box-sizing", &[("bb", "border-box"), ("cb", "content-box")]),
+    ("user-select", &[("n", "none"), ("a", "auto"), ("t", "text"), ("al", "all")]),
+    ("pointer-events", &[("n", "none"), ("a", "auto")]),
+    ("border", &[("n", "none")]),
+    ("outline", &[("n", "none")]),
+    ("list-style", &[("n", "none")]),
+    ("background", &[("n", "none")]),
+    ("width", &[("a", "auto")]),
+    ("height", &[("a", "auto")]),
+    ("margin", &[("a", "auto")]),
+];
+
+/// Properties whose bare numbers take no unit.
+const UNITLESS: &[&str] = &["z-index", "opacity", "line-height", "flex", "flex-grow", "flex-shrink", "font-weight", "order"];
+
+fn keyword(property: &str, abbr: &str) -> Option<&'static str> {
+    let (_, words) = CSS_KEYWORDS.iter().find(|(p, _)| *p == property)?;
+    words.iter().find(|(a, _)| *a == abbr).map(|(_, v)| *v)
+}
+
+fn css_color(hex: &str) -> Option<String> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some(match hex.len() {
+        1 => hex.repeat(6),
+        2 => hex.repeat(3),
+        3 | 6 | 8 => hex.to_string(),
+        _ => return None,
+    })
+    .map(|h| format!("#{}", h.to_lowercase()))
+}
+
+/// `10-20`, `-5`, `1.5e`, `100p` → space-separated values with units.
+fn css_numbers(value: &str, property: &str) -> Option<String> {
+    let mut parts = Vec::new();
+    let mut rest = value;
+    while !rest.is_empty() {
+        let negative = rest.starts_with('-');
+        let body = if negative { &rest[1..] } else { rest };
+        let digits = body.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(body.len());
+        if digits == 0 {
+            return None;
+        }
+        let (num, after) = body.split_at(digits);
+        let unit_len = after.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(after.len());
+        let (unit, after) = after.split_at(unit_len);
+        let unit = match unit {
+            "" if UNITLESS.contains(&property) || num == "0" => "",
+            "" => "px",
+            "p" => "%",
+            "e" => "em",
+            "r" => "rem",
+            "x" => "ex",
+            "w" => "vw",
+            "h" => "vh",
+            other => other,
+        };
+        parts.push(format!("{}{}{}", if negative { "-" } else { "" }, num, unit));
+        // A single `-` separates values; a second one makes the next negative.
+        rest = after.strip_prefix('-').filter(|r| !r.is_empty()).unwrap_or(after);
+        if rest.len() == after.len() && !rest.is_empty() && !rest.starts_with('-') {
+            return None;
+        }
+    }
+    Some(parts.join(" "))
+}
+
+fn css_value(property: &str, value: &str) -> Option<String> {
+    if value.is_empty() {
+        return Some(String::new());
+    }
+    if let Some(hex) = value.strip_prefix('#') {
+        return css_color(hex);
+    }
+    keyword(property, value).map(str::to_string).or_else(|| css_numbers(value, property))
+}
+
+fn expand_css_part(part: &str, tabstops: bool, next_tabstop: &mut usize) -> Result<String, String> {
+    let (part, important) = match part.strip_suffix('!') {
+        Some(p) => (p, " !important"),
+        None => (part, ""),
+    };
+    let (property, value) = if let Some((abbr, value)) = part.split_once(':') {
+        let property = CSS_PROPERTIES.iter().find(|(a, _)| *a == abbr).map(|(_, p)| *p);
+        property.and_then(|p| css_value(p, value).map(|v| (p, v)))
+    } else {
+        // Longest property abbreviation whose remainder is a valid value.
+        let mut candidates: Vec<&(&str, &str)> = CSS_PROPERTIES.iter().filter(|(a, _)| part.starts_with(a)).collect();
+        candidates.sort_by_key(|(a, _)| std::cmp::Reverse(a.len()));
+        candidates.into_iter().find_map(|(a, p)| css_value(p, &part[a.len()..]).map(|v| (*p, v)))
+    }
+    .ok_or_else(|| format!("Unknown CSS abbreviation: {}", part))?;
+    let value = if value.is_empty() && tabstops {
+        *next_tabstop += 1;
+        format!("${{{}}}", next_tabstop)
+    } else {
+        value
+    };
+    Ok(format!("{}: {}{};", property, value, important))
+}
+
+fn expand_css(abbr: &str, tabstops: bool) -> Result<String, String> {
+    let mut next_tabstop = 0;
+    abbr.split('+')
+        .filter(|p| !p.is_empty())
+        .map(|part| expand_css_part(part, tabstops, &mut next_tabstop))
+        .collect::<Result<Vec<_>, _>>()
+        .map(|lines| lines.join("\n"))
+}
+
+fn expand(abbr: &str, language: &str, options: &EmmetOptions) -> Result<String, String> {
+    let abbr = abbr.trim();
+    if abbr.is_empty() {
+        return Err("Abbreviation is empty".to_string());
+    }
+    let tabstops = options.tabstops.unwrap_or(false);
+    match syntax_of(language)? {
+        Syntax::Css => expand_css(abbr, tabstops),
+        syntax => expand_markup(abbr, syntax, options.indent.as_deref().unwrap_or("  "), tabstops),
+    }
+}
+
+/// Expand an Emmet abbreviation for `language` (an id or extension such
+/// as `html`, `tsx` or `scss`).
+#[tauri::command]
+pub fn expand_abbreviation(abbr: String, language: String, options: Option<EmmetOptions>) -> Result<String, String> {
+    expand(&abbr, &language, &options.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn html(abbr: &str) -> String {
+        expand(abbr, "html", &EmmetOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn nesting_siblings_and_climbing() {
+        assert_eq!(html("div>p+p"), "<div>\n  <p></p>\n  <p></p>\n</div>");
+        assert_eq!(html("div>p>span^a"), "<div>\n  <p>\n    <span></span>\n  </p>\n  <a href=\"\"></a>\n</div>");
+        assert_eq!(html("(header>h1)+footer"), "<header>\n  <h1></h1>\n</header>\n<footer></footer>");
+    }
+
+    #[test]
+    fn repetition_numbering_and_implicit_tags() {
+        assert_eq!(
+            html("ul#nav>.item-$$*2>a{Link $@-}"),
+            "<ul id=\"nav\">\n  <li class=\"item-01\">\n    <a href=\"\">Link 2</a>\n  </li>\n  <li class=\"item-02\">\n    <a href=\"\">Link 1</a>\n  </li>\n</ul>"
+        );
+        assert_eq!(html("input[type=email required]+br"), "<input type=\"email\" required=\"\">\n<br>");
+    }
+
+    #[test]
+    fn jsx_and_tabstops() {
+        let options = EmmetOptions { tabstops: Some(true), ..Default::default() };
+        assert_eq!(
+            expand("label.field+img", "tsx", &options).unwrap(),
+            "<label className=\"field\" htmlFor=\"${1}\">${2}</label>\n<img src=\"${3}\" alt=\"${4}\" />"
+        );
+        assert!(expand("div>(p", "html", &options).is_err());
+        assert!(expand("p*100000", "html", &options).is_err());
+    }
+
+    #[test]
+    fn css_shorthands() {
+        let css = |a: &str| expand(a, "css", &EmmetOptions::default()).unwrap();
+        assert_eq!(css("m10-20"), "margin: 10px 20px;");
+        assert_eq!(css("m-10--5"), "margin: -10px -5px;");
+        assert_eq!(css("w100p+h2.5e"), "width: 100%;\nheight: 2.5em;");
+        assert_eq!(css("dib+pos:a+z10"), "display: inline-block;\nposition: absolute;\nz-index: 10;");
+        assert_eq!(css("bgc#e0+c#f!"), "background-color: #e0e0e0;\ncolor: #ffffff !important;");
+        assert_eq!(css("jcsb+lh1.5+p0"), "justify-content: space-between;\nline-height: 1.5;\npadding: 0;");
+        assert!(expand("zz10", "css", &EmmetOptions::default()).is_err());
+    }
+}
@@ -0,0 +1,231 @@
+//! Every event the backend sends to the frontend, declared in one place.
+//!
+//! Each event is a constant pairing its name with its payload type, and
+//! is sent through that constant (`events::COMPRESS_PROGRESS.emit_to(..)`)
+//! rather than a string, so the payload can't drift from the declaration.
+//! Scoped events carry an instance id after the name
+//! (`terminal-output-{id}`).
+//!
+//! `src/lib/generated/events.ts` is generated from the same list: the
+//! payload types, `EventPayloads` / `ScopedEventPayloads` maps from name
+//! to payload, and `IPC_VERSION`. A test fails when the file is stale;
+//! regenerate it with `UPDATE_IPC_BINDINGS=1 cargo test events`.
+//!
+//! Bump `IPC_VERSION` for changes an older frontend would misread: a
+//! renamed or removed event or field, or a field whose meaning changed.
+//! New events and new optional fields don't need it. The frontend
+//! compares its copy with `ipc_version` at startup.
+//!
+//! Menu events (`menu:*`) carry no payload and are listed in `menu`.
+
+use serde::Serialize;
+#[cfg(test)]
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::marker::PhantomData;
+use tauri::{AppHandle, Emitter, Runtime};
+#[cfg(test)]
+use ts_rs::{TypeVisitor, TS};
+
+use crate::modules::ai::agent::AgentStep;
+use crate::modules::ai::StreamChunk;
+use crate::modules::fs::archive::CompressProgress;
+use crate::modules::fs::chunked::FileChunk;
+use crate::modules::fs::delete::DeleteProgress;
+use crate::modules::fs::tail::TailEvent;
+use crate::modules::git::ignored::IgnoredFilesChanged;
+use crate::modules::git::stats::GcProgress;
+use crate::modules::knowledge::IndexProgress;
+use crate::modules::operations::OperationEvent;
+use crate::modules::shell::activity::TerminalActivity;
+use crate::modules::shell::title::TerminalTitle;
+
+/// Version of the event and command contract; see the module docs.
+pub const IPC_VERSION: u32 = 1;
+
+/// An event sent under a fixed name.
+pub struct Event<P> {
+    name: &'static str,
+    payload: PhantomData<fn(P)>,
+}
+
+impl<P: Serialize + Clone> Event<P> {
+    #[cfg(test)]
+    const SCOPED: bool = false;
+
+    const fn new(name: &'static str) -> Self {
+        Self { name, payload: PhantomData }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Send to one window.
+    pub fn emit_to<R: Runtime>(&self, app: &AppHandle<R>, window: &str, payload: P) {
+        let target = tauri::EventTarget::WebviewWindow { label: window.to_string() };
+        let _ = app.emit_to(target, self.name, payload);
+    }
+
+    /// Send to every window.
+    pub fn emit<R: Runtime>(&self, app: &AppHandle<R>, payload: P) {
+        let _ = app.emit(self.name, payload);
+    }
+}
+
+/// An event sent under its name plus an instance id, e.g.
+/// `terminal-output-3`.
+pub struct ScopedEvent<P> {
+    prefix: &'static str,
+    payload: PhantomData<fn(P)>,
+}
+
+impl<P: Serialize + Clone> ScopedEvent<P> {
+    #[cfg(test)]
+    const SCOPED: bool = true;
+
+    const fn new(prefix: &'static str) -> Self {
+        Self { prefix, payload: PhantomData }
+    }
+
+    pub fn name(&self, scope: impl Display) -> String {
+        format!("{}-{}", self.prefix, scope)
+    }
+
+    /// Send to one window.
+    pub fn emit_to<R: Runtime>(&self, app: &AppHandle<R>, window: &str, scope: impl Display, payload: P) {
+        let target = tauri::EventTarget::WebviewWindow { label: window.to_string() };
+        let _ = app.emit_to(target, &self.name(scope), payload);
+    }
+}
+
+/// Declares the events and the list the TypeScript file is built from.
+macro_rules! events {
+    ($($(#[$doc:meta])* $konst:ident: $kind:ident<$payload:ty> = $name:literal;)*) => {
+        $(
+            $(#[$doc])*
+            pub const $konst: $kind<$payload> = $kind::new($name);
+        )*
+
+        #[cfg(test)]
+        fn declare(bindings: &mut Bindings) {
+            $( bindings.event::<$payload>($name, $kind::<$payload>::SCOPED); )*
+        }
+    };
+}
+
+events! {
+    /// A workspace agent step started, needs approval, or finished.
+    AGENT_STEP: Event<AgentStep> = "agent-step";
+    /// Streamed chat reply text; the last chunk has `done` set.
+    AI_STREAM_CHUNK: Event<StreamChunk> = "ai-stream-chunk";
+    COMPRESS_PROGRESS: Event<CompressProgress> = "compress-progress";
+    DELETE_PROGRESS: Event<DeleteProgress> = "delete-progress";
+    /// A piece of a file read with `read_file_chunked`.
+    FILE_CHUNK: Event<FileChunk> = "file-chunk";
+    GIT_GC_PROGRESS: Event<GcProgress> = "git-gc-progress";
+    IGNORED_FILES_CHANGED: Event<IgnoredFilesChanged> = "ignored-files-changed";
+    INDEXING_PROGRESS: Event<IndexProgress> = "indexing-progress";
+    OPERATION_PROGRESS: Event<OperationEvent> = "operation-progress";
+    SEMANTIC_INDEXING_PROGRESS: Event<IndexProgress> = "semantic-indexing-progress";
+    /// New lines of a followed log file.
+    TAIL_LINES: Event<TailEvent> = "tail-lines";
+    /// Bell, output after quiet, or quiet after a busy stretch; by session id.
+    TERMINAL_ACTIVITY: ScopedEvent<TerminalActivity> = "terminal-activity";
+    /// The session's process ended; by session id.
+    TERMINAL_EXIT: ScopedEvent<()> = "terminal-exit";
+    /// PTY output, split on UTF-8 boundaries; by session id.
+    TERMINAL_OUTPUT: ScopedEvent<String> = "terminal-output";
+    TERMINAL_TITLE: ScopedEvent<TerminalTitle> = "terminal-title";
+}
+
+// ── TypeScript ──
+//
+// Only the staleness test builds the bindings, so none of this is compiled
+// into the app.
+
+/// Collects the payload types and their declarations.
+#[cfg(test)]
+#[derive(Default)]
+struct Bindings {
+    /// Type name to `export type` declaration.
+    types: BTreeMap<String, String>,
+    seen: std::collections::HashSet<std::any::TypeId>,
+    events: Vec<(&'static str, String)>,
+    scoped: Vec<(&'static str, String)>,
+}
+
+#[cfg(test)]
+impl TypeVisitor for Bindings {
+    fn visit<T: TS + 'static + ?Sized>(&mut self) {
+        if !self.seen.insert(std::any::TypeId::of::<T>()) {
+            return;
+        }
+        // Built-ins (strings, numbers, `Option`, `Vec`) have no file and
+        // need no declaration.
+        if T::output_path().is_some() {
+            self.types.insert(T::name(), format!("export {}", T::decl()));
+        }
+        T::visit_dependencies(self);
+        T::visit_generics(self);
+    }
+}
+
+#[cfg(test)]
+impl Bindings {
+    fn event<P: TS + 'static>(&mut self, name: &'static str, scoped: bool) {
+        self.visit::<P>();
+        let list = if scoped { &mut self.scoped } else { &mut self.events };
+        list.push((name, P::name()));
+    }
+}
+
+/// The contents of `src/lib/generated/events.ts`.
+#[cfg(test)]
+fn typescript() -> String {
+    let mut bindings = Bindings::default();
+    declare(&mut bindings);
+    let map = |entries: &[(&str, String)]| {
+        entries.iter().map(|(name, payload)| format!("  '{}': {};\n", name, payload)).collect::<String>()
+    };
+    format!(
+        "// Generated from src-tauri/src/modules/events/mod.rs; do not edit.\n\
+         // Regenerate with `UPDATE_IPC_BINDINGS=1 cargo test events`.\n\n\
+         export const IPC_VERSION = {};\n\n\
+         {}\n\n\
+         /** Payload of each event, by name. */\n\
+         export interface EventPayloads {{\n{}}}\n\n\
+         /** Payload of each scoped event, by the name before `-{{id}}`. */\n\
+         export interface ScopedEventPayloads {{\n{}}}\n",
+        IPC_VERSION,
+        bindings.types.into_values().collect::<Vec<_>>().join("\n\n"),
+        map(&bindings.events),
+        map(&bindings.scoped),
+    )
+}
+
+// ── Commands ──
+
+/// The backend's `IPC_VERSION`, for the frontend to check against its own.
+#[tauri::command]
+pub fn ipc_version() -> u32 {
+    IPC_VERSION
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_typescript_is_up_to_date() {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../src/lib/generated/events.ts");
+        let expected = typescript();
+        // 64-bit integers come out as `bigint`, but JSON delivers numbers.
+        assert!(!expected.contains("bigint"), "mark u64 payload fields #[ts(type = \"number\")]");
+        if std::env::var_os("UPDATE_IPC_BINDINGS").is_some() {
+            std::fs::write(&path, &expected).unwrap();
+        }
+        let actual = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(actual == expected, "{} is stale; run `UPDATE_IPC_BINDINGS=1 cargo test events`", path.display());
+    }
+}
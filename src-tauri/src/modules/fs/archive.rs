@@ -0,0 +1,250 @@
+//! Zip export of files and folders from the file tree.
+//!
+//! `compress_entries` writes the selected entries into one archive, each
+//! under its own name (a selected `src/` becomes `src/…` in the zip).
+//! `.git` and ignored files are left out unless asked for. Progress is
+//! reported to the requesting window as `compress-progress` events, and
+//! the export runs as a cancellable background operation. The zip is
+//! written to a temp file first so a failed or cancelled export never
+//! leaves a truncated archive behind.
+
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use ts_rs::TS;
+use zip::write::SimpleFileOptions;
+
+use super::{project_root_async, resolve_within_root, ProjectRootState};
+use crate::modules::events;
+use crate::modules::operations::{self, OperationKind};
+
+/// Minimum gap between progress events.
+const PROGRESS_EVERY_BYTES: u64 = 1024 * 1024;
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct CompressOptions {
+    /// Also include files matched by .gitignore and friends.
+    pub include_ignored: Option<bool>,
+    /// Also include `.git` directories.
+    pub include_git: Option<bool>,
+}
+
+#[derive(Serialize, Clone, Debug, TS)]
+pub struct CompressProgress {
+    pub output_path: String,
+    pub files_done: usize,
+    pub files_total: usize,
+    #[ts(type = "number")]
+    pub bytes_done: u64,
+    #[ts(type = "number")]
+    pub bytes_total: u64,
+    /// Archive name of the file being added.
+    pub current: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CompressResult {
+    pub output_path: String,
+    pub files: usize,
+    /// Uncompressed bytes added.
+    pub bytes: u64,
+    /// Size of the written archive.
+    pub archive_bytes: u64,
+}
+
+/// A file to add and its name inside the archive.
+pub(super) struct ArchiveEntry {
+    path: PathBuf,
+    name: String,
+    size: u64,
+    is_dir: bool,
+}
+
+fn archive_name(base: &Path, path: &Path) -> String {
+    let rel = path.strip_prefix(base).unwrap_or(path);
+    rel.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/")
+}
+
+pub(super) fn collect(sources: &[PathBuf], output: &Path, options: &CompressOptions) -> Result<Vec<ArchiveEntry>, String> {
+    let include_ignored = options.include_ignored.unwrap_or(false);
+    let include_git = options.include_git.unwrap_or(false);
+    let mut entries = Vec::new();
+    for source in sources {
+        let base = source.parent().unwrap_or(source);
+        let walker = ignore::WalkBuilder::new(source)
+            .hidden(false)
+            .git_ignore(!include_ignored)
+            .git_exclude(!include_ignored)
+            .ignore(!include_ignored)
+            .parents(!include_ignored)
+            .filter_entry(move |e| include_git || e.file_name() != ".git")
+            .build();
+        for entry in walker {
+            let entry = entry.map_err(|e| format!("Failed to read directory: {}", e))?;
+            let path = entry.path();
+            if path == output {
+                continue;
+            }
+            let Some(file_type) = entry.file_type() else { continue };
+            if !file_type.is_file() && !file_type.is_dir() {
+                continue;
+            }
+            let size = if file_type.is_file() { entry.metadata().map(|m| m.len()).unwrap_or(0) } else { 0 };
+            entries.push(ArchiveEntry { path: path.to_path_buf(), name: archive_name(base, path), size, is_dir: file_type.is_dir() });
+        }
+    }
+    Ok(entries)
+}
+
+/// Write `entries` to `output`. `cancelled` is checked before each entry;
+/// once it returns true the partial archive is discarded.
+pub(super) fn write_zip(
+    entries: &[ArchiveEntry],
+    output: &Path,
+    mut progress: impl FnMut(CompressProgress),
+    cancelled: impl Fn() -> bool,
+) -> Result<CompressResult, String> {
+    let io_err = |e: std::io::Error| format!("Failed to write archive: {}", e.kind());
+    let zip_err = |e: zip::result::ZipError| format!("Failed to write archive: {}", e);
+    let dir = output.parent().ok_or("Invalid output path")?;
+    let name = output.file_name().ok_or("Invalid output path")?.to_string_lossy();
+    let tmp = dir.join(format!(".{}.{}.tmp", name, std::process::id()));
+
+    let result: Result<(usize, u64), String> = (|| {
+        let mut zip = zip::ZipWriter::new(BufWriter::new(File::create(&tmp).map_err(io_err)?));
+        let files_total = entries.iter().filter(|e| !e.is_dir).count();
+        let bytes_total: u64 = entries.iter().map(|e| e.size).sum();
+        let (mut files_done, mut bytes_done, mut reported) = (0, 0u64, 0u64);
+        for entry in entries {
+            if cancelled() {
+                return Err("Compression cancelled".to_string());
+            }
+            let mut options = SimpleFileOptions::default().large_file(entry.size > u32::MAX as u64);
+            #[cfg(unix)]
+            if let Ok(meta) = std::fs::metadata(&entry.path) {
+                use std::os::unix::fs::PermissionsExt;
+                options = options.unix_permissions(meta.permissions().mode());
+            }
+            if entry.is_dir {
+                zip.add_directory(entry.name.as_str(), options).map_err(zip_err)?;
+                continue;
+            }
+            zip.start_file(entry.name.as_str(), options).map_err(zip_err)?;
+            let mut file = File::open(&entry.path).map_err(io_err)?;
+            bytes_done += std::io::copy(&mut file, &mut zip).map_err(io_err)?;
+            files_done += 1;
+            if bytes_done - reported >= PROGRESS_EVERY_BYTES || files_done == files_total {
+                reported = bytes_done;
+                progress(CompressProgress {
+                    output_path: output.to_string_lossy().into_owned(),
+                    files_done,
+                    files_total,
+                    bytes_done,
+                    bytes_total,
+                    current: entry.name.clone(),
+                });
+            }
+        }
+        let mut writer = zip.finish().map_err(zip_err)?;
+        writer.flush().map_err(io_err)?;
+        writer.get_ref().sync_all().map_err(io_err)?;
+        Ok((files_done, bytes_done))
+    })();
+
+    let (files, bytes) = result.inspect_err(|_| {
+        let _ = std::fs::remove_file(&tmp);
+    })?;
+    std::fs::rename(&tmp, output).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        io_err(e)
+    })?;
+    let archive_bytes = std::fs::metadata(output).map(|m| m.len()).unwrap_or(0);
+    Ok(CompressResult { output_path: output.to_string_lossy().into_owned(), files, bytes, archive_bytes })
+}
+
+/// Zip `paths` (files or folders inside the project) into `output_path`,
+/// which may be anywhere, e.g. a location picked in a save dialog.
+/// An existing file at `output_path` is replaced.
+#[tauri::command]
+pub async fn compress_entries(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    app: AppHandle,
+    paths: Vec<String>,
+    output_path: String,
+    options: Option<CompressOptions>,
+) -> Result<CompressResult, String> {
+    let root = project_root_async(window.label(), &state).await?;
+    if paths.is_empty() {
+        return Err("Nothing to compress".to_string());
+    }
+    let sources = paths.iter().map(|p| resolve_within_root(p, &root)).collect::<Result<Vec<_>, _>>()?;
+    let output = PathBuf::from(&output_path);
+    if !output.is_absolute() || output.is_dir() {
+        return Err("Invalid output path".to_string());
+    }
+    let options = options.unwrap_or_default();
+    let label = window.label().to_string();
+    let title = output.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_else(|| output_path.clone());
+    let op = operations::manager().start(OperationKind::Archive, &title, Some(window.label()), true);
+
+    tokio::task::spawn_blocking(move || {
+        let entries = op.track(collect(&sources, &output, &options))?;
+        let result = write_zip(
+            &entries,
+            &output,
+            |p| {
+                op.progress(p.bytes_done, Some(p.bytes_total), Some(p.current.clone()));
+                events::COMPRESS_PROGRESS.emit_to(&app, &label, p);
+            },
+            || op.is_cancelled(),
+        );
+        op.track(result)
+    })
+    .await
+    .map_err(|e| format!("Compression failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn zips_selection_without_git_or_ignored_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("proj");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::write(root.join(".git/HEAD"), "ref").unwrap();
+        std::fs::write(root.join(".gitignore"), "*.log\n").unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(root.join("debug.log"), "noise").unwrap();
+
+        let output = dir.path().join("proj.zip");
+        let entries = collect(std::slice::from_ref(&root), &output, &CompressOptions::default()).unwrap();
+        let mut events = 0;
+        let result = write_zip(&entries, &output, |_| events += 1, || false).unwrap();
+        assert_eq!(result.files, 2);
+        assert!(events > 0);
+
+        let mut zip = zip::ZipArchive::new(File::open(&output).unwrap()).unwrap();
+        let mut names: Vec<String> = zip.file_names().map(str::to_string).collect();
+        names.sort();
+        assert_eq!(names, vec!["proj/", "proj/.gitignore", "proj/src/", "proj/src/main.rs"]);
+        let mut content = String::new();
+        zip.by_name("proj/src/main.rs").unwrap().read_to_string(&mut content).unwrap();
+        assert_eq!(content, "fn main() {}");
+
+        let all = collect(&[root], &output, &CompressOptions { include_ignored: Some(true), include_git: Some(true) }).unwrap();
+        assert!(all.iter().any(|e| e.name == "proj/debug.log"));
+        assert!(all.iter().any(|e| e.name == "proj/.git/HEAD"));
+
+        let cancelled = dir.path().join("cancelled.zip");
+        assert!(write_zip(&entries, &cancelled, |_| {}, || true).is_err());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2, "no archive or temp file is left");
+    }
+}
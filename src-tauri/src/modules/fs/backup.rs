@@ -0,0 +1,376 @@
+//! Opt-in project backups to a folder of the user's choosing.
+//!
+//! With backups enabled in `~/.leo-ide/backups.json`, every project that
+//! has files saved in it is zipped into the backup folder once per
+//! interval — only after activity, so an untouched project doesn't pile
+//! up identical copies. Each project gets its own subfolder there and
+//! keeps its newest `keep` backups. Like "Export as Zip", a backup leaves
+//! out `.git` and ignored files. Restoring writes a backup's files back
+//! over the project after backing up the current state, so a restore can
+//! itself be undone; files that aren't in the backup are left alone.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, Once, OnceLock};
+use std::time::Duration;
+
+use super::archive::{self, CompressOptions};
+use super::{resolve_within_root, write_atomic, ProjectRootState};
+use crate::modules::git::branch_policy::slugify;
+
+const DEFAULT_INTERVAL_MINUTES: u32 = 30;
+const MIN_INTERVAL_MINUTES: u32 = 5;
+const DEFAULT_KEEP: usize = 10;
+/// How often the scheduler looks for projects that are due.
+const CHECK_EVERY: Duration = Duration::from_secs(60);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct BackupSettings {
+    pub enabled: bool,
+    /// Folder backups go to; each project gets a subfolder.
+    pub directory: Option<String>,
+    /// Least time between two backups of a project.
+    pub interval_minutes: u32,
+    /// Backups kept per project; older ones are deleted.
+    pub keep: usize,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self { enabled: false, directory: None, interval_minutes: DEFAULT_INTERVAL_MINUTES, keep: DEFAULT_KEEP }
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct BackupInfo {
+    pub id: String,
+    /// When the backup was taken, in milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+    pub path: String,
+    /// Size of the archive.
+    pub size: u64,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct BackupRestore {
+    /// Files written back.
+    pub files: usize,
+    /// The backup of the project taken just before restoring.
+    pub previous: BackupInfo,
+}
+
+// ── Settings ──
+
+fn settings_path() -> PathBuf {
+    dirs::home_dir().unwrap_or_default().join(".leo-ide").join("backups.json")
+}
+
+fn load_settings() -> BackupSettings {
+    std::fs::read_to_string(settings_path()).ok().and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default()
+}
+
+/// The backup folder for `root` under `directory`: the project's name and
+/// a hash of its path, so two projects named `app` don't share one.
+fn project_dir(directory: &Path, root: &Path) -> PathBuf {
+    let digest = Sha256::digest(root.to_string_lossy().as_bytes());
+    let hash: String = digest.iter().take(8).map(|b| format!("{:02x}", b)).collect();
+    let name = root.file_name().map(|n| slugify(&n.to_string_lossy())).filter(|n| !n.is_empty());
+    directory.join(format!("{}-{}", name.unwrap_or_else(|| "project".to_string()), hash))
+}
+
+fn backup_dir(settings: &BackupSettings, root: &Path) -> Result<PathBuf, String> {
+    let directory = PathBuf::from(settings.directory.as_deref().ok_or("No backup folder is configured")?);
+    if directory.starts_with(root) {
+        return Err("The backup folder can't be inside the project".to_string());
+    }
+    Ok(project_dir(&directory, root))
+}
+
+// ── Backups ──
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+fn file_name(id: &str) -> String {
+    format!("backup-{}.zip", id)
+}
+
+/// Backups in `dir`, newest first.
+fn list(dir: &Path) -> Vec<BackupInfo> {
+    let mut backups: Vec<BackupInfo> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let id = name.strip_prefix("backup-")?.strip_suffix(".zip")?.to_string();
+            let timestamp_ms = id.parse().ok()?;
+            let size = entry.metadata().ok()?.len();
+            Some(BackupInfo { id, timestamp_ms, path: entry.path().to_string_lossy().into_owned(), size })
+        })
+        .collect();
+    backups.sort_by_key(|b| std::cmp::Reverse(b.timestamp_ms));
+    backups
+}
+
+/// Zip `root`'s files into `dir`.
+fn create(root: &Path, dir: &Path, now: u64) -> Result<BackupInfo, String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create backup folder: {}", e.kind()))?;
+    let id = now.to_string();
+    let output = dir.join(file_name(&id));
+    let entries = archive::collect(&[root.to_path_buf()], &output, &CompressOptions::default())?;
+    let result = archive::write_zip(&entries, &output, |_| {}, || false)?;
+    Ok(BackupInfo { id, timestamp_ms: now, path: result.output_path, size: result.archive_bytes })
+}
+
+/// Delete all but the newest `keep` backups in `dir`.
+fn rotate(dir: &Path, keep: usize) {
+    for old in list(dir).into_iter().skip(keep.max(1)) {
+        let _ = std::fs::remove_file(&old.path);
+    }
+}
+
+/// Write the files in backup `id` back into `root`. Archive names start
+/// with the project folder's name, which is dropped.
+fn restore(root: &Path, dir: &Path, id: &str) -> Result<usize, String> {
+    let zip_err = |e: zip::result::ZipError| format!("Failed to read backup: {}", e);
+    let file = std::fs::File::open(dir.join(file_name(id))).map_err(|_| "That backup no longer exists".to_string())?;
+    let mut zip = zip::ZipArchive::new(file).map_err(zip_err)?;
+    let mut restored = 0;
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).map_err(zip_err)?;
+        let Some(name) = entry.enclosed_name() else { continue };
+        let rel: PathBuf = name.components().skip(1).collect();
+        if entry.is_dir() || rel.as_os_str().is_empty() {
+            continue;
+        }
+        let target = resolve_within_root(&root.join(&rel).to_string_lossy(), root)?;
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content).map_err(|e| format!("Failed to read backup: {}", e.kind()))?;
+        if std::fs::read(&target).is_ok_and(|current| current == content) {
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("Failed to restore {}: {}", rel.display(), e.kind()))?;
+        }
+        write_atomic(&target, &content)?;
+        restored += 1;
+    }
+    Ok(restored)
+}
+
+// ── Scheduling ──
+
+/// Projects with saves since their last backup.
+fn changed() -> &'static Mutex<HashSet<PathBuf>> {
+    static CHANGED: OnceLock<Mutex<HashSet<PathBuf>>> = OnceLock::new();
+    CHANGED.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// Called by saves: `root` has changed, so back it up once it is due.
+/// The first call starts the scheduler.
+pub(super) fn note_activity(root: &Path) {
+    static STARTED: Once = Once::new();
+    STARTED.call_once(|| {
+        std::thread::spawn(|| loop {
+            std::thread::sleep(CHECK_EVERY);
+            back_up_due(&load_settings(), now_ms());
+        });
+    });
+    if let Ok(mut changed) = changed().lock() {
+        changed.insert(root.to_path_buf());
+    }
+}
+
+/// Back up the changed projects whose newest backup is older than the
+/// interval.
+fn back_up_due(settings: &BackupSettings, now: u64) {
+    if !settings.enabled {
+        return;
+    }
+    let interval_ms = settings.interval_minutes.max(MIN_INTERVAL_MINUTES) as u64 * 60 * 1000;
+    let roots: Vec<PathBuf> = changed().lock().map(|c| c.iter().cloned().collect()).unwrap_or_default();
+    for root in roots {
+        let dir = match backup_dir(settings, &root) {
+            Ok(dir) => dir,
+            Err(e) => {
+                log::warn!("backup of {} skipped: {}", root.display(), e);
+                continue;
+            }
+        };
+        if list(&dir).first().is_some_and(|newest| now.saturating_sub(newest.timestamp_ms) < interval_ms) {
+            continue;
+        }
+        if let Ok(mut changed) = changed().lock() {
+            changed.remove(&root);
+        }
+        match create(&root, &dir, now) {
+            Ok(_) => rotate(&dir, settings.keep),
+            Err(e) => log::warn!("backup of {} failed: {}", root.display(), e),
+        }
+    }
+}
+
+fn project_root(window: &tauri::WebviewWindow, state: &tauri::State<'_, ProjectRootState>) -> Result<PathBuf, String> {
+    Ok(state.blocking_read().get(window.label()).and_then(|o| o.clone()).ok_or("No project is open")?)
+}
+
+// ── Commands ──
+
+#[tauri::command]
+pub fn get_backup_settings() -> BackupSettings {
+    load_settings()
+}
+
+/// Save the backup settings; enabling backups needs a folder to put them
+/// in, which is created if missing.
+#[tauri::command]
+pub fn configure_backups(settings: BackupSettings) -> Result<BackupSettings, String> {
+    let mut settings = settings;
+    settings.interval_minutes = settings.interval_minutes.max(MIN_INTERVAL_MINUTES);
+    settings.keep = settings.keep.max(1);
+    if let Some(directory) = &settings.directory {
+        let path = Path::new(directory);
+        if !path.is_absolute() {
+            return Err("The backup folder must be an absolute path".to_string());
+        }
+        std::fs::create_dir_all(path).map_err(|e| format!("Failed to create backup folder: {}", e.kind()))?;
+    } else if settings.enabled {
+        return Err("Choose a backup folder to enable backups".to_string());
+    }
+    let path = settings_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to save backup settings: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+    write_atomic(&path, json.as_bytes()).map_err(|e| format!("Failed to save backup settings: {}", e))?;
+    Ok(settings)
+}
+
+/// Back up the window's project now, whether or not backups are enabled
+/// or due.
+#[tauri::command]
+pub async fn run_backup_now(window: tauri::WebviewWindow, state: tauri::State<'_, ProjectRootState>) -> Result<BackupInfo, String> {
+    let root = super::project_root_async(window.label(), &state).await?;
+    let settings = load_settings();
+    let dir = backup_dir(&settings, &root)?;
+    tokio::task::spawn_blocking(move || {
+        let backup = create(&root, &dir, now_ms())?;
+        if let Ok(mut changed) = changed().lock() {
+            changed.remove(&root);
+        }
+        rotate(&dir, settings.keep);
+        Ok(backup)
+    })
+    .await
+    .map_err(|e| format!("Backup failed: {}", e))?
+}
+
+/// The window's project's backups, newest first.
+#[tauri::command]
+pub fn list_backups(window: tauri::WebviewWindow, state: tauri::State<'_, ProjectRootState>) -> Result<Vec<BackupInfo>, String> {
+    let root = project_root(&window, &state)?;
+    Ok(list(&backup_dir(&load_settings(), &root)?))
+}
+
+/// Write the files of backup `id` back into the project, backing up the
+/// current state first.
+#[tauri::command]
+pub async fn restore_backup(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    id: String,
+) -> Result<BackupRestore, String> {
+    let root = super::project_root_async(window.label(), &state).await?;
+    let settings = load_settings();
+    let dir = backup_dir(&settings, &root)?;
+    tokio::task::spawn_blocking(move || {
+        if !list(&dir).iter().any(|b| b.id == id) {
+            return Err("That backup no longer exists".to_string());
+        }
+        // Rotating only afterwards, so this can't delete the backup being
+        // restored.
+        let previous = create(&root, &dir, now_ms())?;
+        let files = restore(&root, &dir, &id)?;
+        rotate(&dir, settings.keep);
+        Ok(BackupRestore { files, previous })
+    })
+    .await
+    .map_err(|e| format!("Restore failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backs_up_rotates_and_restores() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("app");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join(".gitignore"), "target/\n").unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::create_dir_all(root.join("target")).unwrap();
+        std::fs::write(root.join("target/app"), "binary").unwrap();
+        let backups = project_dir(&dir.path().join("backups"), &root);
+
+        create(&root, &backups, 1_000).unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() { broken").unwrap();
+        std::fs::remove_file(root.join(".gitignore")).unwrap();
+        std::fs::write(root.join("src/new.rs"), "// new").unwrap();
+
+        // Changed and deleted files come back; new and ignored ones stay.
+        assert_eq!(restore(&root, &backups, "1000").unwrap(), 2);
+        assert_eq!(std::fs::read_to_string(root.join("src/main.rs")).unwrap(), "fn main() {}");
+        assert_eq!(std::fs::read_to_string(root.join(".gitignore")).unwrap(), "target/\n");
+        assert!(root.join("src/new.rs").exists());
+        assert_eq!(std::fs::read_to_string(root.join("target/app")).unwrap(), "binary");
+        assert_eq!(restore(&root, &backups, "1000").unwrap(), 0);
+
+        create(&root, &backups, 2_000).unwrap();
+        create(&root, &backups, 3_000).unwrap();
+        assert_eq!(list(&backups).iter().map(|b| b.timestamp_ms).collect::<Vec<_>>(), [3_000, 2_000, 1_000]);
+        rotate(&backups, 2);
+        assert_eq!(list(&backups).iter().map(|b| b.timestamp_ms).collect::<Vec<_>>(), [3_000, 2_000]);
+        assert!(restore(&root, &backups, "1000").is_err());
+    }
+
+    #[test]
+    fn backs_up_changed_projects_when_due() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("site");
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("index.html"), "<p>hi</p>").unwrap();
+        let settings = BackupSettings {
+            enabled: true,
+            directory: Some(dir.path().join("backups").to_string_lossy().into_owned()),
+            interval_minutes: 10,
+            keep: 3,
+        };
+        let backups = backup_dir(&settings, &root).unwrap();
+        let minute = 60 * 1000;
+
+        back_up_due(&settings, 100 * minute);
+        assert!(list(&backups).is_empty());
+        changed().lock().unwrap().insert(root.clone());
+        back_up_due(&settings, 100 * minute);
+        assert_eq!(list(&backups).len(), 1);
+        // Not changed since.
+        back_up_due(&settings, 200 * minute);
+        assert_eq!(list(&backups).len(), 1);
+        // Changed, but not due yet.
+        changed().lock().unwrap().insert(root.clone());
+        back_up_due(&settings, 105 * minute);
+        assert_eq!(list(&backups).len(), 1);
+        back_up_due(&settings, 111 * minute);
+        assert_eq!(list(&backups).len(), 2);
+        assert!(!changed().lock().unwrap().contains(&root));
+
+        assert!(backup_dir(&BackupSettings { directory: Some(root.join("bak").to_string_lossy().into_owned()), ..settings }, &root).is_err());
+    }
+}
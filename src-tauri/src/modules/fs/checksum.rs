@@ -0,0 +1,101 @@
+//! File checksums for verifying build artifacts and downloads.
+//!
+//! Files are hashed in 64 KB chunks on a blocking thread, so a multi-GB
+//! firmware image neither sits in memory nor stalls the async runtime.
+
+use serde::{Deserialize, Serialize};
+use sha2::Digest;
+use std::io::Read;
+use std::path::Path;
+
+use super::{project_root_async, resolve_within_root, ProjectRootState};
+
+const CHUNK_BYTES: usize = 64 * 1024;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    Sha256,
+    Sha1,
+    Md5,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct FileHash {
+    pub algorithm: HashAlgorithm,
+    /// Lowercase hex digest.
+    pub hex: String,
+    pub size: u64,
+    /// Whether `hex` equals the expected digest, when one was given.
+    pub matches: Option<bool>,
+}
+
+fn digest<D: Digest>(mut reader: impl Read) -> std::io::Result<(String, u64)> {
+    let mut hasher = D::new();
+    let mut buf = vec![0u8; CHUNK_BYTES];
+    let mut size = 0u64;
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        size += n as u64;
+    }
+    let hex = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+    Ok((hex, size))
+}
+
+pub(crate) fn hash(path: &Path, algorithm: HashAlgorithm) -> Result<(String, u64), String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("Failed to read file: {}", e.kind()))?;
+    let result = match algorithm {
+        HashAlgorithm::Sha256 => digest::<sha2::Sha256>(file),
+        HashAlgorithm::Sha1 => digest::<sha1::Sha1>(file),
+        HashAlgorithm::Md5 => digest::<md5::Md5>(file),
+    };
+    result.map_err(|e| format!("Failed to read file: {}", e.kind()))
+}
+
+/// Hash `path` with `algorithm`. With `expected` (hex, any case, spaces
+/// ignored), also reports whether the digest matches.
+#[tauri::command]
+pub async fn hash_file(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: String,
+    algorithm: HashAlgorithm,
+    expected: Option<String>,
+) -> Result<FileHash, String> {
+    let root = project_root_async(window.label(), &state).await?;
+    let file = resolve_within_root(&path, &root)?;
+    if file.is_dir() {
+        return Err("Cannot hash a directory".to_string());
+    }
+    let (hex, size) = tokio::task::spawn_blocking(move || hash(&file, algorithm))
+        .await
+        .map_err(|e| format!("Hashing failed: {}", e))??;
+    let matches = expected.map(|e| e.split_whitespace().collect::<String>().eq_ignore_ascii_case(&hex));
+    Ok(FileHash { algorithm, hex, size, matches })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_with_each_algorithm() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("firmware.bin");
+        std::fs::write(&file, b"abc").unwrap();
+        assert_eq!(
+            hash(&file, HashAlgorithm::Sha256).unwrap(),
+            ("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad".to_string(), 3)
+        );
+        assert_eq!(hash(&file, HashAlgorithm::Sha1).unwrap().0, "a9993e364706816aba3e25717850c26c9cd0d89d");
+        assert_eq!(hash(&file, HashAlgorithm::Md5).unwrap().0, "900150983cd24fb0d6963f7d28e17f72");
+
+        let big = dir.path().join("big.bin");
+        std::fs::write(&big, vec![7u8; CHUNK_BYTES * 2 + 5]).unwrap();
+        assert_eq!(hash(&big, HashAlgorithm::Md5).unwrap().1, (CHUNK_BYTES * 2 + 5) as u64);
+    }
+}
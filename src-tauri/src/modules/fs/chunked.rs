@@ -0,0 +1,279 @@
+//! Chunked reads for files too large to load as one string.
+//!
+//! `read_file_chunked` streams the file to the requesting window as
+//! `file-chunk` events; `read_file_window` fetches a single chunk at a
+//! byte offset on demand, for editors that only want the visible part.
+//! Chunks end on a line break where possible, so the frontend can index
+//! lines without stitching text across chunks. A line longer than the
+//! chunk size is split at a UTF-8 boundary and the chunk is flagged with
+//! `partial_line`.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use tauri::AppHandle;
+use ts_rs::TS;
+
+use super::{validate_path, ProjectRootState};
+use crate::modules::events;
+
+const DEFAULT_CHUNK_BYTES: usize = 1024 * 1024;
+const MAX_CHUNK_BYTES: usize = 16 * 1024 * 1024;
+/// How far past the chunk size to look for a line break before splitting.
+const MAX_LINE_OVERRUN: u64 = 64 * 1024;
+
+#[derive(Serialize, Clone, Debug, PartialEq, TS)]
+pub struct FileChunk {
+    pub request_id: String,
+    pub index: usize,
+    /// Byte offset of `text` in the file.
+    #[ts(type = "number")]
+    pub start_byte: u64,
+    /// Byte offset just past this chunk; pass to `read_file_window` to
+    /// continue from here.
+    #[ts(type = "number")]
+    pub next_byte: u64,
+    /// 0-based line number of the first line in `text`, counted from the
+    /// start of the read.
+    pub start_line: usize,
+    pub text: String,
+    /// The chunk ends mid-line because the line exceeds the chunk size.
+    pub partial_line: bool,
+    pub done: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ChunkedReadInfo {
+    pub request_id: String,
+    pub size: u64,
+}
+
+// ── Chunking ──
+
+struct Chunk {
+    start_byte: u64,
+    start_line: usize,
+    text: String,
+    partial_line: bool,
+}
+
+struct Chunker<R> {
+    reader: R,
+    /// Bytes of an incomplete UTF-8 sequence held back from the last chunk.
+    carry: Vec<u8>,
+    offset: u64,
+    line: usize,
+    target: usize,
+}
+
+impl<R: BufRead> Chunker<R> {
+    fn new(reader: R, offset: u64, target: usize) -> Self {
+        Self { reader, carry: Vec::new(), offset, line: 0, target }
+    }
+
+    fn next_chunk(&mut self) -> std::io::Result<Option<Chunk>> {
+        let mut buf = std::mem::take(&mut self.carry);
+        let want = self.target.saturating_sub(buf.len()) as u64;
+        (&mut self.reader).take(want).read_to_end(&mut buf)?;
+        if buf.is_empty() {
+            return Ok(None);
+        }
+        if !buf.ends_with(b"\n") {
+            (&mut self.reader).take(MAX_LINE_OVERRUN).read_until(b'\n', &mut buf)?;
+        }
+        let at_eof = self.reader.fill_buf()?.is_empty();
+        let partial_line = !buf.ends_with(b"\n") && !at_eof;
+
+        if partial_line {
+            if let Err(e) = std::str::from_utf8(&buf) {
+                if e.error_len().is_none() {
+                    self.carry = buf.split_off(e.valid_up_to());
+                }
+            }
+        }
+
+        let chunk = Chunk {
+            start_byte: self.offset,
+            start_line: self.line,
+            text: String::from_utf8_lossy(&buf).into_owned(),
+            partial_line,
+        };
+        self.offset += buf.len() as u64;
+        self.line += buf.iter().filter(|&&b| b == b'\n').count();
+        Ok(Some(chunk))
+    }
+}
+
+fn chunk_size(requested: Option<usize>) -> usize {
+    requested.unwrap_or(DEFAULT_CHUNK_BYTES).clamp(4096, MAX_CHUNK_BYTES)
+}
+
+// ── Cancellation ──
+
+fn active_reads() -> &'static Mutex<HashMap<String, Arc<AtomicBool>>> {
+    static ACTIVE: OnceLock<Mutex<HashMap<String, Arc<AtomicBool>>>> = OnceLock::new();
+    ACTIVE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+// ── Commands ──
+
+/// Stream `path` to the calling window as `file-chunk` events tagged with
+/// `request_id`. The last event has `done: true` (and `error` set if the
+/// read failed). Returns as soon as streaming has started.
+#[tauri::command]
+pub fn read_file_chunked(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    app: AppHandle,
+    path: String,
+    request_id: String,
+    chunk_bytes: Option<usize>,
+) -> Result<ChunkedReadInfo, String> {
+    let path = validate_path(&path, window.label(), &state)?;
+    let file = File::open(&path).map_err(|e| format!("Failed to read file: {}", e.kind()))?;
+    let size = file.metadata().map_err(|e| format!("Failed to read file: {}", e.kind()))?.len();
+
+    let cancelled = Arc::new(AtomicBool::new(false));
+    active_reads().lock().unwrap().insert(request_id.clone(), cancelled.clone());
+
+    let label = window.label().to_string();
+    let id = request_id.clone();
+    let target_bytes = chunk_size(chunk_bytes);
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut chunker = Chunker::new(BufReader::new(file), 0, target_bytes);
+        let mut index = 0;
+        let error = loop {
+            if cancelled.load(Ordering::Relaxed) {
+                break Some("Cancelled".to_string());
+            }
+            match chunker.next_chunk() {
+                Ok(Some(chunk)) => {
+                    let event = FileChunk {
+                        request_id: id.clone(),
+                        index,
+                        next_byte: chunker.offset,
+                        start_byte: chunk.start_byte,
+                        start_line: chunk.start_line,
+                        text: chunk.text,
+                        partial_line: chunk.partial_line,
+                        done: false,
+                        error: None,
+                    };
+                    events::FILE_CHUNK.emit_to(&app, &label, event);
+                    index += 1;
+                }
+                Ok(None) => break None,
+                Err(e) => break Some(format!("Failed to read file: {}", e.kind())),
+            }
+        };
+        events::FILE_CHUNK.emit_to(&app, &label, FileChunk {
+            request_id: id.clone(),
+            index,
+            start_byte: chunker.offset,
+            next_byte: chunker.offset,
+            start_line: chunker.line,
+            text: String::new(),
+            partial_line: false,
+            done: true,
+            error,
+        });
+        active_reads().lock().unwrap().remove(&id);
+    });
+
+    Ok(ChunkedReadInfo { request_id, size })
+}
+
+/// Stop a running `read_file_chunked`. Returns false if it already ended.
+#[tauri::command]
+pub fn cancel_read_file_chunked(request_id: String) -> bool {
+    match active_reads().lock().unwrap().get(&request_id) {
+        Some(flag) => {
+            flag.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Read one chunk starting at byte `offset` — normally a `next_byte` from
+/// an earlier chunk. `start_line` is relative to `offset`.
+#[tauri::command]
+pub fn read_file_window(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: String,
+    offset: u64,
+    chunk_bytes: Option<usize>,
+) -> Result<FileChunk, String> {
+    let path = validate_path(&path, window.label(), &state)?;
+    let mut file = File::open(&path).map_err(|e| format!("Failed to read file: {}", e.kind()))?;
+    file.seek(SeekFrom::Start(offset)).map_err(|e| format!("Failed to read file: {}", e.kind()))?;
+    let mut chunker = Chunker::new(BufReader::new(file), offset, chunk_size(chunk_bytes));
+    let chunk = chunker.next_chunk().map_err(|e| format!("Failed to read file: {}", e.kind()))?;
+    let done = chunker.reader.fill_buf().map(|b| b.is_empty()).unwrap_or(true) && chunker.carry.is_empty();
+    Ok(match chunk {
+        Some(chunk) => FileChunk {
+            request_id: String::new(),
+            index: 0,
+            next_byte: chunker.offset,
+            start_byte: chunk.start_byte,
+            start_line: chunk.start_line,
+            text: chunk.text,
+            partial_line: chunk.partial_line,
+            done,
+            error: None,
+        },
+        None => FileChunk {
+            request_id: String::new(),
+            index: 0,
+            start_byte: offset,
+            next_byte: offset,
+            start_line: 0,
+            text: String::new(),
+            partial_line: false,
+            done: true,
+            error: None,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn chunks(data: &[u8], target: usize) -> Vec<Chunk> {
+        let mut chunker = Chunker::new(Cursor::new(data.to_vec()), 0, target);
+        std::iter::from_fn(|| chunker.next_chunk().unwrap()).collect()
+    }
+
+    #[test]
+    fn chunks_end_on_line_breaks() {
+        let out = chunks(b"aaaa\nbbbb\ncccc\n", 6);
+        let texts: Vec<&str> = out.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(texts, vec!["aaaa\nbbbb\n", "cccc\n"]);
+        assert_eq!((out[1].start_byte, out[1].start_line), (10, 2));
+        assert!(out.iter().all(|c| !c.partial_line));
+    }
+
+    #[test]
+    fn long_lines_split_on_char_boundaries() {
+        let line = "é".repeat(100_000);
+        let out = chunks(line.as_bytes(), 4097);
+        assert!(out.len() > 1);
+        assert!(out[0].partial_line);
+        assert!(out.iter().all(|c| !c.text.contains('\u{FFFD}')));
+        let joined: String = out.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(joined, line);
+        assert_eq!(out[1].start_byte, out[0].text.len() as u64);
+    }
+
+    #[test]
+    fn empty_file_has_no_chunks() {
+        assert!(chunks(b"", 4096).is_empty());
+    }
+}
@@ -0,0 +1,182 @@
+//! Path completion for in-app path inputs ("Move to…", "New file in…",
+//! markdown links).
+//!
+//! Completion only ever lists a single directory — the one named by the
+//! part of the prefix before the last `/` — so it stays fast in large
+//! trees. Entries ignored by `.gitignore` (including parent and global
+//! ignore files) are left out.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::{validate_path, ProjectRootState};
+
+const MAX_COMPLETIONS: usize = 50;
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PathKind {
+    File,
+    Dir,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct PathCompletion {
+    /// Entry name, e.g. `components`.
+    pub name: String,
+    /// Replacement for the whole prefix, e.g. `src/components/`.
+    /// Directories end in `/` so the user can keep typing.
+    pub completion: String,
+    /// Absolute path of the entry.
+    pub path: String,
+    pub is_dir: bool,
+}
+
+/// How well `name` matches the typed fragment; lower is better, `None`
+/// is no match.
+fn match_tier(name: &str, fragment: &str) -> Option<u8> {
+    if fragment.is_empty() || name.starts_with(fragment) {
+        return Some(0);
+    }
+    let name_lower = name.to_lowercase();
+    let fragment_lower = fragment.to_lowercase();
+    if name_lower.starts_with(&fragment_lower) {
+        return Some(1);
+    }
+    if name_lower.contains(&fragment_lower) {
+        return Some(2);
+    }
+    let mut chars = name_lower.chars();
+    fragment_lower.chars().all(|f| chars.any(|c| c == f)).then_some(3)
+}
+
+/// Split a typed prefix into its directory part (kept verbatim, including
+/// the trailing `/`) and the fragment being completed.
+fn split_prefix(prefix: &str) -> (&str, &str) {
+    match prefix.rfind('/') {
+        Some(i) => (&prefix[..=i], &prefix[i + 1..]),
+        None => ("", prefix),
+    }
+}
+
+fn complete_in(
+    dir: &Path,
+    dir_part: &str,
+    fragment: &str,
+    kinds: &[PathKind],
+) -> Vec<PathCompletion> {
+    let walker = ignore::WalkBuilder::new(dir)
+        .max_depth(Some(1))
+        .hidden(false)
+        .parents(true)
+        .git_ignore(true)
+        .git_global(true)
+        .git_exclude(true)
+        .build();
+
+    // Dotfiles only show up once the user starts typing a dot.
+    let show_hidden = fragment.starts_with('.');
+    let mut ranked: Vec<(u8, PathCompletion)> = walker
+        .flatten()
+        .filter(|e| e.depth() == 1)
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name == ".git" || (name.starts_with('.') && !show_hidden) {
+                return None;
+            }
+            let is_dir = entry.path().is_dir();
+            let kind = if is_dir { PathKind::Dir } else { PathKind::File };
+            // Directories stay completable when only files are wanted, so
+            // the user can navigate down to them.
+            if !kinds.contains(&kind) && !is_dir {
+                return None;
+            }
+            let tier = match_tier(&name, fragment)?;
+            let completion = format!("{}{}{}", dir_part, name, if is_dir { "/" } else { "" });
+            Some((tier, PathCompletion {
+                completion,
+                path: entry.path().to_string_lossy().to_string(),
+                name,
+                is_dir,
+            }))
+        })
+        .collect();
+
+    ranked.sort_by(|(ta, a), (tb, b)| {
+        ta.cmp(tb)
+            .then(b.is_dir.cmp(&a.is_dir))
+            .then(a.name.len().cmp(&b.name.len()))
+            .then(a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    ranked.into_iter().take(MAX_COMPLETIONS).map(|(_, c)| c).collect()
+}
+
+/// Complete `prefix`, interpreted relative to `base_dir` (or to the
+/// project root when it starts with `/`). `kinds` defaults to both files
+/// and directories.
+#[tauri::command]
+pub fn complete_path(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    prefix: String,
+    base_dir: String,
+    kinds: Option<Vec<PathKind>>,
+) -> Result<Vec<PathCompletion>, String> {
+    let base = validate_path(&base_dir, window.label(), &state)?;
+    let root = {
+        let map = state.blocking_read();
+        map.get(window.label()).and_then(|o| o.clone()).ok_or_else(|| "No project is open".to_string())?
+    };
+    let kinds = kinds.unwrap_or_else(|| vec![PathKind::File, PathKind::Dir]);
+
+    let (dir_part, fragment) = split_prefix(&prefix);
+    let dir = match dir_part.strip_prefix('/') {
+        Some(rel) => root.join(rel),
+        None => base.join(dir_part),
+    };
+    let dir = match validate_path(&dir.to_string_lossy(), window.label(), &state) {
+        Ok(d) if d.is_dir() => d,
+        _ => return Ok(Vec::new()),
+    };
+    Ok(complete_in(&dir, dir_part, fragment, &kinds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiers_rank_prefix_before_substring_before_fuzzy() {
+        assert_eq!(match_tier("Button.svelte", "Butt"), Some(0));
+        assert_eq!(match_tier("Button.svelte", "butt"), Some(1));
+        assert_eq!(match_tier("MyButton.svelte", "button"), Some(2));
+        assert_eq!(match_tier("MyButton.svelte", "mbs"), Some(3));
+        assert_eq!(match_tier("MyButton.svelte", "xyz"), None);
+    }
+
+    #[test]
+    fn split_prefix_keeps_directory_verbatim() {
+        assert_eq!(split_prefix("src/lib/Comp"), ("src/lib/", "Comp"));
+        assert_eq!(split_prefix("src/"), ("src/", ""));
+        assert_eq!(split_prefix("REA"), ("", "REA"));
+    }
+
+    #[test]
+    fn completes_dirs_first_and_respects_gitignore() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir(root.join(".git")).unwrap();
+        std::fs::write(root.join(".gitignore"), "secret.txt\n").unwrap();
+        std::fs::create_dir(root.join("scripts")).unwrap();
+        std::fs::write(root.join("setup.py"), "").unwrap();
+        std::fs::write(root.join("secret.txt"), "").unwrap();
+
+        let all = complete_in(root, "", "s", &[PathKind::File, PathKind::Dir]);
+        let names: Vec<&str> = all.iter().map(|c| c.completion.as_str()).collect();
+        assert_eq!(names, vec!["scripts/", "setup.py"]);
+
+        let dirs = complete_in(root, "", "s", &[PathKind::Dir]);
+        assert_eq!(dirs.len(), 1);
+        assert!(dirs[0].is_dir);
+    }
+}
@@ -0,0 +1,231 @@
+//! Permanent deletion for trees too big to send to the trash.
+//!
+//! `delete_entries` moves entries to the trash, which for a
+//! `node_modules` with tens of thousands of files can take minutes and
+//! can't be stopped. `delete_entries_permanent` removes them outright on
+//! a background thread instead, counting the files first so it can report
+//! `delete-progress` events against a total. `cancel_delete_entries` stops
+//! it between files; whatever was removed by then stays removed. Symlinks
+//! are removed, never followed.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use ts_rs::TS;
+
+use super::{validate_path, ProjectRootState};
+use crate::modules::events;
+
+/// Minimum time between progress events.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Serialize, Clone, Debug, PartialEq, TS)]
+pub struct DeleteProgress {
+    pub operation_id: String,
+    /// Files, links and directories removed so far.
+    pub deleted: usize,
+    pub total: usize,
+    /// The entry being removed when the event was sent.
+    pub current: Option<String>,
+    pub done: bool,
+    pub cancelled: bool,
+    pub error: Option<String>,
+}
+
+fn active_deletes() -> &'static Mutex<HashMap<String, Arc<AtomicBool>>> {
+    static ACTIVE: OnceLock<Mutex<HashMap<String, Arc<AtomicBool>>>> = OnceLock::new();
+    ACTIVE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Entries under (and including) `path`, without following symlinks.
+fn count_entries(path: &Path, cancelled: &AtomicBool) -> usize {
+    let mut count = 0;
+    let mut stack = vec![path.to_path_buf()];
+    while let Some(p) = stack.pop() {
+        if cancelled.load(Ordering::Relaxed) {
+            break;
+        }
+        count += 1;
+        if fs::symlink_metadata(&p).is_ok_and(|m| m.is_dir()) {
+            if let Ok(entries) = fs::read_dir(&p) {
+                stack.extend(entries.flatten().map(|e| e.path()));
+            }
+        }
+    }
+    count
+}
+
+/// Why a removal stopped early.
+#[derive(Debug, PartialEq)]
+enum Stop {
+    Cancelled,
+    Failed(String),
+}
+
+/// Remove `path` depth-first, calling `on_removed` after each entry.
+/// Checks `cancelled` before every removal.
+fn remove_tree(path: &Path, cancelled: &AtomicBool, on_removed: &mut impl FnMut(&Path)) -> Result<(), Stop> {
+    // Directories are pushed twice: once to list them, then (marked
+    // `true`) to remove them once their children are gone.
+    let mut stack = vec![(path.to_path_buf(), false)];
+    while let Some((p, emptied)) = stack.pop() {
+        if cancelled.load(Ordering::Relaxed) {
+            return Err(Stop::Cancelled);
+        }
+        let failed = |e: std::io::Error| Stop::Failed(format!("Failed to delete {}: {}", p.display(), e));
+        let meta = match fs::symlink_metadata(&p) {
+            Ok(m) => m,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(failed(e)),
+        };
+        if !meta.is_dir() {
+            fs::remove_file(&p).map_err(failed)?;
+        } else if emptied {
+            fs::remove_dir(&p).map_err(failed)?;
+        } else {
+            let children: Vec<PathBuf> = fs::read_dir(&p).map_err(failed)?.flatten().map(|e| e.path()).collect();
+            stack.push((p, true));
+            stack.extend(children.into_iter().map(|c| (c, false)));
+            continue;
+        }
+        on_removed(&p);
+    }
+    Ok(())
+}
+
+/// Permanently delete `paths`, bypassing the trash. Progress is sent to
+/// the calling window as `delete-progress` events tagged with
+/// `operation_id`; the last has `done: true`. Returns once the delete has
+/// started.
+#[tauri::command]
+pub fn delete_entries_permanent(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    app: AppHandle,
+    paths: Vec<String>,
+    operation_id: String,
+) -> Result<(), String> {
+    let root = state
+        .blocking_read()
+        .get(window.label())
+        .and_then(|o| o.clone())
+        .ok_or("No project is open")?;
+    let mut targets = Vec::new();
+    for path in &paths {
+        let p = validate_path(path, window.label(), &state)?;
+        if p == root {
+            return Err("Refusing to delete the project root".to_string());
+        }
+        // Validation resolves links; remove the link itself, not its target.
+        targets.push(PathBuf::from(path));
+    }
+
+    let cancelled = Arc::new(AtomicBool::new(false));
+    active_deletes().lock().unwrap().insert(operation_id.clone(), cancelled.clone());
+
+    let label = window.label().to_string();
+    let id = operation_id;
+    tauri::async_runtime::spawn_blocking(move || {
+        let total = targets.iter().map(|p| count_entries(p, &cancelled)).sum();
+        let mut deleted = 0;
+        let mut last_emit = Instant::now();
+        let mut result = Ok(());
+        for path in &targets {
+            result = remove_tree(path, &cancelled, &mut |p| {
+                deleted += 1;
+                if last_emit.elapsed() >= PROGRESS_INTERVAL {
+                    last_emit = Instant::now();
+                    events::DELETE_PROGRESS.emit_to(&app, &label, DeleteProgress {
+                        operation_id: id.clone(),
+                        deleted,
+                        total,
+                        current: Some(p.to_string_lossy().into_owned()),
+                        done: false,
+                        cancelled: false,
+                        error: None,
+                    });
+                }
+            });
+            if result.is_err() {
+                break;
+            }
+        }
+        let (cancelled, error) = match result {
+            Ok(()) => (false, None),
+            Err(Stop::Cancelled) => (true, None),
+            Err(Stop::Failed(e)) => (false, Some(e)),
+        };
+        events::DELETE_PROGRESS.emit_to(&app, &label, DeleteProgress {
+            operation_id: id.clone(),
+            deleted,
+            total,
+            current: None,
+            done: true,
+            cancelled,
+            error,
+        });
+        active_deletes().lock().unwrap().remove(&id);
+    });
+
+    Ok(())
+}
+
+/// Stop a running `delete_entries_permanent`. Returns false if it
+/// already ended.
+#[tauri::command]
+pub fn cancel_delete_entries(operation_id: String) -> bool {
+    match active_deletes().lock().unwrap().get(&operation_id) {
+        Some(flag) => {
+            flag.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removes_trees_and_stops_when_cancelled() {
+        let dir = tempfile::tempdir().unwrap();
+        let tree = dir.path().join("node_modules");
+        for pkg in ["a", "b", "c"] {
+            fs::create_dir_all(tree.join(pkg).join("lib")).unwrap();
+            fs::write(tree.join(pkg).join("index.js"), "x").unwrap();
+            fs::write(tree.join(pkg).join("lib/util.js"), "x").unwrap();
+        }
+        let keep = dir.path().join("keep");
+        fs::create_dir(&keep).unwrap();
+        fs::write(keep.join("file"), "x").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&keep, tree.join("a/linked")).unwrap();
+
+        let never = AtomicBool::new(false);
+        let total = count_entries(&tree, &never);
+        assert_eq!(total, if cfg!(unix) { 14 } else { 13 });
+
+        let stop = AtomicBool::new(false);
+        let mut removed = 0;
+        let result = remove_tree(&tree, &stop, &mut |_| {
+            removed += 1;
+            if removed == 3 {
+                stop.store(true, Ordering::Relaxed);
+            }
+        });
+        assert_eq!(result, Err(Stop::Cancelled));
+        assert!(tree.exists());
+
+        let mut removed_after = 0;
+        remove_tree(&tree, &never, &mut |_| removed_after += 1).unwrap();
+        assert_eq!(removed + removed_after, total);
+        assert!(!tree.exists());
+        assert!(keep.join("file").exists());
+    }
+}
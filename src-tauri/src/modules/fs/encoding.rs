@@ -0,0 +1,349 @@
+//! Encoding, BOM and line-ending audit.
+//!
+//! `audit_encodings` walks the project like search does and reports text
+//! files that aren't plain UTF-8 — a byte-order mark, UTF-16/32, or a
+//! legacy 8-bit encoding — or that mix line endings. Legacy files are
+//! read as Windows-1252, a superset of Latin-1 and by far the most common
+//! source of stray non-UTF-8 bytes. `fix_encodings` rewrites files as
+//! UTF-8 without a BOM, optionally normalizing line endings; like
+//! `replace_in_project` it previews unless `apply` is set.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::{history, project_root_async, resolve_within_root, write_atomic, ProjectRootState};
+use crate::modules::search::project_files;
+
+const MAX_FILES: usize = 100_000;
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Windows-1252 characters for bytes 0x80–0x9F; the rest match Latin-1.
+const CP1252_HIGH: [u16; 32] = [
+    0x20AC, 0x0081, 0x201A, 0x0192, 0x201E, 0x2026, 0x2020, 0x2021, 0x02C6, 0x2030, 0x0160, 0x2039, 0x0152, 0x008D,
+    0x017D, 0x008F, 0x0090, 0x2018, 0x2019, 0x201C, 0x201D, 0x2022, 0x2013, 0x2014, 0x02DC, 0x2122, 0x0161, 0x203A,
+    0x0153, 0x009D, 0x017E, 0x0178,
+];
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Encoding {
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    Utf32Le,
+    Utf32Be,
+    /// Not valid UTF-8; read as Windows-1252.
+    Legacy,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct LineEndings {
+    pub lf: usize,
+    pub crlf: usize,
+    pub cr: usize,
+}
+
+impl LineEndings {
+    fn count(text: &str) -> Self {
+        let mut counts = Self::default();
+        let bytes = text.as_bytes();
+        for (i, &b) in bytes.iter().enumerate() {
+            match b {
+                b'\n' if i > 0 && bytes[i - 1] == b'\r' => counts.crlf += 1,
+                b'\n' => counts.lf += 1,
+                b'\r' if bytes.get(i + 1) != Some(&b'\n') => counts.cr += 1,
+                _ => {}
+            }
+        }
+        counts
+    }
+
+    pub fn mixed(&self) -> bool {
+        [self.lf, self.crlf, self.cr].iter().filter(|&&n| n > 0).count() > 1
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct EncodingIssue {
+    /// Path relative to the project root.
+    pub path: String,
+    pub encoding: Encoding,
+    pub line_endings: LineEndings,
+    pub mixed_line_endings: bool,
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct EncodingAudit {
+    /// Sorted by path.
+    pub files: Vec<EncodingIssue>,
+    pub files_scanned: usize,
+    /// Whether the walk stopped at `MAX_FILES`.
+    pub truncated: bool,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEndingStyle {
+    Lf,
+    Crlf,
+    /// Whichever of LF and CRLF the file uses most.
+    Majority,
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct EncodingFixResult {
+    /// Files that were (or, in a preview, would be) rewritten.
+    pub files: Vec<String>,
+    /// False for a preview; true once files were written.
+    pub applied: bool,
+    /// Files that failed to read or write, with the error.
+    pub failed: Vec<(String, String)>,
+}
+
+/// Whether `bytes` are mostly printable, as opposed to a binary format.
+fn mostly_text(bytes: &[u8]) -> bool {
+    let control = bytes.iter().filter(|&&b| b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0C | 0x1B)).count();
+    control * 10 <= bytes.len()
+}
+
+/// UTF-16 without a BOM: ASCII-heavy text has a zero in every other byte.
+fn bomless_utf16(bytes: &[u8]) -> Option<Encoding> {
+    let pairs = bytes.len() / 2;
+    if pairs == 0 || bytes.len() % 2 != 0 {
+        return None;
+    }
+    let zeros_at = |offset: usize| bytes.iter().skip(offset).step_by(2).filter(|&&b| b == 0).count();
+    let (even, odd) = (zeros_at(0), zeros_at(1));
+    if odd * 10 >= pairs * 4 && even * 10 < pairs {
+        Some(Encoding::Utf16Le)
+    } else if even * 10 >= pairs * 4 && odd * 10 < pairs {
+        Some(Encoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+/// The encoding of `bytes`, or `None` for binary content.
+pub(crate) fn detect(bytes: &[u8]) -> Option<Encoding> {
+    let encoding = if bytes.starts_with(&[0xFF, 0xFE, 0, 0]) {
+        Encoding::Utf32Le
+    } else if bytes.starts_with(&[0, 0, 0xFE, 0xFF]) {
+        Encoding::Utf32Be
+    } else if bytes.starts_with(&[0xFF, 0xFE]) {
+        Encoding::Utf16Le
+    } else if bytes.starts_with(&[0xFE, 0xFF]) {
+        Encoding::Utf16Be
+    } else if bytes.starts_with(b"\xEF\xBB\xBF") {
+        Encoding::Utf8Bom
+    } else if bytes.contains(&0) {
+        bomless_utf16(bytes)?
+    } else if !mostly_text(bytes) {
+        return None;
+    } else if std::str::from_utf8(bytes).is_ok() {
+        Encoding::Utf8
+    } else {
+        Encoding::Legacy
+    };
+    decode(bytes, encoding).map(|_| encoding)
+}
+
+/// `bytes` as text, without any BOM.
+pub(crate) fn decode(bytes: &[u8], encoding: Encoding) -> Option<String> {
+    let units16 = |from_bytes: fn([u8; 2]) -> u16| -> Option<Vec<u16>> {
+        (bytes.len() % 2 == 0).then(|| bytes.chunks_exact(2).map(|c| from_bytes([c[0], c[1]])).collect())
+    };
+    let units32 = |from_bytes: fn([u8; 4]) -> u32| -> Option<String> {
+        if bytes.len() % 4 != 0 {
+            return None;
+        }
+        bytes.chunks_exact(4).map(|c| char::from_u32(from_bytes([c[0], c[1], c[2], c[3]]))).collect()
+    };
+    let text = match encoding {
+        Encoding::Utf8 | Encoding::Utf8Bom => String::from_utf8(bytes.to_vec()).ok()?,
+        Encoding::Utf16Le => String::from_utf16(&units16(u16::from_le_bytes)?).ok()?,
+        Encoding::Utf16Be => String::from_utf16(&units16(u16::from_be_bytes)?).ok()?,
+        Encoding::Utf32Le => units32(u32::from_le_bytes)?,
+        Encoding::Utf32Be => units32(u32::from_be_bytes)?,
+        Encoding::Legacy => bytes
+            .iter()
+            .map(|&b| match b {
+                0x80..=0x9F => char::from_u32(CP1252_HIGH[(b - 0x80) as usize] as u32).unwrap_or('\u{FFFD}'),
+                _ => b as char,
+            })
+            .collect(),
+    };
+    Some(text.strip_prefix('\u{FEFF}').map(str::to_string).unwrap_or(text))
+}
+
+fn read_bytes(path: &Path) -> Option<Vec<u8>> {
+    if std::fs::metadata(path).ok()?.len() > MAX_FILE_BYTES {
+        return None;
+    }
+    std::fs::read(path).ok()
+}
+
+fn inspect(root: &Path, path: &Path) -> Option<EncodingIssue> {
+    let bytes = read_bytes(path)?;
+    let encoding = detect(&bytes)?;
+    let line_endings = LineEndings::count(&decode(&bytes, encoding)?);
+    let mixed_line_endings = line_endings.mixed();
+    (encoding != Encoding::Utf8 || mixed_line_endings).then(|| EncodingIssue {
+        path: path.strip_prefix(root).unwrap_or(path).to_string_lossy().to_string(),
+        encoding,
+        line_endings,
+        mixed_line_endings,
+    })
+}
+
+pub(crate) fn audit(root: &Path, dir: &Path) -> EncodingAudit {
+    let mut report = EncodingAudit::default();
+    for (i, path) in project_files(root, dir).enumerate() {
+        if i == MAX_FILES {
+            report.truncated = true;
+            break;
+        }
+        report.files_scanned += 1;
+        report.files.extend(inspect(root, &path));
+    }
+    report.files.sort_by(|a, b| a.path.cmp(&b.path));
+    report
+}
+
+/// `bytes` rewritten as UTF-8 without a BOM and, with `style`, with one
+/// kind of line ending. `None` for binary content or nothing to change.
+pub(crate) fn fix_bytes(bytes: &[u8], style: Option<LineEndingStyle>) -> Option<Vec<u8>> {
+    let text = decode(bytes, detect(bytes)?)?;
+    let text = match style {
+        None => text,
+        Some(style) => {
+            let counts = LineEndings::count(&text);
+            let crlf = match style {
+                LineEndingStyle::Lf => false,
+                LineEndingStyle::Crlf => true,
+                LineEndingStyle::Majority => counts.crlf > counts.lf,
+            };
+            let lf = text.replace("\r\n", "\n").replace('\r', "\n");
+            if crlf {
+                lf.replace('\n', "\r\n")
+            } else {
+                lf
+            }
+        }
+    };
+    (text.as_bytes() != bytes).then(|| text.into_bytes())
+}
+
+/// Files in the project, or under `path`, with a BOM, a non-UTF-8
+/// encoding or mixed line endings.
+#[tauri::command]
+pub async fn audit_encodings(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: Option<String>,
+) -> Result<EncodingAudit, String> {
+    let root = project_root_async(window.label(), &state).await?;
+    let dir = match path {
+        Some(p) => resolve_within_root(&p, &root)?,
+        None => root.clone(),
+    };
+    tokio::task::spawn_blocking(move || audit(&root, &dir))
+        .await
+        .map_err(|e| format!("Encoding audit failed: {}", e))
+}
+
+/// Convert `paths` (every file the audit reports when omitted) to UTF-8
+/// without a BOM, normalizing line endings to `line_endings` when given.
+/// Only previews unless `apply` is set.
+#[tauri::command]
+pub async fn fix_encodings(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    paths: Option<Vec<String>>,
+    line_endings: Option<LineEndingStyle>,
+    apply: Option<bool>,
+) -> Result<EncodingFixResult, String> {
+    let root = project_root_async(window.label(), &state).await?;
+    let targets: Vec<PathBuf> = match paths {
+        Some(paths) => paths.iter().map(|p| resolve_within_root(p, &root).map_err(String::from)).collect::<Result<_, _>>()?,
+        None => {
+            let scan_root = root.clone();
+            let report = tokio::task::spawn_blocking(move || audit(&scan_root, &scan_root))
+                .await
+                .map_err(|e| format!("Encoding audit failed: {}", e))?;
+            report.files.iter().map(|f| root.join(&f.path)).collect()
+        }
+    };
+    let apply = apply.unwrap_or(false);
+    tokio::task::spawn_blocking(move || {
+        let mut result = EncodingFixResult { applied: apply, ..Default::default() };
+        for path in targets {
+            let rel = path.strip_prefix(&root).unwrap_or(&path).to_string_lossy().to_string();
+            let Some(bytes) = read_bytes(&path) else {
+                result.failed.push((rel, "Failed to read file".to_string()));
+                continue;
+            };
+            let Some(fixed) = fix_bytes(&bytes, line_endings) else { continue };
+            if apply {
+                history::keep(&root, &path, &fixed);
+                if let Err(e) = write_atomic(&path, &fixed) {
+                    result.failed.push((rel, e));
+                    continue;
+                }
+            }
+            result.files.push(rel);
+        }
+        result.files.sort();
+        result
+    })
+    .await
+    .map_err(|e| format!("Fixing encodings failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16le(text: &str, bom: bool) -> Vec<u8> {
+        let prefix: &[u8] = if bom { &[0xFF, 0xFE] } else { &[] };
+        prefix.iter().copied().chain(text.encode_utf16().flat_map(|u| u.to_le_bytes())).collect()
+    }
+
+    #[test]
+    fn detects_encodings_and_line_endings() {
+        assert_eq!(detect(b"plain\n"), Some(Encoding::Utf8));
+        assert_eq!(detect(b"\xEF\xBB\xBFbom\n"), Some(Encoding::Utf8Bom));
+        assert_eq!(detect(&utf16le("wide\r\n", true)), Some(Encoding::Utf16Le));
+        assert_eq!(detect(&utf16le("no bom here\n", false)), Some(Encoding::Utf16Le));
+        assert_eq!(detect(b"caf\xe9 \x93quoted\x94\n"), Some(Encoding::Legacy));
+        assert_eq!(decode(b"caf\xe9 \x93quoted\x94", Encoding::Legacy).unwrap(), "caf\u{e9} \u{201c}quoted\u{201d}");
+        assert_eq!(detect(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"), None);
+
+        let counts = LineEndings::count("a\r\nb\nc\rd\r\n");
+        assert_eq!(counts, LineEndings { lf: 1, crlf: 2, cr: 1 });
+        assert!(counts.mixed());
+        assert!(!LineEndings::count("a\nb\n").mixed());
+    }
+
+    #[test]
+    fn audits_and_fixes_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("clean.txt"), "ok\n").unwrap();
+        std::fs::write(root.join("bom.txt"), b"\xEF\xBB\xBFa\r\nb\r\nc\n").unwrap();
+        std::fs::write(root.join("wide.txt"), utf16le("hi\n", true)).unwrap();
+        std::fs::write(root.join("image.png"), b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+
+        let report = audit(root, root);
+        let found: Vec<_> = report.files.iter().map(|f| (f.path.as_str(), f.encoding, f.mixed_line_endings)).collect();
+        assert_eq!(found, [("bom.txt", Encoding::Utf8Bom, true), ("wide.txt", Encoding::Utf16Le, false)]);
+        assert_eq!(report.files_scanned, 4);
+
+        let bom = std::fs::read(root.join("bom.txt")).unwrap();
+        assert_eq!(fix_bytes(&bom, None).unwrap(), b"a\r\nb\r\nc\n");
+        assert_eq!(fix_bytes(&bom, Some(LineEndingStyle::Majority)).unwrap(), b"a\r\nb\r\nc\r\n");
+        assert_eq!(fix_bytes(&bom, Some(LineEndingStyle::Lf)).unwrap(), b"a\nb\nc\n");
+        assert_eq!(fix_bytes(&utf16le("hi\n", true), None).unwrap(), b"hi\n");
+        assert_eq!(fix_bytes(b"ok\n", Some(LineEndingStyle::Lf)), None);
+    }
+}
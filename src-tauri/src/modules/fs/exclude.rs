@@ -0,0 +1,159 @@
+//! Per-project exclude patterns.
+//!
+//! Patterns use gitignore syntax (`**/dist`, `*.o`, `build/`, `!keep.o`)
+//! relative to the project root and hide matching entries from the file
+//! tree, `list_all_files` and project search. `.git` is always skipped on
+//! top of these. Projects without their own list get `DEFAULT_PATTERNS`.
+//! Lists live in `~/.leo-ide/excludes.json`, keyed by project root.
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::{write_atomic, ProjectRootState};
+
+pub const DEFAULT_PATTERNS: &[&str] = &["node_modules/", "target/", ".DS_Store"];
+
+/// Compiled exclude patterns for one project.
+#[derive(Clone)]
+pub struct Excludes {
+    root: PathBuf,
+    matcher: Gitignore,
+}
+
+impl Excludes {
+    pub fn new(root: &Path, patterns: &[String]) -> Result<Self, String> {
+        let mut builder = GitignoreBuilder::new(root);
+        for pattern in patterns.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+            builder.add_line(None, pattern).map_err(|e| format!("Invalid exclude pattern '{}': {}", pattern, e))?;
+        }
+        let matcher = builder.build().map_err(|e| format!("Invalid exclude patterns: {}", e))?;
+        Ok(Self { root: root.to_path_buf(), matcher })
+    }
+
+    /// The project's saved patterns, or the defaults.
+    pub fn for_project(root: &Path) -> Self {
+        Self::new(root, &project_patterns(root))
+            .or_else(|_| Self::new(root, &default_patterns()))
+            .expect("default exclude patterns are valid")
+    }
+
+    /// Whether `path` (absolute, inside the project) or one of its parent
+    /// directories matches. Paths outside the root never match.
+    pub fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        path.strip_prefix(&self.root).is_ok_and(|rel| {
+            !rel.as_os_str().is_empty() && self.matcher.matched_path_or_any_parents(rel, is_dir).is_ignore()
+        })
+    }
+}
+
+fn default_patterns() -> Vec<String> {
+    DEFAULT_PATTERNS.iter().map(|p| p.to_string()).collect()
+}
+
+// ── Settings ──
+
+const SETTINGS_FILE: &str = "excludes.json";
+
+/// Per-project pattern lists stored in `~/.leo-ide/<file>`, keyed by
+/// project root. Shared with `generated`'s directory patterns.
+pub(super) fn load_pattern_lists(file: &str) -> HashMap<String, Vec<String>> {
+    let path = dirs::home_dir().unwrap_or_default().join(".leo-ide").join(file);
+    std::fs::read_to_string(path).ok().and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default()
+}
+
+pub(super) fn save_pattern_lists(file: &str, lists: &HashMap<String, Vec<String>>) -> Result<(), String> {
+    let path = dirs::home_dir().unwrap_or_default().join(".leo-ide").join(file);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(lists).map_err(|e| e.to_string())?;
+    write_atomic(&path, json.as_bytes())
+}
+
+fn load_settings() -> HashMap<String, Vec<String>> {
+    load_pattern_lists(SETTINGS_FILE)
+}
+
+fn patterns_for(settings: &HashMap<String, Vec<String>>, root: &Path) -> Vec<String> {
+    settings.get(root.to_string_lossy().as_ref()).cloned().unwrap_or_else(default_patterns)
+}
+
+/// The project's saved patterns, or the defaults.
+pub(crate) fn project_patterns(root: &Path) -> Vec<String> {
+    patterns_for(&load_settings(), root)
+}
+
+fn project_root(window: &tauri::WebviewWindow, state: &tauri::State<'_, ProjectRootState>) -> Result<PathBuf, String> {
+    state.blocking_read().get(window.label()).and_then(|o| o.clone()).ok_or_else(|| "No project is open".to_string())
+}
+
+// ── Commands ──
+
+#[tauri::command]
+pub fn get_exclude_patterns(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+) -> Result<Vec<String>, String> {
+    let root = project_root(&window, &state)?;
+    Ok(patterns_for(&load_settings(), &root))
+}
+
+/// Replace the project's exclude patterns; `None` restores the defaults.
+/// Every pattern is checked before anything is saved.
+#[tauri::command]
+pub fn set_exclude_patterns(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    patterns: Option<Vec<String>>,
+) -> Result<Vec<String>, String> {
+    let root = project_root(&window, &state)?;
+    let mut settings = load_settings();
+    let key = root.to_string_lossy().into_owned();
+    match patterns {
+        Some(patterns) => {
+            let patterns: Vec<String> =
+                patterns.iter().map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect();
+            Excludes::new(&root, &patterns)?;
+            settings.insert(key, patterns);
+        }
+        None => {
+            settings.remove(&key);
+        }
+    }
+    save_pattern_lists(SETTINGS_FILE, &settings).map_err(|e| format!("Failed to save exclude patterns: {}", e))?;
+    Ok(patterns_for(&settings, &root))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_gitignore_style_patterns() {
+        let root = Path::new("/proj");
+        let patterns: Vec<String> = ["**/dist", "*.o", "build/", "!keep.o"].iter().map(|p| p.to_string()).collect();
+        let excludes = Excludes::new(root, &patterns).unwrap();
+        assert!(excludes.is_excluded(Path::new("/proj/web/dist"), true));
+        assert!(excludes.is_excluded(Path::new("/proj/web/dist/app.js"), false));
+        assert!(excludes.is_excluded(Path::new("/proj/src/main.o"), false));
+        assert!(!excludes.is_excluded(Path::new("/proj/src/keep.o"), false));
+        assert!(excludes.is_excluded(Path::new("/proj/build"), true));
+        assert!(!excludes.is_excluded(Path::new("/proj/build"), false));
+        assert!(!excludes.is_excluded(Path::new("/proj/src/main.rs"), false));
+        assert!(!excludes.is_excluded(Path::new("/elsewhere/main.o"), false));
+        assert!(!excludes.is_excluded(root, true));
+        assert!(Excludes::new(root, &["src/{a,b".to_string()]).is_err());
+    }
+
+    #[test]
+    fn falls_back_to_defaults() {
+        let mut settings = HashMap::new();
+        settings.insert("/a".to_string(), vec!["*.log".to_string()]);
+        assert_eq!(patterns_for(&settings, Path::new("/a")), vec!["*.log"]);
+        assert_eq!(patterns_for(&settings, Path::new("/b")), default_patterns());
+        let excludes = Excludes::new(Path::new("/b"), &default_patterns()).unwrap();
+        assert!(excludes.is_excluded(Path::new("/b/web/node_modules/x/index.js"), false));
+        assert!(excludes.is_excluded(Path::new("/b/target"), true));
+    }
+}
@@ -0,0 +1,204 @@
+//! File type detection for picking a viewer.
+//!
+//! The first 8 KB are checked for a known signature (via `infer`), so a
+//! PNG saved as `.dat` still opens in the image viewer. Files without one
+//! are told apart as text or binary with `sniff_binary`, and the
+//! extension then narrows the MIME type: a text file named `.svg` is an
+//! image, one named `.ts` is TypeScript. The editor to open it in comes
+//! from `routing`. `is_runnable` tells programs and scripts apart from
+//! documents, for `open_externally`.
+
+use serde::Serialize;
+use std::io::Read;
+use std::path::Path;
+
+use super::routing::{self, EditorKind};
+use super::{resolve_within_root, sniff_binary, ProjectRootState, BINARY_SNIFF_BYTES};
+use crate::modules::i18n::Localized;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FileCategory {
+    Image,
+    Video,
+    Text,
+    Archive,
+    Binary,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct FileType {
+    pub mime: String,
+    pub category: FileCategory,
+    /// Whether the type came from the file's content rather than its name.
+    pub sniffed: bool,
+    /// Where the frontend should open it.
+    pub editor: EditorKind,
+}
+
+impl FileType {
+    fn new(mime: &str, category: FileCategory, sniffed: bool) -> Self {
+        let mut file_type = FileType { mime: mime.to_string(), category, sniffed, editor: EditorKind::Text };
+        file_type.editor = routing::editor_for_type(&file_type);
+        file_type
+    }
+}
+
+/// MIME type and category for an extension, for files without a
+/// signature `infer` knows.
+fn by_extension(ext: &str) -> Option<(&'static str, FileCategory)> {
+    use FileCategory::*;
+    Some(match ext {
+        "svg" => ("image/svg+xml", Image),
+        "html" | "htm" => ("text/html", Text),
+        "css" => ("text/css", Text),
+        "js" | "mjs" | "cjs" | "jsx" => ("text/javascript", Text),
+        "ts" | "mts" | "cts" | "tsx" => ("text/typescript", Text),
+        "json" | "map" => ("application/json", Text),
+        "xml" => ("application/xml", Text),
+        "md" | "markdown" => ("text/markdown", Text),
+        "csv" => ("text/csv", Text),
+        "yaml" | "yml" => ("application/yaml", Text),
+        "toml" => ("application/toml", Text),
+        "rs" => ("text/x-rust", Text),
+        "py" => ("text/x-python", Text),
+        "sh" | "bash" | "zsh" => ("application/x-sh", Text),
+        "tar" => ("application/x-tar", Archive),
+        "wasm" => ("application/wasm", Binary),
+        _ => return None,
+    })
+}
+
+fn category_of(kind: infer::MatcherType) -> FileCategory {
+    match kind {
+        infer::MatcherType::Image => FileCategory::Image,
+        infer::MatcherType::Video => FileCategory::Video,
+        infer::MatcherType::Text => FileCategory::Text,
+        infer::MatcherType::Archive => FileCategory::Archive,
+        _ => FileCategory::Binary,
+    }
+}
+
+/// Extensions the desktop runs rather than opens in a viewer.
+const RUNNABLE_EXTENSIONS: &[&str] = &[
+    "exe", "com", "bat", "cmd", "msi", "scr", "pif", "cpl", "ps1", "psm1", "vbs", "vbe", "js", "jse", "wsf",
+    "wsh", "hta", "lnk", "reg", "jar", "sh", "bash", "zsh", "command", "app", "scpt", "desktop", "appimage",
+    "run",
+];
+
+/// The first `BINARY_SNIFF_BYTES` of `file`, and whether there is more.
+pub(crate) fn read_sample(file: &Path) -> Result<(Vec<u8>, bool), String> {
+    let mut f = std::fs::File::open(file).map_err(|e| format!("Failed to read file: {}", e.kind()))?;
+    let size = f.metadata().map_err(|e| format!("Failed to read file: {}", e.kind()))?.len();
+    let mut sample = Vec::with_capacity(BINARY_SNIFF_BYTES);
+    f.by_ref()
+        .take(BINARY_SNIFF_BYTES as u64)
+        .read_to_end(&mut sample)
+        .map_err(|e| format!("Failed to read file: {}", e.kind()))?;
+    let truncated = size > sample.len() as u64;
+    Ok((sample, truncated))
+}
+
+/// Whether handing `path` to the system would run it rather than open
+/// it: an executable by signature, a script by shebang or extension, or
+/// on Unix anything with an execute bit set.
+pub(crate) fn is_runnable(path: &Path, sample: &[u8]) -> bool {
+    let ext = path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase);
+    if infer::is_app(sample) || sample.starts_with(b"#!") || ext.is_some_and(|e| RUNNABLE_EXTENSIONS.contains(&e.as_str())) {
+        return true;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if std::fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Detect the type of a file named `path` from its first bytes.
+/// `truncated` says there is more to the file than `sample`.
+pub(crate) fn detect(path: &Path, sample: &[u8], truncated: bool) -> FileType {
+    let ext = path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase);
+    let named = ext.as_deref().and_then(by_extension);
+    if let Some(kind) = infer::get(sample) {
+        let category = category_of(kind.matcher_type());
+        // Text signatures (`<?xml`, `#!`) are generic; the name says more.
+        if category != FileCategory::Text || named.is_none() {
+            return FileType::new(kind.mime_type(), category, true);
+        }
+    }
+    let text = sniff_binary(sample, truncated).is_none();
+    match named {
+        Some((mime, category)) if text || category != FileCategory::Text => FileType::new(mime, category, false),
+        _ if text => FileType::new("text/plain", FileCategory::Text, true),
+        _ => FileType::new("application/octet-stream", FileCategory::Binary, true),
+    }
+}
+
+/// MIME type and category of `path`, and the editor the routing rules
+/// pick for it, so the frontend knows which viewer to open and what a drop
+/// does. Like every fs command it only looks inside the open project.
+#[tauri::command]
+pub fn detect_file_type(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: String,
+) -> Result<FileType, String> {
+    let root = state
+        .blocking_read()
+        .get(window.label())
+        .and_then(|o| o.clone())
+        .ok_or_else(|| Localized::new("no-project-open", &[]))?;
+    let file = resolve_within_root(&path, &root)?;
+    let (sample, truncated) = read_sample(&file)?;
+    let mut file_type = detect(&file, &sample, truncated);
+    file_type.editor = routing::editor_for(&root, &file, &file_type);
+    Ok(file_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind(name: &str, sample: &[u8]) -> (String, FileCategory, bool) {
+        let t = detect(Path::new(name), sample, false);
+        (t.mime, t.category, t.sniffed)
+    }
+
+    #[test]
+    fn detects_by_content_then_name() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+        assert_eq!(kind("logo.dat", png), ("image/png".into(), FileCategory::Image, true));
+        assert_eq!(kind("bundle.zip", b"PK\x03\x04\x14\0\0\0"), ("application/zip".into(), FileCategory::Archive, true));
+
+        let svg = b"<?xml version=\"1.0\"?><svg xmlns=\"http://www.w3.org/2000/svg\"/>";
+        assert_eq!(kind("icon.svg", svg), ("image/svg+xml".into(), FileCategory::Image, false));
+        assert_eq!(kind("feed.rss", svg).1, FileCategory::Text);
+
+        assert_eq!(kind("main.ts", b"export const x = 1;\n"), ("text/typescript".into(), FileCategory::Text, false));
+        assert_eq!(kind("NOTES", b"plain words\n"), ("text/plain".into(), FileCategory::Text, true));
+        assert_eq!(kind("main.ts", b"\x01\x02\0\0garbage"), ("application/octet-stream".into(), FileCategory::Binary, true));
+        assert_eq!(kind("empty.bin", b""), ("text/plain".into(), FileCategory::Text, true));
+    }
+
+    #[test]
+    fn tells_programs_and_scripts_from_documents() {
+        let dir = tempfile::tempdir().unwrap();
+        let doc = dir.path().join("report.pdf");
+        std::fs::write(&doc, b"%PDF-1.7").unwrap();
+        assert!(!is_runnable(&doc, b"%PDF-1.7"));
+        assert!(is_runnable(&dir.path().join("setup.EXE"), b""));
+        let elf = [b"\x7fELF\x02\x01\x01".as_slice(), &[0; 64]].concat();
+        assert!(is_runnable(&dir.path().join("tool"), &elf));
+        assert!(is_runnable(&dir.path().join("deploy"), b"#!/bin/sh\nrm -rf build\n"));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&doc, std::fs::Permissions::from_mode(0o755)).unwrap();
+            assert!(is_runnable(&doc, b"%PDF-1.7"));
+        }
+    }
+}
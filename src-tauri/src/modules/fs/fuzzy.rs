@@ -0,0 +1,240 @@
+//! Fuzzy file finder for the quick-open palette.
+//!
+//! Paths come from the window's file index (see `index`), so typing in the
+//! palette only re-scores paths instead of walking the tree again. The
+//! palette passes `refresh` when it opens, which starts a background
+//! rescan in case the watcher missed something.
+//!
+//! Each whitespace-separated term of the query must appear in the path as
+//! a case-insensitive subsequence. Terms are aligned with a small
+//! Smith-Waterman pass that rewards matches at segment starts (after `/`,
+//! `_`, `-`, `.` or a camelCase hump), runs of consecutive characters and
+//! matches in the file name, and charges for gaps.
+
+use serde::Serialize;
+
+use super::{index::FileIndexState, project_root_async, ProjectRootState};
+
+const DEFAULT_LIMIT: usize = 50;
+/// Paths longer than this are only matched, not aligned for the best score.
+const MAX_SCORED_CHARS: usize = 512;
+
+const SCORE_MATCH: i32 = 16;
+const BONUS_SEGMENT: i32 = 10;
+const BONUS_WORD: i32 = 8;
+const BONUS_CAMEL: i32 = 7;
+const BONUS_CONSECUTIVE: i32 = 6;
+const BONUS_FILE_NAME: i32 = 2;
+const PENALTY_GAP_START: i32 = 3;
+const PENALTY_GAP_EXTEND: i32 = 1;
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct FuzzyMatch {
+    /// Path relative to the project root.
+    pub path: String,
+    pub score: i32,
+    /// Matched character positions in `path`, ascending, for highlighting.
+    pub indices: Vec<usize>,
+}
+
+fn bonus_at(chars: &[char], j: usize, file_name_start: usize) -> i32 {
+    let boundary = match j.checked_sub(1).map(|p| chars[p]) {
+        None | Some('/') | Some('\\') => BONUS_SEGMENT,
+        Some('_' | '-' | '.' | ' ') => BONUS_WORD,
+        Some(prev) if prev.is_lowercase() && chars[j].is_uppercase() => BONUS_CAMEL,
+        _ => 0,
+    };
+    boundary + if j >= file_name_start { BONUS_FILE_NAME } else { 0 }
+}
+
+fn fold(c: char) -> char {
+    if c.is_ascii() {
+        c.to_ascii_lowercase()
+    } else {
+        c.to_lowercase().next().unwrap_or(c)
+    }
+}
+
+fn is_subsequence(term: &[char], path: &str) -> bool {
+    let mut rest = path.chars().map(fold);
+    term.iter().all(|c| rest.any(|l| l == *c))
+}
+
+/// Buffers reused across paths so scoring a large project doesn't
+/// allocate per file.
+#[derive(Default)]
+struct Scorer {
+    chars: Vec<char>,
+    lower: Vec<char>,
+    /// `score[i * n + j]`: best score with term[i] matched at j.
+    score: Vec<i32>,
+    /// `from[i * n + j]`: where term[i - 1] was matched on that path.
+    from: Vec<usize>,
+}
+
+impl Scorer {
+    /// Best alignment of `term` (lowercase) in the loaded path, as a score
+    /// and the matched positions.
+    fn align(&mut self, term: &[char], file_name_start: usize) -> Option<(i32, Vec<usize>)> {
+        let (m, n) = (term.len(), self.chars.len());
+        if m == 0 || m > n {
+            return None;
+        }
+        let (chars, lower) = (&self.chars, &self.lower);
+        let (score, from) = (&mut self.score, &mut self.from);
+        score.clear();
+        score.resize(m * n, i32::MIN);
+        from.clear();
+        from.resize(m * n, 0);
+        let at = |i: usize, j: usize| i * n + j;
+        for i in 0..m {
+            // Best (score, position) for term[i - 1] strictly before j - 1,
+            // already charged for the gap up to j.
+            let mut carry: Option<(i32, usize)> = None;
+            for j in i..n {
+                if i > 0 && j >= 2 && score[at(i - 1, j - 2)] != i32::MIN {
+                    let opened = score[at(i - 1, j - 2)] - PENALTY_GAP_START;
+                    carry = match carry {
+                        Some((s, k)) if s - PENALTY_GAP_EXTEND >= opened => Some((s - PENALTY_GAP_EXTEND, k)),
+                        _ => Some((opened, j - 2)),
+                    };
+                } else if let Some((s, k)) = carry {
+                    carry = Some((s - PENALTY_GAP_EXTEND, k));
+                }
+                if lower[j] != term[i] {
+                    continue;
+                }
+                let here = SCORE_MATCH + bonus_at(chars, j, file_name_start);
+                if i == 0 {
+                    score[at(i, j)] = here;
+                    continue;
+                }
+                let adjacent = (j >= 1 && score[at(i - 1, j - 1)] != i32::MIN)
+                    .then(|| score[at(i - 1, j - 1)] + here + BONUS_CONSECUTIVE);
+                let gapped = carry.map(|(s, k)| (s + here, k));
+                let best = match (adjacent, gapped) {
+                    (Some(a), Some((g, _))) if a >= g => Some((a, j - 1)),
+                    (Some(a), None) => Some((a, j - 1)),
+                    (_, gapped) => gapped,
+                };
+                if let Some((s, k)) = best {
+                    (score[at(i, j)], from[at(i, j)]) = (s, k);
+                }
+            }
+        }
+        let (best, mut j) = (0..n).filter(|&j| score[at(m - 1, j)] != i32::MIN).map(|j| (score[at(m - 1, j)], j)).max()?;
+        let mut indices = vec![0; m];
+        for i in (0..m).rev() {
+            indices[i] = j;
+            j = from[at(i, j)];
+        }
+        Some((best, indices))
+    }
+
+    /// Score `path` against the query `terms` (each lowercase).
+    fn score(&mut self, terms: &[Vec<char>], path: &str) -> Option<(i32, Vec<usize>)> {
+        if !terms.iter().all(|t| is_subsequence(t, path)) {
+            return None;
+        }
+        self.chars.clear();
+        self.chars.extend(path.chars());
+        if self.chars.len() > MAX_SCORED_CHARS {
+            return Some((0, Vec::new()));
+        }
+        self.lower.clear();
+        self.lower.extend(self.chars.iter().map(|&c| fold(c)));
+        let file_name_start = self.chars.iter().rposition(|&c| c == '/').map_or(0, |p| p + 1);
+        let mut total = 0;
+        let mut indices = Vec::new();
+        for term in terms {
+            let (score, positions) = self.align(term, file_name_start)?;
+            total += score;
+            indices.extend(positions);
+        }
+        indices.sort_unstable();
+        indices.dedup();
+        Some((total, indices))
+    }
+}
+
+/// The `limit` best matches for `query` among `files`, best first. Ties go
+/// to the shorter path. An empty query returns the first `limit` files.
+pub(crate) fn rank(files: &[String], query: &str, limit: usize) -> Vec<FuzzyMatch> {
+    let terms: Vec<Vec<char>> = query.split_whitespace().map(|t| t.chars().map(fold).collect()).collect();
+    if terms.is_empty() {
+        return files
+            .iter()
+            .take(limit)
+            .map(|f| FuzzyMatch { path: f.clone(), score: 0, indices: Vec::new() })
+            .collect();
+    }
+    let mut scorer = Scorer::default();
+    let mut hits: Vec<(i32, &String, Vec<usize>)> = files
+        .iter()
+        .filter_map(|f| scorer.score(&terms, f).map(|(score, indices)| (score, f, indices)))
+        .collect();
+    let order = |a: &(i32, &String, Vec<usize>), b: &(i32, &String, Vec<usize>)| {
+        b.0.cmp(&a.0).then(a.1.len().cmp(&b.1.len())).then(a.1.cmp(b.1))
+    };
+    if hits.len() > limit && limit > 0 {
+        hits.select_nth_unstable_by(limit - 1, order);
+    }
+    hits.truncate(limit);
+    hits.sort_by(order);
+    hits.into_iter().map(|(score, path, indices)| FuzzyMatch { path: path.clone(), score, indices }).collect()
+}
+
+/// Files in the project matching `query`, best first, at most `limit`
+/// (default 50). Pass `refresh` to also rescan the project in the
+/// background.
+#[tauri::command]
+pub async fn fuzzy_find_files(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    indexes: tauri::State<'_, FileIndexState>,
+    query: String,
+    limit: Option<usize>,
+    refresh: Option<bool>,
+) -> Result<Vec<FuzzyMatch>, String> {
+    let root = project_root_async(window.label(), &state).await?;
+    let index = tokio::task::block_in_place(|| indexes.get(window.label(), &root))?;
+    if refresh.unwrap_or(false) {
+        index.rescan();
+    }
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+    tokio::task::spawn_blocking(move || rank(&index.paths(), &query, limit))
+        .await
+        .map_err(|e| format!("File search failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn prefers_file_names_and_word_starts() {
+        let files = paths(&["src/domain/main.rs", "docs/maintenance.md", "src/main.rs", "src/modules/fs/mod.rs"]);
+        let top: Vec<_> = rank(&files, "main", 10).into_iter().map(|m| m.path).collect();
+        assert_eq!(top, ["src/main.rs", "src/domain/main.rs", "docs/maintenance.md"]);
+
+        let best = &rank(&files, "fsmod", 10)[0];
+        assert_eq!(best.path, "src/modules/fs/mod.rs");
+        assert_eq!(best.indices, [12, 13, 15, 16, 17]);
+
+        let camel = paths(&["src/FileTree.svelte", "src/filters.ts"]);
+        assert_eq!(rank(&camel, "ft", 10)[0].path, "src/FileTree.svelte");
+    }
+
+    #[test]
+    fn every_term_must_match() {
+        let files = paths(&["src/git/mod.rs", "src/git/parse.rs", "tests/git.rs"]);
+        let hits: Vec<_> = rank(&files, "git parse", 10).into_iter().map(|m| m.path).collect();
+        assert_eq!(hits, ["src/git/parse.rs"]);
+        assert!(rank(&files, "zzz", 10).is_empty());
+        assert_eq!(rank(&files, "  ", 2).len(), 2);
+    }
+}
@@ -0,0 +1,273 @@
+//! Heuristics for generated and minified files.
+//!
+//! Lockfiles, bundles, source maps and files carrying a "generated"
+//! marker are rarely what a user is looking for, and they blow up search
+//! results and AI prompts. Search, review and indexing skip them by
+//! default; the file tree marks them so the UI can dim them.
+//!
+//! Projects can also mark whole directories as generated (`dist/`,
+//! `build/`, codegen output) with gitignore-style patterns kept in
+//! `~/.leo-ide/generated-dirs.json`. Everything under them is treated
+//! like a generated file, and saves there come back with a warning.
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use serde::Serialize;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use super::exclude::{load_pattern_lists, save_pattern_lists};
+use super::{validate_path, ProjectRootState};
+
+const LOCKFILES: &[&str] = &[
+    "package-lock.json", "npm-shrinkwrap.json", "yarn.lock", "pnpm-lock.yaml", "bun.lockb",
+    "Cargo.lock", "poetry.lock", "Pipfile.lock", "uv.lock", "composer.lock", "Gemfile.lock",
+    "go.sum", "flake.lock", "pubspec.lock", "mix.lock",
+];
+const GENERATED_SUFFIXES: &[&str] = &[".pb.go", "_pb2.py", "_pb2_grpc.py", ".pb.rs", ".g.dart", ".freezed.dart"];
+const MARKERS: &[&str] = &["@generated", "do not edit", "code generated", "auto-generated", "autogenerated"];
+
+/// Bytes sampled from each end of a file for content checks.
+const SAMPLE_BYTES: u64 = 64 * 1024;
+const MINIFIED_MAX_LINE: usize = 1000;
+const MINIFIED_AVG_LINE: usize = 200;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GeneratedReason {
+    Lockfile,
+    Minified,
+    SourceMap,
+    /// Name follows a code generator convention, e.g. `*_pb2.py`.
+    GeneratedName,
+    /// Header says so: `@generated`, `DO NOT EDIT`, …
+    Marker,
+    /// Inside a directory the project marks as generated.
+    Directory,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct GeneratedInfo {
+    pub generated: bool,
+    pub reason: Option<GeneratedReason>,
+}
+
+/// Cheap check on the file name alone.
+pub fn generated_by_name(path: &Path) -> Option<GeneratedReason> {
+    let name = path.file_name()?.to_str()?;
+    if LOCKFILES.contains(&name) {
+        return Some(GeneratedReason::Lockfile);
+    }
+    let lower = name.to_lowercase();
+    if lower.ends_with(".map") {
+        return Some(GeneratedReason::SourceMap);
+    }
+    if lower.ends_with(".min.js") || lower.ends_with(".min.css") || lower.ends_with(".min.mjs") {
+        return Some(GeneratedReason::Minified);
+    }
+    GENERATED_SUFFIXES.iter().any(|s| lower.ends_with(s)).then_some(GeneratedReason::GeneratedName)
+}
+
+/// Full check using the file name and (a sample of) its content.
+pub fn is_generated(path: &Path, content: &str) -> Option<GeneratedReason> {
+    if let Some(reason) = generated_by_name(path) {
+        return Some(reason);
+    }
+
+    let header: String = content.lines().take(5).collect::<Vec<_>>().join("\n").to_lowercase();
+    if MARKERS.iter().any(|m| header.contains(m)) {
+        return Some(GeneratedReason::Marker);
+    }
+
+    let tail = &content[floor_char_boundary(content, content.len().saturating_sub(512))..];
+    if tail.contains("sourceMappingURL=") {
+        return Some(GeneratedReason::Minified);
+    }
+
+    let (mut lines, mut longest) = (0usize, 0usize);
+    for line in content.lines() {
+        lines += 1;
+        longest = longest.max(line.len());
+    }
+    let minified = lines > 0 && longest > MINIFIED_MAX_LINE && content.len() / lines > MINIFIED_AVG_LINE;
+    minified.then_some(GeneratedReason::Minified)
+}
+
+/// `is_generated` for a file on disk, reading only its first and last
+/// `SAMPLE_BYTES`.
+pub fn check_file(path: &Path) -> Option<GeneratedReason> {
+    if let Some(reason) = generated_by_name(path) {
+        return Some(reason);
+    }
+    let mut file = std::fs::File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    let mut head = Vec::new();
+    (&mut file).take(SAMPLE_BYTES).read_to_end(&mut head).ok()?;
+    if len > SAMPLE_BYTES * 2 {
+        // Only the sourceMappingURL check looks at the end of the file.
+        let mut tail = Vec::new();
+        file.seek(SeekFrom::End(-512)).ok()?;
+        file.read_to_end(&mut tail).ok()?;
+        head.extend_from_slice(b"\n");
+        head.extend_from_slice(&tail);
+    } else {
+        file.read_to_end(&mut head).ok()?;
+    }
+    is_generated(path, &String::from_utf8_lossy(&head))
+}
+
+fn floor_char_boundary(s: &str, mut i: usize) -> usize {
+    while !s.is_char_boundary(i) {
+        i -= 1;
+    }
+    i
+}
+
+// ── Generated directories ──
+
+const SETTINGS_FILE: &str = "generated-dirs.json";
+
+/// A project's generated-directory patterns, compiled.
+#[derive(Clone)]
+pub struct GeneratedDirs {
+    root: PathBuf,
+    matcher: Gitignore,
+}
+
+impl GeneratedDirs {
+    pub fn new(root: &Path, patterns: &[String]) -> Result<Self, String> {
+        let mut builder = GitignoreBuilder::new(root);
+        for pattern in patterns.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+            builder
+                .add_line(None, pattern)
+                .map_err(|e| format!("Invalid generated directory pattern '{}': {}", pattern, e))?;
+        }
+        let matcher = builder.build().map_err(|e| format!("Invalid generated directory patterns: {}", e))?;
+        Ok(Self { root: root.to_path_buf(), matcher })
+    }
+
+    /// The project's saved patterns; none by default.
+    pub fn for_project(root: &Path) -> Self {
+        Self::new(root, &project_patterns(root)).or_else(|_| Self::new(root, &[])).expect("empty patterns are valid")
+    }
+
+    /// The pattern that marks `path` (absolute) or one of its parent
+    /// directories as generated.
+    pub fn matching(&self, path: &Path, is_dir: bool) -> Option<String> {
+        let rel = path.strip_prefix(&self.root).ok().filter(|rel| !rel.as_os_str().is_empty())?;
+        match self.matcher.matched_path_or_any_parents(rel, is_dir) {
+            ignore::Match::Ignore(glob) => Some(glob.original().to_string()),
+            _ => None,
+        }
+    }
+
+    /// Whether the file at `path` sits in a generated directory.
+    pub fn contains(&self, path: &Path) -> bool {
+        self.matching(path, false).is_some()
+    }
+}
+
+fn project_patterns(root: &Path) -> Vec<String> {
+    load_pattern_lists(SETTINGS_FILE).remove(root.to_string_lossy().as_ref()).unwrap_or_default()
+}
+
+/// Why the file at `path` counts as generated in the project at `root`:
+/// its directory first, then `check_file`.
+pub fn reason_in_project(root: &Path, path: &Path) -> Option<GeneratedReason> {
+    if GeneratedDirs::for_project(root).contains(path) {
+        Some(GeneratedReason::Directory)
+    } else {
+        check_file(path)
+    }
+}
+
+fn project_root(window: &tauri::WebviewWindow, state: &tauri::State<'_, ProjectRootState>) -> Result<PathBuf, String> {
+    state.blocking_read().get(window.label()).and_then(|o| o.clone()).ok_or_else(|| "No project is open".to_string())
+}
+
+#[tauri::command]
+pub fn is_generated_file(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: String,
+) -> Result<GeneratedInfo, String> {
+    let root = project_root(&window, &state)?;
+    let path = validate_path(&path, window.label(), &state)?;
+    let reason = reason_in_project(&root, &path);
+    Ok(GeneratedInfo { generated: reason.is_some(), reason })
+}
+
+#[tauri::command]
+pub fn get_generated_dirs(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+) -> Result<Vec<String>, String> {
+    Ok(project_patterns(&project_root(&window, &state)?))
+}
+
+/// Replace the project's generated-directory patterns; `None` clears them.
+#[tauri::command]
+pub fn set_generated_dirs(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    patterns: Option<Vec<String>>,
+) -> Result<Vec<String>, String> {
+    let root = project_root(&window, &state)?;
+    let patterns: Vec<String> =
+        patterns.unwrap_or_default().iter().map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect();
+    GeneratedDirs::new(&root, &patterns)?;
+    let mut lists = load_pattern_lists(SETTINGS_FILE);
+    let key = root.to_string_lossy().into_owned();
+    if patterns.is_empty() {
+        lists.remove(&key);
+    } else {
+        lists.insert(key, patterns.clone());
+    }
+    save_pattern_lists(SETTINGS_FILE, &lists).map_err(|e| format!("Failed to save generated directories: {}", e))?;
+    Ok(patterns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_identify_lockfiles_bundles_and_codegen() {
+        assert_eq!(generated_by_name(Path::new("a/Cargo.lock")), Some(GeneratedReason::Lockfile));
+        assert_eq!(generated_by_name(Path::new("dist/app.min.js")), Some(GeneratedReason::Minified));
+        assert_eq!(generated_by_name(Path::new("dist/app.js.map")), Some(GeneratedReason::SourceMap));
+        assert_eq!(generated_by_name(Path::new("api/user_pb2.py")), Some(GeneratedReason::GeneratedName));
+        assert_eq!(generated_by_name(Path::new("src/main.rs")), None);
+    }
+
+    #[test]
+    fn content_markers_and_minified_code() {
+        let p = Path::new("out.js");
+        assert_eq!(is_generated(p, "// Code generated by protoc. DO NOT EDIT.\npackage x\n"), Some(GeneratedReason::Marker));
+        assert_eq!(is_generated(p, "var a=1;\n//# sourceMappingURL=out.js.map\n"), Some(GeneratedReason::Minified));
+        let bundle = format!("{}\n", "a=1;".repeat(2000));
+        assert_eq!(is_generated(p, &bundle), Some(GeneratedReason::Minified));
+        assert_eq!(is_generated(p, "function add(a, b) {\n  return a + b;\n}\n"), None);
+    }
+
+    #[test]
+    fn check_file_samples_both_ends() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bundle.js");
+        let body = "x = 1;\n".repeat(40_000);
+        std::fs::write(&path, format!("{}//# sourceMappingURL=bundle.js.map\n", body)).unwrap();
+        assert_eq!(check_file(&path), Some(GeneratedReason::Minified));
+    }
+
+    #[test]
+    fn directory_patterns_report_the_matching_pattern() {
+        let root = Path::new("/proj");
+        let patterns = ["dist/", "src/**/gen"].map(String::from);
+        let dirs = GeneratedDirs::new(root, &patterns).unwrap();
+        assert_eq!(dirs.matching(Path::new("/proj/dist/app.js"), false).as_deref(), Some("dist/"));
+        assert_eq!(dirs.matching(Path::new("/proj/src/api/gen"), true).as_deref(), Some("src/**/gen"));
+        assert!(dirs.contains(Path::new("/proj/src/api/gen/client.ts")));
+        assert!(!dirs.contains(Path::new("/proj/src/api/client.ts")));
+        assert!(!dirs.contains(Path::new("/proj/dist")));
+        assert!(!dirs.contains(Path::new("/elsewhere/dist/app.js")));
+    }
+}
@@ -0,0 +1,125 @@
+//! Hidden-file filtering for directory listings.
+//!
+//! An entry is hidden when its name starts with a dot on Unix, or when it
+//! carries the hidden attribute on Windows. `read_dir_tree` and
+//! `list_all_files` drop hidden entries when the caller turns
+//! `show_hidden` off, and always drop names on the skip list (exact names
+//! or `*.ext` globs, the same syntax as the explorer's visibility
+//! patterns). `.git` is skipped regardless.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Names skipped even when the caller passes its own skip list.
+const ALWAYS_SKIP: &[&str] = &[".git"];
+
+#[derive(Clone, Debug)]
+pub struct HiddenFilter {
+    show_hidden: bool,
+    skip: Vec<String>,
+}
+
+impl Default for HiddenFilter {
+    /// Everything but `.git` is shown.
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
+impl HiddenFilter {
+    /// `show_hidden` defaults to true, so callers that don't pass it keep
+    /// seeing dotfiles.
+    pub fn new(show_hidden: Option<bool>, skip: Option<Vec<String>>) -> Self {
+        let skip = skip
+            .unwrap_or_default()
+            .into_iter()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .chain(ALWAYS_SKIP.iter().map(|s| s.to_string()))
+            .collect();
+        Self { show_hidden: show_hidden.unwrap_or(true), skip }
+    }
+
+    /// Whether the entry `name` at `path` should be left out of a listing.
+    pub fn skips(&self, path: &Path, name: &str) -> bool {
+        self.skip.iter().any(|pattern| skip_matches(pattern, name)) || (!self.show_hidden && is_hidden(path, name))
+    }
+
+    /// Drop paths (relative to `base`) that are skipped themselves or sit
+    /// under a skipped directory.
+    pub fn retain_visible(&self, base: &Path, paths: &mut Vec<String>) {
+        // Each directory is checked once; on Windows that's a stat call.
+        let mut dirs: HashMap<PathBuf, bool> = HashMap::new();
+        paths.retain(|rel| {
+            let rel = Path::new(rel);
+            let mut abs = base.to_path_buf();
+            let mut components = rel.iter().peekable();
+            while let Some(name) = components.next() {
+                abs.push(name);
+                let name = name.to_string_lossy();
+                let skipped = if components.peek().is_some() {
+                    *dirs.entry(abs.clone()).or_insert_with(|| self.skips(&abs, &name))
+                } else {
+                    self.skips(&abs, &name)
+                };
+                if skipped {
+                    return false;
+                }
+            }
+            true
+        });
+    }
+}
+
+fn skip_matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_prefix('*') {
+        Some(suffix) if suffix.starts_with('.') => name.ends_with(suffix),
+        _ => name == pattern,
+    }
+}
+
+/// Dotfiles on Unix; entries with `FILE_ATTRIBUTE_HIDDEN` on Windows.
+#[cfg(windows)]
+pub fn is_hidden(path: &Path, _name: &str) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+    std::fs::symlink_metadata(path).is_ok_and(|m| m.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0)
+}
+
+/// Dotfiles on Unix; entries with `FILE_ATTRIBUTE_HIDDEN` on Windows.
+#[cfg(not(windows))]
+pub fn is_hidden(_path: &Path, name: &str) -> bool {
+    name.starts_with('.')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_hidden_and_skipped_paths() {
+        let base = Path::new("/proj");
+        let all = || -> Vec<String> {
+            ["src/main.rs", ".env", ".github/workflows/ci.yml", "debug.log", "node_modules/x/index.js", ".git/HEAD"]
+                .iter()
+                .map(|p| p.to_string())
+                .collect()
+        };
+
+        let mut paths = all();
+        HiddenFilter::default().retain_visible(base, &mut paths);
+        assert_eq!(paths, ["src/main.rs", ".env", ".github/workflows/ci.yml", "debug.log", "node_modules/x/index.js"]);
+
+        let mut paths = all();
+        let skip = vec!["node_modules".to_string(), "*.log".to_string(), " ".to_string()];
+        HiddenFilter::new(Some(true), Some(skip.clone())).retain_visible(base, &mut paths);
+        assert_eq!(paths, ["src/main.rs", ".env", ".github/workflows/ci.yml"]);
+
+        #[cfg(not(windows))]
+        {
+            let mut paths = all();
+            HiddenFilter::new(Some(false), Some(skip)).retain_visible(base, &mut paths);
+            assert_eq!(paths, ["src/main.rs"]);
+        }
+    }
+}
@@ -0,0 +1,216 @@
+//! Local history: earlier versions of saved files, independent of git.
+//!
+//! Every write the IDE makes to a project file — saves through
+//! `write_file_content` or `write_files_batch`, workspace edits (which
+//! also carry the AI agent's approved changes), project-wide replace,
+//! encoding fixes, version bumps and notebook saves — first keeps the
+//! content it replaces in `~/.leo-ide/history/<project>/`. A file a
+//! workspace edit renames or deletes is kept under its old path. Every
+//! file gets a folder there holding a manifest of its versions and the
+//! versions themselves, stored by content hash so flipping back and forth
+//! between two states keeps each only once. A file keeps its newest
+//! `MAX_VERSIONS` versions from the last 30 days; larger files than
+//! `MAX_SNAPSHOT_BYTES` aren't kept at all.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{content_hash, save_text, validate_path, write_atomic, FileVersion, ProjectRootState};
+
+const MAX_VERSIONS: usize = 50;
+const MAX_AGE_MS: u64 = 30 * 24 * 60 * 60 * 1000;
+const MAX_SNAPSHOT_BYTES: u64 = 5 * 1024 * 1024;
+const MANIFEST: &str = "versions.json";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct HistoryEntry {
+    /// `<timestamp>-<hash prefix>`.
+    pub id: String,
+    /// When this content was replaced, in milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+    pub size: u64,
+    /// SHA-256 of the content, as in `FileVersion::hash`.
+    pub hash: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct Manifest {
+    /// Path relative to the project root, for finding your way around
+    /// the store by hand.
+    path: String,
+    /// Oldest first.
+    versions: Vec<HistoryEntry>,
+}
+
+fn hex_digest(s: &str, len: usize) -> String {
+    Sha256::digest(s.as_bytes()).iter().take(len / 2).map(|b| format!("{:02x}", b)).collect()
+}
+
+fn store_dir(root: &Path) -> PathBuf {
+    dirs::home_dir().unwrap_or_default().join(".leo-ide").join("history").join(hex_digest(&root.to_string_lossy(), 16))
+}
+
+fn file_dir(store: &Path, rel: &str) -> PathBuf {
+    store.join(hex_digest(rel, 32))
+}
+
+fn read_manifest(dir: &Path) -> Manifest {
+    fs::read_to_string(dir.join(MANIFEST)).ok().and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default()
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+fn rel_path(root: &Path, file: &Path) -> String {
+    file.strip_prefix(root).unwrap_or(file).to_string_lossy().into_owned()
+}
+
+/// Keep `old`, the content `rel` had until `now`, as a version.
+fn snapshot(store: &Path, rel: &str, old: &[u8], now: u64) -> Result<(), String> {
+    let dir = file_dir(store, rel);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to write local history: {}", e.kind()))?;
+    let hash = content_hash(old);
+    let mut manifest = read_manifest(&dir);
+    if manifest.versions.last().is_some_and(|v| v.hash == hash) {
+        return Ok(());
+    }
+    if !dir.join(&hash).exists() {
+        write_atomic(&dir.join(&hash), old)?;
+    }
+    manifest.path = rel.to_string();
+    manifest.versions.push(HistoryEntry { id: format!("{}-{}", now, &hash[..12]), timestamp_ms: now, size: old.len() as u64, hash });
+    manifest.versions.retain(|v| now.saturating_sub(v.timestamp_ms) <= MAX_AGE_MS);
+    let excess = manifest.versions.len().saturating_sub(MAX_VERSIONS);
+    manifest.versions.drain(..excess);
+
+    // Contents no remaining version refers to.
+    for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name != MANIFEST && !manifest.versions.iter().any(|v| v.hash == name) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+    let json = serde_json::to_string(&manifest).map_err(|e| e.to_string())?;
+    write_atomic(&dir.join(MANIFEST), json.as_bytes())
+}
+
+/// Called by saves before `file` is overwritten with `new`: keeps the
+/// current content unless it is unchanged, missing or too large.
+fn record(root: &Path, file: &Path, new: &[u8]) -> Result<(), String> {
+    if !fs::metadata(file).is_ok_and(|m| m.is_file() && m.len() <= MAX_SNAPSHOT_BYTES) {
+        return Ok(());
+    }
+    let old = fs::read(file).map_err(|e| format!("Failed to read file: {}", e.kind()))?;
+    if old == new {
+        return Ok(());
+    }
+    snapshot(&store_dir(root), &rel_path(root, file), &old, now_ms())
+}
+
+/// `record` for writes that go ahead regardless; a file that is about to
+/// be deleted or moved away passes an empty `new`.
+pub(crate) fn keep(root: &Path, file: &Path, new: &[u8]) {
+    if let Err(e) = record(root, file, new) {
+        log::warn!("local history not kept for {}: {}", file.display(), e);
+    }
+}
+
+fn versions(store: &Path, rel: &str) -> Vec<HistoryEntry> {
+    let mut versions = read_manifest(&file_dir(store, rel)).versions;
+    versions.reverse();
+    versions
+}
+
+fn read_version(store: &Path, rel: &str, id: &str) -> Result<String, String> {
+    let version = versions(store, rel).into_iter().find(|v| v.id == id).ok_or("That version is no longer in the local history")?;
+    let bytes = fs::read(file_dir(store, rel).join(&version.hash)).map_err(|e| format!("Failed to read local history: {}", e.kind()))?;
+    String::from_utf8(bytes).map_err(|_| "Local history version is not valid UTF-8".to_string())
+}
+
+fn project_root(window: &tauri::WebviewWindow, state: &tauri::State<'_, ProjectRootState>) -> Result<PathBuf, String> {
+    Ok(state.blocking_read().get(window.label()).and_then(|o| o.clone()).ok_or("No project is open")?)
+}
+
+// ── Commands ──
+
+/// Earlier versions of `path`, newest first.
+#[tauri::command]
+pub fn list_file_history(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: String,
+) -> Result<Vec<HistoryEntry>, String> {
+    let file = validate_path(&path, window.label(), &state)?;
+    let root = project_root(&window, &state)?;
+    Ok(versions(&store_dir(&root), &rel_path(&root, &file)))
+}
+
+/// The content of version `id` of `path`, e.g. to diff against the buffer.
+#[tauri::command]
+pub fn read_file_version(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: String,
+    id: String,
+) -> Result<String, String> {
+    let file = validate_path(&path, window.label(), &state)?;
+    let root = project_root(&window, &state)?;
+    read_version(&store_dir(&root), &rel_path(&root, &file), &id)
+}
+
+/// Put version `id` of `path` back on disk, recreating the file if it was
+/// deleted. This is a save like any other, so the content it replaces
+/// becomes a version of its own and the restore can be reverted.
+#[tauri::command]
+pub fn restore_file_version(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: String,
+    id: String,
+) -> Result<FileVersion, String> {
+    let file = validate_path(&path, window.label(), &state)?;
+    let root = project_root(&window, &state)?;
+    let content = read_version(&store_dir(&root), &rel_path(&root, &file), &id)?;
+    save_text(&root, &file, &content, None, None, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_capped_deduplicated_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = dir.path().join("history");
+        let day = 24 * 60 * 60 * 1000;
+
+        snapshot(&store, "src/a.rs", b"one", day).unwrap();
+        snapshot(&store, "src/a.rs", b"one", day + 1).unwrap();
+        snapshot(&store, "src/a.rs", b"two", day + 2).unwrap();
+        snapshot(&store, "src/a.rs", b"one", day + 3).unwrap();
+        let listed = versions(&store, "src/a.rs");
+        assert_eq!(listed.iter().map(|v| v.timestamp_ms).collect::<Vec<_>>(), [day + 3, day + 2, day]);
+        assert_eq!(read_version(&store, "src/a.rs", &listed[1].id).unwrap(), "two");
+        // Two distinct contents plus the manifest.
+        assert_eq!(fs::read_dir(file_dir(&store, "src/a.rs")).unwrap().count(), 3);
+        assert!(versions(&store, "src/b.rs").is_empty());
+
+        // A month later the old versions age out and their contents go.
+        snapshot(&store, "src/a.rs", b"three", 40 * day).unwrap();
+        let listed = versions(&store, "src/a.rs");
+        assert_eq!(listed.len(), 1);
+        assert!(read_version(&store, "src/a.rs", &format!("{}-{}", day, &content_hash(b"one")[..12])).is_err());
+        assert_eq!(fs::read_dir(file_dir(&store, "src/a.rs")).unwrap().count(), 2);
+
+        for i in 0..MAX_VERSIONS as u64 + 5 {
+            snapshot(&store, "src/a.rs", i.to_string().as_bytes(), 41 * day + i).unwrap();
+        }
+        let listed = versions(&store, "src/a.rs");
+        assert_eq!(listed.len(), MAX_VERSIONS);
+        assert_eq!(read_version(&store, "src/a.rs", &listed[0].id).unwrap(), (MAX_VERSIONS + 4).to_string());
+        assert_eq!(fs::read_dir(file_dir(&store, "src/a.rs")).unwrap().count(), MAX_VERSIONS + 1);
+    }
+}
@@ -0,0 +1,403 @@
+//! Persistent index of the project's files for quick-open and listing.
+//!
+//! Each window keeps an index of every file in its project (relative path,
+//! size and mtime), walked with the same exclude patterns as the file
+//! tree. A file watcher keeps it current: changed paths are re-read,
+//! deleted paths drop out along with everything under them, and new
+//! directories are walked. Once changes settle, the changed files are
+//! handed to the knowledge base's file index and semantic search, and the
+//! index is saved under `~/.leo-ide/file-index/`, so reopening a large
+//! project serves the saved list right away while a background walk picks
+//! up whatever changed while the app was closed.
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, UNIX_EPOCH};
+
+use super::exclude::{self, Excludes};
+use super::{write_atomic, MAX_COLLECT_DEPTH, MAX_COLLECT_FILES};
+use crate::modules::knowledge::{self, semantic};
+
+/// Save this long after the last change, so a checkout or build writes
+/// the index once.
+const SAVE_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct FileMeta {
+    pub size: u64,
+    /// Seconds since the Unix epoch.
+    pub mtime: u64,
+}
+
+impl FileMeta {
+    fn of(meta: &std::fs::Metadata) -> Self {
+        let mtime = meta.modified().ok().and_then(|t| t.duration_since(UNIX_EPOCH).ok()).map_or(0, |d| d.as_secs());
+        Self { size: meta.len(), mtime }
+    }
+}
+
+/// What is written to disk for one project.
+#[derive(Serialize, Deserialize)]
+struct Saved {
+    root: PathBuf,
+    patterns: Vec<String>,
+    files: BTreeMap<String, FileMeta>,
+}
+
+#[derive(Default)]
+struct Files {
+    /// Keyed by path relative to the root, with the platform's separator.
+    entries: BTreeMap<String, FileMeta>,
+    /// Sorted path list handed out to callers, rebuilt after changes.
+    paths: Option<Arc<Vec<String>>>,
+    dirty: bool,
+}
+
+/// The file index for one project root.
+pub struct ProjectIndex {
+    root: PathBuf,
+    patterns: Vec<String>,
+    excludes: Excludes,
+    files: Mutex<Files>,
+    scanning: AtomicBool,
+}
+
+fn walk(root: &Path, dir: &Path, out: &mut BTreeMap<String, FileMeta>, depth: u32, excludes: &Excludes) {
+    if depth > MAX_COLLECT_DEPTH || out.len() >= MAX_COLLECT_FILES {
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        if out.len() >= MAX_COLLECT_FILES {
+            return;
+        }
+        let Ok(ft) = entry.file_type() else { continue };
+        if entry.file_name() == ".git" || ft.is_symlink() {
+            continue;
+        }
+        let path = entry.path();
+        if excludes.is_excluded(&path, ft.is_dir()) {
+            continue;
+        }
+        if ft.is_dir() {
+            walk(root, &path, out, depth + 1, excludes);
+        } else if let (Ok(rel), Ok(meta)) = (path.strip_prefix(root), entry.metadata()) {
+            out.insert(rel.to_string_lossy().into_owned(), FileMeta::of(&meta));
+        }
+    }
+}
+
+/// Keys of `entries` inside directory `rel`.
+fn keys_under(entries: &BTreeMap<String, FileMeta>, rel: &str) -> Vec<String> {
+    let prefix = format!("{}{}", rel, std::path::MAIN_SEPARATOR);
+    entries.range(prefix.clone()..).take_while(|(k, _)| k.starts_with(&prefix)).map(|(k, _)| k.clone()).collect()
+}
+
+fn index_file(root: &Path) -> PathBuf {
+    let digest = Sha256::digest(root.to_string_lossy().as_bytes());
+    let name: String = digest.iter().take(8).map(|b| format!("{:02x}", b)).collect();
+    dirs::home_dir().unwrap_or_default().join(".leo-ide").join("file-index").join(format!("{}.json", name))
+}
+
+impl ProjectIndex {
+    fn new(root: &Path, patterns: Vec<String>) -> Self {
+        let excludes = Excludes::new(root, &patterns).unwrap_or_else(|_| Excludes::for_project(root));
+        Self {
+            root: root.to_path_buf(),
+            patterns,
+            excludes,
+            files: Mutex::new(Files::default()),
+            scanning: AtomicBool::new(false),
+        }
+    }
+
+    fn files(&self) -> std::sync::MutexGuard<'_, Files> {
+        self.files.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Walk the whole tree and replace the index with the result.
+    pub(crate) fn rebuild(&self) {
+        let mut entries = BTreeMap::new();
+        walk(&self.root, &self.root, &mut entries, 0, &self.excludes);
+        let mut files = self.files();
+        if files.entries != entries {
+            *files = Files { entries, paths: None, dirty: true };
+        }
+    }
+
+    /// Start a background `rebuild` unless one is already running.
+    pub fn rescan(self: &Arc<Self>) {
+        if self.scanning.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let index = self.clone();
+        std::thread::spawn(move || {
+            index.rebuild();
+            index.scanning.store(false, Ordering::Release);
+        });
+    }
+
+    /// Bring the entries for `paths` (absolute) in line with the disk.
+    pub(crate) fn apply(&self, paths: &[PathBuf]) {
+        let mut files = self.files();
+        for path in paths {
+            let Ok(rel) = path.strip_prefix(&self.root) else { continue };
+            if rel.as_os_str().is_empty() || rel.components().any(|c| c.as_os_str() == ".git") {
+                continue;
+            }
+            let key = rel.to_string_lossy().into_owned();
+            let changed = match std::fs::symlink_metadata(path) {
+                Ok(meta) if meta.is_file() => {
+                    let meta = FileMeta::of(&meta);
+                    let room = files.entries.len() < MAX_COLLECT_FILES || files.entries.contains_key(&key);
+                    room && !self.excludes.is_excluded(path, false) && files.entries.insert(key, meta) != Some(meta)
+                }
+                // A directory with nothing indexed under it was just created
+                // or moved in. Existing ones report their own children.
+                Ok(meta) if meta.is_dir() => {
+                    if self.excludes.is_excluded(path, true) || !keys_under(&files.entries, &key).is_empty() {
+                        continue;
+                    }
+                    let mut found = BTreeMap::new();
+                    walk(&self.root, path, &mut found, 0, &self.excludes);
+                    let changed = !found.is_empty();
+                    files.entries.extend(found);
+                    changed
+                }
+                Ok(_) => false,
+                Err(_) => {
+                    let before = files.entries.len();
+                    for gone in keys_under(&files.entries, &key).into_iter().chain([key]) {
+                        files.entries.remove(&gone);
+                    }
+                    files.entries.len() != before
+                }
+            };
+            if changed {
+                files.paths = None;
+                files.dirty = true;
+            }
+        }
+    }
+
+    /// Every indexed path, sorted.
+    pub fn paths(&self) -> Arc<Vec<String>> {
+        let mut files = self.files();
+        if files.paths.is_none() {
+            files.paths = Some(Arc::new(files.entries.keys().cloned().collect()));
+        }
+        files.paths.clone().unwrap_or_default()
+    }
+
+    /// Indexed paths inside `dir` (relative to the root; empty for the
+    /// whole project), relative to `dir`.
+    pub fn list(&self, dir: &Path) -> Vec<String> {
+        if dir.as_os_str().is_empty() {
+            return self.paths().to_vec();
+        }
+        let files = self.files();
+        let prefix = format!("{}{}", dir.to_string_lossy(), std::path::MAIN_SEPARATOR);
+        keys_under(&files.entries, &dir.to_string_lossy()).into_iter().map(|k| k[prefix.len()..].to_string()).collect()
+    }
+
+    /// Indexed files inside `dir` (relative to the root; empty for the
+    /// whole project) modified at or after `since`, newest first, at most
+    /// `limit`. Paths stay relative to the root.
+    pub fn recent(&self, dir: &Path, since: u64, limit: usize) -> Vec<(String, FileMeta)> {
+        let files = self.files();
+        let prefix = format!("{}{}", dir.to_string_lossy(), std::path::MAIN_SEPARATOR);
+        let mut found: Vec<(String, FileMeta)> = files
+            .entries
+            .iter()
+            .filter(|(k, meta)| meta.mtime >= since && (dir.as_os_str().is_empty() || k.starts_with(&prefix)))
+            .map(|(k, meta)| (k.clone(), *meta))
+            .collect();
+        found.sort_by(|a, b| b.1.mtime.cmp(&a.1.mtime).then_with(|| a.0.cmp(&b.0)));
+        found.truncate(limit);
+        found
+    }
+
+    fn load(&self, file: &Path) -> bool {
+        let saved: Option<Saved> = std::fs::read(file).ok().and_then(|b| serde_json::from_slice(&b).ok());
+        match saved {
+            Some(saved) if saved.root == self.root && saved.patterns == self.patterns => {
+                *self.files() = Files { entries: saved.files, paths: None, dirty: false };
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn save(&self, file: &Path) -> Result<(), String> {
+        let saved = {
+            let mut files = self.files();
+            if !files.dirty {
+                return Ok(());
+            }
+            files.dirty = false;
+            Saved { root: self.root.clone(), patterns: self.patterns.clone(), files: files.entries.clone() }
+        };
+        if let Some(dir) = file.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Failed to save file index: {}", e))?;
+        }
+        let json = serde_json::to_vec(&saved).map_err(|e| e.to_string())?;
+        write_atomic(file, &json)
+    }
+}
+
+/// A window's index and the watcher feeding it. Dropping this stops the
+/// watcher, and the update thread saves and exits.
+struct WindowIndex {
+    index: Arc<ProjectIndex>,
+    _watcher: RecommendedWatcher,
+}
+
+fn open(root: &Path, patterns: Vec<String>) -> Result<WindowIndex, String> {
+    let index = Arc::new(ProjectIndex::new(root, patterns));
+    let file = index_file(root);
+    let loaded = index.load(&file);
+    if !loaded {
+        index.rebuild();
+    }
+
+    let (tx, rx) = mpsc::channel::<Vec<PathBuf>>();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if let Ok(event) = event {
+            if !event.kind.is_access() {
+                let _ = tx.send(event.paths);
+            }
+        }
+    })
+    .map_err(|e| format!("Failed to watch files: {}", e))?;
+    watcher.watch(root, RecursiveMode::Recursive).map_err(|e| format!("Failed to watch files: {}", e))?;
+
+    let worker = index.clone();
+    std::thread::spawn(move || {
+        if loaded {
+            worker.rebuild();
+        }
+        let project_root = worker.root.to_string_lossy().into_owned();
+        let mut changed = BTreeSet::new();
+        loop {
+            match rx.recv_timeout(SAVE_DELAY) {
+                Ok(paths) => {
+                    worker.apply(&paths);
+                    changed.extend(paths);
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    if !changed.is_empty() {
+                        let paths: Vec<PathBuf> = std::mem::take(&mut changed).into_iter().collect();
+                        if let Err(e) = knowledge::reindex_paths(&project_root, &worker.root, &paths) {
+                            log::warn!("knowledge index not updated: {}", e);
+                        }
+                        if let Err(e) = semantic::reindex_paths(&project_root, &worker.root, &paths) {
+                            log::warn!("semantic search index not updated: {}", e);
+                        }
+                    }
+                    let _ = worker.save(&file);
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    let _ = worker.save(&file);
+                    break;
+                }
+            }
+        }
+    });
+    Ok(WindowIndex { index, _watcher: watcher })
+}
+
+/// File indexes, keyed by window label.
+#[derive(Default)]
+pub struct FileIndexState(Mutex<HashMap<String, WindowIndex>>);
+
+impl FileIndexState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The index for `root` in window `label`, opening it on first use and
+    /// whenever the window's project or exclude patterns change. Opening a
+    /// project with no saved index walks it first, so call this off the
+    /// async runtime.
+    pub fn get(&self, label: &str, root: &Path) -> Result<Arc<ProjectIndex>, String> {
+        let patterns = exclude::project_patterns(root);
+        {
+            let windows = self.0.lock().map_err(|e| e.to_string())?;
+            if let Some(current) = windows.get(label) {
+                if current.index.root == root && current.index.patterns == patterns {
+                    return Ok(current.index.clone());
+                }
+            }
+        }
+        let opened = open(root, patterns)?;
+        let index = opened.index.clone();
+        self.0.lock().map_err(|e| e.to_string())?.insert(label.to_string(), opened);
+        Ok(index)
+    }
+
+    pub fn remove_window(&self, label: &str) {
+        if let Ok(mut windows) = self.0.lock() {
+            windows.remove(label);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sep(path: &str) -> String {
+        path.replace('/', std::path::MAIN_SEPARATOR_STR)
+    }
+
+    #[test]
+    fn applies_changes_and_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("target")).unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(root.join("target/out"), "").unwrap();
+
+        let patterns = vec!["target/".to_string()];
+        let index = ProjectIndex::new(root, patterns.clone());
+        index.rebuild();
+        assert_eq!(*index.paths(), [sep("src/main.rs")]);
+
+        std::fs::create_dir_all(root.join("src/net/http")).unwrap();
+        std::fs::write(root.join("src/net/http/client.rs"), "").unwrap();
+        std::fs::write(root.join("README.md"), "# hi").unwrap();
+        std::fs::write(root.join("target/more"), "").unwrap();
+        index.apply(&[root.join("src/net"), root.join("README.md"), root.join("target/more")]);
+        assert_eq!(*index.paths(), [sep("README.md"), sep("src/main.rs"), sep("src/net/http/client.rs")]);
+        assert_eq!(index.files().entries[&sep("README.md")].size, 4);
+        assert_eq!(index.list(Path::new("src")), [sep("main.rs"), sep("net/http/client.rs")]);
+
+        index.files().entries.get_mut(&sep("src/main.rs")).unwrap().mtime = 100;
+        index.files().entries.get_mut(&sep("src/net/http/client.rs")).unwrap().mtime = 300;
+        index.files().entries.get_mut(&sep("README.md")).unwrap().mtime = 200;
+        let recent = |dir: &str, since, limit| {
+            index.recent(Path::new(dir), since, limit).into_iter().map(|(p, _)| p).collect::<Vec<_>>()
+        };
+        assert_eq!(recent("", 0, 2), [sep("src/net/http/client.rs"), sep("README.md")]);
+        assert_eq!(recent("src", 0, 10), [sep("src/net/http/client.rs"), sep("src/main.rs")]);
+        assert_eq!(recent("", 150, 10), [sep("src/net/http/client.rs"), sep("README.md")]);
+
+        std::fs::remove_dir_all(root.join("src/net")).unwrap();
+        index.apply(&[root.join("src/net")]);
+        assert_eq!(*index.paths(), [sep("README.md"), sep("src/main.rs")]);
+
+        let file = dir.path().join("saved/index.json");
+        index.save(&file).unwrap();
+        let reopened = ProjectIndex::new(root, patterns);
+        assert!(reopened.load(&file));
+        assert_eq!(reopened.paths(), index.paths());
+        assert!(!ProjectIndex::new(root, Vec::new()).load(&file));
+    }
+}
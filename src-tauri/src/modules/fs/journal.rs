@@ -0,0 +1,243 @@
+//! Undo journal for file tree operations.
+//!
+//! Rename, move, paste, import, duplicate and workspace edits record how
+//! to reverse themselves, per window. `undo_last_fs_operation` replays the
+//! inverse of the newest entry — moving entries back, trashing the copies
+//! that were created, or writing back the contents a workspace edit
+//! replaced or deleted. Each step remembers the state of the path it
+//! touched; if that path has changed since (edited, moved away,
+//! replaced), the entry is dropped instead of undoing on top of someone
+//! else's work.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use super::mtime_ms;
+
+const MAX_ENTRIES: usize = 50;
+
+/// Identity of a path right after an operation touched it. For
+/// directories only the directory's own mtime is compared, so edits deep
+/// inside a copied tree don't invalidate the entry.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Stamp {
+    is_dir: bool,
+    len: u64,
+    mtime_ms: u64,
+}
+
+impl Stamp {
+    fn of(path: &Path) -> Option<Self> {
+        let meta = std::fs::symlink_metadata(path).ok()?;
+        Some(Self { is_dir: meta.is_dir(), len: if meta.is_dir() { 0 } else { meta.len() }, mtime_ms: mtime_ms(&meta) })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(super) enum Step {
+    /// Move `current` back to `original`.
+    MoveBack { current: PathBuf, original: PathBuf, stamp: Option<Stamp> },
+    /// Trash `path`, which the operation created.
+    Remove { path: PathBuf, stamp: Option<Stamp> },
+    /// Write `content` back to `path`, which the operation overwrote or
+    /// deleted (then `stamp` is `None`).
+    Restore { path: PathBuf, content: Vec<u8>, stamp: Option<Stamp> },
+}
+
+impl Step {
+    pub(super) fn moved(from: PathBuf, to: PathBuf) -> Self {
+        let stamp = Stamp::of(&to);
+        Step::MoveBack { current: to, original: from, stamp }
+    }
+
+    pub(super) fn created(path: PathBuf) -> Self {
+        let stamp = Stamp::of(&path);
+        Step::Remove { path, stamp }
+    }
+
+    pub(super) fn restored(path: PathBuf, content: Vec<u8>) -> Self {
+        let stamp = Stamp::of(&path);
+        Step::Restore { path, content, stamp }
+    }
+
+    /// The path whose state `stamp` records.
+    fn current_path(&self) -> &Path {
+        match self {
+            Step::MoveBack { current, .. } => current,
+            Step::Remove { path, .. } => path,
+            Step::Restore { path, .. } => path,
+        }
+    }
+
+    /// Why this step can no longer be undone, if it can't. Paths for
+    /// which `settled` is true are put back by a step undone before this
+    /// one and aren't checked.
+    fn conflict(&self, settled: impl Fn(&Path) -> bool) -> Option<String> {
+        let (path, stamp) = match self {
+            Step::MoveBack { current, stamp, .. } => (current, stamp),
+            Step::Remove { path, stamp } => (path, stamp),
+            Step::Restore { path, stamp, .. } => (path, stamp),
+        };
+        let now = Stamp::of(path);
+        // A deleted file is restorable as long as nothing took its place.
+        let gone_ok = matches!(self, Step::Restore { .. });
+        if !settled(path) && ((now.is_none() && !gone_ok) || now != *stamp) {
+            return Some(format!("{} has changed since", path.display()));
+        }
+        match self {
+            Step::MoveBack { original, .. } if !settled(original) && original.exists() => {
+                Some(format!("{} already exists", original.display()))
+            }
+            _ => None,
+        }
+    }
+
+    fn undo(&self) -> Result<(), String> {
+        match self {
+            Step::MoveBack { current, original, .. } => {
+                // A merged move removes the emptied source folder.
+                if let Some(parent) = original.parent() {
+                    std::fs::create_dir_all(parent).map_err(|e| format!("Failed to move '{}' back: {}", current.display(), e))?;
+                }
+                std::fs::rename(current, original).map_err(|e| format!("Failed to move '{}' back: {}", current.display(), e))
+            }
+            Step::Remove { path, .. } => {
+                trash::delete(path).map_err(|e| format!("Failed to move to trash: {}", e))
+            }
+            Step::Restore { path, content, .. } => {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent).map_err(|e| format!("Failed to restore '{}': {}", path.display(), e))?;
+                }
+                super::write_atomic(path, content)
+            }
+        }
+    }
+
+    fn original_path(&self) -> &Path {
+        match self {
+            Step::MoveBack { original, .. } => original,
+            Step::Remove { path, .. } => path,
+            Step::Restore { path, .. } => path,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Entry {
+    description: String,
+    steps: Vec<Step>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct UndoResult {
+    /// What was undone, e.g. "Rename" or "Paste 3 items".
+    pub description: String,
+    /// Paths restored (for moves) or removed (for copies), for the tree to
+    /// refresh and re-select.
+    pub paths: Vec<String>,
+}
+
+fn journals() -> &'static Mutex<HashMap<String, Vec<Entry>>> {
+    static JOURNALS: OnceLock<Mutex<HashMap<String, Vec<Entry>>>> = OnceLock::new();
+    JOURNALS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Record an operation for `window_label`. Operations that did nothing are
+/// not recorded.
+pub(super) fn record(window_label: &str, description: &str, steps: Vec<Step>) {
+    if steps.is_empty() {
+        return;
+    }
+    let description = if steps.len() == 1 { description.to_string() } else { format!("{} {} items", description, steps.len()) };
+    let mut journals = journals().lock().unwrap();
+    let entries = journals.entry(window_label.to_string()).or_default();
+    entries.push(Entry { description, steps });
+    if entries.len() > MAX_ENTRIES {
+        entries.remove(0);
+    }
+}
+
+/// Forget a window's history, e.g. when it opens another project or closes.
+pub fn clear(window_label: &str) {
+    journals().lock().unwrap().remove(window_label);
+}
+
+fn undo_last(window_label: &str) -> Result<UndoResult, String> {
+    let entry = journals()
+        .lock()
+        .unwrap()
+        .get_mut(window_label)
+        .and_then(Vec::pop)
+        .ok_or("Nothing to undo")?;
+    // A workspace edit can touch a path more than once; only the last
+    // step on it has to match the disk as it is now.
+    let mut later: Vec<&Path> = Vec::new();
+    for step in entry.steps.iter().rev() {
+        let settled = |p: &Path| later.iter().any(|q| p.starts_with(q) || q.starts_with(p));
+        if let Some(conflict) = step.conflict(settled) {
+            return Err(format!("UNDO_STALE: Cannot undo {}: {}", entry.description.to_lowercase(), conflict));
+        }
+        later.push(step.current_path());
+        if let Step::MoveBack { original, .. } = step {
+            later.push(original);
+        }
+    }
+    for step in entry.steps.iter().rev() {
+        step.undo()?;
+    }
+    Ok(UndoResult {
+        description: entry.description,
+        paths: entry.steps.iter().map(|s| s.original_path().to_string_lossy().into_owned()).collect(),
+    })
+}
+
+/// Revert the calling window's most recent rename/move/paste/duplicate or
+/// workspace edit.
+/// Fails with `UNDO_STALE:` (and forgets the entry) when the files have
+/// changed since.
+#[tauri::command]
+pub fn undo_last_fs_operation(window: tauri::WebviewWindow) -> Result<UndoResult, String> {
+    undo_last(window.label())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undoes_moves_in_reverse_and_rejects_stale_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let (a, b, c) = (dir.path().join("a.txt"), dir.path().join("b.txt"), dir.path().join("c.txt"));
+        std::fs::write(&a, "a").unwrap();
+        let label = "journal-test";
+
+        std::fs::rename(&a, &b).unwrap();
+        record(label, "Rename", vec![Step::moved(a.clone(), b.clone())]);
+        std::fs::rename(&b, &c).unwrap();
+        record(label, "Rename", vec![Step::moved(b.clone(), c.clone())]);
+
+        assert_eq!(undo_last(label).unwrap().paths, vec![b.to_string_lossy()]);
+        assert!(b.exists() && !c.exists());
+
+        // Someone recreated a.txt; moving b.txt back would clobber it.
+        std::fs::write(&a, "new").unwrap();
+        assert!(undo_last(label).unwrap_err().starts_with("UNDO_STALE:"));
+        assert_eq!(std::fs::read_to_string(&a).unwrap(), "new");
+        assert_eq!(undo_last(label).unwrap_err(), "Nothing to undo");
+    }
+
+    #[test]
+    fn edited_copies_are_not_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let copy = dir.path().join("copy.txt");
+        std::fs::write(&copy, "copy").unwrap();
+        let label = "journal-test-copy";
+        record(label, "Paste", vec![Step::created(copy.clone())]);
+
+        std::fs::write(&copy, "copy, then edited").unwrap();
+        assert!(undo_last(label).is_err());
+        assert!(copy.exists());
+    }
+}
@@ -0,0 +1,176 @@
+//! `media://` protocol for previewing large media files.
+//!
+//! `read_file_binary` sends a whole file over IPC as base64, which is
+//! fine for an icon but not for a 300 MB video. Video and audio elements
+//! load `media://` URLs instead (`convertFileSrc(path, 'media')` on the
+//! frontend): the webview asks for byte ranges as playback and seeking
+//! need them, and each request reads only that range from disk. Files are
+//! served only from the requesting window's project.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{Manager, Runtime, UriSchemeContext, UriSchemeResponder};
+
+use super::{filetype, project_root_async, resolve_within_root, ProjectRootState, BINARY_SNIFF_BYTES};
+use crate::modules::preview_server::percent_decode;
+
+pub const SCHEME: &str = "media";
+
+/// Largest slice returned for one request; open-ended ranges
+/// (`bytes=100-`) are cut to this and the webview asks for the rest. A
+/// request without a range for a larger file gets its first slice as a
+/// partial response rather than the whole file in memory.
+const MAX_RANGE_BYTES: u64 = 4 * 1024 * 1024;
+
+/// The part of a file a request asks for.
+#[derive(Debug, PartialEq)]
+enum Span {
+    Whole,
+    /// First and last byte, inclusive.
+    Part(u64, u64),
+    Unsatisfiable,
+}
+
+/// First and last byte asked for by a `bytes=` range, before clamping
+/// to the file. `u64::MAX` stands for "to the end".
+fn requested(spec: &str, size: u64) -> Option<(u64, u64)> {
+    let (start, end) = spec.split(',').next()?.trim().split_once('-')?;
+    if start.is_empty() {
+        // `bytes=-500`: the last 500 bytes.
+        let suffix: u64 = end.parse().ok().filter(|&n| n > 0)?;
+        return Some((size.saturating_sub(suffix), u64::MAX));
+    }
+    let start: u64 = start.parse().ok()?;
+    let end: u64 = if end.is_empty() { u64::MAX } else { end.parse().ok()? };
+    (start <= end).then_some((start, end))
+}
+
+/// Parse a `Range` header against a file of `size` bytes. Only the first
+/// range of a multi-range request is served; a header that doesn't parse
+/// is ignored, as HTTP allows.
+fn parse_range(range: Option<&str>, size: u64) -> Span {
+    let spec = range.and_then(|r| r.trim().strip_prefix("bytes="));
+    match spec.and_then(|spec| requested(spec, size)) {
+        None if size > MAX_RANGE_BYTES => Span::Part(0, MAX_RANGE_BYTES - 1),
+        None => Span::Whole,
+        Some((start, _)) if start >= size => Span::Unsatisfiable,
+        Some((start, end)) => Span::Part(start, end.min(size - 1).min(start + MAX_RANGE_BYTES - 1)),
+    }
+}
+
+fn read_span(file: &mut File, start: u64, len: u64) -> std::io::Result<Vec<u8>> {
+    file.seek(SeekFrom::Start(start))?;
+    let mut bytes = Vec::with_capacity(len as usize);
+    file.take(len).read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn plain(status: StatusCode, message: &str) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+        .body(message.as_bytes().to_vec())
+        .unwrap_or_default()
+}
+
+/// The response for `uri_path` (`/` and the percent-encoded absolute
+/// path) with an optional `Range` header, served from `root`.
+fn respond(root: &Path, uri_path: &str, range: Option<&str>) -> Response<Vec<u8>> {
+    let path = percent_decode(uri_path.trim_start_matches('/'));
+    let Some(file) = resolve_within_root(&path, root).ok().filter(|p| p.is_file()) else { return plain(StatusCode::NOT_FOUND, "Not found") };
+    let read_failed = || plain(StatusCode::INTERNAL_SERVER_ERROR, "Failed to read file");
+    let Ok(mut f) = File::open(&file) else { return read_failed() };
+    let Ok(size) = f.metadata().map(|m| m.len()) else { return read_failed() };
+
+    let Ok(sample) = read_span(&mut f, 0, BINARY_SNIFF_BYTES as u64) else { return read_failed() };
+    let mime = filetype::detect(&file, &sample, size > sample.len() as u64).mime;
+    let builder = Response::builder()
+        .header(header::CONTENT_TYPE, mime)
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header(header::CACHE_CONTROL, "no-store");
+
+    let (builder, start, end) = match parse_range(range, size) {
+        Span::Whole if size == 0 => return builder.status(StatusCode::OK).body(Vec::new()).unwrap_or_default(),
+        Span::Whole => (builder.status(StatusCode::OK), 0, size - 1),
+        Span::Part(start, end) => (
+            builder
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, size)),
+            start,
+            end,
+        ),
+        Span::Unsatisfiable => {
+            return builder
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .header(header::CONTENT_RANGE, format!("bytes */{}", size))
+                .body(Vec::new())
+                .unwrap_or_default()
+        }
+    };
+    match read_span(&mut f, start, end - start + 1) {
+        Ok(body) => builder.header(header::CONTENT_LENGTH, body.len()).body(body).unwrap_or_default(),
+        Err(_) => read_failed(),
+    }
+}
+
+/// Handler for the `media` scheme, registered on the app builder.
+pub fn handle<R: Runtime>(ctx: UriSchemeContext<'_, R>, request: Request<Vec<u8>>, responder: UriSchemeResponder) {
+    let app = ctx.app_handle().clone();
+    let label = ctx.webview_label().to_string();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<ProjectRootState>();
+        let response = match project_root_async(&label, &state).await {
+            Ok(root) => {
+                let range = request.headers().get(header::RANGE).and_then(|v| v.to_str().ok()).map(str::to_string);
+                let uri_path = request.uri().path().to_string();
+                tauri::async_runtime::spawn_blocking(move || respond(&root, &uri_path, range.as_deref()))
+                    .await
+                    .unwrap_or_else(|_| plain(StatusCode::INTERNAL_SERVER_ERROR, "Failed to read file"))
+            }
+            Err(_) => plain(StatusCode::FORBIDDEN, "No project is open"),
+        };
+        responder.respond(response);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serves_ranges_from_the_project_only() {
+        assert_eq!(parse_range(None, 100), Span::Whole);
+        assert_eq!(parse_range(Some("bytes=10-19"), 100), Span::Part(10, 19));
+        assert_eq!(parse_range(Some("bytes=90-200"), 100), Span::Part(90, 99));
+        assert_eq!(parse_range(Some("bytes=-30"), 100), Span::Part(70, 99));
+        assert_eq!(parse_range(Some("bytes=0-"), 10 * MAX_RANGE_BYTES), Span::Part(0, MAX_RANGE_BYTES - 1));
+        assert_eq!(parse_range(Some("bytes=100-"), 100), Span::Unsatisfiable);
+        assert_eq!(parse_range(Some("bytes=5-1"), 100), Span::Whole);
+        assert_eq!(parse_range(Some("lines=1-2"), 100), Span::Whole);
+        assert_eq!(parse_range(None, MAX_RANGE_BYTES), Span::Whole);
+        assert_eq!(parse_range(None, 10 * MAX_RANGE_BYTES), Span::Part(0, MAX_RANGE_BYTES - 1));
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = std::fs::canonicalize(dir.path()).unwrap();
+        let video = root.join("clip one.mp4");
+        let mut bytes = b"\0\0\0\x18ftypmp42".to_vec();
+        bytes.extend((0..1000u32).map(|i| i as u8));
+        std::fs::write(&video, &bytes).unwrap();
+        let uri = format!("/{}", video.to_string_lossy().replace(' ', "%20").replace('/', "%2F"));
+
+        let r = respond(&root, &uri, Some("bytes=4-11"));
+        assert_eq!(r.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(r.body(), b"ftypmp42");
+        assert_eq!(r.headers()[header::CONTENT_RANGE], format!("bytes 4-11/{}", bytes.len()));
+        assert_eq!(r.headers()[header::CONTENT_TYPE], "video/mp4");
+
+        let r = respond(&root, &uri, None);
+        assert_eq!((r.status(), r.body().len()), (StatusCode::OK, bytes.len()));
+
+        let outside = tempfile::NamedTempFile::new().unwrap();
+        let uri = format!("/{}", outside.path().to_string_lossy().replace('/', "%2F"));
+        assert_eq!(respond(&root, &uri, None).status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -1,5 +1,5 @@
 use base64::Engine;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -7,13 +7,40 @@ use std::process::Command;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+use crate::modules::i18n::Localized;
+use crate::modules::operations::{self, OperationKind};
+
+pub mod archive;
+pub mod backup;
+pub mod checksum;
+pub mod chunked;
+pub mod complete;
+pub mod delete;
+pub mod encoding;
+pub mod exclude;
+pub mod filetype;
+pub mod fuzzy;
+pub mod generated;
+pub mod hidden;
+pub mod history;
+pub mod index;
+pub mod journal;
+pub mod media;
+pub mod permissions;
+pub mod preview;
+pub mod routing;
+pub mod symlinks;
+pub mod tail;
+pub mod thumbnail;
+pub mod workspace_edit;
+
 /// Per-window project root. Each Tauri window has its own entry,
 /// keyed by `WebviewWindow::label()`. The outer RwLock guards the map;
 /// inner Option holds the per-window root.
 pub type ProjectRootState = Arc<RwLock<HashMap<String, Option<PathBuf>>>>;
 
-const MAX_TEXT_FILE_BYTES: u64 = 50 * 1024 * 1024; // 50 MB
-const MAX_BINARY_FILE_BYTES: u64 = 100 * 1024 * 1024; // 100 MB
+pub(crate) const MAX_TEXT_FILE_BYTES: u64 = 50 * 1024 * 1024; // 50 MB
+pub(crate) const MAX_BINARY_FILE_BYTES: u64 = 100 * 1024 * 1024; // 100 MB
 
 pub fn create_project_root_state() -> ProjectRootState {
     Arc::new(RwLock::new(HashMap::new()))
@@ -29,6 +56,7 @@ pub fn set_project_root(
     let canonical_str = canonical.to_string_lossy().to_string();
     let mut map = state.blocking_write();
     map.insert(window.label().to_string(), Some(canonical));
+    journal::clear(window.label());
     Ok(canonical_str)
 }
 
@@ -51,16 +79,38 @@ pub fn validate_path(
     path: &str,
     window_label: &str,
     state: &tauri::State<'_, ProjectRootState>,
-) -> Result<PathBuf, String> {
+) -> Result<PathBuf, Localized> {
     let map = state.blocking_read();
     let root = map
         .get(window_label)
         .and_then(|opt| opt.as_ref())
-        .ok_or_else(|| "No project is open".to_string())?;
+        .ok_or_else(|| Localized::new("no-project-open", &[]))?;
+    resolve_within_root(path, root)
+}
 
+/// Look up the calling window's project root from an async command.
+/// `blocking_read` panics inside the tokio runtime, so async commands
+/// clone the root out with `.read().await` and then validate paths
+/// against it via `resolve_within_root`.
+pub async fn project_root_async(
+    window_label: &str,
+    state: &ProjectRootState,
+) -> Result<PathBuf, Localized> {
+    let map = state.read().await;
+    map.get(window_label)
+        .and_then(|opt| opt.as_ref())
+        .cloned()
+        .ok_or_else(|| Localized::new("no-project-open", &[]))
+}
+
+/// Canonicalize `path` and check that it stays inside `root`. Paths that
+/// don't exist yet are resolved through their nearest existing ancestor
+/// so creates and renames can be validated before the target exists.
+pub fn resolve_within_root(path: &str, root: &Path) -> Result<PathBuf, Localized> {
+    let invalid = |reason: String| Localized::new("path-invalid", &[("reason", reason)]);
     let p = PathBuf::from(path);
     let canonical = if p.exists() {
-        fs::canonicalize(&p).map_err(|e| format!("Invalid path: {}", e))?
+        fs::canonicalize(&p).map_err(|e| invalid(e.to_string()))?
     } else {
         let mut ancestor = p.as_path();
         let mut trailing_parts: Vec<&std::ffi::OsStr> = Vec::new();
@@ -69,22 +119,21 @@ pub fn validate_path(
                 if let Some(name) = ancestor.file_name() {
                     trailing_parts.push(name);
                 } else {
-                    return Err("Invalid path".to_string());
+                    return Err(invalid(path.to_string()));
                 }
                 ancestor = parent;
                 if ancestor.exists() {
                     break;
                 }
             } else {
-                return Err("Invalid path: no existing ancestor found".to_string());
+                return Err(invalid("no existing ancestor found".to_string()));
             }
         }
-        let mut canonical =
-            fs::canonicalize(ancestor).map_err(|e| format!("Invalid path: {}", e))?;
+        let mut canonical = fs::canonicalize(ancestor).map_err(|e| invalid(e.to_string()))?;
         for part in trailing_parts.iter().rev() {
             let s = part.to_string_lossy();
             if s == ".." || s == "." {
-                return Err("Invalid path: traversal not allowed".to_string());
+                return Err(Localized::new("path-traversal", &[]));
             }
             canonical.push(part);
         }
@@ -92,12 +141,142 @@ pub fn validate_path(
     };
 
     if !canonical.starts_with(root) {
-        return Err("Access denied: path is outside the project directory".to_string());
+        return Err(Localized::new("path-outside-project", &[]));
     }
 
     Ok(canonical)
 }
 
+// ── Atomic write helper ──────────────────────────────────────────
+
+/// Write `bytes` to a temp file beside `path`, fsync it, and rename it
+/// into place, so a crash mid-write leaves either the old or the new
+/// content — never a truncated file. The original file's permissions are
+/// carried over. `path` should already be canonical: renaming onto a
+/// symlink would replace the link instead of its target.
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    use std::io::Write;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    static NEXT_TMP: AtomicU64 = AtomicU64::new(0);
+
+    let dir = path.parent().ok_or_else(|| "Invalid path".to_string())?;
+    let name = path.file_name().ok_or_else(|| "Invalid path".to_string())?.to_string_lossy();
+    let tmp = dir.join(format!(".{}.{}-{}.tmp", name, std::process::id(), NEXT_TMP.fetch_add(1, Ordering::Relaxed)));
+    let write_err = |e: std::io::Error| format!("Failed to write file: {}", e.kind());
+
+    let written = fs::File::create(&tmp).and_then(|mut f| {
+        f.write_all(bytes)?;
+        if let Ok(meta) = fs::metadata(path) {
+            f.set_permissions(meta.permissions())?;
+        }
+        f.sync_all()
+    });
+    if let Err(e) = written.and_then(|_| fs::rename(&tmp, path)) {
+        let _ = fs::remove_file(&tmp);
+        return Err(write_err(e));
+    }
+    // Persist the rename itself. Directories can't be opened for syncing
+    // on Windows, where the rename is already durable.
+    #[cfg(unix)]
+    if let Ok(d) = fs::File::open(dir) {
+        let _ = d.sync_all();
+    }
+    Ok(())
+}
+
+/// Copy the current contents of `path` to `<name>.bak` beside it. Does
+/// nothing when the file doesn't exist yet.
+pub fn backup_file(path: &Path) -> Result<(), String> {
+    if !path.is_file() {
+        return Ok(());
+    }
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read file: {}", e.kind()))?;
+    write_atomic(Path::new(&backup), &bytes)
+}
+
+// ── File versions (external-change detection) ────────────────────
+
+/// What the editor last saw of a file on disk. Passed back on save so
+/// edits made by other programs in the meantime aren't overwritten.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct FileVersion {
+    /// Modification time in milliseconds since the Unix epoch.
+    pub mtime_ms: u64,
+    pub size: u64,
+    /// SHA-256 of the content, hex encoded.
+    pub hash: String,
+    /// On saves and from `get_file_version`, the pattern of the generated
+    /// directory the file sits in. Edits there are usually overwritten by the next build, so the
+    /// editor warns.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generated_dir: Option<String>,
+    /// Why the content looks generated (lockfile, minified, marker, …),
+    /// so the editor can say so before the user edits it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generated: Option<generated::GeneratedReason>,
+}
+
+fn mtime_ms(meta: &fs::Metadata) -> u64 {
+    meta.modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn content_hash(bytes: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+pub fn file_version(path: &Path) -> Result<FileVersion, String> {
+    let meta = fs::metadata(path).map_err(|e| format!("Failed to read file: {}", e.kind()))?;
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read file: {}", e.kind()))?;
+    Ok(FileVersion {
+        mtime_ms: mtime_ms(&meta),
+        size: meta.len(),
+        hash: content_hash(&bytes),
+        generated_dir: None,
+        generated: generated::is_generated(path, &String::from_utf8_lossy(&bytes)),
+    })
+}
+
+/// Fail with `FILE_CHANGED:` when the file on disk no longer matches what
+/// the caller expects. A matching hash wins over a differing mtime, since
+/// touching a file without changing it is harmless. A file that has been
+/// deleted since counts as changed.
+fn check_unchanged(path: &Path, expected_mtime: Option<u64>, expected_hash: Option<&str>) -> Result<(), String> {
+    if expected_mtime.is_none() && expected_hash.is_none() {
+        return Ok(());
+    }
+    let changed = || Err(format!("FILE_CHANGED: {} was modified on disk", path.display()));
+    let Ok(meta) = fs::metadata(path) else { return changed() };
+    if let Some(hash) = expected_hash {
+        let bytes = fs::read(path).map_err(|e| format!("Failed to read file: {}", e.kind()))?;
+        return if content_hash(&bytes) == hash { Ok(()) } else { changed() };
+    }
+    if expected_mtime != Some(mtime_ms(&meta)) {
+        return changed();
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_file_version(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: String,
+) -> Result<FileVersion, String> {
+    let path = validate_path(&path, window.label(), &state)?;
+    let mut version = file_version(&path)?;
+    if let Some(root) = state.blocking_read().get(window.label()).and_then(|o| o.clone()) {
+        version.generated_dir = generated::GeneratedDirs::for_project(&root).matching(&path, false);
+    }
+    Ok(version)
+}
+
 // ── Copy-naming helper (used by paste, import, duplicate) ────────
 
 /// Generate a unique copy name in `dest_dir` for a file/folder with the given stem and extension.
@@ -136,9 +315,33 @@ pub struct FileEntry {
     /// `validate_path` canonicalizes before any I/O and rejects paths
     /// that escape the root.
     pub is_symlink: bool,
+    /// Lockfile, bundle, source map or codegen output, judged by name
+    /// alone so listing stays cheap, or anything inside a directory the
+    /// project marks as generated (that directory included). See
+    /// `generated::check_file` for the content-based check.
+    pub is_generated: bool,
     pub children: Option<Vec<FileEntry>>,
+    /// Number of entries in the directory when its listing was read.
+    /// Nested listings stop at `MAX_NESTED_ENTRIES`, so this can exceed
+    /// `children.len()`; `read_dir_page` fetches the rest.
+    pub child_count: Option<usize>,
 }
 
+/// One page of a directory listing from `read_dir_page`.
+#[derive(Serialize, Clone)]
+pub struct DirPage {
+    pub entries: Vec<FileEntry>,
+    /// Entries in the whole directory after filtering.
+    pub total: usize,
+    /// Offset of the next page, if there is one.
+    pub next_offset: Option<usize>,
+}
+
+/// Most children returned for a directory below the one requested.
+const MAX_NESTED_ENTRIES: usize = 1000;
+/// Page size when `read_dir_page` isn't given a limit.
+const DEFAULT_PAGE_SIZE: usize = 500;
+
 // ── File system commands ─────────────────────────────────────────
 
 #[tauri::command]
@@ -147,19 +350,100 @@ pub fn read_dir_tree(
     state: tauri::State<'_, ProjectRootState>,
     path: String,
     depth: Option<u32>,
+    show_hidden: Option<bool>,
+    skip: Option<Vec<String>>,
+    follow_symlinks: Option<bool>,
 ) -> Result<Vec<FileEntry>, String> {
     validate_path(&path, window.label(), &state)?;
+    let root = state.blocking_read().get(window.label()).and_then(|o| o.clone()).ok_or("No project is open")?;
+    let excludes = exclude::Excludes::for_project(&root);
+    let hidden = hidden::HiddenFilter::new(show_hidden, skip);
+    let mut links = symlinks::Symlinks::requested(follow_symlinks, &root);
     let max_depth = depth.unwrap_or(1).min(50);
     let mut visited = std::collections::HashSet::new();
-    read_dir_recursive(&PathBuf::from(path), 0, max_depth, &mut visited)
+    let mut entries = read_dir_recursive(&PathBuf::from(path), 0, max_depth, &mut visited, &excludes, &hidden, &mut links)?;
+    mark_generated_dirs(&mut entries, &generated::GeneratedDirs::for_project(&root));
+    Ok(entries)
+}
+
+/// `offset`/`limit` slice of `path`'s entries in tree order (directories
+/// first, then by name), so giant folders can be shown a page at a time
+/// instead of in one huge payload. Like `read_dir_tree` with depth 1,
+/// directories on the page come with their first `MAX_NESTED_ENTRIES`
+/// children.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub fn read_dir_page(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    show_hidden: Option<bool>,
+    skip: Option<Vec<String>>,
+    follow_symlinks: Option<bool>,
+) -> Result<DirPage, String> {
+    validate_path(&path, window.label(), &state)?;
+    let root = state.blocking_read().get(window.label()).and_then(|o| o.clone()).ok_or("No project is open")?;
+    let excludes = exclude::Excludes::for_project(&root);
+    let hidden = hidden::HiddenFilter::new(show_hidden, skip);
+    let mut links = symlinks::Symlinks::requested(follow_symlinks, &root);
+    let mut visited = std::collections::HashSet::new();
+    let entries = read_dir_recursive(&PathBuf::from(path), 0, 0, &mut visited, &excludes, &hidden, &mut links)?;
+    let mut page = paginate(entries, offset.unwrap_or(0), limit.unwrap_or(DEFAULT_PAGE_SIZE).max(1));
+    for entry in page.entries.iter_mut().filter(|e| e.is_dir) {
+        let dir = PathBuf::from(&entry.path);
+        if entry.is_symlink && links.follow(&dir).is_none() {
+            continue;
+        }
+        let (children, count) =
+            read_dir_listing(&dir, 1, 1, &mut visited, &excludes, &hidden, &mut links).unwrap_or_default();
+        entry.children = Some(children);
+        entry.child_count = Some(count);
+    }
+    mark_generated_dirs(&mut page.entries, &generated::GeneratedDirs::for_project(&root));
+    Ok(page)
+}
+
+fn paginate(entries: Vec<FileEntry>, offset: usize, limit: usize) -> DirPage {
+    let total = entries.len();
+    let end = offset.saturating_add(limit).min(total);
+    let entries: Vec<FileEntry> = entries.into_iter().skip(offset).take(limit).collect();
+    DirPage { entries, total, next_offset: (end < total).then_some(end) }
+}
+
+fn mark_generated_dirs(entries: &mut [FileEntry], dirs: &generated::GeneratedDirs) {
+    for entry in entries {
+        entry.is_generated |= dirs.matching(Path::new(&entry.path), entry.is_dir).is_some();
+        if let Some(children) = &mut entry.children {
+            mark_generated_dirs(children, dirs);
+        }
+    }
 }
 
-fn read_dir_recursive(
+pub(crate) fn read_dir_recursive(
     path: &Path,
     current_depth: u32,
     max_depth: u32,
     visited: &mut std::collections::HashSet<PathBuf>,
+    excludes: &exclude::Excludes,
+    hidden: &hidden::HiddenFilter,
+    links: &mut symlinks::Symlinks,
 ) -> Result<Vec<FileEntry>, String> {
+    read_dir_listing(path, current_depth, max_depth, visited, excludes, hidden, links).map(|(entries, _)| entries)
+}
+
+/// The entries of `path` and how many there were before nested listings
+/// (`current_depth > 0`) were cut to `MAX_NESTED_ENTRIES`.
+fn read_dir_listing(
+    path: &Path,
+    current_depth: u32,
+    max_depth: u32,
+    visited: &mut std::collections::HashSet<PathBuf>,
+    excludes: &exclude::Excludes,
+    hidden: &hidden::HiddenFilter,
+    links: &mut symlinks::Symlinks,
+) -> Result<(Vec<FileEntry>, usize), String> {
     // Cycle detection: track the canonical path of every directory we
     // descend into. A symlinked directory that points back to (or
     // through) an ancestor would otherwise loop forever once
@@ -168,21 +452,20 @@ fn read_dir_recursive(
     // are detected as a cycle.
     let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
     if !visited.insert(canonical) {
-        return Ok(Vec::new());
+        return Ok((Vec::new(), 0));
     }
 
     let entries = fs::read_dir(path).map_err(|e| e.to_string())?;
-    let mut result: Vec<FileEntry> = Vec::new();
+    let mut listed: Vec<(FileEntry, bool)> = Vec::new();
 
     for entry in entries {
         let entry = entry.map_err(|e| e.to_string())?;
         let file_name = entry.file_name().to_string_lossy().to_string();
-
-        if file_name == ".git" {
+        let file_path = entry.path();
+        if hidden.skips(&file_path, &file_name) {
             continue;
         }
 
-        let file_path = entry.path();
         let ft = entry.file_type().map_err(|e| e.to_string())?;
         let is_symlink = ft.is_symlink();
 
@@ -196,40 +479,53 @@ fn read_dir_recursive(
         } else {
             ft.is_dir()
         };
+        if excludes.is_excluded(&file_path, is_dir) {
+            continue;
+        }
 
-        // Recurse into REAL directories only. Symlinked directories
-        // are listed but never expanded — that's the cheapest way to
-        // avoid the cycles the visited-set is also guarding against.
-        // Users can still navigate symlinked dirs by clicking through
-        // (FileTree.svelte fetches their children lazily), where the
-        // visited set provides the real safety net.
-        let children = if is_dir && !is_symlink && current_depth < max_depth {
-            Some(
-                read_dir_recursive(&file_path, current_depth + 1, max_depth, visited)
-                    .unwrap_or_default(),
-            )
-        } else if is_dir {
-            Some(Vec::new())
-        } else {
-            None
-        };
-
-        result.push(FileEntry {
+        // Recurse into real directories, and into symlinked ones only
+        // when `links` allows it (in-root following is on and the target
+        // hasn't been entered yet). Other symlinked dirs are listed but
+        // not expanded; users can still click through (FileTree.svelte
+        // fetches their children lazily), where the visited set provides
+        // the real safety net.
+        let expand = is_dir && current_depth < max_depth && (!is_symlink || links.follow(&file_path).is_some());
+        let entry = FileEntry {
             name: file_name,
             path: file_path.to_string_lossy().to_string(),
             is_dir,
             is_symlink,
-            children,
-        });
+            is_generated: !is_dir && generated::generated_by_name(&file_path).is_some(),
+            children: is_dir.then(Vec::new),
+            child_count: None,
+        };
+        listed.push((entry, expand));
     }
 
-    result.sort_by(|a, b| {
+    listed.sort_by(|(a, _), (b, _)| {
         b.is_dir
             .cmp(&a.is_dir)
             .then(a.name.to_lowercase().cmp(&b.name.to_lowercase()))
     });
+    // Sort before cutting so the kept entries are the first ones shown,
+    // and only those are descended into.
+    let total = listed.len();
+    if current_depth > 0 {
+        listed.truncate(MAX_NESTED_ENTRIES);
+    }
 
-    Ok(result)
+    let mut result = Vec::with_capacity(listed.len());
+    for (mut entry, expand) in listed {
+        if expand {
+            let (children, count) =
+                read_dir_listing(Path::new(&entry.path), current_depth + 1, max_depth, visited, excludes, hidden, links)
+                    .unwrap_or_default();
+            entry.children = Some(children);
+            entry.child_count = Some(count);
+        }
+        result.push(entry);
+    }
+    Ok((result, total))
 }
 
 #[tauri::command]
@@ -252,12 +548,94 @@ pub fn write_file_content(
     state: tauri::State<'_, ProjectRootState>,
     path: String,
     content: String,
-) -> Result<(), String> {
-    validate_path(&path, window.label(), &state)?;
+    expected_mtime: Option<u64>,
+    expected_hash: Option<String>,
+    backup: Option<bool>,
+) -> Result<FileVersion, String> {
+    let file = validate_path(&path, window.label(), &state)?;
+    let root = state.blocking_read().get(window.label()).and_then(|o| o.clone()).ok_or("No project is open")?;
+    let mut version = save_text(&root, &file, &content, expected_mtime, expected_hash.as_deref(), backup.unwrap_or(false))?;
+    version.generated_dir = generated::GeneratedDirs::for_project(&root).matching(&file, false);
+    Ok(version)
+}
+
+/// Shared save path for `write_file_content` and `write_files_batch`.
+/// The content being replaced goes to the local history first.
+fn save_text(
+    root: &Path,
+    file: &Path,
+    content: &str,
+    expected_mtime: Option<u64>,
+    expected_hash: Option<&str>,
+    backup: bool,
+) -> Result<FileVersion, String> {
     if content.len() as u64 > MAX_TEXT_FILE_BYTES {
         return Err(format!("CONTENT_TOO_LARGE: {} bytes; limit {}", content.len(), MAX_TEXT_FILE_BYTES));
     }
-    fs::write(&path, &content).map_err(|e| format!("Failed to write file: {}", e.kind()))
+    check_unchanged(file, expected_mtime, expected_hash)?;
+    if backup {
+        backup_file(file)?;
+    }
+    history::keep(root, file, content.as_bytes());
+    write_atomic(file, content.as_bytes())?;
+    backup::note_activity(root);
+    let meta = fs::metadata(file).map_err(|e| format!("Failed to read file: {}", e.kind()))?;
+    Ok(FileVersion {
+        mtime_ms: mtime_ms(&meta),
+        size: meta.len(),
+        hash: content_hash(content.as_bytes()),
+        generated_dir: None,
+        generated: generated::is_generated(file, content),
+    })
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct FileWrite {
+    pub path: String,
+    pub content: String,
+    pub expected_mtime: Option<u64>,
+    pub expected_hash: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct FileWriteResult {
+    pub path: String,
+    /// New version on success.
+    pub version: Option<FileVersion>,
+    pub error: Option<String>,
+}
+
+fn write_batch(root: &Path, files: Vec<FileWrite>, backup: bool) -> Vec<FileWriteResult> {
+    let generated_dirs = generated::GeneratedDirs::for_project(root);
+    files
+        .into_iter()
+        .map(|f| {
+            let saved = resolve_within_root(&f.path, root).map_err(String::from).and_then(|file| {
+                let mut version = save_text(root, &file, &f.content, f.expected_mtime, f.expected_hash.as_deref(), backup)?;
+                version.generated_dir = generated_dirs.matching(&file, false);
+                Ok(version)
+            });
+            let (version, error) = match saved {
+                Ok(v) => (Some(v), None),
+                Err(e) => (None, Some(e)),
+            };
+            FileWriteResult { path: f.path, version, error }
+        })
+        .collect()
+}
+
+/// Save several files in one call ("Save All"). Each file is written
+/// atomically and independently; a failure (e.g. `FILE_CHANGED:`) is
+/// reported for that file and doesn't stop the rest.
+#[tauri::command]
+pub fn write_files_batch(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    files: Vec<FileWrite>,
+    backup: Option<bool>,
+) -> Result<Vec<FileWriteResult>, String> {
+    let root = state.blocking_read().get(window.label()).and_then(|o| o.clone()).ok_or("No project is open")?;
+    Ok(write_batch(&root, files, backup.unwrap_or(false)))
 }
 
 #[tauri::command]
@@ -275,6 +653,147 @@ pub fn read_file_binary(
     Ok(base64::engine::general_purpose::STANDARD.encode(&bytes))
 }
 
+/// Largest range `read_file_hex` returns at once.
+const MAX_HEX_READ_BYTES: u64 = 1024 * 1024;
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct HexRange {
+    pub offset: u64,
+    pub bytes: Vec<u8>,
+    pub total_size: u64,
+}
+
+fn read_range(path: &Path, offset: u64, length: u64) -> Result<HexRange, String> {
+    use std::io::{Read, Seek, SeekFrom};
+    let mut file = fs::File::open(path).map_err(|e| format!("Failed to read file: {}", e.kind()))?;
+    let total_size = file.metadata().map_err(|e| format!("Failed to read file: {}", e.kind()))?.len();
+    let offset = offset.min(total_size);
+    let length = length.min(MAX_HEX_READ_BYTES).min(total_size - offset);
+    file.seek(SeekFrom::Start(offset)).map_err(|e| format!("Failed to read file: {}", e.kind()))?;
+    let mut bytes = Vec::with_capacity(length as usize);
+    file.take(length)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read file: {}", e.kind()))?;
+    Ok(HexRange { offset, bytes, total_size })
+}
+
+/// Up to `length` bytes (at most 1 MB) starting at `offset`, for the hex
+/// viewer. Reads past the end are cut short rather than failing.
+#[tauri::command]
+pub fn read_file_hex(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: String,
+    offset: u64,
+    length: u64,
+) -> Result<HexRange, String> {
+    let path = validate_path(&path, window.label(), &state)?;
+    read_range(&path, offset, length)
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct TextRange {
+    /// Where `text` starts; can be a little past the requested offset so
+    /// the text doesn't begin mid-character.
+    pub offset: u64,
+    pub text: String,
+    /// Where the next read should start.
+    pub next_offset: u64,
+    pub total_size: u64,
+}
+
+/// `range` as text, dropping a partial character at either end.
+fn text_range(range: HexRange) -> TextRange {
+    let bytes = &range.bytes;
+    let start = bytes.iter().take(3).take_while(|b| *b & 0xC0 == 0x80).count();
+    let mut end = bytes.len();
+    if range.offset + end as u64 != range.total_size {
+        while end > start && std::str::from_utf8(&bytes[start..end]).is_err() && bytes.len() - end < 3 {
+            end -= 1;
+        }
+    }
+    TextRange {
+        offset: range.offset + start as u64,
+        text: String::from_utf8_lossy(&bytes[start..end]).into_owned(),
+        next_offset: range.offset + end as u64,
+        total_size: range.total_size,
+    }
+}
+
+/// Up to `length` bytes (at most 1 MB) of `path` from `offset`, decoded as
+/// text, for log viewers that page through a file too big to open.
+#[tauri::command]
+pub fn read_file_range(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: String,
+    offset: u64,
+    length: u64,
+) -> Result<TextRange, String> {
+    let path = validate_path(&path, window.label(), &state)?;
+    Ok(text_range(read_range(&path, offset, length)?))
+}
+
+// ── Binary detection ─────────────────────────────────────────────
+
+/// Bytes inspected by `is_binary_file`.
+const BINARY_SNIFF_BYTES: usize = 8 * 1024;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BinaryReason {
+    NulByte,
+    InvalidUtf8,
+    /// Mostly control characters, e.g. a packed format that happens to be
+    /// valid UTF-8.
+    ControlChars,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BinaryCheck {
+    pub binary: bool,
+    pub reason: Option<BinaryReason>,
+    pub size: u64,
+}
+
+/// Classify a file from its first bytes. `truncated` says the sample was
+/// cut off, so a multi-byte character split at the end isn't an error.
+pub(crate) fn sniff_binary(sample: &[u8], truncated: bool) -> Option<BinaryReason> {
+    let text = sample.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(sample);
+    if text.contains(&0) {
+        return Some(BinaryReason::NulByte);
+    }
+    if let Err(e) = std::str::from_utf8(text) {
+        if e.error_len().is_some() || !truncated {
+            return Some(BinaryReason::InvalidUtf8);
+        }
+    }
+    let control = text.iter().filter(|&&b| b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0C | 0x1B)).count();
+    (control * 10 > text.len()).then_some(BinaryReason::ControlChars)
+}
+
+/// Whether `path` looks binary, judged from its first 8 KB. Lets the
+/// frontend pick the hex or image viewer before `read_file_content`
+/// fails on invalid UTF-8.
+#[tauri::command]
+pub fn is_binary_file(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: String,
+) -> Result<BinaryCheck, String> {
+    use std::io::Read;
+    let file = validate_path(&path, window.label(), &state)?;
+    let mut f = fs::File::open(&file).map_err(|e| format!("Failed to read file: {}", e.kind()))?;
+    let size = f.metadata().map_err(|e| format!("Failed to read file: {}", e.kind()))?.len();
+    let mut sample = Vec::with_capacity(BINARY_SNIFF_BYTES);
+    f.by_ref()
+        .take(BINARY_SNIFF_BYTES as u64)
+        .read_to_end(&mut sample)
+        .map_err(|e| format!("Failed to read file: {}", e.kind()))?;
+    let reason = sniff_binary(&sample, size > sample.len() as u64);
+    Ok(BinaryCheck { binary: reason.is_some(), reason, size })
+}
+
 #[tauri::command]
 pub fn get_home_dir() -> Result<String, String> {
     dirs::home_dir()
@@ -290,16 +809,17 @@ pub fn create_project_dir(path: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub fn create_file(window: tauri::WebviewWindow, state: tauri::State<'_, ProjectRootState>, path: String) -> Result<(), String> {
+pub fn create_file(window: tauri::WebviewWindow, state: tauri::State<'_, ProjectRootState>, path: String) -> Result<(), Localized> {
     validate_path(&path, window.label(), &state)?;
     let p = PathBuf::from(&path);
     if p.exists() {
-        return Err("File already exists".to_string());
+        return Err(Localized::new("file-exists", &[]));
     }
+    let create_failed = |e: std::io::Error| Localized::new("create-failed", &[("path", path.clone()), ("reason", e.to_string())]);
     if let Some(parent) = p.parent() {
-        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        fs::create_dir_all(parent).map_err(create_failed)?;
     }
-    fs::write(&p, "").map_err(|e| e.to_string())
+    fs::write(&p, "").map_err(create_failed)
 }
 
 #[tauri::command]
@@ -307,13 +827,13 @@ pub fn create_folder(
     window: tauri::WebviewWindow,
     state: tauri::State<'_, ProjectRootState>,
     path: String,
-) -> Result<(), String> {
+) -> Result<(), Localized> {
     validate_path(&path, window.label(), &state)?;
     let p = PathBuf::from(&path);
     if p.exists() {
-        return Err("Folder already exists".to_string());
+        return Err(Localized::new("folder-exists", &[]));
     }
-    fs::create_dir_all(&p).map_err(|e| e.to_string())
+    fs::create_dir_all(&p).map_err(|e| Localized::new("create-failed", &[("path", path.clone()), ("reason", e.to_string())]))
 }
 
 #[tauri::command]
@@ -321,7 +841,7 @@ pub fn delete_entries(
     window: tauri::WebviewWindow,
     state: tauri::State<'_, ProjectRootState>,
     paths: Vec<String>,
-) -> Result<(), String> {
+) -> Result<(), Localized> {
     for path in &paths {
         validate_path(path, window.label(), &state)?;
     }
@@ -330,7 +850,7 @@ pub fn delete_entries(
         if !p.exists() {
             continue;
         }
-        trash::delete(&p).map_err(|e| format!("Failed to move to trash: {}", e))?;
+        trash::delete(&p).map_err(|e| Localized::new("trash-failed", &[("reason", e.to_string())]))?;
     }
     Ok(())
 }
@@ -341,45 +861,193 @@ pub fn rename_entry(
     state: tauri::State<'_, ProjectRootState>,
     old_path: String,
     new_path: String,
-) -> Result<(), String> {
-    validate_path(&old_path, window.label(), &state)?;
-    validate_path(&new_path, window.label(), &state)?;
-    fs::rename(&old_path, &new_path).map_err(|e| format!("Failed to rename: {}", e))
+) -> Result<(), Localized> {
+    let from = validate_path(&old_path, window.label(), &state)?;
+    let to = validate_path(&new_path, window.label(), &state)?;
+    fs::rename(&old_path, &new_path).map_err(|e| Localized::new("rename-failed", &[("reason", e.to_string())]))?;
+    journal::record(window.label(), "Rename", vec![journal::Step::moved(from, to)]);
+    Ok(())
+}
+
+/// What to do when a pasted or moved entry's name is already taken in
+/// the destination.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// Move the existing entry to the trash and put the new one in its place.
+    Overwrite,
+    /// Leave the existing entry and don't transfer this source.
+    Skip,
+    /// Transfer under a free "<name> copy" name.
+    KeepBoth,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct EntryConflict {
+    pub source: String,
+    /// The existing entry the source collides with.
+    pub target: String,
+    pub is_dir: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Transfer {
+    Move,
+    Copy,
 }
 
+/// Move or copy `sources` into `dest`, resolving name collisions with
+/// `policy`. Without a policy, copies keep both and moves fail. With
+/// `merge`, a directory landing on an existing directory is transferred
+/// into it entry by entry instead, with `policy` applied to each
+/// collision inside, and is removed once empty. With `dry_run` nothing is
+/// touched; either way the collisions are returned. Completed steps are
+/// appended to `steps` even when a later source fails. Copied
+/// directories follow the symlinks `links` allows.
+#[allow(clippy::too_many_arguments)]
+fn transfer_entries(
+    sources: &[String],
+    dest: &Path,
+    kind: Transfer,
+    policy: Option<ConflictPolicy>,
+    merge: bool,
+    dry_run: bool,
+    steps: &mut Vec<journal::Step>,
+    links: &mut symlinks::Symlinks,
+) -> Result<Vec<EntryConflict>, Localized> {
+    if !dest.is_dir() {
+        return Err(Localized::new("dest-not-dir", &[]));
+    }
+    let mut conflicts = Vec::new();
+    for src in sources {
+        let invalid_source = || Localized::new("source-invalid", &[("path", src.clone())]);
+        let src_path = match kind {
+            Transfer::Move => fs::canonicalize(src).map_err(|_| invalid_source())?,
+            Transfer::Copy => PathBuf::from(src),
+        };
+        if kind == Transfer::Move && dest.starts_with(&src_path) {
+            return Err(Localized::new("move-into-itself", &[("path", src.clone())]));
+        }
+        let file_name = src_path.file_name().ok_or_else(invalid_source)?;
+        let mut target = dest.join(file_name);
+
+        if fs::symlink_metadata(&target).is_ok() {
+            let same = fs::canonicalize(&target).ok() == fs::canonicalize(&src_path).ok();
+            if same && kind == Transfer::Move {
+                continue;
+            }
+            let both_dirs = [&src_path, &target].iter().all(|p| fs::symlink_metadata(p).is_ok_and(|m| m.is_dir()));
+            if merge && both_dirs && !same {
+                let children: Vec<String> = fs::read_dir(&src_path)
+                    .map_err(|_| invalid_source())?
+                    .flatten()
+                    .map(|e| e.path().to_string_lossy().into_owned())
+                    .collect();
+                conflicts.extend(transfer_entries(&children, &target, kind, policy, true, dry_run, steps, links)?);
+                if kind == Transfer::Move && !dry_run {
+                    // Entries skipped by the policy keep the source in place.
+                    let _ = fs::remove_dir(&src_path);
+                }
+                continue;
+            }
+            conflicts.push(EntryConflict {
+                source: src.clone(),
+                target: target.to_string_lossy().into_owned(),
+                is_dir: target.is_dir(),
+            });
+            if dry_run {
+                continue;
+            }
+            match (policy, kind) {
+                (Some(ConflictPolicy::Skip), _) => continue,
+                (Some(ConflictPolicy::Overwrite), _) if same => continue,
+                (Some(ConflictPolicy::Overwrite), _) => {
+                    trash::delete(&target).map_err(|e| Localized::new("trash-failed", &[("reason", e.to_string())]))?;
+                }
+                (Some(ConflictPolicy::KeepBoth), _) | (None, Transfer::Copy) => {
+                    let is_dir = src_path.is_dir();
+                    let (stem, ext) = if is_dir {
+                        (file_name.to_string_lossy().into_owned(), String::new())
+                    } else {
+                        let stem = target.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+                        (stem, target.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default())
+                    };
+                    target = next_copy_name(dest, &stem, &ext, is_dir)?;
+                }
+                (None, Transfer::Move) => {
+                    return Err(Localized::new("target-exists", &[("name", file_name.to_string_lossy().into_owned())]));
+                }
+            }
+        }
+        if dry_run {
+            continue;
+        }
+
+        match kind {
+            Transfer::Move => {
+                fs::rename(&src_path, &target)
+                    .map_err(|e| Localized::new("move-failed", &[("path", src.clone()), ("reason", e.to_string())]))?;
+                steps.push(journal::Step::moved(src_path, target));
+            }
+            Transfer::Copy => {
+                let copy_failed = |reason: String| Localized::new("copy-failed", &[("path", src.clone()), ("reason", reason)]);
+                if src_path.is_dir() {
+                    copy_dir_recursive(&src_path, &target, links).map_err(copy_failed)?;
+                } else {
+                    fs::copy(&src_path, &target).map_err(|e| copy_failed(e.to_string()))?;
+                }
+                steps.push(journal::Step::created(target));
+            }
+        }
+    }
+    Ok(conflicts)
+}
+
+/// Move `sources` into `dest_dir`. A name already taken in the
+/// destination is an error unless `on_conflict` says otherwise; with
+/// `merge`, a folder moved onto an existing folder is merged into it and
+/// `on_conflict` applies to each file inside. With `dry_run` only the
+/// collisions are reported, so the UI can ask first.
 #[tauri::command]
 pub fn move_entries(
     window: tauri::WebviewWindow,
     state: tauri::State<'_, ProjectRootState>,
     sources: Vec<String>,
     dest_dir: String,
-) -> Result<(), String> {
+    on_conflict: Option<ConflictPolicy>,
+    merge: Option<bool>,
+    dry_run: Option<bool>,
+) -> Result<Vec<EntryConflict>, Localized> {
     for src in &sources {
         validate_path(src, window.label(), &state)?;
     }
-    validate_path(&dest_dir, window.label(), &state)?;
-
-    let dest = fs::canonicalize(&dest_dir).map_err(|e| format!("Invalid destination: {}", e))?;
-    if !dest.is_dir() {
-        return Err("Destination is not a directory".into());
-    }
+    let dest = validate_path(&dest_dir, window.label(), &state)?;
+
+    let mut steps = Vec::new();
+    let result = transfer_entries(
+        &sources,
+        &dest,
+        Transfer::Move,
+        on_conflict,
+        merge.unwrap_or(false),
+        dry_run.unwrap_or(false),
+        &mut steps,
+        &mut symlinks::Symlinks::default(),
+    );
+    journal::record(window.label(), "Move", steps);
+    result
+}
 
-    for src in &sources {
-        let src_path = fs::canonicalize(src).map_err(|e| format!("Invalid source: {}", e))?;
-        if dest.starts_with(&src_path) {
-            return Err(format!(
-                "Cannot move '{}' into itself or a subdirectory",
-                src
-            ));
-        }
-        let file_name = src_path.file_name().ok_or("Invalid source file name")?;
-        let dst_path = dest.join(file_name);
-        if src_path == dst_path {
-            continue;
-        }
-        fs::rename(&src_path, &dst_path).map_err(|e| format!("Failed to move '{}': {}", src, e))?;
-    }
-    Ok(())
+/// Whether `path` lies in a credentials folder files are never imported
+/// from or exported to.
+fn in_sensitive_dir(path: &Path) -> bool {
+    let blocked = [".ssh", ".gnupg", ".aws"];
+    path.components().any(|c| {
+        matches!(
+            c,
+            std::path::Component::Normal(name) if blocked.iter().any(|b| name == std::ffi::OsStr::new(b))
+        )
+    })
 }
 
 #[tauri::command]
@@ -394,21 +1062,15 @@ pub fn import_external_files(
     if !dest.is_dir() {
         return Err("Destination is not a directory".to_string());
     }
-    for src in sources {
-        let src_path = PathBuf::from(&src);
+    let mut steps = Vec::new();
+    let result = sources.iter().try_for_each(|src| {
+        let src_path = PathBuf::from(src);
         if !src_path.exists() {
             return Err(format!("Source does not exist: {}", src));
         }
         let canonical_src =
             fs::canonicalize(&src_path).map_err(|e| format!("Invalid source: {}", e))?;
-        let blocked = [".ssh", ".gnupg", ".aws"];
-        let is_sensitive = canonical_src.components().any(|c| {
-            matches!(
-                c,
-                std::path::Component::Normal(name) if blocked.iter().any(|b| name == std::ffi::OsStr::new(b))
-            )
-        });
-        if is_sensitive {
+        if in_sensitive_dir(&canonical_src) {
             return Err(format!("Cannot import from sensitive directory: {}", src));
         }
         let file_name = src_path
@@ -421,63 +1083,148 @@ pub fn import_external_files(
             target = next_copy_name(&dest, &stem, &ext, src_path.is_dir())?;
         }
         if src_path.is_dir() {
-            copy_dir_recursive(&src_path, &target)
+            copy_dir_recursive(&src_path, &target, &mut symlinks::Symlinks::default())
                 .map_err(|e| format!("Failed to copy {}: {}", src, e))?;
         } else {
             fs::copy(&src_path, &target).map_err(|e| format!("Failed to copy {}: {}", src, e))?;
         }
+        steps.push(journal::Step::created(target));
+        Ok(())
+    });
+    journal::record(window.label(), "Import", steps);
+    result
+}
+
+/// Copy `src` to `dest`, an absolute path anywhere on disk. The copy goes
+/// to a temp file beside `dest` first and is renamed into place, so an
+/// existing file there is replaced only by a complete one.
+fn export_to(src: &Path, dest: &Path) -> Result<(), String> {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    static NEXT_TMP: AtomicU64 = AtomicU64::new(0);
+
+    let invalid = || "Invalid export path".to_string();
+    if !dest.is_absolute() || dest.is_dir() {
+        return Err(invalid());
+    }
+    let name = dest.file_name().ok_or_else(invalid)?;
+    let dir = dest.parent().and_then(|d| fs::canonicalize(d).ok()).ok_or("Export folder does not exist")?;
+    if in_sensitive_dir(&dir) {
+        return Err(format!("Cannot export to sensitive directory: {}", dir.display()));
+    }
+    let n = NEXT_TMP.fetch_add(1, Ordering::Relaxed);
+    let tmp = dir.join(format!(".{}.{}-{}.tmp", name.to_string_lossy(), std::process::id(), n));
+    if let Err(e) = fs::copy(src, &tmp).and_then(|_| fs::rename(&tmp, dir.join(name))) {
+        let _ = fs::remove_file(&tmp);
+        return Err(format!("Failed to export {}: {}", src.display(), e));
     }
     Ok(())
 }
 
+/// Copy the project file `src_path` to `dest_path`, which may be outside
+/// the project, e.g. a location picked in a save dialog. An existing file
+/// at `dest_path` is replaced.
 #[tauri::command]
-pub fn paste_entries(
+pub fn export_file(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    src_path: String,
+    dest_path: String,
+) -> Result<(), String> {
+    let src = validate_path(&src_path, window.label(), &state)?;
+    if !src.is_file() {
+        return Err("Only files can be exported".to_string());
+    }
+    export_to(&src, Path::new(&dest_path))
+}
+
+/// Copy `sources` into `dest_dir`. Names already taken get a " copy"
+/// suffix unless `on_conflict` says otherwise; with `dry_run` only the
+/// collisions are reported. With `follow_symlinks`, links inside copied
+/// directories that resolve within the project are copied as their
+/// targets instead of being skipped. A real copy runs as a background
+/// operation that can be cancelled between sources.
+#[tauri::command]
+pub async fn paste_entries(
     window: tauri::WebviewWindow,
     state: tauri::State<'_, ProjectRootState>,
     sources: Vec<String>,
     dest_dir: String,
-) -> Result<(), String> {
+    on_conflict: Option<ConflictPolicy>,
+    dry_run: Option<bool>,
+    follow_symlinks: Option<bool>,
+) -> Result<Vec<EntryConflict>, Localized> {
+    let root = project_root_async(window.label(), &state).await?;
     for src in &sources {
-        validate_path(src, window.label(), &state)?;
-    }
-    validate_path(&dest_dir, window.label(), &state)?;
-    let dest = PathBuf::from(&dest_dir);
-    if !dest.is_dir() {
-        return Err("Destination is not a directory".to_string());
+        resolve_within_root(src, &root)?;
     }
-    for src in sources {
-        let src_path = PathBuf::from(&src);
-        let file_name = src_path
-            .file_name()
-            .ok_or_else(|| format!("Invalid source path: {}", src))?;
-        let mut target = dest.join(file_name);
-        if target.exists() {
-            let stem = target.file_stem().unwrap_or_default().to_string_lossy().to_string();
-            let ext = target.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
-            target = next_copy_name(&dest, &stem, &ext, src_path.is_dir())?;
+    let dest = resolve_within_root(&dest_dir, &root)?;
+    let dry_run = dry_run.unwrap_or(false);
+    let label = window.label().to_string();
+    let op = (!dry_run)
+        .then(|| operations::manager().start(OperationKind::Copy, &dest.to_string_lossy(), Some(&label), true));
+
+    tokio::task::spawn_blocking(move || {
+        let mut links = symlinks::Symlinks::requested(follow_symlinks, &root);
+        let (mut steps, mut conflicts) = (Vec::new(), Vec::new());
+        let mut result = Ok(());
+        for (i, src) in sources.iter().enumerate() {
+            if let Some(op) = &op {
+                if op.is_cancelled() {
+                    result = Err(Localized::new("copy-cancelled", &[]));
+                    break;
+                }
+                let name = Path::new(src).file_name().map(|n| n.to_string_lossy().into_owned());
+                op.progress(i as u64, Some(sources.len() as u64), name);
+            }
+            match transfer_entries(
+                std::slice::from_ref(src),
+                &dest,
+                Transfer::Copy,
+                on_conflict,
+                false,
+                dry_run,
+                &mut steps,
+                &mut links,
+            ) {
+                Ok(found) => conflicts.extend(found),
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
         }
-        if src_path.is_dir() {
-            copy_dir_recursive(&src_path, &target)
-                .map_err(|e| format!("Failed to copy {}: {}", src, e))?;
-        } else {
-            fs::copy(&src_path, &target).map_err(|e| format!("Failed to copy {}: {}", src, e))?;
+        // What was copied before a cancel or failure stays, and can be undone.
+        journal::record(&label, "Paste", steps);
+        let result = result.map(|()| conflicts);
+        match &op {
+            Some(op) => op.track(result),
+            None => result,
         }
-    }
-    Ok(())
+    })
+    .await
+    .map_err(|e| Localized::from(format!("Paste failed: {}", e)))?
 }
 
+/// Copy `path` beside itself under the next free " copy" name. See
+/// `paste_entries` for `follow_symlinks`.
 #[tauri::command]
 pub fn duplicate_entry(
     window: tauri::WebviewWindow,
     state: tauri::State<'_, ProjectRootState>,
     path: String,
-) -> Result<(), String> {
+    follow_symlinks: Option<bool>,
+) -> Result<(), Localized> {
     validate_path(&path, window.label(), &state)?;
+    let root = state
+        .blocking_read()
+        .get(window.label())
+        .and_then(|o| o.clone())
+        .ok_or_else(|| Localized::new("no-project-open", &[]))?;
     let src_path = PathBuf::from(&path);
     if !src_path.exists() {
-        return Err("Path does not exist".to_string());
+        return Err(Localized::new("path-not-found", &[]));
     }
-    let parent = src_path.parent().ok_or("No parent directory")?;
+    let parent = src_path.parent().ok_or_else(|| Localized::new("no-parent-dir", &[]))?;
     let stem = src_path.file_stem().unwrap_or_default().to_string_lossy().to_string();
     let is_dir = src_path.is_dir();
     let ext = if is_dir {
@@ -489,10 +1236,12 @@ pub fn duplicate_entry(
     let target = next_copy_name(parent, &stem, &ext, is_dir)?;
 
     if is_dir {
-        copy_dir_recursive(&src_path, &target)?;
+        copy_dir_recursive(&src_path, &target, &mut symlinks::Symlinks::requested(follow_symlinks, &root))?;
     } else {
-        fs::copy(&src_path, &target).map_err(|e| format!("Failed to duplicate: {}", e))?;
+        fs::copy(&src_path, &target)
+            .map_err(|e| Localized::new("copy-failed", &[("path", path.clone()), ("reason", e.to_string())]))?;
     }
+    journal::record(window.label(), "Duplicate", vec![journal::Step::created(target)]);
     Ok(())
 }
 
@@ -532,15 +1281,52 @@ pub fn reveal_in_file_manager(
     Ok(())
 }
 
+/// Open `path` with the system's default application, for PDFs,
+/// spreadsheets and design files the editor can't show. Programs and
+/// scripts would run instead of opening, so they fail with
+/// `EXECUTABLE:` unless `confirm` is set.
+#[tauri::command]
+pub fn open_externally(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: String,
+    confirm: Option<bool>,
+) -> Result<(), String> {
+    let canonical = validate_path(&path, window.label(), &state)?;
+    if !confirm.unwrap_or(false) {
+        // A folder can be an app bundle too; only its name says so.
+        let sample = if canonical.is_file() { filetype::read_sample(&canonical)?.0 } else { Vec::new() };
+        if filetype::is_runnable(&canonical, &sample) {
+            return Err(format!(
+                "EXECUTABLE: {} is a program or script; opening it would run it",
+                canonical.file_name().unwrap_or_default().to_string_lossy()
+            ));
+        }
+    }
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = Command::new("explorer");
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = Command::new("xdg-open");
+    command
+        .arg(&canonical)
+        .spawn()
+        .map_err(|e| format!("Failed to open {}: {}", canonical.display(), e))?;
+    Ok(())
+}
+
 // ── Directory copy helper ────────────────────────────────────────
 
 const MAX_COPY_DEPTH: u32 = 50;
 
-fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<(), String> {
-    copy_dir_recursive_inner(src, dst, 0)
+/// Symlinks inside `src` are skipped unless `links` follows them, in
+/// which case their targets are copied as real files and directories.
+fn copy_dir_recursive(src: &Path, dst: &Path, links: &mut symlinks::Symlinks) -> Result<(), String> {
+    copy_dir_recursive_inner(src, dst, 0, links)
 }
 
-fn copy_dir_recursive_inner(src: &Path, dst: &Path, depth: u32) -> Result<(), String> {
+fn copy_dir_recursive_inner(src: &Path, dst: &Path, depth: u32, links: &mut symlinks::Symlinks) -> Result<(), String> {
     if depth > MAX_COPY_DEPTH {
         return Err("Maximum directory depth exceeded during copy".to_string());
     }
@@ -550,12 +1336,12 @@ fn copy_dir_recursive_inner(src: &Path, dst: &Path, depth: u32) -> Result<(), St
         let src_path = entry.path();
         let dst_path = dst.join(entry.file_name());
         if let Ok(meta) = fs::symlink_metadata(&src_path) {
-            if meta.file_type().is_symlink() {
+            if meta.file_type().is_symlink() && links.follow(&src_path).is_none() {
                 continue;
             }
         }
         if src_path.is_dir() {
-            copy_dir_recursive_inner(&src_path, &dst_path, depth + 1)?;
+            copy_dir_recursive_inner(&src_path, &dst_path, depth + 1, links)?;
         } else {
             fs::copy(&src_path, &dst_path).map_err(|e| e.to_string())?;
         }
@@ -565,23 +1351,77 @@ fn copy_dir_recursive_inner(src: &Path, dst: &Path, depth: u32) -> Result<(), St
 
 // ── File listing ─────────────────────────────────────────────────
 
+/// Every file under `path`, relative to it. Served from the window's
+/// file index, which the watcher keeps current. The index never follows
+/// symlinks, so `follow_symlinks` walks the directory instead.
 #[tauri::command]
 pub fn list_all_files(
     window: tauri::WebviewWindow,
     state: tauri::State<'_, ProjectRootState>,
+    indexes: tauri::State<'_, index::FileIndexState>,
     path: String,
+    show_hidden: Option<bool>,
+    skip: Option<Vec<String>>,
+    follow_symlinks: Option<bool>,
 ) -> Result<Vec<String>, String> {
-    validate_path(&path, window.label(), &state)?;
-    let root = PathBuf::from(&path);
-    let mut files = Vec::new();
-    collect_files(&root, &root, &mut files, 0);
+    let dir = validate_path(&path, window.label(), &state)?;
+    let root = state.blocking_read().get(window.label()).and_then(|o| o.clone()).ok_or("No project is open")?;
+    let mut files = match dir.strip_prefix(&root) {
+        Ok(rel) if follow_symlinks != Some(true) => indexes.get(window.label(), &root)?.list(rel),
+        _ => {
+            let mut files = Vec::new();
+            let mut links = symlinks::Symlinks::requested(follow_symlinks, &root);
+            collect_files(&dir, &dir, &mut files, 0, &exclude::Excludes::for_project(&root), &mut links);
+            files
+        }
+    };
+    hidden::HiddenFilter::new(show_hidden, skip).retain_visible(&dir, &mut files);
     Ok(files)
 }
 
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct RecentFile {
+    /// Relative to the project root.
+    pub path: String,
+    pub size: u64,
+    /// Seconds since the Unix epoch.
+    pub mtime: u64,
+}
+
+/// Files under `path` (default: the whole project) modified at or after
+/// `since` (seconds since the Unix epoch; default: ever), newest first and
+/// at most `limit` (default 50). Served from the file index, so nothing
+/// is walked.
+#[tauri::command]
+pub fn recently_modified_files(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    indexes: tauri::State<'_, index::FileIndexState>,
+    path: Option<String>,
+    since: Option<u64>,
+    limit: Option<usize>,
+) -> Result<Vec<RecentFile>, String> {
+    let root = state.blocking_read().get(window.label()).and_then(|o| o.clone()).ok_or("No project is open")?;
+    let dir = match path {
+        Some(p) => validate_path(&p, window.label(), &state)?,
+        None => root.clone(),
+    };
+    let rel = dir.strip_prefix(&root).map_err(|_| "Path is outside the project".to_string())?;
+    let recent = indexes.get(window.label(), &root)?.recent(rel, since.unwrap_or(0), limit.unwrap_or(50));
+    Ok(recent.into_iter().map(|(path, meta)| RecentFile { path, size: meta.size, mtime: meta.mtime }).collect())
+}
+
 const MAX_COLLECT_DEPTH: u32 = 100;
 const MAX_COLLECT_FILES: usize = 100_000;
 
-fn collect_files(root: &Path, dir: &Path, out: &mut Vec<String>, depth: u32) {
+fn collect_files(
+    root: &Path,
+    dir: &Path,
+    out: &mut Vec<String>,
+    depth: u32,
+    excludes: &exclude::Excludes,
+    links: &mut symlinks::Symlinks,
+) {
     if depth > MAX_COLLECT_DEPTH || out.len() >= MAX_COLLECT_FILES {
         return;
     }
@@ -594,19 +1434,27 @@ fn collect_files(root: &Path, dir: &Path, out: &mut Vec<String>, depth: u32) {
             return;
         }
         let name = entry.file_name().to_string_lossy().to_string();
-        if name == ".git" || name == "node_modules" || name == "target" || name == ".DS_Store" {
+        if name == ".git" {
             continue;
         }
         let ft = match entry.file_type() {
             Ok(ft) => ft,
             Err(_) => continue,
         };
-        if ft.is_symlink() {
+        let path = entry.path();
+        let is_dir = if ft.is_symlink() {
+            match links.follow(&path) {
+                Some(target) => target.is_dir(),
+                None => continue,
+            }
+        } else {
+            ft.is_dir()
+        };
+        if excludes.is_excluded(&path, is_dir) {
             continue;
         }
-        let path = entry.path();
-        if ft.is_dir() {
-            collect_files(root, &path, out, depth + 1);
+        if is_dir {
+            collect_files(root, &path, out, depth + 1, excludes, links);
         } else {
             if let Ok(rel) = path.strip_prefix(root) {
                 out.push(rel.to_string_lossy().to_string());
@@ -620,6 +1468,191 @@ fn collect_files(root: &Path, dir: &Path, out: &mut Vec<String>, depth: u32) {
 mod tests {
     use super::*;
 
+    #[test]
+    fn transfers_resolve_name_conflicts() {
+        let dir = tempfile::tempdir().unwrap();
+        let (src, dest) = (dir.path().join("src"), dir.path().join("dest"));
+        fs::create_dir_all(&src).unwrap();
+        fs::create_dir_all(&dest).unwrap();
+        fs::write(src.join("a.txt"), "new").unwrap();
+        fs::write(src.join("b.txt"), "b").unwrap();
+        fs::write(dest.join("a.txt"), "old").unwrap();
+        let sources = ["a.txt", "b.txt"].map(|n| src.join(n).to_string_lossy().into_owned());
+        let mut steps = Vec::new();
+        let mut links = symlinks::Symlinks::default();
+
+        // A dry run reports the collision and touches nothing.
+        let conflicts = transfer_entries(&sources, &dest, Transfer::Copy, None, false, true, &mut steps, &mut links).unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].target, dest.join("a.txt").to_string_lossy());
+        assert!(steps.is_empty() && !dest.join("b.txt").exists());
+
+        // Moves fail on a collision unless told otherwise.
+        let err = transfer_entries(&sources, &dest, Transfer::Move, None, false, false, &mut steps, &mut links).unwrap_err();
+        assert_eq!(err.id, "target-exists");
+        transfer_entries(&sources, &dest, Transfer::Move, Some(ConflictPolicy::Skip), false, false, &mut steps, &mut links).unwrap();
+        assert_eq!(fs::read_to_string(dest.join("a.txt")).unwrap(), "old");
+        assert!(src.join("a.txt").exists() && !src.join("b.txt").exists() && dest.join("b.txt").exists());
+
+        // Copies keep both by default.
+        transfer_entries(&sources[..1], &dest, Transfer::Copy, None, false, false, &mut steps, &mut links).unwrap();
+        assert_eq!(fs::read_to_string(dest.join("a copy.txt")).unwrap(), "new");
+        assert_eq!(steps.len(), 2);
+
+        // Moving an entry onto itself is not a conflict.
+        let own = [dest.join("a.txt").to_string_lossy().into_owned()];
+        assert!(transfer_entries(&own, &dest, Transfer::Move, None, false, true, &mut steps, &mut links).unwrap().is_empty());
+    }
+
+    #[test]
+    fn merged_moves_apply_the_policy_per_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let (src, dest) = (dir.path().join("in/assets"), dir.path().join("out"));
+        fs::create_dir_all(src.join("img")).unwrap();
+        fs::create_dir_all(dest.join("assets/img")).unwrap();
+        fs::write(src.join("img/logo.png"), "new").unwrap();
+        fs::write(src.join("img/icon.png"), "icon").unwrap();
+        fs::write(src.join("readme.md"), "readme").unwrap();
+        fs::write(dest.join("assets/img/logo.png"), "old").unwrap();
+        let sources = [src.to_string_lossy().into_owned()];
+        let mut steps = Vec::new();
+        let mut links = symlinks::Symlinks::default();
+
+        // Only the file that collides is reported, not the folders.
+        let conflicts = transfer_entries(&sources, &dest, Transfer::Move, None, true, true, &mut steps, &mut links).unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].target, dest.join("assets/img/logo.png").to_string_lossy());
+
+        let policy = Some(ConflictPolicy::Skip);
+        transfer_entries(&sources, &dest, Transfer::Move, policy, true, false, &mut steps, &mut links).unwrap();
+        assert_eq!(fs::read_to_string(dest.join("assets/img/logo.png")).unwrap(), "old");
+        assert!(dest.join("assets/img/icon.png").exists() && dest.join("assets/readme.md").exists());
+        // The skipped file keeps its folders in place.
+        assert!(src.join("img/logo.png").exists() && !src.join("readme.md").exists());
+        assert_eq!(steps.len(), 2);
+    }
+
+    #[test]
+    fn path_errors_carry_message_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("project");
+        fs::create_dir(&root).unwrap();
+        let root = root.canonicalize().unwrap();
+
+        let outside = resolve_within_root(&dir.path().join("secret.txt").to_string_lossy(), &root).unwrap_err();
+        assert_eq!(outside.id, "path-outside-project");
+        assert!(resolve_within_root(&root.join("new/file.rs").to_string_lossy(), &root).is_ok());
+    }
+
+    #[test]
+    fn text_range_trims_partial_characters() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("app.log");
+        fs::write(&file, "héllo wörld\n").unwrap();
+
+        // Byte 2 is the second half of "é"; byte 8 the first half of "ö".
+        let range = text_range(read_range(&file, 2, 7).unwrap());
+        assert_eq!((range.offset, range.text.as_str(), range.next_offset), (3, "llo w", 8));
+        let rest = text_range(read_range(&file, range.next_offset, 100).unwrap());
+        assert_eq!((rest.text.as_str(), rest.next_offset, rest.total_size), ("örld\n", 14, 14));
+    }
+
+    #[test]
+    fn read_range_pages_through_binary_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("fw.bin");
+        let data: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+        fs::write(&file, &data).unwrap();
+
+        let page = read_range(&file, 256, 16).unwrap();
+        assert_eq!((page.offset, page.total_size), (256, 1000));
+        assert_eq!(page.bytes, (0..16u8).collect::<Vec<_>>());
+        assert_eq!(read_range(&file, 990, 64).unwrap().bytes, data[990..].to_vec());
+        let past_end = read_range(&file, 5000, 16).unwrap();
+        assert_eq!((past_end.offset, past_end.bytes.len()), (1000, 0));
+    }
+
+    #[test]
+    fn write_atomic_replaces_content_and_keeps_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("main.rs");
+        fs::write(&path, "old").unwrap();
+
+        backup_file(&path).unwrap();
+        write_atomic(&path, b"new").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(fs::read_to_string(dir.path().join("main.rs.bak")).unwrap(), "old");
+        let leftovers: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().ends_with(".tmp"))
+            .collect();
+        assert!(leftovers.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_atomic_keeps_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.sh");
+        fs::write(&path, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        write_atomic(&path, b"#!/bin/sh\necho hi\n").unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o755);
+    }
+
+    #[test]
+    fn write_batch_reports_each_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = fs::canonicalize(dir.path()).unwrap();
+        fs::write(root.join("a.txt"), "a").unwrap();
+        fs::write(root.join("b.txt"), "b").unwrap();
+        let file = |name: &str, hash: Option<&str>| FileWrite {
+            path: root.join(name).to_string_lossy().into_owned(),
+            content: format!("{} saved", name),
+            expected_mtime: None,
+            expected_hash: hash.map(str::to_string),
+        };
+
+        let results = write_batch(&root, vec![file("a.txt", None), file("b.txt", Some("stale")), file("../x.txt", None)], false);
+        assert!(results[0].version.is_some());
+        assert!(results[1].error.as_deref().unwrap().starts_with("FILE_CHANGED:"));
+        assert!(results[2].error.is_some());
+        assert_eq!(fs::read_to_string(root.join("a.txt")).unwrap(), "a.txt saved");
+        assert_eq!(fs::read_to_string(root.join("b.txt")).unwrap(), "b");
+    }
+
+    #[test]
+    fn check_unchanged_detects_external_edits() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        fs::write(&path, "one").unwrap();
+        let seen = file_version(&path).unwrap();
+
+        assert!(check_unchanged(&path, None, None).is_ok());
+        assert!(check_unchanged(&path, Some(seen.mtime_ms), None).is_ok());
+        assert!(check_unchanged(&path, Some(seen.mtime_ms + 1), Some(&seen.hash)).is_ok());
+
+        fs::write(&path, "two").unwrap();
+        let err = check_unchanged(&path, None, Some(&seen.hash)).unwrap_err();
+        assert!(err.starts_with("FILE_CHANGED:"));
+
+        fs::remove_file(&path).unwrap();
+        assert!(check_unchanged(&path, Some(seen.mtime_ms), None).is_err());
+    }
+
+    #[test]
+    fn file_versions_flag_generated_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let (plain, lockfile) = (dir.path().join("a.txt"), dir.path().join("Cargo.lock"));
+        fs::write(&plain, "one").unwrap();
+        fs::write(&lockfile, "# This file is automatically @generated by Cargo.\n").unwrap();
+        assert_eq!(file_version(&plain).unwrap().generated, None);
+        assert_eq!(file_version(&lockfile).unwrap().generated, Some(generated::GeneratedReason::Lockfile));
+    }
+
     #[test]
     fn create_project_root_state_starts_empty() {
         let state = create_project_root_state();
@@ -698,6 +1731,11 @@ mod tests {
         std::os::unix::fs::symlink(target, link)
     }
 
+    #[cfg(unix)]
+    fn no_excludes(root: &Path) -> exclude::Excludes {
+        exclude::Excludes::new(root, &[]).unwrap()
+    }
+
     #[cfg(unix)]
     fn find<'a>(entries: &'a [FileEntry], name: &str) -> Option<&'a FileEntry> {
         entries.iter().find(|e| e.name == name)
@@ -713,8 +1751,16 @@ mod tests {
         symlink(&target, &link).unwrap();
 
         let mut visited = std::collections::HashSet::new();
-        let entries =
-            read_dir_recursive(dir.path(), 0, 5, &mut visited).expect("walk");
+        let entries = read_dir_recursive(
+            dir.path(),
+            0,
+            5,
+            &mut visited,
+            &no_excludes(dir.path()),
+            &Default::default(),
+            &mut Default::default(),
+        )
+        .expect("walk");
 
         let real = find(&entries, "real.txt").expect("real entry present");
         assert!(!real.is_symlink, "regular file must not be flagged as symlink");
@@ -738,8 +1784,16 @@ mod tests {
         symlink(&real_dir, &link_dir).unwrap();
 
         let mut visited = std::collections::HashSet::new();
-        let entries =
-            read_dir_recursive(dir.path(), 0, 5, &mut visited).expect("walk");
+        let entries = read_dir_recursive(
+            dir.path(),
+            0,
+            5,
+            &mut visited,
+            &no_excludes(dir.path()),
+            &Default::default(),
+            &mut Default::default(),
+        )
+        .expect("walk");
 
         let real = find(&entries, "real_dir").expect("real_dir present");
         assert!(real.is_dir);
@@ -777,8 +1831,16 @@ mod tests {
         symlink(&parent, &cycle_link).unwrap();
 
         let mut visited = std::collections::HashSet::new();
-        let entries =
-            read_dir_recursive(&parent, 0, 5, &mut visited).expect("walk");
+        let entries = read_dir_recursive(
+            &parent,
+            0,
+            5,
+            &mut visited,
+            &no_excludes(&parent),
+            &Default::default(),
+            &mut Default::default(),
+        )
+        .expect("walk");
 
         // The walk completes (no infinite loop) and reports the link.
         let loop_entry = find(&entries, "loop").expect("loop entry present");
@@ -799,8 +1861,16 @@ mod tests {
         symlink(Path::new("/no/such/path/leo-dangling-target"), &link).unwrap();
 
         let mut visited = std::collections::HashSet::new();
-        let entries =
-            read_dir_recursive(dir.path(), 0, 5, &mut visited).expect("walk");
+        let entries = read_dir_recursive(
+            dir.path(),
+            0,
+            5,
+            &mut visited,
+            &no_excludes(dir.path()),
+            &Default::default(),
+            &mut Default::default(),
+        )
+        .expect("walk");
 
         let dangling = find(&entries, "dangling").expect("dangling entry present");
         assert!(dangling.is_symlink);
@@ -808,4 +1878,114 @@ mod tests {
         // file (is_dir=false). Better than panicking.
         assert!(!dangling.is_dir);
     }
+
+    #[test]
+    fn collect_files_applies_exclude_patterns() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("web/dist")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        std::fs::write(root.join("web/dist/app.js"), "").unwrap();
+        std::fs::write(root.join("web/main.o"), "").unwrap();
+        std::fs::write(root.join("web/main.c"), "").unwrap();
+        std::fs::write(root.join("node_modules/pkg/index.js"), "").unwrap();
+
+        let patterns = vec!["**/dist".to_string(), "*.o".to_string()];
+        let mut files = Vec::new();
+        collect_files(root, root, &mut files, 0, &exclude::Excludes::new(root, &patterns).unwrap(), &mut Default::default());
+        files.sort();
+        assert_eq!(files, vec!["node_modules/pkg/index.js", "web/main.c"]);
+
+        let defaults: Vec<String> = exclude::DEFAULT_PATTERNS.iter().map(|p| p.to_string()).collect();
+        let mut files = Vec::new();
+        collect_files(root, root, &mut files, 0, &exclude::Excludes::new(root, &defaults).unwrap(), &mut Default::default());
+        assert!(!files.iter().any(|f| f.starts_with("node_modules")));
+    }
+
+    #[test]
+    fn nested_listings_are_capped_and_pages_follow_tree_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let big = dir.path().join("big");
+        fs::create_dir_all(big.join("sub")).unwrap();
+        for i in 0..MAX_NESTED_ENTRIES + 5 {
+            fs::write(big.join(format!("f{:05}", i)), "").unwrap();
+        }
+        let excludes = exclude::Excludes::new(dir.path(), &[]).unwrap();
+
+        let mut visited = std::collections::HashSet::new();
+        let entries =
+            read_dir_recursive(dir.path(), 0, 1, &mut visited, &excludes, &Default::default(), &mut Default::default())
+                .unwrap();
+        assert_eq!(entries[0].child_count, Some(MAX_NESTED_ENTRIES + 6));
+        let children = entries[0].children.as_ref().unwrap();
+        assert_eq!(children.len(), MAX_NESTED_ENTRIES);
+        assert_eq!(children[0].name, "sub");
+        assert_eq!(children[0].child_count, None);
+
+        let mut visited = std::collections::HashSet::new();
+        let all = read_dir_recursive(&big, 0, 0, &mut visited, &excludes, &Default::default(), &mut Default::default())
+            .unwrap();
+        let first = paginate(all.clone(), 0, 500);
+        assert_eq!((first.total, first.entries.len(), first.next_offset), (MAX_NESTED_ENTRIES + 6, 500, Some(500)));
+        assert_eq!(first.entries[0].name, "sub");
+        let last = paginate(all, 1000, 500);
+        assert_eq!((last.entries.len(), last.next_offset), (6, None));
+        assert_eq!(last.entries[0].name, "f00999");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn collect_files_follows_in_root_symlinks_when_asked() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = fs::canonicalize(dir.path()).unwrap();
+        fs::create_dir_all(root.join("packages/core")).unwrap();
+        fs::create_dir_all(root.join("app")).unwrap();
+        fs::write(root.join("packages/core/index.js"), "").unwrap();
+        symlink(&root.join("packages/core"), &root.join("app/core")).unwrap();
+        symlink(&root, &root.join("app/loop")).unwrap();
+
+        let mut files = Vec::new();
+        collect_files(&root, &root, &mut files, 0, &no_excludes(&root), &mut Default::default());
+        assert_eq!(files, vec!["packages/core/index.js"]);
+
+        let mut files = Vec::new();
+        collect_files(&root, &root, &mut files, 0, &no_excludes(&root), &mut symlinks::Symlinks::within(&root));
+        files.sort();
+        assert_eq!(files, vec!["app/core/index.js", "packages/core/index.js"]);
+    }
+
+    #[test]
+    fn exports_files_outside_the_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("proj").join("report.pdf");
+        let out = dir.path().join("Downloads");
+        fs::create_dir_all(src.parent().unwrap()).unwrap();
+        fs::create_dir_all(out.join(".ssh")).unwrap();
+        fs::write(&src, b"%PDF-1.7").unwrap();
+        fs::write(out.join("report.pdf"), b"old").unwrap();
+
+        export_to(&src, &out.join("report.pdf")).unwrap();
+        assert_eq!(fs::read(out.join("report.pdf")).unwrap(), b"%PDF-1.7");
+        assert_eq!(fs::read_dir(&out).unwrap().count(), 2);
+
+        assert!(export_to(&src, Path::new("report.pdf")).is_err());
+        assert!(export_to(&src, &out).is_err());
+        assert!(export_to(&src, &out.join("missing").join("report.pdf")).is_err());
+        assert!(export_to(&src, &out.join(".ssh").join("id_rsa")).unwrap_err().contains("sensitive"));
+        assert!(!out.join(".ssh").join("id_rsa").exists());
+    }
+
+    #[test]
+    fn sniffs_binary_content() {
+        assert_eq!(sniff_binary(b"fn main() {}\n", false), None);
+        assert_eq!(sniff_binary(b"", false), None);
+        assert_eq!(sniff_binary("\u{feff}caf\u{e9}".as_bytes(), false), None);
+        assert_eq!(sniff_binary(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR", false), Some(BinaryReason::NulByte));
+        assert_eq!(sniff_binary(b"caf\xe9 au lait", false), Some(BinaryReason::InvalidUtf8));
+        // A multi-byte character cut off by the sample window is fine.
+        let cut = &"caf\u{e9}".as_bytes()[..4];
+        assert_eq!(sniff_binary(cut, true), None);
+        assert_eq!(sniff_binary(cut, false), Some(BinaryReason::InvalidUtf8));
+        assert_eq!(sniff_binary(b"\x01\x02\x03\x04abc", false), Some(BinaryReason::ControlChars));
+    }
 }
@@ -0,0 +1,201 @@
+//! File permission changes from the file tree.
+//!
+//! On unix `mode` takes what `chmod` takes: an octal mode (`755`) or
+//! symbolic clauses (`+x`, `u+x,go-w`, `a=rX`). A missing `who` means
+//! everyone; the umask is not applied. Windows only has the read-only
+//! attribute, so there `readonly` is the only setting that applies.
+
+use serde::Serialize;
+use std::path::Path;
+
+use super::{validate_path, ProjectRootState};
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct FilePermissions {
+    /// Permission bits (including setuid/setgid/sticky); `None` on Windows.
+    pub mode: Option<u32>,
+    /// `mode` as four octal digits, e.g. "0755".
+    pub octal: Option<String>,
+    /// `ls -l` style, e.g. "rwxr-xr-x".
+    pub symbolic: Option<String>,
+    pub readonly: bool,
+    pub executable: bool,
+}
+
+fn describe(meta: &std::fs::Metadata) -> FilePermissions {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = meta.permissions().mode() & 0o7777;
+        FilePermissions {
+            mode: Some(mode),
+            octal: Some(format!("{:04o}", mode)),
+            symbolic: Some(symbolic(mode)),
+            readonly: meta.permissions().readonly(),
+            executable: mode & 0o111 != 0,
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        FilePermissions { mode: None, octal: None, symbolic: None, readonly: meta.permissions().readonly(), executable: false }
+    }
+}
+
+#[cfg_attr(not(unix), allow(dead_code))]
+fn symbolic(mode: u32) -> String {
+    let mut out = String::with_capacity(9);
+    for (shift, special, special_char) in [(6, 0o4000, 's'), (3, 0o2000, 's'), (0, 0o1000, 't')] {
+        let bits = (mode >> shift) & 0o7;
+        out.push(if bits & 4 != 0 { 'r' } else { '-' });
+        out.push(if bits & 2 != 0 { 'w' } else { '-' });
+        out.push(match (bits & 1 != 0, mode & special != 0) {
+            (true, true) => special_char,
+            (false, true) => special_char.to_ascii_uppercase(),
+            (true, false) => 'x',
+            (false, false) => '-',
+        });
+    }
+    out
+}
+
+/// Apply `spec` (octal or symbolic) to `current`.
+#[cfg_attr(not(unix), allow(dead_code))]
+fn apply_mode(spec: &str, current: u32, is_dir: bool) -> Result<u32, String> {
+    let spec = spec.trim();
+    let invalid = || format!("Invalid mode: {}", spec);
+    if !spec.is_empty() && spec.chars().all(|c| c.is_digit(8)) {
+        let mode = u32::from_str_radix(spec, 8).map_err(|_| invalid())?;
+        return if mode <= 0o7777 { Ok(mode) } else { Err(invalid()) };
+    }
+
+    let mut mode = current & 0o7777;
+    for clause in spec.split(',') {
+        let who_end = clause.find(['+', '-', '=']).ok_or_else(invalid)?;
+        let mut who = 0u32;
+        for c in clause[..who_end].chars() {
+            who |= match c {
+                'u' => 0o4700,
+                'g' => 0o2070,
+                'o' => 0o1007,
+                'a' => 0o7777,
+                _ => return Err(invalid()),
+            };
+        }
+        if who == 0 {
+            who = 0o7777;
+        }
+        let op = clause.as_bytes()[who_end];
+        let mut perms = 0u32;
+        for c in clause[who_end + 1..].chars() {
+            perms |= match c {
+                'r' => 0o444,
+                'w' => 0o222,
+                'x' => 0o111,
+                'X' if is_dir || mode & 0o111 != 0 => 0o111,
+                'X' => 0,
+                's' => 0o6000,
+                't' => 0o1000,
+                _ => return Err(invalid()),
+            };
+        }
+        let bits = perms & who;
+        mode = match op {
+            b'+' => mode | bits,
+            b'-' => mode & !bits,
+            // `=` resets the rwx bits of the named classes; special bits
+            // only change when mentioned.
+            _ => (mode & !(who & 0o777)) | bits,
+        };
+    }
+    Ok(mode)
+}
+
+fn set_permissions(path: &Path, mode: Option<&str>, readonly: Option<bool>) -> Result<FilePermissions, String> {
+    let meta = std::fs::metadata(path).map_err(|e| format!("Failed to read permissions: {}", e))?;
+    let mut perms = meta.permissions();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Some(spec) = mode {
+            perms.set_mode(apply_mode(spec, perms.mode(), meta.is_dir())?);
+        }
+        if let Some(readonly) = readonly {
+            // Like the file manager toggle: only the owner's write bit.
+            let m = perms.mode();
+            perms.set_mode(if readonly { m & !0o200 } else { m | 0o200 });
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        if mode.is_some() {
+            return Err("Permission modes are not supported on this platform; use readonly".to_string());
+        }
+        if let Some(readonly) = readonly {
+            perms.set_readonly(readonly);
+        }
+    }
+    std::fs::set_permissions(path, perms).map_err(|e| format!("Failed to set permissions: {}", e))?;
+    let meta = std::fs::metadata(path).map_err(|e| format!("Failed to read permissions: {}", e))?;
+    Ok(describe(&meta))
+}
+
+// ── Commands ──
+
+#[tauri::command]
+pub fn get_file_permissions(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: String,
+) -> Result<FilePermissions, String> {
+    let path = validate_path(&path, window.label(), &state)?;
+    let meta = std::fs::metadata(&path).map_err(|e| format!("Failed to read permissions: {}", e))?;
+    Ok(describe(&meta))
+}
+
+/// Change the permissions of `path`, e.g. `mode: "+x"` to make a script
+/// executable, and return the result.
+#[tauri::command]
+pub fn set_file_permissions(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: String,
+    mode: Option<String>,
+    readonly: Option<bool>,
+) -> Result<FilePermissions, String> {
+    let path = validate_path(&path, window.label(), &state)?;
+    set_permissions(&path, mode.as_deref(), readonly)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applies_octal_and_symbolic_modes() {
+        assert_eq!(apply_mode("755", 0o644, false), Ok(0o755));
+        assert_eq!(apply_mode("+x", 0o644, false), Ok(0o755));
+        assert_eq!(apply_mode("u+x,go-r", 0o644, false), Ok(0o700));
+        assert_eq!(apply_mode("a=rX", 0o640, true), Ok(0o555));
+        assert_eq!(apply_mode("a=rX", 0o640, false), Ok(0o444));
+        assert_eq!(apply_mode("g+s", 0o755, true), Ok(0o2755));
+        assert!(apply_mode("u+q", 0o644, false).is_err());
+        assert!(apply_mode("99999", 0o644, false).is_err());
+        assert_eq!(symbolic(0o4755), "rwsr-xr-x");
+        assert_eq!(symbolic(0o1644), "rw-r--r-T");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sets_permissions_on_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("run.sh");
+        std::fs::write(&script, "#!/bin/sh\n").unwrap();
+        let perms = set_permissions(&script, Some("644"), None).unwrap();
+        assert!(!perms.executable);
+        let perms = set_permissions(&script, Some("+x"), None).unwrap();
+        assert_eq!(perms.octal.as_deref(), Some("0755"));
+        assert_eq!(perms.symbolic.as_deref(), Some("rwxr-xr-x"));
+        let perms = set_permissions(&script, None, Some(true)).unwrap();
+        assert_eq!(perms.mode, Some(0o555));
+    }
+}
@@ -0,0 +1,130 @@
+//! Cheap previews of the start of a file.
+//!
+//! `preview_file` reads at most `max_bytes` from disk and returns the first
+//! `max_lines` lines, decoded with the same detection `audit_encodings`
+//! uses, so hover cards and the quick-open peek never load a whole file.
+//! A character cut off by the byte limit is dropped rather than shown as
+//! garbage; binary files come back with no text.
+
+use serde::Serialize;
+use std::fs::File;
+use std::io::Read;
+
+use super::encoding::{decode, detect, Encoding};
+use super::{validate_path, ProjectRootState};
+
+const DEFAULT_MAX_LINES: usize = 50;
+const MAX_LINES: usize = 5_000;
+const DEFAULT_MAX_BYTES: usize = 16 * 1024;
+const MAX_BYTES: usize = 1024 * 1024;
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct FilePreview {
+    pub text: String,
+    /// `None` for binary files.
+    pub encoding: Option<Encoding>,
+    pub binary: bool,
+    /// Lines in `text`.
+    pub lines: usize,
+    /// Whether the file goes on past `text`.
+    pub truncated: bool,
+    /// Size of the whole file in bytes.
+    pub size: u64,
+}
+
+/// Detect and decode a sample. When the sample was `cut` short, up to
+/// three trailing bytes — a split UTF-8 sequence, UTF-16 unit or
+/// surrogate pair — are dropped until it decodes as something better
+/// than the Windows-1252 fallback.
+fn decode_sample(sample: &[u8], cut: bool) -> Option<(Encoding, String)> {
+    let attempt = |bytes: &[u8]| detect(bytes).and_then(|e| Some((e, decode(bytes, e)?)));
+    if cut {
+        let shortest = sample.len().saturating_sub(3);
+        let found = (shortest..=sample.len())
+            .rev()
+            .filter_map(|end| attempt(&sample[..end]))
+            .find(|(e, _)| *e != Encoding::Legacy);
+        if found.is_some() {
+            return found;
+        }
+    }
+    attempt(sample)
+}
+
+/// The first `max_lines` lines of `text`, line endings kept, and
+/// whether anything was left out.
+fn first_lines(text: &str, max_lines: usize) -> (&str, bool) {
+    if max_lines == 0 {
+        return ("", !text.is_empty());
+    }
+    match text.match_indices('\n').nth(max_lines - 1) {
+        Some((i, _)) => (&text[..=i], i + 1 < text.len()),
+        None => (text, false),
+    }
+}
+
+fn preview(sample: &[u8], size: u64, max_lines: usize) -> FilePreview {
+    let cut = size > sample.len() as u64;
+    let Some((encoding, text)) = decode_sample(sample, cut) else {
+        return FilePreview { text: String::new(), encoding: None, binary: true, lines: 0, truncated: cut, size };
+    };
+    let (head, more_lines) = first_lines(&text, max_lines);
+    FilePreview {
+        text: head.to_string(),
+        encoding: Some(encoding),
+        binary: false,
+        lines: head.lines().count(),
+        truncated: cut || more_lines,
+        size,
+    }
+}
+
+/// The start of a text file: at most `max_lines` lines (default 50) from
+/// the first `max_bytes` bytes (default 16 KB), with its encoding and
+/// whether there is more.
+#[tauri::command]
+pub fn preview_file(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: String,
+    max_lines: Option<usize>,
+    max_bytes: Option<usize>,
+) -> Result<FilePreview, String> {
+    let path = validate_path(&path, window.label(), &state)?;
+    let f = File::open(&path).map_err(|e| format!("Failed to read file: {}", e.kind()))?;
+    let size = f.metadata().map_err(|e| format!("Failed to read file: {}", e.kind()))?.len();
+    let max_bytes = max_bytes.unwrap_or(DEFAULT_MAX_BYTES).clamp(1, MAX_BYTES);
+    let mut sample = Vec::with_capacity(max_bytes.min(size as usize));
+    f.take(max_bytes as u64)
+        .read_to_end(&mut sample)
+        .map_err(|e| format!("Failed to read file: {}", e.kind()))?;
+    Ok(preview(&sample, size, max_lines.unwrap_or(DEFAULT_MAX_LINES).min(MAX_LINES)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn previews_lines_and_drops_split_characters() {
+        let text = "one\ntwo\nthree\n";
+        let p = preview(text.as_bytes(), text.len() as u64, 2);
+        assert_eq!((p.text.as_str(), p.lines, p.truncated), ("one\ntwo\n", 2, true));
+        let p = preview(text.as_bytes(), text.len() as u64, 3);
+        assert_eq!((p.text.as_str(), p.truncated, p.encoding), (text, false, Some(Encoding::Utf8)));
+
+        // "héllo wörld" cut inside the "ö".
+        let bytes = "héllo wörld".as_bytes();
+        let p = preview(&bytes[..9], bytes.len() as u64, 10);
+        assert_eq!((p.text.as_str(), p.encoding, p.truncated), ("héllo w", Some(Encoding::Utf8), true));
+
+        // UTF-16LE with a BOM, cut mid code unit.
+        let mut utf16 = vec![0xFF, 0xFE];
+        utf16.extend("hi\nthere".encode_utf16().flat_map(u16::to_le_bytes));
+        let p = preview(&utf16[..9], utf16.len() as u64, 10);
+        assert_eq!((p.text.as_str(), p.encoding), ("hi\n", Some(Encoding::Utf16Le)));
+
+        let p = preview(&[0x89, b'P', b'N', b'G', 0, 0, 0, 0x0D], 4096, 10);
+        assert!(p.binary && p.encoding.is_none() && p.text.is_empty());
+    }
+}
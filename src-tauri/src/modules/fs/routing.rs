@@ -0,0 +1,231 @@
+//! Which editor or viewer opens a file.
+//!
+//! Rules map a glob (gitignore syntax, relative to the project root) to an
+//! editor kind. The project's rules are tried first, then the user's, then
+//! `DEFAULT_RULES`; within a list the last matching rule wins, as in a
+//! gitignore. A file no rule matches gets the editor for its detected type
+//! (`detect_file_type` reports the result as `editor`). Rules live in
+//! `~/.leo-ide/editor-routing.json`.
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::filetype::{FileCategory, FileType};
+use super::{write_atomic, ProjectRootState};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum EditorKind {
+    Text,
+    Hex,
+    /// The media viewer: images, PDFs, audio and video.
+    Image,
+    Table,
+    Notebook,
+    /// The text editor with the rendered preview open beside it.
+    MarkdownPreview,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RoutingRule {
+    pub glob: String,
+    pub editor: EditorKind,
+}
+
+const DEFAULT_RULES: &[(&str, EditorKind)] = &[
+    ("*.csv", EditorKind::Table),
+    ("*.tsv", EditorKind::Table),
+    ("*.ipynb", EditorKind::Notebook),
+    ("*.md", EditorKind::MarkdownPreview),
+    ("*.mdx", EditorKind::MarkdownPreview),
+    ("*.markdown", EditorKind::MarkdownPreview),
+];
+
+fn default_rules() -> Vec<RoutingRule> {
+    DEFAULT_RULES.iter().map(|(glob, editor)| RoutingRule { glob: glob.to_string(), editor: *editor }).collect()
+}
+
+/// The editor for a file no rule matches.
+pub(super) fn editor_for_type(file_type: &FileType) -> EditorKind {
+    match file_type.category {
+        FileCategory::Image | FileCategory::Video => EditorKind::Image,
+        FileCategory::Text => EditorKind::Text,
+        _ if file_type.mime == "application/pdf" || file_type.mime.starts_with("audio/") => EditorKind::Image,
+        FileCategory::Archive | FileCategory::Binary => EditorKind::Hex,
+    }
+}
+
+/// One rule list, compiled.
+struct Rules(Vec<(Gitignore, EditorKind)>);
+
+impl Rules {
+    fn new(root: &Path, rules: &[RoutingRule]) -> Result<Self, String> {
+        let mut compiled = Vec::with_capacity(rules.len());
+        for rule in rules {
+            let glob = rule.glob.trim();
+            let invalid = |e: ignore::Error| format!("Invalid editor routing glob '{}': {}", glob, e);
+            if glob.is_empty() || glob.starts_with('!') {
+                return Err(format!("Invalid editor routing glob '{}'", glob));
+            }
+            let mut builder = GitignoreBuilder::new(root);
+            builder.add_line(None, glob).map_err(invalid)?;
+            compiled.push((builder.build().map_err(invalid)?, rule.editor));
+        }
+        Ok(Self(compiled))
+    }
+
+    fn editor_for(&self, rel: &Path) -> Option<EditorKind> {
+        self.0.iter().rev().find(|(m, _)| m.matched_path_or_any_parents(rel, false).is_ignore()).map(|(_, e)| *e)
+    }
+}
+
+// ── Settings ──
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(default)]
+struct RoutingSettings {
+    /// The user's rules, for every project.
+    rules: Vec<RoutingRule>,
+    /// Rules by project root.
+    projects: HashMap<String, Vec<RoutingRule>>,
+}
+
+fn settings_path() -> PathBuf {
+    dirs::home_dir().unwrap_or_default().join(".leo-ide").join("editor-routing.json")
+}
+
+fn load_settings(path: &Path) -> RoutingSettings {
+    std::fs::read_to_string(path).ok().and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default()
+}
+
+fn save_settings(path: &Path, settings: &RoutingSettings) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    write_atomic(path, json.as_bytes()).map_err(|e| format!("Failed to save editor routing: {}", e))
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct EditorRouting {
+    pub project: Vec<RoutingRule>,
+    pub user: Vec<RoutingRule>,
+    /// Built in, tried after the other two.
+    pub defaults: Vec<RoutingRule>,
+}
+
+fn routing_for(settings: &RoutingSettings, root: &Path) -> EditorRouting {
+    EditorRouting {
+        project: settings.projects.get(root.to_string_lossy().as_ref()).cloned().unwrap_or_default(),
+        user: settings.rules.clone(),
+        defaults: default_rules(),
+    }
+}
+
+/// The editor `routing` picks for `path` (inside `root`), if a rule
+/// matches. Lists that no longer compile are skipped.
+fn route(routing: &EditorRouting, root: &Path, path: &Path) -> Option<EditorKind> {
+    let rel = path.strip_prefix(root).unwrap_or(path);
+    [&routing.project, &routing.user, &routing.defaults]
+        .into_iter()
+        .find_map(|rules| Rules::new(root, rules).ok()?.editor_for(rel))
+}
+
+/// The editor for `path` in the project at `root`, given its detected type.
+pub(crate) fn editor_for(root: &Path, path: &Path, file_type: &FileType) -> EditorKind {
+    route(&routing_for(&load_settings(&settings_path()), root), root, path).unwrap_or_else(|| editor_for_type(file_type))
+}
+
+fn set_rules(settings_file: &Path, root: &Path, rules: Vec<RoutingRule>, project: bool) -> Result<EditorRouting, String> {
+    let rules: Vec<RoutingRule> = rules
+        .into_iter()
+        .map(|r| RoutingRule { glob: r.glob.trim().to_string(), editor: r.editor })
+        .filter(|r| !r.glob.is_empty())
+        .collect();
+    Rules::new(root, &rules)?;
+    let mut settings = load_settings(settings_file);
+    let key = root.to_string_lossy().into_owned();
+    match (project, rules.is_empty()) {
+        (true, true) => {
+            settings.projects.remove(&key);
+        }
+        (true, false) => {
+            settings.projects.insert(key, rules);
+        }
+        (false, _) => settings.rules = rules,
+    }
+    save_settings(settings_file, &settings)?;
+    Ok(routing_for(&settings, root))
+}
+
+fn project_root(window: &tauri::WebviewWindow, state: &tauri::State<'_, ProjectRootState>) -> Result<PathBuf, String> {
+    state.blocking_read().get(window.label()).and_then(|o| o.clone()).ok_or_else(|| "No project is open".to_string())
+}
+
+// ── Commands ──
+
+/// The rule lists that decide which editor opens a file in this project.
+#[tauri::command]
+pub fn get_editor_routing(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+) -> Result<EditorRouting, String> {
+    let root = project_root(&window, &state)?;
+    Ok(routing_for(&load_settings(&settings_path()), &root))
+}
+
+/// Replace the project's rules (`project`) or the user's. Every glob is
+/// checked before anything is saved; an empty project list removes it.
+#[tauri::command]
+pub fn set_editor_routing(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    rules: Vec<RoutingRule>,
+    project: bool,
+) -> Result<EditorRouting, String> {
+    let root = project_root(&window, &state)?;
+    set_rules(&settings_path(), &root, rules, project)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(glob: &str, editor: EditorKind) -> RoutingRule {
+        RoutingRule { glob: glob.into(), editor }
+    }
+
+    #[test]
+    fn project_rules_win_over_user_rules_and_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("editor-routing.json");
+        let root = Path::new("/proj");
+        set_rules(&file, root, vec![rule("*.bin", EditorKind::Hex), rule("*.log", EditorKind::Text)], false).unwrap();
+        let routing = set_rules(&file, root, vec![rule("data/**", EditorKind::Table), rule(" ", EditorKind::Hex)], true).unwrap();
+        assert_eq!(routing.project, [rule("data/**", EditorKind::Table)]);
+
+        let at = |p: &str| route(&routing, root, &root.join(p));
+        assert_eq!(at("data/out.bin"), Some(EditorKind::Table));
+        assert_eq!(at("fw/out.bin"), Some(EditorKind::Hex));
+        assert_eq!(at("report.csv"), Some(EditorKind::Table));
+        assert_eq!(at("docs/README.md"), Some(EditorKind::MarkdownPreview));
+        assert_eq!(at("main.rs"), None);
+
+        // Another project only sees the user's rules.
+        let other = routing_for(&load_settings(&file), Path::new("/other"));
+        assert!(other.project.is_empty());
+        assert_eq!(other.user.len(), 2);
+
+        assert!(set_rules(&file, root, vec![rule("!*.md", EditorKind::Text)], true).is_err());
+        assert_eq!(load_settings(&file).projects[&root.to_string_lossy().into_owned()].len(), 1);
+        set_rules(&file, root, Vec::new(), true).unwrap();
+        assert!(load_settings(&file).projects.is_empty());
+
+        let typed = |mime: &str, category| FileType { mime: mime.into(), category, sniffed: true, editor: EditorKind::Text };
+        assert_eq!(editor_for_type(&typed("image/png", FileCategory::Image)), EditorKind::Image);
+        assert_eq!(editor_for_type(&typed("application/pdf", FileCategory::Binary)), EditorKind::Image);
+        assert_eq!(editor_for_type(&typed("application/octet-stream", FileCategory::Binary)), EditorKind::Hex);
+    }
+}
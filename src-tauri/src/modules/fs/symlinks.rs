@@ -0,0 +1,80 @@
+//! Which symlinks a directory walk may follow.
+//!
+//! Walks skip symlinked entries by default. With in-root following on, a
+//! link is followed when its resolved target stays inside the project
+//! root — monorepos that link workspace packages into each other then
+//! list and copy like plain trees. Each target is entered at most once
+//! per walk, and links pointing at one of their own ancestors are never
+//! followed, so cycles end after one step.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default)]
+pub struct Symlinks {
+    /// Canonical project root; `None` follows nothing.
+    root: Option<PathBuf>,
+    followed: HashSet<PathBuf>,
+}
+
+impl Symlinks {
+    /// Follow links whose target resolves inside `root` (canonical).
+    pub fn within(root: &Path) -> Self {
+        Self { root: Some(root.to_path_buf()), followed: HashSet::new() }
+    }
+
+    /// `within(root)` when the caller asked for it, otherwise follow nothing.
+    pub fn requested(follow: Option<bool>, root: &Path) -> Self {
+        if follow.unwrap_or(false) {
+            Self::within(root)
+        } else {
+            Self::default()
+        }
+    }
+
+    /// The canonical target of the symlink at `link` if the walk should
+    /// follow it. Dangling links, targets outside the root, targets that
+    /// contain the link itself and targets already entered are refused.
+    pub fn follow(&mut self, link: &Path) -> Option<PathBuf> {
+        let root = self.root.as_ref()?;
+        let target = fs::canonicalize(link).ok()?;
+        if !target.starts_with(root) {
+            return None;
+        }
+        let parent = link.parent().and_then(|p| fs::canonicalize(p).ok())?;
+        if parent.starts_with(&target) || !self.followed.insert(target.clone()) {
+            return None;
+        }
+        Some(target)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+
+    #[test]
+    fn follows_in_root_links_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = fs::canonicalize(dir.path()).unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        fs::create_dir_all(root.join("packages/core")).unwrap();
+        fs::create_dir_all(root.join("app/node_modules")).unwrap();
+        symlink(root.join("packages/core"), root.join("app/node_modules/core")).unwrap();
+        symlink(root.join("packages/core"), root.join("app/core-again")).unwrap();
+        symlink(&root, root.join("packages/core/up")).unwrap();
+        symlink(outside.path(), root.join("app/outside")).unwrap();
+        symlink(root.join("missing"), root.join("app/dangling")).unwrap();
+
+        let mut links = Symlinks::within(&root);
+        assert_eq!(links.follow(&root.join("app/node_modules/core")), Some(root.join("packages/core")));
+        assert_eq!(links.follow(&root.join("app/core-again")), None);
+        assert_eq!(links.follow(&root.join("packages/core/up")), None);
+        assert_eq!(links.follow(&root.join("app/outside")), None);
+        assert_eq!(links.follow(&root.join("app/dangling")), None);
+
+        assert_eq!(Symlinks::requested(None, &root).follow(&root.join("app/core-again")), None);
+    }
+}
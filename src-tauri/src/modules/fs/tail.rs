@@ -0,0 +1,395 @@
+//! Log viewer backend: tail a file and follow appended lines.
+//!
+//! `tail_file` sends the last lines of a file to the requesting window as
+//! `tail-lines` events and, in follow mode, keeps polling for appended
+//! lines until stopped. A file watcher wakes the follower as soon as the
+//! file changes, with a slow poll as a fallback for filesystems that don't
+//! report changes. Lines can be filtered with a regex and tagged with a log
+//! level and timestamp when one is recognisable. A file that shrinks
+//! (rotated or truncated) is re-read from the start.
+
+use notify::{RecursiveMode, Watcher};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tauri::AppHandle;
+use ts_rs::TS;
+
+use super::{validate_path, ProjectRootState};
+use crate::modules::events;
+
+const DEFAULT_INITIAL_LINES: usize = 500;
+/// How far back from the end to look for the initial lines.
+const INITIAL_WINDOW_BYTES: u64 = 4 * 1024 * 1024;
+/// Fallback poll when no change notification arrives.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Lines per event, so a burst of output doesn't become one huge message.
+const MAX_BATCH_LINES: usize = 1000;
+
+// ── Types ──
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct TailOptions {
+    /// Keep watching for appended lines (default true).
+    pub follow: Option<bool>,
+    /// Only send lines matching this regex.
+    pub filter_regex: Option<String>,
+    /// Detect level and timestamp on each line (default true).
+    pub level_parse: Option<bool>,
+    /// Existing lines to send from the end of the file (default 500).
+    pub initial_lines: Option<usize>,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, TS)]
+pub struct LogLine {
+    /// Byte offset of the line in the file.
+    #[ts(type = "number")]
+    pub offset: u64,
+    pub text: String,
+    pub level: Option<LogLevel>,
+    pub timestamp: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug, TS)]
+pub struct TailEvent {
+    pub tail_id: String,
+    pub lines: Vec<LogLine>,
+    /// The file shrank and is being read again from the start; the viewer
+    /// should clear what it has.
+    pub reset: bool,
+    pub done: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TailInfo {
+    pub tail_id: String,
+    pub size: u64,
+}
+
+// ── Line parsing ──
+
+fn timestamp_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:[.,]\d+)?(?:Z|[+-]\d{2}:?\d{2})?").unwrap()
+    })
+}
+
+fn level_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)\b(trace|debug|info|warn(?:ing)?|error|err|fatal|critical|crit)\b").unwrap()
+    })
+}
+
+/// Level of a line, looking only near its start so words in the message
+/// body ("no error found") don't count.
+fn parse_level(line: &str) -> Option<LogLevel> {
+    let mut end = line.len().min(120);
+    while !line.is_char_boundary(end) {
+        end -= 1;
+    }
+    let head = &line[..end];
+    let word = level_regex().captures(head)?.get(1)?.as_str().to_ascii_lowercase();
+    Some(match word.as_str() {
+        "trace" => LogLevel::Trace,
+        "debug" => LogLevel::Debug,
+        "info" => LogLevel::Info,
+        "warn" | "warning" => LogLevel::Warn,
+        _ => LogLevel::Error,
+    })
+}
+
+fn parse_line(offset: u64, text: String, parse_levels: bool) -> LogLine {
+    let (level, timestamp) = if parse_levels {
+        (parse_level(&text), timestamp_regex().find(&text).map(|m| m.as_str().to_string()))
+    } else {
+        (None, None)
+    };
+    LogLine { offset, text, level, timestamp }
+}
+
+// ── Tailing ──
+
+struct Tailer {
+    path: PathBuf,
+    /// Position just past the last complete line read.
+    offset: u64,
+    /// Bytes of a line whose newline hasn't been written yet.
+    pending: Vec<u8>,
+    filter: Option<Regex>,
+    parse_levels: bool,
+}
+
+impl Tailer {
+    fn new(path: &Path, options: &TailOptions) -> Result<Self, String> {
+        let filter = match options.filter_regex.as_deref().filter(|f| !f.is_empty()) {
+            Some(f) => Some(Regex::new(f).map_err(|e| format!("Invalid regex: {}", e))?),
+            None => None,
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            offset: 0,
+            pending: Vec::new(),
+            filter,
+            parse_levels: options.level_parse.unwrap_or(true),
+        })
+    }
+
+    /// The last `count` lines, leaving the tailer positioned after them.
+    /// A final line without a newline yet is held back for `poll`.
+    fn initial(&mut self, count: usize) -> std::io::Result<Vec<LogLine>> {
+        let size = std::fs::metadata(&self.path)?.len();
+        let start = size.saturating_sub(INITIAL_WINDOW_BYTES);
+        self.offset = start;
+        if start > 0 {
+            // Begin at the first full line inside the window.
+            let mut file = File::open(&self.path)?;
+            file.seek(SeekFrom::Start(start))?;
+            let mut skipped = Vec::new();
+            BufReader::new(file).read_until(b'\n', &mut skipped)?;
+            self.offset += skipped.len() as u64;
+        }
+        let mut lines = self.read_new(false)?;
+        let excess = lines.len().saturating_sub(count);
+        lines.drain(..excess);
+        Ok(lines)
+    }
+
+    /// Lines appended since the last call. Returns `reset` when the file
+    /// shrank and reading restarted from the top. With `flush`, a final
+    /// line without a newline is returned too.
+    fn poll(&mut self, flush: bool) -> std::io::Result<(Vec<LogLine>, bool)> {
+        let size = std::fs::metadata(&self.path)?.len();
+        let reset = size < self.offset + self.pending.len() as u64;
+        if reset {
+            self.offset = 0;
+            self.pending.clear();
+        }
+        Ok((self.read_new(flush)?, reset))
+    }
+
+    fn read_new(&mut self, flush: bool) -> std::io::Result<Vec<LogLine>> {
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(self.offset + self.pending.len() as u64))?;
+        let mut buf = std::mem::take(&mut self.pending);
+        file.read_to_end(&mut buf)?;
+
+        let mut lines = Vec::new();
+        let mut start = 0;
+        while start < buf.len() {
+            let end = match buf[start..].iter().position(|&b| b == b'\n') {
+                Some(i) => start + i + 1,
+                None if flush => buf.len(),
+                None => break,
+            };
+            let text = String::from_utf8_lossy(&buf[start..end]).trim_end_matches(['\n', '\r']).to_string();
+            let offset = self.offset + start as u64;
+            if self.filter.as_ref().map_or(true, |re| re.is_match(&text)) {
+                lines.push(parse_line(offset, text, self.parse_levels));
+            }
+            start = end;
+        }
+        self.offset += start as u64;
+        self.pending = buf.split_off(start);
+        Ok(lines)
+    }
+}
+
+// ── Control ──
+
+#[derive(Default)]
+struct TailControl {
+    paused: AtomicBool,
+    stopped: AtomicBool,
+}
+
+fn active_tails() -> &'static Mutex<HashMap<String, Arc<TailControl>>> {
+    static ACTIVE: OnceLock<Mutex<HashMap<String, Arc<TailControl>>>> = OnceLock::new();
+    ACTIVE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn with_tail(tail_id: &str, f: impl FnOnce(&TailControl)) -> bool {
+    match active_tails().lock().unwrap().get(tail_id) {
+        Some(control) => {
+            f(control);
+            true
+        }
+        None => false,
+    }
+}
+
+// ── Commands ──
+
+/// Change notifications for `path`. The parent directory is watched so a
+/// rotated file (renamed away and recreated) keeps reporting.
+fn watch_file(path: &Path) -> Option<(notify::RecommendedWatcher, std::sync::mpsc::Receiver<()>)> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let target = path.to_path_buf();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if event.is_ok_and(|e| e.paths.iter().any(|p| p == &target)) {
+            let _ = tx.send(());
+        }
+    })
+    .ok()?;
+    watcher.watch(path.parent()?, RecursiveMode::NonRecursive).ok()?;
+    Some((watcher, rx))
+}
+
+/// Send the last `lines` of `path` (default 500) to the calling window as
+/// `tail-lines` events tagged with `tail_id`, then keep sending appended
+/// lines while `follow` is on. The last event has `done: true`. `lines`
+/// takes precedence over `options.initial_lines`.
+#[tauri::command]
+pub fn tail_file(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    app: AppHandle,
+    path: String,
+    tail_id: String,
+    lines: Option<usize>,
+    options: Option<TailOptions>,
+) -> Result<TailInfo, String> {
+    let path = validate_path(&path, window.label(), &state)?;
+    let mut options = options.unwrap_or_default();
+    if lines.is_some() {
+        options.initial_lines = lines;
+    }
+    let size = std::fs::metadata(&path).map_err(|e| format!("Failed to read file: {}", e.kind()))?.len();
+    let mut tailer = Tailer::new(&path, &options)?;
+    let follow = options.follow.unwrap_or(true);
+
+    let control = Arc::new(TailControl::default());
+    if let Some(old) = active_tails().lock().unwrap().insert(tail_id.clone(), control.clone()) {
+        old.stopped.store(true, Ordering::Relaxed);
+    }
+
+    let label = window.label().to_string();
+    let id = tail_id.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let emit = |lines: Vec<LogLine>, reset: bool| {
+            let mut batches: Vec<Vec<LogLine>> = Vec::new();
+            let mut lines = lines.into_iter().peekable();
+            while lines.peek().is_some() {
+                batches.push(lines.by_ref().take(MAX_BATCH_LINES).collect());
+            }
+            for (i, batch) in batches.into_iter().enumerate() {
+                let event = TailEvent { tail_id: id.clone(), lines: batch, reset: reset && i == 0, done: false, error: None };
+                events::TAIL_LINES.emit_to(&app, &label, event);
+            }
+        };
+
+        let count = options.initial_lines.unwrap_or(DEFAULT_INITIAL_LINES);
+        let mut result = tailer.initial(count).map(|lines| emit(lines, false));
+        let watch = if follow { watch_file(&tailer.path) } else { None };
+        while result.is_ok() && follow && !control.stopped.load(Ordering::Relaxed) {
+            match &watch {
+                Some((_, changes)) => {
+                    let _ = changes.recv_timeout(POLL_INTERVAL);
+                    // One read covers a burst of writes.
+                    while changes.try_recv().is_ok() {}
+                }
+                None => std::thread::sleep(POLL_INTERVAL),
+            }
+            if control.paused.load(Ordering::Relaxed) {
+                continue;
+            }
+            result = tailer.poll(false).map(|(lines, reset)| {
+                if reset || !lines.is_empty() {
+                    emit(lines, reset);
+                }
+            });
+        }
+
+        if !follow {
+            result = result.and_then(|_| tailer.poll(true)).map(|(lines, reset)| emit(lines, reset));
+        }
+
+        let error = result.err().map(|e| format!("Failed to read file: {}", e.kind()));
+        events::TAIL_LINES.emit_to(&app, &label, TailEvent { tail_id: id.clone(), lines: Vec::new(), reset: false, done: true, error });
+        let mut active = active_tails().lock().unwrap();
+        if active.get(&id).is_some_and(|c| Arc::ptr_eq(c, &control)) {
+            active.remove(&id);
+        }
+    });
+
+    Ok(TailInfo { tail_id, size })
+}
+
+/// Hold back new lines until `resume_tail`; they're sent then, not lost.
+#[tauri::command]
+pub fn pause_tail(tail_id: String) -> bool {
+    with_tail(&tail_id, |c| c.paused.store(true, Ordering::Relaxed))
+}
+
+#[tauri::command]
+pub fn resume_tail(tail_id: String) -> bool {
+    with_tail(&tail_id, |c| c.paused.store(false, Ordering::Relaxed))
+}
+
+/// Stop following. Returns false if the tail already ended.
+#[tauri::command]
+pub fn stop_tail(tail_id: String) -> bool {
+    with_tail(&tail_id, |c| c.stopped.store(true, Ordering::Relaxed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn parses_levels_and_timestamps() {
+        let line = parse_line(0, "2024-05-01T12:00:03.120Z [WARN] disk almost full".into(), true);
+        assert_eq!(line.level, Some(LogLevel::Warn));
+        assert_eq!(line.timestamp.as_deref(), Some("2024-05-01T12:00:03.120Z"));
+        assert_eq!(parse_level("E 10:00 fatal: broken"), Some(LogLevel::Error));
+        assert_eq!(parse_level(r#"{"level":"debug","msg":"x"}"#), Some(LogLevel::Debug));
+        assert_eq!(parse_level("compiling information module"), None);
+    }
+
+    #[test]
+    fn follows_appends_filters_and_resets() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        std::fs::write(&path, "INFO one\nDEBUG two\nERROR three\n").unwrap();
+        let options = TailOptions { filter_regex: Some("INFO|ERROR".into()), ..Default::default() };
+        let mut tailer = Tailer::new(&path, &options).unwrap();
+
+        let initial = tailer.initial(1).unwrap();
+        assert_eq!(initial.iter().map(|l| l.text.as_str()).collect::<Vec<_>>(), vec!["ERROR three"]);
+
+        let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+        write!(file, "INFO four\nINFO fi").unwrap();
+        let (lines, reset) = tailer.poll(false).unwrap();
+        assert!(!reset);
+        assert_eq!(lines.len(), 1);
+        assert_eq!((lines[0].text.as_str(), lines[0].offset), ("INFO four", 31));
+
+        writeln!(file, "ve").unwrap();
+        assert_eq!(tailer.poll(false).unwrap().0[0].text, "INFO five");
+
+        std::fs::write(&path, "INFO new\n").unwrap();
+        let (lines, reset) = tailer.poll(false).unwrap();
+        assert!(reset);
+        assert_eq!(lines[0].text, "INFO new");
+    }
+}
@@ -0,0 +1,166 @@
+//! Image thumbnails for the file tree and asset previews.
+//!
+//! PNG, JPEG, GIF, WebP, BMP and ICO files are decoded, scaled down to fit
+//! a square of `max_size` pixels and re-encoded as PNG. Results are cached
+//! in `~/.leo-ide/thumbnails`, keyed by path, size, mtime and `max_size`,
+//! so an edited image gets a fresh thumbnail and an unchanged one is never
+//! decoded twice.
+
+use base64::Engine;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use super::{mtime_ms, project_root_async, resolve_within_root, ProjectRootState, MAX_BINARY_FILE_BYTES};
+
+const DEFAULT_SIZE: u32 = 128;
+const MIN_SIZE: u32 = 16;
+const MAX_SIZE: u32 = 1024;
+const MAX_CACHE_ENTRIES: usize = 2000;
+/// Refuse images whose decoded pixels would need more memory than this.
+const MAX_DECODE_BYTES: u64 = 512 * 1024 * 1024;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Thumbnail {
+    /// Base64-encoded PNG.
+    pub data: String,
+    pub width: u32,
+    pub height: u32,
+    pub original_width: u32,
+    pub original_height: u32,
+    pub cached: bool,
+}
+
+fn cache_dir() -> PathBuf {
+    dirs::home_dir().unwrap_or_default().join(".leo-ide").join("thumbnails")
+}
+
+fn cache_key(path: &Path, meta: &std::fs::Metadata, max_size: u32) -> String {
+    let mut h = Sha256::new();
+    h.update(path.to_string_lossy().as_bytes());
+    h.update(format!("\0{}\0{}\0{}", meta.len(), mtime_ms(meta), max_size).as_bytes());
+    h.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The cached PNG starts with a small header holding the four dimensions.
+fn cache_get(dir: &Path, key: &str) -> Option<Thumbnail> {
+    let bytes = std::fs::read(dir.join(format!("{}.thumb", key))).ok()?;
+    let header = bytes.get(..16)?;
+    let dim = |i: usize| u32::from_le_bytes(header[i * 4..i * 4 + 4].try_into().expect("4 bytes"));
+    Some(Thumbnail {
+        data: base64::engine::general_purpose::STANDARD.encode(&bytes[16..]),
+        width: dim(0),
+        height: dim(1),
+        original_width: dim(2),
+        original_height: dim(3),
+        cached: true,
+    })
+}
+
+/// Best-effort; drops the oldest entries once the cache is full.
+fn cache_put(dir: &Path, key: &str, dims: [u32; 4], png: &[u8]) {
+    if std::fs::create_dir_all(dir).is_err() {
+        return;
+    }
+    let mut bytes: Vec<u8> = dims.iter().flat_map(|d| d.to_le_bytes()).collect();
+    bytes.extend_from_slice(png);
+    let _ = super::write_atomic(&dir.join(format!("{}.thumb", key)), &bytes);
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    let mut files: Vec<_> = entries
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|x| x == "thumb"))
+        .map(|e| (e.metadata().and_then(|m| m.modified()).ok(), e.path()))
+        .collect();
+    if files.len() > MAX_CACHE_ENTRIES {
+        files.sort();
+        for (_, path) in &files[..files.len() - MAX_CACHE_ENTRIES] {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+fn render(path: &Path, max_size: u32) -> Result<([u32; 4], Vec<u8>), String> {
+    let mut reader = image::ImageReader::open(path)
+        .and_then(|r| r.with_guessed_format())
+        .map_err(|e| format!("Failed to read image: {}", e.kind()))?;
+    let mut limits = image::Limits::default();
+    limits.max_alloc = Some(MAX_DECODE_BYTES);
+    reader.limits(limits);
+    if reader.format().is_none() {
+        return Err("Unsupported image format".to_string());
+    }
+    let img = reader.decode().map_err(|e| format!("Failed to decode image: {}", e))?;
+    let (ow, oh) = (img.width(), img.height());
+    let thumb = if ow <= max_size && oh <= max_size { img } else { img.thumbnail(max_size, max_size) };
+    let mut png = Vec::new();
+    thumb
+        .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode thumbnail: {}", e))?;
+    Ok(([thumb.width(), thumb.height(), ow, oh], png))
+}
+
+fn thumbnail(path: &Path, max_size: u32, cache: &Path) -> Result<Thumbnail, String> {
+    let meta = std::fs::metadata(path).map_err(|e| format!("Failed to read image: {}", e.kind()))?;
+    if meta.len() > MAX_BINARY_FILE_BYTES {
+        return Err(format!("FILE_TOO_LARGE: {} bytes; limit {}", meta.len(), MAX_BINARY_FILE_BYTES));
+    }
+    let key = cache_key(path, &meta, max_size);
+    if let Some(thumb) = cache_get(cache, &key) {
+        return Ok(thumb);
+    }
+    let (dims, png) = render(path, max_size)?;
+    cache_put(cache, &key, dims, &png);
+    Ok(Thumbnail {
+        data: base64::engine::general_purpose::STANDARD.encode(&png),
+        width: dims[0],
+        height: dims[1],
+        original_width: dims[2],
+        original_height: dims[3],
+        cached: false,
+    })
+}
+
+/// A PNG thumbnail of the image at `path`, at most `max_size` pixels
+/// (default 128, clamped to 16–1024) on its longer side. Images already
+/// that small are re-encoded at their own size.
+#[tauri::command]
+pub async fn get_thumbnail(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: String,
+    max_size: Option<u32>,
+) -> Result<Thumbnail, String> {
+    let root = project_root_async(window.label(), &state).await?;
+    let file = resolve_within_root(&path, &root)?;
+    let max_size = max_size.unwrap_or(DEFAULT_SIZE).clamp(MIN_SIZE, MAX_SIZE);
+    tokio::task::spawn_blocking(move || thumbnail(&file, max_size, &cache_dir()))
+        .await
+        .map_err(|e| format!("Thumbnail failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scales_and_caches_thumbnails() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("cache");
+        let src = dir.path().join("wide.png");
+        image::RgbImage::from_pixel(400, 100, image::Rgb([200, 10, 10])).save(&src).unwrap();
+
+        let first = thumbnail(&src, 64, &cache).unwrap();
+        assert_eq!((first.width, first.height, first.original_width, first.original_height), (64, 16, 400, 100));
+        assert!(!first.cached);
+        let second = thumbnail(&src, 64, &cache).unwrap();
+        assert!(second.cached);
+        assert_eq!((second.width, second.height, second.data), (64, 16, first.data));
+
+        let small = thumbnail(&src, 1024, &cache).unwrap();
+        assert_eq!((small.width, small.height), (400, 100));
+
+        std::fs::write(dir.path().join("notes.txt"), "hello").unwrap();
+        assert!(thumbnail(&dir.path().join("notes.txt"), 64, &cache).is_err());
+    }
+}
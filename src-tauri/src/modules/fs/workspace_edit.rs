@@ -0,0 +1,619 @@
+//! Multi-file edits applied as one transaction.
+//!
+//! A `WorkspaceEdit` is an ordered list of file creates, deletes, renames
+//! and range edits — what a rename refactor, an AI change set or a
+//! structural replace produces. `apply_workspace_edit` first plays the
+//! whole list against an in-memory view of the project, so a missing
+//! file, an overlapping range or a rename onto an existing file fails
+//! before anything is touched; `dry_run` stops there and returns the
+//! diffs. Applying then runs the planned steps in order and, if one fails,
+//! reverses the ones already done. A successful edit is recorded in the
+//! undo journal with the contents it replaced, so
+//! `undo_last_fs_operation` reverts it as a whole, and every file it
+//! rewrites, renames or deletes keeps its old content in the local history.
+//!
+//! Positions are LSP-style: 0-based lines and UTF-16 columns. Paths may be
+//! absolute or relative to the project root. Deletes are for files only;
+//! folders go through `delete_entries`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use super::{history, journal, resolve_within_root, write_atomic, ProjectRootState};
+use crate::modules::search::unified_diff;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct Position {
+    pub line: u32,
+    /// UTF-16 code units from the start of the line.
+    pub character: u32,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct TextEdit {
+    pub range: Range,
+    pub new_text: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FileOperation {
+    Create {
+        path: String,
+        #[serde(default)]
+        content: String,
+        /// Replace the file if it already exists.
+        #[serde(default)]
+        overwrite: bool,
+    },
+    Delete {
+        path: String,
+    },
+    Rename {
+        from: String,
+        to: String,
+        /// Replace `to` if it is an existing file.
+        #[serde(default)]
+        overwrite: bool,
+    },
+    Edit {
+        path: String,
+        edits: Vec<TextEdit>,
+        /// `FileVersion::hash` the edits were computed against.
+        expected_hash: Option<String>,
+    },
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct WorkspaceEdit {
+    pub operations: Vec<FileOperation>,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ApplyEditOptions {
+    /// Validate and return the diffs without writing anything.
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct FileChange {
+    /// `create`, `delete`, `rename` or `edit`.
+    pub kind: String,
+    /// Path relative to the project root; the old path for renames.
+    pub path: String,
+    pub new_path: Option<String>,
+    /// Unified diff for creates and edits.
+    pub diff: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct WorkspaceEditResult {
+    pub changes: Vec<FileChange>,
+    /// False for a dry run.
+    pub applied: bool,
+}
+
+// ── Positions ──
+
+impl Position {
+    /// The position of byte `offset` in `text`.
+    pub fn at(text: &str, offset: usize) -> Self {
+        let before = &text[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Position {
+            line: before.matches('\n').count() as u32,
+            character: before[line_start..].encode_utf16().count() as u32,
+        }
+    }
+
+    /// The byte offset of this position in `text`, if it is inside it. A
+    /// column may point at the end of its line but not past it.
+    fn offset_in(&self, text: &str) -> Option<usize> {
+        let line_start = if self.line == 0 {
+            0
+        } else {
+            text.match_indices('\n').nth(self.line as usize - 1)?.0 + 1
+        };
+        let rest = &text[line_start..];
+        let line = &rest[..rest.find('\n').unwrap_or(rest.len())];
+        let mut units = 0;
+        for (i, c) in line.char_indices() {
+            if units == self.character as usize {
+                return Some(line_start + i);
+            }
+            units += c.len_utf16();
+        }
+        (units == self.character as usize).then_some(line_start + line.len())
+    }
+}
+
+fn apply_text_edits(text: &str, edits: &[TextEdit]) -> Result<String, String> {
+    let mut spans = edits
+        .iter()
+        .map(|e| {
+            let start = e.range.start.offset_in(text);
+            let end = e.range.end.offset_in(text);
+            match (start, end) {
+                (Some(s), Some(end)) if s <= end => Ok((s, end, e.new_text.as_str())),
+                _ => Err(format!(
+                    "Edit range {}:{}-{}:{} is outside the file",
+                    e.range.start.line, e.range.start.character, e.range.end.line, e.range.end.character
+                )),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    spans.sort_by_key(|&(start, end, _)| (start, end));
+    if spans.windows(2).any(|w| w[0].1 > w[1].0) {
+        return Err("Edits overlap".to_string());
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for (start, end, new_text) in spans {
+        out.push_str(&text[last..start]);
+        out.push_str(new_text);
+        last = end;
+    }
+    out.push_str(&text[last..]);
+    Ok(out)
+}
+
+// ── Planning ──
+
+/// A file as the plan sees it, once an operation has touched it.
+#[derive(Clone)]
+enum Entry {
+    Text(String),
+    /// Renamed here, unread; content is still at this path on disk.
+    Moved(PathBuf),
+    Gone,
+}
+
+/// One disk step, in order.
+#[derive(Debug, PartialEq)]
+enum Action {
+    Write { path: PathBuf, content: String },
+    Delete { path: PathBuf },
+    Rename { from: PathBuf, to: PathBuf },
+}
+
+struct Plan {
+    actions: Vec<Action>,
+    changes: Vec<FileChange>,
+}
+
+/// The in-memory view the edit is played against.
+struct Sim<'a> {
+    root: &'a Path,
+    files: HashMap<PathBuf, Entry>,
+    /// Directory renames so far, oldest first.
+    moved_dirs: Vec<(PathBuf, PathBuf)>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    File,
+    Dir,
+}
+
+impl<'a> Sim<'a> {
+    /// Where `path` is on disk right now, before any of the edit runs;
+    /// `None` if it was inside a directory renamed away.
+    fn on_disk(&self, path: &Path) -> Option<PathBuf> {
+        let mut p = path.to_path_buf();
+        for (from, to) in self.moved_dirs.iter().rev() {
+            if let Ok(rest) = p.strip_prefix(to) {
+                p = from.join(rest);
+            } else if p.starts_with(from) {
+                return None;
+            }
+        }
+        Some(p)
+    }
+
+    fn kind(&self, path: &Path) -> Option<Kind> {
+        match self.files.get(path) {
+            Some(Entry::Text(_) | Entry::Moved(_)) => return Some(Kind::File),
+            Some(Entry::Gone) => {}
+            None => match self.on_disk(path).and_then(|p| fs::symlink_metadata(p).ok()) {
+                Some(meta) if meta.is_dir() => return Some(Kind::Dir),
+                Some(_) => return Some(Kind::File),
+                None => {}
+            },
+        }
+        let created_inside = self.files.iter().any(|(p, e)| !matches!(e, Entry::Gone) && p != path && p.starts_with(path));
+        created_inside.then_some(Kind::Dir)
+    }
+
+    fn text(&self, path: &Path) -> Result<String, String> {
+        let disk = match self.files.get(path) {
+            Some(Entry::Text(t)) => return Ok(t.clone()),
+            Some(Entry::Moved(p)) => Some(p.clone()),
+            Some(Entry::Gone) => None,
+            None => self.on_disk(path),
+        };
+        let disk = disk.filter(|p| p.is_file()).ok_or_else(|| format!("{} does not exist", self.rel(path)))?;
+        let bytes = fs::read(&disk).map_err(|e| format!("Failed to read {}: {}", self.rel(path), e.kind()))?;
+        String::from_utf8(bytes).map_err(|_| format!("{} is not a UTF-8 text file", self.rel(path)))
+    }
+
+    fn rel(&self, path: &Path) -> String {
+        path.strip_prefix(self.root).unwrap_or(path).to_string_lossy().into_owned()
+    }
+
+    fn parent_is_free(&self, path: &Path) -> Result<(), String> {
+        let mut dir = path.parent();
+        while let Some(d) = dir.filter(|d| d.starts_with(self.root)) {
+            if self.kind(d) == Some(Kind::File) {
+                return Err(format!("{} is a file, not a folder", self.rel(d)));
+            }
+            dir = d.parent();
+        }
+        Ok(())
+    }
+}
+
+/// `path` inside the project, with the last component left unresolved so
+/// renames and deletes act on a symlink rather than its target.
+fn resolve_entry(raw: &str, root: &Path) -> Result<PathBuf, String> {
+    let p = Path::new(raw);
+    let full = if p.is_relative() { root.join(p) } else { p.to_path_buf() };
+    let name = match full.components().next_back() {
+        Some(Component::Normal(name)) => name.to_os_string(),
+        _ => return Err(format!("Invalid path: {}", raw)),
+    };
+    let parent = full.parent().ok_or_else(|| format!("Invalid path: {}", raw))?;
+    let parent = resolve_within_root(&parent.to_string_lossy(), root)?;
+    Ok(parent.join(name))
+}
+
+/// `path` inside the project, symlinks resolved, for reading and writing.
+fn resolve_content(raw: &str, root: &Path) -> Result<PathBuf, String> {
+    let p = Path::new(raw);
+    let full = if p.is_relative() { root.join(p) } else { p.to_path_buf() };
+    Ok(resolve_within_root(&full.to_string_lossy(), root)?)
+}
+
+fn plan(root: &Path, edit: &WorkspaceEdit) -> Result<Plan, String> {
+    let mut sim = Sim { root, files: HashMap::new(), moved_dirs: Vec::new() };
+    let mut plan = Plan { actions: Vec::new(), changes: Vec::new() };
+    for (i, op) in edit.operations.iter().enumerate() {
+        let fail = |e: String| format!("Operation {}: {}", i + 1, e);
+        match op {
+            FileOperation::Create { path, content, overwrite } => {
+                let path = resolve_entry(path, root).map_err(fail)?;
+                let old = match sim.kind(&path) {
+                    None => String::new(),
+                    Some(Kind::File) if *overwrite => sim.text(&path).unwrap_or_default(),
+                    Some(_) => return Err(fail(format!("{} already exists", sim.rel(&path)))),
+                };
+                sim.parent_is_free(&path).map_err(fail)?;
+                plan.changes.push(FileChange {
+                    kind: "create".to_string(),
+                    path: sim.rel(&path),
+                    new_path: None,
+                    diff: Some(unified_diff(&sim.rel(&path), &old, content)),
+                });
+                sim.files.insert(path.clone(), Entry::Text(content.clone()));
+                plan.actions.push(Action::Write { path, content: content.clone() });
+            }
+            FileOperation::Delete { path } => {
+                let path = resolve_entry(path, root).map_err(fail)?;
+                match sim.kind(&path) {
+                    Some(Kind::File) => {}
+                    Some(Kind::Dir) => return Err(fail(format!("{} is a folder; only files can be deleted", sim.rel(&path)))),
+                    None => return Err(fail(format!("{} does not exist", sim.rel(&path)))),
+                }
+                plan.changes.push(FileChange { kind: "delete".to_string(), path: sim.rel(&path), new_path: None, diff: None });
+                sim.files.insert(path.clone(), Entry::Gone);
+                plan.actions.push(Action::Delete { path });
+            }
+            FileOperation::Rename { from, to, overwrite } => {
+                let from = resolve_entry(from, root).map_err(fail)?;
+                let to = resolve_entry(to, root).map_err(fail)?;
+                let kind = sim.kind(&from).ok_or_else(|| fail(format!("{} does not exist", sim.rel(&from))))?;
+                if to.starts_with(&from) {
+                    return Err(fail(format!("Cannot move {} into itself", sim.rel(&from))));
+                }
+                match sim.kind(&to) {
+                    None => {}
+                    Some(Kind::File) if *overwrite && kind == Kind::File => {}
+                    Some(_) => return Err(fail(format!("{} already exists", sim.rel(&to)))),
+                }
+                sim.parent_is_free(&to).map_err(fail)?;
+                if kind == Kind::Dir {
+                    let inside: Vec<PathBuf> = sim.files.keys().filter(|p| p.starts_with(&from)).cloned().collect();
+                    for p in inside {
+                        let entry = sim.files.remove(&p).unwrap_or(Entry::Gone);
+                        sim.files.insert(to.join(p.strip_prefix(&from).unwrap_or(&p)), entry);
+                    }
+                    sim.moved_dirs.push((from.clone(), to.clone()));
+                } else {
+                    let entry = match sim.files.get(&from) {
+                        Some(e) => e.clone(),
+                        None => Entry::Moved(sim.on_disk(&from).unwrap_or_else(|| from.clone())),
+                    };
+                    sim.files.insert(to.clone(), entry);
+                    sim.files.insert(from.clone(), Entry::Gone);
+                }
+                plan.changes.push(FileChange {
+                    kind: "rename".to_string(),
+                    path: sim.rel(&from),
+                    new_path: Some(sim.rel(&to)),
+                    diff: None,
+                });
+                plan.actions.push(Action::Rename { from, to });
+            }
+            FileOperation::Edit { path, edits, expected_hash } => {
+                let path = resolve_content(path, root).map_err(fail)?;
+                let old = sim.text(&path).map_err(fail)?;
+                if let Some(hash) = expected_hash {
+                    if super::content_hash(old.as_bytes()) != *hash {
+                        return Err(fail(format!("FILE_CHANGED: {} was modified on disk", sim.rel(&path))));
+                    }
+                }
+                let new = apply_text_edits(&old, edits).map_err(|e| fail(format!("{}: {}", sim.rel(&path), e)))?;
+                plan.changes.push(FileChange {
+                    kind: "edit".to_string(),
+                    path: sim.rel(&path),
+                    new_path: None,
+                    diff: Some(unified_diff(&sim.rel(&path), &old, &new)),
+                });
+                sim.files.insert(path.clone(), Entry::Text(new.clone()));
+                plan.actions.push(Action::Write { path, content: new });
+            }
+        }
+    }
+    Ok(plan)
+}
+
+// ── Applying ──
+
+/// What a finished action needs to be reversed, now or from the journal.
+enum Done {
+    Wrote { path: PathBuf, previous: Option<Vec<u8>>, made_dirs: Vec<PathBuf> },
+    Deleted { path: PathBuf, content: Vec<u8> },
+    Renamed { from: PathBuf, to: PathBuf, replaced: Option<Vec<u8>>, made_dirs: Vec<PathBuf> },
+}
+
+/// Create the missing ancestors of `path`, returning them outermost first.
+fn make_parents(path: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut missing: Vec<PathBuf> = path.ancestors().skip(1).take_while(|d| !d.exists()).map(Path::to_path_buf).collect();
+    missing.reverse();
+    for dir in &missing {
+        fs::create_dir(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    Ok(missing)
+}
+
+fn run(root: &Path, action: &Action) -> Result<Done, String> {
+    match action {
+        Action::Write { path, content } => {
+            let previous = if path.exists() {
+                Some(fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?)
+            } else {
+                None
+            };
+            let made_dirs = make_parents(path)?;
+            history::keep(root, path, content.as_bytes());
+            write_atomic(path, content.as_bytes())?;
+            Ok(Done::Wrote { path: path.clone(), previous, made_dirs })
+        }
+        Action::Delete { path } => {
+            let content = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            history::keep(root, path, &[]);
+            fs::remove_file(path).map_err(|e| format!("Failed to delete {}: {}", path.display(), e))?;
+            Ok(Done::Deleted { path: path.clone(), content })
+        }
+        Action::Rename { from, to } => {
+            let replaced = if to.is_file() {
+                Some(fs::read(to).map_err(|e| format!("Failed to read {}: {}", to.display(), e))?)
+            } else {
+                None
+            };
+            let made_dirs = make_parents(to)?;
+            // Folders aren't kept; `keep` skips anything but files.
+            history::keep(root, from, &[]);
+            if replaced.is_some() {
+                history::keep(root, to, &[]);
+            }
+            // Windows won't rename onto an existing file.
+            if replaced.is_some() && cfg!(windows) {
+                fs::remove_file(to).map_err(|e| format!("Failed to replace {}: {}", to.display(), e))?;
+            }
+            fs::rename(from, to).map_err(|e| format!("Failed to rename {}: {}", from.display(), e))?;
+            Ok(Done::Renamed { from: from.clone(), to: to.clone(), replaced, made_dirs })
+        }
+    }
+}
+
+fn remove_made_dirs(dirs: &[PathBuf]) {
+    for dir in dirs.iter().rev() {
+        let _ = fs::remove_dir(dir);
+    }
+}
+
+fn revert(done: &Done) -> Result<(), String> {
+    match done {
+        Done::Wrote { path, previous, made_dirs } => {
+            match previous {
+                Some(bytes) => write_atomic(path, bytes)?,
+                None => fs::remove_file(path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?,
+            }
+            remove_made_dirs(made_dirs);
+        }
+        Done::Deleted { path, content } => write_atomic(path, content)?,
+        Done::Renamed { from, to, replaced, made_dirs } => {
+            fs::rename(to, from).map_err(|e| format!("Failed to move {} back: {}", to.display(), e))?;
+            if let Some(bytes) = replaced {
+                write_atomic(to, bytes)?;
+            }
+            remove_made_dirs(made_dirs);
+        }
+    }
+    Ok(())
+}
+
+/// The journal steps that reverse `done`, taken right after it ran.
+fn journal_steps(done: &Done) -> Vec<journal::Step> {
+    let created_dirs = |dirs: &[PathBuf]| dirs.iter().map(|d| journal::Step::created(d.clone())).collect::<Vec<_>>();
+    match done {
+        Done::Wrote { path, previous: Some(bytes), .. } => vec![journal::Step::restored(path.clone(), bytes.clone())],
+        Done::Wrote { path, previous: None, made_dirs } => {
+            let mut steps = created_dirs(made_dirs);
+            steps.push(journal::Step::created(path.clone()));
+            steps
+        }
+        Done::Deleted { path, content } => vec![journal::Step::restored(path.clone(), content.clone())],
+        Done::Renamed { from, to, replaced, made_dirs } => {
+            // Undone in reverse: the file moves back first, then the one
+            // it replaced is written again.
+            let mut steps = created_dirs(made_dirs);
+            if let Some(bytes) = replaced {
+                steps.push(journal::Step::restored(to.clone(), bytes.clone()));
+            }
+            steps.push(journal::Step::moved(from.clone(), to.clone()));
+            steps
+        }
+    }
+}
+
+/// Run every action or none: on failure, finished actions are reverted
+/// newest first. Returns the journal steps that undo the whole run.
+fn run_all(root: &Path, actions: &[Action]) -> Result<Vec<journal::Step>, String> {
+    let mut done = Vec::new();
+    let mut steps = Vec::new();
+    for action in actions {
+        match run(root, action) {
+            Ok(d) => {
+                steps.extend(journal_steps(&d));
+                done.push(d);
+            }
+            Err(e) => {
+                let rollback: Vec<String> = done.iter().rev().filter_map(|d| revert(d).err()).collect();
+                return Err(if rollback.is_empty() {
+                    format!("{}; no changes were made", e)
+                } else {
+                    format!("{}; rolling back also failed: {}", e, rollback.join("; "))
+                });
+            }
+        }
+    }
+    Ok(steps)
+}
+
+/// Validate `edit` and, unless `dry_run`, apply it and record it in
+/// `window_label`'s undo journal.
+pub(crate) fn apply_edit(
+    root: &Path,
+    window_label: &str,
+    edit: &WorkspaceEdit,
+    dry_run: bool,
+) -> Result<WorkspaceEditResult, String> {
+    let plan = plan(root, edit)?;
+    if dry_run {
+        return Ok(WorkspaceEditResult { changes: plan.changes, applied: false });
+    }
+    let steps = run_all(root, &plan.actions)?;
+    journal::record(window_label, "Edit", steps);
+    Ok(WorkspaceEditResult { changes: plan.changes, applied: true })
+}
+
+/// Apply a set of file creates, deletes, renames and text edits all
+/// together or not at all. See the module docs.
+#[tauri::command]
+pub fn apply_workspace_edit(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    edit: WorkspaceEdit,
+    options: Option<ApplyEditOptions>,
+) -> Result<WorkspaceEditResult, String> {
+    let root = state.blocking_read().get(window.label()).and_then(|o| o.clone()).ok_or("No project is open")?;
+    let dry_run = options.unwrap_or_default().dry_run.unwrap_or(false);
+    apply_edit(&root, window.label(), &edit, dry_run)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(path: &str, (l1, c1): (u32, u32), (l2, c2): (u32, u32), new_text: &str) -> FileOperation {
+        let range = Range { start: Position { line: l1, character: c1 }, end: Position { line: l2, character: c2 } };
+        FileOperation::Edit {
+            path: path.to_string(),
+            edits: vec![TextEdit { range, new_text: new_text.to_string() }],
+            expected_hash: None,
+        }
+    }
+
+    #[test]
+    fn plans_in_order_and_rolls_back_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = fs::canonicalize(dir.path()).unwrap();
+        fs::create_dir_all(root.join("src/util")).unwrap();
+        fs::write(root.join("src/util/mod.rs"), "pub fn old_name() {}\n").unwrap();
+        fs::write(root.join("src/main.rs"), "// ü\nfn main() { util::old_name(); }\n").unwrap();
+
+        // Rename a folder, then edit a file inside it by its new path.
+        let ops = WorkspaceEdit {
+            operations: vec![
+                FileOperation::Rename { from: "src/util".into(), to: "src/helpers".into(), overwrite: false },
+                edit("src/helpers/mod.rs", (0, 7), (0, 15), "new_name"),
+                edit("src/main.rs", (1, 12), (1, 26), "helpers::new_name"),
+                FileOperation::Create { path: "src/helpers/extra.rs".into(), content: "// extra\n".into(), overwrite: false },
+            ],
+        };
+        let dry = apply_edit(&root, "edit-test", &ops, true).unwrap();
+        assert!(!dry.applied && root.join("src/util").exists());
+        assert_eq!(dry.changes.iter().map(|c| c.kind.as_str()).collect::<Vec<_>>(), ["rename", "edit", "edit", "create"]);
+
+        apply_edit(&root, "edit-test", &ops, false).unwrap();
+        assert_eq!(fs::read_to_string(root.join("src/helpers/mod.rs")).unwrap(), "pub fn new_name() {}\n");
+        assert_eq!(fs::read_to_string(root.join("src/main.rs")).unwrap(), "// ü\nfn main() { helpers::new_name(); }\n");
+
+        // Validation catches problems before anything is written.
+        let bad = WorkspaceEdit {
+            operations: vec![
+                edit("src/main.rs", (0, 0), (0, 0), "// header\n"),
+                FileOperation::Delete { path: "src/missing.rs".into() },
+            ],
+        };
+        assert!(apply_edit(&root, "edit-test", &bad, false).unwrap_err().starts_with("Operation 2:"));
+        let overlapping = WorkspaceEdit {
+            operations: vec![FileOperation::Edit {
+                path: "src/main.rs".into(),
+                edits: vec![
+                    TextEdit { range: Range { start: Position { line: 1, character: 0 }, end: Position { line: 1, character: 5 } }, new_text: "x".into() },
+                    TextEdit { range: Range { start: Position { line: 1, character: 3 }, end: Position { line: 1, character: 8 } }, new_text: "y".into() },
+                ],
+                expected_hash: None,
+            }],
+        };
+        assert!(apply_edit(&root, "edit-test", &overlapping, true).unwrap_err().contains("Edits overlap"));
+
+        // A failure while applying reverts the steps already taken.
+        let main = root.join("src/main.rs");
+        let before = fs::read_to_string(&main).unwrap();
+        let actions = [
+            Action::Write { path: main.clone(), content: "changed".into() },
+            Action::Write { path: root.join("new/dir/file.rs"), content: "new".into() },
+            Action::Rename { from: root.join("nope.rs"), to: root.join("also-nope.rs") },
+        ];
+        assert!(run_all(&root, &actions).unwrap_err().ends_with("no changes were made"));
+        assert_eq!(fs::read_to_string(&main).unwrap(), before);
+        assert!(!root.join("new").exists());
+
+        // Columns count UTF-16 units; the emoji is two of them.
+        assert_eq!(Position::at("ab\n😀x", 7), Position { line: 1, character: 2 });
+        assert_eq!(Position { line: 1, character: 2 }.offset_in("ab\n😀x"), Some(7));
+        assert_eq!(Position { line: 1, character: 1 }.offset_in("ab\n😀x"), None);
+    }
+}
@@ -0,0 +1,284 @@
+//! Guided `git bisect`.
+//!
+//! `git_bisect_start` checks out the first commit to test, optionally
+//! saving a shell command as the test. Each step is then marked `good`,
+//! `bad` or `skip` by hand, or with `run`, which runs the saved command in
+//! the repository and marks the commit the way `git bisect run` would:
+//! exit 0 is good, 125 is skip, 1–127 is bad, anything else stops the
+//! hunt. The panel calls `run` repeatedly to automate the search, showing
+//! each result as it goes. Everything but the test command lives in git's
+//! own bisect state, so a bisect started in a terminal shows up too.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use super::validate_repo_path;
+use crate::modules::fs::ProjectRootState;
+use crate::modules::process::ProcessCommand;
+
+/// Saved next to git's `BISECT_*` files.
+const TEST_COMMAND_FILE: &str = "leo-bisect-test";
+/// Lines of test output kept for the panel.
+const OUTPUT_TAIL: usize = 40;
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct BisectCommit {
+    pub hash: String,
+    pub subject: String,
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct BisectStatus {
+    pub active: bool,
+    /// The commit checked out for testing.
+    pub current: Option<BisectCommit>,
+    pub good: Vec<BisectCommit>,
+    pub bad: Option<BisectCommit>,
+    pub skipped: Vec<BisectCommit>,
+    /// Set once git has narrowed the regression to one commit.
+    pub first_bad: Option<BisectCommit>,
+    pub remaining_revisions: Option<u64>,
+    pub remaining_steps: Option<u64>,
+    pub test_command: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BisectTestRun {
+    pub exit_code: Option<i32>,
+    /// `good`, `bad` or `skip`.
+    pub verdict: String,
+    pub output: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BisectStep {
+    /// The test run, when the step was marked with `run`.
+    pub test: Option<BisectTestRun>,
+    pub status: BisectStatus,
+}
+
+fn git(repo: &Path, args: &[&str]) -> Result<String, String> {
+    let output = ProcessCommand::new("git").args(args).current_dir(repo).output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Revisions are passed straight to git, so anything it could read as an
+/// option or that isn't a single word is refused. `HEAD~3`, tags and
+/// hashes are fine.
+pub(super) fn validate_rev(rev: &str) -> Result<(), String> {
+    if rev.is_empty() || rev.starts_with('-') || rev.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(format!("Invalid revision: {}", rev));
+    }
+    Ok(())
+}
+
+fn test_command_path(repo: &Path) -> Result<PathBuf, String> {
+    git(repo, &["rev-parse", "--git-path", TEST_COMMAND_FILE]).map(|p| repo.join(p.trim()))
+}
+
+/// The `# good: [hash] subject` comments of `git bisect log`, in order.
+fn parse_log(log: &str, status: &mut BisectStatus) {
+    for line in log.lines() {
+        let Some((kind, rest)) = line.strip_prefix("# ").and_then(|l| l.split_once(": [")) else { continue };
+        let Some((hash, subject)) = rest.split_once("] ") else { continue };
+        let commit = BisectCommit { hash: hash.to_string(), subject: subject.to_string() };
+        match kind {
+            "good" => status.good.push(commit),
+            "bad" => status.bad = Some(commit),
+            "skip" => status.skipped.push(commit),
+            "first bad commit" => status.first_bad = Some(commit),
+            _ => {}
+        }
+    }
+}
+
+/// `bisect_all` and `bisect_steps` from `git rev-list --bisect-vars`.
+fn parse_bisect_vars(out: &str) -> (Option<u64>, Option<u64>) {
+    let var = |name: &str| {
+        out.lines()
+            .find_map(|l| l.strip_prefix(name)?.strip_prefix('='))
+            .and_then(|v| v.trim().trim_matches('\'').parse().ok())
+    };
+    (var("bisect_all"), var("bisect_steps"))
+}
+
+/// The verdict `git bisect run` gives an exit code, or `None` to abort.
+fn verdict(exit_code: Option<i32>) -> Option<&'static str> {
+    match exit_code? {
+        0 => Some("good"),
+        125 => Some("skip"),
+        1..=127 => Some("bad"),
+        _ => None,
+    }
+}
+
+fn output_tail(stdout: &[u8], stderr: &[u8]) -> String {
+    let text = format!("{}{}", String::from_utf8_lossy(stdout), String::from_utf8_lossy(stderr));
+    let lines: Vec<&str> = text.lines().collect();
+    lines[lines.len().saturating_sub(OUTPUT_TAIL)..].join("\n")
+}
+
+pub fn bisect_status(repo: &Path) -> Result<BisectStatus, String> {
+    let Ok(log) = git(repo, &["bisect", "log"]) else {
+        return Ok(BisectStatus::default());
+    };
+    let mut status = BisectStatus { active: true, ..Default::default() };
+    parse_log(&log, &mut status);
+
+    let head = git(repo, &["log", "-1", "--format=%H%x00%s"])?;
+    status.current = head
+        .trim_end()
+        .split_once('\0')
+        .map(|(hash, subject)| BisectCommit { hash: hash.to_string(), subject: subject.to_string() });
+
+    if let (Some(bad), false, None) = (&status.bad, status.good.is_empty(), &status.first_bad) {
+        let mut args = vec!["rev-list", "--bisect-vars", bad.hash.as_str(), "--not"];
+        args.extend(status.good.iter().map(|c| c.hash.as_str()));
+        if let Ok(vars) = git(repo, &args) {
+            (status.remaining_revisions, status.remaining_steps) = parse_bisect_vars(&vars);
+        }
+    }
+    status.test_command = test_command_path(repo)
+        .ok()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .filter(|c| !c.trim().is_empty());
+    Ok(status)
+}
+
+/// Start bisecting between a known-good and a known-bad revision and
+/// check out the first commit to test. `test_command`, when given, is
+/// what `git_bisect_mark("run")` runs.
+#[tauri::command]
+pub fn git_bisect_start(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    repo_path: String,
+    good: String,
+    bad: String,
+    test_command: Option<String>,
+) -> Result<BisectStatus, String> {
+    let repo = validate_repo_path(&repo_path, window.label(), &state)?;
+    validate_rev(&good)?;
+    validate_rev(&bad)?;
+    git(&repo, &["bisect", "start", &bad, &good, "--"])?;
+    let path = test_command_path(&repo)?;
+    match test_command.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+        Some(command) => std::fs::write(&path, command).map_err(|e| e.to_string())?,
+        None => {
+            let _ = std::fs::remove_file(&path);
+        }
+    }
+    bisect_status(&repo)
+}
+
+/// Mark the checked-out commit `good`, `bad` or `skip`, or `run` the
+/// saved test command and mark it by its exit code.
+#[tauri::command]
+pub fn git_bisect_mark(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    repo_path: String,
+    mark: String,
+) -> Result<BisectStep, String> {
+    let repo = validate_repo_path(&repo_path, window.label(), &state)?;
+    let status = bisect_status(&repo)?;
+    if !status.active {
+        return Err("No bisect in progress".to_string());
+    }
+    if status.first_bad.is_some() {
+        return Err("The first bad commit has already been found".to_string());
+    }
+    let (mark_as, test) = match mark.as_str() {
+        "good" | "bad" | "skip" => (mark.clone(), None),
+        "run" => {
+            let command = status.test_command.ok_or("No test command was set for this bisect")?;
+            let output = ProcessCommand::new("sh")
+                .args(["-c", &command])
+                .current_dir(&repo)
+                .output()
+                .map_err(|e| format!("Failed to run test command: {}", e))?;
+            let exit_code = output.status.code();
+            let run_output = output_tail(&output.stdout, &output.stderr);
+            let Some(v) = verdict(exit_code) else {
+                return Err(format!(
+                    "Test command exited with {}; bisect stopped\n{}",
+                    exit_code.map_or("a signal".to_string(), |c| c.to_string()),
+                    run_output
+                ));
+            };
+            (v.to_string(), Some(BisectTestRun { exit_code, verdict: v.to_string(), output: run_output }))
+        }
+        _ => return Err(format!("Unknown bisect mark: {}", mark)),
+    };
+    git(&repo, &["bisect", &mark_as])?;
+    Ok(BisectStep { test, status: bisect_status(&repo)? })
+}
+
+/// Where the bisect stands: marked commits, the commit under test, the
+/// steps left, and the first bad commit once found.
+#[tauri::command]
+pub fn git_bisect_status(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    repo_path: String,
+) -> Result<BisectStatus, String> {
+    let repo = validate_repo_path(&repo_path, window.label(), &state)?;
+    bisect_status(&repo)
+}
+
+/// End the bisect, returning to the branch it started from.
+#[tauri::command]
+pub fn git_bisect_reset(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    repo_path: String,
+) -> Result<(), String> {
+    let repo = validate_repo_path(&repo_path, window.label(), &state)?;
+    git(&repo, &["bisect", "reset"])?;
+    if let Ok(path) = test_command_path(&repo) {
+        let _ = std::fs::remove_file(path);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_bisect_log_vars_and_verdicts() {
+        let log = "\
+git bisect start 'bad1' 'good1' '--'
+# status: waiting for both good and bad commits
+# bad: [bbb111] Break the parser
+# good: [aaa111] Release 1.0
+git bisect good aaa111
+# skip: [ccc111] WIP
+# bad: [bbb222] Tweak lexer
+# first bad commit: [bbb222] Tweak lexer
+";
+        let mut status = BisectStatus::default();
+        parse_log(log, &mut status);
+        let commit = |hash: &str, subject: &str| BisectCommit { hash: hash.into(), subject: subject.into() };
+        assert_eq!(status.good, [commit("aaa111", "Release 1.0")]);
+        assert_eq!(status.bad, Some(commit("bbb222", "Tweak lexer")));
+        assert_eq!(status.skipped, [commit("ccc111", "WIP")]);
+        assert_eq!(status.first_bad, Some(commit("bbb222", "Tweak lexer")));
+
+        let vars = "bisect_rev='abc'\nbisect_nr=3\nbisect_good=2\nbisect_bad=3\nbisect_all=7\nbisect_steps=2\n";
+        assert_eq!(parse_bisect_vars(vars), (Some(7), Some(2)));
+
+        assert_eq!(verdict(Some(0)), Some("good"));
+        assert_eq!(verdict(Some(125)), Some("skip"));
+        assert_eq!(verdict(Some(1)), Some("bad"));
+        assert_eq!(verdict(Some(128)), None);
+        assert_eq!(verdict(None), None);
+
+        assert!(validate_rev("HEAD~3").is_ok());
+        assert!(validate_rev("--all").is_err());
+        assert!(validate_rev("a b").is_err());
+    }
+}
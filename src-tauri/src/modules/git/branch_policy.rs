@@ -0,0 +1,229 @@
+//! Branch naming templates and policy.
+//!
+//! A template such as `{user}/{type}/{ticket}-{slug}` is filled from
+//! caller-supplied values; `{user}` defaults to the slugified
+//! `git config user.name` and `{slug}` to the slugified `title`. The
+//! result must be a valid ref name and, if the repository has one, match
+//! the policy regex. Template and policy are stored in the repository's
+//! git config as `leo.branchTemplate` and `leo.branchPolicy`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::{validate_git_ref_name, validate_repo_path};
+use crate::modules::fs::ProjectRootState;
+use crate::modules::net;
+use crate::modules::process::ProcessCommand;
+
+const TEMPLATE_KEY: &str = "leo.branchTemplate";
+const POLICY_KEY: &str = "leo.branchPolicy";
+const DEFAULT_TEMPLATE: &str = "{type}/{slug}";
+const MAX_SLUG_LEN: usize = 48;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct BranchPolicy {
+    pub template: Option<String>,
+    /// Regex the whole branch name must match.
+    pub pattern: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct BranchFromTemplate {
+    pub repo_path: String,
+    /// Overrides the repository's template.
+    pub template: Option<String>,
+    /// Placeholder values, e.g. `{"type": "feat", "ticket": "ABC-12"}`.
+    pub values: HashMap<String, String>,
+    /// Source for `{slug}` when `values` has none.
+    pub title: Option<String>,
+    /// Branch or commit to start from; HEAD when omitted.
+    pub start_point: Option<String>,
+    pub checkout: bool,
+    /// Push the new branch to `origin` and track it.
+    pub set_upstream: bool,
+    /// Only render and validate the name.
+    pub dry_run: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CreatedBranch {
+    pub name: String,
+    pub created: bool,
+    pub checked_out: bool,
+    pub upstream: Option<String>,
+}
+
+fn git(repo: &Path, args: &[&str]) -> Result<String, String> {
+    let output = ProcessCommand::new("git").args(args).current_dir(repo).output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn config_value(repo: &Path, key: &str) -> Option<String> {
+    git(repo, &["config", "--get", key]).ok().filter(|v| !v.is_empty())
+}
+
+/// Lowercase ASCII words joined by `-`, at most `MAX_SLUG_LEN` long.
+pub(crate) fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for word in text.split(|c: char| !c.is_ascii_alphanumeric()).filter(|w| !w.is_empty()) {
+        if !slug.is_empty() && slug.len() + 1 + word.len() > MAX_SLUG_LEN {
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(&word.to_ascii_lowercase());
+    }
+    slug.truncate(MAX_SLUG_LEN);
+    slug
+}
+
+fn render(template: &str, values: &HashMap<String, String>) -> Result<String, String> {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let close = rest[open..].find('}').ok_or("Invalid branch template: unclosed '{'")? + open;
+        let key = &rest[open + 1..close];
+        let value = values.get(key).map(|v| v.trim()).filter(|v| !v.is_empty());
+        out.push_str(value.ok_or_else(|| format!("Missing value for {{{}}}", key))?);
+        rest = &rest[close + 1..];
+    }
+    out.push_str(rest);
+    validate_git_ref_name(&out)?;
+    Ok(out)
+}
+
+fn check_policy(name: &str, pattern: Option<&str>) -> Result<(), String> {
+    let Some(pattern) = pattern else { return Ok(()) };
+    let re = regex::Regex::new(&format!("^(?:{})$", pattern)).map_err(|e| format!("Invalid branch policy: {}", e))?;
+    if !re.is_match(name) {
+        return Err(format!("Branch name '{}' does not match the policy {}", name, pattern));
+    }
+    Ok(())
+}
+
+fn branch_name(repo: &Path, params: &BranchFromTemplate) -> Result<String, String> {
+    let template = params.template.clone().or_else(|| config_value(repo, TEMPLATE_KEY)).unwrap_or(DEFAULT_TEMPLATE.into());
+    let mut values = params.values.clone();
+    if !values.contains_key("user") {
+        if let Some(user) = config_value(repo, "user.name") {
+            values.insert("user".into(), slugify(&user));
+        }
+    }
+    if !values.contains_key("slug") {
+        if let Some(title) = &params.title {
+            values.insert("slug".into(), slugify(title));
+        }
+    }
+    let name = render(&template, &values)?;
+    check_policy(&name, config_value(repo, POLICY_KEY).as_deref())?;
+    Ok(name)
+}
+
+// ── Commands ──
+
+#[tauri::command]
+pub fn get_branch_policy(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    repo_path: String,
+) -> Result<BranchPolicy, String> {
+    let repo = validate_repo_path(&repo_path, window.label(), &state)?;
+    Ok(BranchPolicy { template: config_value(&repo, TEMPLATE_KEY), pattern: config_value(&repo, POLICY_KEY) })
+}
+
+/// Save the template and policy to the repository's local git config.
+/// `None` fields are removed.
+#[tauri::command]
+pub fn set_branch_policy(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    repo_path: String,
+    policy: BranchPolicy,
+) -> Result<BranchPolicy, String> {
+    let repo = validate_repo_path(&repo_path, window.label(), &state)?;
+    if let Some(pattern) = &policy.pattern {
+        regex::Regex::new(pattern).map_err(|e| format!("Invalid branch policy: {}", e))?;
+    }
+    for (key, value) in [(TEMPLATE_KEY, &policy.template), (POLICY_KEY, &policy.pattern)] {
+        match value {
+            Some(v) => git(&repo, &["config", "--local", key, v])?,
+            // Exit code 5 means the key wasn't set, which is fine.
+            None => git(&repo, &["config", "--local", "--unset", key]).unwrap_or_default(),
+        };
+    }
+    Ok(policy)
+}
+
+/// Create a branch named from the template (see module docs), optionally
+/// checking it out and pushing it with upstream tracking. With `dry_run`
+/// only the validated name is returned.
+#[tauri::command]
+pub fn git_create_branch_from_template(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    params: BranchFromTemplate,
+) -> Result<CreatedBranch, String> {
+    let repo = validate_repo_path(&params.repo_path, window.label(), &state)?;
+    let name = branch_name(&repo, &params)?;
+    let mut result = CreatedBranch { name: name.clone(), created: false, checked_out: false, upstream: None };
+    if params.dry_run {
+        return Ok(result);
+    }
+    if git(&repo, &["rev-parse", "--verify", "--quiet", &format!("refs/heads/{}", name)]).is_ok() {
+        return Err(format!("Branch '{}' already exists", name));
+    }
+    let mut args = if params.checkout { vec!["checkout", "-b", &name] } else { vec!["branch", &name] };
+    if let Some(start) = &params.start_point {
+        if start.starts_with('-') {
+            return Err("Invalid start point".to_string());
+        }
+        args.push(start);
+    }
+    git(&repo, &args)?;
+    result.created = true;
+    result.checked_out = params.checkout;
+
+    if params.set_upstream {
+        net::ensure_online("git push")?;
+        git(&repo, &["push", "--set-upstream", "origin", &name])?;
+        result.upstream = Some(format!("origin/{}", name));
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn renders_templates() {
+        assert_eq!(slugify("Fix: login fails on Safari (again)!"), "fix-login-fails-on-safari-again");
+        assert!(slugify(&"word ".repeat(40)).len() <= MAX_SLUG_LEN);
+
+        let v = values(&[("user", "ada"), ("type", "feat"), ("ticket", "ABC-12"), ("slug", "dark-mode")]);
+        assert_eq!(render("{user}/{type}/{ticket}-{slug}", &v).unwrap(), "ada/feat/ABC-12-dark-mode");
+        assert_eq!(render("{type}/{ticket}", &values(&[("type", "fix")])).unwrap_err(), "Missing value for {ticket}");
+        assert!(render("{type", &v).is_err());
+        assert!(render("{type}/../{slug}", &v).is_err());
+    }
+
+    #[test]
+    fn enforces_policy() {
+        let policy = Some(r"(feat|fix|chore)/[A-Z]+-\d+-[a-z0-9-]+");
+        assert!(check_policy("feat/ABC-12-dark-mode", policy).is_ok());
+        assert!(check_policy("feature/ABC-12-dark-mode", policy).is_err());
+        assert!(check_policy("xfeat/ABC-12-x", Some("feat/.*")).is_err());
+        assert!(check_policy("anything", None).is_ok());
+    }
+}
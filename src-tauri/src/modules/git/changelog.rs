@@ -0,0 +1,328 @@
+//! Release notes from Conventional Commits.
+//!
+//! Commits between two refs are parsed as `type(scope)!: subject` and
+//! grouped into sections by type, scopes shown in bold. `!` or a
+//! `BREAKING CHANGE:` footer also lists the commit under "Breaking
+//! Changes". With links enabled, commit hashes and `#12` references link
+//! to the forge detected from the `origin` remote (GitHub, GitLab,
+//! Bitbucket or Gitea-style hosts).
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::validate_repo_path;
+use crate::modules::fs::ProjectRootState;
+use crate::modules::process::ProcessCommand;
+
+/// Section titles in output order. Types not listed go to "Other Changes".
+const SECTIONS: &[(&str, &str)] = &[
+    ("feat", "Features"),
+    ("fix", "Bug Fixes"),
+    ("perf", "Performance"),
+    ("refactor", "Refactoring"),
+    ("docs", "Documentation"),
+    ("test", "Tests"),
+    ("build", "Build System"),
+    ("ci", "Continuous Integration"),
+    ("style", "Styles"),
+    ("chore", "Chores"),
+    ("revert", "Reverts"),
+];
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ChangelogOptions {
+    /// Heading for the release, e.g. "1.4.0"; defaults to `to_ref`.
+    pub version: Option<String>,
+    /// Link commits, issues and the compare view to the forge.
+    pub links: bool,
+    /// Include commits that aren't conventional under "Other Changes".
+    pub include_other: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Changelog {
+    pub markdown: String,
+    /// The start ref actually used (the latest tag when not given).
+    pub from_ref: Option<String>,
+    pub to_ref: String,
+    pub commits: usize,
+    pub forge_url: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+struct ConventionalCommit {
+    hash: String,
+    kind: String,
+    scope: Option<String>,
+    subject: String,
+    breaking: bool,
+}
+
+fn parse_commit(hash: &str, subject: &str, body: &str) -> Option<ConventionalCommit> {
+    let (head, rest) = subject.split_once(": ")?;
+    let (head, bang) = match head.strip_suffix('!') {
+        Some(h) => (h, true),
+        None => (head, false),
+    };
+    let (kind, scope) = match head.split_once('(') {
+        Some((kind, scope)) => (kind, Some(scope.strip_suffix(')')?.to_string())),
+        None => (head, None),
+    };
+    if kind.is_empty() || !kind.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let breaking = bang || body.lines().any(|l| l.starts_with("BREAKING CHANGE:") || l.starts_with("BREAKING-CHANGE:"));
+    Some(ConventionalCommit {
+        hash: hash.to_string(),
+        kind: kind.to_ascii_lowercase(),
+        scope: scope.filter(|s| !s.is_empty()),
+        subject: rest.trim().to_string(),
+        breaking,
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Forge {
+    GitHub,
+    GitLab,
+    Bitbucket,
+    /// Gitea, Forgejo and others that copy GitHub's URL layout.
+    Other,
+}
+
+/// Web URL of a remote: `git@github.com:o/r.git` and
+/// `https://user@github.com/o/r.git` both become `https://github.com/o/r`.
+fn forge_from_remote(remote: &str) -> Option<(Forge, String)> {
+    let remote = remote.trim();
+    let (host, path) = if let Some(rest) = remote.strip_prefix("git@") {
+        rest.split_once(':')?
+    } else {
+        let rest = remote.split_once("://")?.1;
+        let rest = rest.rsplit_once('@').map_or(rest, |(_, r)| r);
+        rest.split_once('/')?
+    };
+    let host = host.split(':').next()?;
+    let path = path.trim_matches('/').trim_end_matches(".git");
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+    let forge = match host {
+        h if h.contains("github") => Forge::GitHub,
+        h if h.contains("gitlab") => Forge::GitLab,
+        h if h.contains("bitbucket") => Forge::Bitbucket,
+        _ => Forge::Other,
+    };
+    Some((forge, format!("https://{}/{}", host, path)))
+}
+
+struct Links {
+    forge: Forge,
+    base: String,
+}
+
+impl Links {
+    fn commit(&self, hash: &str) -> String {
+        match self.forge {
+            Forge::GitLab => format!("{}/-/commit/{}", self.base, hash),
+            Forge::Bitbucket => format!("{}/commits/{}", self.base, hash),
+            Forge::GitHub | Forge::Other => format!("{}/commit/{}", self.base, hash),
+        }
+    }
+
+    fn issue(&self, number: &str) -> String {
+        match self.forge {
+            Forge::GitLab => format!("{}/-/issues/{}", self.base, number),
+            Forge::GitHub | Forge::Bitbucket | Forge::Other => format!("{}/issues/{}", self.base, number),
+        }
+    }
+
+    fn compare(&self, from: &str, to: &str) -> String {
+        match self.forge {
+            Forge::GitLab => format!("{}/-/compare/{}...{}", self.base, from, to),
+            Forge::Bitbucket => format!("{}/branches/compare/{}%0D{}", self.base, to, from),
+            Forge::GitHub | Forge::Other => format!("{}/compare/{}...{}", self.base, from, to),
+        }
+    }
+
+    /// Turn `#12` into a link.
+    fn issues_in(&self, text: &str) -> String {
+        let re = regex::Regex::new(r"(^|[\s(])#(\d+)\b").expect("valid regex");
+        re.replace_all(text, |c: &regex::Captures| format!("{}[#{}]({})", &c[1], &c[2], self.issue(&c[2]))).into_owned()
+    }
+}
+
+fn entry(commit: &ConventionalCommit, links: Option<&Links>, with_type: bool) -> String {
+    let mut line = String::from("- ");
+    if with_type {
+        line.push_str(&format!("{}: ", commit.kind));
+    }
+    if let Some(scope) = &commit.scope {
+        line.push_str(&format!("**{}:** ", scope));
+    }
+    let short = &commit.hash[..commit.hash.len().min(7)];
+    match links {
+        Some(l) => line.push_str(&format!("{} ([{}]({}))", l.issues_in(&commit.subject), short, l.commit(&commit.hash))),
+        None => line.push_str(&format!("{} ({})", commit.subject, short)),
+    }
+    line
+}
+
+fn render(
+    heading: &str,
+    date: Option<&str>,
+    commits: &[ConventionalCommit],
+    others: &[(String, String)],
+    links: Option<&Links>,
+    compare: Option<String>,
+) -> String {
+    let mut out = match (compare, date) {
+        (Some(url), Some(d)) => format!("## [{}]({}) ({})\n", heading, url, d),
+        (Some(url), None) => format!("## [{}]({})\n", heading, url),
+        (None, Some(d)) => format!("## {} ({})\n", heading, d),
+        (None, None) => format!("## {}\n", heading),
+    };
+    let mut section = |title: &str, lines: Vec<String>| {
+        if !lines.is_empty() {
+            out.push_str(&format!("\n### {}\n\n{}\n", title, lines.join("\n")));
+        }
+    };
+    section("⚠ Breaking Changes", commits.iter().filter(|c| c.breaking).map(|c| entry(c, links, true)).collect());
+    for (kind, title) in SECTIONS {
+        section(title, commits.iter().filter(|c| c.kind == *kind).map(|c| entry(c, links, false)).collect());
+    }
+    let mut other: Vec<String> = commits
+        .iter()
+        .filter(|c| !SECTIONS.iter().any(|(k, _)| *k == c.kind))
+        .map(|c| entry(c, links, true))
+        .collect();
+    for (hash, subject) in others {
+        let short = &hash[..hash.len().min(7)];
+        other.push(match links {
+            Some(l) => format!("- {} ([{}]({}))", l.issues_in(subject), short, l.commit(hash)),
+            None => format!("- {} ({})", subject, short),
+        });
+    }
+    section("Other Changes", other);
+    out
+}
+
+fn git(repo: &Path, args: &[&str]) -> Result<String, String> {
+    let output = ProcessCommand::new("git").args(args).current_dir(repo).output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn check_ref(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.starts_with('-') || name.contains("..") {
+        return Err(format!("Invalid ref: {}", name));
+    }
+    Ok(())
+}
+
+/// Markdown release notes for the commits in `from_ref..to_ref`.
+/// `from_ref` defaults to the latest tag reachable from `to_ref` (the
+/// whole history when there is none); `to_ref` defaults to HEAD.
+#[tauri::command]
+pub fn generate_changelog(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    repo_path: String,
+    from_ref: Option<String>,
+    to_ref: Option<String>,
+    options: Option<ChangelogOptions>,
+) -> Result<Changelog, String> {
+    let repo = validate_repo_path(&repo_path, window.label(), &state)?;
+    let options = options.unwrap_or_default();
+    let to_ref = to_ref.unwrap_or_else(|| "HEAD".to_string());
+    check_ref(&to_ref)?;
+    let from_ref = match from_ref {
+        Some(r) => Some(r),
+        None => git(&repo, &["describe", "--tags", "--abbrev=0", &to_ref]).ok().map(|t| t.trim().to_string()),
+    };
+    if let Some(r) = &from_ref {
+        check_ref(r)?;
+    }
+
+    let range = match &from_ref {
+        Some(from) => format!("{}..{}", from, to_ref),
+        None => to_ref.clone(),
+    };
+    let log = git(&repo, &["log", "--no-merges", "--format=%H%x1f%s%x1f%b%x1e", &range, "--"])?;
+    let mut commits = Vec::new();
+    let mut others = Vec::new();
+    let mut total = 0;
+    for record in log.split('\x1e').map(|r| r.trim_start_matches('\n')).filter(|r| !r.is_empty()) {
+        let mut fields = record.splitn(3, '\x1f');
+        let (Some(hash), Some(subject)) = (fields.next(), fields.next()) else { continue };
+        total += 1;
+        match parse_commit(hash, subject, fields.next().unwrap_or_default()) {
+            Some(c) => commits.push(c),
+            None if options.include_other => others.push((hash.to_string(), subject.to_string())),
+            None => {}
+        }
+    }
+
+    let links = git(&repo, &["remote", "get-url", "origin"])
+        .ok()
+        .and_then(|url| forge_from_remote(&url))
+        .map(|(forge, base)| Links { forge, base });
+    let forge_url = links.as_ref().map(|l| l.base.clone());
+    let links = links.filter(|_| options.links);
+    let compare = links.as_ref().zip(from_ref.as_ref()).map(|(l, from)| l.compare(from, &to_ref));
+    let date = git(&repo, &["log", "-1", "--format=%cs", &to_ref, "--"]).ok().map(|d| d.trim().to_string());
+    let heading = options.version.clone().unwrap_or_else(|| to_ref.clone());
+    let markdown = render(&heading, date.as_deref().filter(|d| !d.is_empty()), &commits, &others, links.as_ref(), compare);
+
+    Ok(Changelog { markdown, from_ref, to_ref, commits: total, forge_url })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_conventional_subjects() {
+        let c = parse_commit("abc1234567", "feat(parser)!: support tabs", "").unwrap();
+        assert_eq!((c.kind.as_str(), c.scope.as_deref(), c.breaking), ("feat", Some("parser"), true));
+        let c = parse_commit("abc", "fix: handle #12", "details\n\nBREAKING CHANGE: renamed API").unwrap();
+        assert!(c.breaking);
+        assert_eq!(c.subject, "handle #12");
+        assert!(parse_commit("abc", "Merge branch 'main'", "").is_none());
+        assert!(parse_commit("abc", "feat(broken: x", "").is_none());
+    }
+
+    #[test]
+    fn detects_forges() {
+        assert_eq!(forge_from_remote("git@github.com:acme/app.git"), Some((Forge::GitHub, "https://github.com/acme/app".into())));
+        assert_eq!(
+            forge_from_remote("https://ci-token@gitlab.example.com/group/sub/app.git\n"),
+            Some((Forge::GitLab, "https://gitlab.example.com/group/sub/app".into()))
+        );
+        assert_eq!(forge_from_remote("ssh://git@bitbucket.org:22/acme/app"), Some((Forge::Bitbucket, "https://bitbucket.org/acme/app".into())));
+        assert_eq!(forge_from_remote("/srv/git/app.git"), None);
+    }
+
+    #[test]
+    fn renders_grouped_markdown() {
+        let commits = vec![
+            parse_commit("1111111aaaa", "feat(ui): dark mode (#12)", "").unwrap(),
+            parse_commit("2222222bbbb", "fix: crash on save", "").unwrap(),
+            parse_commit("3333333cccc", "feat!: drop node 16", "").unwrap(),
+            parse_commit("4444444dddd", "wip: experiments", "").unwrap(),
+        ];
+        let links = Links { forge: Forge::GitHub, base: "https://github.com/acme/app".into() };
+        let md = render("1.2.0", Some("2024-05-01"), &commits, &[], Some(&links), Some(links.compare("v1.1.0", "HEAD")));
+        assert!(md.starts_with("## [1.2.0](https://github.com/acme/app/compare/v1.1.0...HEAD) (2024-05-01)\n"));
+        assert!(md.contains("### ⚠ Breaking Changes\n\n- feat: drop node 16"));
+        assert!(md.contains(
+            "### Features\n\n- **ui:** dark mode ([#12](https://github.com/acme/app/issues/12)) ([1111111](https://github.com/acme/app/commit/1111111aaaa))\n- drop node 16"
+        ));
+        assert!(md.contains("### Bug Fixes\n\n- crash on save"));
+        assert!(md.contains("### Other Changes\n\n- wip: experiments"));
+        let plain = render("HEAD", None, &commits[1..2], &[("5555555".into(), "Update README".into())], None, None);
+        assert_eq!(plain, "## HEAD\n\n### Bug Fixes\n\n- crash on save (2222222)\n\n### Other Changes\n\n- Update README (5555555)\n");
+    }
+}
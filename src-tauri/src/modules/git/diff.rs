@@ -0,0 +1,193 @@
+//! Diff options and in-memory text diffs.
+//!
+//! `DiffOptions` is shared by `git_diff`, which hands it to git as
+//! `--ignore-all-space`, `--ignore-blank-lines` and `-U<n>`, and
+//! `diff_strings`, which diffs two
+//! texts directly and applies the same rules itself: with whitespace
+//! ignored, lines are compared with all whitespace removed and unchanged
+//! lines show their new text; with blank lines ignored, a run of changes
+//! that only adds or removes blank lines doesn't start a hunk of its own.
+//! Both return the `DiffLine`s `parse_unified_diff` produces, hunk headers
+//! included, so the same viewer renders either.
+
+use serde::Deserialize;
+use similar::{capture_diff_slices, Algorithm, DiffOp};
+
+use super::DiffLine;
+
+const DEFAULT_CONTEXT_LINES: u32 = 3;
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct DiffOptions {
+    /// Ignore changes in whitespace, including whitespace added inside a line.
+    pub ignore_whitespace: Option<bool>,
+    /// Ignore changes whose lines are all blank.
+    pub ignore_blank_lines: Option<bool>,
+    /// Unchanged lines shown around each change; defaults to 3.
+    pub context_lines: Option<u32>,
+}
+
+impl DiffOptions {
+    /// The matching `git diff` flags.
+    pub fn git_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.ignore_whitespace.unwrap_or(false) {
+            args.push("--ignore-all-space".to_string());
+        }
+        if self.ignore_blank_lines.unwrap_or(false) {
+            args.push("--ignore-blank-lines".to_string());
+        }
+        if let Some(n) = self.context_lines {
+            args.push(format!("-U{}", n));
+        }
+        args
+    }
+}
+
+/// One line of the edit script: unchanged (old and new index), removed or
+/// added.
+#[derive(Clone, Copy)]
+enum Item {
+    Same(usize, usize),
+    Del(usize),
+    Add(usize),
+}
+
+fn edit_script(old: &[&str], new: &[&str], ignore_whitespace: bool) -> Vec<Item> {
+    let key = |line: &&str| if ignore_whitespace { line.split_whitespace().collect() } else { line.to_string() };
+    let old_keys: Vec<String> = old.iter().map(key).collect();
+    let new_keys: Vec<String> = new.iter().map(key).collect();
+    let mut items = Vec::new();
+    for op in capture_diff_slices(Algorithm::Myers, &old_keys, &new_keys) {
+        match op {
+            DiffOp::Equal { old_index, new_index, len } => {
+                items.extend((0..len).map(|i| Item::Same(old_index + i, new_index + i)));
+            }
+            DiffOp::Delete { old_index, old_len, .. } => items.extend((old_index..old_index + old_len).map(Item::Del)),
+            DiffOp::Insert { new_index, new_len, .. } => items.extend((new_index..new_index + new_len).map(Item::Add)),
+            DiffOp::Replace { old_index, old_len, new_index, new_len } => {
+                items.extend((old_index..old_index + old_len).map(Item::Del));
+                items.extend((new_index..new_index + new_len).map(Item::Add));
+            }
+        }
+    }
+    items
+}
+
+/// Diff two texts line by line.
+pub fn diff_lines(old: &str, new: &str, options: &DiffOptions) -> Vec<DiffLine> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let items = edit_script(&old_lines, &new_lines, options.ignore_whitespace.unwrap_or(false));
+    let context = options.context_lines.unwrap_or(DEFAULT_CONTEXT_LINES) as usize;
+
+    // Which changes start a hunk: all of them, unless blank-only runs are
+    // ignored.
+    let mut significant = vec![false; items.len()];
+    let mut i = 0;
+    while i < items.len() {
+        if matches!(items[i], Item::Same(..)) {
+            i += 1;
+            continue;
+        }
+        let end = (i..items.len()).find(|&j| matches!(items[j], Item::Same(..))).unwrap_or(items.len());
+        let blank_only = items[i..end].iter().all(|item| match *item {
+            Item::Del(o) => old_lines[o].trim().is_empty(),
+            Item::Add(n) => new_lines[n].trim().is_empty(),
+            Item::Same(..) => true,
+        });
+        let keep = !(blank_only && options.ignore_blank_lines.unwrap_or(false));
+        significant[i..end].iter_mut().for_each(|s| *s = keep);
+        i = end;
+    }
+
+    // Hunk ranges over `items`, merged when their context touches.
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for (i, _) in significant.iter().enumerate().filter(|(_, &s)| s) {
+        let (start, end) = (i.saturating_sub(context), (i + context + 1).min(items.len()));
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut old_seen, mut new_seen, mut at) = (0, 0, 0);
+    let line = |kind: &str, old_num: Option<usize>, new_num: Option<usize>, text: &str| DiffLine {
+        kind: kind.to_string(),
+        old_num: old_num.map(|n| n as u32 + 1),
+        new_num: new_num.map(|n| n as u32 + 1),
+        text: text.to_string(),
+    };
+    for (start, end) in hunks {
+        for item in &items[at..start] {
+            match item {
+                Item::Same(..) => (old_seen, new_seen) = (old_seen + 1, new_seen + 1),
+                Item::Del(_) => old_seen += 1,
+                Item::Add(_) => new_seen += 1,
+            }
+        }
+        let hunk = &items[start..end];
+        let old_count = hunk.iter().filter(|item| !matches!(item, Item::Add(_))).count();
+        let new_count = hunk.iter().filter(|item| !matches!(item, Item::Del(_))).count();
+        // An empty side starts at the line before it, as in git's output.
+        let old_start = if old_count == 0 { old_seen } else { old_seen + 1 };
+        let new_start = if new_count == 0 { new_seen } else { new_seen + 1 };
+        let header = format!("@@ -{},{} +{},{} @@", old_start, old_count, new_start, new_count);
+        lines.push(line("ctx", None, None, &header));
+        for item in hunk {
+            lines.push(match *item {
+                Item::Same(o, n) => line("ctx", Some(o), Some(n), new_lines[n]),
+                Item::Del(o) => line("del", Some(o), None, old_lines[o]),
+                Item::Add(n) => line("add", None, Some(n), new_lines[n]),
+            });
+        }
+        (old_seen, new_seen, at) = (old_seen + old_count, new_seen + new_count, end);
+    }
+    lines
+}
+
+/// Diff two texts, e.g. an editor buffer against the file on disk.
+#[tauri::command]
+pub fn diff_strings(old: String, new: String, options: Option<DiffOptions>) -> Vec<DiffLine> {
+    diff_lines(&old, &new, &options.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(lines: &[DiffLine]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|l| match l.kind.as_str() {
+                "add" => format!("+{}", l.text),
+                "del" => format!("-{}", l.text),
+                _ if l.old_num.is_none() => l.text.clone(),
+                _ => format!(" {}", l.text),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn options_control_whitespace_blank_lines_and_context() {
+        let old = "a\n  b\nc\nd\ne\nf\n";
+        let new = "a\nb\nc\n\nd\ne\nF\n";
+
+        let all = diff_lines(old, new, &DiffOptions { context_lines: Some(0), ..Default::default() });
+        assert_eq!(render(&all), ["@@ -2,1 +2,1 @@", "-  b", "+b", "@@ -3,0 +4,1 @@", "+", "@@ -6,1 +7,1 @@", "-f", "+F"]);
+
+        let quiet = DiffOptions {
+            ignore_whitespace: Some(true),
+            ignore_blank_lines: Some(true),
+            context_lines: Some(1),
+        };
+        let lines = diff_lines(old, new, &quiet);
+        assert_eq!(render(&lines), ["@@ -5,2 +6,2 @@", " e", "-f", "+F"]);
+        assert_eq!((lines[1].old_num, lines[1].new_num), (Some(5), Some(6)));
+
+        assert!(diff_lines("x\n", "x\n", &DiffOptions::default()).is_empty());
+        assert_eq!(quiet.git_args(), ["--ignore-all-space", "--ignore-blank-lines", "-U1"]);
+    }
+}
@@ -0,0 +1,217 @@
+//! Gitignored paths, kept current as ignore files change.
+//!
+//! `get_git_ignored` answers once. `watch_git_ignored` also watches the
+//! repo's ignore files — every `.gitignore`, `.git/info/exclude` and the
+//! user's global excludes file — and lists the ignored paths again once
+//! edits to them settle, sending the new set to the window as an
+//! `ignored-files-changed` event so the file tree's dimming follows along.
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::AppHandle;
+use ts_rs::TS;
+
+use super::validate_repo_path;
+use crate::modules::events;
+use crate::modules::fs::ProjectRootState;
+use crate::modules::process::ProcessCommand;
+
+/// List again this long after the last ignore file change, so saving
+/// several at once (a checkout, a search and replace) lists once.
+const SETTLE_DELAY: Duration = Duration::from_millis(300);
+
+#[derive(Serialize, Clone, Debug, TS)]
+pub struct IgnoredFilesChanged {
+    /// The repo as passed to `watch_git_ignored`.
+    pub repo_path: String,
+    pub ignored: Vec<String>,
+}
+
+/// Absolute paths of the ignored files and directories in `repo`, as
+/// `git ls-files` reports them (an ignored directory, not its contents).
+pub(crate) fn list(repo: &Path) -> Result<Vec<String>, String> {
+    let output = ProcessCommand::new("git")
+        .args([
+            "ls-files",
+            "--others",
+            "--ignored",
+            "--exclude-standard",
+            "--directory",
+        ])
+        .current_dir(repo)
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        return Ok(Vec::new());
+    }
+    Ok(parse_ls_files(&String::from_utf8_lossy(&output.stdout), repo))
+}
+
+fn parse_ls_files(stdout: &str, repo: &Path) -> Vec<String> {
+    stdout
+        .lines()
+        .filter(|l| !l.is_empty())
+        .map(|l| repo.join(l.trim_end_matches('/')).to_string_lossy().to_string())
+        .collect()
+}
+
+/// The user's `core.excludesFile`, or git's default for it.
+fn global_excludes(repo: &Path) -> Option<PathBuf> {
+    let configured = ProcessCommand::new("git")
+        .args(["config", "--path", "core.excludesFile"])
+        .current_dir(repo)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .filter(|s| !s.is_empty());
+    match configured {
+        Some(path) => Some(repo.join(path)),
+        None => std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|p| p.is_absolute())
+            .or_else(|| dirs::home_dir().map(|h| h.join(".config")))
+            .map(|config| config.join("git").join("ignore")),
+    }
+}
+
+/// Whether a change to `path` can change what `repo` ignores.
+fn is_ignore_file(repo: &Path, global: Option<&Path>, path: &Path) -> bool {
+    if global == Some(path) || path == repo.join(".git").join("info").join("exclude") {
+        return true;
+    }
+    let in_git_dir = path.strip_prefix(repo).is_ok_and(|rel| rel.components().any(|c| c.as_os_str() == ".git"));
+    path.file_name().is_some_and(|name| name == ".gitignore") && !in_git_dir
+}
+
+/// Watch `repo`'s ignore files, calling `on_change` with the ignored
+/// paths whenever they differ from `current`. Dropping the watcher stops
+/// it, and the thread behind it exits.
+fn watch(
+    repo: &Path,
+    current: Vec<String>,
+    on_change: impl Fn(Vec<String>) + Send + 'static,
+) -> Result<RecommendedWatcher, String> {
+    let global = global_excludes(repo);
+    let (tx, rx) = mpsc::channel::<()>();
+    let (watched_repo, watched_global) = (repo.to_path_buf(), global.clone());
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else { return };
+        if !event.kind.is_access() && event.paths.iter().any(|p| is_ignore_file(&watched_repo, watched_global.as_deref(), p)) {
+            let _ = tx.send(());
+        }
+    })
+    .map_err(|e| format!("Failed to watch ignore files: {}", e))?;
+    watcher.watch(repo, RecursiveMode::Recursive).map_err(|e| format!("Failed to watch ignore files: {}", e))?;
+    // The global file may not exist yet; watch its folder for it to appear.
+    if let Some(dir) = global.as_deref().and_then(Path::parent).filter(|d| d.is_dir() && !d.starts_with(repo)) {
+        let _ = watcher.watch(dir, RecursiveMode::NonRecursive);
+    }
+
+    let repo = repo.to_path_buf();
+    std::thread::spawn(move || {
+        let mut current = current;
+        while rx.recv().is_ok() {
+            loop {
+                match rx.recv_timeout(SETTLE_DELAY) {
+                    Ok(()) => continue,
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+            let Ok(ignored) = list(&repo) else { continue };
+            if ignored != current {
+                current = ignored.clone();
+                on_change(ignored);
+            }
+        }
+    });
+    Ok(watcher)
+}
+
+struct RepoWatch {
+    repo: PathBuf,
+    _watcher: RecommendedWatcher,
+}
+
+/// Ignore file watchers, keyed by window label.
+#[derive(Default)]
+pub struct IgnoredWatchState(Mutex<HashMap<String, Vec<RepoWatch>>>);
+
+impl IgnoredWatchState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn remove_window(&self, label: &str) {
+        if let Ok(mut windows) = self.0.lock() {
+            windows.remove(label);
+        }
+    }
+}
+
+// ── Commands ──
+
+/// The ignored paths in `path`, like `get_git_ignored`, and from now on an
+/// `ignored-files-changed` event whenever editing an ignore file changes
+/// them. Watching a repo again is a no-op; repos outside the window's
+/// current project stop being watched.
+#[tauri::command]
+pub fn watch_git_ignored(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    watches: tauri::State<'_, IgnoredWatchState>,
+    app: AppHandle,
+    path: String,
+) -> Result<Vec<String>, String> {
+    let repo = validate_repo_path(&path, window.label(), &state)?;
+    let root = state.blocking_read().get(window.label()).and_then(|o| o.clone()).ok_or("No project is open")?;
+    let ignored = list(Path::new(&path))?;
+
+    let mut windows = watches.0.lock().map_err(|e| e.to_string())?;
+    let repos = windows.entry(window.label().to_string()).or_default();
+    repos.retain(|w| w.repo.starts_with(&root));
+    if repos.iter().any(|w| w.repo == repo) {
+        return Ok(ignored);
+    }
+
+    let label = window.label().to_string();
+    let repo_path = path.clone();
+    let watcher = watch(Path::new(&path), ignored.clone(), move |ignored| {
+        events::IGNORED_FILES_CHANGED.emit_to(&app, &label, IgnoredFilesChanged { repo_path: repo_path.clone(), ignored });
+    })?;
+    repos.push(RepoWatch { repo, _watcher: watcher });
+    Ok(ignored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_ignore_files() {
+        let repo = Path::new("/work/app");
+        let global = Path::new("/home/me/.config/git/ignore");
+        let changed = |path: &str| is_ignore_file(repo, Some(global), Path::new(path));
+
+        assert!(changed("/work/app/.gitignore"));
+        assert!(changed("/work/app/web/assets/.gitignore"));
+        assert!(changed("/work/app/.git/info/exclude"));
+        assert!(changed("/home/me/.config/git/ignore"));
+        assert!(!changed("/work/app/.git/index"));
+        assert!(!changed("/work/app/.git/modules/lib/.gitignore"));
+        assert!(!changed("/work/app/src/gitignore.rs"));
+        assert!(!changed("/work/app/.dockerignore"));
+
+        assert_eq!(
+            parse_ls_files("target/\nnotes.log\n\n", repo),
+            [format!("/work/app{}target", std::path::MAIN_SEPARATOR), format!("/work/app{}notes.log", std::path::MAIN_SEPARATOR)]
+        );
+    }
+}
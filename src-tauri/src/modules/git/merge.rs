@@ -0,0 +1,144 @@
+//! Merging a branch into the checked-out one.
+//!
+//! `git_merge` runs `git merge` without opening an editor. When the merge
+//! stops on conflicts the repository is left in its merge state and the
+//! conflicted paths are returned, so the UI can open the conflict resolver
+//! (`git_conflict_versions` / `git_resolve_conflict`) and the user can
+//! finish with a commit or back out with `git merge --abort`.
+
+use serde::Serialize;
+use std::path::Path;
+
+use super::{validate_git_ref_name, validate_repo_path};
+use crate::modules::fs::ProjectRootState;
+use crate::modules::process::ProcessCommand;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStatus {
+    /// Nothing to merge.
+    UpToDate,
+    FastForward,
+    /// A merge commit was created.
+    Merged,
+    /// The merge stopped on conflicts and is waiting to be resolved.
+    Conflicts,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct MergeResult {
+    pub status: MergeStatus,
+    /// Conflicted paths, relative to the repository root.
+    pub conflicts: Vec<String>,
+    /// What git printed.
+    pub output: String,
+}
+
+/// How a successful `git merge` went, from its output.
+fn success_status(stdout: &str) -> MergeStatus {
+    if stdout.contains("Already up to date") || stdout.contains("Already up-to-date") {
+        MergeStatus::UpToDate
+    } else if stdout.lines().any(|l| l.trim() == "Fast-forward") {
+        MergeStatus::FastForward
+    } else {
+        MergeStatus::Merged
+    }
+}
+
+/// Paths with unmerged entries in the index.
+pub(super) fn conflicted_paths(repo: &Path) -> Result<Vec<String>, String> {
+    let output = ProcessCommand::new("git")
+        .args(["diff", "--name-only", "--diff-filter=U", "-z"])
+        .current_dir(repo)
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+    Ok(output
+        .stdout
+        .split(|b| *b == 0)
+        .filter(|p| !p.is_empty())
+        .map(|p| String::from_utf8_lossy(p).into_owned())
+        .collect())
+}
+
+pub fn merge(repo: &Path, branch: &str, no_ff: bool) -> Result<MergeResult, String> {
+    let output = ProcessCommand::new("git")
+        .args(["merge", "--no-edit", if no_ff { "--no-ff" } else { "--ff" }, branch])
+        .current_dir(repo)
+        .output()
+        .map_err(|e| format!("Failed to run git merge: {}", e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    let text = format!("{}{}", stdout, stderr).trim().to_string();
+    if output.status.success() {
+        return Ok(MergeResult { status: success_status(&stdout), conflicts: Vec::new(), output: text });
+    }
+    // Refusals (dirty tree, unknown branch, unrelated histories) leave no
+    // unmerged paths behind and are reported as plain errors.
+    let conflicts = conflicted_paths(repo)?;
+    if conflicts.is_empty() {
+        return Err(text);
+    }
+    Ok(MergeResult { status: MergeStatus::Conflicts, conflicts, output: text })
+}
+
+/// Merge `branch` into the current branch. With `no_ff` a merge commit is
+/// always created. Conflicts are not an error: the result lists them and
+/// the merge is left in progress.
+#[tauri::command]
+pub fn git_merge(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    repo_path: String,
+    branch: String,
+    no_ff: Option<bool>,
+) -> Result<MergeResult, String> {
+    let repo = validate_repo_path(&repo_path, window.label(), &state)?;
+    validate_git_ref_name(&branch)?;
+    merge(&repo, &branch, no_ff.unwrap_or(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::process::{with_runner, MockResponse, MockRunner};
+    use std::sync::Arc;
+
+    const UNMERGED: &[&str] = &["diff", "--name-only", "--diff-filter=U", "-z"];
+
+    #[test]
+    fn reports_conflicts_and_refusals() {
+        let repo = Path::new("/repo");
+        assert_eq!(success_status("Already up to date.\n"), MergeStatus::UpToDate);
+        assert_eq!(success_status("Updating 1a2b3c4..5d6e7f8\nFast-forward\n a.rs | 2 +-\n"), MergeStatus::FastForward);
+        assert_eq!(success_status("Merge made by the 'ort' strategy.\n a.rs | 2 +-\n"), MergeStatus::Merged);
+
+        let mock = Arc::new(
+            MockRunner::new()
+                .on(
+                    "git",
+                    &["merge", "--no-edit", "--no-ff", "feature"],
+                    MockResponse {
+                        code: 1,
+                        stdout: b"Auto-merging a.rs\nCONFLICT (content): Merge conflict in a.rs\n".to_vec(),
+                        stderr: Vec::new(),
+                    },
+                )
+                .on("git", UNMERGED, MockResponse::ok("a.rs\0docs/b c.md\0")),
+        );
+        let result = with_runner(mock, || merge(repo, "feature", true)).unwrap();
+        assert_eq!(result.status, MergeStatus::Conflicts);
+        assert_eq!(result.conflicts, ["a.rs", "docs/b c.md"]);
+        assert!(result.output.contains("CONFLICT (content)"));
+
+        let refusal = "error: Your local changes to the following files would be overwritten by merge:\n\ta.rs";
+        let mock = Arc::new(
+            MockRunner::new()
+                .on("git", &["merge", "--no-edit", "--ff", "feature"], MockResponse::fail(2, refusal))
+                .on("git", UNMERGED, MockResponse::ok("")),
+        );
+        assert_eq!(with_runner(mock, || merge(repo, "feature", false)).unwrap_err(), refusal);
+    }
+}
@@ -1,10 +1,40 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Component, Path, PathBuf};
-use std::process::Command;
 
+use crate::modules::fs::generated::{self, GeneratedReason};
 use crate::modules::fs::ProjectRootState;
+use crate::modules::net;
+use crate::modules::operations::{self, Operation, OperationKind};
+use crate::modules::policy::Category;
+use crate::modules::process::ProcessCommand;
+
+pub mod bisect;
+pub mod branch_policy;
+pub mod changelog;
+pub mod diff;
+pub mod ignored;
+pub mod merge;
+pub mod owners;
+pub mod parse;
+pub mod rebase;
+pub mod share;
+pub mod stats;
+
+/// Policy categories of the git commands that reach a remote, here and in
+/// the submodules. `git_create_branch_from_template` only does when it
+/// sets the upstream, so `policy::categories` decides it from the call.
+pub(crate) const COMMAND_CATEGORIES: &[(&str, &[Category])] = &[
+    ("git_push", &[Category::Network, Category::GitPush]),
+    ("git_fetch", &[Category::Network]),
+    ("git_pull", &[Category::Network]),
+    ("git_pull_rebase", &[Category::Network]),
+    ("git_clone", &[Category::Network]),
+    ("export_shareable_diff", &[Category::Network]),
+];
+
+pub(crate) use parse::{parse_status_porcelain_z, parse_unified_diff};
 
 /// Validate that a repo_path is within (or equal to) the project root for git commands.
 pub fn validate_repo_path(
@@ -93,7 +123,7 @@ pub fn validate_git_ref_name(name: &str) -> Result<(), String> {
 
 // ── Serializable types ───────────────────────────────────────────
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct DiffLine {
     pub kind: String,
     pub old_num: Option<u32>,
@@ -101,6 +131,15 @@ pub struct DiffLine {
     pub text: String,
 }
 
+/// One file's diff from `git_diff`.
+#[derive(Serialize, Clone)]
+pub struct FileDiffLines {
+    pub lines: Vec<DiffLine>,
+    /// Set when the file is generated (lockfile, bundle, codegen output or
+    /// in a generated directory), so diff views can collapse it.
+    pub generated: Option<GeneratedReason>,
+}
+
 #[derive(Serialize, Clone)]
 pub struct DiffRange {
     pub kind: String,
@@ -115,7 +154,7 @@ pub struct AheadBehind {
     pub upstream: Option<String>,
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct GitLogCommit {
     pub hash: String,
     pub short_hash: String,
@@ -124,7 +163,7 @@ pub struct GitLogCommit {
     pub message: String,
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct GitGraphRow {
     pub graph: String,
     pub commit: Option<GitLogCommit>,
@@ -137,147 +176,6 @@ pub struct BranchInfo {
     pub is_remote: bool,
 }
 
-// ── Internal helpers ─────────────────────────────────────────────
-
-fn parse_unified_diff(diff: &str) -> Vec<DiffLine> {
-    let mut lines = Vec::new();
-    let mut old_line: u32 = 0;
-    let mut new_line: u32 = 0;
-    let mut in_hunk = false;
-
-    for raw in diff.lines() {
-        if raw.starts_with("@@") {
-            in_hunk = true;
-            if let Some(rest) = raw.strip_prefix("@@ -") {
-                let parts: Vec<&str> = rest.splitn(2, '+').collect();
-                if parts.len() == 2 {
-                    old_line = parts[0]
-                        .split(',')
-                        .next()
-                        .unwrap_or("1")
-                        .trim()
-                        .parse()
-                        .unwrap_or(1);
-                    new_line = parts[1]
-                        .split([',', ' '])
-                        .next()
-                        .unwrap_or("1")
-                        .parse()
-                        .unwrap_or(1);
-                }
-            }
-            lines.push(DiffLine {
-                kind: "ctx".to_string(),
-                old_num: None,
-                new_num: None,
-                text: raw.to_string(),
-            });
-        } else if !in_hunk {
-            continue;
-        } else if let Some(text) = raw.strip_prefix('+') {
-            lines.push(DiffLine {
-                kind: "add".to_string(),
-                old_num: None,
-                new_num: Some(new_line),
-                text: text.to_string(),
-            });
-            new_line += 1;
-        } else if let Some(text) = raw.strip_prefix('-') {
-            lines.push(DiffLine {
-                kind: "del".to_string(),
-                old_num: Some(old_line),
-                new_num: None,
-                text: text.to_string(),
-            });
-            old_line += 1;
-        } else {
-            let text = raw.strip_prefix(' ').unwrap_or(raw);
-            lines.push(DiffLine {
-                kind: "ctx".to_string(),
-                old_num: Some(old_line),
-                new_num: Some(new_line),
-                text: text.to_string(),
-            });
-            old_line += 1;
-            new_line += 1;
-        }
-    }
-    lines
-}
-
-fn parse_hunk_range(s: &str) -> (u32, u32) {
-    let parts: Vec<&str> = s.split(',').collect();
-    let start: u32 = parts[0].parse().unwrap_or(0);
-    let count: u32 = if parts.len() > 1 {
-        parts[1].parse().unwrap_or(1)
-    } else {
-        1
-    };
-    (start, count)
-}
-
-// ── Tauri commands ───────────────────────────────────────────────
-
-/// One row from `git status --porcelain -z` output. The full stdout is
-/// a sequence of NUL-separated entries; rename ('R') and copy ('C')
-/// entries are followed by an additional entry containing the source
-/// path. Parsing as raw bytes (not `String::from_utf8_lossy` over the
-/// whole buffer) preserves non-UTF-8 path bytes per-entry rather than
-/// smearing replacement characters across the whole buffer.
-pub(crate) struct PorcelainEntry {
-    pub index_status: u8,
-    pub wt_status: u8,
-    pub file: String,
-    /// Source path for renames/copies. `None` for everything else.
-    #[allow(dead_code)] // present for future consumers; not used yet
-    pub orig: Option<String>,
-}
-
-/// Parse byte output from `git status --porcelain -z`.
-///
-/// The `-z` flag produces NUL-separated entries with no quoting, so we
-/// can treat the buffer as `&[u8]` and only convert per-entry. Each
-/// entry has the layout `XY <path>` where X is the index status, Y is
-/// the working-tree status, and one space follows. Rename and copy
-/// entries are followed by an extra NUL-separated entry containing the
-/// source path.
-pub(crate) fn parse_status_porcelain_z(stdout: &[u8]) -> Vec<PorcelainEntry> {
-    let entries: Vec<&[u8]> = stdout.split(|&b| b == 0).collect();
-    let mut result = Vec::new();
-    let mut i = 0;
-    while i < entries.len() {
-        let entry = entries[i];
-        if entry.len() < 4 {
-            i += 1;
-            continue;
-        }
-        let index_status = entry[0];
-        let wt_status = entry[1];
-        // entry[2] is a space separator; the path is everything after.
-        let file_bytes = &entry[3..];
-        let file = String::from_utf8_lossy(file_bytes).into_owned();
-
-        let orig = if (index_status == b'R' || index_status == b'C')
-            && i + 1 < entries.len()
-            && !entries[i + 1].is_empty()
-        {
-            i += 1;
-            Some(String::from_utf8_lossy(entries[i]).into_owned())
-        } else {
-            None
-        };
-
-        result.push(PorcelainEntry {
-            index_status,
-            wt_status,
-            file,
-            orig,
-        });
-        i += 1;
-    }
-    result
-}
-
 #[tauri::command]
 pub fn get_git_status(
     window: tauri::WebviewWindow,
@@ -285,9 +183,15 @@ pub fn get_git_status(
     path: String,
 ) -> Result<HashMap<String, String>, String> {
     validate_repo_path(&path, window.label(), &state)?;
-    let output = Command::new("git")
+    status_map(Path::new(&path))
+}
+
+/// Absolute path to single-letter status (U, C, A, S, D or M) for every
+/// changed file in `repo`. Empty outside a repository.
+pub fn status_map(repo: &Path) -> Result<HashMap<String, String>, String> {
+    let output = ProcessCommand::new("git")
         .args(["status", "--porcelain", "-uall", "-z"])
-        .current_dir(&path)
+        .current_dir(repo)
         .output()
         .map_err(|e| e.to_string())?;
 
@@ -297,7 +201,7 @@ pub fn get_git_status(
 
     let mut result = HashMap::new();
     for entry in parse_status_porcelain_z(&output.stdout) {
-        let abs_path = PathBuf::from(&path).join(&entry.file);
+        let abs_path = repo.join(&entry.file);
         let abs_str = abs_path.to_string_lossy().to_string();
 
         let status = match (entry.index_status, entry.wt_status) {
@@ -332,7 +236,7 @@ pub fn get_git_remote_status(
 ) -> Result<HashMap<String, String>, String> {
     validate_repo_path(&path, window.label(), &state)?;
 
-    let upstream_check = Command::new("git")
+    let upstream_check = ProcessCommand::new("git")
         .args(["rev-parse", "--abbrev-ref", "@{u}"])
         .current_dir(&path)
         .output()
@@ -342,7 +246,7 @@ pub fn get_git_remote_status(
         return Ok(HashMap::new());
     }
 
-    let output = Command::new("git")
+    let output = ProcessCommand::new("git")
         .args(["diff", "--name-status", "HEAD...@{u}"])
         .current_dir(&path)
         .output()
@@ -412,34 +316,7 @@ pub fn get_git_ignored(
     path: String,
 ) -> Result<Vec<String>, String> {
     validate_repo_path(&path, window.label(), &state)?;
-    let output = Command::new("git")
-        .args([
-            "ls-files",
-            "--others",
-            "--ignored",
-            "--exclude-standard",
-            "--directory",
-        ])
-        .current_dir(&path)
-        .output()
-        .map_err(|e| e.to_string())?;
-
-    if !output.status.success() {
-        return Ok(Vec::new());
-    }
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let root = PathBuf::from(&path);
-    let result: Vec<String> = stdout
-        .lines()
-        .filter(|l| !l.is_empty())
-        .map(|l| {
-            let clean = l.trim_end_matches('/');
-            root.join(clean).to_string_lossy().to_string()
-        })
-        .collect();
-
-    Ok(result)
+    ignored::list(Path::new(&path))
 }
 
 #[tauri::command]
@@ -489,14 +366,17 @@ pub fn git_diff(
     file_path: String,
     staged: bool,
     is_untracked: Option<bool>,
-) -> Result<Vec<DiffLine>, String> {
-    validate_repo_path(&repo_path, window.label(), &state)?;
+    options: Option<diff::DiffOptions>,
+) -> Result<FileDiffLines, String> {
+    let repo = validate_repo_path(&repo_path, window.label(), &state)?;
     validate_git_file_path(&file_path)?;
+    let root = state.blocking_read().get(window.label()).and_then(|o| o.clone()).unwrap_or_else(|| repo.clone());
+    let flags = options.unwrap_or_default().git_args();
 
     let untracked = match is_untracked {
         Some(v) => v,
         None => {
-            let status_out = Command::new("git")
+            let status_out = ProcessCommand::new("git")
                 .args(["status", "--porcelain", "--", &file_path])
                 .current_dir(&repo_path)
                 .output()
@@ -508,27 +388,35 @@ pub fn git_diff(
 
     let output = if untracked {
         let abs = PathBuf::from(&repo_path).join(&file_path);
-        Command::new("git")
-            .args(["diff", "--no-index", "/dev/null", &abs.to_string_lossy()])
+        ProcessCommand::new("git")
+            .arg("diff")
+            .args(&flags)
+            .args(["--no-index", "/dev/null", &abs.to_string_lossy()])
             .current_dir(&repo_path)
             .output()
             .map_err(|e| e.to_string())?
     } else if staged {
-        Command::new("git")
-            .args(["diff", "--cached", "--", &file_path])
+        ProcessCommand::new("git")
+            .arg("diff")
+            .args(&flags)
+            .args(["--cached", "--", &file_path])
             .current_dir(&repo_path)
             .output()
             .map_err(|e| e.to_string())?
     } else {
-        Command::new("git")
-            .args(["diff", "--", &file_path])
+        ProcessCommand::new("git")
+            .arg("diff")
+            .args(&flags)
+            .args(["--", &file_path])
             .current_dir(&repo_path)
             .output()
             .map_err(|e| e.to_string())?
     };
 
     let stdout = String::from_utf8_lossy(&output.stdout);
-    Ok(parse_unified_diff(&stdout))
+    // Deleted files are judged by name alone.
+    let generated = generated::reason_in_project(&root, &repo.join(&file_path));
+    Ok(FileDiffLines { lines: parse_unified_diff(&stdout), generated })
 }
 
 #[tauri::command]
@@ -544,7 +432,7 @@ pub fn git_stage(
     }
     let mut args = vec!["add".to_string(), "--".to_string()];
     args.extend(paths);
-    let output = Command::new("git")
+    let output = ProcessCommand::new("git")
         .args(&args)
         .current_dir(&repo_path)
         .output()
@@ -572,7 +460,7 @@ pub fn git_unstage(
         "--".to_string(),
     ];
     args.extend(paths);
-    let output = Command::new("git")
+    let output = ProcessCommand::new("git")
         .args(&args)
         .current_dir(&repo_path)
         .output()
@@ -603,7 +491,7 @@ pub fn git_discard(
         "--".to_string(),
     ];
     status_args.extend(paths.iter().cloned());
-    let status_output = Command::new("git")
+    let status_output = ProcessCommand::new("git")
         .args(&status_args)
         .current_dir(&repo_path)
         .output()
@@ -626,7 +514,7 @@ pub fn git_discard(
     if !tracked.is_empty() {
         let mut args = vec!["checkout".to_string(), "--".to_string()];
         args.extend(tracked);
-        let output = Command::new("git")
+        let output = ProcessCommand::new("git")
             .args(&args)
             .current_dir(&repo_path)
             .output()
@@ -671,9 +559,14 @@ pub fn git_commit(
     message: String,
 ) -> Result<String, String> {
     validate_repo_path(&repo_path, window.label(), &state)?;
-    let output = Command::new("git")
-        .args(["commit", "-m", &message])
-        .current_dir(&repo_path)
+    commit(Path::new(&repo_path), &message)
+}
+
+/// Commit what is staged; returns the short hash git printed.
+pub fn commit(repo: &Path, message: &str) -> Result<String, String> {
+    let output = ProcessCommand::new("git")
+        .args(["commit", "-m", message])
+        .current_dir(repo)
         .output()
         .map_err(|e| e.to_string())?;
     if !output.status.success() {
@@ -697,17 +590,24 @@ pub fn git_push(
     repo_path: String,
 ) -> Result<String, String> {
     validate_repo_path(&repo_path, window.label(), &state)?;
-    let output = Command::new("git")
+    net::ensure_online("git push")?;
+    push(Path::new(&repo_path))
+}
+
+/// Push the current branch, setting `origin` as its upstream if it has
+/// none yet. Returns git's progress output.
+pub fn push(repo: &Path) -> Result<String, String> {
+    let output = ProcessCommand::new("git")
         .args(["push"])
-        .current_dir(&repo_path)
+        .current_dir(repo)
         .output()
         .map_err(|e| e.to_string())?;
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         if stderr.contains("no upstream") || stderr.contains("has no upstream branch") {
-            let branch_output = Command::new("git")
+            let branch_output = ProcessCommand::new("git")
                 .args(["rev-parse", "--abbrev-ref", "HEAD"])
-                .current_dir(&repo_path)
+                .current_dir(repo)
                 .output()
                 .map_err(|e| e.to_string())?;
             let branch_name = String::from_utf8_lossy(&branch_output.stdout)
@@ -716,9 +616,9 @@ pub fn git_push(
             if branch_name.is_empty() {
                 return Err(stderr);
             }
-            let retry = Command::new("git")
+            let retry = ProcessCommand::new("git")
                 .args(["push", "--set-upstream", "origin", &branch_name])
-                .current_dir(&repo_path)
+                .current_dir(repo)
                 .output()
                 .map_err(|e| e.to_string())?;
             if !retry.status.success() {
@@ -738,7 +638,8 @@ pub fn git_fetch(
     repo_path: String,
 ) -> Result<String, String> {
     validate_repo_path(&repo_path, window.label(), &state)?;
-    let output = Command::new("git")
+    net::ensure_online("git fetch")?;
+    let output = ProcessCommand::new("git")
         .args(["fetch"])
         .current_dir(&repo_path)
         .output()
@@ -756,7 +657,8 @@ pub fn git_pull(
     repo_path: String,
 ) -> Result<String, String> {
     validate_repo_path(&repo_path, window.label(), &state)?;
-    let output = Command::new("git")
+    net::ensure_online("git pull")?;
+    let output = ProcessCommand::new("git")
         .args(["pull"])
         .current_dir(&repo_path)
         .output()
@@ -776,7 +678,8 @@ pub fn git_pull_rebase(
     repo_path: String,
 ) -> Result<String, String> {
     validate_repo_path(&repo_path, window.label(), &state)?;
-    let output = Command::new("git")
+    net::ensure_online("git pull")?;
+    let output = ProcessCommand::new("git")
         .args(["pull", "--rebase"])
         .current_dir(&repo_path)
         .output()
@@ -799,9 +702,14 @@ pub fn git_delete_branch(
 ) -> Result<String, String> {
     validate_repo_path(&repo_path, window.label(), &state)?;
     validate_git_ref_name(&branch)?;
-    let head_output = Command::new("git")
+    delete_branch(Path::new(&repo_path), &branch, force)
+}
+
+/// Delete a local branch other than the checked-out one.
+pub fn delete_branch(repo: &Path, branch: &str, force: bool) -> Result<String, String> {
+    let head_output = ProcessCommand::new("git")
         .args(["rev-parse", "--abbrev-ref", "HEAD"])
-        .current_dir(&repo_path)
+        .current_dir(repo)
         .output()
         .map_err(|e| e.to_string())?;
     let current_branch = String::from_utf8_lossy(&head_output.stdout)
@@ -811,9 +719,9 @@ pub fn git_delete_branch(
         return Err("Cannot delete the currently checked-out branch".to_string());
     }
     let flag = if force { "-D" } else { "-d" };
-    let output = Command::new("git")
-        .args(["branch", flag, &branch])
-        .current_dir(&repo_path)
+    let output = ProcessCommand::new("git")
+        .args(["branch", flag, branch])
+        .current_dir(repo)
         .output()
         .map_err(|e| e.to_string())?;
     if !output.status.success() {
@@ -829,7 +737,7 @@ pub fn git_ahead_behind(
     repo_path: String,
 ) -> Result<AheadBehind, String> {
     validate_repo_path(&repo_path, window.label(), &state)?;
-    let upstream_out = Command::new("git")
+    let upstream_out = ProcessCommand::new("git")
         .args(["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{u}"])
         .current_dir(&repo_path)
         .output()
@@ -847,7 +755,7 @@ pub fn git_ahead_behind(
         .trim()
         .to_string();
 
-    let output = Command::new("git")
+    let output = ProcessCommand::new("git")
         .args(["rev-list", "--count", "--left-right", "HEAD...@{u}"])
         .current_dir(&repo_path)
         .output()
@@ -899,54 +807,14 @@ pub fn git_diff_line_ranges(
         .map(|n| n.to_string_lossy().to_string())
         .unwrap_or_else(|| file_path.clone());
 
-    let output = Command::new("git")
+    let output = ProcessCommand::new("git")
         .args(["diff", "-U0", "--", &file_name])
         .current_dir(&work_dir)
         .output()
         .map_err(|e| e.to_string())?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut ranges = Vec::new();
-
-    for line in stdout.lines() {
-        if !line.starts_with("@@") {
-            continue;
-        }
-        if let Some(rest) = line.strip_prefix("@@ -") {
-            let parts: Vec<&str> = rest.splitn(2, '+').collect();
-            if parts.len() != 2 {
-                continue;
-            }
-
-            let old_part = parts[0].trim().trim_end_matches(',');
-            let new_part = parts[1].split_whitespace().next().unwrap_or("0");
-
-            let (_old_start, old_count) = parse_hunk_range(old_part);
-            let (new_start, new_count) = parse_hunk_range(new_part);
-
-            if old_count == 0 && new_count > 0 {
-                ranges.push(DiffRange {
-                    kind: "add".to_string(),
-                    start: new_start,
-                    end: new_start + new_count - 1,
-                });
-            } else if new_count == 0 && old_count > 0 {
-                ranges.push(DiffRange {
-                    kind: "del".to_string(),
-                    start: new_start.max(1),
-                    end: new_start.max(1),
-                });
-            } else {
-                ranges.push(DiffRange {
-                    kind: "mod".to_string(),
-                    start: new_start,
-                    end: new_start + new_count - 1,
-                });
-            }
-        }
-    }
-
-    Ok(ranges)
+    Ok(parse::diff_line_ranges(&stdout))
 }
 
 #[tauri::command]
@@ -959,7 +827,7 @@ pub fn git_log(
     validate_repo_path(&repo_path, window.label(), &state)?;
     let limit = count.unwrap_or(50).min(500).to_string();
     let format = "%H\x09%h\x09%an\x09%ar\x09%s".to_string();
-    let output = Command::new("git")
+    let output = ProcessCommand::new("git")
         .args([
             "log",
             "--graph",
@@ -1018,7 +886,7 @@ pub fn git_list_branches(
     repo_path: String,
 ) -> Result<Vec<BranchInfo>, String> {
     validate_repo_path(&repo_path, window.label(), &state)?;
-    let output = Command::new("git")
+    let output = ProcessCommand::new("git")
         .args(["branch", "-a", "--no-color"])
         .current_dir(&repo_path)
         .output()
@@ -1082,13 +950,13 @@ pub fn git_checkout_branch(
     }
 
     let output = if is_remote {
-        Command::new("git")
+        ProcessCommand::new("git")
             .args(["checkout", "--track", &format!("remotes/{}", branch)])
             .current_dir(&repo_path)
             .output()
             .map_err(|e| e.to_string())?
     } else {
-        Command::new("git")
+        ProcessCommand::new("git")
             .args(["checkout", &branch])
             .current_dir(&repo_path)
             .output()
@@ -1123,7 +991,7 @@ pub fn git_resolve_conflict(
     fs::write(&abs_path, &content).map_err(|e| format!("Failed to write file: {}", e))?;
 
     if stage {
-        let output = Command::new("git")
+        let output = ProcessCommand::new("git")
             .args(["add", "--", &file_path])
             .current_dir(&canonical_repo)
             .output()
@@ -1138,6 +1006,76 @@ pub fn git_resolve_conflict(
     Ok(())
 }
 
+/// Unified diff of the working changes, for tools that consume raw patch
+/// text. `staged_only` limits it to the index; otherwise staged and
+/// unstaged changes are combined against `HEAD` (or the index alone in a
+/// repository with no commits yet).
+pub(crate) fn read_working_diff(repo: &Path, staged_only: bool) -> Result<String, String> {
+    let run = |args: &[&str]| {
+        ProcessCommand::new("git")
+            .args(["diff", "--no-color", "--no-ext-diff"])
+            .args(args)
+            .current_dir(repo)
+            .output()
+            .map_err(|e| e.to_string())
+    };
+    let mut output = if staged_only { run(&["--cached"])? } else { run(&["HEAD"])? };
+    if !staged_only && !output.status.success() {
+        output = run(&["--cached"])?;
+    }
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The three index stages of a conflicted file. `base` is `None` when the
+/// file has no common ancestor (add/add), and `ours`/`theirs` are `None`
+/// when that side deleted the file.
+#[derive(Serialize, Clone)]
+pub struct ConflictVersions {
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+}
+
+/// Read stages 1–3 of a conflicted path from the index. Shared by the
+/// `git_conflict_versions` command and the AI conflict resolver.
+pub(crate) fn read_conflict_versions(repo: &Path, file_path: &str) -> Result<ConflictVersions, String> {
+    validate_git_file_path(file_path)?;
+    let stage = |n: u8| -> Result<Option<String>, String> {
+        let output = ProcessCommand::new("git")
+            .args(["show", &format!(":{}:./{}", n, file_path)])
+            .current_dir(repo)
+            .output()
+            .map_err(|e| e.to_string())?;
+        Ok(output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).into_owned()))
+    };
+    let versions = ConflictVersions {
+        base: stage(1)?,
+        ours: stage(2)?,
+        theirs: stage(3)?,
+    };
+    if versions.ours.is_none() && versions.theirs.is_none() {
+        return Err(format!("'{}' is not in a conflicted state", file_path));
+    }
+    Ok(versions)
+}
+
+#[tauri::command]
+pub fn git_conflict_versions(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    repo_path: String,
+    file_path: String,
+) -> Result<ConflictVersions, String> {
+    let repo = validate_repo_path(&repo_path, window.label(), &state)?;
+    read_conflict_versions(&repo, &file_path)
+}
+
 // ── Checkpoints (agent undo) ──
 
 #[derive(Serialize, Clone)]
@@ -1164,7 +1102,7 @@ pub fn git_create_checkpoint(
     let checkpoint_id = format!("leo-checkpoint-{}", timestamp);
 
     // Stage all changes (including untracked)
-    let add_out = Command::new("git")
+    let add_out = ProcessCommand::new("git")
         .args(["add", "-A"])
         .current_dir(&repo)
         .output()
@@ -1174,7 +1112,7 @@ pub fn git_create_checkpoint(
     }
 
     // Create a tree object from the current index
-    let tree_out = Command::new("git")
+    let tree_out = ProcessCommand::new("git")
         .args(["write-tree"])
         .current_dir(&repo)
         .output()
@@ -1185,7 +1123,7 @@ pub fn git_create_checkpoint(
     let tree_sha = String::from_utf8_lossy(&tree_out.stdout).trim().to_string();
 
     // Get current HEAD for parent
-    let head_out = Command::new("git")
+    let head_out = ProcessCommand::new("git")
         .args(["rev-parse", "HEAD"])
         .current_dir(&repo)
         .output()
@@ -1202,7 +1140,7 @@ pub fn git_create_checkpoint(
     commit_args.extend(parent_arg);
     commit_args.extend(["-m".to_string(), message.clone()]);
 
-    let commit_out = Command::new("git")
+    let commit_out = ProcessCommand::new("git")
         .args(&commit_args)
         .current_dir(&repo)
         .output()
@@ -1214,7 +1152,7 @@ pub fn git_create_checkpoint(
 
     // Store as a hidden ref
     let ref_name = format!("refs/leo/checkpoints/{}", checkpoint_id);
-    let update_out = Command::new("git")
+    let update_out = ProcessCommand::new("git")
         .args(["update-ref", &ref_name, &commit_sha])
         .current_dir(&repo)
         .output()
@@ -1224,7 +1162,7 @@ pub fn git_create_checkpoint(
     }
 
     // Reset index back to HEAD (don't leave staged changes from our add -A)
-    let _ = Command::new("git")
+    let _ = ProcessCommand::new("git")
         .args(["reset", "HEAD"])
         .current_dir(&repo)
         .output();
@@ -1250,7 +1188,7 @@ pub fn git_restore_checkpoint(
     let ref_name = format!("refs/leo/checkpoints/{}", checkpoint_id);
 
     // Verify the ref exists
-    let verify_out = Command::new("git")
+    let verify_out = ProcessCommand::new("git")
         .args(["rev-parse", "--verify", &ref_name])
         .current_dir(&repo)
         .output()
@@ -1261,7 +1199,7 @@ pub fn git_restore_checkpoint(
     let commit_sha = String::from_utf8_lossy(&verify_out.stdout).trim().to_string();
 
     // Checkout the tree from that commit (overwrites working directory)
-    let checkout_out = Command::new("git")
+    let checkout_out = ProcessCommand::new("git")
         .args(["checkout", &commit_sha, "--", "."])
         .current_dir(&repo)
         .output()
@@ -1284,7 +1222,7 @@ pub fn git_list_checkpoints(
     let repo = validate_repo_path(&repo_path, window.label(), &state)?;
 
     // List refs under refs/leo/checkpoints/
-    let out = Command::new("git")
+    let out = ProcessCommand::new("git")
         .args(["for-each-ref", "--format=%(refname:short) %(subject) %(creatordate:unix)", "refs/leo/checkpoints/"])
         .current_dir(&repo)
         .output()
@@ -1349,21 +1287,98 @@ pub fn find_git_repos(
     Ok(repos)
 }
 
+/// Whether a clone source needs the network: URLs with a scheme other
+/// than `file://`, and scp-style `user@host:path` remotes.
+fn is_remote_url(url: &str) -> bool {
+    match url.split_once("://") {
+        Some((scheme, _)) => scheme != "file",
+        None => url.split_once(':').is_some_and(|(host, _)| host.contains('@') || host.contains('.')),
+    }
+}
+
+/// Directory `git clone <url>` creates: the last path component, without
+/// `.git`.
+fn clone_dir_name(url: &str) -> Option<String> {
+    let last = url.trim_end_matches('/').rsplit(['/', ':']).next()?;
+    let name = last.strip_suffix(".git").unwrap_or(last);
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// `(phase, done, total)` from a git progress line such as
+/// `Receiving objects:  45% (450/1000), 1.20 MiB | 2.00 MiB/s`.
+fn parse_clone_progress(line: &str) -> Option<(String, u64, u64)> {
+    let line = line.strip_prefix("remote: ").unwrap_or(line);
+    let (phase, rest) = line.split_once(':')?;
+    let counts = rest.split_once('(')?.1.split_once(')')?.0;
+    let (done, total) = counts.split_once('/')?;
+    Some((phase.trim().to_string(), done.trim().parse().ok()?, total.trim().parse().ok()?))
+}
+
+async fn run_clone(op: &Operation, url: &str, dest: &str) -> Result<(), String> {
+    use tokio::io::AsyncReadExt;
+    let failed = |e: std::io::Error| format!("Failed to run git clone: {}", e);
+    let mut child = tokio::process::Command::new("git")
+        .args(["clone", "--progress", url])
+        .current_dir(dest)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(failed)?;
+    let mut stderr = child.stderr.take().ok_or("Failed to run git clone")?;
+    // Progress lines end in `\r` while they update and `\n` once a phase
+    // is done; everything else is kept for the error message.
+    let (mut pending, mut messages, mut buf) = (Vec::new(), String::new(), [0u8; 4096]);
+    loop {
+        let n = tokio::select! {
+            n = stderr.read(&mut buf) => n.map_err(failed)?,
+            _ = op.cancelled() => {
+                let _ = child.kill().await;
+                return Err("Clone cancelled".to_string());
+            }
+        };
+        if n == 0 {
+            break;
+        }
+        pending.extend_from_slice(&buf[..n]);
+        while let Some(end) = pending.iter().position(|b| *b == b'\r' || *b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            match parse_clone_progress(line.trim_end()) {
+                Some((phase, done, total)) => op.progress(done, Some(total), Some(phase)),
+                None if !line.trim().is_empty() => {
+                    messages.push_str(line.trim_end());
+                    messages.push('\n');
+                }
+                None => {}
+            }
+        }
+    }
+    messages.push_str(&String::from_utf8_lossy(&pending));
+    if !child.wait().await.map_err(failed)?.success() {
+        return Err(format!("git clone failed: {}", messages));
+    }
+    Ok(())
+}
+
 /// Clone a git repository. Does not require a project to be open.
-/// Used by the "Clone Repo" welcome screen action.
+/// Used by the "Clone Repo" welcome screen action. Progress is reported as
+/// a background operation, and cancelling it removes the partial clone.
 #[tauri::command]
-pub async fn git_clone(url: String, dest: String) -> Result<(), String> {
-    let output = tokio::process::Command::new("git")
-        .args(["clone", &url])
-        .current_dir(&dest)
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run git clone: {}", e))?;
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("git clone failed: {}", stderr));
+pub async fn git_clone(window: tauri::WebviewWindow, url: String, dest: String) -> Result<(), String> {
+    if is_remote_url(&url) {
+        net::ensure_online("git clone")?;
+    }
+    let target = clone_dir_name(&url).map(|name| Path::new(&dest).join(name));
+    let existed = target.as_ref().is_some_and(|t| t.exists());
+    let op = operations::manager().start(OperationKind::Clone, &url, Some(window.label()), true);
+    let result = run_clone(&op, &url, &dest).await;
+    if op.is_cancelled() && !existed {
+        if let Some(target) = &target {
+            let _ = fs::remove_dir_all(target);
+        }
     }
-    Ok(())
+    op.track(result)
 }
 
 #[cfg(test)]
@@ -1556,4 +1571,29 @@ mod tests {
         assert!(entries[0].orig.is_none(),
             "truncated rename must not produce an empty-string source");
     }
+
+    #[test]
+    fn test_is_remote_url() {
+        assert!(is_remote_url("https://github.com/a/b.git"));
+        assert!(is_remote_url("git@github.com:a/b.git"));
+        assert!(!is_remote_url("file:///srv/repos/b.git"));
+        assert!(!is_remote_url("/srv/repos/b.git"));
+        assert!(!is_remote_url("C:/repos/b"));
+    }
+
+    #[test]
+    fn test_clone_progress_and_dir_name() {
+        assert_eq!(
+            parse_clone_progress("Receiving objects:  45% (450/1000), 1.20 MiB | 2.00 MiB/s"),
+            Some(("Receiving objects".to_string(), 450, 1000))
+        );
+        assert_eq!(
+            parse_clone_progress("remote: Counting objects: 100% (12/12), done."),
+            Some(("Counting objects".to_string(), 12, 12))
+        );
+        assert_eq!(parse_clone_progress("Cloning into 'b'..."), None);
+        assert_eq!(clone_dir_name("https://github.com/a/b.git/").as_deref(), Some("b"));
+        assert_eq!(clone_dir_name("git@github.com:a/b").as_deref(), Some("b"));
+        assert_eq!(clone_dir_name("/srv/repos/c").as_deref(), Some("c"));
+    }
 }
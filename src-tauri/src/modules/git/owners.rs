@@ -0,0 +1,162 @@
+//! CODEOWNERS lookup.
+//!
+//! Follows GitHub's rules: the first of `.github/CODEOWNERS`, `CODEOWNERS`
+//! and `docs/CODEOWNERS` in the repository is used, patterns follow
+//! gitignore syntax (except that `dir/*` doesn't reach into
+//! subdirectories), and the last matching line wins. A matching line with
+//! no owners leaves the file unowned. GitLab `[Section]` headers are
+//! skipped, so their rules are treated as one list.
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::modules::fs::{validate_path, ProjectRootState};
+
+const LOCATIONS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct CodeOwners {
+    /// Path relative to the repository root, `/`-separated.
+    pub path: String,
+    /// `@user`, `@org/team` or email addresses; empty when unowned.
+    pub owners: Vec<String>,
+    /// The CODEOWNERS file consulted, if any.
+    pub file: Option<String>,
+    /// 1-based line of the rule that matched.
+    pub line: Option<usize>,
+    pub pattern: Option<String>,
+}
+
+struct Rule {
+    line: usize,
+    pattern: String,
+    owners: Vec<String>,
+    matcher: Gitignore,
+    /// `dir/*` matches direct children only.
+    shallow: bool,
+}
+
+fn parse(root: &Path, content: &str) -> Vec<Rule> {
+    let mut rules = Vec::new();
+    for (i, raw) in content.lines().enumerate() {
+        let line = raw.split(" #").next().unwrap_or_default().trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with('[') || line.starts_with("^[") {
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        let Some(pattern) = parts.next() else { continue };
+        let owners: Vec<String> = parts.map(str::to_string).collect();
+        let mut builder = GitignoreBuilder::new(root);
+        // Invalid patterns are skipped, as GitHub does.
+        if builder.add_line(None, pattern).is_err() {
+            continue;
+        }
+        let Ok(matcher) = builder.build() else { continue };
+        rules.push(Rule { line: i + 1, pattern: pattern.to_string(), owners, matcher, shallow: pattern.ends_with("/*") });
+    }
+    rules
+}
+
+fn matching_rule<'a>(rules: &'a [Rule], rel: &Path, is_dir: bool) -> Option<&'a Rule> {
+    rules.iter().rev().find(|r| {
+        let m = if r.shallow { r.matcher.matched(rel, is_dir) } else { r.matcher.matched_path_or_any_parents(rel, is_dir) };
+        m.is_ignore()
+    })
+}
+
+/// Nearest directory at or above `path` (but not above `root`) holding a
+/// `.git`, or `root` itself.
+fn repo_root(path: &Path, root: &Path) -> PathBuf {
+    path.ancestors()
+        .take_while(|dir| dir.starts_with(root))
+        .find(|dir| dir.join(".git").exists())
+        .unwrap_or(root)
+        .to_path_buf()
+}
+
+fn resolve(repo: &Path, path: &Path) -> CodeOwners {
+    let rel = path.strip_prefix(repo).unwrap_or(path);
+    let rel_str = rel.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+    let mut result = CodeOwners { path: rel_str, owners: Vec::new(), file: None, line: None, pattern: None };
+    let Some((file, content)) =
+        LOCATIONS.iter().map(|l| repo.join(l)).find_map(|f| std::fs::read_to_string(&f).ok().map(|c| (f, c)))
+    else {
+        return result;
+    };
+    result.file = Some(file.to_string_lossy().into_owned());
+    let rules = parse(repo, &content);
+    if let Some(rule) = matching_rule(&rules, rel, path.is_dir()) {
+        result.owners = rule.owners.clone();
+        result.line = Some(rule.line);
+        result.pattern = Some(rule.pattern.clone());
+    }
+    result
+}
+
+/// Owners of `path` (a file or folder in the project) according to its
+/// repository's CODEOWNERS file.
+#[tauri::command]
+pub fn resolve_code_owners(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: String,
+) -> Result<CodeOwners, String> {
+    let path = validate_path(&path, window.label(), &state)?;
+    let root = state.blocking_read().get(window.label()).and_then(|o| o.clone()).ok_or("No project is open")?;
+    Ok(resolve(&repo_root(&path, &root), &path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODEOWNERS: &str = "\
+# Default owners
+*       @org/everyone
+*.js    @js-owner  # inline comment
+/docs/  docs@example.com
+apps/*  @apps-team
+/vendor/
+[Backend]
+/server/ @org/platform-team @alice
+";
+
+    fn owners_of(rel: &str) -> (Vec<String>, Option<usize>) {
+        let root = Path::new("/repo");
+        let rules = parse(root, CODEOWNERS);
+        let rule = matching_rule(&rules, Path::new(rel), false);
+        (rule.map(|r| r.owners.clone()).unwrap_or_default(), rule.map(|r| r.line))
+    }
+
+    #[test]
+    fn last_matching_rule_wins() {
+        assert_eq!(owners_of("README.md"), (vec!["@org/everyone".to_string()], Some(2)));
+        assert_eq!(owners_of("web/app.js").0, vec!["@js-owner"]);
+        assert_eq!(owners_of("docs/guide/intro.md").0, vec!["docs@example.com"]);
+        assert_eq!(owners_of("server/main.rs").0, vec!["@org/platform-team", "@alice"]);
+        assert_eq!(owners_of("vendor/lib.js"), (vec![], Some(6)));
+        assert_eq!(owners_of("apps/web.js").0, vec!["@apps-team"]);
+        // `apps/*` doesn't reach into subdirectories.
+        assert_eq!(owners_of("apps/web/index.js").0, vec!["@js-owner"]);
+    }
+
+    #[test]
+    fn finds_codeowners_in_repo() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+        std::fs::create_dir_all(repo.join(".github")).unwrap();
+        std::fs::create_dir_all(repo.join("src")).unwrap();
+        std::fs::write(repo.join(".github/CODEOWNERS"), "/src/ @core\n").unwrap();
+        std::fs::write(repo.join("CODEOWNERS"), "* @ignored\n").unwrap();
+        std::fs::write(repo.join("src/lib.rs"), "").unwrap();
+
+        let file = repo.join("src/lib.rs");
+        assert_eq!(repo_root(&file, dir.path()), repo);
+        let owners = resolve(&repo, &file);
+        assert_eq!(owners.path, "src/lib.rs");
+        assert_eq!(owners.owners, vec!["@core"]);
+        assert!(owners.file.unwrap().ends_with("CODEOWNERS"));
+    }
+}
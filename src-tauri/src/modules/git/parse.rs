@@ -0,0 +1,450 @@
+//! Parsers for git's diff and status output.
+//!
+//! These are pure functions over the text git printed, so they can be
+//! property-tested without a repository. They never panic, and a line is
+//! only counted as part of a hunk while the hunk header says lines remain,
+//! so file headers after a hunk (`--- a/x`, `+++ b/x`) are never mistaken
+//! for removed or added lines. Combined diffs (`@@@`, printed for merge
+//! conflicts) are understood too; their lines carry only new-side numbers.
+
+use super::{DiffLine, DiffRange};
+
+/// Ranges from a hunk header. Combined diffs have one old range per parent.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct HunkHeader {
+    pub old: Vec<(u32, u32)>,
+    pub new: (u32, u32),
+}
+
+fn parse_range(s: &str) -> Option<(u32, u32)> {
+    match s.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((s.parse().ok()?, 1)),
+    }
+}
+
+/// Parse `@@ -a,b +c,d @@ context` (or `@@@ -a,b -c,d +e,f @@@` for a
+/// combined diff). Missing counts mean 1.
+pub(crate) fn parse_hunk_header(line: &str) -> Option<HunkHeader> {
+    let marks = line.bytes().take_while(|&b| b == b'@').count();
+    if marks < 2 {
+        return None;
+    }
+    let fence = &line[..marks];
+    let (ranges, _context) = line[marks..].trim_start().split_once(fence)?;
+    let mut old = Vec::new();
+    let mut new = None;
+    for token in ranges.split_whitespace() {
+        if let Some(r) = token.strip_prefix('-') {
+            old.push(parse_range(r)?);
+        } else if let Some(r) = token.strip_prefix('+') {
+            if new.is_some() {
+                return None;
+            }
+            new = Some(parse_range(r)?);
+        } else {
+            return None;
+        }
+    }
+    if old.len() != marks - 1 {
+        return None;
+    }
+    Some(HunkHeader { old, new: new? })
+}
+
+struct Hunk {
+    /// Next line number and lines left, per parent.
+    old: Vec<(u32, u32)>,
+    new: (u32, u32),
+}
+
+impl Hunk {
+    fn done(&self) -> bool {
+        self.new.1 == 0 && self.old.iter().all(|o| o.1 == 0)
+    }
+
+    /// Number `raw` and count it against the hunk, or `None` if it isn't a
+    /// line this hunk still has room for.
+    fn take(&mut self, raw: &str) -> Option<DiffLine> {
+        let parents = self.old.len();
+        // Some tools strip the single space off empty context lines.
+        let (prefix, text) = if raw.is_empty() { ("", "") } else { (raw.get(..parents)?, &raw[parents..]) };
+        if !prefix.bytes().all(|b| matches!(b, b' ' | b'+' | b'-')) {
+            return None;
+        }
+        // A line is in the result unless some column is '-', and in
+        // parent i unless column i is '+'.
+        let in_new = !prefix.contains('-');
+        let in_old: Vec<bool> = (0..parents).map(|i| prefix.as_bytes().get(i) != Some(&b'+')).collect();
+        if (in_new && self.new.1 == 0) || in_old.iter().zip(&self.old).any(|(&has, o)| has && o.1 == 0) {
+            return None;
+        }
+        let kind = if prefix.contains('+') {
+            "add"
+        } else if prefix.contains('-') {
+            "del"
+        } else {
+            "ctx"
+        };
+        let old_num = (parents == 1 && in_old[0]).then_some(self.old[0].0);
+        let new_num = in_new.then_some(self.new.0);
+        for (has, o) in in_old.iter().zip(self.old.iter_mut()) {
+            if *has {
+                *o = (o.0.saturating_add(1), o.1 - 1);
+            }
+        }
+        if in_new {
+            self.new = (self.new.0.saturating_add(1), self.new.1 - 1);
+        }
+        Some(diff_line(kind, old_num, new_num, text))
+    }
+}
+
+fn diff_line(kind: &str, old_num: Option<u32>, new_num: Option<u32>, text: &str) -> DiffLine {
+    DiffLine { kind: kind.to_string(), old_num, new_num, text: text.to_string() }
+}
+
+/// Parse `git diff` output into numbered lines. Hunk headers and
+/// `\ No newline at end of file` markers come through as unnumbered
+/// `ctx` lines; file headers and anything outside a hunk are dropped.
+pub(crate) fn parse_unified_diff(diff: &str) -> Vec<DiffLine> {
+    let mut lines = Vec::new();
+    let mut hunk: Option<Hunk> = None;
+    // Whether a hunk just ended, so a trailing "\ No newline" belongs to it.
+    let mut after_hunk = false;
+
+    for raw in diff.lines() {
+        if let Some(h) = hunk.as_mut() {
+            if raw.starts_with('\\') {
+                lines.push(diff_line("ctx", None, None, raw));
+                continue;
+            }
+            if let Some(line) = h.take(raw) {
+                lines.push(line);
+                if h.done() {
+                    hunk = None;
+                    after_hunk = true;
+                }
+                continue;
+            }
+            // Not a hunk line, or more lines than the header announced.
+            hunk = None;
+            after_hunk = false;
+        }
+
+        if let Some(header) = raw.starts_with("@@").then(|| parse_hunk_header(raw)).flatten() {
+            lines.push(diff_line("ctx", None, None, raw));
+            let h = Hunk { old: header.old, new: header.new };
+            after_hunk = h.done();
+            hunk = (!h.done()).then_some(h);
+        } else if raw.starts_with('\\') && after_hunk {
+            lines.push(diff_line("ctx", None, None, raw));
+        } else {
+            after_hunk = false;
+        }
+    }
+    lines
+}
+
+/// Changed line ranges in the new file from `git diff -U0` output, for
+/// gutter markers.
+pub(crate) fn diff_line_ranges(diff: &str) -> Vec<DiffRange> {
+    let mut ranges = Vec::new();
+    for line in diff.lines().filter(|l| l.starts_with("@@ ")) {
+        let Some(HunkHeader { old, new: (start, count) }) = parse_hunk_header(line) else { continue };
+        let old_count = old[0].1;
+        let (kind, start, end) = match (old_count, count) {
+            (0, 0) => continue,
+            (0, _) => ("add", start, start.saturating_add(count - 1)),
+            // A deletion is marked on the line after which it happened.
+            (_, 0) => ("del", start.max(1), start.max(1)),
+            _ => ("mod", start, start.saturating_add(count - 1)),
+        };
+        ranges.push(DiffRange { kind: kind.to_string(), start, end });
+    }
+    ranges
+}
+
+/// One row from `git status --porcelain -z` output. The full stdout is
+/// a sequence of NUL-separated entries; rename ('R') and copy ('C')
+/// entries are followed by an additional entry containing the source
+/// path. Parsing as raw bytes (not `String::from_utf8_lossy` over the
+/// whole buffer) preserves non-UTF-8 path bytes per-entry rather than
+/// smearing replacement characters across the whole buffer.
+pub(crate) struct PorcelainEntry {
+    pub index_status: u8,
+    pub wt_status: u8,
+    pub file: String,
+    /// Source path for renames/copies. `None` for everything else.
+    #[allow(dead_code)] // present for future consumers; not used yet
+    pub orig: Option<String>,
+}
+
+/// Parse byte output from `git status --porcelain -z`.
+///
+/// The `-z` flag produces NUL-separated entries with no quoting, so we
+/// can treat the buffer as `&[u8]` and only convert per-entry; paths may
+/// contain tabs, newlines and spaces. Each entry has the layout
+/// `XY <path>` where X is the index status, Y is the working-tree status,
+/// and one space follows. Rename and copy entries (in either column) are
+/// followed by an extra NUL-separated entry containing the source path.
+/// Entries that don't fit the layout are skipped.
+pub(crate) fn parse_status_porcelain_z(stdout: &[u8]) -> Vec<PorcelainEntry> {
+    let entries: Vec<&[u8]> = stdout.split(|&b| b == 0).collect();
+    let mut result = Vec::new();
+    let mut i = 0;
+    while i < entries.len() {
+        let entry = entries[i];
+        if entry.len() < 4 || entry[2] != b' ' {
+            i += 1;
+            continue;
+        }
+        let index_status = entry[0];
+        let wt_status = entry[1];
+        // entry[2] is a space separator; the path is everything after.
+        let file_bytes = &entry[3..];
+        let file = String::from_utf8_lossy(file_bytes).into_owned();
+
+        let has_source = matches!(index_status, b'R' | b'C') || matches!(wt_status, b'R' | b'C');
+        let orig = if has_source && i + 1 < entries.len() && !entries[i + 1].is_empty() {
+            i += 1;
+            Some(String::from_utf8_lossy(entries[i]).into_owned())
+        } else {
+            None
+        };
+
+        result.push(PorcelainEntry {
+            index_status,
+            wt_status,
+            file,
+            orig,
+        });
+        i += 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn summary(lines: &[DiffLine]) -> Vec<(String, Option<u32>, Option<u32>, String)> {
+        lines.iter().map(|l| (l.kind.clone(), l.old_num, l.new_num, l.text.clone())).collect()
+    }
+
+    fn row(kind: &str, old: Option<u32>, new: Option<u32>, text: &str) -> (String, Option<u32>, Option<u32>, String) {
+        (kind.to_string(), old, new, text.to_string())
+    }
+
+    #[test]
+    fn parses_hunk_headers() {
+        assert_eq!(parse_hunk_header("@@ -1,3 +1,4 @@ fn main()"), Some(HunkHeader { old: vec![(1, 3)], new: (1, 4) }));
+        assert_eq!(parse_hunk_header("@@ -0,0 +1 @@"), Some(HunkHeader { old: vec![(0, 0)], new: (1, 1) }));
+        assert_eq!(
+            parse_hunk_header("@@@ -1,2 -1,3 +1,4 @@@"),
+            Some(HunkHeader { old: vec![(1, 2), (1, 3)], new: (1, 4) })
+        );
+        for bad in ["@@ -1,3 @@", "@@ -x +1 @@", "@@@ -1 +1 @@@", "@ -1 +1 @", "@@ -1 +1", "@@ -99999999999 +1 @@"] {
+            assert_eq!(parse_hunk_header(bad), None, "{bad}");
+        }
+    }
+
+    #[test]
+    fn multi_file_diff_headers_are_not_hunk_lines() {
+        let lines = parse_unified_diff(include_str!("testdata/multi_file.diff"));
+        assert_eq!(
+            summary(&lines),
+            vec![
+                row("ctx", None, None, "@@ -1,2 +1,2 @@"),
+                row("del", Some(1), None, "-- old dashes"),
+                row("add", None, Some(1), "++ new pluses"),
+                row("ctx", Some(2), Some(2), "tail"),
+                row("ctx", None, None, "@@ -3 +3,0 @@"),
+                row("del", Some(3), None, "gone"),
+                row("ctx", None, None, "@@ -1 +1 @@"),
+                row("del", Some(1), None, "a\tb"),
+                row("ctx", None, None, "\\ No newline at end of file"),
+                row("add", None, Some(1), "a\tc"),
+                row("ctx", None, None, "\\ No newline at end of file"),
+            ]
+        );
+    }
+
+    #[test]
+    fn crlf_and_stripped_context_lines() {
+        let lines = parse_unified_diff(include_str!("testdata/crlf.diff"));
+        assert_eq!(
+            summary(&lines),
+            vec![
+                row("ctx", None, None, "@@ -1,3 +1,3 @@"),
+                row("ctx", Some(1), Some(1), "one"),
+                row("ctx", Some(2), Some(2), ""),
+                row("del", Some(3), None, "three"),
+                row("add", None, Some(3), "THREE"),
+            ]
+        );
+    }
+
+    #[test]
+    fn combined_conflict_diff() {
+        let lines = parse_unified_diff(include_str!("testdata/combined.diff"));
+        assert_eq!(
+            summary(&lines),
+            vec![
+                row("ctx", None, None, "@@@ -1,3 -1,3 +1,7 @@@"),
+                row("ctx", None, Some(1), "start"),
+                row("add", None, Some(2), "<<<<<<< HEAD"),
+                row("add", None, Some(3), "ours"),
+                row("add", None, Some(4), "======="),
+                row("add", None, Some(5), "theirs"),
+                row("add", None, Some(6), ">>>>>>> feature"),
+                row("ctx", None, Some(7), "end"),
+            ]
+        );
+    }
+
+    #[test]
+    fn binary_and_new_file_diffs() {
+        let lines = parse_unified_diff(include_str!("testdata/new_and_binary.diff"));
+        assert_eq!(
+            summary(&lines),
+            vec![row("ctx", None, None, "@@ -0,0 +1,2 @@"), row("add", None, Some(1), "x"), row("add", None, Some(2), "y")]
+        );
+    }
+
+    #[test]
+    fn line_ranges_from_zero_context_diff() {
+        let diff = "@@ -0,0 +1,2 @@\n+a\n+b\n@@ -5 +6,0 @@\n-c\n@@ -9,2 +9,3 @@\n@@ -0,0 +0,0 @@\n@@ -1 +4294967295,2 @@\n";
+        let ranges: Vec<(String, u32, u32)> =
+            diff_line_ranges(diff).into_iter().map(|r| (r.kind, r.start, r.end)).collect();
+        assert_eq!(
+            ranges,
+            vec![
+                ("add".to_string(), 1, 2),
+                ("del".to_string(), 6, 6),
+                ("mod".to_string(), 9, 11),
+                ("mod".to_string(), u32::MAX, u32::MAX),
+            ]
+        );
+    }
+
+    #[test]
+    fn porcelain_with_tabs_newlines_and_worktree_renames() {
+        let raw = b" M with\ttab.txt\0?? new\nline.txt\0 R renamed.txt\0original.txt\0XYbad\0A  ok.rs\0";
+        let entries = parse_status_porcelain_z(raw);
+        let files: Vec<(&str, Option<&str>)> = entries.iter().map(|e| (e.file.as_str(), e.orig.as_deref())).collect();
+        assert_eq!(
+            files,
+            vec![("with\ttab.txt", None), ("new\nline.txt", None), ("renamed.txt", Some("original.txt")), ("ok.rs", None)]
+        );
+    }
+
+    // ── Properties ──
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        Ctx(String),
+        Add(String),
+        Del(String),
+    }
+
+    fn content() -> impl Strategy<Value = String> {
+        // Content that looks like diff syntax is the interesting case.
+        prop_oneof![
+            "[a-z \t]{0,12}",
+            "(--- a/|\\+\\+\\+ b/|@@ -1 \\+1 @@|diff --git |-|\\+| )[a-z]{0,6}",
+        ]
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        prop_oneof![content().prop_map(Op::Ctx), content().prop_map(Op::Add), content().prop_map(Op::Del)]
+    }
+
+    fn render(files: &[(u32, Vec<Op>)]) -> String {
+        let mut out = String::new();
+        for (i, (start, ops)) in files.iter().enumerate() {
+            let old = ops.iter().filter(|o| !matches!(o, Op::Add(_))).count();
+            let new = ops.iter().filter(|o| !matches!(o, Op::Del(_))).count();
+            out.push_str(&format!("diff --git a/f{i} b/f{i}\n--- a/f{i}\n+++ b/f{i}\n@@ -{start},{old} +{start},{new} @@\n"));
+            for op in ops {
+                let (p, t) = match op {
+                    Op::Ctx(t) => (' ', t),
+                    Op::Add(t) => ('+', t),
+                    Op::Del(t) => ('-', t),
+                };
+                out.push(p);
+                out.push_str(t);
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    proptest! {
+        #[test]
+        fn diff_parser_never_panics(input in "(?s).{0,400}") {
+            let _ = parse_unified_diff(&input);
+            let _ = diff_line_ranges(&input);
+        }
+
+        #[test]
+        fn diff_parser_never_panics_on_diffish_input(
+            lines in prop::collection::vec("(@@@? -?[0-9,]{0,6} [-+][0-9,]{0,6} @@@?|[-+ \\\\@]{0,3}[a-z]{0,4}|)", 0..40)
+        ) {
+            let _ = parse_unified_diff(&lines.join("\n"));
+        }
+
+        #[test]
+        fn diff_round_trips(files in prop::collection::vec((1u32..1000, prop::collection::vec(op(), 1..20)), 1..4)) {
+            let lines = parse_unified_diff(&render(&files));
+            let body: Vec<&DiffLine> = lines.iter().filter(|l| l.old_num.is_some() || l.new_num.is_some()).collect();
+            let expected: Vec<&Op> = files.iter().flat_map(|(_, ops)| ops).collect();
+            prop_assert_eq!(body.len(), expected.len());
+            let mut at = 0;
+            for (start, ops) in &files {
+                let (mut old, mut new) = (*start, *start);
+                for op in ops {
+                    let line = body[at];
+                    at += 1;
+                    let (kind, text, o, n) = match op {
+                        Op::Ctx(t) => ("ctx", t, Some(old), Some(new)),
+                        Op::Add(t) => ("add", t, None, Some(new)),
+                        Op::Del(t) => ("del", t, Some(old), None),
+                    };
+                    prop_assert_eq!(&line.kind, kind);
+                    prop_assert_eq!(&line.text, text);
+                    prop_assert_eq!((line.old_num, line.new_num), (o, n));
+                    if o.is_some() { old += 1; }
+                    if n.is_some() { new += 1; }
+                }
+            }
+        }
+
+        #[test]
+        fn status_parser_never_panics(input in prop::collection::vec(any::<u8>(), 0..300)) {
+            let _ = parse_status_porcelain_z(&input);
+        }
+
+        #[test]
+        fn status_round_trips(entries in prop::collection::vec(
+            ("[ MADRCU?!]", "[ MADRCU?!]", "[^\\x00]{1,20}", "[^\\x00]{1,20}"), 0..12)
+        ) {
+            let mut raw = Vec::new();
+            for (x, y, path, orig) in &entries {
+                raw.extend_from_slice(format!("{x}{y} {path}\0").as_bytes());
+                if matches!(x.as_str(), "R" | "C") || matches!(y.as_str(), "R" | "C") {
+                    raw.extend_from_slice(format!("{orig}\0").as_bytes());
+                }
+            }
+            let parsed = parse_status_porcelain_z(&raw);
+            prop_assert_eq!(parsed.len(), entries.len());
+            for (p, (x, y, path, orig)) in parsed.iter().zip(&entries) {
+                prop_assert_eq!((p.index_status, p.wt_status), (x.as_bytes()[0], y.as_bytes()[0]));
+                prop_assert_eq!(&p.file, path);
+                let renamed = matches!(x.as_str(), "R" | "C") || matches!(y.as_str(), "R" | "C");
+                prop_assert_eq!(p.orig.as_deref(), renamed.then_some(orig.as_str()));
+            }
+        }
+    }
+}
@@ -0,0 +1,290 @@
+//! Interactive rebase without an editor.
+//!
+//! `git_rebase_plan` lists the commits after a base, oldest first, each
+//! set to `pick`; the UI changes actions, reorders, and passes the list
+//! back to `git_rebase_start`. That writes git's todo list for it (as
+//! `sequence.editor`) and answers every message prompt with the message
+//! git proposes, so a squash keeps both messages and a reword gets the
+//! message given for it. A rebase that stops on conflicts stays in
+//! progress for the conflict resolver; `git_rebase_continue`, `_skip` and
+//! `_abort` carry on from there, like their `git rebase` counterparts.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::bisect::validate_rev;
+use super::merge::conflicted_paths;
+use super::validate_repo_path;
+use crate::modules::fs::ProjectRootState;
+use crate::modules::process::ProcessCommand;
+
+/// Holds the todo list and reword messages while a rebase runs.
+const WORK_DIR: &str = "leo-rebase";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RebaseAction {
+    Pick,
+    /// Pick, then replace the message with the step's `message`.
+    Reword,
+    /// Fold into the previous commit, keeping both messages.
+    Squash,
+    Drop,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RebaseStep {
+    pub hash: String,
+    pub action: RebaseAction,
+    /// The commit's subject; ignored by `git_rebase_start`.
+    #[serde(default)]
+    pub subject: String,
+    /// New message for `reword`.
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RebaseStatus {
+    Done,
+    /// Stopped on conflicts; resolve them, then continue.
+    Conflicts,
+    /// Stopped without conflicts, e.g. a pick that became empty.
+    Stopped,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct RebaseResult {
+    pub status: RebaseStatus,
+    /// Conflicted paths, relative to the repository root.
+    pub conflicts: Vec<String>,
+    /// What git printed.
+    pub output: String,
+}
+
+fn git(repo: &Path, args: &[&str]) -> Result<String, String> {
+    let output = ProcessCommand::new("git").args(args).current_dir(repo).output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn git_path(repo: &Path, name: &str) -> Result<PathBuf, String> {
+    git(repo, &["rev-parse", "--git-path", name]).map(|p| repo.join(p.trim()))
+}
+
+fn in_progress(repo: &Path) -> Result<bool, String> {
+    Ok(git_path(repo, "rebase-merge")?.is_dir())
+}
+
+/// Quote for `sh`, which git runs editors and `exec` lines with.
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// Commits after `base`, oldest first, as `pick` steps. Merges are left
+/// out, as `git rebase -i` leaves them out.
+pub fn plan(repo: &Path, base: &str) -> Result<Vec<RebaseStep>, String> {
+    let range = format!("{}..HEAD", base);
+    let log = git(repo, &["log", "--reverse", "--no-merges", "--format=%H%x00%s", &range, "--"])?;
+    Ok(log
+        .lines()
+        .filter_map(|l| l.split_once('\0'))
+        .map(|(hash, subject)| RebaseStep {
+            hash: hash.to_string(),
+            action: RebaseAction::Pick,
+            subject: subject.to_string(),
+            message: None,
+        })
+        .collect())
+}
+
+/// Check `steps` against the planned commits: each exactly once (drops
+/// must be explicit), something to squash into, a message for each reword.
+fn validate_steps(steps: &[RebaseStep], planned: &[RebaseStep]) -> Result<(), String> {
+    let mut hashes: Vec<&str> = steps.iter().map(|s| s.hash.as_str()).collect();
+    let mut expected: Vec<&str> = planned.iter().map(|s| s.hash.as_str()).collect();
+    hashes.sort_unstable();
+    expected.sort_unstable();
+    if hashes != expected {
+        return Err("The rebase plan must list each commit after the base exactly once".to_string());
+    }
+    let first_kept = steps.iter().find(|s| s.action != RebaseAction::Drop);
+    if first_kept.is_some_and(|s| s.action == RebaseAction::Squash) {
+        return Err("The first commit can't be squashed; there is nothing before it to squash into".to_string());
+    }
+    if let Some(step) = steps
+        .iter()
+        .find(|s| s.action == RebaseAction::Reword && s.message.as_deref().unwrap_or("").trim().is_empty())
+    {
+        return Err(format!("No new message for reworded commit {}", &step.hash[..step.hash.len().min(7)]));
+    }
+    Ok(())
+}
+
+/// git's todo list for `steps`. Each reword is a pick followed by an
+/// amend from `message_file(i)`, so no editor opens mid-rebase.
+fn todo(steps: &[RebaseStep], message_file: impl Fn(usize) -> PathBuf) -> String {
+    let mut out = String::new();
+    for (i, step) in steps.iter().enumerate() {
+        let action = match step.action {
+            RebaseAction::Pick | RebaseAction::Reword => "pick",
+            RebaseAction::Squash => "squash",
+            RebaseAction::Drop => "drop",
+        };
+        out.push_str(&format!("{} {}\n", action, step.hash));
+        if step.action == RebaseAction::Reword {
+            let file = message_file(i).to_string_lossy().into_owned();
+            out.push_str(&format!("exec git commit --amend --only --allow-empty --no-verify -F {}\n", shell_quote(&file)));
+        }
+    }
+    out
+}
+
+/// Run a rebase command, answering any message prompt with git's own
+/// proposal, and report where it left off.
+fn run(repo: &Path, config: &[String], args: &[&str]) -> Result<RebaseResult, String> {
+    let mut cmd = ProcessCommand::new("git");
+    for c in config {
+        cmd.args(["-c", c]);
+    }
+    let output = cmd
+        .args(["-c", "core.editor=true", "rebase"])
+        .args(args)
+        .current_dir(repo)
+        .output()
+        .map_err(|e| format!("Failed to run git rebase: {}", e))?;
+    let text = format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr))
+        .trim()
+        .to_string();
+    if !in_progress(repo)? {
+        if let Ok(dir) = git_path(repo, WORK_DIR) {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+        if !output.status.success() {
+            return Err(text);
+        }
+        return Ok(RebaseResult { status: RebaseStatus::Done, conflicts: Vec::new(), output: text });
+    }
+    let conflicts = conflicted_paths(repo)?;
+    let status = if conflicts.is_empty() { RebaseStatus::Stopped } else { RebaseStatus::Conflicts };
+    Ok(RebaseResult { status, conflicts, output: text })
+}
+
+pub fn start(repo: &Path, base: &str, steps: &[RebaseStep]) -> Result<RebaseResult, String> {
+    if in_progress(repo)? {
+        return Err("A rebase is already in progress".to_string());
+    }
+    validate_steps(steps, &plan(repo, base)?)?;
+    let dir = git_path(repo, WORK_DIR)?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let message_file = |i: usize| dir.join(format!("message-{}", i));
+    for (i, step) in steps.iter().enumerate() {
+        if let (RebaseAction::Reword, Some(message)) = (step.action, &step.message) {
+            std::fs::write(message_file(i), message).map_err(|e| e.to_string())?;
+        }
+    }
+    let todo_file = dir.join("todo");
+    std::fs::write(&todo_file, todo(steps, message_file)).map_err(|e| e.to_string())?;
+    let editor = format!("sequence.editor=cp {}", shell_quote(&todo_file.to_string_lossy()));
+    run(repo, &[editor], &["-i", base])
+}
+
+// ── Commands ──
+
+/// The commits a rebase onto `base` would replay, oldest first, each set
+/// to `pick`.
+#[tauri::command]
+pub fn git_rebase_plan(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    repo_path: String,
+    base: String,
+) -> Result<Vec<RebaseStep>, String> {
+    let repo = validate_repo_path(&repo_path, window.label(), &state)?;
+    validate_rev(&base)?;
+    plan(&repo, &base)
+}
+
+/// Rebase the current branch onto `base` following `steps`, which must
+/// list every commit from `git_rebase_plan` once, in the new order.
+#[tauri::command]
+pub fn git_rebase_start(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    repo_path: String,
+    base: String,
+    steps: Vec<RebaseStep>,
+) -> Result<RebaseResult, String> {
+    let repo = validate_repo_path(&repo_path, window.label(), &state)?;
+    validate_rev(&base)?;
+    start(&repo, &base, &steps)
+}
+
+/// Carry on after resolving conflicts. Resolved files must be staged.
+#[tauri::command]
+pub fn git_rebase_continue(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    repo_path: String,
+) -> Result<RebaseResult, String> {
+    let repo = validate_repo_path(&repo_path, window.label(), &state)?;
+    run(&repo, &[], &["--continue"])
+}
+
+/// Leave out the commit the rebase stopped on and carry on.
+#[tauri::command]
+pub fn git_rebase_skip(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    repo_path: String,
+) -> Result<RebaseResult, String> {
+    let repo = validate_repo_path(&repo_path, window.label(), &state)?;
+    run(&repo, &[], &["--skip"])
+}
+
+/// Give up and put the branch back where it was.
+#[tauri::command]
+pub fn git_rebase_abort(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    repo_path: String,
+) -> Result<RebaseResult, String> {
+    let repo = validate_repo_path(&repo_path, window.label(), &state)?;
+    run(&repo, &[], &["--abort"])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(hash: &str, action: RebaseAction, message: Option<&str>) -> RebaseStep {
+        RebaseStep { hash: hash.into(), action, subject: String::new(), message: message.map(str::to_string) }
+    }
+
+    #[test]
+    fn writes_todo_and_rejects_bad_plans() {
+        use RebaseAction::*;
+        let planned = [step("aaa", Pick, None), step("bbb", Pick, None), step("ccc", Pick, None)];
+        let steps = [step("bbb", Reword, Some("Better message")), step("aaa", Squash, None), step("ccc", Drop, None)];
+        assert_eq!(validate_steps(&steps, &planned), Ok(()));
+        assert_eq!(
+            todo(&steps, |i| PathBuf::from(format!("/r/.git/it's/{}", i))),
+            "pick bbb\n\
+             exec git commit --amend --only --allow-empty --no-verify -F '/r/.git/it'\\''s/0'\n\
+             squash aaa\n\
+             drop ccc\n"
+        );
+
+        let missing = [step("aaa", Pick, None), step("bbb", Pick, None)];
+        assert!(validate_steps(&missing, &planned).unwrap_err().contains("exactly once"));
+        let twice = [step("aaa", Pick, None), step("aaa", Pick, None), step("ccc", Pick, None)];
+        assert!(validate_steps(&twice, &planned).is_err());
+        let squash_first = [step("aaa", Drop, None), step("bbb", Squash, None), step("ccc", Pick, None)];
+        assert!(validate_steps(&squash_first, &planned).unwrap_err().contains("can't be squashed"));
+        let no_message = [step("aaa", Reword, Some("  ")), step("bbb", Pick, None), step("ccc", Pick, None)];
+        assert_eq!(validate_steps(&no_message, &planned).unwrap_err(), "No new message for reworded commit aaa");
+    }
+}
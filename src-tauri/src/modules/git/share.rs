@@ -0,0 +1,425 @@
+//! Diffs to pass to a teammate without opening a pull request.
+//!
+//! `export_shareable_diff` renders a range's changes as one self-contained
+//! HTML page — styles inline, no scripts — with the commits it covers, a
+//! file list and every file's hunks. The page is saved under
+//! `~/.leo-ide/shared-diffs/`, or uploaded to the paste endpoint configured
+//! in `~/.leo-ide/diff-share.json`. The endpoint gets the page as the body
+//! of a `POST` (`text/html`), with the token from the secure key storage as
+//! a bearer token when one is set, and answers with the paste's URL as
+//! plain text or as the `url` of a JSON object.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::branch_policy::slugify;
+use super::{parse_unified_diff, DiffLine};
+use crate::modules::fs::{project_root_async, write_atomic, ProjectRootState};
+use crate::modules::process::ProcessCommand;
+use crate::modules::{ai, net};
+
+const TOKEN_NAME: &str = "diff-share";
+/// Larger diffs make pages nobody reads and pastes most services refuse.
+const MAX_DIFF_BYTES: usize = 5 * 1024 * 1024;
+const MAX_COMMITS: usize = 200;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct DiffShareSettings {
+    /// Paste service URL pages are `POST`ed to.
+    pub endpoint: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct SharedDiff {
+    /// Path of the saved page, or the paste URL when uploaded.
+    pub location: String,
+    pub uploaded: bool,
+    pub files: usize,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+struct FileDiff {
+    path: String,
+    /// Set when the file was renamed.
+    old_path: Option<String>,
+    binary: bool,
+    lines: Vec<DiffLine>,
+}
+
+impl FileDiff {
+    fn count(&self, kind: &str) -> usize {
+        self.lines.iter().filter(|l| l.kind == kind && (l.old_num.is_some() || l.new_num.is_some())).count()
+    }
+}
+
+struct Commit {
+    hash: String,
+    author: String,
+    subject: String,
+}
+
+// ── Settings ──
+
+fn settings_path() -> PathBuf {
+    dirs::home_dir().unwrap_or_default().join(".leo-ide").join("diff-share.json")
+}
+
+fn load_settings() -> DiffShareSettings {
+    std::fs::read_to_string(settings_path()).ok().and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default()
+}
+
+// ── Collecting ──
+
+/// What to diff: uncommitted changes against `HEAD` when `range` is empty,
+/// `a..b` or `a...b` as git reads them, or a single commit's own changes.
+fn check_range(range: &str) -> Result<(), String> {
+    let sides: Vec<&str> = if range.contains("...") { range.split("...").collect() } else { range.split("..").collect() };
+    for side in sides {
+        if side.starts_with('-') || side.chars().any(|c| c.is_whitespace() || c.is_control()) {
+            return Err(format!("Invalid range: {}", range));
+        }
+    }
+    Ok(())
+}
+
+fn git(repo: &Path, args: &[&str]) -> Result<String, String> {
+    let output = ProcessCommand::new("git").args(args).current_dir(repo).output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn diff_text(repo: &Path, range: &str) -> Result<String, String> {
+    const FLAGS: [&str; 3] = ["--no-color", "--no-ext-diff", "--find-renames"];
+    if range.is_empty() {
+        git(repo, &[&["diff"], &FLAGS[..], &["HEAD", "--"]].concat())
+    } else if range.contains("..") {
+        git(repo, &[&["diff"], &FLAGS[..], &[range, "--"]].concat())
+    } else {
+        git(repo, &[&["show", "--format="], &FLAGS[..], &[range, "--"]].concat())
+    }
+}
+
+fn commits(repo: &Path, range: &str) -> Vec<Commit> {
+    if range.is_empty() {
+        return Vec::new();
+    }
+    let limit = format!("-{}", MAX_COMMITS);
+    let mut args = vec!["log", "--format=%h%x1f%an%x1f%s", &limit];
+    if !range.contains("..") {
+        args.push("-1");
+    }
+    args.extend([range, "--"]);
+    let Ok(out) = git(repo, &args) else { return Vec::new() };
+    out.lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\x1f');
+            Some(Commit { hash: fields.next()?.to_string(), author: fields.next()?.to_string(), subject: fields.next()?.to_string() })
+        })
+        .collect()
+}
+
+/// `git diff` output split into files, each parsed into numbered lines.
+fn split_files(diff: &str) -> Vec<FileDiff> {
+    let mut sections: Vec<Vec<&str>> = Vec::new();
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") || sections.is_empty() {
+            sections.push(Vec::new());
+        }
+        if let Some(section) = sections.last_mut() {
+            section.push(line);
+        }
+    }
+    sections
+        .into_iter()
+        .filter(|s| s.first().is_some_and(|l| l.starts_with("diff --git ")))
+        .map(|section| {
+            let header = |prefix: &str| {
+                section.iter().find_map(|l| l.strip_prefix(prefix)).map(|p| p.trim_end_matches('\t').to_string())
+            };
+            let old = header("--- a/").or_else(|| header("rename from "));
+            let new = header("+++ b/").or_else(|| header("rename to "));
+            // Binary and mode-only changes have no ---/+++ lines.
+            let fallback = section[0].strip_prefix("diff --git a/").and_then(|l| l.split_once(" b/")).map(|(_, b)| b.to_string());
+            let path = new.clone().or_else(|| old.clone()).or(fallback).unwrap_or_default();
+            FileDiff {
+                old_path: old.filter(|o| new.as_ref().is_some_and(|n| n != o)),
+                path,
+                binary: section.iter().any(|l| l.starts_with("Binary files ") || *l == "GIT binary patch"),
+                lines: parse_unified_diff(&section.join("\n")),
+            }
+        })
+        .collect()
+}
+
+// ── Rendering ──
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+const STYLE: &str = "\
+:root{color-scheme:light dark;--bg:#fff;--fg:#1f2328;--muted:#656d76;--border:#d0d7de;--head:#f6f8fa;--add:#e6ffec;--del:#ffebe9;--hunk:#ddf4ff}
+@media(prefers-color-scheme:dark){:root{--bg:#0d1117;--fg:#e6edf3;--muted:#8d96a0;--border:#30363d;--head:#161b22;--add:#12261e;--del:#25171c;--hunk:#121d2f}}
+body{margin:0 auto;max-width:1200px;padding:24px;background:var(--bg);color:var(--fg);font:14px/1.5 -apple-system,BlinkMacSystemFont,'Segoe UI',sans-serif}
+h1{font-size:20px;margin:0 0 4px}.muted{color:var(--muted)}.add{color:#1a7f37}.del{color:#cf222e}
+ul{padding-left:20px}a{color:inherit}code,table{font:12px/1.45 ui-monospace,SFMono-Regular,Menlo,Consolas,monospace}
+details{border:1px solid var(--border);border-radius:6px;margin:16px 0;overflow:hidden}
+summary{background:var(--head);padding:8px 12px;cursor:pointer;font-family:ui-monospace,monospace}
+table{border-collapse:collapse;width:100%}td{padding:0 8px;white-space:pre-wrap;word-break:break-all;vertical-align:top}
+td.n{width:1%;min-width:40px;text-align:right;color:var(--muted);user-select:none}
+tr.a{background:var(--add)}tr.d{background:var(--del)}tr.h td{background:var(--hunk);color:var(--muted)}
+p.binary{padding:8px 12px;margin:0}
+";
+
+/// Added and removed lines across `files`.
+fn totals(files: &[FileDiff]) -> (usize, usize) {
+    files.iter().fold((0, 0), |(a, d), f| (a + f.count("add"), d + f.count("del")))
+}
+
+fn render(title: &str, range_label: &str, commits: &[Commit], files: &[FileDiff]) -> String {
+    let (additions, deletions) = totals(files);
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width,initial-scale=1\">\n<title>{title} · {range}</title>\n<style>\n{STYLE}</style>\n</head>\n<body>\n<h1>{title}</h1>\n<p class=\"muted\"><code>{range}</code> · {files} file{s} changed, <span class=\"add\">+{additions}</span> <span class=\"del\">−{deletions}</span></p>\n",
+        title = escape(title),
+        range = escape(range_label),
+        files = files.len(),
+        s = if files.len() == 1 { "" } else { "s" },
+    );
+    if !commits.is_empty() {
+        html.push_str("<h2>Commits</h2>\n<ul>\n");
+        for c in commits {
+            html.push_str(&format!("<li><code>{}</code> {} <span class=\"muted\">— {}</span></li>\n", escape(&c.hash), escape(&c.subject), escape(&c.author)));
+        }
+        html.push_str("</ul>\n");
+    }
+    html.push_str("<h2>Files</h2>\n<ul>\n");
+    for (i, f) in files.iter().enumerate() {
+        html.push_str(&format!(
+            "<li><a href=\"#f{}\"><code>{}</code></a> <span class=\"add\">+{}</span> <span class=\"del\">−{}</span></li>\n",
+            i,
+            escape(&f.path),
+            f.count("add"),
+            f.count("del")
+        ));
+    }
+    html.push_str("</ul>\n");
+    for (i, f) in files.iter().enumerate() {
+        let name = match &f.old_path {
+            Some(old) => format!("{} → {}", escape(old), escape(&f.path)),
+            None => escape(&f.path),
+        };
+        html.push_str(&format!("<details open id=\"f{}\">\n<summary>{}</summary>\n", i, name));
+        if f.binary {
+            html.push_str("<p class=\"binary muted\">Binary file changed</p>\n");
+        } else {
+            html.push_str("<table>\n");
+            for line in &f.lines {
+                let num = |n: Option<u32>| n.map(|n| n.to_string()).unwrap_or_default();
+                let class = match line.kind.as_str() {
+                    "add" => "a",
+                    "del" => "d",
+                    _ if line.old_num.is_none() && line.new_num.is_none() => "h",
+                    _ => "c",
+                };
+                let sign = match class {
+                    "a" => "+",
+                    "d" => "-",
+                    "h" => "",
+                    _ => " ",
+                };
+                html.push_str(&format!(
+                    "<tr class=\"{}\"><td class=\"n\">{}</td><td class=\"n\">{}</td><td>{}{}</td></tr>\n",
+                    class,
+                    num(line.old_num),
+                    num(line.new_num),
+                    sign,
+                    escape(&line.text)
+                ));
+            }
+            html.push_str("</table>\n");
+        }
+        html.push_str("</details>\n");
+    }
+    html.push_str(&format!("<p class=\"muted\"><span class=\"add\">+{}</span> <span class=\"del\">−{}</span></p>\n</body>\n</html>\n", additions, deletions));
+    html
+}
+
+// ── Sharing ──
+
+/// The paste URL in an endpoint's response.
+fn paste_url(body: &str) -> Option<String> {
+    let url = match serde_json::from_str::<serde_json::Value>(body) {
+        Ok(json) => json["url"].as_str()?.to_string(),
+        Err(_) => body.trim().to_string(),
+    };
+    (url.starts_with("https://") || url.starts_with("http://")).then_some(url)
+}
+
+async fn upload_page(endpoint: &str, html: String) -> Result<String, String> {
+    net::ensure_online("Diff sharing")?;
+    let mut req = net::client().post(endpoint).header("Content-Type", "text/html; charset=utf-8").body(html);
+    if let Some(token) = ai::get_key(TOKEN_NAME)? {
+        req = req.bearer_auth(token);
+    }
+    let response = req.send().await.map_err(|e| format!("Upload failed: {}", e))?;
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+    if !status.is_success() {
+        return Err(format!("Paste service error {}: {}", status, text));
+    }
+    paste_url(&text).ok_or_else(|| "The paste service didn't answer with a URL".to_string())
+}
+
+fn save(repo: &Path, range: &str, html: &str) -> Result<PathBuf, String> {
+    let dir = dirs::home_dir().unwrap_or_default().join(".leo-ide").join("shared-diffs");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to save diff: {}", e))?;
+    let repo_name = repo.file_name().map(|n| slugify(&n.to_string_lossy())).unwrap_or_default();
+    let what = if range.is_empty() { "uncommitted".to_string() } else { slugify(range) };
+    let stamp = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let path = dir.join(format!("{}-{}-{}.html", repo_name, what, stamp));
+    write_atomic(&path, html.as_bytes())?;
+    Ok(path)
+}
+
+// ── Commands ──
+
+#[tauri::command]
+pub fn get_diff_share_settings() -> DiffShareSettings {
+    load_settings()
+}
+
+/// Save the paste endpoint. `token` replaces the stored token when given;
+/// an empty string removes it.
+#[tauri::command]
+pub fn set_diff_share_settings(settings: DiffShareSettings, token: Option<String>) -> Result<(), String> {
+    if let Some(endpoint) = &settings.endpoint {
+        let url = reqwest::Url::parse(endpoint).map_err(|_| format!("Invalid paste endpoint: {}", endpoint))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err("The paste endpoint must be an http(s) URL".to_string());
+        }
+    }
+    if let Some(token) = token {
+        ai::set_key(TOKEN_NAME, token.trim())?;
+    }
+    let path = settings_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to save diff sharing settings: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+    write_atomic(&path, json.as_bytes()).map_err(|e| format!("Failed to save diff sharing settings: {}", e))
+}
+
+/// Render the changes in `range` (see `check_range`; uncommitted changes
+/// when omitted) as a standalone HTML page. With `upload` the page goes to
+/// the configured paste endpoint and its URL comes back; otherwise it is
+/// saved and its path comes back.
+#[tauri::command]
+pub async fn export_shareable_diff(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    repo_path: String,
+    range: Option<String>,
+    upload: Option<bool>,
+) -> Result<SharedDiff, String> {
+    let root = project_root_async(window.label(), &state).await?;
+    let repo = std::fs::canonicalize(&repo_path).map_err(|e| format!("Invalid repo path: {}", e))?;
+    if !repo.starts_with(&root) {
+        return Err("Access denied: repo path is outside the project directory".to_string());
+    }
+    let range = range.unwrap_or_default().trim().to_string();
+    check_range(&range)?;
+    let endpoint = match upload.unwrap_or(false) {
+        true => Some(load_settings().endpoint.ok_or("No paste endpoint is configured")?),
+        false => None,
+    };
+
+    let (blocking_repo, blocking_range) = (repo.clone(), range.clone());
+    let (html, files, (additions, deletions)) = tokio::task::spawn_blocking(move || {
+        let (repo, range) = (blocking_repo, blocking_range);
+        let diff = diff_text(&repo, &range)?;
+        if diff.len() > MAX_DIFF_BYTES {
+            return Err("The diff is too large to share".to_string());
+        }
+        let files = split_files(&diff);
+        if files.is_empty() {
+            return Err("No changes to share".to_string());
+        }
+        let title = repo.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        let label = if range.is_empty() { "uncommitted changes" } else { range.as_str() };
+        Ok((render(&title, label, &commits(&repo, &range), &files), files.len(), totals(&files)))
+    })
+    .await
+    .map_err(|e| format!("Diff export failed: {}", e))??;
+
+    let (location, uploaded) = match endpoint {
+        Some(endpoint) => (upload_page(&endpoint, html).await?, true),
+        None => (save(&repo, &range, &html)?.to_string_lossy().into_owned(), false),
+    };
+    Ok(SharedDiff { location, uploaded, files, additions, deletions })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_files_and_finds_paste_urls() {
+        let diff = "\
+diff --git a/src/main.rs b/src/main.rs
+index 1111111..2222222 100644
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -1,3 +1,3 @@
+ fn main() {
+-    println!(\"hi\");
++    println!(\"<hello> & bye\");
+ }
+diff --git a/old.txt b/new.txt
+similarity 100%
+rename from old.txt
+rename to new.txt
+diff --git a/logo.png b/logo.png
+new file mode 100644
+index 0000000..3333333
+Binary files /dev/null and b/logo.png differ
+diff --git a/gone.md b/gone.md
+deleted file mode 100644
+--- a/gone.md
++++ /dev/null
+@@ -1 +0,0 @@
+-bye
+";
+        let files = split_files(diff);
+        let summary: Vec<_> = files.iter().map(|f| (f.path.as_str(), f.old_path.as_deref(), f.binary, f.count("add"), f.count("del"))).collect();
+        assert_eq!(summary, [
+            ("src/main.rs", None, false, 1, 1),
+            ("new.txt", Some("old.txt"), false, 0, 0),
+            ("logo.png", None, true, 0, 0),
+            ("gone.md", None, false, 0, 1),
+        ]);
+
+        let commits = [Commit { hash: "abc1234".into(), author: "Sam".into(), subject: "Greet <everyone>".into() }];
+        let html = render("app", "main..feature", &commits, &files);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(!html.contains("<script"));
+        assert!(html.contains("4 files changed, <span class=\"add\">+1</span> <span class=\"del\">−2</span>"));
+        assert!(html.contains("<code>abc1234</code> Greet &lt;everyone&gt;"));
+        assert!(html.contains("<tr class=\"a\"><td class=\"n\"></td><td class=\"n\">2</td><td>+    println!(&quot;&lt;hello&gt; &amp; bye&quot;);</td></tr>"));
+        assert!(html.contains("<summary>old.txt → new.txt</summary>"));
+        assert!(html.contains("Binary file changed"));
+
+        assert!(check_range("HEAD~3..HEAD").is_ok());
+        assert!(check_range("main...feature").is_ok());
+        assert!(check_range("--output=/tmp/x").is_err());
+        assert!(check_range("a..--exec").is_err());
+
+        assert_eq!(paste_url("https://paste.example/abc\n").as_deref(), Some("https://paste.example/abc"));
+        assert_eq!(paste_url("{\"url\":\"https://paste.example/x\",\"id\":\"x\"}").as_deref(), Some("https://paste.example/x"));
+        assert_eq!(paste_url("{\"error\":\"nope\"}"), None);
+        assert_eq!(paste_url("<html>rate limited</html>"), None);
+    }
+}
@@ -0,0 +1,314 @@
+//! Repository size diagnostics and garbage collection.
+//!
+//! `git_repo_stats` explains why a repository is slow to clone: object
+//! and pack totals from `git count-objects -v`, the largest blobs in the
+//! object database (named by the first path `git rev-list --objects`
+//! reports for them), and how much Git LFS stores locally. Path lookup
+//! reads at most `PATH_SAMPLE` rev-list entries, so blobs outside that
+//! sample are listed without a path and `sampled` is set.
+//!
+//! `git_gc` runs `git gc` off the main thread and streams its progress to
+//! the window as `git-gc-progress` events.
+
+use portable_pty::{native_pty_system, CommandBuilder, PtySize};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use tauri::AppHandle;
+use ts_rs::TS;
+
+use super::validate_repo_path;
+use crate::modules::events;
+use crate::modules::fs::{project_root_async, ProjectRootState};
+use crate::modules::process::ProcessCommand;
+
+/// How many blobs `largest_blobs` lists.
+const LARGEST_BLOBS: usize = 20;
+/// rev-list entries read when looking up blob paths.
+const PATH_SAMPLE: usize = 200_000;
+
+/// `git count-objects -v`, sizes in bytes.
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct ObjectCounts {
+    pub loose_objects: u64,
+    pub loose_bytes: u64,
+    pub packed_objects: u64,
+    pub packs: u64,
+    pub pack_bytes: u64,
+    pub prune_packable: u64,
+    pub garbage_bytes: u64,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct BlobSize {
+    pub oid: String,
+    pub bytes: u64,
+    /// First path the blob appears under, if it was in the sample.
+    pub path: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct LfsUsage {
+    /// Whether the `git lfs` extension answered.
+    pub installed: bool,
+    /// Files in the current checkout tracked by LFS.
+    pub tracked_files: usize,
+    /// Bytes under `.git/lfs/objects`.
+    pub local_bytes: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RepoStats {
+    pub objects: ObjectCounts,
+    pub largest_blobs: Vec<BlobSize>,
+    /// True when blob paths came from a truncated rev-list.
+    pub sampled: bool,
+    pub lfs: LfsUsage,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, TS)]
+pub struct GcProgress {
+    pub phase: String,
+    pub percent: Option<u8>,
+    pub line: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct GcResult {
+    pub before: ObjectCounts,
+    pub after: ObjectCounts,
+}
+
+fn git(repo: &Path, args: &[&str]) -> Result<String, String> {
+    let output = ProcessCommand::new("git").args(args).current_dir(repo).output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Parse `git count-objects -v`. Sizes are reported in KiB.
+pub fn parse_count_objects(out: &str) -> ObjectCounts {
+    let mut counts = ObjectCounts::default();
+    for line in out.lines() {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        let value: u64 = value.trim().parse().unwrap_or(0);
+        match key.trim() {
+            "count" => counts.loose_objects = value,
+            "size" => counts.loose_bytes = value * 1024,
+            "in-pack" => counts.packed_objects = value,
+            "packs" => counts.packs = value,
+            "size-pack" => counts.pack_bytes = value * 1024,
+            "prune-packable" => counts.prune_packable = value,
+            "size-garbage" => counts.garbage_bytes = value * 1024,
+            _ => {}
+        }
+    }
+    counts
+}
+
+/// The `limit` largest blobs from `cat-file --batch-check` lines of the
+/// form `<type> <oid> <size>`, paths filled in from `rev-list --objects`
+/// lines (`<oid> <path>`).
+pub fn largest_blobs(batch_check: &str, rev_list: &str, limit: usize) -> Vec<BlobSize> {
+    let mut blobs: Vec<(String, u64)> = batch_check
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split(' ');
+            if parts.next()? != "blob" {
+                return None;
+            }
+            let oid = parts.next()?;
+            let bytes = parts.next()?.parse().ok()?;
+            Some((oid.to_string(), bytes))
+        })
+        .collect();
+    blobs.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    blobs.truncate(limit);
+
+    let wanted: HashMap<&str, usize> = blobs.iter().enumerate().map(|(i, (oid, _))| (oid.as_str(), i)).collect();
+    let mut paths: Vec<Option<String>> = vec![None; blobs.len()];
+    for line in rev_list.lines() {
+        let Some((oid, path)) = line.split_once(' ') else { continue };
+        if let Some(&i) = wanted.get(oid) {
+            if paths[i].is_none() {
+                paths[i] = Some(path.to_string());
+            }
+        }
+    }
+    blobs.into_iter().zip(paths).map(|((oid, bytes), path)| BlobSize { oid, bytes, path }).collect()
+}
+
+/// One line of `git gc --progress` stderr, e.g.
+/// `Compressing objects:  45% (120/266)`.
+pub fn parse_gc_progress(line: &str) -> Option<GcProgress> {
+    let line = line.trim();
+    let (phase, rest) = line.split_once(':')?;
+    if phase.is_empty() {
+        return None;
+    }
+    let percent = rest.split_whitespace().next().and_then(|w| w.strip_suffix('%')).and_then(|p| p.parse().ok());
+    Some(GcProgress { phase: phase.to_string(), percent, line: line.to_string() })
+}
+
+fn count_objects(repo: &Path) -> Result<ObjectCounts, String> {
+    git(repo, &["count-objects", "-v"]).map(|out| parse_count_objects(&out))
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else { return 0 };
+    entries
+        .flatten()
+        .map(|e| match e.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&e.path()),
+            Ok(t) if t.is_file() => e.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+fn lfs_usage(repo: &Path) -> LfsUsage {
+    let tracked = git(repo, &["lfs", "ls-files", "--name-only"]);
+    let local_bytes = git(repo, &["rev-parse", "--git-common-dir"])
+        .map(|dir| dir_size(&repo.join(dir.trim()).join("lfs").join("objects")))
+        .unwrap_or(0);
+    LfsUsage {
+        installed: tracked.is_ok(),
+        tracked_files: tracked.map(|out| out.lines().filter(|l| !l.is_empty()).count()).unwrap_or(0),
+        local_bytes,
+    }
+}
+
+/// Object totals, largest blobs and LFS usage for the repository.
+#[tauri::command]
+pub fn git_repo_stats(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    repo_path: String,
+) -> Result<RepoStats, String> {
+    let repo = validate_repo_path(&repo_path, window.label(), &state)?;
+    let objects = count_objects(&repo)?;
+    let batch_check = git(
+        &repo,
+        &["cat-file", "--batch-all-objects", "--batch-check=%(objecttype) %(objectname) %(objectsize)"],
+    )?;
+    let rev_list = git(&repo, &["rev-list", "--objects", "--all"]).unwrap_or_default();
+    let sample_end = rev_list.match_indices('\n').nth(PATH_SAMPLE - 1).map(|(i, _)| i);
+    let sample = sample_end.map_or(rev_list.as_str(), |end| &rev_list[..end]);
+    Ok(RepoStats {
+        objects,
+        largest_blobs: largest_blobs(&batch_check, sample, LARGEST_BLOBS),
+        sampled: sample_end.is_some_and(|end| end + 1 < rev_list.len()),
+        lfs: lfs_usage(&repo),
+    })
+}
+
+/// Run `git gc` under a pseudo-terminal — git only reports progress to a
+/// terminal — passing each progress line to `on_progress`.
+fn run_gc(repo: &Path, aggressive: bool, mut on_progress: impl FnMut(GcProgress)) -> Result<(), String> {
+    let pair = native_pty_system()
+        .openpty(PtySize { rows: 24, cols: 200, pixel_width: 0, pixel_height: 0 })
+        .map_err(|e| e.to_string())?;
+    let mut cmd = CommandBuilder::new("git");
+    cmd.arg("gc");
+    if aggressive {
+        cmd.arg("--aggressive");
+    }
+    cmd.cwd(repo);
+    let mut child = pair.slave.spawn_command(cmd).map_err(|e| format!("Failed to run git gc: {}", e))?;
+    drop(pair.slave);
+    let reader = pair.master.try_clone_reader().map_err(|e| e.to_string())?;
+
+    // Progress redraws with `\r`, so split on both line endings. The
+    // last few lines are kept for the error message. The read fails
+    // rather than ending once git exits on some platforms.
+    let mut tail: Vec<String> = Vec::new();
+    let mut line = Vec::new();
+    for byte in BufReader::new(reader).bytes() {
+        let Ok(byte) = byte else { break };
+        if byte != b'\r' && byte != b'\n' {
+            line.push(byte);
+            continue;
+        }
+        let text = String::from_utf8_lossy(&line).replace("\x1b[K", "");
+        line.clear();
+        if let Some(progress) = parse_gc_progress(&text) {
+            on_progress(progress);
+        }
+        if !text.trim().is_empty() {
+            tail.push(text);
+            if tail.len() > 5 {
+                tail.remove(0);
+            }
+        }
+    }
+    let status = child.wait().map_err(|e| e.to_string())?;
+    if !status.success() {
+        return Err(format!("git gc failed: {}", tail.join("\n")));
+    }
+    Ok(())
+}
+
+/// Run `git gc` (optionally `--aggressive`), emitting each progress line
+/// as a `git-gc-progress` event. Returns the object totals from before
+/// and after so the caller can show what was reclaimed.
+#[tauri::command]
+pub async fn git_gc(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    app: AppHandle,
+    repo_path: String,
+    aggressive: Option<bool>,
+) -> Result<GcResult, String> {
+    let root = project_root_async(window.label(), &state).await?;
+    let repo = std::fs::canonicalize(&repo_path).map_err(|e| format!("Invalid repo path: {}", e))?;
+    if !repo.starts_with(&root) {
+        return Err("Access denied: repo path is outside the project directory".to_string());
+    }
+    let label = window.label().to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let before = count_objects(&repo)?;
+        run_gc(&repo, aggressive.unwrap_or(false), |progress| {
+            events::GIT_GC_PROGRESS.emit_to(&app, &label, progress);
+        })?;
+        Ok(GcResult { before, after: count_objects(&repo)? })
+    })
+    .await
+    .map_err(|e| format!("git gc failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_counts_blobs_and_progress() {
+        let counts = parse_count_objects(
+            "count: 12\nsize: 48\nin-pack: 3050\npacks: 2\nsize-pack: 10240\nprune-packable: 1\ngarbage: 0\nsize-garbage: 0\n",
+        );
+        assert_eq!(counts.loose_objects, 12);
+        assert_eq!(counts.loose_bytes, 48 * 1024);
+        assert_eq!(counts.packed_objects, 3050);
+        assert_eq!(counts.packs, 2);
+        assert_eq!(counts.pack_bytes, 10240 * 1024);
+
+        let batch = "commit c1 240\nblob aaa 10\nblob bbb 5000000\ntree t1 90\nblob ccc 700\n";
+        let revs = "c1\nt1 \nccc assets/logo.png\nbbb build/app.bin\nbbb old/app.bin\n";
+        let blobs = largest_blobs(batch, revs, 2);
+        assert_eq!(
+            blobs,
+            [
+                BlobSize { oid: "bbb".into(), bytes: 5_000_000, path: Some("build/app.bin".into()) },
+                BlobSize { oid: "ccc".into(), bytes: 700, path: Some("assets/logo.png".into()) },
+            ]
+        );
+        assert_eq!(largest_blobs(batch, "", 1)[0].path, None);
+
+        let p = parse_gc_progress("Compressing objects:  45% (120/266)").unwrap();
+        assert_eq!((p.phase.as_str(), p.percent), ("Compressing objects", Some(45)));
+        assert_eq!(parse_gc_progress("Enumerating objects: 3062, done.").unwrap().percent, None);
+        assert_eq!(parse_gc_progress("no phase here"), None);
+    }
+}
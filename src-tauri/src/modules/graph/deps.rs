@@ -0,0 +1,579 @@
+//! Module-level import graph for architecture reviews.
+//!
+//! Imports are read with tree-sitter: `import`/`export ... from`, dynamic
+//! `import()` and `require()` in JS/TS (and the `<script>` blocks of
+//! Svelte and Vue files), `import`/`from` in Python, and `use` paths in
+//! Rust. Each one is resolved to a project file the same way dead-file
+//! detection does; imports of packages and the standard library are left
+//! out. Rust `use` paths resolve to the deepest module file they name, and
+//! `mod` declarations are not edges, since a parent declaring a child is
+//! not a dependency.
+//!
+//! Cycles are the graph's strongly connected components. Each is reported
+//! with its modules and one concrete loop through them, and its edges are
+//! flagged so the panel and the DOT export can highlight them.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use tree_sitter::{Node, Parser};
+
+use super::unreferenced::{python_import_candidates, resolve, script_base, script_candidates, Lang};
+use crate::modules::fs::{project_root_async, resolve_within_root, ProjectRootState};
+use crate::modules::search::{project_files, read_text};
+use crate::modules::symbols::language_for_extension;
+
+/// Stop after this many source files; parsing is the slow part.
+const MAX_FILES: usize = 20_000;
+const MAX_FILE_BYTES: u64 = 512 * 1024;
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct GraphModule {
+    /// Path relative to the project root.
+    pub path: String,
+    pub language: String,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ImportEdge {
+    pub from: String,
+    pub to: String,
+    /// Whether both ends are in the same cycle.
+    pub in_cycle: bool,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ImportCycle {
+    /// Every module in the strongly connected component, sorted.
+    pub modules: Vec<String>,
+    /// One loop through the component, starting and ending at its first
+    /// module.
+    pub path: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct ImportGraph {
+    pub modules: Vec<GraphModule>,
+    pub edges: Vec<ImportEdge>,
+    /// Largest first.
+    pub cycles: Vec<ImportCycle>,
+    /// Whether the walk stopped at `MAX_FILES`.
+    pub truncated: bool,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphFormat {
+    Json,
+    Dot,
+}
+
+fn node_text<'a>(node: Node, source: &'a str) -> &'a str {
+    &source[node.byte_range()]
+}
+
+fn string_value<'a>(node: Node, source: &'a str) -> Option<&'a str> {
+    let text = node_text(node, source);
+    match node.kind() {
+        "string" => Some(text.trim_matches(|c| c == '\'' || c == '"')),
+        "template_string" => {
+            let mut cursor = node.walk();
+            let dynamic = node.named_children(&mut cursor).any(|c| c.kind() == "template_substitution");
+            (!dynamic).then(|| text.trim_matches('`'))
+        }
+        _ => None,
+    }
+}
+
+fn parse(source: &str, ext: &str) -> Option<tree_sitter::Tree> {
+    let mut parser = Parser::new();
+    parser.set_language(&language_for_extension(ext)?).ok()?;
+    parser.parse(source, None)
+}
+
+// ── Scripts ──
+
+fn script_specifiers<'a>(node: Node, source: &'a str, out: &mut Vec<&'a str>) {
+    match node.kind() {
+        "import_statement" | "export_statement" => {
+            if let Some(spec) = node.child_by_field_name("source").and_then(|s| string_value(s, source)) {
+                out.push(spec);
+            }
+        }
+        "call_expression" => {
+            let callee = node.child_by_field_name("function").map(|f| node_text(f, source));
+            if matches!(callee, Some("import" | "require")) {
+                let arg = node.child_by_field_name("arguments").and_then(|a| a.named_child(0));
+                if let Some(spec) = arg.and_then(|a| string_value(a, source)) {
+                    out.push(spec);
+                }
+            }
+        }
+        _ => {}
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        script_specifiers(child, source, out);
+    }
+}
+
+/// The contents of a component's `<script>` blocks, blanking the markup so
+/// the scripts parse as one TypeScript file.
+fn component_scripts(source: &str) -> String {
+    let mut out = String::new();
+    let mut rest = source;
+    while let Some(open) = rest.find("<script") {
+        let Some(body) = rest[open..].find('>').map(|i| open + i + 1) else { break };
+        let end = rest[body..].find("</script>").map_or(rest.len(), |i| body + i);
+        out.push_str(&rest[body..end]);
+        out.push('\n');
+        rest = &rest[end..];
+    }
+    out
+}
+
+fn script_imports(file: &Path, source: &str, root: &Path, known: &HashSet<PathBuf>) -> Vec<PathBuf> {
+    let ext = file.extension().and_then(|e| e.to_str()).unwrap_or_default();
+    let (source, ext) = match ext {
+        "svelte" | "vue" => (component_scripts(source), "ts"),
+        "mjs" | "cjs" => (source.to_string(), "js"),
+        "mts" | "cts" => (source.to_string(), "ts"),
+        _ => (source.to_string(), ext),
+    };
+    let Some(tree) = parse(&source, ext) else { return Vec::new() };
+    let mut specs = Vec::new();
+    script_specifiers(tree.root_node(), &source, &mut specs);
+    let dir = file.parent().unwrap_or(root);
+    specs
+        .into_iter()
+        .filter_map(|spec| script_base(spec.split(['?', '#']).next().unwrap_or(spec), dir, root))
+        .filter_map(|base| resolve(script_candidates(&base), known))
+        .collect()
+}
+
+// ── Python ──
+
+fn python_imports(file: &Path, source: &str, root: &Path, known: &HashSet<PathBuf>) -> Vec<PathBuf> {
+    fn walk(node: Node, source: &str, out: &mut Vec<(usize, String, Vec<String>)>) {
+        let names = |field: &str| -> Vec<String> {
+            let mut cursor = node.walk();
+            node.children_by_field_name(field, &mut cursor)
+                .map(|n| n.child_by_field_name("name").unwrap_or(n))
+                .map(|n| node_text(n, source).to_string())
+                .collect()
+        };
+        match node.kind() {
+            "import_statement" => out.extend(names("name").into_iter().map(|m| (0, m, Vec::new()))),
+            "import_from_statement" => {
+                let module = node.child_by_field_name("module_name").map(|m| node_text(m, source)).unwrap_or_default();
+                let dots = module.len() - module.trim_start_matches('.').len();
+                out.push((dots, module.trim_start_matches('.').to_string(), names("name")));
+            }
+            _ => {
+                let mut cursor = node.walk();
+                for child in node.named_children(&mut cursor) {
+                    walk(child, source, out);
+                }
+            }
+        }
+    }
+    let Some(tree) = parse(source, "py") else { return Vec::new() };
+    let mut imports = Vec::new();
+    walk(tree.root_node(), source, &mut imports);
+    imports
+        .iter()
+        .flat_map(|(dots, module, names)| {
+            let names: Vec<&str> = names.iter().map(String::as_str).collect();
+            python_import_candidates(file, root, *dots, module, &names)
+        })
+        .filter_map(|group| resolve(group, known))
+        .collect()
+}
+
+// ── Rust ──
+
+/// Full paths named by a `use` tree, with lists expanded.
+fn use_paths(node: Node, source: &str, prefix: &str, out: &mut Vec<String>) {
+    let join = |path: &str| if prefix.is_empty() { path.to_string() } else { format!("{}::{}", prefix, path) };
+    match node.kind() {
+        "scoped_use_list" => {
+            let path = node.child_by_field_name("path").map_or_else(|| prefix.to_string(), |p| join(node_text(p, source)));
+            if let Some(list) = node.child_by_field_name("list") {
+                use_paths(list, source, &path, out);
+            }
+        }
+        "use_list" => {
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                use_paths(child, source, prefix, out);
+            }
+        }
+        "use_as_clause" => {
+            if let Some(path) = node.child_by_field_name("path") {
+                use_paths(path, source, prefix, out);
+            }
+        }
+        "use_wildcard" => out.push(join(node_text(node, source).trim_end_matches('*').trim_end_matches("::"))),
+        _ => out.push(join(node_text(node, source))),
+    }
+}
+
+/// `use` paths in `node`, each with the inline modules (`mod x { ... }`)
+/// it appears in.
+fn rust_uses(node: Node, source: &str, inline: &mut Vec<String>, out: &mut Vec<(Vec<String>, String)>) {
+    match node.kind() {
+        "use_declaration" => {
+            let mut paths = Vec::new();
+            if let Some(arg) = node.child_by_field_name("argument") {
+                use_paths(arg, source, "", &mut paths);
+            }
+            out.extend(paths.into_iter().map(|p| (inline.clone(), p)));
+        }
+        "mod_item" => {
+            if let (Some(name), Some(body)) = (node.child_by_field_name("name"), node.child_by_field_name("body")) {
+                inline.push(node_text(name, source).to_string());
+                rust_uses(body, source, inline, out);
+                inline.pop();
+            }
+        }
+        _ => {
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                rust_uses(child, source, inline, out);
+            }
+        }
+    }
+}
+
+/// The crate's `src` directory and `file`'s module path inside it.
+fn rust_module(file: &Path, known: &HashSet<PathBuf>) -> Option<(PathBuf, Vec<String>)> {
+    let src = file.ancestors().skip(1).find(|d| known.contains(&d.join("Cargo.toml")))?.join("src");
+    let rel = file.strip_prefix(&src).ok()?;
+    let mut segments: Vec<String> = rel.iter().map(|s| s.to_string_lossy().into_owned()).collect();
+    let last = segments.pop()?;
+    match last.as_str() {
+        "mod.rs" => {}
+        "lib.rs" | "main.rs" if segments.is_empty() => {}
+        name => segments.push(name.strip_suffix(".rs")?.to_string()),
+    }
+    Some((src, segments))
+}
+
+fn rust_imports(file: &Path, source: &str, known: &HashSet<PathBuf>) -> Vec<PathBuf> {
+    let Some((src, module)) = rust_module(file, known) else { return Vec::new() };
+    let Some(tree) = parse(source, "rs") else { return Vec::new() };
+    let mut uses = Vec::new();
+    rust_uses(tree.root_node(), source, &mut Vec::new(), &mut uses);
+    let crate_root = resolve([src.join("lib.rs"), src.join("main.rs")], known);
+    uses.into_iter()
+        .filter_map(|(inline, path)| {
+            let mut parts = path.trim_start_matches("::").split("::");
+            let mut segments = match parts.next()? {
+                "crate" => Vec::new(),
+                "self" => module.iter().chain(&inline).cloned().collect(),
+                "super" => {
+                    let mut segments: Vec<String> = module.iter().chain(&inline).cloned().collect();
+                    segments.pop();
+                    segments
+                }
+                _ => return None,
+            };
+            for part in parts {
+                match part {
+                    "super" => {
+                        segments.pop();
+                    }
+                    "self" => {}
+                    part => segments.push(part.to_string()),
+                }
+            }
+            (1..=segments.len())
+                .rev()
+                .find_map(|k| {
+                    let base = src.join(segments[..k].join("/"));
+                    resolve([base.with_extension("rs"), base.join("mod.rs")], known)
+                })
+                .or_else(|| crate_root.clone())
+        })
+        .collect()
+}
+
+// ── Cycles ──
+
+/// Strongly connected components with more than one member (Tarjan's
+/// algorithm, iterative so deep chains can't overflow the stack).
+fn components(adjacency: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let n = adjacency.len();
+    let mut index = vec![usize::MAX; n];
+    let mut low = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = Vec::new();
+    let mut found = Vec::new();
+    let mut next = 0;
+    for start in 0..n {
+        if index[start] != usize::MAX {
+            continue;
+        }
+        let mut work = vec![(start, 0)];
+        while let Some(&mut (v, ref mut edge)) = work.last_mut() {
+            if *edge == 0 {
+                index[v] = next;
+                low[v] = next;
+                next += 1;
+                stack.push(v);
+                on_stack[v] = true;
+            }
+            if let Some(&w) = adjacency[v].get(*edge) {
+                *edge += 1;
+                if index[w] == usize::MAX {
+                    work.push((w, 0));
+                } else if on_stack[w] {
+                    low[v] = low[v].min(index[w]);
+                }
+                continue;
+            }
+            work.pop();
+            if let Some(&(parent, _)) = work.last() {
+                low[parent] = low[parent].min(low[v]);
+            }
+            if low[v] == index[v] {
+                let mut component = Vec::new();
+                while let Some(w) = stack.pop() {
+                    on_stack[w] = false;
+                    component.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                if component.len() > 1 {
+                    found.push(component);
+                }
+            }
+        }
+    }
+    found
+}
+
+/// The shortest loop from `start` back to itself inside `members`.
+fn shortest_loop(adjacency: &[Vec<usize>], members: &HashSet<usize>, start: usize) -> Vec<usize> {
+    let mut previous: HashMap<usize, usize> = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+    while let Some(v) = queue.pop_front() {
+        for &w in &adjacency[v] {
+            if !members.contains(&w) {
+                continue;
+            }
+            if w == start {
+                let mut path = vec![start, v];
+                let mut at = v;
+                while at != start {
+                    at = previous[&at];
+                    path.push(at);
+                }
+                path.reverse();
+                return path;
+            }
+            if let std::collections::hash_map::Entry::Vacant(e) = previous.entry(w) {
+                e.insert(v);
+                queue.push_back(w);
+            }
+        }
+    }
+    Vec::new()
+}
+
+// ── Graph ──
+
+pub(crate) fn build(project: &Path, dir: &Path) -> ImportGraph {
+    let mut known = HashSet::new();
+    let mut sources = Vec::new();
+    let mut truncated = false;
+    for path in project_files(project, dir) {
+        if let Some(lang) = Lang::of(&path) {
+            if sources.len() == MAX_FILES {
+                truncated = true;
+                break;
+            }
+            sources.push((path.clone(), lang));
+        }
+        known.insert(path);
+    }
+    sources.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let rel = |p: &Path| p.strip_prefix(project).unwrap_or(p).to_string_lossy().replace('\\', "/");
+    let position: HashMap<&Path, usize> = sources.iter().enumerate().map(|(i, (p, _))| (p.as_path(), i)).collect();
+    let mut adjacency: Vec<Vec<usize>> = vec![Vec::new(); sources.len()];
+    for (i, (path, lang)) in sources.iter().enumerate() {
+        if std::fs::metadata(path).map_or(true, |m| m.len() > MAX_FILE_BYTES) {
+            continue;
+        }
+        let Some(source) = read_text(path) else { continue };
+        let targets = match lang {
+            Lang::Script => script_imports(path, &source, project, &known),
+            Lang::Python => python_imports(path, &source, project, &known),
+            Lang::Rust => rust_imports(path, &source, &known),
+        };
+        let edges: BTreeSet<usize> = targets.iter().filter_map(|t| position.get(t.as_path()).copied()).filter(|&j| j != i).collect();
+        adjacency[i] = edges.into_iter().collect();
+    }
+
+    let mut component_of = vec![usize::MAX; sources.len()];
+    let mut cycles = Vec::new();
+    for (c, mut members) in components(&adjacency).into_iter().enumerate() {
+        members.sort_unstable();
+        for &m in &members {
+            component_of[m] = c;
+        }
+        let path = shortest_loop(&adjacency, &members.iter().copied().collect(), members[0]);
+        cycles.push(ImportCycle {
+            modules: members.iter().map(|&m| rel(&sources[m].0)).collect(),
+            path: path.iter().map(|&m| rel(&sources[m].0)).collect(),
+        });
+    }
+    cycles.sort_by(|a, b| b.modules.len().cmp(&a.modules.len()).then_with(|| a.modules.cmp(&b.modules)));
+
+    let edges = adjacency
+        .iter()
+        .enumerate()
+        .flat_map(|(i, targets)| targets.iter().map(move |&j| (i, j)))
+        .map(|(i, j)| ImportEdge {
+            from: rel(&sources[i].0),
+            to: rel(&sources[j].0),
+            in_cycle: component_of[i] != usize::MAX && component_of[i] == component_of[j],
+        })
+        .collect();
+    let modules = sources.iter().map(|(p, lang)| GraphModule { path: rel(p), language: lang.name(p).to_string() }).collect();
+    ImportGraph { modules, edges, cycles, truncated }
+}
+
+fn dot_quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Graphviz source for `graph`, with cycle edges in red.
+pub(crate) fn to_dot(graph: &ImportGraph) -> String {
+    let mut out = String::from("digraph imports {\n    rankdir=LR;\n    node [shape=box, fontname=\"Helvetica\"];\n");
+    for module in &graph.modules {
+        out.push_str(&format!("    {};\n", dot_quote(&module.path)));
+    }
+    for edge in &graph.edges {
+        let style = if edge.in_cycle { " [color=red]" } else { "" };
+        out.push_str(&format!("    {} -> {}{};\n", dot_quote(&edge.from), dot_quote(&edge.to), style));
+    }
+    out.push_str("}\n");
+    out
+}
+
+async fn graph_for(window: &tauri::WebviewWindow, state: &ProjectRootState, root: Option<String>) -> Result<ImportGraph, String> {
+    let project = project_root_async(window.label(), state).await?;
+    let dir = match root {
+        Some(root) => resolve_within_root(&root, &project)?,
+        None => project.clone(),
+    };
+    tokio::task::spawn_blocking(move || build(&project, &dir))
+        .await
+        .map_err(|e| format!("Building the import graph failed: {}", e))
+}
+
+/// Import edges between the project's source files, or those under
+/// `root` when given, with any dependency cycles.
+#[tauri::command]
+pub async fn build_import_graph(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    root: Option<String>,
+) -> Result<ImportGraph, String> {
+    graph_for(&window, &state, root).await
+}
+
+/// The import graph as Graphviz DOT or pretty-printed JSON, for saving or
+/// pasting into other tools.
+#[tauri::command]
+pub async fn export_import_graph(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    root: Option<String>,
+    format: GraphFormat,
+) -> Result<String, String> {
+    let graph = graph_for(&window, &state, root).await?;
+    match format {
+        GraphFormat::Json => serde_json::to_string_pretty(&graph).map_err(|e| e.to_string()),
+        GraphFormat::Dot => Ok(to_dot(&graph)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, rel: &str, content: &str) {
+        let path = root.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn edges(graph: &ImportGraph) -> Vec<(&str, &str, bool)> {
+        graph.edges.iter().map(|e| (e.from.as_str(), e.to.as_str(), e.in_cycle)).collect()
+    }
+
+    #[test]
+    fn finds_script_edges_and_cycles() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "src/a.ts", "import { b } from './b';\nimport React from 'react';\nexport * from './util/index.js';\n");
+        write(root, "src/b.ts", "export const b = () => import('./c');\n");
+        write(root, "src/c.ts", "const a = require('./a');\n");
+        write(root, "src/util/index.ts", "export {};\n");
+        write(root, "src/App.svelte", "<script lang=\"ts\">\n  import { b } from './b';\n</script>\n<p>{b}</p>\n");
+
+        let graph = build(root, root);
+        assert_eq!(
+            edges(&graph),
+            [
+                ("src/App.svelte", "src/b.ts", false),
+                ("src/a.ts", "src/b.ts", true),
+                ("src/a.ts", "src/util/index.ts", false),
+                ("src/b.ts", "src/c.ts", true),
+                ("src/c.ts", "src/a.ts", true),
+            ]
+        );
+        assert_eq!(graph.cycles.len(), 1);
+        assert_eq!(graph.cycles[0].path, ["src/a.ts", "src/b.ts", "src/c.ts", "src/a.ts"]);
+
+        let dot = to_dot(&graph);
+        assert!(dot.contains("    \"src/c.ts\" -> \"src/a.ts\" [color=red];\n"));
+        assert!(dot.contains("    \"src/a.ts\" -> \"src/util/index.ts\";\n"));
+    }
+
+    #[test]
+    fn resolves_rust_uses_and_python_imports() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "Cargo.toml", "[package]\nname = \"app\"\n");
+        write(root, "src/main.rs", "mod net;\nmod util;\nuse crate::net::http::{get, Client as C};\nfn main() {}\n");
+        write(root, "src/net/mod.rs", "pub mod http;\nuse super::util::*;\n");
+        write(root, "src/net/http.rs", "use super::{self, Pool};\nuse std::io;\n#[cfg(test)]\nmod tests {\n    use super::*;\n}\n");
+        write(root, "src/util.rs", "use crate::Config;\n");
+
+        write(root, "app/__init__.py", "");
+        write(root, "app/core.py", "from . import models\nimport app.views as v\n");
+        write(root, "app/models.py", "def lazy():\n    from .core import thing\n");
+        write(root, "app/views.py", "import os\n");
+
+        let graph = build(root, root);
+        assert_eq!(
+            edges(&graph),
+            [
+                ("app/core.py", "app/models.py", true),
+                ("app/core.py", "app/views.py", false),
+                ("app/models.py", "app/core.py", true),
+                ("src/main.rs", "src/net/http.rs", true),
+                ("src/net/http.rs", "src/net/mod.rs", true),
+                ("src/net/mod.rs", "src/util.rs", true),
+                ("src/util.rs", "src/main.rs", true),
+            ]
+        );
+        let cycles: Vec<Vec<String>> = graph.cycles.iter().map(|c| c.modules.clone()).collect();
+        assert_eq!(cycles, [vec!["src/main.rs", "src/net/http.rs", "src/net/mod.rs", "src/util.rs"], vec!["app/core.py", "app/models.py"]]);
+    }
+}
@@ -2,6 +2,9 @@ use serde::Serialize;
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
+pub mod deps;
+pub mod unreferenced;
+
 // ── Types ──
 
 #[derive(Serialize, Clone)]
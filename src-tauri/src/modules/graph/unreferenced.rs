@@ -0,0 +1,605 @@
+//! Dead-file detection: source files nothing imports.
+//!
+//! Every source file in the project is scanned for imports the same
+//! line-based way as the file graph panel, and each import is resolved to
+//! a project file. A file is in use when something imports it or when it
+//! is an entry point: a crate root or Cargo target, a `package.json`
+//! `main`/`bin`/`exports` target, a script referenced from HTML, a
+//! framework route, config or test file, or a Python `__main__`. Generated
+//! files and files matching the caller's exclude patterns are never
+//! reported.
+//!
+//! Rust modules are resolved through `mod` declarations, so a reported
+//! Rust file is not compiled at all. Scripts and Python modules can also
+//! be loaded by computed paths the scan can't follow, so those whose file
+//! name appears elsewhere in the project are reported with low confidence.
+
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::normalize_path;
+use crate::modules::fs::{exclude::Excludes, generated, project_root_async, ProjectRootState};
+use crate::modules::search::{project_files, read_text};
+
+/// Stop after this many files so a huge tree can't stall the scan.
+const MAX_FILES: usize = 100_000;
+
+const SCRIPT_EXTENSIONS: &[&str] = &["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs", "svelte", "vue"];
+
+/// Names too generic to count as a mention of a file elsewhere.
+const GENERIC_STEMS: &[&str] = &["index", "main", "mod", "lib", "utils", "types", "__init__"];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum Lang {
+    Script,
+    Rust,
+    Python,
+}
+
+impl Lang {
+    pub(super) fn of(path: &Path) -> Option<Lang> {
+        match path.extension()?.to_str()? {
+            "rs" => Some(Lang::Rust),
+            "py" => Some(Lang::Python),
+            ext if SCRIPT_EXTENSIONS.contains(&ext) => Some(Lang::Script),
+            _ => None,
+        }
+    }
+
+    pub(super) fn name(self, path: &Path) -> &'static str {
+        match (self, path.extension().and_then(|e| e.to_str())) {
+            (Lang::Rust, _) => "rust",
+            (Lang::Python, _) => "python",
+            (Lang::Script, Some("ts" | "tsx" | "mts" | "cts")) => "typescript",
+            (Lang::Script, Some("svelte")) => "svelte",
+            (Lang::Script, Some("vue")) => "vue",
+            (Lang::Script, _) => "javascript",
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    /// Nothing refers to the file at all.
+    High,
+    /// Not imported, but its name appears in another file, so it may be
+    /// loaded by a computed path.
+    Low,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct UnreferencedFile {
+    /// Path relative to the project root.
+    pub path: String,
+    pub language: String,
+    pub size: u64,
+    pub confidence: Confidence,
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct UnreferencedReport {
+    /// Sorted by path.
+    pub files: Vec<UnreferencedFile>,
+    pub files_scanned: usize,
+    pub entry_points: usize,
+    /// Whether the walk stopped at `MAX_FILES`.
+    pub truncated: bool,
+}
+
+/// Everything the scan found, before deciding what is unreferenced.
+#[derive(Default)]
+struct Scan {
+    sources: Vec<(PathBuf, Lang)>,
+    known: HashSet<PathBuf>,
+    used: HashSet<PathBuf>,
+    entries: HashSet<PathBuf>,
+    /// Directories named by glob and template imports; files under them
+    /// count as imported.
+    glob_dirs: Vec<PathBuf>,
+}
+
+impl Scan {
+    fn mark(&mut self, candidates: impl IntoIterator<Item = PathBuf>) {
+        if let Some(hit) = resolve(candidates, &self.known) {
+            self.used.insert(hit);
+        }
+    }
+}
+
+/// The first of `candidates` that is a known file.
+pub(super) fn resolve(candidates: impl IntoIterator<Item = PathBuf>, known: &HashSet<PathBuf>) -> Option<PathBuf> {
+    candidates.into_iter().map(|p| normalize_path(&p)).find(|p| known.contains(p))
+}
+
+/// Quoted strings on `line` that follow one of `prefixes` (ignoring
+/// whitespace), e.g. the `'./a'` in `} from './a'`.
+fn quoted_after<'a>(line: &'a str, prefixes: &[&str]) -> Vec<&'a str> {
+    let mut found = Vec::new();
+    let mut rest = 0;
+    while let Some(at) = line[rest..].find(['\'', '"', '`']).map(|i| i + rest) {
+        let quote = line.as_bytes()[at] as char;
+        let Some(len) = line[at + 1..].find(quote) else { break };
+        let before = line[..at].trim_end();
+        if prefixes.iter().any(|p| before.ends_with(p)) {
+            found.push(&line[at + 1..at + 1 + len]);
+        }
+        rest = at + len + 2;
+    }
+    found
+}
+
+// ── Scripts ──
+
+const SCRIPT_IMPORT_PREFIXES: &[&str] = &["from", "import", "import(", "require(", "glob(", "URL(", "Worker(", "src="];
+
+/// Where a script specifier could point, before extensions are tried.
+pub(super) fn script_base(spec: &str, file_dir: &Path, root: &Path) -> Option<PathBuf> {
+    if spec.starts_with('.') {
+        Some(file_dir.join(spec))
+    } else if let Some(rest) = spec.strip_prefix("$lib/") {
+        Some(root.join("src/lib").join(rest))
+    } else if let Some(rest) = spec.strip_prefix("@/").or_else(|| spec.strip_prefix("~/")) {
+        Some(root.join("src").join(rest))
+    } else {
+        // Root-relative, as in a Vite `index.html`.
+        spec.strip_prefix('/').map(|rest| file_dir.join(rest))
+    }
+}
+
+pub(super) fn script_candidates(base: &Path) -> Vec<PathBuf> {
+    let mut candidates = vec![base.to_path_buf()];
+    let text = base.to_string_lossy();
+    for ext in SCRIPT_EXTENSIONS {
+        candidates.push(PathBuf::from(format!("{}.{}", text, ext)));
+    }
+    for ext in SCRIPT_EXTENSIONS {
+        candidates.push(base.join(format!("index.{}", ext)));
+    }
+    // TypeScript ESM imports name the compiled `.js` file.
+    if let Some(stem) = text.strip_suffix(".js").or_else(|| text.strip_suffix(".jsx")).or_else(|| text.strip_suffix(".mjs")) {
+        for ext in ["ts", "tsx", "mts"] {
+            candidates.push(PathBuf::from(format!("{}.{}", stem, ext)));
+        }
+    }
+    candidates
+}
+
+/// The directory part of a specifier with a wildcard or template
+/// placeholder, as in `import.meta.glob('./pages/*.svelte')` or
+/// `` import(`./locales/${lang}.ts`) ``.
+fn pattern_dir(spec: &str) -> Option<&str> {
+    let wildcard = spec.find('*').into_iter().chain(spec.find("${")).min()?;
+    Some(spec[..wildcard].rfind('/').map_or("", |slash| &spec[..=slash]))
+}
+
+fn scan_script(scan: &mut Scan, file: &Path, content: &str, root: &Path) {
+    let dir = file.parent().unwrap_or(root);
+    for line in content.lines() {
+        for spec in quoted_after(line, SCRIPT_IMPORT_PREFIXES) {
+            let spec = spec.split(['?', '#']).next().unwrap_or(spec);
+            match pattern_dir(spec) {
+                Some(prefix) => {
+                    if let Some(base) = script_base(prefix, dir, root) {
+                        scan.glob_dirs.push(normalize_path(&base));
+                    }
+                }
+                None => {
+                    if let Some(base) = script_base(spec, dir, root) {
+                        scan.mark(script_candidates(&base));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Values of the `package.json` fields that name entry files.
+fn package_entries(content: &str) -> Vec<String> {
+    fn strings(value: &serde_json::Value, out: &mut Vec<String>) {
+        match value {
+            serde_json::Value::String(s) => out.push(s.clone()),
+            serde_json::Value::Array(items) => items.iter().for_each(|v| strings(v, out)),
+            serde_json::Value::Object(map) => map.values().for_each(|v| strings(v, out)),
+            _ => {}
+        }
+    }
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(content) else { return Vec::new() };
+    let mut out = Vec::new();
+    for field in ["main", "module", "browser", "types", "typings", "bin", "exports", "svelte"] {
+        if let Some(value) = json.get(field) {
+            strings(value, &mut out);
+        }
+    }
+    out
+}
+
+fn is_script_entry(rel: &str) -> bool {
+    let name = rel.rsplit('/').next().unwrap_or(rel);
+    let stem = name.split('.').next().unwrap_or(name);
+    let dirs: Vec<&str> = rel.split('/').rev().skip(1).collect();
+    let top_level = dirs.is_empty() || dirs == ["src"];
+    name.starts_with('.')
+        || name.starts_with('+')
+        || [".config.", ".test.", ".spec.", ".stories.", ".worker.", ".d."].iter().any(|m| name.contains(m))
+        || dirs.iter().any(|d| matches!(*d, "__tests__" | "tests" | "test" | "e2e" | "pages" | "routes" | "scripts" | "bin"))
+        || matches!(stem, "hooks" | "service-worker" | "sw" | "middleware" | "instrumentation")
+        || (top_level && matches!(stem, "main" | "index" | "app" | "server" | "cli"))
+        || (dirs.first() == Some(&"app") && matches!(stem, "page" | "layout" | "route" | "loading" | "error" | "not-found" | "template"))
+}
+
+// ── Rust ──
+
+/// The directory `mod` declarations in `file` resolve against.
+fn rust_module_dir(file: &Path) -> PathBuf {
+    let dir = file.parent().unwrap_or(Path::new("")).to_path_buf();
+    match file.file_stem().and_then(|s| s.to_str()) {
+        Some("mod" | "lib" | "main") | None => dir,
+        Some(_) if dir.ends_with("src/bin") || dir.ends_with("examples") || dir.ends_with("tests") || dir.ends_with("benches") => dir,
+        Some(stem) => dir.join(stem),
+    }
+}
+
+fn scan_rust(scan: &mut Scan, file: &Path, content: &str) {
+    let dir = file.parent().unwrap_or(Path::new(""));
+    let module_dir = rust_module_dir(file);
+    let mut path_attr: Option<&str> = None;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("#[path") {
+            path_attr = quoted_after(trimmed, &["="]).first().copied();
+            continue;
+        }
+        for included in quoted_after(trimmed, &["include!(", "include_str!("]) {
+            scan.mark([dir.join(included)]);
+        }
+        let declared = trimmed
+            .strip_prefix("pub ")
+            .or_else(|| trimmed.split_once(") ").filter(|(vis, _)| vis.starts_with("pub(")).map(|(_, rest)| rest))
+            .unwrap_or(trimmed)
+            .strip_prefix("mod ")
+            .and_then(|rest| rest.strip_suffix(';'));
+        if let Some(name) = declared {
+            let name = name.trim().trim_start_matches("r#");
+            match path_attr.take() {
+                Some(path) => scan.mark([dir.join(path)]),
+                None => scan.mark([module_dir.join(format!("{}.rs", name)), module_dir.join(name).join("mod.rs")]),
+            }
+        } else if !trimmed.starts_with("#[") && !trimmed.is_empty() {
+            path_attr = None;
+        }
+    }
+}
+
+/// Crate roots and Cargo target files, relative to the crate directory.
+fn is_rust_target(rel: &str) -> bool {
+    let parts: Vec<&str> = rel.split('/').collect();
+    matches!(
+        parts.as_slice(),
+        ["src", "main.rs" | "lib.rs"]
+            | ["build.rs"]
+            | ["examples" | "tests" | "benches", _]
+            | ["src", "bin", _]
+            | ["examples" | "tests" | "benches", _, "main.rs"]
+            | ["src", "bin", _, "main.rs"]
+    )
+}
+
+/// `path = "..."` targets in a `Cargo.toml`.
+fn cargo_paths(content: &str) -> Vec<&str> {
+    content
+        .lines()
+        .filter(|l| l.trim_start().starts_with("path"))
+        .flat_map(|l| quoted_after(l, &["="]))
+        .filter(|p| p.ends_with(".rs"))
+        .collect()
+}
+
+// ── Python ──
+
+fn python_candidates(base: &Path) -> [PathBuf; 2] {
+    [base.with_extension("py"), base.join("__init__.py")]
+}
+
+/// Candidate groups for a Python import in `file`: `module` is the dotted
+/// name without its leading `dots`, and `names` are what a `from` import
+/// brings in, which may be submodules. Absolute imports are tried against
+/// the project root, `src/` and the importing file's directory.
+pub(super) fn python_import_candidates(file: &Path, root: &Path, dots: usize, module: &str, names: &[&str]) -> Vec<[PathBuf; 2]> {
+    let dir = file.parent().unwrap_or(root);
+    let module = module.replace('.', "/");
+    let bases: Vec<PathBuf> = if dots > 0 {
+        let mut base = dir.to_path_buf();
+        for _ in 1..dots {
+            base.pop();
+        }
+        vec![base.join(&module)]
+    } else {
+        [root.to_path_buf(), root.join("src"), dir.to_path_buf()].iter().map(|r| r.join(&module)).collect()
+    };
+    let mut groups = Vec::new();
+    for base in bases {
+        if !module.is_empty() {
+            groups.push(python_candidates(&base));
+        }
+        // `from pkg import submodule`
+        groups.extend(names.iter().map(|name| python_candidates(&base.join(name))));
+    }
+    groups
+}
+
+fn scan_python(scan: &mut Scan, file: &Path, content: &str, root: &Path) {
+    for line in content.lines() {
+        let trimmed = line.trim();
+        let groups = if let Some(rest) = trimmed.strip_prefix("import ") {
+            rest.split(',')
+                .flat_map(|m| python_import_candidates(file, root, 0, m.split_whitespace().next().unwrap_or_default(), &[]))
+                .collect()
+        } else if let Some((module, names)) = trimmed.strip_prefix("from ").and_then(|r| r.split_once(" import ")) {
+            let dots = module.len() - module.trim_start_matches('.').len();
+            let names: Vec<&str> = names
+                .trim_matches(|c| c == '(' || c == ')')
+                .split(',')
+                .filter_map(|n| n.split_whitespace().next())
+                .collect();
+            python_import_candidates(file, root, dots, module.trim_start_matches('.'), &names)
+        } else {
+            continue;
+        };
+        for group in groups {
+            scan.mark(group);
+        }
+    }
+}
+
+fn is_python_entry(rel: &str, content: &str) -> bool {
+    let name = rel.rsplit('/').next().unwrap_or(rel);
+    matches!(name, "__init__.py" | "__main__.py" | "setup.py" | "conftest.py" | "manage.py" | "wsgi.py" | "asgi.py")
+        || name.starts_with("test_")
+        || name.ends_with("_test.py")
+        || rel.split('/').any(|d| d == "tests")
+        || content.contains("__name__ == \"__main__\"")
+        || content.contains("__name__ == '__main__'")
+}
+
+/// Modules named by `pyproject.toml` scripts and entry points
+/// (`"pkg.cli:main"`).
+fn pyproject_modules(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .flat_map(|l| quoted_after(l, &["="]))
+        .filter_map(|v| v.split_once(':').map(|(module, _)| module))
+        .filter(|m| !m.is_empty() && m.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '.'))
+        .map(|m| m.replace('.', "/"))
+        .collect()
+}
+
+// ── Scan ──
+
+fn collect(root: &Path, excludes: &Excludes) -> (Scan, bool) {
+    let mut scan = Scan::default();
+    let mut manifests = Vec::new();
+    let mut markup = Vec::new();
+    let mut truncated = false;
+    for (i, path) in project_files(root, root).enumerate() {
+        if i == MAX_FILES {
+            truncated = true;
+            break;
+        }
+        match path.file_name().and_then(|n| n.to_str()) {
+            Some("package.json" | "Cargo.toml" | "pyproject.toml") => manifests.push(path.clone()),
+            Some(name) if name.ends_with(".html") => markup.push(path.clone()),
+            _ => {}
+        }
+        if let Some(lang) = Lang::of(&path) {
+            if !excludes.is_excluded(&path, false) {
+                scan.sources.push((path.clone(), lang));
+            }
+        }
+        scan.known.insert(path);
+    }
+
+    let crate_dirs: Vec<PathBuf> = manifests
+        .iter()
+        .filter(|m| m.ends_with("Cargo.toml"))
+        .filter_map(|m| m.parent().map(Path::to_path_buf))
+        .collect();
+    for manifest in &manifests {
+        let Some(content) = read_text(manifest) else { continue };
+        let dir = manifest.parent().unwrap_or(root);
+        let targets: Vec<PathBuf> = match manifest.file_name().and_then(|n| n.to_str()) {
+            Some("package.json") => package_entries(&content)
+                .iter()
+                .flat_map(|e| script_candidates(&dir.join(e)))
+                .collect(),
+            Some("Cargo.toml") => cargo_paths(&content).iter().map(|p| dir.join(p)).collect(),
+            _ => pyproject_modules(&content)
+                .iter()
+                .flat_map(|m| [dir.join(m), dir.join("src").join(m)])
+                .flat_map(|base| python_candidates(&base))
+                .collect(),
+        };
+        scan.entries.extend(targets.iter().map(|p| normalize_path(p)).filter(|p| scan.known.contains(p)));
+    }
+    for page in &markup {
+        if let Some(content) = read_text(page) {
+            scan_script(&mut scan, page, &content, root);
+        }
+    }
+
+    let sources = std::mem::take(&mut scan.sources);
+    for (path, lang) in &sources {
+        let Some(content) = read_text(path) else { continue };
+        let rel = path.strip_prefix(root).unwrap_or(path).to_string_lossy().replace('\\', "/");
+        let entry = match lang {
+            Lang::Script => is_script_entry(&rel),
+            Lang::Python => is_python_entry(&rel, &content),
+            Lang::Rust => crate_dirs
+                .iter()
+                .filter_map(|d| path.strip_prefix(d).ok())
+                .any(|r| is_rust_target(&r.to_string_lossy().replace('\\', "/"))),
+        };
+        if entry || generated::is_generated(path, &content).is_some() {
+            scan.entries.insert(path.clone());
+        }
+        match lang {
+            Lang::Script => scan_script(&mut scan, path, &content, root),
+            Lang::Rust => scan_rust(&mut scan, path, &content),
+            Lang::Python => scan_python(&mut scan, path, &content, root),
+        }
+    }
+    scan.sources = sources;
+    (scan, truncated)
+}
+
+/// Mark files whose name appears in another source file as low confidence.
+fn lower_mentioned(files: &mut [UnreferencedFile], scan: &Scan, root: &Path) {
+    let mut by_stem: HashMap<String, Vec<usize>> = HashMap::new();
+    for (i, file) in files.iter().enumerate() {
+        if file.language == "rust" {
+            continue;
+        }
+        let name = file.path.rsplit('/').next().unwrap_or(&file.path);
+        let stem = name.split('.').next().unwrap_or(name);
+        if stem.len() >= 3 && !GENERIC_STEMS.contains(&stem) {
+            by_stem.entry(stem.to_string()).or_default().push(i);
+        }
+    }
+    if by_stem.is_empty() {
+        return;
+    }
+    let alternation: Vec<String> = by_stem.keys().map(|s| regex::escape(s)).collect();
+    let Ok(re) = Regex::new(&format!(r"\b(?:{})\b", alternation.join("|"))) else { return };
+    for (path, _) in &scan.sources {
+        let Some(content) = read_text(path) else { continue };
+        let rel = path.strip_prefix(root).unwrap_or(path).to_string_lossy().replace('\\', "/");
+        for m in re.find_iter(&content) {
+            for &i in by_stem.get(m.as_str()).into_iter().flatten() {
+                if files[i].path != rel {
+                    files[i].confidence = Confidence::Low;
+                }
+            }
+        }
+    }
+}
+
+/// Source files under `root` that nothing imports and that aren't entry
+/// points. Files matching `excludes` are skipped but their imports still
+/// count.
+pub(crate) fn find_unreferenced(root: &Path, excludes: &Excludes) -> UnreferencedReport {
+    let (scan, truncated) = collect(root, excludes);
+    let mut files: Vec<UnreferencedFile> = scan
+        .sources
+        .iter()
+        .filter(|(path, _)| !scan.used.contains(path) && !scan.entries.contains(path))
+        .filter(|(path, _)| !scan.glob_dirs.iter().any(|d| path.starts_with(d)))
+        .map(|(path, lang)| UnreferencedFile {
+            path: path.strip_prefix(root).unwrap_or(path).to_string_lossy().replace('\\', "/"),
+            language: lang.name(path).to_string(),
+            size: std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+            confidence: Confidence::High,
+        })
+        .collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    lower_mentioned(&mut files, &scan, root);
+    UnreferencedReport {
+        files,
+        files_scanned: scan.sources.len(),
+        entry_points: scan.entries.len(),
+        truncated,
+    }
+}
+
+/// Source files in the open project that nothing imports. `exclude` takes
+/// gitignore-style patterns for files that should never be reported, such
+/// as plugin directories loaded at runtime.
+#[tauri::command]
+pub async fn find_unreferenced_files(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    exclude: Option<Vec<String>>,
+) -> Result<UnreferencedReport, String> {
+    let root = project_root_async(window.label(), &state).await?;
+    let excludes = Excludes::new(&root, &exclude.unwrap_or_default())?;
+    tokio::task::spawn_blocking(move || find_unreferenced(&root, &excludes))
+        .await
+        .map_err(|e| format!("Dead file scan failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, rel: &str, content: &str) {
+        let path = root.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn unreferenced(root: &Path, exclude: &[&str]) -> Vec<(String, Confidence)> {
+        let patterns: Vec<String> = exclude.iter().map(|s| s.to_string()).collect();
+        let report = find_unreferenced(root, &Excludes::new(root, &patterns).unwrap());
+        report.files.into_iter().map(|f| (f.path, f.confidence)).collect()
+    }
+
+    #[test]
+    fn follows_script_imports_and_entry_points() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "package.json", r#"{"name": "app", "bin": {"app": "./cli/run.js"}}"#);
+        write(root, "index.html", r#"<script type="module" src="/src/main.ts"></script>"#);
+        write(root, "src/main.ts", "import App from './App.svelte';\nimport { fmt } from '$lib/format';\n");
+        write(root, "src/App.svelte", "<script>\n  import {\n    helper,\n  } from './util/helper.js';\n</script>\n");
+        write(root, "src/util/helper.ts", "export const helper = 1;\n");
+        write(root, "src/lib/format.ts", "export const fmt = () => import('./lazy');\n");
+        write(root, "src/lib/lazy.ts", "export {};\n");
+        write(root, "cli/run.js", "console.log('hi');\n");
+        write(root, "src/routes/+page.svelte", "<h1>home</h1>\n");
+        write(root, "src/old/legacy.ts", "export const legacy = 1;\n");
+        write(root, "src/locales/de.ts", "export default {};\n");
+        write(root, "src/i18n.ts", "export const load = (l) => import(`./locales/${l}.ts`);\n");
+        write(root, "src/widgets/chart.ts", "export {};\n");
+        write(root, "src/registry.ts", "export const names = ['chart'];\n");
+        write(root, "plugins/extra.ts", "export {};\n");
+
+        assert_eq!(
+            unreferenced(root, &["plugins/"]),
+            [
+                ("src/i18n.ts".to_string(), Confidence::High),
+                ("src/old/legacy.ts".to_string(), Confidence::High),
+                ("src/registry.ts".to_string(), Confidence::High),
+                ("src/widgets/chart.ts".to_string(), Confidence::Low),
+            ]
+        );
+    }
+
+    #[test]
+    fn follows_rust_mods_and_python_imports() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "Cargo.toml", "[package]\nname = \"app\"\n\n[[bin]]\nname = \"tool\"\npath = \"tools/tool.rs\"\n");
+        write(root, "src/main.rs", "mod net;\npub(crate) mod util;\n#[path = \"gen/out.rs\"]\nmod out;\n");
+        write(root, "src/net/mod.rs", "pub mod http;\n");
+        write(root, "src/net/http.rs", "");
+        write(root, "src/util.rs", "mod inner;\n");
+        write(root, "src/util/inner.rs", "");
+        write(root, "src/gen/out.rs", "");
+        write(root, "src/orphan.rs", "");
+        write(root, "tools/tool.rs", "fn main() {}\n");
+        write(root, "tests/smoke.rs", "");
+
+        write(root, "app/__main__.py", "from app.core import engine\nfrom . import helpers\n");
+        write(root, "app/__init__.py", "");
+        write(root, "app/core/__init__.py", "from .models import User\n");
+        write(root, "app/core/engine.py", "");
+        write(root, "app/core/models.py", "");
+        write(root, "app/helpers.py", "");
+        write(root, "app/unused.py", "");
+        write(root, "scripts/run.py", "if __name__ == \"__main__\":\n    pass\n");
+
+        let found: Vec<String> = unreferenced(root, &[]).into_iter().map(|(path, _)| path).collect();
+        assert_eq!(found, ["app/unused.py", "src/orphan.rs"]);
+    }
+}
@@ -0,0 +1,36 @@
+error-other = { $message }
+
+## Project and path validation
+
+no-project-open = Es ist kein Projekt geöffnet
+path-invalid = Ungültiger Pfad: { $reason }
+path-traversal = Ungültiger Pfad: Verzeichniswechsel nach oben ist nicht erlaubt
+path-outside-project = Zugriff verweigert: Der Pfad liegt außerhalb des Projektordners
+
+## File tree operations
+
+file-exists = Die Datei existiert bereits
+folder-exists = Der Ordner existiert bereits
+path-not-found = Der Pfad existiert nicht
+no-parent-dir = Kein übergeordneter Ordner
+dest-not-dir = Das Ziel ist kein Ordner
+source-invalid = Ungültige Quelle: { $path }
+move-into-itself = '{ $path }' kann nicht in sich selbst oder einen Unterordner verschoben werden
+target-exists = '{ $name }' existiert im Ziel bereits
+create-failed = '{ $path }' konnte nicht erstellt werden: { $reason }
+trash-failed = Verschieben in den Papierkorb fehlgeschlagen: { $reason }
+rename-failed = Umbenennen fehlgeschlagen: { $reason }
+move-failed = '{ $path }' konnte nicht verschoben werden: { $reason }
+copy-failed = { $path } konnte nicht kopiert werden: { $reason }
+copy-cancelled = Kopieren abgebrochen; bereits kopierte Elemente wurden behalten
+
+## Command policy
+
+policy-denied =
+    { $category ->
+        [network] Netzwerkzugriff ist
+        [terminal] Das Terminal ist
+        [git_push] Pushen zu Git-Remotes ist
+        [ai] KI-Unterstützung ist
+       *[other] Dieser Befehl ist
+    } in diesem Arbeitsbereich per Richtlinie deaktiviert
@@ -0,0 +1,39 @@
+# Backend messages. Every message ID here must also exist in the other
+# locales; missing ones fall back to this file.
+
+error-other = { $message }
+
+## Project and path validation
+
+no-project-open = No project is open
+path-invalid = Invalid path: { $reason }
+path-traversal = Invalid path: traversal not allowed
+path-outside-project = Access denied: path is outside the project directory
+
+## File tree operations
+
+file-exists = File already exists
+folder-exists = Folder already exists
+path-not-found = Path does not exist
+no-parent-dir = No parent directory
+dest-not-dir = Destination is not a directory
+source-invalid = Invalid source: { $path }
+move-into-itself = Cannot move '{ $path }' into itself or a subdirectory
+target-exists = '{ $name }' already exists in the destination
+create-failed = Failed to create '{ $path }': { $reason }
+trash-failed = Failed to move to trash: { $reason }
+rename-failed = Failed to rename: { $reason }
+move-failed = Failed to move '{ $path }': { $reason }
+copy-failed = Failed to copy { $path }: { $reason }
+copy-cancelled = Copy cancelled; items copied so far were kept
+
+## Command policy
+
+policy-denied =
+    { $category ->
+        [network] Network access is
+        [terminal] The terminal is
+        [git_push] Pushing to git remotes is
+        [ai] AI assistance is
+       *[other] This command is
+    } disabled by policy in this workspace
@@ -0,0 +1,197 @@
+//! Localized backend messages.
+//!
+//! Messages live in Fluent files under `locales/`, one per locale, and are
+//! compiled into the binary. Commands that report errors through
+//! `Localized` send the message ID and its arguments next to the text
+//! rendered in the current locale, so the frontend can show the text as is
+//! or look the ID up in its own catalog.
+//!
+//! The locale is app-wide and set with `set_locale`; it starts as
+//! `en-US`. IDs missing from a locale fall back to `en-US`. Errors from
+//! code that still returns plain strings arrive as `error-other` with the
+//! English text in `message`.
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::{OnceLock, RwLock};
+use unic_langid::LanguageIdentifier;
+
+pub const DEFAULT_LOCALE: &str = "en-US";
+
+const LOCALES: &[(&str, &str)] = &[
+    ("en-US", include_str!("locales/en-US.ftl")),
+    ("de", include_str!("locales/de.ftl")),
+];
+
+static LOCALE: RwLock<Option<&'static str>> = RwLock::new(None);
+
+fn bundles() -> &'static HashMap<&'static str, FluentBundle<FluentResource>> {
+    static BUNDLES: OnceLock<HashMap<&'static str, FluentBundle<FluentResource>>> = OnceLock::new();
+    BUNDLES.get_or_init(|| {
+        LOCALES
+            .iter()
+            .map(|(locale, source)| {
+                let langid: LanguageIdentifier = locale.parse().expect("valid locale id");
+                let resource = FluentResource::try_new(source.to_string()).expect("valid Fluent file");
+                let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+                // Isolation marks around arguments end up verbatim in toasts.
+                bundle.set_use_isolating(false);
+                bundle.add_resource(resource).expect("no duplicate message ids");
+                (*locale, bundle)
+            })
+            .collect()
+    })
+}
+
+/// The supported locale closest to `requested`: an exact match, then one
+/// with the same language (`de-AT` → `de`), then `en-US`.
+pub fn negotiate(requested: &str) -> &'static str {
+    let requested = requested.trim().replace('_', "-");
+    let language = requested.split('-').next().unwrap_or_default();
+    LOCALES
+        .iter()
+        .map(|(locale, _)| *locale)
+        .find(|l| l.eq_ignore_ascii_case(&requested))
+        .or_else(|| {
+            LOCALES
+                .iter()
+                .map(|(locale, _)| *locale)
+                .find(|l| l.split('-').next().is_some_and(|lang| lang.eq_ignore_ascii_case(language)))
+        })
+        .unwrap_or(DEFAULT_LOCALE)
+}
+
+pub fn current_locale() -> &'static str {
+    LOCALE.read().ok().and_then(|l| *l).unwrap_or(DEFAULT_LOCALE)
+}
+
+/// Render `id` in `locale`. Unknown IDs render as the ID itself.
+pub fn render(locale: &str, id: &str, args: &BTreeMap<String, String>) -> String {
+    let bundles = bundles();
+    let mut fluent_args = FluentArgs::new();
+    for (key, value) in args {
+        fluent_args.set(key.as_str(), value.as_str());
+    }
+    for candidate in [locale, DEFAULT_LOCALE] {
+        let Some(bundle) = bundles.get(candidate) else { continue };
+        let Some(pattern) = bundle.get_message(id).and_then(|m| m.value()) else { continue };
+        let mut errors = Vec::new();
+        return bundle.format_pattern(pattern, Some(&fluent_args), &mut errors).into_owned();
+    }
+    id.to_string()
+}
+
+/// A message ID with its arguments and the text rendered in the current
+/// locale. Commands use it as their error type; it converts to and from
+/// `String` so it mixes with the string errors used elsewhere.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Localized {
+    pub id: String,
+    pub args: BTreeMap<String, String>,
+    pub message: String,
+}
+
+impl Localized {
+    pub fn new(id: &str, args: &[(&str, String)]) -> Self {
+        let args: BTreeMap<String, String> = args.iter().map(|(k, v)| (k.to_string(), v.clone())).collect();
+        let message = render(current_locale(), id, &args);
+        Self { id: id.to_string(), args, message }
+    }
+}
+
+impl fmt::Display for Localized {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<String> for Localized {
+    fn from(message: String) -> Self {
+        let args = BTreeMap::from([("message".to_string(), message.clone())]);
+        Self { id: "error-other".to_string(), args, message }
+    }
+}
+
+impl From<&str> for Localized {
+    fn from(message: &str) -> Self {
+        Self::from(message.to_string())
+    }
+}
+
+impl From<Localized> for String {
+    fn from(error: Localized) -> Self {
+        error.message
+    }
+}
+
+// ── Commands ──
+
+/// Switch backend messages to the supported locale closest to `locale`
+/// (a BCP 47 tag such as `navigator.language`). Returns the locale used.
+#[tauri::command]
+pub fn set_locale(locale: String) -> Result<String, String> {
+    let chosen = negotiate(&locale);
+    *LOCALE.write().map_err(|e| e.to_string())? = Some(chosen);
+    Ok(chosen.to_string())
+}
+
+#[tauri::command]
+pub fn get_locale() -> String {
+    current_locale().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn renders_with_fallback() {
+        let a = args(&[("path", "src"), ("reason", "denied")]);
+        assert_eq!(render("en-US", "move-failed", &a), "Failed to move 'src': denied");
+        assert_eq!(render("de", "move-failed", &a), "'src' konnte nicht verschoben werden: denied");
+        assert_eq!(render("fr", "file-exists", &BTreeMap::new()), "File already exists");
+        assert_eq!(render("de", "no-such-message", &BTreeMap::new()), "no-such-message");
+        let denied = args(&[("category", "git_push"), ("command", "git_push")]);
+        assert_eq!(render("en-US", "policy-denied", &denied), "Pushing to git remotes is disabled by policy in this workspace");
+    }
+
+    #[test]
+    fn every_locale_has_every_message() {
+        let ids = LOCALES[0]
+            .1
+            .lines()
+            .filter(|l| l.starts_with(|c: char| c.is_ascii_lowercase()))
+            .filter_map(|l| l.split_once(" ="))
+            .map(|(id, _)| id);
+        for id in ids {
+            for (locale, bundle) in bundles() {
+                assert!(bundle.has_message(id), "{} is missing {}", locale, id);
+            }
+        }
+    }
+
+    #[test]
+    fn negotiates_locales() {
+        assert_eq!(negotiate("de"), "de");
+        assert_eq!(negotiate("de_AT"), "de");
+        assert_eq!(negotiate("EN-us"), "en-US");
+        assert_eq!(negotiate("en-GB"), "en-US");
+        assert_eq!(negotiate("ja-JP"), "en-US");
+        assert_eq!(negotiate(""), "en-US");
+    }
+
+    #[test]
+    fn wraps_plain_string_errors() {
+        let e = Localized::from("Disk full");
+        assert_eq!((e.id.as_str(), e.message.as_str()), ("error-other", "Disk full"));
+        assert_eq!(e.args["message"], "Disk full");
+        assert_eq!(String::from(e), "Disk full");
+    }
+}
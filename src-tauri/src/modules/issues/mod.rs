@@ -0,0 +1,391 @@
+//! Issue tracker integration (GitHub Issues and Jira).
+//!
+//! One tracker is configured at a time, in `~/.leo-ide/issues.json`; its
+//! token goes through the same secure key storage as AI provider keys.
+//! Besides listing and fetching issues, this turns an issue into a branch
+//! name and a commit trailer so work on a ticket can start from the
+//! command palette. `todos` goes the other way, filing TODO comments as
+//! new issues.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::PathBuf;
+
+use crate::modules::git::branch_policy::slugify;
+use crate::modules::policy::Category;
+use crate::modules::{ai, net};
+
+pub mod todos;
+
+/// Policy categories of the issue tracker commands, here and in `todos`.
+pub(crate) const COMMAND_CATEGORIES: &[(&str, &[Category])] = &[
+    ("list_assigned_issues", &[Category::Network]),
+    ("get_issue", &[Category::Network]),
+    ("issue_work_refs", &[Category::Network]),
+    ("create_issue_from_todo", &[Category::Network]),
+];
+
+const GITHUB_API: &str = "https://api.github.com";
+const MAX_ISSUES: usize = 50;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackerKind {
+    Github,
+    Jira,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct IssueTrackerConfig {
+    pub kind: TrackerKind,
+    /// `owner/repo` for GitHub, used to resolve bare issue numbers.
+    pub repo: Option<String>,
+    /// Jira site, e.g. `https://acme.atlassian.net`.
+    pub base_url: Option<String>,
+    /// Jira Cloud account email; with it the token is sent as basic auth,
+    /// without it as a bearer personal access token (Jira Server/DC).
+    pub email: Option<String>,
+    /// Jira project new issues are filed in, e.g. `ABC`.
+    #[serde(default)]
+    pub project_key: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Issue {
+    /// `owner/repo#12` or `ABC-12`.
+    pub id: String,
+    pub title: String,
+    pub url: String,
+    pub state: String,
+    /// GitHub labels or the Jira issue type.
+    pub labels: Vec<String>,
+    pub body: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct IssueRefs {
+    pub branch_name: String,
+    pub commit_trailer: String,
+}
+
+// ── Settings ──
+
+fn settings_path() -> PathBuf {
+    dirs::home_dir().unwrap_or_default().join(".leo-ide").join("issues.json")
+}
+
+fn load_config() -> Result<IssueTrackerConfig, String> {
+    std::fs::read_to_string(settings_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .ok_or_else(|| "No issue tracker is configured".to_string())
+}
+
+fn token_name(kind: &TrackerKind) -> &'static str {
+    match kind {
+        TrackerKind::Github => "issues-github",
+        TrackerKind::Jira => "issues-jira",
+    }
+}
+
+fn token(kind: &TrackerKind) -> Result<String, String> {
+    ai::get_key(token_name(kind))?.ok_or_else(|| "No issue tracker token is set".to_string())
+}
+
+// ── Providers ──
+
+fn github_issue(v: &Value) -> Issue {
+    let repo = v["repository"]["full_name"]
+        .as_str()
+        .map(str::to_string)
+        .or_else(|| {
+            // `repository_url` is `https://api.github.com/repos/<owner>/<repo>`.
+            let url = v["repository_url"].as_str()?;
+            url.split("/repos/").nth(1).map(str::to_string)
+        })
+        .unwrap_or_default();
+    Issue {
+        id: format!("{}#{}", repo, v["number"].as_u64().unwrap_or(0)),
+        title: v["title"].as_str().unwrap_or_default().to_string(),
+        url: v["html_url"].as_str().unwrap_or_default().to_string(),
+        state: v["state"].as_str().unwrap_or_default().to_string(),
+        labels: v["labels"].as_array().into_iter().flatten().filter_map(|l| l["name"].as_str().map(str::to_string)).collect(),
+        body: v["body"].as_str().map(str::to_string),
+    }
+}
+
+fn jira_issue(v: &Value, base_url: &str) -> Issue {
+    let key = v["key"].as_str().unwrap_or_default();
+    let fields = &v["fields"];
+    Issue {
+        id: key.to_string(),
+        title: fields["summary"].as_str().unwrap_or_default().to_string(),
+        url: format!("{}/browse/{}", base_url.trim_end_matches('/'), key),
+        state: fields["status"]["name"].as_str().unwrap_or_default().to_string(),
+        labels: fields["issuetype"]["name"].as_str().map(|t| vec![t.to_string()]).unwrap_or_default(),
+        body: fields["description"].as_str().map(str::to_string),
+    }
+}
+
+/// `owner/repo#12`, `#12` or `12` → (`owner/repo`, 12).
+fn parse_github_id(id: &str, default_repo: Option<&str>) -> Result<(String, u64), String> {
+    let invalid = || format!("Invalid GitHub issue id: {}", id);
+    let (repo, number) = match id.trim().rsplit_once('#') {
+        Some((repo, n)) if !repo.is_empty() => (repo.to_string(), n),
+        Some((_, n)) => (default_repo.ok_or_else(invalid)?.to_string(), n),
+        None => (default_repo.ok_or_else(invalid)?.to_string(), id.trim()),
+    };
+    let number = number.parse().map_err(|_| invalid())?;
+    let valid_repo = repo.split('/').count() == 2
+        && repo.split('/').all(|p| !p.is_empty() && !p.starts_with('.') && p.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c)));
+    if !valid_repo {
+        return Err(invalid());
+    }
+    Ok((repo, number))
+}
+
+fn jira_base(config: &IssueTrackerConfig) -> Result<String, String> {
+    let base = config.base_url.as_deref().ok_or("Jira base URL is not configured")?;
+    let url = reqwest::Url::parse(base).map_err(|e| format!("Invalid Jira base URL: {}", e))?;
+    if url.scheme() != "https" && url.scheme() != "http" {
+        return Err("Invalid Jira base URL".to_string());
+    }
+    Ok(base.trim_end_matches('/').to_string())
+}
+
+async fn get_json(config: &IssueTrackerConfig, url: &str) -> Result<Value, String> {
+    send_json(config, reqwest::Method::GET, url, None).await
+}
+
+async fn send_json(config: &IssueTrackerConfig, method: reqwest::Method, url: &str, body: Option<&Value>) -> Result<Value, String> {
+    net::ensure_online("Issue tracker")?;
+    let token = token(&config.kind)?;
+    let mut req = net::client().request(method, url).header("Accept", "application/json");
+    if let Some(body) = body {
+        req = req.json(body);
+    }
+    req = match (&config.kind, &config.email) {
+        (TrackerKind::Github, _) => req
+            .bearer_auth(&token)
+            .header("Accept", "application/vnd.github+json")
+            .header("User-Agent", "leo-ide"),
+        (TrackerKind::Jira, Some(email)) => req.basic_auth(email, Some(&token)),
+        (TrackerKind::Jira, None) => req.bearer_auth(&token),
+    };
+    let response = req.send().await.map_err(|e| format!("Request failed: {}", e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("API error {}: {}", status, text));
+    }
+    response.json().await.map_err(|e| format!("Parse error: {}", e))
+}
+
+/// Open a new issue in the configured GitHub repo or Jira project.
+async fn create_issue(config: &IssueTrackerConfig, title: &str, body: &str) -> Result<Issue, String> {
+    match config.kind {
+        TrackerKind::Github => {
+            let repo = config.repo.as_deref().ok_or("Set a repository for the issue tracker to create issues")?;
+            // Checked like an issue id, so a bad setting can't reach the URL.
+            let (repo, _) = parse_github_id(&format!("{}#0", repo), None)?;
+            let payload = serde_json::json!({ "title": title, "body": body });
+            let url = format!("{}/repos/{}/issues", GITHUB_API, repo);
+            let v = send_json(config, reqwest::Method::POST, &url, Some(&payload)).await?;
+            let mut issue = github_issue(&v);
+            issue.id = format!("{}#{}", repo, v["number"].as_u64().unwrap_or(0));
+            Ok(issue)
+        }
+        TrackerKind::Jira => {
+            let project = config.project_key.as_deref().ok_or("Set a Jira project for the issue tracker to create issues")?;
+            let base = jira_base(config)?;
+            let payload = serde_json::json!({
+                "fields": {
+                    "project": { "key": project },
+                    "summary": title,
+                    "description": body,
+                    "issuetype": { "name": "Task" },
+                }
+            });
+            let created = send_json(config, reqwest::Method::POST, &format!("{}/rest/api/2/issue", base), Some(&payload)).await?;
+            let key = created["key"].as_str().ok_or("Jira did not return the new issue key")?;
+            let v = get_json(config, &format!("{}/rest/api/2/issue/{}", base, key)).await?;
+            Ok(jira_issue(&v, &base))
+        }
+    }
+}
+
+// ── Branches and trailers ──
+
+/// How code and commits refer to an issue: `#12` in the configured repo,
+/// `owner/repo#12` elsewhere, `ABC-7` for Jira.
+fn short_ref(issue: &Issue, kind: &TrackerKind, config_repo: Option<&str>) -> String {
+    match kind {
+        TrackerKind::Github => {
+            let (repo, number) = issue.id.rsplit_once('#').unwrap_or(("", issue.id.as_str()));
+            if config_repo == Some(repo) || repo.is_empty() { format!("#{}", number) } else { issue.id.clone() }
+        }
+        TrackerKind::Jira => issue.id.clone(),
+    }
+}
+
+fn issue_refs(issue: &Issue, kind: &TrackerKind, config_repo: Option<&str>) -> IssueRefs {
+    let is_bug = issue.labels.iter().any(|l| l.eq_ignore_ascii_case("bug"));
+    let prefix = if is_bug { "fix" } else { "feat" };
+    let reference = short_ref(issue, kind, config_repo);
+    let (ticket, trailer) = match kind {
+        TrackerKind::Github => {
+            let number = issue.id.rsplit_once('#').map_or(issue.id.as_str(), |(_, n)| n);
+            (number.to_string(), format!("Closes {}", reference))
+        }
+        TrackerKind::Jira => (issue.id.clone(), format!("Refs: {}", reference)),
+    };
+    let slug = slugify(&issue.title);
+    let branch_name = if slug.is_empty() { format!("{}/{}", prefix, ticket) } else { format!("{}/{}-{}", prefix, ticket, slug) };
+    IssueRefs { branch_name, commit_trailer: trailer }
+}
+
+// ── Commands ──
+
+#[tauri::command]
+pub fn get_issue_tracker() -> Option<IssueTrackerConfig> {
+    load_config().ok()
+}
+
+/// Save the tracker configuration. `token` replaces the stored token when
+/// given; an empty string removes it.
+#[tauri::command]
+pub fn set_issue_tracker(config: IssueTrackerConfig, token: Option<String>) -> Result<(), String> {
+    if config.kind == TrackerKind::Jira {
+        jira_base(&config)?;
+    }
+    if let Some(token) = token {
+        ai::set_key(token_name(&config.kind), token.trim())?;
+    }
+    let path = settings_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to save issue tracker: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    crate::modules::fs::write_atomic(&path, json.as_bytes()).map_err(|e| format!("Failed to save issue tracker: {}", e))
+}
+
+/// Open issues assigned to the token's user, most recently updated first.
+#[tauri::command]
+pub async fn list_assigned_issues() -> Result<Vec<Issue>, String> {
+    let config = load_config()?;
+    match config.kind {
+        TrackerKind::Github => {
+            let url = format!("{}/issues?filter=assigned&state=open&sort=updated&per_page={}", GITHUB_API, MAX_ISSUES);
+            let items = get_json(&config, &url).await?;
+            Ok(items
+                .as_array()
+                .into_iter()
+                .flatten()
+                // This endpoint also returns pull requests.
+                .filter(|v| v.get("pull_request").is_none())
+                .map(github_issue)
+                .collect())
+        }
+        TrackerKind::Jira => {
+            let base = jira_base(&config)?;
+            let mut url = reqwest::Url::parse(&format!("{}/rest/api/2/search", base)).map_err(|e| e.to_string())?;
+            url.query_pairs_mut()
+                .append_pair("jql", "assignee = currentUser() AND resolution = Unresolved ORDER BY updated DESC")
+                .append_pair("fields", "summary,status,issuetype,description")
+                .append_pair("maxResults", &MAX_ISSUES.to_string());
+            let result = get_json(&config, url.as_str()).await?;
+            Ok(result["issues"].as_array().into_iter().flatten().map(|v| jira_issue(v, &base)).collect())
+        }
+    }
+}
+
+/// Fetch one issue: `owner/repo#12` or `12` (with a configured repo) for
+/// GitHub, `ABC-12` for Jira.
+#[tauri::command]
+pub async fn get_issue(id: String) -> Result<Issue, String> {
+    let config = load_config()?;
+    match config.kind {
+        TrackerKind::Github => {
+            let (repo, number) = parse_github_id(&id, config.repo.as_deref())?;
+            let v = get_json(&config, &format!("{}/repos/{}/issues/{}", GITHUB_API, repo, number)).await?;
+            let mut issue = github_issue(&v);
+            issue.id = format!("{}#{}", repo, number);
+            Ok(issue)
+        }
+        TrackerKind::Jira => {
+            let key = id.trim();
+            if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+                return Err(format!("Invalid Jira issue key: {}", id));
+            }
+            let base = jira_base(&config)?;
+            let v = get_json(&config, &format!("{}/rest/api/2/issue/{}", base, key)).await?;
+            Ok(jira_issue(&v, &base))
+        }
+    }
+}
+
+/// Branch name (`feat/12-add-dark-mode`, `fix/ABC-7-...` for bugs) and
+/// commit trailer (`Closes #12`, `Refs: ABC-7`) for starting work on an
+/// issue.
+#[tauri::command]
+pub async fn issue_work_refs(id: String) -> Result<IssueRefs, String> {
+    let config = load_config()?;
+    let issue = get_issue(id).await?;
+    Ok(issue_refs(&issue, &config.kind, config.repo.as_deref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_provider_payloads() {
+        let gh = github_issue(&json!({
+            "number": 12, "title": "Add dark mode", "state": "open",
+            "html_url": "https://github.com/acme/app/issues/12",
+            "repository_url": "https://api.github.com/repos/acme/app",
+            "labels": [{"name": "bug"}, {"name": "ui"}], "body": null
+        }));
+        assert_eq!(gh.id, "acme/app#12");
+        assert_eq!(gh.labels, vec!["bug", "ui"]);
+
+        let jira = jira_issue(
+            &json!({"key": "ABC-7", "fields": {"summary": "Crash on save", "status": {"name": "In Progress"}, "issuetype": {"name": "Bug"}}}),
+            "https://acme.atlassian.net/",
+        );
+        assert_eq!(jira.url, "https://acme.atlassian.net/browse/ABC-7");
+        assert_eq!(jira.state, "In Progress");
+    }
+
+    #[test]
+    fn parses_github_ids() {
+        assert_eq!(parse_github_id("acme/app#12", None), Ok(("acme/app".to_string(), 12)));
+        assert_eq!(parse_github_id("#3", Some("acme/app")), Ok(("acme/app".to_string(), 3)));
+        assert_eq!(parse_github_id("3", Some("acme/app")), Ok(("acme/app".to_string(), 3)));
+        assert!(parse_github_id("3", None).is_err());
+        assert!(parse_github_id("../x#3", None).is_err());
+    }
+
+    #[test]
+    fn builds_branch_names_and_trailers() {
+        let issue = |id: &str, labels: &[&str]| Issue {
+            id: id.to_string(),
+            title: "Crash when saving: large files!".to_string(),
+            url: String::new(),
+            state: "open".to_string(),
+            labels: labels.iter().map(|l| l.to_string()).collect(),
+            body: None,
+        };
+        let refs = issue_refs(&issue("acme/app#12", &["Bug"]), &TrackerKind::Github, Some("acme/app"));
+        assert_eq!(refs.branch_name, "fix/12-crash-when-saving-large-files");
+        assert_eq!(refs.commit_trailer, "Closes #12");
+        let refs = issue_refs(&issue("acme/lib#4", &[]), &TrackerKind::Github, Some("acme/app"));
+        assert_eq!(refs.commit_trailer, "Closes acme/lib#4");
+        let refs = issue_refs(&issue("ABC-7", &["Story"]), &TrackerKind::Jira, None);
+        assert_eq!(refs, IssueRefs {
+            branch_name: "feat/ABC-7-crash-when-saving-large-files".to_string(),
+            commit_trailer: "Refs: ABC-7".to_string(),
+        });
+    }
+}
@@ -0,0 +1,245 @@
+//! TODO comments as tracker issues.
+//!
+//! `scan_todos` lists the TODO, FIXME, HACK and XXX comments in the
+//! project. Their ids work like search match ids — `path:offset:hash`,
+//! with the file's content hash at scan time — so a comment can be picked
+//! from the list later and a file that changed in between is caught.
+//! `create_issue_from_todo` files one as a new issue in the configured
+//! tracker and returns a workspace edit that tags the comment with the
+//! issue (`// TODO(#12): ...`); the frontend previews and applies it
+//! through `apply_workspace_edit`, so it can be undone like any other edit.
+
+use regex::Regex;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::sync::OnceLock;
+
+use super::{create_issue, load_config, short_ref, Issue};
+use crate::modules::fs::workspace_edit::{FileOperation, Position, Range, TextEdit, WorkspaceEdit};
+use crate::modules::fs::{generated, project_root_async, resolve_within_root, ProjectRootState};
+use crate::modules::search::{content_hash, match_id, project_files, read_text, search_root};
+
+const MAX_TODOS: usize = 5_000;
+/// Longest issue title taken from a comment, in characters.
+const MAX_TITLE_CHARS: usize = 80;
+/// Lines quoted above and below the comment in the issue body.
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Todo {
+    pub id: String,
+    /// Path relative to the project root.
+    pub path: String,
+    /// 1-based line.
+    pub line: usize,
+    /// `TODO`, `FIXME`, `HACK` or `XXX`.
+    pub kind: String,
+    pub text: String,
+    /// Issue the comment already refers to, e.g. `#12` in `TODO(#12)`.
+    pub issue: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TodoIssue {
+    pub issue: Issue,
+    /// Adds the issue reference to the comment.
+    pub edit: WorkspaceEdit,
+}
+
+// ── Scanning ──
+
+/// A marker right after a comment opener: `// TODO: x`, `# FIXME(alice) x`,
+/// ` * HACK x`. Groups: the marker, its parenthesized tag, the text.
+fn todo_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?m)(?://+|#+|/\*+|^[ \t]*\*+|<!--|--|;+)[ \t]*\b(TODO|FIXME|HACK|XXX)\b(\([^)\n]*\))?:?[ \t]*([^\n]*)")
+            .expect("valid regex")
+    })
+}
+
+/// `#12`, `owner/repo#12` or a Jira key in a marker's tag.
+fn issue_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?:[\w.-]+/[\w.-]+)?#\d+\b|\b[A-Z][A-Z0-9_]+-\d+\b").expect("valid regex"))
+}
+
+fn todos_in(rel: &str, content: &str, out: &mut Vec<Todo>) {
+    let hash = content_hash(content);
+    let (mut line, mut counted) = (1, 0);
+    for caps in todo_regex().captures_iter(content) {
+        if out.len() >= MAX_TODOS {
+            return;
+        }
+        let marker = caps.get(1).expect("marker group");
+        line += content[counted..marker.start()].matches('\n').count();
+        counted = marker.start();
+        let text = caps[3].trim_end().trim_end_matches("-->").trim_end_matches("*/").trim();
+        out.push(Todo {
+            id: match_id(rel, marker.start(), &hash),
+            path: rel.to_string(),
+            line,
+            kind: marker.as_str().to_string(),
+            text: text.to_string(),
+            issue: caps.get(2).and_then(|tag| issue_regex().find(tag.as_str())).map(|m| m.as_str().to_string()),
+        });
+    }
+}
+
+/// TODO comments in the files under `dir`, skipping generated files.
+pub(crate) fn scan(root: &Path, dir: &Path) -> Vec<Todo> {
+    let generated_dirs = generated::GeneratedDirs::for_project(root);
+    let mut todos = Vec::new();
+    for path in project_files(root, dir) {
+        if todos.len() >= MAX_TODOS {
+            break;
+        }
+        if generated_dirs.contains(&path) {
+            continue;
+        }
+        let Some(content) = read_text(&path) else { continue };
+        if generated::is_generated(&path, &content).is_some() {
+            continue;
+        }
+        let rel = path.strip_prefix(root).unwrap_or(&path).to_string_lossy().to_string();
+        todos_in(&rel, &content, &mut todos);
+    }
+    todos
+}
+
+/// The TODO `id` names, its offset and the file's content, provided the
+/// file hasn't changed since the scan.
+fn find_todo(root: &Path, id: &str) -> Result<(Todo, usize, String), String> {
+    let invalid = || format!("Invalid TODO id: {}", id);
+    let mut parts = id.rsplitn(3, ':');
+    let (Some(hash), Some(offset), Some(rel)) = (parts.next(), parts.next(), parts.next()) else { return Err(invalid()) };
+    let offset: usize = offset.parse().map_err(|_| invalid())?;
+    let path = resolve_within_root(&root.join(rel).to_string_lossy(), root)?;
+    let content = read_text(&path).ok_or_else(|| format!("Failed to read {}", rel))?;
+    if content_hash(&content) != hash {
+        return Err(format!("{} changed since the TODO scan; scan again", rel));
+    }
+    let mut todos = Vec::new();
+    todos_in(rel, &content, &mut todos);
+    let todo = todos.into_iter().find(|t| t.id == id).ok_or_else(invalid)?;
+    Ok((todo, offset, content))
+}
+
+// ── Issues ──
+
+fn issue_title(todo: &Todo) -> String {
+    if todo.text.is_empty() {
+        return format!("{} in {}:{}", todo.kind, todo.path, todo.line);
+    }
+    let title: String = todo.text.chars().take(MAX_TITLE_CHARS).collect();
+    if title.len() < todo.text.len() {
+        format!("{}…", title.trim_end())
+    } else {
+        title
+    }
+}
+
+fn issue_body(todo: &Todo, content: &str) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let first = (todo.line - 1).saturating_sub(CONTEXT_LINES);
+    let last = (todo.line + CONTEXT_LINES).min(lines.len());
+    format!("{}\n\nFrom `{}:{}`:\n\n```\n{}\n```\n", todo.text, todo.path, todo.line, lines[first..last].join("\n"))
+}
+
+/// Insert `reference` into the tag of the marker at `offset` — after what
+/// is there (`TODO(alice, #12)`) or as a new one (`TODO(#12)`).
+fn tag_edit(content: &str, offset: usize, kind: &str, reference: &str) -> TextEdit {
+    let after = offset + kind.len();
+    let rest = &content[after..];
+    let line = &rest[..rest.find('\n').unwrap_or(rest.len())];
+    let (at, new_text) = match line.strip_prefix('(').and_then(|tag| tag.find(')')) {
+        Some(0) => (after + 1, reference.to_string()),
+        Some(close) => (after + 1 + close, format!(", {}", reference)),
+        None => (after, format!("({})", reference)),
+    };
+    let at = Position::at(content, at);
+    TextEdit { range: Range { start: at, end: at }, new_text }
+}
+
+// ── Commands ──
+
+/// TODO, FIXME, HACK and XXX comments under `path` (the project root when
+/// omitted).
+#[tauri::command]
+pub fn scan_todos(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: Option<String>,
+) -> Result<Vec<Todo>, String> {
+    let (root, dir) = search_root(path.as_deref(), window.label(), &state)?;
+    Ok(scan(&root, &dir))
+}
+
+/// File the TODO `todo_id` (from `scan_todos`) as a new issue. Returns the
+/// issue and the edit that adds its reference to the comment; the edit
+/// fails to apply if the file changes before it is applied.
+#[tauri::command]
+pub async fn create_issue_from_todo(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    todo_id: String,
+) -> Result<TodoIssue, String> {
+    let root = project_root_async(window.label(), &state).await?;
+    let config = load_config()?;
+    let (todo, offset, content) = find_todo(&root, &todo_id)?;
+    if let Some(issue) = &todo.issue {
+        return Err(format!("This {} already refers to {}", todo.kind, issue));
+    }
+    let issue = create_issue(&config, &issue_title(&todo), &issue_body(&todo, &content)).await?;
+    let reference = short_ref(&issue, &config.kind, config.repo.as_deref());
+    let edit = WorkspaceEdit {
+        operations: vec![FileOperation::Edit {
+            path: todo.path.clone(),
+            edits: vec![tag_edit(&content, offset, &todo.kind, &reference)],
+            // `FileVersion::hash` of the content the edit was made for.
+            expected_hash: Some(format!("{:x}", Sha256::digest(content.as_bytes()))),
+        }],
+    };
+    Ok(TodoIssue { issue, edit })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::fs::workspace_edit::apply_edit;
+
+    #[test]
+    fn scans_todos_and_tags_them_with_the_issue() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = std::fs::canonicalize(dir.path()).unwrap();
+        let src = "fn main() {\n    // TODO: handle errors\n    run(); // FIXME(alice) slow on big inputs\n}\n/* HACK(#4) */\nlet todo_list = TODO;\n";
+        std::fs::write(root.join("main.rs"), src).unwrap();
+        std::fs::write(root.join("notes.py"), "# XXX\n").unwrap();
+
+        let mut todos = scan(&root, &root);
+        todos.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+        let summary: Vec<_> = todos.iter().map(|t| (t.path.as_str(), t.line, t.kind.as_str(), t.text.as_str(), t.issue.as_deref())).collect();
+        assert_eq!(summary, [
+            ("main.rs", 2, "TODO", "handle errors", None),
+            ("main.rs", 3, "FIXME", "slow on big inputs", None),
+            ("main.rs", 5, "HACK", "", Some("#4")),
+            ("notes.py", 1, "XXX", "", None),
+        ]);
+        assert_eq!(issue_title(&todos[3]), "XXX in notes.py:1");
+        assert!(issue_body(&todos[0], src).contains("From `main.rs:2`:\n\n```\nfn main() {\n    // TODO: handle errors\n"));
+
+        let (todo, offset, content) = find_todo(&root, &todos[0].id).unwrap();
+        let tag = tag_edit(&content, offset, &todo.kind, "#12");
+        let (_, offset, _) = find_todo(&root, &todos[1].id).unwrap();
+        let tagged = tag_edit(&content, offset, "FIXME", "ABC-7");
+        let edit = WorkspaceEdit {
+            operations: vec![FileOperation::Edit { path: "main.rs".to_string(), edits: vec![tag, tagged], expected_hash: None }],
+        };
+        apply_edit(&root, "main", &edit, false).unwrap();
+        let content = std::fs::read_to_string(root.join("main.rs")).unwrap();
+        assert!(content.contains("// TODO(#12): handle errors\n    run(); // FIXME(alice, ABC-7) slow"));
+
+        assert!(find_todo(&root, &todos[0].id).unwrap_err().contains("changed since the TODO scan"));
+    }
+}
@@ -4,10 +4,15 @@ use sha2::{Digest, Sha256};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tauri::{AppHandle, Emitter};
+use tauri::AppHandle;
 use tokio::sync::Mutex;
+use ts_rs::TS;
 
-use crate::modules::fs::ProjectRootState;
+use crate::modules::events;
+use crate::modules::fs::{generated, ProjectRootState};
+use crate::modules::operations::{self, OperationKind};
+
+pub mod semantic;
 
 // ── State ──
 
@@ -100,7 +105,7 @@ pub struct ProjectInfo {
     pub last_updated: i64,
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Clone, TS)]
 pub struct IndexProgress {
     pub done: u32,
     pub total: u32,
@@ -123,6 +128,31 @@ fn db_path(project_root: &str) -> PathBuf {
     knowledge_dir().join(format!("{}.db", db_hash_of(project_root)))
 }
 
+/// Whether a full `knowledge_index` pass has completed for the project
+/// behind `conn`. Until then the `files` table may be missing files.
+fn fully_indexed(conn: &Connection) -> bool {
+    conn.query_row("SELECT value FROM project_meta WHERE key = 'files_indexed'", [], |r| r.get::<_, String>(0)).is_ok()
+}
+
+/// Indexed files whose exports include `symbol`, relative to the project
+/// root. The file watcher keeps the index current (see `reindex_paths`).
+/// `None` until a full index run has completed.
+pub(crate) fn files_exporting(project_root: &str, symbol: &str) -> Option<Vec<String>> {
+    let path = db_path(project_root);
+    if !path.exists() {
+        return None;
+    }
+    let conn = Connection::open(&path).ok()?;
+    if !fully_indexed(&conn) {
+        return None;
+    }
+    let mut stmt = conn.prepare("SELECT path, exports FROM files WHERE exports LIKE ?1").ok()?;
+    let rows = stmt
+        .query_map(params![format!("%{}%", symbol)], |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?)))
+        .ok()?;
+    Some(rows.flatten().filter(|(_, exports)| exports.split(", ").any(|name| name == symbol)).map(|(path, _)| path).collect())
+}
+
 // ── Schema ──
 
 fn init_schema(conn: &Connection) -> Result<(), String> {
@@ -156,6 +186,13 @@ fn init_schema(conn: &Connection) -> Result<(), String> {
     conn.execute_batch(
         "ALTER TABLE files ADD COLUMN mtime INTEGER NOT NULL DEFAULT 0;"
     ).ok();
+    // Migration: exports now cover every importable declaration; forget
+    // hashes so the next index run re-extracts them.
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |r| r.get(0)).unwrap_or(0);
+    if version < 1 {
+        conn.execute_batch("UPDATE files SET hash = '', mtime = 0; PRAGMA user_version = 1;")
+            .map_err(|e| format!("Schema migration failed: {}", e))?;
+    }
     Ok(())
 }
 
@@ -226,15 +263,16 @@ pub async fn knowledge_index(
     validate_knowledge_root(&project_root, window.label(), &root_state).await?;
     let root = PathBuf::from(&project_root);
     let window_label = window.label().to_string();
+    let op = operations::manager().start(OperationKind::Index, &project_root, Some(window.label()), true);
 
     tokio::task::spawn_blocking(move || {
-        let skip: HashSet<&str> = ["node_modules", ".git", "dist", "build", "target", ".next", "__pycache__", ".svelte-kit"].into_iter().collect();
+        let skip: HashSet<&str> = SKIP_DIRS.iter().copied().collect();
         let mut files: Vec<PathBuf> = Vec::new();
         walk_files(&root, &skip, &mut files);
+        let generated_dirs = generated::GeneratedDirs::for_project(&root);
 
         let total = files.len() as u32;
-        let target = tauri::EventTarget::WebviewWindow { label: window_label.clone() };
-        let _ = app.emit_to(target.clone(), "indexing-progress", IndexProgress { done: 0, total });
+        events::INDEXING_PROGRESS.emit_to(&app, &window_label, IndexProgress { done: 0, total });
 
         // Open DB in this thread
         let db_p = db_path(&root.to_string_lossy());
@@ -246,49 +284,27 @@ pub async fn knowledge_index(
 
         for (i, file) in files.iter().enumerate() {
             let rel = file.strip_prefix(&root).unwrap_or(file).to_string_lossy().to_string();
-
-            // Get file mtime
-            let mtime = std::fs::metadata(file)
-                .ok()
-                .and_then(|m| m.modified().ok())
-                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-                .map(|d| d.as_secs() as i64)
-                .unwrap_or(0);
-
-            // Check if mtime matches — skip file entirely if unchanged
-            let existing: Option<(String, i64)> = conn
-                .query_row("SELECT hash, mtime FROM files WHERE path = ?1", params![rel], |r| Ok((r.get(0)?, r.get::<_, i64>(1).unwrap_or(0))))
-                .ok();
-
-            if let Some((_, db_mtime)) = &existing {
-                if *db_mtime == mtime && mtime > 0 { continue; }
+            // Stopping halfway is safe: the next run picks up where this one left off.
+            if op.is_cancelled() {
+                break;
             }
-
-            // mtime changed or new file — read content
-            let Ok(content) = std::fs::read_to_string(file) else { continue };
-            let hash = format!("{:x}", Sha256::digest(content.as_bytes()));
-
-            // If hash unchanged (mtime changed but content didn't), just bump mtime
-            if existing.as_ref().map(|(h, _)| h.as_str()) == Some(&hash) {
-                conn.execute("UPDATE files SET mtime = ?1 WHERE path = ?2", params![mtime, rel]).ok();
+            op.progress(i as u64, Some(total as u64), Some(rel.clone()));
+            if generated_dirs.contains(file) {
+                conn.execute("DELETE FROM files WHERE path = ?1", params![rel]).ok();
                 continue;
             }
 
-            let lang = detect_lang(file);
-            let size = content.len() as i64;
-            let summary = extract_summary(&content, &lang);
-            let exports = extract_exports(&content, &lang);
-            let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs() as i64;
-
-            conn.execute(
-                "INSERT OR REPLACE INTO files (path, hash, language, size, last_indexed, summary, exports, mtime) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-                params![rel, hash, lang, size, now, summary, exports, mtime],
-            ).ok();
+            index_file(&conn, file, &rel);
 
             if (i + 1) % 20 == 0 || i + 1 == files.len() {
-                let _ = app.emit_to(target.clone(), "indexing-progress", IndexProgress { done: (i + 1) as u32, total });
+                events::INDEXING_PROGRESS.emit_to(&app, &window_label, IndexProgress { done: (i + 1) as u32, total });
             }
         }
+        // From here on the file watcher keeps the table current, so import
+        // suggestions can rely on it.
+        if !op.is_cancelled() {
+            conn.execute("INSERT OR REPLACE INTO project_meta (key, value) VALUES ('files_indexed', '1')", []).ok();
+        }
     }).await.map_err(|e| format!("Indexing failed: {}", e))?;
 
     Ok(())
@@ -719,7 +735,10 @@ pub async fn knowledge_admin_delete_conversations(
 
 // ── Helpers ──
 
-fn walk_files(dir: &Path, skip: &HashSet<&str>, files: &mut Vec<PathBuf>) {
+/// Folders neither the full index nor watcher updates look into.
+pub(crate) const SKIP_DIRS: &[&str] = &["node_modules", ".git", "dist", "build", "target", ".next", "__pycache__", ".svelte-kit"];
+
+pub(crate) fn walk_files(dir: &Path, skip: &HashSet<&str>, files: &mut Vec<PathBuf>) {
     let Ok(entries) = std::fs::read_dir(dir) else { return };
     for entry in entries.flatten() {
         let path = entry.path();
@@ -728,15 +747,118 @@ fn walk_files(dir: &Path, skip: &HashSet<&str>, files: &mut Vec<PathBuf>) {
             if !skip.contains(name.as_ref()) && !name.starts_with('.') {
                 walk_files(&path, skip, files);
             }
+        } else if is_walked_file(&path) {
+            files.push(path);
+        }
+    }
+}
+
+/// Whether `walk_files` picks up the file at `path`, leaving aside the
+/// folders it skips: a source or config file under 500 KB that isn't
+/// generated by its name.
+pub(crate) fn is_walked_file(path: &Path) -> bool {
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    matches!(ext, "js"|"jsx"|"ts"|"tsx"|"svelte"|"rs"|"py"|"go"|"java"|"css"|"html"|"json"|"md"|"toml"|"yaml"|"yml"|"sql"|"sh"|"vue")
+        && generated::generated_by_name(path).is_none()
+        && path.metadata().map(|m| m.len() < 500_000).unwrap_or(false)
+}
+
+/// Whether the full index would pick up `file`: see `walk_files`.
+pub(crate) fn is_indexed(root: &Path, file: &Path) -> bool {
+    let Ok(rel) = file.strip_prefix(root) else { return false };
+    let in_skipped_dir = rel.parent().is_some_and(|dir| {
+        dir.components().any(|c| {
+            let name = c.as_os_str().to_string_lossy();
+            name.starts_with('.') || SKIP_DIRS.contains(&name.as_ref())
+        })
+    });
+    !in_skipped_dir && file.is_file() && is_walked_file(file)
+}
+
+/// Bring `file`'s row up to date: skipped when its mtime is unchanged,
+/// dropped when its content turns out to be generated.
+fn index_file(conn: &Connection, file: &Path, rel: &str) {
+    let mtime = std::fs::metadata(file)
+        .ok()
+        .and_then(|m| m.modified().ok())
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+
+    // Check if mtime matches — skip file entirely if unchanged
+    let existing: Option<(String, i64)> = conn
+        .query_row("SELECT hash, mtime FROM files WHERE path = ?1", params![rel], |r| Ok((r.get(0)?, r.get::<_, i64>(1).unwrap_or(0))))
+        .ok();
+
+    if let Some((_, db_mtime)) = &existing {
+        if *db_mtime == mtime && mtime > 0 { return; }
+    }
+
+    // mtime changed or new file — read content
+    let Ok(content) = std::fs::read_to_string(file) else { return };
+    // Bundles and codegen output only add noise to AI context.
+    if generated::is_generated(file, &content).is_some() {
+        conn.execute("DELETE FROM files WHERE path = ?1", params![rel]).ok();
+        return;
+    }
+    let hash = format!("{:x}", Sha256::digest(content.as_bytes()));
+
+    // If hash unchanged (mtime changed but content didn't), just bump mtime
+    if existing.as_ref().map(|(h, _)| h.as_str()) == Some(&hash) {
+        conn.execute("UPDATE files SET mtime = ?1 WHERE path = ?2", params![mtime, rel]).ok();
+        return;
+    }
+
+    let lang = detect_lang(file);
+    let size = content.len() as i64;
+    let summary = extract_summary(&content, &lang);
+    let exports = extract_exports(&content, &lang);
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs() as i64;
+
+    conn.execute(
+        "INSERT OR REPLACE INTO files (path, hash, language, size, last_indexed, summary, exports, mtime) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![rel, hash, lang, size, now, summary, exports, mtime],
+    ).ok();
+}
+
+/// Update the `files` rows for `paths` (absolute, or relative to `root`)
+/// after they changed on disk. Paths the full index wouldn't include —
+/// deleted, generated or in a skipped folder — are dropped, along with
+/// anything indexed under them. Projects without a completed index run
+/// aren't touched.
+pub(crate) fn reindex_paths(project_root: &str, root: &Path, paths: &[PathBuf]) -> Result<(), String> {
+    let db_p = db_path(project_root);
+    if !db_p.exists() {
+        return Ok(());
+    }
+    let conn = Connection::open(&db_p).map_err(|e| format!("DB open failed: {}", e))?;
+    init_schema(&conn)?;
+    reindex_files(&conn, root, paths)
+}
+
+fn reindex_files(conn: &Connection, root: &Path, paths: &[PathBuf]) -> Result<(), String> {
+    if !fully_indexed(conn) {
+        return Ok(());
+    }
+    let generated_dirs = generated::GeneratedDirs::for_project(root);
+    for file in paths {
+        let file = if file.is_absolute() { file.clone() } else { root.join(file) };
+        let Ok(rel) = file.strip_prefix(root) else { continue };
+        if rel.as_os_str().is_empty() {
+            continue;
+        }
+        let rel = rel.to_string_lossy().to_string();
+        if is_indexed(root, &file) && !generated_dirs.contains(&file) {
+            index_file(conn, &file, &rel);
         } else {
-            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-            if matches!(ext, "js"|"jsx"|"ts"|"tsx"|"svelte"|"rs"|"py"|"go"|"java"|"css"|"html"|"json"|"md"|"toml"|"yaml"|"yml"|"sql"|"sh"|"vue") {
-                if path.metadata().map(|m| m.len() < 500_000).unwrap_or(false) {
-                    files.push(path);
-                }
-            }
+            conn.execute(
+                "DELETE FROM files WHERE path = ?1 OR substr(path, 1, length(?1) + 1) = ?1 || ?2",
+                params![rel, std::path::MAIN_SEPARATOR.to_string()],
+            )
+            .map_err(|e| e.to_string())?;
         }
     }
+    Ok(())
 }
 
 fn detect_lang(path: &Path) -> String {
@@ -773,38 +895,107 @@ fn extract_summary(content: &str, _lang: &str) -> String {
     summary.chars().take(200).collect()
 }
 
+/// The identifier at the start of `s`, without a leading `*` (generators).
+fn leading_ident(s: &str) -> &str {
+    let s = s.trim_start_matches('*');
+    let end = s.find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$')).unwrap_or(s.len());
+    &s[..end]
+}
+
+/// Names a file exports, comma-joined. Import suggestions look symbols up
+/// here, so this covers the declaration forms they can import.
 fn extract_exports(content: &str, lang: &str) -> String {
-    let mut exports = Vec::new();
+    let mut exports: Vec<String> = Vec::new();
     for line in content.lines() {
         let t = line.trim();
         match lang {
             "javascript" | "typescript" | "svelte" => {
-                if t.starts_with("export ") {
-                    let name = t.split_whitespace().nth(2).or_else(|| t.split_whitespace().nth(1)).unwrap_or("").split('(').next().unwrap_or("").trim_end_matches(|c: char| !c.is_alphanumeric() && c != '_');
-                    if !name.is_empty() && name != "{" { exports.push(name.to_string()); }
+                let Some(rest) = t.strip_prefix("export ") else { continue };
+                if let Some(list) = rest.trim_start().strip_prefix('{') {
+                    for item in list.split('}').next().unwrap_or("").split(',') {
+                        let name = leading_ident(item.rsplit(" as ").next().unwrap_or("").trim());
+                        if !name.is_empty() { exports.push(name.to_string()); }
+                    }
+                    continue;
+                }
+                let mut words = rest.split_whitespace();
+                while let Some(w) = words.next() {
+                    match w {
+                        "default" | "declare" | "abstract" | "async" => continue,
+                        "function" | "function*" | "class" | "interface" | "type" | "enum" | "const" | "let" | "var" | "namespace" => {
+                            let name = leading_ident(words.next().unwrap_or(""));
+                            if !name.is_empty() { exports.push(name.to_string()); }
+                        }
+                        _ => {}
+                    }
+                    break;
                 }
             }
             "rust" => {
-                if t.starts_with("pub fn ") || t.starts_with("pub async fn ") {
-                    let name = t.split("fn ").nth(1).unwrap_or("").split('(').next().unwrap_or("").trim();
-                    if !name.is_empty() { exports.push(name.to_string()); }
-                } else if t.starts_with("pub struct ") || t.starts_with("pub enum ") {
-                    let name = t.split_whitespace().nth(2).unwrap_or("").split(|c: char| !c.is_alphanumeric() && c != '_').next().unwrap_or("");
-                    if !name.is_empty() { exports.push(name.to_string()); }
+                let Some(rest) = t.strip_prefix("pub ").or_else(|| t.strip_prefix("pub(crate) ")) else { continue };
+                let mut words = rest.split_whitespace().peekable();
+                while let Some(w) = words.next() {
+                    match w {
+                        "async" | "unsafe" | "extern" | "\"C\"" => continue,
+                        "const" if matches!(words.peek(), Some(&"fn") | Some(&"unsafe")) => continue,
+                        "fn" | "struct" | "enum" | "union" | "trait" | "type" | "const" | "static" | "mod" => {
+                            let word = match words.next() {
+                                Some("mut") => words.next().unwrap_or(""),
+                                other => other.unwrap_or(""),
+                            };
+                            let name = leading_ident(word);
+                            if !name.is_empty() { exports.push(name.to_string()); }
+                        }
+                        _ => {}
+                    }
+                    break;
                 }
             }
             "python" => {
-                if t.starts_with("def ") && !t.starts_with("def _") {
-                    let name = t.trim_start_matches("def ").split('(').next().unwrap_or("").trim();
-                    if !name.is_empty() { exports.push(name.to_string()); }
-                } else if t.starts_with("class ") {
-                    let name = t.trim_start_matches("class ").split(|c: char| !c.is_alphanumeric() && c != '_').next().unwrap_or("");
-                    if !name.is_empty() { exports.push(name.to_string()); }
+                if let Some(rest) = t.strip_prefix("def ").or_else(|| t.strip_prefix("async def ")).or_else(|| t.strip_prefix("class ")) {
+                    let name = leading_ident(rest);
+                    if !name.is_empty() && !name.starts_with('_') { exports.push(name.to_string()); }
+                } else if !line.starts_with(char::is_whitespace) {
+                    // Module-level `NAME = ...` and `NAME: type = ...`.
+                    let name = leading_ident(t);
+                    let after = t[name.len()..].trim_start();
+                    let assigns = (after.starts_with('=') && !after.starts_with("=="))
+                        || after.strip_prefix(':').is_some_and(|a| !a.trim().is_empty() && !a.trim_start().starts_with('#'));
+                    if !name.is_empty() && !name.starts_with('_') && assigns { exports.push(name.to_string()); }
                 }
             }
             _ => {}
         }
-        if exports.len() >= 20 { break; }
+        if exports.len() >= 100 { break; }
     }
     exports.join(", ")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn watcher_updates_keep_exports_current() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src/api")).unwrap();
+        std::fs::write(root.join("src/api/user.ts"), "export function loadUser() {}\n").unwrap();
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        let changed = [root.join("src/api/user.ts")];
+
+        reindex_files(&conn, root, &changed).unwrap();
+        let count = |conn: &Connection| conn.query_row("SELECT COUNT(*) FROM files", [], |r| r.get::<_, i64>(0)).unwrap();
+        assert_eq!(count(&conn), 0, "a project that was never fully indexed is left alone");
+
+        conn.execute("INSERT INTO project_meta (key, value) VALUES ('files_indexed', '1')", []).unwrap();
+        reindex_files(&conn, root, &changed).unwrap();
+        let exports: String = conn.query_row("SELECT exports FROM files", [], |r| r.get(0)).unwrap();
+        assert_eq!(exports, "loadUser");
+
+        std::fs::remove_dir_all(root.join("src/api")).unwrap();
+        reindex_files(&conn, root, &[root.join("src/api")]).unwrap();
+        assert_eq!(count(&conn), 0);
+    }
+}
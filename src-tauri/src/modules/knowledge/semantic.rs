@@ -0,0 +1,677 @@
+//! Opt-in semantic search over project code.
+//!
+//! Project files are split into overlapping line windows, each window is
+//! embedded with a local sentence-embedding model (BAAI bge-small-en-v1.5
+//! via fastembed on ONNX Runtime), and the vectors are stored next to the
+//! rest of the project's knowledge in `~/.leo-ide/knowledge/<hash>.db`.
+//! Queries are embedded the same way and ranked by cosine similarity, so
+//! "where do we retry failed uploads?" finds `uploadWithRetry` as well as
+//! code that only talks about resending.
+//!
+//! Embedding runs on this machine; no code or query leaves it. The model
+//! is downloaded once into `~/.leo-ide/models` the first time it's needed.
+//! ONNX Runtime is loaded at run time rather than linked, so the app starts
+//! without it; see `find_onnx_runtime` for where it is looked for.
+//! `EMBEDDER_ID` is stored with the index so a different model invalidates
+//! old vectors, and `SemanticStatus::embedder` reports it.
+//!
+//! Once a project is indexed, the file watcher behind `fs::index` keeps it
+//! current: changed files are re-embedded via `reindex_paths` after edits
+//! settle.
+
+use fastembed::{EmbeddingModel, InitOptions, TextEmbedding};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::AppHandle;
+use ts_rs::TS;
+
+use super::{db_path, init_schema, is_indexed, validate_knowledge_root, walk_files, SKIP_DIRS};
+use crate::modules::events;
+use crate::modules::fs::{generated, ProjectRootState};
+use crate::modules::operations::{self, Operation, OperationKind};
+
+const EMBEDDER_ID: &str = "bge-small-en-v1.5";
+const MODEL: EmbeddingModel = EmbeddingModel::BGESmallENV15;
+const CHUNK_LINES: usize = 40;
+const CHUNK_STEP: usize = 30;
+const SNIPPET_LINES: usize = 8;
+const DEFAULT_TOP_K: usize = 6;
+/// Budget for retrieved code injected into a chat turn.
+const MAX_CONTEXT_CHARS: usize = 16_000;
+
+/// Loaded on first use and kept for the life of the app.
+static EMBEDDER: Mutex<Option<TextEmbedding>> = Mutex::new(None);
+
+// ── Types ──
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SemanticHit {
+    pub path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub score: f32,
+    pub snippet: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct SemanticStatus {
+    pub enabled: bool,
+    /// The embedding model the vectors come from.
+    pub embedder: String,
+    pub indexed_files: i64,
+    pub chunks: i64,
+}
+
+/// A retrieved chunk as cited in AI response metadata.
+#[derive(Serialize, Clone, Debug, TS)]
+pub struct Citation {
+    pub path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub score: f32,
+}
+
+/// Exactly what retrieval adds to a chat turn: the citations and the
+/// formatted context block that is prepended to the system prompt.
+#[derive(Serialize, Clone, Default)]
+pub struct RetrievedContext {
+    pub citations: Vec<Citation>,
+    pub context: String,
+}
+
+struct Chunk {
+    start_line: usize,
+    end_line: usize,
+    text: String,
+}
+
+// ── Schema ──
+
+fn init_semantic_schema(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS semantic_files (
+            path TEXT PRIMARY KEY,
+            hash TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS semantic_chunks (
+            path TEXT NOT NULL,
+            start_line INTEGER NOT NULL,
+            end_line INTEGER NOT NULL,
+            vector BLOB NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS semantic_chunks_path ON semantic_chunks(path);",
+    )
+    .map_err(|e| format!("Schema init failed: {}", e))?;
+
+    // Vectors from another embedder live in a different space — drop them.
+    let stored: Option<String> = conn
+        .query_row("SELECT value FROM project_meta WHERE key = 'semantic_embedder'", [], |r| r.get(0))
+        .ok();
+    if stored.as_deref() != Some(EMBEDDER_ID) {
+        conn.execute_batch("DELETE FROM semantic_chunks; DELETE FROM semantic_files;")
+            .map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT OR REPLACE INTO project_meta (key, value) VALUES ('semantic_embedder', ?1)",
+            params![EMBEDDER_ID],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn open_db(project_root: &str) -> Result<Connection, String> {
+    let conn = Connection::open(db_path(project_root)).map_err(|e| format!("DB open failed: {}", e))?;
+    init_schema(&conn)?;
+    init_semantic_schema(&conn)?;
+    Ok(conn)
+}
+
+fn is_enabled(conn: &Connection) -> bool {
+    conn.query_row("SELECT value FROM project_meta WHERE key = 'semantic_enabled'", [], |r| {
+        r.get::<_, String>(0)
+    })
+    .map(|v| v == "1")
+    .unwrap_or(false)
+}
+
+fn require_enabled(conn: &Connection) -> Result<(), String> {
+    if is_enabled(conn) {
+        Ok(())
+    } else {
+        Err("Semantic search is not enabled for this project".to_string())
+    }
+}
+
+// ── Embedding ──
+
+fn models_dir() -> PathBuf {
+    dirs::home_dir().unwrap_or_default().join(".leo-ide").join("models")
+}
+
+/// ONNX Runtime's shared library: `ORT_DYLIB_PATH` if set, else
+/// `~/.leo-ide/models`, else next to the app binary. `ort` aborts the
+/// process when it can't load the library, so it is only asked to once
+/// the file is known to exist.
+fn find_onnx_runtime() -> Result<PathBuf, String> {
+    let name = format!("{}onnxruntime{}", std::env::consts::DLL_PREFIX, std::env::consts::DLL_SUFFIX);
+    let from_env = std::env::var_os("ORT_DYLIB_PATH").map(PathBuf::from);
+    let beside_app = std::env::current_exe().ok().and_then(|exe| exe.parent().map(|dir| dir.join(&name)));
+    from_env
+        .into_iter()
+        .chain([models_dir().join(&name)])
+        .chain(beside_app)
+        .find(|p| p.is_file())
+        .ok_or_else(|| {
+            format!(
+                "Semantic search needs ONNX Runtime: put {} in {} or set ORT_DYLIB_PATH",
+                name,
+                models_dir().display()
+            )
+        })
+}
+
+fn load_embedder() -> Result<TextEmbedding, String> {
+    std::env::set_var("ORT_DYLIB_PATH", find_onnx_runtime()?);
+    let options = InitOptions::new(MODEL)
+        .with_cache_dir(models_dir())
+        .with_show_download_progress(false);
+    TextEmbedding::try_new(options).map_err(|e| format!("Failed to load the embedding model: {}", e))
+}
+
+/// Embed `texts` into L2-normalized vectors, loading the model first if
+/// this is the first call.
+fn embed(texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+    if texts.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut embedder = EMBEDDER.lock().map_err(|e| e.to_string())?;
+    let model = match &mut *embedder {
+        Some(model) => model,
+        slot => slot.insert(load_embedder()?),
+    };
+    model.embed(texts.to_vec(), None).map_err(|e| format!("Embedding failed: {}", e))
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn vector_to_blob(v: &[f32]) -> Vec<u8> {
+    v.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+fn blob_to_vector(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+// ── Chunking ──
+
+/// Overlapping line windows. The path is included in every chunk's text
+/// because file and directory names carry a lot of meaning on their own.
+fn chunk_file(rel_path: &str, content: &str) -> Vec<Chunk> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < lines.len() {
+        let end = (start + CHUNK_LINES).min(lines.len());
+        let body = lines[start..end].join("\n");
+        if !body.trim().is_empty() {
+            chunks.push(Chunk {
+                start_line: start + 1,
+                end_line: end,
+                text: format!("{rel_path}\n{body}"),
+            });
+        }
+        if end == lines.len() {
+            break;
+        }
+        start += CHUNK_STEP;
+    }
+    chunks
+}
+
+/// Re-embed one file if its content changed. Returns false when the file
+/// no longer exists, isn't readable text, or is generated code, in which
+/// case its chunks are removed.
+fn index_file(conn: &Connection, root: &Path, file: &Path) -> Result<bool, String> {
+    let rel = file.strip_prefix(root).unwrap_or(file).to_string_lossy().to_string();
+    let Ok(content) = std::fs::read_to_string(file) else {
+        remove_file(conn, &rel)?;
+        return Ok(false);
+    };
+    if generated::is_generated(file, &content).is_some() {
+        remove_file(conn, &rel)?;
+        return Ok(false);
+    }
+    let hash = format!("{:x}", Sha256::digest(content.as_bytes()));
+    let existing: Option<String> = conn
+        .query_row("SELECT hash FROM semantic_files WHERE path = ?1", params![rel], |r| r.get(0))
+        .ok();
+    if existing.as_deref() == Some(hash.as_str()) {
+        return Ok(true);
+    }
+
+    let chunks = chunk_file(&rel, &content);
+    let texts: Vec<String> = chunks.iter().map(|c| c.text.clone()).collect();
+    let vectors = embed(&texts)?;
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM semantic_chunks WHERE path = ?1", params![rel])
+        .map_err(|e| e.to_string())?;
+    for (chunk, vector) in chunks.iter().zip(vectors) {
+        tx.execute(
+            "INSERT INTO semantic_chunks (path, start_line, end_line, vector) VALUES (?1, ?2, ?3, ?4)",
+            params![rel, chunk.start_line as i64, chunk.end_line as i64, vector_to_blob(&vector)],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    tx.execute(
+        "INSERT OR REPLACE INTO semantic_files (path, hash) VALUES (?1, ?2)",
+        params![rel, hash],
+    )
+    .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+fn remove_file(conn: &Connection, rel: &str) -> Result<(), String> {
+    conn.execute("DELETE FROM semantic_chunks WHERE path = ?1", params![rel])
+        .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM semantic_files WHERE path = ?1", params![rel])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn snippet(root: &Path, rel: &str, start_line: usize) -> String {
+    std::fs::read_to_string(root.join(rel))
+        .map(|c| {
+            c.lines()
+                .skip(start_line.saturating_sub(1))
+                .take(SNIPPET_LINES)
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .unwrap_or_default()
+}
+
+// ── Search ──
+
+/// Rank indexed chunks against `query`. Kept separate from the command so
+/// RAG context building can reuse it.
+pub(crate) fn search(project_root: &str, query: &str, limit: usize) -> Result<Vec<SemanticHit>, String> {
+    let conn = open_db(project_root)?;
+    require_enabled(&conn)?;
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let q = embed(&[query.to_string()])?.pop().unwrap_or_default();
+    rank(&conn, Path::new(project_root), &q, limit)
+}
+
+/// The `limit` chunks closest to the query vector `q`.
+fn rank(conn: &Connection, root: &Path, q: &[f32], limit: usize) -> Result<Vec<SemanticHit>, String> {
+    let mut stmt = conn
+        .prepare("SELECT path, start_line, end_line, vector FROM semantic_chunks")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, i64>(1)? as usize,
+                r.get::<_, i64>(2)? as usize,
+                r.get::<_, Vec<u8>>(3)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+    let mut scored: Vec<(f32, String, usize, usize)> = rows
+        .flatten()
+        .map(|(path, start, end, blob)| (cosine(q, &blob_to_vector(&blob)), path, start, end))
+        .filter(|(score, ..)| *score > 0.0)
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    // One hit per file region: skip chunks overlapping a better one.
+    let mut hits: Vec<SemanticHit> = Vec::new();
+    for (score, path, start, end) in scored {
+        if hits.len() >= limit {
+            break;
+        }
+        if hits.iter().any(|h| h.path == path && start <= h.end_line && end >= h.start_line) {
+            continue;
+        }
+        hits.push(SemanticHit {
+            snippet: snippet(root, &path, start),
+            path,
+            start_line: start,
+            end_line: end,
+            score,
+        });
+    }
+    Ok(hits)
+}
+
+// ── Retrieval ──
+
+fn format_context(root: &Path, hits: &[SemanticHit]) -> RetrievedContext {
+    let mut out = RetrievedContext::default();
+    for hit in hits {
+        let Ok(content) = std::fs::read_to_string(root.join(&hit.path)) else { continue };
+        let body: Vec<&str> = content
+            .lines()
+            .skip(hit.start_line.saturating_sub(1))
+            .take(hit.end_line + 1 - hit.start_line)
+            .collect();
+        let block = format!(
+            "### {}:{}-{}\n```\n{}\n```\n\n",
+            hit.path,
+            hit.start_line,
+            hit.end_line,
+            body.join("\n")
+        );
+        if out.context.len() + block.len() > MAX_CONTEXT_CHARS {
+            break;
+        }
+        out.context.push_str(&block);
+        out.citations.push(Citation {
+            path: hit.path.clone(),
+            start_line: hit.start_line,
+            end_line: hit.end_line,
+            score: hit.score,
+        });
+    }
+    if !out.context.is_empty() {
+        out.context = format!(
+            "## Retrieved code\nThese excerpts were retrieved from the project as possibly relevant. \
+             Cite them as path:start-end when you rely on them.\n\n{}",
+            out.context
+        );
+    }
+    out
+}
+
+/// Retrieve the top-k chunks for `prompt` for the calling window's project.
+/// Shared by `preview_retrieved_context` and `ai_chat_stream` so the
+/// preview always matches what is sent.
+pub(crate) async fn retrieve_context(
+    window_label: &str,
+    project_root: &str,
+    prompt: &str,
+    top_k: Option<usize>,
+    root_state: &tauri::State<'_, ProjectRootState>,
+) -> Result<RetrievedContext, String> {
+    let root = validate_knowledge_root(project_root, window_label, root_state).await?;
+    let project_root = project_root.to_string();
+    let prompt = prompt.to_string();
+    let k = top_k.unwrap_or(DEFAULT_TOP_K).clamp(1, 20);
+    tokio::task::spawn_blocking(move || {
+        let hits = search(&project_root, &prompt, k)?;
+        Ok(format_context(&root, &hits))
+    })
+    .await
+    .map_err(|e| format!("Retrieval failed: {}", e))?
+}
+
+// ── Commands ──
+
+#[tauri::command]
+pub async fn semantic_status(
+    window: tauri::WebviewWindow,
+    project_root: String,
+    root_state: tauri::State<'_, ProjectRootState>,
+) -> Result<SemanticStatus, String> {
+    validate_knowledge_root(&project_root, window.label(), &root_state).await?;
+    let conn = open_db(&project_root)?;
+    let count = |sql: &str| conn.query_row(sql, [], |r| r.get::<_, i64>(0)).unwrap_or(0);
+    Ok(SemanticStatus {
+        enabled: is_enabled(&conn),
+        embedder: EMBEDDER_ID.to_string(),
+        indexed_files: count("SELECT COUNT(*) FROM semantic_files"),
+        chunks: count("SELECT COUNT(*) FROM semantic_chunks"),
+    })
+}
+
+/// Turn semantic search on or off for a project. Disabling also drops the
+/// stored vectors so the feature leaves nothing behind.
+#[tauri::command]
+pub async fn semantic_set_enabled(
+    window: tauri::WebviewWindow,
+    project_root: String,
+    enabled: bool,
+    root_state: tauri::State<'_, ProjectRootState>,
+) -> Result<(), String> {
+    validate_knowledge_root(&project_root, window.label(), &root_state).await?;
+    let conn = open_db(&project_root)?;
+    conn.execute(
+        "INSERT OR REPLACE INTO project_meta (key, value) VALUES ('semantic_enabled', ?1)",
+        params![if enabled { "1" } else { "0" }],
+    )
+    .map_err(|e| e.to_string())?;
+    if !enabled {
+        conn.execute_batch("DELETE FROM semantic_chunks; DELETE FROM semantic_files;")
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn index_project(project_root: &str, root: &Path, app: &AppHandle, window_label: &str, op: &Operation) -> Result<(), String> {
+    let conn = open_db(project_root)?;
+    require_enabled(&conn)?;
+
+    let skip: HashSet<&str> = SKIP_DIRS.iter().copied().collect();
+    let mut files: Vec<PathBuf> = Vec::new();
+    walk_files(root, &skip, &mut files);
+    // Files in generated directories count as gone, so they're dropped below.
+    let generated_dirs = generated::GeneratedDirs::for_project(root);
+    files.retain(|f| !generated_dirs.contains(f));
+
+    // Forget files that disappeared since the last run.
+    let seen: HashSet<String> = files
+        .iter()
+        .map(|f| f.strip_prefix(root).unwrap_or(f).to_string_lossy().to_string())
+        .collect();
+    let stale: Vec<String> = {
+        let mut stmt = conn.prepare("SELECT path FROM semantic_files").map_err(|e| e.to_string())?;
+        let rows = stmt.query_map([], |r| r.get::<_, String>(0)).map_err(|e| e.to_string())?;
+        rows.flatten().filter(|p| !seen.contains(p)).collect()
+    };
+    for rel in stale {
+        remove_file(&conn, &rel)?;
+    }
+
+    let total = files.len() as u32;
+    events::SEMANTIC_INDEXING_PROGRESS.emit_to(app, window_label, super::IndexProgress { done: 0, total });
+    for (i, file) in files.iter().enumerate() {
+        if op.is_cancelled() {
+            break;
+        }
+        op.progress(i as u64, Some(total as u64), Some(file.strip_prefix(root).unwrap_or(file).to_string_lossy().into_owned()));
+        index_file(&conn, root, file)?;
+        if (i + 1) % 20 == 0 || i + 1 == files.len() {
+            events::SEMANTIC_INDEXING_PROGRESS.emit_to(app, window_label, super::IndexProgress { done: (i + 1) as u32, total });
+        }
+    }
+    Ok(())
+}
+
+/// Build or refresh the whole index. Unchanged files are skipped by
+/// content hash, so re-running after a restart is cheap. Emits
+/// `semantic-indexing-progress` with the same payload as `indexing-progress`.
+#[tauri::command]
+pub async fn semantic_index(
+    window: tauri::WebviewWindow,
+    project_root: String,
+    app: AppHandle,
+    root_state: tauri::State<'_, ProjectRootState>,
+) -> Result<(), String> {
+    let root = validate_knowledge_root(&project_root, window.label(), &root_state).await?;
+    let window_label = window.label().to_string();
+    let op = operations::manager().start(OperationKind::Index, &project_root, Some(window.label()), true);
+
+    tokio::task::spawn_blocking(move || op.track(index_project(&project_root, &root, &app, &window_label, &op)))
+        .await
+        .map_err(|e| format!("Indexing failed: {}", e))?
+}
+
+/// Re-embed `paths` (absolute, or relative to `root`) if semantic search
+/// is on for the project. Paths the full index wouldn't include — deleted,
+/// unreadable, generated or in a skipped folder — are dropped from it.
+/// Projects that never turned the feature on aren't touched.
+pub(crate) fn reindex_paths(project_root: &str, root: &Path, paths: &[PathBuf]) -> Result<(), String> {
+    if !db_path(project_root).exists() {
+        return Ok(());
+    }
+    let conn = open_db(project_root)?;
+    if !is_enabled(&conn) {
+        return Ok(());
+    }
+    let generated_dirs = generated::GeneratedDirs::for_project(root);
+    for file in paths {
+        let file = if file.is_absolute() { file.clone() } else { root.join(file) };
+        if !file.starts_with(root) {
+            continue;
+        }
+        if is_indexed(root, &file) && !generated_dirs.contains(&file) {
+            index_file(&conn, root, &file)?;
+        } else {
+            let rel = file.strip_prefix(root).unwrap_or(&file).to_string_lossy().to_string();
+            remove_file(&conn, &rel)?;
+        }
+    }
+    Ok(())
+}
+
+/// Incrementally re-embed `paths`; see `reindex_paths`. The file watcher
+/// already does this for the open project.
+#[tauri::command]
+pub async fn semantic_reindex_paths(
+    window: tauri::WebviewWindow,
+    project_root: String,
+    paths: Vec<String>,
+    root_state: tauri::State<'_, ProjectRootState>,
+) -> Result<(), String> {
+    let root = validate_knowledge_root(&project_root, window.label(), &root_state).await?;
+    let paths: Vec<PathBuf> = paths.into_iter().map(PathBuf::from).collect();
+    tokio::task::spawn_blocking(move || reindex_paths(&project_root, &root, &paths))
+        .await
+        .map_err(|e| format!("Indexing failed: {}", e))?
+}
+
+#[tauri::command]
+pub async fn semantic_search(
+    window: tauri::WebviewWindow,
+    project_root: String,
+    query: String,
+    limit: Option<usize>,
+    root_state: tauri::State<'_, ProjectRootState>,
+) -> Result<Vec<SemanticHit>, String> {
+    validate_knowledge_root(&project_root, window.label(), &root_state).await?;
+    let limit = limit.unwrap_or(20).min(100);
+    tokio::task::spawn_blocking(move || search(&project_root, &query, limit))
+        .await
+        .map_err(|e| format!("Search failed: {}", e))?
+}
+
+/// Show the chunks and context block that retrieval would attach to a
+/// chat turn with this prompt, without sending anything.
+#[tauri::command]
+pub async fn preview_retrieved_context(
+    window: tauri::WebviewWindow,
+    project_root: String,
+    prompt: String,
+    top_k: Option<usize>,
+    root_state: tauri::State<'_, ProjectRootState>,
+) -> Result<RetrievedContext, String> {
+    retrieve_context(window.label(), &project_root, &prompt, top_k, &root_state).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks_chunks_by_similarity_one_per_region() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        init_semantic_schema(&conn).unwrap();
+        for (path, start, end, v) in [
+            ("upload.ts", 1, 40, [0.9f32, 0.1, 0.0]),
+            ("upload.ts", 31, 70, [0.8, 0.2, 0.0]),
+            ("sidebar.rs", 1, 40, [0.0, 0.2, 0.9]),
+            ("retry.ts", 1, 20, [0.6, 0.6, 0.0]),
+        ] {
+            conn.execute(
+                "INSERT INTO semantic_chunks (path, start_line, end_line, vector) VALUES (?1, ?2, ?3, ?4)",
+                params![path, start, end, vector_to_blob(&v)],
+            )
+            .unwrap();
+        }
+        let hits = rank(&conn, Path::new("/nonexistent"), &[1.0, 0.0, 0.0], 5).unwrap();
+        let found: Vec<(&str, usize)> = hits.iter().map(|h| (h.path.as_str(), h.start_line)).collect();
+        assert_eq!(found, vec![("upload.ts", 1), ("retry.ts", 1)]);
+    }
+
+    #[test]
+    #[ignore = "downloads the embedding model and needs ONNX Runtime"]
+    fn embedding_ranks_related_code_above_unrelated() {
+        let v = embed(&[
+            "where do we retry failed uploads?".to_string(),
+            "async function uploadWithRetry(file) {\n  for (let attempt = 0; attempt < 3; attempt++) {\n    try { return await putObject(file) } catch (err) { if (isFailure(err)) continue }\n  }\n}".to_string(),
+            "fn render_sidebar(theme: &Theme) -> Html {\n    html! { <nav class={theme.class()} /> }\n}".to_string(),
+        ])
+        .unwrap();
+        assert!(cosine(&v[0], &v[1]) > cosine(&v[0], &v[2]));
+    }
+
+    #[test]
+    fn chunks_overlap_and_cover_the_file() {
+        let content: String = (1..=75).map(|i| format!("line {i}\n")).collect();
+        let chunks = chunk_file("a.rs", &content);
+        let ranges: Vec<(usize, usize)> = chunks.iter().map(|c| (c.start_line, c.end_line)).collect();
+        assert_eq!(ranges, vec![(1, 40), (31, 70), (61, 75)]);
+        assert!(chunks[0].text.starts_with("a.rs\n"));
+    }
+
+    #[test]
+    fn watcher_updates_cover_what_the_full_index_walks() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for rel in ["src/upload.ts", "node_modules/x/index.js", ".cache/a.ts", "logo.png"] {
+            std::fs::create_dir_all(root.join(rel).parent().unwrap()).unwrap();
+            std::fs::write(root.join(rel), "x").unwrap();
+        }
+        assert!(is_indexed(root, &root.join("src/upload.ts")));
+        assert!(!is_indexed(root, &root.join("node_modules/x/index.js")));
+        assert!(!is_indexed(root, &root.join(".cache/a.ts")));
+        assert!(!is_indexed(root, &root.join("logo.png")));
+        assert!(!is_indexed(root, &root.join("src/gone.ts")));
+    }
+
+    #[test]
+    fn vector_blob_round_trips() {
+        let v = vec![0.25f32, -1.5, 0.0, 3.0e-7];
+        assert_eq!(blob_to_vector(&vector_to_blob(&v)), v);
+    }
+
+    #[test]
+    fn format_context_cites_line_ranges() {
+        let dir = tempfile::tempdir().unwrap();
+        let body: String = (1..=10).map(|i| format!("line {i}\n")).collect();
+        std::fs::write(dir.path().join("a.rs"), body).unwrap();
+        let hits = vec![SemanticHit {
+            path: "a.rs".into(),
+            start_line: 3,
+            end_line: 4,
+            score: 0.5,
+            snippet: String::new(),
+        }];
+        let ctx = format_context(dir.path(), &hits);
+        assert_eq!(ctx.citations.len(), 1);
+        assert!(ctx.context.contains("### a.rs:3-4\n```\nline 3\nline 4\n```"));
+    }
+}
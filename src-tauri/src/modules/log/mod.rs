@@ -6,7 +6,8 @@ use std::sync::Mutex;
 
 const MAX_LOG_SIZE: u64 = 5 * 1024 * 1024; // 5 MB
 const MAX_HISTORY_FILES: usize = 10;
-const SECRET_PATTERN: &[&str] = &["api_key", "apikey", "api-key", "token", "password", "secret"];
+pub(crate) const SECRET_PATTERN: &[&str] =
+    &["api_key", "apikey", "api-key", "token", "password", "passphrase", "secret", "credential"];
 
 pub struct LogState {
     writer: Mutex<Option<BufWriter<File>>>,
@@ -154,8 +155,6 @@ pub fn log_record(
     err: Option<LogError>,
     state: tauri::State<'_, LogState>,
 ) {
-    rotate_if_needed(&state);
-
     let data_redacted = data.as_deref().map(redact_value);
 
     let entry = serde_json::json!({
@@ -169,6 +168,13 @@ pub fn log_record(
         })),
     });
 
+    append(&state, &entry, level == "error");
+}
+
+/// Write one entry to the log, for backend modules that log on their own.
+pub(crate) fn append(state: &LogState, entry: &serde_json::Value, flush: bool) {
+    rotate_if_needed(state);
+
     let mut guard = match state.writer.lock() {
         Ok(g) => g,
         Err(_) => return, // Poisoned mutex — drop the log silently
@@ -178,7 +184,7 @@ pub fn log_record(
         let line = entry.to_string();
         let _ = writeln!(writer, "{}", line);
         // Flush immediately for error-level logs
-        if level == "error" {
+        if flush {
             let _ = writer.flush();
         }
     }
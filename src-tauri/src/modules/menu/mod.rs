@@ -43,6 +43,9 @@ pub fn build_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<Menu<R>> {
         .item(&MenuItemBuilder::with_id("close_tab", "Close Tab").accelerator("CmdOrCtrl+W").build(app)?)
         .item(&MenuItemBuilder::with_id("close_window", "Close Window").accelerator("CmdOrCtrl+Shift+W").build(app)?)
         .item(&MenuItemBuilder::with_id("revert_file", "Revert File").build(app)?)
+        .separator()
+        .item(&MenuItemBuilder::with_id("export_session", "Export Session…").build(app)?)
+        .item(&MenuItemBuilder::with_id("import_session", "Import Session…").build(app)?)
         .build()?;
 
     // ── Edit submenu ──
@@ -129,6 +132,8 @@ pub fn handle_menu_event<R: Runtime>(app: &AppHandle<R>, event_id: &str) {
         "close_tab" => { emit_to_focused(app, "menu:close-tab"); }
         "close_window" => { emit_to_focused(app, "menu:close-window"); }
         "revert_file" => { emit_to_focused(app, "menu:revert-file"); }
+        "export_session" => { emit_to_focused(app, "menu:export-session"); }
+        "import_session" => { emit_to_focused(app, "menu:import-session"); }
         "find" => { emit_to_focused(app, "menu:find"); }
         "replace" => { emit_to_focused(app, "menu:replace"); }
         "undo_ai_edit" => { emit_to_focused(app, "menu:undo-last-ai-edit"); }
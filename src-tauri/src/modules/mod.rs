@@ -1,11 +1,34 @@
+pub mod a11y;
 pub mod ai;
+pub mod code_image;
+pub mod color;
+pub mod config_tools;
+pub mod data;
+pub mod diagram;
+pub mod emmet;
+pub mod events;
 pub mod fs;
 pub mod git;
 pub mod graph;
+pub mod i18n;
+pub mod issues;
 pub mod knowledge;
 pub mod log;
 pub mod menu;
+pub mod net;
+pub mod operations;
+pub mod policy;
+pub mod preview_server;
+pub mod process;
+pub mod profile;
+pub mod release;
+pub mod scaffold;
+pub mod search;
 pub mod session;
+pub mod share_server;
 pub mod shell;
+pub mod stats;
 pub mod symbols;
+pub mod sync;
+pub mod watchdog;
 pub mod window_mgr;
@@ -0,0 +1,296 @@
+//! Long-running background work, tracked in one place.
+//!
+//! Clones, project searches, indexing, copies, archive exports and AI
+//! requests register an `Operation` while they run, so the frontend can
+//! show them all in one background tasks popover: `list_operations` gives
+//! a snapshot and `operation-progress` events follow each one from start
+//! to end. `cancel_operation` asks an operation to stop; each checks at its
+//! own safe points (between files, between chunks of output) and then ends
+//! in the `cancelled` state.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tokio::sync::watch;
+use ts_rs::TS;
+
+use crate::modules::events;
+
+/// Minimum gap between progress events for one operation.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationKind {
+    Clone,
+    Search,
+    Index,
+    Copy,
+    Archive,
+    Ai,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationState {
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, TS)]
+pub struct OperationInfo {
+    #[ts(type = "number")]
+    pub id: u64,
+    pub kind: OperationKind,
+    /// What is being worked on, e.g. the repository being cloned.
+    pub title: String,
+    /// Label of the window that started it; `None` for app-wide work.
+    pub window: Option<String>,
+    /// Units done so far (files, objects, bytes — whatever the kind counts).
+    #[ts(type = "number")]
+    pub done: u64,
+    /// Units in all, once known.
+    #[ts(type = "number | null")]
+    pub total: Option<u64>,
+    /// The current step, e.g. the file being copied.
+    pub message: Option<String>,
+    pub cancellable: bool,
+    /// Unix milliseconds.
+    #[ts(type = "number")]
+    pub started_at: u64,
+}
+
+/// Payload of `operation-progress`.
+#[derive(Serialize, Clone, Debug, PartialEq, TS)]
+pub struct OperationEvent {
+    #[serde(flatten)]
+    pub info: OperationInfo,
+    pub state: OperationState,
+    pub error: Option<String>,
+}
+
+struct Entry {
+    info: OperationInfo,
+    cancel: watch::Sender<bool>,
+    last_emit: Instant,
+}
+
+type Emit = Box<dyn Fn(&OperationEvent) + Send + Sync>;
+
+pub struct OperationManager {
+    next_id: AtomicU64,
+    active: Mutex<HashMap<u64, Entry>>,
+    emit: Emit,
+}
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// Let operations send events. Called once from setup; before that (and
+/// in tests) they run silently.
+pub fn init(app: AppHandle) {
+    let _ = APP.set(app);
+}
+
+pub fn manager() -> &'static OperationManager {
+    static MANAGER: OnceLock<OperationManager> = OnceLock::new();
+    MANAGER.get_or_init(|| {
+        OperationManager::new(Box::new(|event| {
+            let Some(app) = APP.get() else { return };
+            match &event.info.window {
+                Some(label) => events::OPERATION_PROGRESS.emit_to(app, label, event.clone()),
+                None => events::OPERATION_PROGRESS.emit(app, event.clone()),
+            }
+        }))
+    })
+}
+
+impl OperationManager {
+    fn new(emit: Emit) -> Self {
+        Self { next_id: AtomicU64::new(1), active: Mutex::new(HashMap::new()), emit }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<u64, Entry>> {
+        self.active.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Register an operation. It stays listed until the returned handle is
+    /// dropped.
+    pub fn start(&'static self, kind: OperationKind, title: &str, window: Option<&str>, cancellable: bool) -> Operation {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let info = OperationInfo {
+            id,
+            kind,
+            title: title.to_string(),
+            window: window.map(str::to_string),
+            done: 0,
+            total: None,
+            message: None,
+            cancellable,
+            started_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+        };
+        let (cancel, cancelled) = watch::channel(false);
+        (self.emit)(&OperationEvent { info: info.clone(), state: OperationState::Running, error: None });
+        self.lock().insert(id, Entry { info, cancel, last_emit: Instant::now() });
+        Operation { manager: self, id, cancelled, error: Mutex::new(None) }
+    }
+
+    pub fn list(&self) -> Vec<OperationInfo> {
+        let mut list: Vec<OperationInfo> = self.lock().values().map(|e| e.info.clone()).collect();
+        list.sort_by_key(|o| o.id);
+        list
+    }
+
+    /// Ask operation `id` to stop. Returns false if it already ended or
+    /// can't be cancelled.
+    pub fn cancel(&self, id: u64) -> bool {
+        match self.lock().get(&id) {
+            Some(entry) if entry.info.cancellable => entry.cancel.send(true).is_ok(),
+            _ => false,
+        }
+    }
+}
+
+/// A registered operation. Dropping it ends the operation: `done`, or
+/// `failed` if an error went through `track`, or `cancelled` once a cancel
+/// was requested.
+pub struct Operation {
+    manager: &'static OperationManager,
+    id: u64,
+    cancelled: watch::Receiver<bool>,
+    error: Mutex<Option<String>>,
+}
+
+impl Operation {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.cancelled.borrow()
+    }
+
+    /// Resolves once a cancel is requested; never, if none is.
+    pub async fn cancelled(&self) {
+        let mut rx = self.cancelled.clone();
+        if rx.wait_for(|c| *c).await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
+
+    /// Mark the operation cancelled from its own side, e.g. when the work
+    /// was stopped some other way.
+    pub fn cancel(&self) {
+        self.manager.cancel(self.id);
+    }
+
+    /// Record progress. Events are rate limited, except for the last unit.
+    pub fn progress(&self, done: u64, total: Option<u64>, message: Option<String>) {
+        let event = {
+            let mut active = self.manager.lock();
+            let Some(entry) = active.get_mut(&self.id) else { return };
+            entry.info.done = done;
+            entry.info.total = total;
+            entry.info.message = message;
+            if entry.last_emit.elapsed() < PROGRESS_INTERVAL && Some(done) != total {
+                return;
+            }
+            entry.last_emit = Instant::now();
+            OperationEvent { info: entry.info.clone(), state: OperationState::Running, error: None }
+        };
+        (self.manager.emit)(&event);
+    }
+
+    /// Pass `result` through, remembering its error for the final event.
+    pub fn track<T, E: ToString>(&self, result: Result<T, E>) -> Result<T, E> {
+        if let Err(e) = &result {
+            *self.error.lock().unwrap_or_else(|e| e.into_inner()) = Some(e.to_string());
+        }
+        result
+    }
+}
+
+impl Drop for Operation {
+    fn drop(&mut self) {
+        let Some(entry) = self.manager.lock().remove(&self.id) else { return };
+        let error = self.error.lock().unwrap_or_else(|e| e.into_inner()).take();
+        let state = match (&error, self.is_cancelled()) {
+            (_, true) => OperationState::Cancelled,
+            (Some(_), false) => OperationState::Failed,
+            (None, false) => OperationState::Done,
+        };
+        (self.manager.emit)(&OperationEvent { info: entry.info, state, error });
+    }
+}
+
+// ── Commands ──
+
+#[tauri::command]
+pub fn list_operations() -> Vec<OperationInfo> {
+    manager().list()
+}
+
+/// Ask a running operation to stop. Returns false if it already ended or
+/// can't be cancelled.
+#[tauri::command]
+pub fn cancel_operation(id: u64) -> bool {
+    manager().cancel(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn fresh() -> (&'static OperationManager, Arc<Mutex<Vec<OperationEvent>>>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let manager = OperationManager::new(Box::new(move |e| sink.lock().unwrap().push(e.clone())));
+        (Box::leak(Box::new(manager)), events)
+    }
+
+    #[tokio::test]
+    async fn reports_progress_and_how_operations_end() {
+        let (m, events) = fresh();
+        let copy = m.start(OperationKind::Copy, "src", Some("main"), true);
+        let index = m.start(OperationKind::Index, "proj", None, false);
+        assert_eq!(m.list().iter().map(|o| o.kind).collect::<Vec<_>>(), [OperationKind::Copy, OperationKind::Index]);
+
+        copy.progress(1, Some(3), Some("a.rs".into()));
+        copy.progress(3, Some(3), None);
+        assert_eq!(m.list()[0].done, 3);
+        assert!(!m.cancel(index.id()), "indexing isn't cancellable");
+        let _ = index.track(Err::<(), _>("disk full"));
+        drop(index);
+
+        assert!(m.cancel(copy.id()));
+        tokio::time::timeout(Duration::from_secs(1), copy.cancelled()).await.unwrap();
+        assert!(copy.is_cancelled());
+        let copy_id = copy.id();
+        drop(copy);
+        assert!(m.list().is_empty());
+        assert!(!m.cancel(copy_id));
+
+        let events = events.lock().unwrap();
+        let states: Vec<_> = events.iter().map(|e| (e.info.kind, e.state, e.info.done)).collect();
+        assert_eq!(
+            states,
+            [
+                (OperationKind::Copy, OperationState::Running, 0),
+                (OperationKind::Index, OperationState::Running, 0),
+                // The first update fell within the rate limit; the last unit always goes out.
+                (OperationKind::Copy, OperationState::Running, 3),
+                (OperationKind::Index, OperationState::Failed, 0),
+                (OperationKind::Copy, OperationState::Cancelled, 3),
+            ]
+        );
+        assert_eq!(events[3].error.as_deref(), Some("disk full"));
+    }
+}
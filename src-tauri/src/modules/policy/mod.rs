@@ -0,0 +1,370 @@
+//! Command policy for classroom and kiosk setups.
+//!
+//! Whole categories of commands — network access, the terminal, pushing
+//! to git remotes, AI assistance — can be switched off per workspace.
+//! Policies come from `~/.leo-ide/policy.json`, which can disable
+//! categories everywhere (`disabled`) or for given project roots
+//! (`projects`), and from a project's own `.leo-ide/policy.json`. Sources
+//! can only take permissions away: a category is off if any of them turns
+//! it off.
+//!
+//! ```json
+//! { "disabled": ["ai"], "projects": { "/home/pupil/lab1": { "disabled": ["network", "terminal"] } } }
+//! ```
+//!
+//! `wrap` checks every IPC call against the calling window's project
+//! before the command runs and rejects denied ones with a `policy-denied`
+//! `Localized` error, so commands don't check for themselves.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::ipc::{Invoke, InvokeBody};
+use tauri::{Manager, Runtime};
+
+use crate::modules::fs::ProjectRootState;
+use crate::modules::i18n::Localized;
+use crate::modules::{ai, diagram, git, issues, net, share_server, shell, sync};
+
+/// How long a loaded policy is trusted before the files are read again.
+const CACHE_TTL: Duration = Duration::from_secs(2);
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Category {
+    Network,
+    Terminal,
+    GitPush,
+    Ai,
+}
+
+impl Category {
+    fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "network" => Some(Self::Network),
+            "terminal" => Some(Self::Terminal),
+            "git_push" => Some(Self::GitPush),
+            "ai" => Some(Self::Ai),
+            _ => None,
+        }
+    }
+
+    fn id(self) -> &'static str {
+        match self {
+            Self::Network => "network",
+            Self::Terminal => "terminal",
+            Self::GitPush => "git_push",
+            Self::Ai => "ai",
+        }
+    }
+}
+
+/// Each module's `COMMAND_CATEGORIES`, declared next to its commands.
+const DECLARED: &[&[(&str, &[Category])]] = &[
+    ai::COMMAND_CATEGORIES,
+    diagram::COMMAND_CATEGORIES,
+    git::COMMAND_CATEGORIES,
+    issues::COMMAND_CATEGORIES,
+    net::COMMAND_CATEGORIES,
+    share_server::COMMAND_CATEGORIES,
+    shell::COMMAND_CATEGORIES,
+    sync::COMMAND_CATEGORIES,
+];
+
+/// The categories a call to `command` with `args` falls under.
+pub fn categories(command: &str, args: &Value) -> Vec<Category> {
+    use Category::*;
+    // Creating a branch only reaches the remote when it sets the upstream.
+    if command == "git_create_branch_from_template" {
+        let upstream = args["params"]["set_upstream"] == Value::Bool(true);
+        return if upstream { vec![Network, GitPush] } else { Vec::new() };
+    }
+    DECLARED
+        .iter()
+        .flat_map(|declared| declared.iter())
+        .find(|(name, _)| *name == command)
+        .map(|(_, categories)| categories.to_vec())
+        .unwrap_or_default()
+}
+
+// ── Policy files ──
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(default)]
+struct ProjectPolicy {
+    disabled: Vec<String>,
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(default)]
+struct UserPolicy {
+    disabled: Vec<String>,
+    projects: HashMap<String, ProjectPolicy>,
+}
+
+/// The categories turned off for one workspace.
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct CommandPolicy {
+    pub disabled: BTreeSet<Category>,
+}
+
+impl CommandPolicy {
+    /// The first category of `command` that is turned off, if any.
+    pub fn denies(&self, command: &str, args: &Value) -> Option<Category> {
+        categories(command, args).into_iter().find(|c| self.disabled.contains(c))
+    }
+}
+
+fn read_json<T: DeserializeOwned + Default>(path: &Path) -> T {
+    let Ok(text) = std::fs::read_to_string(path) else { return T::default() };
+    serde_json::from_str(&text).unwrap_or_else(|e| {
+        log::warn!("ignoring invalid command policy {}: {}", path.display(), e);
+        T::default()
+    })
+}
+
+fn user_policy_path() -> PathBuf {
+    dirs::home_dir().unwrap_or_default().join(".leo-ide").join("policy.json")
+}
+
+fn resolve(user: &UserPolicy, root: Option<&Path>, project: &ProjectPolicy) -> CommandPolicy {
+    let for_root = root.and_then(|r| user.projects.get(r.to_string_lossy().as_ref()));
+    let names = user.disabled.iter().chain(for_root.into_iter().flat_map(|p| &p.disabled)).chain(&project.disabled);
+    let disabled = names
+        .filter_map(|name| {
+            let category = Category::parse(name);
+            if category.is_none() {
+                log::warn!("unknown command policy category: {}", name);
+            }
+            category
+        })
+        .collect();
+    CommandPolicy { disabled }
+}
+
+/// Resolved policies by project root, with when each was loaded.
+type PolicyCache = Mutex<HashMap<Option<PathBuf>, (Instant, CommandPolicy)>>;
+
+/// The policy for a window with `root` open (or none), cached briefly.
+pub fn policy_for(root: Option<&Path>) -> CommandPolicy {
+    static CACHE: OnceLock<PolicyCache> = OnceLock::new();
+    let key = root.map(Path::to_path_buf);
+    let mut cache = CACHE.get_or_init(Default::default).lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((loaded, policy)) = cache.get(&key) {
+        if loaded.elapsed() < CACHE_TTL {
+            return policy.clone();
+        }
+    }
+    let project = root.map(|r| read_json(&r.join(".leo-ide").join("policy.json"))).unwrap_or_default();
+    let policy = resolve(&read_json(&user_policy_path()), root, &project);
+    cache.insert(key, (Instant::now(), policy.clone()));
+    policy
+}
+
+fn window_root<R: Runtime>(app: &tauri::AppHandle<R>, label: &str) -> Option<PathBuf> {
+    let state = app.try_state::<ProjectRootState>()?;
+    let map = state.blocking_read();
+    map.get(label).and_then(|o| o.clone())
+}
+
+// ── Dispatch ──
+
+/// Wrap the app's invoke handler so calls the calling window's policy
+/// denies are rejected before they reach their command.
+pub fn wrap<R: Runtime>(
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    static NO_ARGS: Value = Value::Null;
+    move |invoke: Invoke<R>| {
+        let command = invoke.message.command().to_string();
+        let args = match invoke.message.payload() {
+            InvokeBody::Json(value) => value,
+            InvokeBody::Raw(_) => &NO_ARGS,
+        };
+        if categories(&command, args).is_empty() {
+            return handler(invoke);
+        }
+        let webview = invoke.message.webview_ref();
+        let root = window_root(webview.app_handle(), webview.label());
+        match policy_for(root.as_deref()).denies(&command, args) {
+            Some(category) => {
+                log::info!("policy denied {} ({})", command, category.id());
+                let error =
+                    Localized::new("policy-denied", &[("category", category.id().to_string()), ("command", command)]);
+                invoke.resolver.reject(error);
+                true
+            }
+            None => handler(invoke),
+        }
+    }
+}
+
+/// The categories turned off for the calling window, so the UI can hide
+/// what it can't use.
+#[tauri::command]
+pub fn get_command_policy(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+) -> CommandPolicy {
+    let root = state.blocking_read().get(window.label()).and_then(|o| o.clone());
+    policy_for(root.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn combines_sources_and_only_ever_restricts() {
+        let user = UserPolicy {
+            disabled: names(&["ai"]),
+            projects: HashMap::from([("/lab".to_string(), ProjectPolicy { disabled: names(&["Terminal", "bogus"]) })]),
+        };
+        let project = ProjectPolicy { disabled: names(&["git-push"]) };
+        let policy = resolve(&user, Some(Path::new("/lab")), &project);
+        assert_eq!(policy.disabled, BTreeSet::from([Category::Terminal, Category::GitPush, Category::Ai]));
+        let elsewhere = resolve(&user, Some(Path::new("/other")), &ProjectPolicy::default());
+        assert_eq!(elsewhere.disabled, BTreeSet::from([Category::Ai]));
+    }
+
+    #[test]
+    fn denies_by_command_category() {
+        let policy = CommandPolicy { disabled: BTreeSet::from([Category::GitPush]) };
+        assert_eq!(policy.denies("git_push", &Value::Null), Some(Category::GitPush));
+        assert_eq!(policy.denies("git_fetch", &Value::Null), None);
+        assert_eq!(policy.denies("read_file_content", &Value::Null), None);
+
+        let branch = |upstream| serde_json::json!({ "params": { "repo_path": "/r", "set_upstream": upstream } });
+        assert_eq!(policy.denies("git_create_branch_from_template", &branch(true)), Some(Category::GitPush));
+        assert_eq!(policy.denies("git_create_branch_from_template", &branch(false)), None);
+
+        let offline = CommandPolicy { disabled: BTreeSet::from([Category::Network]) };
+        assert_eq!(offline.denies("git_push", &Value::Null), Some(Category::Network));
+        for command in ["render_diagram", "create_issue_from_todo", "export_shareable_diff", "sync_now"] {
+            assert_eq!(offline.denies(command, &Value::Null), Some(Category::Network), "{}", command);
+        }
+    }
+
+    /// Functions in the crate's sources, with the paths they call.
+    mod sources {
+        use std::path::{Path, PathBuf};
+        use syn::visit::Visit;
+
+        pub struct Func {
+            pub name: String,
+            pub file: PathBuf,
+            pub command: bool,
+            pub calls: Vec<Vec<String>>,
+        }
+
+        #[derive(Default)]
+        struct Calls(Vec<Vec<String>>);
+
+        impl<'a> Visit<'a> for Calls {
+            fn visit_expr_call(&mut self, call: &'a syn::ExprCall) {
+                if let syn::Expr::Path(p) = &*call.func {
+                    self.0.push(p.path.segments.iter().map(|s| s.ident.to_string()).collect());
+                }
+                syn::visit::visit_expr_call(self, call);
+            }
+        }
+
+        fn is_test(attrs: &[syn::Attribute]) -> bool {
+            attrs.iter().any(|a| a.path().is_ident("cfg") || a.path().is_ident("test"))
+        }
+
+        fn items(list: &[syn::Item], file: &Path, out: &mut Vec<Func>) {
+            for item in list {
+                match item {
+                    syn::Item::Fn(f) if !is_test(&f.attrs) => {
+                        let command =
+                            f.attrs.iter().any(|a| a.path().segments.last().is_some_and(|s| s.ident == "command"));
+                        let mut calls = Calls::default();
+                        calls.visit_block(&f.block);
+                        out.push(Func { name: f.sig.ident.to_string(), file: file.to_path_buf(), command, calls: calls.0 });
+                    }
+                    syn::Item::Mod(m) if !is_test(&m.attrs) => {
+                        if let Some((_, inner)) = &m.content {
+                            items(inner, file, out);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        pub fn functions(dir: &Path, out: &mut Vec<Func>) {
+            for entry in std::fs::read_dir(dir).unwrap().flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    functions(&path, out);
+                } else if path.extension().is_some_and(|e| e == "rs") {
+                    let file = syn::parse_file(&std::fs::read_to_string(&path).unwrap()).unwrap();
+                    items(&file.items, &path, out);
+                }
+            }
+        }
+
+        /// Whether a call to `path` from `from` may be `f`: same name, and
+        /// either qualified by `f`'s module path, or unqualified and in the
+        /// same file (or the only function of that name).
+        pub fn may_call(from: &Path, path: &[String], f: &Func, same_name: usize) -> bool {
+            let Some((name, quals)) = path.split_last() else { return false };
+            if *name != f.name {
+                return false;
+            }
+            let quals: Vec<&str> =
+                quals.iter().map(String::as_str).filter(|q| !matches!(*q, "crate" | "super" | "self" | "modules")).collect();
+            if quals.is_empty() {
+                return same_name == 1 || f.file == from;
+            }
+            let module = f.file.with_extension("");
+            let parts: Vec<String> = module.iter().map(|c| c.to_string_lossy().into_owned()).filter(|c| c != "mod").collect();
+            parts.ends_with(&quals.iter().map(|q| q.to_string()).collect::<Vec<_>>())
+        }
+    }
+
+    #[test]
+    fn every_command_that_goes_online_has_a_category() {
+        let mut fns = Vec::new();
+        sources::functions(&Path::new(env!("CARGO_MANIFEST_DIR")).join("src"), &mut fns);
+        let mut same_name: HashMap<&str, usize> = HashMap::new();
+        for f in &fns {
+            *same_name.entry(f.name.as_str()).or_default() += 1;
+        }
+        let mut online = vec![false; fns.len()];
+        loop {
+            let mut changed = false;
+            for (i, f) in fns.iter().enumerate() {
+                let reaches = f.calls.iter().any(|call| {
+                    call.last().is_some_and(|n| n == "ensure_online")
+                        || fns.iter().enumerate().any(|(j, g)| online[j] && sources::may_call(&f.file, call, g, same_name[g.name.as_str()]))
+                });
+                if reaches && !online[i] {
+                    online[i] = true;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        let upstream = serde_json::json!({ "params": { "set_upstream": true } });
+        let missing: Vec<&str> = fns
+            .iter()
+            .zip(&online)
+            .filter(|(f, online)| **online && f.command && categories(&f.name, &upstream).is_empty())
+            .map(|(f, _)| f.name.as_str())
+            .collect();
+        assert!(missing.is_empty(), "commands that call net::ensure_online without a policy category: {:?}", missing);
+        assert!(fns.iter().zip(&online).any(|(f, online)| *online && f.name == "render_diagram"));
+    }
+}
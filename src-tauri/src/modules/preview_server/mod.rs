@@ -0,0 +1,321 @@
+//! Local HTTP server for previewing static sites.
+//!
+//! `start_preview` serves a folder of the project on `127.0.0.1` at a free
+//! port, one server per window. HTML responses get a small script injected
+//! that listens on `/__livereload` (server-sent events); a file watcher on
+//! the served folder triggers a reload in every open preview whenever
+//! something changes.
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, oneshot};
+
+use crate::modules::fs::{project_root_async, resolve_within_root, ProjectRootState};
+
+const LIVE_RELOAD_PATH: &str = "/__livereload";
+const LIVE_RELOAD_SCRIPT: &str = "<script>new EventSource('/__livereload').onmessage = () => location.reload();</script>";
+const MAX_REQUEST_HEAD: usize = 8 * 1024;
+/// Changes arriving this close together cause one reload.
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(150);
+
+// ── State ──
+
+struct PreviewServer {
+    root: PathBuf,
+    port: u16,
+    shutdown: Option<oneshot::Sender<()>>,
+    _watcher: RecommendedWatcher,
+}
+
+impl Drop for PreviewServer {
+    fn drop(&mut self) {
+        if let Some(tx) = self.shutdown.take() {
+            let _ = tx.send(());
+        }
+    }
+}
+
+/// Running preview servers, keyed by window label.
+#[derive(Default)]
+pub struct PreviewServerState(Mutex<HashMap<String, PreviewServer>>);
+
+impl PreviewServerState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn remove_window(&self, label: &str) {
+        if let Ok(mut servers) = self.0.lock() {
+            servers.remove(label);
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PreviewInfo {
+    pub url: String,
+    pub port: u16,
+    pub root: String,
+}
+
+// ── HTTP ──
+
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref() {
+        Some("html" | "htm") => "text/html; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("js" | "mjs") => "text/javascript; charset=utf-8",
+        Some("json" | "map") => "application/json",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("ico") => "image/x-icon",
+        Some("wasm") => "application/wasm",
+        Some("woff") => "font/woff",
+        Some("woff2") => "font/woff2",
+        Some("txt" | "md") => "text/plain; charset=utf-8",
+        Some("xml") => "application/xml",
+        Some("pdf") => "application/pdf",
+        _ => "application/octet-stream",
+    }
+}
+
+pub(crate) fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok()).and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                out.push(b);
+                i += 3;
+            }
+            (b, _) => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Map a request path to a file under `root`: directories serve their
+/// `index.html`, and `/about` falls back to `/about.html`.
+fn resolve_request(root: &Path, url_path: &str) -> Option<PathBuf> {
+    let path = percent_decode(url_path.split(['?', '#']).next().unwrap_or("/"));
+    let rel = Path::new(path.trim_start_matches('/'));
+    if rel.components().any(|c| !matches!(c, Component::Normal(_))) {
+        return None;
+    }
+    let mut file = root.join(rel);
+    if file.is_dir() {
+        file = file.join("index.html");
+    } else if !file.exists() && file.extension().is_none() {
+        file.set_extension("html");
+    }
+    // Refuse symlinks that lead out of the served folder.
+    let canonical = file.canonicalize().ok()?;
+    (canonical.starts_with(root) && canonical.is_file()).then_some(canonical)
+}
+
+fn inject_live_reload(html: &[u8]) -> Vec<u8> {
+    let text = String::from_utf8_lossy(html);
+    match text.to_ascii_lowercase().rfind("</body>") {
+        Some(i) => format!("{}{}{}", &text[..i], LIVE_RELOAD_SCRIPT, &text[i..]).into_bytes(),
+        None => format!("{}{}", text, LIVE_RELOAD_SCRIPT).into_bytes(),
+    }
+}
+
+/// Status line, headers and body for a GET of `url_path`.
+fn respond(root: &Path, url_path: &str) -> (&'static str, &'static str, Vec<u8>) {
+    let Some(file) = resolve_request(root, url_path) else {
+        return ("404 Not Found", "text/plain; charset=utf-8", b"Not found".to_vec());
+    };
+    match std::fs::read(&file) {
+        Ok(body) => {
+            let kind = content_type(&file);
+            let body = if kind.starts_with("text/html") { inject_live_reload(&body) } else { body };
+            ("200 OK", kind, body)
+        }
+        Err(_) => ("500 Internal Server Error", "text/plain; charset=utf-8", b"Failed to read file".to_vec()),
+    }
+}
+
+pub(crate) async fn read_head(stream: &mut TcpStream) -> std::io::Result<String> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") && head.len() < MAX_REQUEST_HEAD {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+    Ok(String::from_utf8_lossy(&head).into_owned())
+}
+
+async fn live_reload(mut stream: TcpStream, mut reload: broadcast::Receiver<()>) -> std::io::Result<()> {
+    stream
+        .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n: connected\n\n")
+        .await?;
+    loop {
+        match reload.recv().await {
+            Ok(()) | Err(broadcast::error::RecvError::Lagged(_)) => {
+                tokio::time::sleep(RELOAD_DEBOUNCE).await;
+                while reload.try_recv().is_ok() {}
+                stream.write_all(b"data: reload\n\n").await?;
+            }
+            Err(broadcast::error::RecvError::Closed) => return Ok(()),
+        }
+    }
+}
+
+async fn handle(mut stream: TcpStream, root: Arc<PathBuf>, reload: broadcast::Receiver<()>) -> std::io::Result<()> {
+    let head = read_head(&mut stream).await?;
+    let mut parts = head.lines().next().unwrap_or_default().split_whitespace();
+    let (method, url_path) = (parts.next().unwrap_or_default(), parts.next().unwrap_or("/"));
+    if method != "GET" && method != "HEAD" {
+        stream.write_all(b"HTTP/1.1 405 Method Not Allowed\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").await?;
+        return Ok(());
+    }
+    if url_path == LIVE_RELOAD_PATH {
+        return live_reload(stream, reload).await;
+    }
+    let (status, kind, body) = tokio::task::block_in_place(|| respond(&root, url_path));
+    let header = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        status,
+        kind,
+        body.len()
+    );
+    stream.write_all(header.as_bytes()).await?;
+    if method == "GET" {
+        stream.write_all(&body).await?;
+    }
+    stream.shutdown().await
+}
+
+fn watch(root: &Path, reload: broadcast::Sender<()>) -> Result<RecommendedWatcher, String> {
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else { return };
+        if event.kind.is_access() {
+            return;
+        }
+        let relevant = event.paths.iter().any(|p| !p.components().any(|c| c.as_os_str() == ".git" || c.as_os_str() == "node_modules"));
+        if relevant {
+            let _ = reload.send(());
+        }
+    })
+    .map_err(|e| format!("Failed to watch files: {}", e))?;
+    watcher.watch(root, RecursiveMode::Recursive).map_err(|e| format!("Failed to watch files: {}", e))?;
+    Ok(watcher)
+}
+
+// ── Commands ──
+
+/// Serve `path` (a folder in the project; the project root when omitted)
+/// for the calling window. Replaces the window's running preview.
+#[tauri::command]
+pub async fn start_preview(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    servers: tauri::State<'_, PreviewServerState>,
+    path: Option<String>,
+) -> Result<PreviewInfo, String> {
+    let project = project_root_async(window.label(), &state).await?;
+    let root = match path {
+        Some(p) => resolve_within_root(&p, &project)?,
+        None => project,
+    };
+    if !root.is_dir() {
+        return Err("Preview folder does not exist".to_string());
+    }
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.map_err(|e| format!("Failed to start preview server: {}", e))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+    let (reload_tx, _) = broadcast::channel(16);
+    let watcher = watch(&root, reload_tx.clone())?;
+    let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
+
+    let served = Arc::new(root.clone());
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = &mut shutdown_rx => break,
+                accepted = listener.accept() => {
+                    let Ok((stream, _)) = accepted else { continue };
+                    let (root, reload) = (served.clone(), reload_tx.subscribe());
+                    tauri::async_runtime::spawn(async move {
+                        let _ = handle(stream, root, reload).await;
+                    });
+                }
+            }
+        }
+    });
+
+    let server = PreviewServer { root: root.clone(), port, shutdown: Some(shutdown_tx), _watcher: watcher };
+    servers.0.lock().map_err(|e| e.to_string())?.insert(window.label().to_string(), server);
+    Ok(PreviewInfo { url: format!("http://127.0.0.1:{}/", port), port, root: root.to_string_lossy().into_owned() })
+}
+
+/// Stop the calling window's preview server. Returns false if none was
+/// running.
+#[tauri::command]
+pub fn stop_preview(window: tauri::WebviewWindow, servers: tauri::State<'_, PreviewServerState>) -> bool {
+    servers.0.lock().map(|mut s| s.remove(window.label()).is_some()).unwrap_or(false)
+}
+
+/// The calling window's running preview, if any.
+#[tauri::command]
+pub fn get_preview(window: tauri::WebviewWindow, servers: tauri::State<'_, PreviewServerState>) -> Option<PreviewInfo> {
+    let servers = servers.0.lock().ok()?;
+    servers.get(window.label()).map(|s| PreviewInfo {
+        url: format!("http://127.0.0.1:{}/", s.port),
+        port: s.port,
+        root: s.root.to_string_lossy().into_owned(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_requests_inside_root_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::create_dir(root.join("docs")).unwrap();
+        std::fs::write(root.join("index.html"), "<html><body>hi</body></html>").unwrap();
+        std::fs::write(root.join("docs/about.html"), "about").unwrap();
+        std::fs::write(root.join("my page.css"), "p {}").unwrap();
+
+        assert_eq!(resolve_request(&root, "/"), Some(root.join("index.html")));
+        assert_eq!(resolve_request(&root, "/docs/about?x=1"), Some(root.join("docs/about.html")));
+        assert_eq!(resolve_request(&root, "/my%20page.css"), Some(root.join("my page.css")));
+        assert_eq!(resolve_request(&root, "/../etc/passwd"), None);
+        assert_eq!(resolve_request(&root, "/missing.js"), None);
+    }
+
+    #[test]
+    fn injects_live_reload_into_html() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::write(root.join("index.html"), "<html><BODY>hi</BODY></html>").unwrap();
+        std::fs::write(root.join("app.js"), "let a;").unwrap();
+
+        let (status, kind, body) = respond(&root, "/index.html");
+        assert_eq!((status, kind), ("200 OK", "text/html; charset=utf-8"));
+        assert_eq!(String::from_utf8(body).unwrap(), format!("<html><BODY>hi{}</BODY></html>", LIVE_RELOAD_SCRIPT));
+        assert_eq!(respond(&root, "/app.js").2, b"let a;");
+        assert_eq!(respond(&root, "/nope").0, "404 Not Found");
+    }
+}
@@ -0,0 +1,248 @@
+//! Process execution behind a swappable runner.
+//!
+//! Git and the other command-line tools are run through `ProcessCommand`,
+//! which mirrors the parts of `std::process::Command` we use (`args`,
+//! `current_dir`, `output`). It hands the call to the current
+//! `ProcessRunner`: normally `SystemRunner`, which really spawns the
+//! program. Tests install a `MockRunner` with `with_runner` to script
+//! outputs and record every invocation, so git workflows can be exercised
+//! without a repository or network.
+//!
+//! The override is per thread, so it covers code the test calls directly
+//! but not work handed to another thread. Detached launches (revealing a
+//! file in the OS file manager) and async children (`git clone`, the shell
+//! runner, diagram renderers) still use `Command` directly.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::ffi::{OsStr, OsString};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Output};
+use std::sync::{Arc, Mutex};
+
+/// A program invocation as seen by a runner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessCall {
+    pub program: String,
+    pub args: Vec<String>,
+    pub cwd: Option<PathBuf>,
+}
+
+pub trait ProcessRunner: Send + Sync {
+    fn output(&self, program: &OsStr, args: &[OsString], cwd: Option<&Path>) -> io::Result<Output>;
+}
+
+/// Runs programs for real.
+pub struct SystemRunner;
+
+impl ProcessRunner for SystemRunner {
+    fn output(&self, program: &OsStr, args: &[OsString], cwd: Option<&Path>) -> io::Result<Output> {
+        let mut cmd = Command::new(program);
+        cmd.args(args);
+        if let Some(cwd) = cwd {
+            cmd.current_dir(cwd);
+        }
+        cmd.output()
+    }
+}
+
+thread_local! {
+    static RUNNER: RefCell<Option<Arc<dyn ProcessRunner>>> = const { RefCell::new(None) };
+}
+
+/// Run `f` with `runner` handling every `ProcessCommand` on this thread.
+pub fn with_runner<T>(runner: Arc<dyn ProcessRunner>, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<Arc<dyn ProcessRunner>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            RUNNER.with(|r| *r.borrow_mut() = previous);
+        }
+    }
+    let _restore = Restore(RUNNER.with(|r| r.borrow_mut().replace(runner)));
+    f()
+}
+
+/// Builder for one program run; see the module docs.
+pub struct ProcessCommand {
+    program: OsString,
+    args: Vec<OsString>,
+    cwd: Option<PathBuf>,
+}
+
+impl ProcessCommand {
+    pub fn new(program: impl AsRef<OsStr>) -> Self {
+        Self { program: program.as_ref().to_os_string(), args: Vec::new(), cwd: None }
+    }
+
+    pub fn arg(&mut self, arg: impl AsRef<OsStr>) -> &mut Self {
+        self.args.push(arg.as_ref().to_os_string());
+        self
+    }
+
+    pub fn args<I, S>(&mut self, args: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.args.extend(args.into_iter().map(|a| a.as_ref().to_os_string()));
+        self
+    }
+
+    pub fn current_dir(&mut self, dir: impl AsRef<Path>) -> &mut Self {
+        self.cwd = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Run to completion and collect stdout and stderr.
+    pub fn output(&mut self) -> io::Result<Output> {
+        let runner = RUNNER.with(|r| r.borrow().clone());
+        match runner {
+            Some(runner) => runner.output(&self.program, &self.args, self.cwd.as_deref()),
+            None => SystemRunner.output(&self.program, &self.args, self.cwd.as_deref()),
+        }
+    }
+}
+
+// ── Mocking ──
+
+/// A scripted result for `MockRunner`.
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    pub code: i32,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
+impl MockResponse {
+    pub fn ok(stdout: impl Into<Vec<u8>>) -> Self {
+        Self { code: 0, stdout: stdout.into(), stderr: Vec::new() }
+    }
+
+    pub fn fail(code: i32, stderr: impl Into<Vec<u8>>) -> Self {
+        Self { code, stdout: Vec::new(), stderr: stderr.into() }
+    }
+
+    pub fn with_stderr(mut self, stderr: impl Into<Vec<u8>>) -> Self {
+        self.stderr = stderr.into();
+        self
+    }
+}
+
+fn exit_status(code: i32) -> ExitStatus {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        ExitStatus::from_raw((code & 0xff) << 8)
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::ExitStatusExt;
+        ExitStatus::from_raw(code as u32)
+    }
+}
+
+struct Expectation {
+    program: String,
+    args: Vec<String>,
+    responses: VecDeque<MockResponse>,
+}
+
+/// Answers from a script instead of running anything, and records calls.
+///
+/// Each `on` adds a response for an exact program and argument list.
+/// Several responses for the same call are returned in order, the last
+/// one repeating. A call nothing was scripted for fails with `NotFound`,
+/// as if the program weren't installed.
+#[derive(Default)]
+pub struct MockRunner {
+    expectations: Mutex<Vec<Expectation>>,
+    calls: Mutex<Vec<ProcessCall>>,
+}
+
+impl MockRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn on(self, program: &str, args: &[&str], response: MockResponse) -> Self {
+        if let Ok(mut expectations) = self.expectations.lock() {
+            let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+            match expectations.iter_mut().find(|e| e.program == program && e.args == args) {
+                Some(e) => e.responses.push_back(response),
+                None => expectations.push(Expectation {
+                    program: program.to_string(),
+                    args,
+                    responses: VecDeque::from([response]),
+                }),
+            }
+        }
+        self
+    }
+
+    /// Every invocation so far, in order.
+    pub fn calls(&self) -> Vec<ProcessCall> {
+        self.calls.lock().map(|c| c.clone()).unwrap_or_default()
+    }
+}
+
+impl ProcessRunner for MockRunner {
+    fn output(&self, program: &OsStr, args: &[OsString], cwd: Option<&Path>) -> io::Result<Output> {
+        let call = ProcessCall {
+            program: program.to_string_lossy().into_owned(),
+            args: args.iter().map(|a| a.to_string_lossy().into_owned()).collect(),
+            cwd: cwd.map(Path::to_path_buf),
+        };
+        if let Ok(mut calls) = self.calls.lock() {
+            calls.push(call.clone());
+        }
+        let mut expectations = self.expectations.lock().map_err(|e| io::Error::other(e.to_string()))?;
+        let expectation = expectations
+            .iter_mut()
+            .find(|e| e.program == call.program && e.args == call.args)
+            .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::NotFound, format!("No mock for {} {}", call.program, call.args.join(" ")))
+            })?;
+        let response = if expectation.responses.len() > 1 {
+            expectation.responses.pop_front()
+        } else {
+            expectation.responses.front().cloned()
+        };
+        let response = response.ok_or_else(|| io::Error::other("Empty mock"))?;
+        Ok(Output { status: exit_status(response.code), stdout: response.stdout, stderr: response.stderr })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mock_scripts_and_records_calls() {
+        let mock = Arc::new(
+            MockRunner::new()
+                .on("git", &["fetch"], MockResponse::fail(1, "offline"))
+                .on("git", &["fetch"], MockResponse::ok("").with_stderr("done")),
+        );
+        let results: Vec<(bool, String)> = with_runner(mock.clone(), || {
+            (0..3)
+                .map(|_| {
+                    let out = ProcessCommand::new("git").arg("fetch").current_dir("/repo").output().unwrap();
+                    (out.status.success(), String::from_utf8_lossy(&out.stderr).into_owned())
+                })
+                .collect()
+        });
+        assert_eq!(
+            results,
+            vec![(false, "offline".to_string()), (true, "done".to_string()), (true, "done".to_string())]
+        );
+
+        let err = with_runner(mock.clone(), || ProcessCommand::new("git").arg("push").output().unwrap_err());
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        let calls = mock.calls();
+        assert_eq!(calls.len(), 4);
+        assert_eq!(calls[0].cwd.as_deref(), Some(Path::new("/repo")));
+        assert_eq!(calls[3].args, ["push"]);
+    }
+}
@@ -0,0 +1,205 @@
+//! Timing harness for the fs, git and search code paths.
+//!
+//! `profile_operation` runs one operation (or `all`) against the open
+//! project and reports each step separately, so a slow status refresh can
+//! be pinned on `git` itself or on our parsing of its output. The same
+//! step functions back the criterion benches in `benches/fs_git.rs`.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::modules::fs::{exclude::Excludes, hidden::HiddenFilter, project_root_async, read_dir_recursive, ProjectRootState};
+use crate::modules::git::{parse_status_porcelain_z, parse_unified_diff, read_working_diff};
+use crate::modules::process::ProcessCommand;
+use crate::modules::search::{search_files, SearchQuery};
+
+const OPERATIONS: &[&str] = &["tree", "status", "search", "diff"];
+const MAX_ITERATIONS: u32 = 20;
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ProfileParams {
+    /// Runs per step; the report gives min/median/max. Default 1.
+    pub iterations: Option<u32>,
+    /// Tree depth; default 50 (the whole project).
+    pub depth: Option<u32>,
+    /// Search pattern; default "TODO".
+    pub pattern: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ProfileStep {
+    pub name: String,
+    /// What the step produced: entries, files, matches or lines.
+    pub items: usize,
+    pub runs: u32,
+    pub min_ms: f64,
+    pub median_ms: f64,
+    pub max_ms: f64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ProfileReport {
+    pub root: String,
+    pub operation: String,
+    pub steps: Vec<ProfileStep>,
+    /// Sum of the median step times.
+    pub total_ms: f64,
+}
+
+// ── Steps ──
+
+/// Read the whole tree the way the file explorer does; returns the number
+/// of entries.
+pub fn tree(root: &Path, depth: u32) -> Result<usize, String> {
+    fn count(entries: &[crate::modules::fs::FileEntry]) -> usize {
+        entries.iter().map(|e| 1 + e.children.as_deref().map_or(0, count)).sum()
+    }
+    let excludes = Excludes::for_project(root);
+    let mut visited = std::collections::HashSet::new();
+    Ok(count(&read_dir_recursive(root, 0, depth, &mut visited, &excludes, &HiddenFilter::default(), &mut Default::default())?))
+}
+
+/// `git status --porcelain -z` output for `repo`.
+pub fn git_status_output(repo: &Path) -> Result<Vec<u8>, String> {
+    let output = ProcessCommand::new("git")
+        .args(["status", "--porcelain", "-uall", "-z"])
+        .current_dir(repo)
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(output.stdout)
+}
+
+/// Parse status output; returns the number of entries.
+pub fn parse_status(stdout: &[u8]) -> usize {
+    parse_status_porcelain_z(stdout).len()
+}
+
+/// Search the project for `pattern`; returns the number of matches.
+pub fn search(root: &Path, pattern: &str) -> Result<usize, String> {
+    let query = SearchQuery { pattern: pattern.to_string(), ..SearchQuery::default() };
+    Ok(search_files(root, root, &query, None)?.matches.len())
+}
+
+/// Unified diff of the working changes in `repo`.
+pub fn git_diff_output(repo: &Path) -> Result<String, String> {
+    read_working_diff(repo, false)
+}
+
+/// Parse a unified diff; returns the number of lines.
+pub fn parse_diff(diff: &str) -> usize {
+    parse_unified_diff(diff).len()
+}
+
+// ── Timing ──
+
+fn time<T>(name: &str, runs: u32, mut f: impl FnMut() -> Result<(T, usize), String>) -> Result<(ProfileStep, T), String> {
+    let mut times = Vec::with_capacity(runs as usize);
+    let mut last = None;
+    for _ in 0..runs {
+        let start = Instant::now();
+        let result = f()?;
+        times.push(start.elapsed().as_secs_f64() * 1000.0);
+        last = Some(result);
+    }
+    let (value, items) = last.ok_or("No runs")?;
+    times.sort_by(|a, b| a.total_cmp(b));
+    let step = ProfileStep {
+        name: name.to_string(),
+        items,
+        runs,
+        min_ms: times[0],
+        median_ms: times[times.len() / 2],
+        max_ms: times[times.len() - 1],
+    };
+    Ok((step, value))
+}
+
+fn profile_one(root: &Path, op: &str, params: &ProfileParams, runs: u32, steps: &mut Vec<ProfileStep>) -> Result<(), String> {
+    match op {
+        "tree" => {
+            let depth = params.depth.unwrap_or(50).min(50);
+            steps.push(time("tree: read", runs, || tree(root, depth).map(|n| ((), n)))?.0);
+        }
+        "status" => {
+            let (step, stdout) = time("status: git", runs, || git_status_output(root).map(|o| (o, 0)))?;
+            steps.push(step);
+            steps.push(time("status: parse", runs, || Ok(((), parse_status(&stdout))))?.0);
+        }
+        "search" => {
+            let pattern = params.pattern.as_deref().unwrap_or("TODO");
+            steps.push(time("search", runs, || search(root, pattern).map(|n| ((), n)))?.0);
+        }
+        "diff" => {
+            let (step, diff) = time("diff: git", runs, || git_diff_output(root).map(|d| (d, 0)))?;
+            steps.push(step);
+            steps.push(time("diff: parse", runs, || Ok(((), parse_diff(&diff))))?.0);
+        }
+        _ => return Err(format!("Unknown operation: {} (expected {} or all)", op, OPERATIONS.join(", "))),
+    }
+    Ok(())
+}
+
+pub fn run(root: &Path, op: &str, params: &ProfileParams) -> Result<ProfileReport, String> {
+    let runs = params.iterations.unwrap_or(1).clamp(1, MAX_ITERATIONS);
+    let mut steps = Vec::new();
+    if op == "all" {
+        for op in OPERATIONS {
+            // Outside a repository the git steps fail; the rest still count.
+            if let Err(e) = profile_one(root, op, params, runs, &mut steps) {
+                if !matches!(*op, "status" | "diff") {
+                    return Err(e);
+                }
+            }
+        }
+    } else {
+        profile_one(root, op, params, runs, &mut steps)?;
+    }
+    let total_ms = steps.iter().map(|s| s.median_ms).sum();
+    Ok(ProfileReport { root: root.to_string_lossy().into_owned(), operation: op.to_string(), steps, total_ms })
+}
+
+// ── Commands ──
+
+/// Time `op` ("tree", "status", "search", "diff" or "all") on the open
+/// project.
+#[tauri::command]
+pub async fn profile_operation(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    op: String,
+    params: Option<ProfileParams>,
+) -> Result<ProfileReport, String> {
+    let root: PathBuf = project_root_async(window.label(), &state).await?;
+    let params = params.unwrap_or_default();
+    tokio::task::spawn_blocking(move || run(&root, &op, &params))
+        .await
+        .map_err(|e| format!("Profiling failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiles_tree_and_search() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "// TODO: one\nfn main() {} // TODO two\n").unwrap();
+        std::fs::write(dir.path().join("README.md"), "nothing here\n").unwrap();
+
+        let report = run(dir.path(), "all", &ProfileParams { iterations: Some(3), ..Default::default() }).unwrap();
+        let names: Vec<&str> = report.steps.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(&names[..1], ["tree: read"]);
+        assert!(names.contains(&"search"));
+        let tree = &report.steps[0];
+        assert_eq!((tree.items, tree.runs), (3, 3));
+        assert!(tree.min_ms <= tree.median_ms && tree.median_ms <= tree.max_ms);
+        assert_eq!(report.steps.iter().find(|s| s.name == "search").unwrap().items, 2);
+        assert!(run(dir.path(), "compile", &ProfileParams::default()).is_err());
+    }
+}
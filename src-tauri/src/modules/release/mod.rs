@@ -0,0 +1,422 @@
+//! Version bumps across project manifests.
+//!
+//! Manifests are looked for in the project root, its direct
+//! subdirectories (e.g. `src-tauri/`), and the members of a Cargo
+//! workspace or npm/pnpm workspace. Recognized version fields:
+//! `[package]` / `[workspace.package]` in Cargo.toml, the top-level
+//! `version` of package.json and tauri.conf.json, and `[project]` /
+//! `[tool.poetry]` in pyproject.toml. Members inheriting the workspace
+//! version are left alone. Edits are textual, so formatting and comments
+//! survive. Lockfiles are not touched; cargo and npm refresh them on the
+//! next build or install.
+//!
+//! As with `replace_in_project`, nothing is written unless `apply` is set.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::modules::fs::{history, write_atomic, ProjectRootState};
+use crate::modules::process::ProcessCommand;
+
+const SKIP_DIRS: &[&str] = &[".git", "node_modules", "target", "dist", "build", ".venv", "venv"];
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ManifestChange {
+    /// Path relative to the project root.
+    pub path: String,
+    /// Which field, e.g. `package.version`.
+    pub field: String,
+    /// 1-based line of the version field.
+    pub line: usize,
+    pub old_version: String,
+    pub new_version: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct VersionBump {
+    /// Version of the first manifest found (root Cargo.toml, package.json,
+    /// pyproject.toml, then subdirectories).
+    pub old_version: String,
+    pub new_version: String,
+    pub changes: Vec<ManifestChange>,
+    pub applied: bool,
+    /// Hash of the release commit, if one was made.
+    pub commit: Option<String>,
+    pub tag: Option<String>,
+}
+
+// ── Versions ──
+
+#[derive(Debug, PartialEq)]
+struct Semver {
+    major: u64,
+    minor: u64,
+    patch: u64,
+    pre: Option<String>,
+}
+
+fn parse_semver(v: &str) -> Option<Semver> {
+    let v = v.trim().trim_start_matches('v');
+    let v = v.split('+').next()?;
+    let (core, pre) = match v.split_once('-') {
+        Some((core, pre)) => (core, Some(pre.to_string())),
+        None => (v, None),
+    };
+    let mut parts = core.split('.').map(|p| p.parse::<u64>().ok());
+    let (major, minor, patch) = (parts.next()??, parts.next()??, parts.next()??);
+    if parts.next().is_some() || pre.as_deref() == Some("") {
+        return None;
+    }
+    Some(Semver { major, minor, patch, pre })
+}
+
+/// `level` is `major`, `minor`, `patch` or `prerelease`, or an explicit
+/// version. Bumping a pre-release's own level releases it (`1.2.0-rc.1`
+/// + minor = `1.2.0`), as npm does.
+fn next_version(current: &str, level: &str) -> Result<String, String> {
+    if parse_semver(level).is_some() {
+        return Ok(level.trim().trim_start_matches('v').to_string());
+    }
+    let v = parse_semver(current).ok_or_else(|| format!("Current version '{}' is not semver", current))?;
+    let (major, minor, patch) = (v.major, v.minor, v.patch);
+    let released = v.pre.is_none();
+    Ok(match level {
+        "major" if !released && minor == 0 && patch == 0 => format!("{}.0.0", major),
+        "major" => format!("{}.0.0", major + 1),
+        "minor" if !released && patch == 0 => format!("{}.{}.0", major, minor),
+        "minor" => format!("{}.{}.0", major, minor + 1),
+        "patch" if !released => format!("{}.{}.{}", major, minor, patch),
+        "patch" => format!("{}.{}.{}", major, minor, patch + 1),
+        "prerelease" => match &v.pre {
+            Some(pre) => {
+                // Increment the trailing number: rc.1 → rc.2, beta → beta.1.
+                let (head, tail) = pre.rsplit_once('.').unwrap_or(("", pre));
+                match tail.parse::<u64>() {
+                    Ok(n) if head.is_empty() => format!("{}.{}.{}-{}", major, minor, patch, n + 1),
+                    Ok(n) => format!("{}.{}.{}-{}.{}", major, minor, patch, head, n + 1),
+                    Err(_) => format!("{}.{}.{}-{}.1", major, minor, patch, pre),
+                }
+            }
+            None => format!("{}.{}.{}-0", major, minor, patch + 1),
+        },
+        other => return Err(format!("Unknown version level: {}", other)),
+    })
+}
+
+// ── Manifests ──
+
+/// A version field located in a manifest: byte range of the version
+/// string (without quotes) and the field name.
+#[derive(Debug, PartialEq)]
+struct VersionField {
+    field: String,
+    start: usize,
+    end: usize,
+}
+
+/// First `version = "…"` inside one of `sections` of a TOML document.
+fn find_toml_version(source: &str, sections: &[&str]) -> Option<VersionField> {
+    let mut section = String::new();
+    let mut offset = 0;
+    for line in source.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            section = trimmed.trim_start_matches('[').split(']').next().unwrap_or_default().trim().to_string();
+        } else if sections.contains(&section.as_str()) {
+            if let Some(rest) = trimmed.strip_prefix("version") {
+                if let Some(value) = rest.trim_start().strip_prefix('=') {
+                    let value = value.trim_start();
+                    let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+                    let inner = &value[1..];
+                    let len = inner.find(quote)?;
+                    let start = offset + (value.as_ptr() as usize - line.as_ptr() as usize) + 1;
+                    return Some(VersionField { field: format!("{}.version", section), start, end: start + len });
+                }
+            }
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// The top-level `"version": "…"` of a JSON document.
+fn find_json_version(source: &str) -> Option<VersionField> {
+    let bytes = source.as_bytes();
+    let (mut depth, mut i) = (0usize, 0usize);
+    let mut expect_key = false;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' | b'[' => {
+                depth += 1;
+                expect_key = bytes[i] == b'{';
+            }
+            b'}' | b']' => depth = depth.saturating_sub(1),
+            b',' => expect_key = depth == 1,
+            b'"' => {
+                let start = i + 1;
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                let is_key = depth == 1 && expect_key;
+                expect_key = false;
+                if is_key && &source[start..i.min(source.len())] == "version" {
+                    let rest = &source[i + 1..];
+                    let after_colon = rest.trim_start().strip_prefix(':')?;
+                    let value = after_colon.trim_start();
+                    let inner = value.strip_prefix('"')?;
+                    let len = inner.find('"')?;
+                    let value_start = source.len() - inner.len();
+                    return Some(VersionField { field: "version".to_string(), start: value_start, end: value_start + len });
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+fn find_version(path: &Path, source: &str) -> Option<VersionField> {
+    match path.file_name()?.to_str()? {
+        "Cargo.toml" => find_toml_version(source, &["package", "workspace.package"]),
+        "pyproject.toml" => find_toml_version(source, &["project", "tool.poetry"]),
+        "package.json" | "tauri.conf.json" => find_json_version(source),
+        _ => None,
+    }
+}
+
+const MANIFESTS: &[&str] = &["Cargo.toml", "package.json", "pyproject.toml", "tauri.conf.json"];
+
+/// Directories named by workspace member globs (`crates/*`, `packages/app`).
+fn expand_members(root: &Path, patterns: &[String]) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    for pattern in patterns {
+        let pattern = pattern.trim_start_matches("./");
+        if pattern.starts_with('!') || pattern.contains("..") {
+            continue;
+        }
+        match pattern.strip_suffix("/*").or_else(|| pattern.strip_suffix("/**")) {
+            Some(parent) => {
+                let Ok(entries) = std::fs::read_dir(root.join(parent)) else { continue };
+                let mut found: Vec<PathBuf> = entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()).collect();
+                found.sort();
+                dirs.extend(found);
+            }
+            None if !pattern.contains('*') => dirs.push(root.join(pattern)),
+            None => {}
+        }
+    }
+    dirs
+}
+
+fn workspace_members(root: &Path) -> Vec<PathBuf> {
+    let mut patterns = Vec::new();
+    if let Ok(cargo) = std::fs::read_to_string(root.join("Cargo.toml")) {
+        if let Ok(value) = cargo.parse::<toml::Value>() {
+            if let Some(members) = value.get("workspace").and_then(|w| w.get("members")).and_then(|m| m.as_array()) {
+                patterns.extend(members.iter().filter_map(|m| m.as_str().map(str::to_string)));
+            }
+        }
+    }
+    if let Ok(pkg) = std::fs::read_to_string(root.join("package.json")) {
+        if let Ok(value) = serde_json::from_str::<serde_json::Value>(&pkg) {
+            let ws = &value["workspaces"];
+            let list = ws.as_array().or_else(|| ws["packages"].as_array());
+            patterns.extend(list.into_iter().flatten().filter_map(|m| m.as_str().map(str::to_string)));
+        }
+    }
+    if let Ok(pnpm) = std::fs::read_to_string(root.join("pnpm-workspace.yaml")) {
+        if let Ok(value) = serde_yaml::from_str::<serde_json::Value>(&pnpm) {
+            patterns.extend(value["packages"].as_array().into_iter().flatten().filter_map(|m| m.as_str().map(str::to_string)));
+        }
+    }
+    expand_members(root, &patterns)
+}
+
+/// Manifest files with a version field, root first, without duplicates.
+fn find_manifests(root: &Path) -> Vec<PathBuf> {
+    let mut dirs = vec![root.to_path_buf()];
+    if let Ok(entries) = std::fs::read_dir(root) {
+        let mut children: Vec<PathBuf> = entries
+            .flatten()
+            .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+            .filter(|e| !SKIP_DIRS.contains(&e.file_name().to_string_lossy().as_ref()))
+            .map(|e| e.path())
+            .collect();
+        children.sort();
+        dirs.extend(children);
+    }
+    dirs.extend(workspace_members(root));
+
+    let mut manifests = Vec::new();
+    for dir in dirs {
+        if !dir.starts_with(root) {
+            continue;
+        }
+        for name in MANIFESTS {
+            let path = dir.join(name);
+            if manifests.contains(&path) {
+                continue;
+            }
+            let has_version = std::fs::read_to_string(&path).ok().is_some_and(|s| find_version(&path, &s).is_some());
+            if has_version {
+                manifests.push(path);
+            }
+        }
+    }
+    manifests
+}
+
+fn line_of(source: &str, offset: usize) -> usize {
+    source[..offset].matches('\n').count() + 1
+}
+
+/// Compute (and with `apply`, write) the new version in every manifest.
+fn plan(root: &Path, level: &str, apply: bool) -> Result<(String, String, Vec<ManifestChange>, Vec<PathBuf>), String> {
+    let manifests = find_manifests(root);
+    let first = manifests.first().ok_or("No manifest with a version field found")?;
+    let source = std::fs::read_to_string(first).map_err(|e| format!("Failed to read {}: {}", first.display(), e))?;
+    let field = find_version(first, &source).ok_or("No version field found")?;
+    let old_version = source[field.start..field.end].to_string();
+    let new_version = next_version(&old_version, level)?;
+
+    let mut changes = Vec::new();
+    let mut written = Vec::new();
+    for path in manifests {
+        let source = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let Some(field) = find_version(&path, &source) else { continue };
+        let old = &source[field.start..field.end];
+        if old == new_version {
+            continue;
+        }
+        changes.push(ManifestChange {
+            path: path.strip_prefix(root).unwrap_or(&path).to_string_lossy().into_owned(),
+            field: field.field.clone(),
+            line: line_of(&source, field.start),
+            old_version: old.to_string(),
+            new_version: new_version.clone(),
+        });
+        if apply {
+            let updated = format!("{}{}{}", &source[..field.start], new_version, &source[field.end..]);
+            history::keep(root, &path, updated.as_bytes());
+            write_atomic(&path, updated.as_bytes())?;
+            written.push(path);
+        }
+    }
+    Ok((old_version, new_version, changes, written))
+}
+
+fn git(root: &Path, args: &[&str]) -> Result<String, String> {
+    let output = ProcessCommand::new("git").args(args).current_dir(root).output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Bump the project version. `level` is `major`, `minor`, `patch`,
+/// `prerelease` or an explicit version such as `2.0.0-beta.1`. Without
+/// `apply` only the change set is returned. With `apply`, `commit` also
+/// commits the changed manifests as `chore(release): <version>` and `tag`
+/// adds an annotated `v<version>` tag.
+#[tauri::command]
+pub fn bump_version(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    level: String,
+    apply: Option<bool>,
+    commit: Option<bool>,
+    tag: Option<bool>,
+) -> Result<VersionBump, String> {
+    let root = state.blocking_read().get(window.label()).and_then(|o| o.clone()).ok_or("No project is open")?;
+    let apply = apply.unwrap_or(false);
+    let tag_name = |v: &str| format!("v{}", v);
+    if apply && tag.unwrap_or(false) {
+        // Fail before touching any file if the tag is taken.
+        let (_, new_version, _, _) = plan(&root, &level, false)?;
+        if git(&root, &["rev-parse", "--verify", "--quiet", &format!("refs/tags/{}", tag_name(&new_version))]).is_ok() {
+            return Err(format!("Tag {} already exists", tag_name(&new_version)));
+        }
+    }
+    let (old_version, new_version, changes, written) = plan(&root, &level, apply)?;
+    let mut result = VersionBump { old_version, new_version, changes, applied: apply, commit: None, tag: None };
+    if !apply {
+        return Ok(result);
+    }
+
+    if commit.unwrap_or(false) && !written.is_empty() {
+        let mut add = vec!["add", "--"];
+        let paths: Vec<String> = written.iter().map(|p| p.to_string_lossy().into_owned()).collect();
+        add.extend(paths.iter().map(String::as_str));
+        git(&root, &add)?;
+        let message = format!("chore(release): {}", result.new_version);
+        let mut args = vec!["commit", "-m", &message, "--"];
+        args.extend(paths.iter().map(String::as_str));
+        git(&root, &args)?;
+        result.commit = Some(git(&root, &["rev-parse", "HEAD"])?);
+    }
+    if tag.unwrap_or(false) {
+        let name = tag_name(&result.new_version);
+        git(&root, &["tag", "-a", &name, "-m", &name])?;
+        result.tag = Some(name);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_next_versions() {
+        assert_eq!(next_version("1.2.3", "patch").unwrap(), "1.2.4");
+        assert_eq!(next_version("1.2.3", "minor").unwrap(), "1.3.0");
+        assert_eq!(next_version("1.2.3", "major").unwrap(), "2.0.0");
+        assert_eq!(next_version("1.3.0-rc.1", "minor").unwrap(), "1.3.0");
+        assert_eq!(next_version("1.3.0-rc.1", "prerelease").unwrap(), "1.3.0-rc.2");
+        assert_eq!(next_version("1.3.0", "prerelease").unwrap(), "1.3.1-0");
+        assert_eq!(next_version("1.3.0", "v2.0.0-beta.1").unwrap(), "2.0.0-beta.1");
+        assert!(next_version("1.3", "patch").is_err());
+        assert!(next_version("1.3.0", "huge").is_err());
+    }
+
+    #[test]
+    fn finds_version_fields() {
+        let cargo = "[workspace]\nmembers = [\"a\"]\n\n[dependencies]\nversion = \"9\"\n\n[package]\nname = \"x\"\nversion = \"0.2.2\" # keep\n";
+        let f = find_toml_version(cargo, &["package"]).unwrap();
+        assert_eq!((&cargo[f.start..f.end], f.field.as_str(), line_of(cargo, f.start)), ("0.2.2", "package.version", 9));
+
+        let json = r#"{"name": "x", "dependencies": {"version": "1"}, "version" : "1.0.0", "scripts": {}}"#;
+        let f = find_json_version(json).unwrap();
+        assert_eq!(&json[f.start..f.end], "1.0.0");
+        assert!(find_json_version(r#"{"deps": {"version": "1"}}"#).is_none());
+    }
+
+    #[test]
+    fn bumps_workspace_manifests() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("package.json"), "{\n  \"name\": \"app\",\n  \"version\": \"0.4.1\",\n  \"workspaces\": [\"packages/*\"]\n}\n").unwrap();
+        std::fs::create_dir_all(root.join("packages/ui")).unwrap();
+        std::fs::write(root.join("packages/ui/package.json"), "{\"name\":\"ui\",\"version\":\"0.4.1\"}").unwrap();
+        std::fs::create_dir_all(root.join("src-tauri")).unwrap();
+        std::fs::write(root.join("src-tauri/Cargo.toml"), "[package]\nname = \"app\"\nversion = \"0.4.0\"\n").unwrap();
+        std::fs::create_dir_all(root.join("node_modules/dep")).unwrap();
+        std::fs::write(root.join("node_modules/dep/package.json"), "{\"version\":\"0.4.1\"}").unwrap();
+
+        let (old, new, changes, written) = plan(root, "minor", false).unwrap();
+        assert_eq!((old.as_str(), new.as_str()), ("0.4.1", "0.5.0"));
+        let paths: Vec<&str> = changes.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, vec!["package.json", "src-tauri/Cargo.toml", "packages/ui/package.json"]);
+        assert!(written.is_empty());
+
+        plan(root, "minor", true).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(root.join("package.json")).unwrap(),
+            "{\n  \"name\": \"app\",\n  \"version\": \"0.5.0\",\n  \"workspaces\": [\"packages/*\"]\n}\n"
+        );
+        assert!(std::fs::read_to_string(root.join("src-tauri/Cargo.toml")).unwrap().contains("version = \"0.5.0\""));
+        assert_eq!(std::fs::read_to_string(root.join("node_modules/dep/package.json")).unwrap(), "{\"version\":\"0.4.1\"}");
+    }
+}
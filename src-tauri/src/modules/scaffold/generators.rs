@@ -0,0 +1,392 @@
+//! Project-defined code generators.
+//!
+//! A generator is a JSON file in the project's `.leo-ide/generators/`
+//! folder, so it is checked in and shared by everyone on the team:
+//!
+//! ```json
+//! {
+//!   "label": "React component",
+//!   "params": [{ "name": "name", "required": true }, { "name": "styles" }],
+//!   "files": [
+//!     { "path": "src/components/{{pascal_case name}}.tsx", "template": "component.tsx.hbs" },
+//!     { "path": "src/components/{{pascal_case name}}.css", "content": ".{{kebab_case name}} {}\n", "when": "styles" }
+//!   ]
+//! }
+//! ```
+//!
+//! Paths and contents are Handlebars-style templates: `{{param}}`, the
+//! case helpers `{{pascal_case param}}`, `camel_case`, `snake_case`,
+//! `kebab_case`, `upper_case` and `lower_case`, and
+//! `{{#if param}}…{{else}}…{{/if}}`, where a parameter is true unless it
+//! is empty or `false`. Block tags alone on a line take the line with
+//! them. `template` files are read from the generators folder.
+//!
+//! `generate_code` doesn't write anything: it returns a `WorkspaceEdit` of
+//! file creates for the frontend to preview and pass to
+//! `apply_workspace_edit`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use crate::modules::fs::workspace_edit::{FileOperation, WorkspaceEdit};
+use crate::modules::fs::ProjectRootState;
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct GeneratorParam {
+    pub name: String,
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub default: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+struct GeneratorFile {
+    path: String,
+    /// Template file, relative to the generators folder.
+    #[serde(default)]
+    template: Option<String>,
+    /// Inline template, used when there is no `template`.
+    #[serde(default)]
+    content: Option<String>,
+    /// Parameter that must be true for the file to be generated.
+    #[serde(default)]
+    when: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+struct GeneratorSpec {
+    #[serde(default)]
+    label: Option<String>,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    params: Vec<GeneratorParam>,
+    files: Vec<GeneratorFile>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct CodeGenerator {
+    pub id: String,
+    pub label: String,
+    pub description: String,
+    pub params: Vec<GeneratorParam>,
+}
+
+fn generators_dir(root: &Path) -> PathBuf {
+    root.join(".leo-ide").join("generators")
+}
+
+// ── Templates ──
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Token<'a> {
+    Text(&'a str),
+    Tag(&'a str),
+}
+
+fn is_block(tag: &str) -> bool {
+    tag.starts_with("#if ") || tag == "else" || tag == "/if"
+}
+
+fn tokenize(template: &str) -> Result<Vec<Token<'_>>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = template;
+    while let Some(open) = rest.find("{{") {
+        let close = rest[open..].find("}}").ok_or("Unclosed {{ in template")? + open;
+        tokens.push(Token::Text(&rest[..open]));
+        tokens.push(Token::Tag(rest[open + 2..close].trim()));
+        rest = &rest[close + 2..];
+    }
+    tokens.push(Token::Text(rest));
+
+    // A block tag alone on its line drops the line's indentation and
+    // line break, so `{{#if}}` lines don't leave blank lines behind.
+    // Tokens alternate text and tag, starting and ending with text.
+    let standalone: Vec<bool> = (0..tokens.len())
+        .map(|i| match (i.checked_sub(1).map(|p| tokens[p]), tokens[i], tokens.get(i + 1)) {
+            (Some(Token::Text(before)), Token::Tag(tag), Some(Token::Text(after))) if is_block(tag) => {
+                let line = &before[before.rfind('\n').map_or(0, |n| n + 1)..];
+                let starts_line = line.trim().is_empty() && (before.contains('\n') || i == 1);
+                let rest = &after[..after.find('\n').unwrap_or(after.len())];
+                starts_line && rest.trim().is_empty() && (after.contains('\n') || i + 2 == tokens.len())
+            }
+            _ => false,
+        })
+        .collect();
+    for i in (0..tokens.len()).step_by(2) {
+        let Token::Text(text) = tokens[i] else { continue };
+        let start = if i > 0 && standalone[i - 1] { text.find('\n').map_or(text.len(), |n| n + 1) } else { 0 };
+        let end = if standalone.get(i + 1) == Some(&true) { text.rfind('\n').map_or(0, |n| n + 1) } else { text.len() };
+        tokens[i] = Token::Text(&text[start..end.max(start)]);
+    }
+    Ok(tokens)
+}
+
+/// Split an identifier or phrase into lowercase words: `fooBar`,
+/// `foo_bar`, `foo-bar` and `Foo Bar` all give `["foo", "bar"]`.
+fn words(s: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let chars: Vec<char> = s.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        let prev = i.checked_sub(1).map(|p| chars[p]);
+        let next = chars.get(i + 1);
+        // `fooBar` and the `P` of `HTTPServer` start new words.
+        let boundary = c.is_uppercase()
+            && prev.is_some_and(|p| p.is_lowercase() || p.is_numeric() || (p.is_uppercase() && next.is_some_and(|n| n.is_lowercase())));
+        if boundary && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn capitalized(word: &str) -> String {
+    let mut chars = word.chars();
+    chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default()
+}
+
+fn apply_helper(helper: &str, value: &str) -> Result<String, String> {
+    let w = words(value);
+    Ok(match helper {
+        "pascal_case" => w.iter().map(|w| capitalized(w)).collect(),
+        "camel_case" => w.iter().enumerate().map(|(i, w)| if i == 0 { w.clone() } else { capitalized(w) }).collect(),
+        "snake_case" => w.join("_"),
+        "kebab_case" => w.join("-"),
+        "upper_case" => value.to_uppercase(),
+        "lower_case" => value.to_lowercase(),
+        _ => return Err(format!("Unknown helper: {}", helper)),
+    })
+}
+
+fn truthy(params: &HashMap<String, String>, name: &str) -> bool {
+    params.get(name).is_some_and(|v| !v.is_empty() && v != "false")
+}
+
+/// Render tokens from `pos` until `{{else}}`, `{{/if}}` or the end,
+/// writing to `out` only while `emit` holds. Returns the tag it stopped at.
+fn render_block<'a>(
+    tokens: &[Token<'a>],
+    pos: &mut usize,
+    params: &HashMap<String, String>,
+    emit: bool,
+    out: &mut String,
+) -> Result<Option<&'a str>, String> {
+    while let Some(&token) = tokens.get(*pos) {
+        *pos += 1;
+        let tag = match token {
+            Token::Text(text) => {
+                if emit {
+                    out.push_str(text);
+                }
+                continue;
+            }
+            Token::Tag(tag) => tag,
+        };
+        if tag == "else" || tag == "/if" {
+            return Ok(Some(tag));
+        }
+        if let Some(name) = tag.strip_prefix("#if ") {
+            let cond = truthy(params, name.trim());
+            let mut stop = render_block(tokens, pos, params, emit && cond, out)?;
+            if stop == Some("else") {
+                stop = render_block(tokens, pos, params, emit && !cond, out)?;
+            }
+            if stop != Some("/if") {
+                return Err(format!("{{{{#if {}}}}} is not closed", name.trim()));
+            }
+        } else if emit {
+            let (helper, name) = match tag.split_once(' ') {
+                Some((helper, name)) => (Some(helper), name.trim()),
+                None => (None, tag),
+            };
+            let value = params.get(name).ok_or_else(|| format!("Unknown parameter: {}", name))?;
+            out.push_str(&match helper {
+                Some(helper) => apply_helper(helper, value)?,
+                None => value.clone(),
+            });
+        }
+    }
+    Ok(None)
+}
+
+fn render(template: &str, params: &HashMap<String, String>) -> Result<String, String> {
+    let tokens = tokenize(template)?;
+    let mut out = String::with_capacity(template.len());
+    let mut pos = 0;
+    match render_block(&tokens, &mut pos, params, true, &mut out)? {
+        None => Ok(out),
+        Some(tag) => Err(format!("Unexpected {{{{{}}}}}", tag)),
+    }
+}
+
+// ── Generators ──
+
+fn load(root: &Path, id: &str) -> Result<GeneratorSpec, String> {
+    if id.is_empty() || id.contains(['/', '\\']) || id.starts_with('.') {
+        return Err(format!("Invalid generator: {}", id));
+    }
+    let file = generators_dir(root).join(format!("{}.json", id));
+    let text = std::fs::read_to_string(&file).map_err(|_| format!("Unknown generator: {}", id))?;
+    serde_json::from_str(&text).map_err(|e| format!("Invalid generator {}: {}", id, e))
+}
+
+/// The generators defined in `root`, sorted by id. Files that don't parse
+/// are skipped.
+pub fn list_generators(root: &Path) -> Vec<CodeGenerator> {
+    let Ok(entries) = std::fs::read_dir(generators_dir(root)) else { return Vec::new() };
+    let mut generators: Vec<CodeGenerator> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let id = path.file_stem()?.to_str()?.to_string();
+            if path.extension()? != "json" {
+                return None;
+            }
+            let spec = load(root, &id).ok()?;
+            Some(CodeGenerator { label: spec.label.unwrap_or_else(|| id.clone()), id, description: spec.description, params: spec.params })
+        })
+        .collect();
+    generators.sort_by(|a, b| a.id.cmp(&b.id));
+    generators
+}
+
+/// The template text for `file`, read from the generators folder.
+fn template_text(root: &Path, file: &GeneratorFile) -> Result<String, String> {
+    let Some(name) = &file.template else {
+        return file.content.clone().ok_or_else(|| format!("{} has neither a template nor content", file.path));
+    };
+    let dir = generators_dir(root).canonicalize().map_err(|e| e.to_string())?;
+    let path = dir.join(name).canonicalize().map_err(|_| format!("Template not found: {}", name))?;
+    if !path.starts_with(&dir) {
+        return Err(format!("Template is outside the generators folder: {}", name));
+    }
+    std::fs::read_to_string(&path).map_err(|e| format!("Failed to read template {}: {}", name, e))
+}
+
+/// Render generator `id` with `params` into a workspace edit that creates
+/// its files. Nothing is written.
+pub fn generate(root: &Path, id: &str, mut params: HashMap<String, String>) -> Result<WorkspaceEdit, String> {
+    let spec = load(root, id)?;
+    for param in &spec.params {
+        if params.get(&param.name).map_or(true, |v| v.is_empty()) {
+            match &param.default {
+                Some(default) => {
+                    params.insert(param.name.clone(), default.clone());
+                }
+                None if param.required => return Err(format!("Missing parameter: {}", param.name)),
+                None => {
+                    params.entry(param.name.clone()).or_default();
+                }
+            }
+        }
+    }
+
+    let mut operations = Vec::new();
+    for file in &spec.files {
+        if file.when.as_deref().is_some_and(|when| !truthy(&params, when)) {
+            continue;
+        }
+        let path = render(&file.path, &params).map_err(|e| format!("{}: {}", file.path, e))?;
+        let rel = Path::new(&path);
+        if path.trim().is_empty() || !rel.components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir)) {
+            return Err(format!("Generated path must stay inside the project: {}", path));
+        }
+        let content = render(&template_text(root, file)?, &params).map_err(|e| format!("{}: {}", file.path, e))?;
+        operations.push(FileOperation::Create { path, content, overwrite: false });
+    }
+    Ok(WorkspaceEdit { operations })
+}
+
+// ── Commands ──
+
+fn project_root(window: &tauri::WebviewWindow, state: &tauri::State<'_, ProjectRootState>) -> Result<PathBuf, String> {
+    Ok(state.blocking_read().get(window.label()).and_then(|o| o.clone()).ok_or("No project is open")?)
+}
+
+/// Generators defined in the project's `.leo-ide/generators/`.
+#[tauri::command]
+pub fn list_code_generators(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+) -> Result<Vec<CodeGenerator>, String> {
+    Ok(list_generators(&project_root(&window, &state)?))
+}
+
+/// Render a generator into a workspace edit for `apply_workspace_edit`.
+#[tauri::command]
+pub fn generate_code(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    generator_id: String,
+    params: HashMap<String, String>,
+) -> Result<WorkspaceEdit, String> {
+    generate(&project_root(&window, &state)?, &generator_id, params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn renders_templates_and_generates_files() {
+        let p = params(&[("name", "userProfile card"), ("styles", "true"), ("tests", "false")]);
+        assert_eq!(render("{{pascal_case name}} {{camel_case name}}", &p).unwrap(), "UserProfileCard userProfileCard");
+        assert_eq!(render("{{snake_case name}}/{{kebab_case name}}", &p).unwrap(), "user_profile_card/user-profile-card");
+        assert_eq!(words("HTTPServer2go"), ["http", "server2go"]);
+        let template = "a\n{{#if styles}}\n  s\n{{else}}\n  none\n{{/if}}\n{{#if tests}}t{{/if}}b\n  {{#if tests}}\n{{/if}}\n";
+        assert_eq!(render(template, &p).unwrap(), "a\n  s\nb\n");
+        assert!(render("{{#if styles}}x", &p).unwrap_err().contains("not closed"));
+        assert!(render("{{nmae}}", &p).unwrap_err().contains("Unknown parameter"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let gens = generators_dir(root);
+        std::fs::create_dir_all(&gens).unwrap();
+        std::fs::write(gens.join("component.tsx.hbs"), "export function {{pascal_case name}}() {}\n").unwrap();
+        std::fs::write(
+            gens.join("component.json"),
+            r#"{
+                "label": "Component",
+                "params": [{ "name": "name", "required": true }, { "name": "dir", "default": "src" }, { "name": "styles" }],
+                "files": [
+                    { "path": "{{dir}}/{{pascal_case name}}.tsx", "template": "component.tsx.hbs" },
+                    { "path": "{{dir}}/{{kebab_case name}}.css", "content": ".x {}\n", "when": "styles" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        std::fs::write(gens.join("broken.json"), "{").unwrap();
+
+        assert_eq!(list_generators(root).iter().map(|g| g.id.as_str()).collect::<Vec<_>>(), ["component"]);
+        let edit = generate(root, "component", params(&[("name", "nav bar")])).unwrap();
+        match edit.operations.as_slice() {
+            [FileOperation::Create { path, content, overwrite: false }] => {
+                assert_eq!((path.as_str(), content.as_str()), ("src/NavBar.tsx", "export function NavBar() {}\n"));
+            }
+            other => panic!("unexpected operations: {other:?}"),
+        }
+        assert_eq!(generate(root, "component", params(&[("name", "x"), ("styles", "yes")])).unwrap().operations.len(), 2);
+        assert_eq!(generate(root, "component", HashMap::new()).unwrap_err(), "Missing parameter: name");
+        assert!(generate(root, "component", params(&[("name", "x"), ("dir", "../out")])).is_err());
+        assert!(generate(root, "../component", HashMap::new()).is_err());
+    }
+}
@@ -0,0 +1,203 @@
+//! A starting `.gitignore` for projects that have none.
+//!
+//! Without one, the first `git status` in a Node or Rust project lists
+//! every file under `node_modules/` or `target/`, which can be hundreds of
+//! thousands of entries. `suggest_gitignore` composes one from per-stack
+//! templates, picked from the manifests in the project (or given by the
+//! caller), for the UI to show; `write_gitignore` writes what the user
+//! accepted. Scaffolding writes the suggestion directly when asked to.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::Path;
+
+use crate::modules::fs::ProjectRootState;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Stack {
+    Rust,
+    Node,
+    Python,
+    /// Finder metadata.
+    Macos,
+    /// Editor and IDE settings folders and swap files.
+    Ide,
+}
+
+impl Stack {
+    fn label(self) -> &'static str {
+        match self {
+            Stack::Rust => "Rust",
+            Stack::Node => "Node",
+            Stack::Python => "Python",
+            Stack::Macos => "macOS",
+            Stack::Ide => "Editors and IDEs",
+        }
+    }
+
+    fn template(self) -> &'static [&'static str] {
+        match self {
+            Stack::Rust => &["/target/", "**/*.rs.bk"],
+            Stack::Node => &[
+                "node_modules/",
+                "dist/",
+                "build/",
+                "coverage/",
+                ".env",
+                ".env.*",
+                "!.env.example",
+                "npm-debug.log*",
+                "yarn-debug.log*",
+                "yarn-error.log*",
+                "pnpm-debug.log*",
+            ],
+            Stack::Python => &[
+                "__pycache__/",
+                "*.py[cod]",
+                ".venv/",
+                "venv/",
+                "*.egg-info/",
+                "dist/",
+                "build/",
+                ".pytest_cache/",
+                ".mypy_cache/",
+                ".ruff_cache/",
+            ],
+            Stack::Macos => &[".DS_Store", ".AppleDouble", "._*"],
+            Stack::Ide => &[".idea/", ".vscode/*", "!.vscode/extensions.json", "*.swp", "*~"],
+        }
+    }
+
+    /// Files whose presence means the stack is in use.
+    fn manifests(self) -> &'static [&'static str] {
+        match self {
+            Stack::Rust => &["Cargo.toml"],
+            Stack::Node => &["package.json"],
+            Stack::Python => &["pyproject.toml", "requirements.txt", "setup.py", "Pipfile"],
+            Stack::Macos | Stack::Ide => &[],
+        }
+    }
+}
+
+const LANGUAGES: [Stack; 3] = [Stack::Rust, Stack::Node, Stack::Python];
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct GitignoreSuggestion {
+    pub stack: Vec<Stack>,
+    pub content: String,
+    /// The project already has a `.gitignore`; `write_gitignore` won't
+    /// replace it.
+    pub exists: bool,
+}
+
+/// The stacks whose manifests are in `root` or a folder directly under
+/// it (e.g. `src-tauri/Cargo.toml`), plus macOS and IDE files, which
+/// turn up in any project.
+pub fn detect_stack(root: &Path) -> Vec<Stack> {
+    let mut dirs = vec![root.to_path_buf()];
+    if let Ok(entries) = std::fs::read_dir(root) {
+        dirs.extend(entries.flatten().map(|e| e.path()).filter(|p| {
+            let name = p.file_name().unwrap_or_default().to_string_lossy();
+            p.is_dir() && !name.starts_with('.') && name != "node_modules" && name != "target"
+        }));
+    }
+    let mut stack: Vec<Stack> = LANGUAGES
+        .into_iter()
+        .filter(|s| dirs.iter().any(|d| s.manifests().iter().any(|m| d.join(m).is_file())))
+        .collect();
+    stack.extend([Stack::Macos, Stack::Ide]);
+    stack
+}
+
+/// The templates for `stack`, one commented section each, without
+/// repeating a pattern an earlier section already has.
+pub fn compose(stack: &[Stack]) -> String {
+    let mut stack = stack.to_vec();
+    stack.sort();
+    stack.dedup();
+    let mut seen = HashSet::new();
+    let mut sections = Vec::new();
+    for s in stack {
+        let lines: Vec<&str> = s.template().iter().copied().filter(|l| seen.insert(*l)).collect();
+        if !lines.is_empty() {
+            sections.push(format!("# {}\n{}\n", s.label(), lines.join("\n")));
+        }
+    }
+    sections.join("\n")
+}
+
+pub fn suggest(root: &Path, stack: Option<Vec<Stack>>) -> GitignoreSuggestion {
+    let stack = stack.unwrap_or_else(|| detect_stack(root));
+    GitignoreSuggestion { content: compose(&stack), stack, exists: root.join(".gitignore").exists() }
+}
+
+/// Write `content` as the project's `.gitignore`, which must not exist yet.
+pub fn write(root: &Path, content: &str) -> Result<(), String> {
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(root.join(".gitignore"))
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::AlreadyExists => "The project already has a .gitignore".to_string(),
+            _ => format!("Failed to create .gitignore: {}", e),
+        })?;
+    file.write_all(content.as_bytes()).map_err(|e| format!("Failed to write .gitignore: {}", e))
+}
+
+// ── Commands ──
+
+/// A `.gitignore` for `detected_stack`, or for the stacks detected in the
+/// window's project when omitted.
+#[tauri::command]
+pub fn suggest_gitignore(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    detected_stack: Option<Vec<Stack>>,
+) -> Result<GitignoreSuggestion, String> {
+    let root = state.blocking_read().get(window.label()).and_then(|o| o.clone()).ok_or("No project is open")?;
+    Ok(suggest(&root, detected_stack))
+}
+
+/// Create the project's `.gitignore` with `content`, typically an
+/// accepted suggestion. Fails if the file already exists.
+#[tauri::command]
+pub fn write_gitignore(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    content: String,
+) -> Result<(), String> {
+    let root = state.blocking_read().get(window.label()).and_then(|o| o.clone()).ok_or("No project is open")?;
+    write(&root, &content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_manifests_and_composes_templates() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("package.json"), "{}").unwrap();
+        std::fs::create_dir(root.join("src-tauri")).unwrap();
+        std::fs::write(root.join("src-tauri/Cargo.toml"), "").unwrap();
+        std::fs::create_dir_all(root.join("node_modules/x")).unwrap();
+        std::fs::write(root.join("node_modules/x/setup.py"), "").unwrap();
+        assert_eq!(detect_stack(root), [Stack::Rust, Stack::Node, Stack::Macos, Stack::Ide]);
+
+        let content = compose(&[Stack::Python, Stack::Node]);
+        assert!(content.starts_with("# Node\nnode_modules/\ndist/\nbuild/\n"));
+        // Python's dist/ and build/ are already covered by the Node section.
+        let python = content.split("# Python\n").nth(1).unwrap();
+        assert!(python.starts_with("__pycache__/\n*.py[cod]\n.venv/\nvenv/\n*.egg-info/\n.pytest_cache/\n"));
+
+        let suggestion = suggest(root, None);
+        assert!(!suggestion.exists);
+        write(root, &suggestion.content).unwrap();
+        assert!(std::fs::read_to_string(root.join(".gitignore")).unwrap().contains("/target/\n"));
+        assert_eq!(write(root, "x").unwrap_err(), "The project already has a .gitignore");
+        assert!(suggest(root, Some(vec![Stack::Macos])).exists);
+    }
+}
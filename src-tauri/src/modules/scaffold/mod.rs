@@ -0,0 +1,291 @@
+//! New project scaffolding.
+//!
+//! `create_project` fills a new folder from one of the built-in templates,
+//! or hands off to `cargo new` / `npm create`, and then makes it the
+//! window's project root. A folder that was created for the project is
+//! removed again if scaffolding fails.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::modules::fs::{journal, ProjectRootState};
+use crate::modules::process::ProcessCommand;
+
+pub mod generators;
+pub mod gitignore;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ProjectTemplate {
+    pub id: String,
+    pub label: String,
+    pub description: String,
+    /// Program the template shells out to, if any.
+    pub tool: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ProjectOptions {
+    /// Project name used in generated files; defaults to the folder name.
+    pub name: Option<String>,
+    /// Run `git init` (for cargo, leave its own git setup in place).
+    pub git: bool,
+    /// Package for `npm create`, e.g. `vite@latest`. Default `vite@latest`.
+    pub initializer: Option<String>,
+    /// Extra arguments passed to the initializer after `--`,
+    /// e.g. `["--template", "svelte-ts"]`.
+    pub initializer_args: Vec<String>,
+    /// Add a `.gitignore` for the project's stack if the template didn't
+    /// create one.
+    pub gitignore: bool,
+}
+
+type Files = &'static [(&'static str, &'static str)];
+
+const STATIC_SITE: Files = &[
+    (
+        "index.html",
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n  <meta charset=\"UTF-8\">\n  <meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">\n  <title>{{name}}</title>\n  <link rel=\"stylesheet\" href=\"style.css\">\n</head>\n<body>\n  <h1>{{name}}</h1>\n  <script src=\"script.js\"></script>\n</body>\n</html>\n",
+    ),
+    ("style.css", "body {\n  font-family: system-ui, sans-serif;\n  margin: 2rem;\n}\n"),
+    ("script.js", "console.log('{{name}}');\n"),
+    ("README.md", "# {{name}}\n"),
+];
+
+const PYTHON: Files = &[
+    ("main.py", "def main():\n    print(\"Hello from {{name}}\")\n\n\nif __name__ == \"__main__\":\n    main()\n"),
+    ("requirements.txt", ""),
+    (".gitignore", "__pycache__/\n*.pyc\n.venv/\n"),
+    ("README.md", "# {{name}}\n\n```sh\npython main.py\n```\n"),
+];
+
+const EMPTY: Files = &[("README.md", "# {{name}}\n")];
+
+enum Kind {
+    Files(Files),
+    Cargo { lib: bool },
+    NpmCreate,
+}
+
+const TEMPLATES: &[(&str, &str, &str, Kind)] = &[
+    ("empty", "Empty", "A folder with a README", Kind::Files(EMPTY)),
+    ("static-site", "Static site", "HTML, CSS and JavaScript", Kind::Files(STATIC_SITE)),
+    ("python", "Python", "A script with a requirements file", Kind::Files(PYTHON)),
+    ("cargo-bin", "Rust binary", "cargo new", Kind::Cargo { lib: false }),
+    ("cargo-lib", "Rust library", "cargo new --lib", Kind::Cargo { lib: true }),
+    ("npm-create", "npm create", "npm create vite@latest, or another initializer", Kind::NpmCreate),
+];
+
+fn npm() -> &'static str {
+    if cfg!(windows) {
+        "npm.cmd"
+    } else {
+        "npm"
+    }
+}
+
+pub fn templates() -> Vec<ProjectTemplate> {
+    TEMPLATES
+        .iter()
+        .map(|(id, label, description, kind)| ProjectTemplate {
+            id: id.to_string(),
+            label: label.to_string(),
+            description: description.to_string(),
+            tool: match kind {
+                Kind::Files(_) => None,
+                Kind::Cargo { .. } => Some("cargo".to_string()),
+                Kind::NpmCreate => Some("npm".to_string()),
+            },
+        })
+        .collect()
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.starts_with('.') || name.chars().any(|c| matches!(c, '/' | '\\' | ':' | '\0')) {
+        return Err(format!("Invalid project name: {:?}", name));
+    }
+    Ok(())
+}
+
+fn run(program: &str, args: &[&str], cwd: &Path) -> Result<(), String> {
+    let output = ProcessCommand::new(program)
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if !output.status.success() {
+        return Err(format!("{} failed: {}", program, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(())
+}
+
+fn fill(dest: &Path, name: &str, kind: &Kind, options: &ProjectOptions) -> Result<(), String> {
+    let parent = dest.parent().ok_or("Destination has no parent folder")?;
+    let folder = dest.file_name().and_then(|n| n.to_str()).ok_or("Invalid destination")?;
+    match kind {
+        Kind::Files(files) => {
+            for (path, contents) in files.iter() {
+                std::fs::write(dest.join(path), contents.replace("{{name}}", name)).map_err(|e| e.to_string())?;
+            }
+            if options.git {
+                run("git", &["init"], dest)?;
+            }
+        }
+        Kind::Cargo { lib } => {
+            let mut args = vec!["new", folder, "--name", name, "--vcs", if options.git { "git" } else { "none" }];
+            if *lib {
+                args.push("--lib");
+            }
+            run("cargo", &args, parent)?;
+        }
+        Kind::NpmCreate => {
+            let initializer = options.initializer.as_deref().unwrap_or("vite@latest");
+            if initializer.starts_with('-') {
+                return Err(format!("Invalid initializer: {}", initializer));
+            }
+            let mut args = vec!["create", "--yes", initializer, folder];
+            if !options.initializer_args.is_empty() {
+                args.push("--");
+                args.extend(options.initializer_args.iter().map(String::as_str));
+            }
+            run(npm(), &args, parent)?;
+            if options.git {
+                run("git", &["init"], dest)?;
+            }
+        }
+    }
+    if options.gitignore && dest.is_dir() && !dest.join(".gitignore").exists() {
+        gitignore::write(dest, &gitignore::compose(&gitignore::detect_stack(dest)))?;
+    }
+    Ok(())
+}
+
+/// Scaffold `template` into `dest` and return its canonical path. `dest`
+/// must not exist yet, or be an empty folder.
+pub fn scaffold(template: &str, dest: &Path, options: &ProjectOptions) -> Result<PathBuf, String> {
+    let (_, _, _, kind) =
+        TEMPLATES.iter().find(|t| t.0 == template).ok_or_else(|| format!("Unknown template: {}", template))?;
+    let folder = dest.file_name().and_then(|n| n.to_str()).ok_or("Invalid destination")?;
+    let name = options.name.as_deref().unwrap_or(folder);
+    validate_name(folder)?;
+    validate_name(name)?;
+    if !dest.parent().is_some_and(Path::is_dir) {
+        return Err("The parent folder does not exist".to_string());
+    }
+
+    let existed = dest.exists();
+    if existed {
+        let mut entries = std::fs::read_dir(dest).map_err(|e| e.to_string())?;
+        if entries.next().is_some() {
+            return Err(format!("{} already exists and is not empty", dest.display()));
+        }
+    }
+    // cargo and npm create the folder themselves (and cargo refuses an
+    // existing one), so an empty folder is removed first and restored on
+    // failure.
+    let tool_creates = !matches!(kind, Kind::Files(_));
+    if existed && tool_creates {
+        std::fs::remove_dir(dest).map_err(|e| e.to_string())?;
+    } else if !existed && !tool_creates {
+        std::fs::create_dir(dest).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    if let Err(e) = fill(dest, name, kind, options) {
+        if existed {
+            if dest.exists() {
+                let _ = std::fs::remove_dir_all(dest);
+            }
+            let _ = std::fs::create_dir(dest);
+        } else {
+            let _ = std::fs::remove_dir_all(dest);
+        }
+        return Err(e);
+    }
+    if !dest.is_dir() {
+        return Err(format!("{} did not create {}", template, dest.display()));
+    }
+    std::fs::canonicalize(dest).map_err(|e| e.to_string())
+}
+
+// ── Commands ──
+
+#[tauri::command]
+pub fn list_project_templates() -> Vec<ProjectTemplate> {
+    templates()
+}
+
+/// Create a project at `dest` from `template` and open it in the calling
+/// window. Returns the project root. Does not require a project to be open.
+#[tauri::command]
+pub async fn create_project(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    template: String,
+    dest: String,
+    options: Option<ProjectOptions>,
+) -> Result<String, String> {
+    let options = options.unwrap_or_default();
+    let root = tokio::task::spawn_blocking(move || scaffold(&template, Path::new(&dest), &options))
+        .await
+        .map_err(|e| format!("Project creation failed: {}", e))??;
+    let label = window.label().to_string();
+    state.write().await.insert(label.clone(), Some(root.clone()));
+    journal::clear(&label);
+    Ok(root.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::process::{with_runner, MockResponse, MockRunner};
+    use std::sync::Arc;
+
+    #[test]
+    fn scaffolds_built_in_templates() {
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("site");
+        let root = scaffold("static-site", &dest, &ProjectOptions::default()).unwrap();
+        assert_eq!(root, dest.canonicalize().unwrap());
+        assert!(std::fs::read_to_string(dest.join("index.html")).unwrap().contains("<title>site</title>"));
+
+        // An empty folder may be reused; a non-empty one may not.
+        let empty = dir.path().join("empty");
+        std::fs::create_dir(&empty).unwrap();
+        let options = ProjectOptions { name: Some("Notes".into()), ..Default::default() };
+        scaffold("empty", &empty, &options).unwrap();
+        assert_eq!(std::fs::read_to_string(empty.join("README.md")).unwrap(), "# Notes\n");
+        assert!(!empty.join(".gitignore").exists());
+        let options = ProjectOptions { gitignore: true, ..Default::default() };
+        scaffold("static-site", &dir.path().join("ignored"), &options).unwrap();
+        assert!(std::fs::read_to_string(dir.path().join("ignored/.gitignore")).unwrap().starts_with("# macOS\n.DS_Store\n"));
+        assert!(scaffold("python", &empty, &ProjectOptions::default()).unwrap_err().contains("not empty"));
+
+        assert!(scaffold("cobol", &dir.path().join("x"), &ProjectOptions::default()).is_err());
+        assert!(scaffold("empty", &dir.path().join("missing/x"), &ProjectOptions::default()).is_err());
+        assert!(scaffold("empty", &dir.path().join(".hidden"), &ProjectOptions::default()).is_err());
+    }
+
+    #[test]
+    fn shells_out_to_cargo_and_cleans_up_on_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("tool");
+        let mock = Arc::new(
+            MockRunner::new()
+                .on("cargo", &["new", "tool", "--name", "tool", "--vcs", "none", "--lib"], MockResponse::fail(101, "error: no network")),
+        );
+        let err = with_runner(mock.clone(), || scaffold("cargo-lib", &dest, &ProjectOptions::default())).unwrap_err();
+        assert_eq!(err, "cargo failed: error: no network");
+        assert!(!dest.exists());
+        assert_eq!(mock.calls()[0].cwd.as_deref(), Some(dir.path()));
+
+        let mock = Arc::new(MockRunner::new().on(
+            "npm",
+            &["create", "--yes", "vite@latest", "tool", "--", "--template", "svelte-ts"],
+            MockResponse::ok(""),
+        ));
+        let options = ProjectOptions { initializer_args: vec!["--template".into(), "svelte-ts".into()], ..Default::default() };
+        // The mock creates nothing, which must be reported rather than opened.
+        let err = with_runner(mock, || scaffold("npm-create", &dest, &options)).unwrap_err();
+        assert!(err.contains("did not create"), "{err}");
+    }
+}
@@ -0,0 +1,314 @@
+//! Copy-paste detection across the project.
+//!
+//! Source files are split into tokens with identifiers, strings and
+//! numbers normalized to placeholders, so a block that was copied and had
+//! its variables renamed still matches. Every run of `min_tokens` tokens
+//! gets a Rabin-Karp rolling hash; runs that share a hash are compared
+//! token by token and grown to the longest common stretch. `similarity`
+//! then says how much of the original text (before normalization) is
+//! identical: 1.0 for a verbatim copy, less for a renamed one.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::{project_files, read_text};
+use crate::modules::fs::{generated, project_root_async, resolve_within_root, ProjectRootState};
+
+const DEFAULT_MIN_TOKENS: usize = 50;
+const MIN_MIN_TOKENS: usize = 10;
+const MAX_RESULTS: usize = 200;
+/// Tokens indexed across the project before later files are skipped.
+const MAX_TOTAL_TOKENS: usize = 2_000_000;
+/// Windows repeated more often than this are boilerplate, not copies.
+const MAX_BUCKET: usize = 32;
+const HASH_BASE: u64 = 1_000_003;
+
+const CODE_EXTENSIONS: &[&str] = &[
+    "rs", "ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs", "svelte", "vue", "py", "go", "java", "kt", "c", "h",
+    "cpp", "cc", "cxx", "hpp", "cs", "swift", "rb", "php", "scala", "dart", "lua",
+];
+
+/// Words kept as themselves so `if` and `return` don't match any name.
+const KEYWORDS: &[&str] = &[
+    "if", "else", "for", "while", "loop", "do", "return", "break", "continue", "match", "switch", "case", "fn",
+    "function", "def", "class", "struct", "enum", "impl", "trait", "interface", "let", "const", "var", "mut", "pub",
+    "static", "new", "try", "catch", "finally", "throw", "async", "await", "import", "export", "from", "use", "in",
+    "of", "true", "false", "null", "None", "self", "this",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    /// Hash of the normalized token.
+    kind: u64,
+    /// Hash of the token as written.
+    raw: u64,
+    line: usize,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct DuplicateLocation {
+    pub path: String,
+    /// 1-based, inclusive.
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Duplicate {
+    pub tokens: usize,
+    pub lines: usize,
+    /// Share of tokens identical before normalization, 0.0 to 1.0.
+    pub similarity: f32,
+    pub locations: Vec<DuplicateLocation>,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct DuplicateReport {
+    /// Largest first.
+    pub duplicates: Vec<Duplicate>,
+    pub files_scanned: usize,
+    /// Whether files or results were left out because of the caps.
+    pub truncated: bool,
+}
+
+fn hash_str(s: &str) -> u64 {
+    // FNV-1a: stable across runs and cheap for short tokens.
+    s.bytes().fold(0xcbf29ce484222325, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3))
+}
+
+/// Split `source` into tokens. `rust` reads `'a` as a lifetime rather
+/// than the start of a string.
+fn tokenize(source: &str, rust: bool) -> Vec<Token> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let (mut i, mut line) = (0, 1);
+    let mut push =
+        |kind: &str, raw: &str, line: usize| tokens.push(Token { kind: hash_str(kind), raw: hash_str(raw), line });
+    let is_word = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || b == b'$' || b >= 0x80;
+    while i < bytes.len() {
+        let c = bytes[i];
+        let start = i;
+        if c == b'\n' {
+            line += 1;
+            i += 1;
+        } else if c.is_ascii_whitespace() {
+            i += 1;
+        } else if source[i..].starts_with("//") || c == b'#' {
+            while i < bytes.len() && bytes[i] != b'\n' {
+                i += 1;
+            }
+        } else if source[i..].starts_with("/*") {
+            let end = source[i + 2..].find("*/").map_or(bytes.len(), |e| i + 2 + e + 2);
+            line += source[i..end].matches('\n').count();
+            i = end;
+        } else if rust
+            && c == b'\''
+            && bytes.get(i + 1).is_some_and(|b| b.is_ascii_alphabetic() || *b == b'_')
+            && bytes.get(i + 2) != Some(&b'\'')
+        {
+            i += 1;
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                i += 1;
+            }
+            push("$id", &source[start..i], line);
+        } else if c == b'"' || c == b'\'' || c == b'`' {
+            i += 1;
+            // Quotes other than backticks don't span lines unescaped.
+            while i < bytes.len() && bytes[i] != c && (c == b'`' || bytes[i] != b'\n') {
+                if bytes[i] == b'\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            i = (i + 1).min(bytes.len());
+            push("$str", &source[start..i], line);
+            line += source[start..i].matches('\n').count();
+        } else if c.is_ascii_digit() {
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'.' || bytes[i] == b'_') {
+                i += 1;
+            }
+            push("$num", &source[start..i], line);
+        } else if is_word(c) {
+            while i < bytes.len() && is_word(bytes[i]) {
+                i += 1;
+            }
+            let word = &source[start..i];
+            push(if KEYWORDS.contains(&word) { word } else { "$id" }, word, line);
+        } else {
+            i += 1;
+            push(&source[start..i], &source[start..i], line);
+        }
+    }
+    tokens
+}
+
+/// Rolling hashes of every `window`-token run, indexed by start.
+fn window_hashes(tokens: &[Token], window: usize) -> Vec<u64> {
+    if tokens.len() < window {
+        return Vec::new();
+    }
+    let top = (1..window).fold(1u64, |p, _| p.wrapping_mul(HASH_BASE));
+    let mut hash = tokens[..window].iter().fold(0u64, |h, t| h.wrapping_mul(HASH_BASE).wrapping_add(t.kind));
+    let mut hashes = vec![hash];
+    for i in window..tokens.len() {
+        hash = hash
+            .wrapping_sub(tokens[i - window].kind.wrapping_mul(top))
+            .wrapping_mul(HASH_BASE)
+            .wrapping_add(tokens[i].kind);
+        hashes.push(hash);
+    }
+    hashes
+}
+
+fn find_in(files: &[(String, Vec<Token>)], min_tokens: usize) -> (Vec<Duplicate>, bool) {
+    let mut buckets: HashMap<u64, Vec<(usize, usize)>> = HashMap::new();
+    for (f, (_, tokens)) in files.iter().enumerate() {
+        for (start, hash) in window_hashes(tokens, min_tokens).into_iter().enumerate() {
+            buckets.entry(hash).or_default().push((f, start));
+        }
+    }
+    let kinds_equal = |(fa, a): (usize, usize), (fb, b): (usize, usize)| files[fa].1[a].kind == files[fb].1[b].kind;
+    let mut duplicates = Vec::new();
+    for occurrences in buckets.values().filter(|o| o.len() > 1 && o.len() <= MAX_BUCKET) {
+        for (n, &(fa, a)) in occurrences.iter().enumerate() {
+            for &(fb, b) in &occurrences[n + 1..] {
+                let (ta, tb) = (&files[fa].1, &files[fb].1);
+                if fa == fb && b < a + min_tokens {
+                    continue; // overlaps itself
+                }
+                if (0..min_tokens).any(|k| ta[a + k].kind != tb[b + k].kind) {
+                    continue; // hash collision
+                }
+                // Only report from the start of the run; later windows on
+                // the same diagonal are part of this one.
+                if a > 0 && b > 0 && kinds_equal((fa, a - 1), (fb, b - 1)) {
+                    continue;
+                }
+                let mut len = min_tokens;
+                while a + len < ta.len() && b + len < tb.len() && ta[a + len].kind == tb[b + len].kind {
+                    if fa == fb && b <= a + len {
+                        break;
+                    }
+                    len += 1;
+                }
+                let same = (0..len).filter(|k| ta[a + k].raw == tb[b + k].raw).count();
+                let location = |path: &str, tokens: &[Token], start: usize| DuplicateLocation {
+                    path: path.to_string(),
+                    start_line: tokens[start].line,
+                    end_line: tokens[start + len - 1].line,
+                };
+                let first = location(&files[fa].0, ta, a);
+                duplicates.push(Duplicate {
+                    tokens: len,
+                    lines: first.end_line - first.start_line + 1,
+                    similarity: same as f32 / len as f32,
+                    locations: vec![first, location(&files[fb].0, tb, b)],
+                });
+            }
+        }
+    }
+    duplicates.sort_by(|x, y| {
+        y.tokens.cmp(&x.tokens).then_with(|| {
+            let key = |d: &Duplicate| (d.locations[0].path.clone(), d.locations[0].start_line);
+            key(x).cmp(&key(y))
+        })
+    });
+    let truncated = duplicates.len() > MAX_RESULTS;
+    duplicates.truncate(MAX_RESULTS);
+    (duplicates, truncated)
+}
+
+fn scan(root: &Path, dir: &Path, min_tokens: usize, include_generated: bool) -> DuplicateReport {
+    let mut files = Vec::new();
+    let mut total = 0;
+    let mut truncated = false;
+    let generated_dirs = generated::GeneratedDirs::for_project(root);
+    for path in project_files(root, dir) {
+        let is_code = path.extension().and_then(|e| e.to_str()).is_some_and(|e| CODE_EXTENSIONS.contains(&e));
+        if !is_code
+            || (!include_generated && (generated_dirs.contains(&path) || generated::check_file(&path).is_some()))
+        {
+            continue;
+        }
+        let Some(source) = read_text(&path) else { continue };
+        let tokens = tokenize(&source, path.extension().is_some_and(|e| e == "rs"));
+        if total + tokens.len() > MAX_TOTAL_TOKENS {
+            truncated = true;
+            break;
+        }
+        total += tokens.len();
+        let rel = path.strip_prefix(root).unwrap_or(&path).to_string_lossy().to_string();
+        files.push((rel, tokens));
+    }
+    let (duplicates, capped) = find_in(&files, min_tokens);
+    DuplicateReport { duplicates, files_scanned: files.len(), truncated: truncated || capped }
+}
+
+/// Blocks of at least `min_tokens` tokens (default 50) that appear more
+/// than once in the project, or under `path` when given. Generated files
+/// are skipped unless `include_generated` is set.
+#[tauri::command]
+pub async fn find_duplicates(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    min_tokens: Option<usize>,
+    path: Option<String>,
+    include_generated: Option<bool>,
+) -> Result<DuplicateReport, String> {
+    let root = project_root_async(window.label(), &state).await?;
+    let dir: PathBuf = match path {
+        Some(p) => resolve_within_root(&p, &root)?,
+        None => root.clone(),
+    };
+    let min_tokens = min_tokens.unwrap_or(DEFAULT_MIN_TOKENS).max(MIN_MIN_TOKENS);
+    tokio::task::spawn_blocking(move || scan(&root, &dir, min_tokens, include_generated.unwrap_or(false)))
+        .await
+        .map_err(|e| format!("Duplicate search failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGINAL: &str = "fn total(items: &[Item]) -> u32 {\n    let mut sum = 0;\n    for item in items {\n        if item.active {\n            sum += item.price * 2;\n        }\n    }\n    sum\n}\n";
+
+    #[test]
+    fn normalizes_names_and_literals() {
+        let a = tokenize("let x = \"a\"; // note\nfoo(1)", false);
+        let b = tokenize("let y = 'b'; /* other */\nbar(22)", false);
+        let kinds = |t: &[Token]| t.iter().map(|t| t.kind).collect::<Vec<_>>();
+        assert_eq!(kinds(&a), kinds(&b));
+        assert_eq!(a.last().unwrap().line, 2);
+        assert_ne!(a[1].raw, b[1].raw);
+
+        let lifetimes = tokenize("fn f<'a>(s: &'a str) -> char { 'x' }", true);
+        assert_eq!(lifetimes.len(), 18);
+    }
+
+    #[test]
+    fn finds_renamed_copies_once() {
+        let renamed = ORIGINAL.replace("total", "sum_prices").replace("item", "entry");
+        let files = vec![
+            ("a.rs".to_string(), tokenize(&format!("use x;\n\n{}", ORIGINAL), true)),
+            ("b.rs".to_string(), tokenize(&format!("// copy\n{}\nfn other() {{}}\n", renamed), true)),
+            ("c.rs".to_string(), tokenize("fn unrelated() { println!(\"hi\"); }\n", true)),
+        ];
+        let (found, truncated) = find_in(&files, 20);
+        assert!(!truncated);
+        assert_eq!(found.len(), 1);
+        let dup = &found[0];
+        assert_eq!(dup.tokens, tokenize(ORIGINAL, true).len());
+        assert_eq!(
+            dup.locations,
+            [
+                DuplicateLocation { path: "a.rs".into(), start_line: 3, end_line: 11 },
+                DuplicateLocation { path: "b.rs".into(), start_line: 2, end_line: 10 },
+            ]
+        );
+        assert!(dup.similarity > 0.6 && dup.similarity < 1.0, "{}", dup.similarity);
+
+        let verbatim = vec![("a.rs".to_string(), tokenize(ORIGINAL, true)), ("b.rs".to_string(), tokenize(ORIGINAL, true))];
+        assert_eq!(find_in(&verbatim, 20).0[0].similarity, 1.0);
+    }
+}
@@ -0,0 +1,433 @@
+//! Project-wide text search and replace.
+//!
+//! Files are walked with gitignore rules applied and binary files skipped.
+//! Every match gets an id of the form `path:offset:hash`, where `hash` is
+//! a short digest of the file content at search time. Replace uses those
+//! ids to apply only the matches the user kept, and files that changed
+//! since the preview are reported as stale instead of being rewritten.
+//! Generated files (see `fs::generated`) are skipped unless asked for, and
+//! the project's exclude patterns (see `fs::exclude`) always apply.
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::modules::fs::exclude::Excludes;
+use crate::modules::fs::{generated, history, project_root_async, resolve_within_root, validate_path, write_atomic, ProjectRootState};
+use crate::modules::operations::{self, Operation, OperationKind};
+
+pub mod duplicates;
+pub mod playground;
+pub mod structural;
+
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+const MAX_MATCHES: usize = 10_000;
+/// Compiled-size cap shared by every user-supplied regex.
+const REGEX_SIZE_LIMIT: usize = 10 * 1024 * 1024;
+const BINARY_SNIFF_BYTES: usize = 8192;
+
+// ── Types ──
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct SearchQuery {
+    pub pattern: String,
+    pub is_regex: bool,
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    /// Directory to search in; defaults to the project root.
+    pub path: Option<String>,
+    /// Also search lockfiles, bundles and other generated files.
+    pub include_generated: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SearchMatch {
+    pub id: String,
+    /// Path relative to the project root.
+    pub path: String,
+    /// 1-based line of the match start.
+    pub line: usize,
+    /// 0-based character column of the match start.
+    pub column: usize,
+    /// Length of the match in characters.
+    pub length: usize,
+    pub line_text: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SearchResults {
+    pub matches: Vec<SearchMatch>,
+    /// True when the match limit was hit and results are incomplete.
+    pub truncated: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct FileReplacement {
+    pub path: String,
+    pub replacements: usize,
+    /// Unified diff of the change, for preview.
+    pub diff: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ReplaceResult {
+    pub files: Vec<FileReplacement>,
+    /// False for a preview; true once files were written.
+    pub applied: bool,
+    /// Files skipped because they changed since the preview.
+    pub stale: Vec<String>,
+    /// Files that failed to write, with the error.
+    pub failed: Vec<(String, String)>,
+}
+
+// ── Matching ──
+
+fn build_regex(query: &SearchQuery) -> Result<Regex, String> {
+    if query.pattern.is_empty() {
+        return Err("Search pattern is empty".to_string());
+    }
+    let mut pattern = if query.is_regex { query.pattern.clone() } else { regex::escape(&query.pattern) };
+    if query.whole_word {
+        pattern = format!(r"\b(?:{})\b", pattern);
+    }
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!query.case_sensitive)
+        .multi_line(true)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|e| format!("Invalid regex: {}", e))
+}
+
+pub(crate) fn content_hash(content: &str) -> String {
+    let digest = format!("{:x}", Sha256::digest(content.as_bytes()));
+    digest[..12].to_string()
+}
+
+pub(crate) fn match_id(rel: &str, offset: usize, hash: &str) -> String {
+    format!("{}:{}:{}", rel, offset, hash)
+}
+
+/// Read a file as text, skipping large and binary files.
+pub(crate) fn read_text(path: &Path) -> Option<String> {
+    let meta = std::fs::metadata(path).ok()?;
+    if meta.len() > MAX_FILE_BYTES {
+        return None;
+    }
+    let bytes = std::fs::read(path).ok()?;
+    if bytes[..bytes.len().min(BINARY_SNIFF_BYTES)].contains(&0) {
+        return None;
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Files under `dir`, minus gitignored files and the project's exclude
+/// patterns.
+pub(crate) fn project_files(root: &Path, dir: &Path) -> impl Iterator<Item = PathBuf> {
+    let excludes = Excludes::for_project(root);
+    ignore::WalkBuilder::new(dir)
+        .hidden(false)
+        .filter_entry(move |e| {
+            e.file_name() != ".git" && !excludes.is_excluded(e.path(), e.file_type().is_some_and(|t| t.is_dir()))
+        })
+        .build()
+        .flatten()
+        .filter(|e| e.file_type().is_some_and(|t| t.is_file()))
+        .map(|e| e.into_path())
+}
+
+fn find_in_file(re: &Regex, rel: &str, content: &str, out: &mut Vec<SearchMatch>) {
+    let hash = content_hash(content);
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    for m in re.find_iter(content) {
+        if out.len() >= MAX_MATCHES {
+            return;
+        }
+        // Zero-width matches (`^`, `\b`) have nothing to replace or show.
+        if m.start() == m.end() {
+            continue;
+        }
+        let line_idx = line_starts.partition_point(|&s| s <= m.start()) - 1;
+        let line_start = line_starts[line_idx];
+        let line_end = content[line_start..].find('\n').map(|i| line_start + i).unwrap_or(content.len());
+        out.push(SearchMatch {
+            id: match_id(rel, m.start(), &hash),
+            path: rel.to_string(),
+            line: line_idx + 1,
+            column: content[line_start..m.start()].chars().count(),
+            length: m.as_str().chars().count(),
+            line_text: content[line_start..line_end].trim_end_matches('\r').to_string(),
+        });
+    }
+}
+
+/// Apply the replacement to the matches whose ids are in `selected`
+/// (all matches when `None`). Returns the new content and the count.
+fn replace_selected(
+    re: &Regex,
+    rel: &str,
+    content: &str,
+    replacement: &str,
+    literal: bool,
+    selected: Option<&HashSet<String>>,
+) -> (String, usize) {
+    let hash = content_hash(content);
+    let mut out = String::with_capacity(content.len());
+    let mut last = 0;
+    let mut count = 0;
+    for caps in re.captures_iter(content) {
+        let m = caps.get(0).expect("group 0 always matches");
+        if m.start() == m.end() {
+            continue;
+        }
+        if let Some(ids) = selected {
+            if !ids.contains(&match_id(rel, m.start(), &hash)) {
+                continue;
+            }
+        }
+        out.push_str(&content[last..m.start()]);
+        if literal {
+            out.push_str(replacement);
+        } else {
+            caps.expand(replacement, &mut out);
+        }
+        last = m.end();
+        count += 1;
+    }
+    out.push_str(&content[last..]);
+    (out, count)
+}
+
+pub(crate) fn unified_diff(rel: &str, old: &str, new: &str) -> String {
+    similar::TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("a/{}", rel), &format!("b/{}", rel))
+        .to_string()
+}
+
+pub(crate) fn search_root(
+    path: Option<&str>,
+    window_label: &str,
+    state: &tauri::State<'_, ProjectRootState>,
+) -> Result<(PathBuf, PathBuf), String> {
+    let root = {
+        let map = state.blocking_read();
+        map.get(window_label).and_then(|o| o.clone()).ok_or("No project is open")?
+    };
+    let dir = match path {
+        Some(p) => validate_path(p, window_label, state)?,
+        None => root.clone(),
+    };
+    Ok((root, dir))
+}
+
+/// Search the files under `dir`; match paths are relative to `root`.
+/// With `op`, each file scanned is reported as progress and a cancel stops
+/// the search with an error.
+pub(crate) fn search_files(
+    root: &Path,
+    dir: &Path,
+    query: &SearchQuery,
+    op: Option<&Operation>,
+) -> Result<SearchResults, String> {
+    let re = build_regex(query)?;
+    let generated_dirs = generated::GeneratedDirs::for_project(root);
+    let mut matches = Vec::new();
+    for (scanned, path) in project_files(root, dir).enumerate() {
+        if matches.len() >= MAX_MATCHES {
+            break;
+        }
+        if let Some(op) = op {
+            if op.is_cancelled() {
+                return Err("Search cancelled".to_string());
+            }
+            let rel = path.strip_prefix(root).unwrap_or(&path);
+            op.progress(scanned as u64, None, Some(rel.to_string_lossy().into_owned()));
+        }
+        if !query.include_generated && generated_dirs.contains(&path) {
+            continue;
+        }
+        let Some(content) = read_text(&path) else { continue };
+        if !query.include_generated && generated::is_generated(&path, &content).is_some() {
+            continue;
+        }
+        let rel = path.strip_prefix(root).unwrap_or(&path).to_string_lossy().to_string();
+        find_in_file(&re, &rel, &content, &mut matches);
+    }
+    Ok(SearchResults { truncated: matches.len() >= MAX_MATCHES, matches })
+}
+
+/// Selected match ids grouped by file, with the content hash each file had
+/// when the ids were produced.
+type SelectedIds = HashMap<String, (String, HashSet<String>)>;
+
+fn group_ids_by_file(ids: Vec<String>) -> SelectedIds {
+    let mut by_file = SelectedIds::new();
+    for id in ids {
+        let mut parts = id.rsplitn(3, ':');
+        let (Some(hash), Some(_offset), Some(rel)) = (parts.next(), parts.next(), parts.next()) else { continue };
+        let entry = by_file.entry(rel.to_string()).or_insert_with(|| (hash.to_string(), HashSet::new()));
+        entry.1.insert(id);
+    }
+    by_file
+}
+
+// ── Commands ──
+
+/// Runs as a cancellable background operation.
+#[tauri::command]
+pub async fn search_in_project(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    query: SearchQuery,
+) -> Result<SearchResults, String> {
+    let root = project_root_async(window.label(), &state).await?;
+    let dir = match query.path.as_deref() {
+        Some(p) => resolve_within_root(p, &root)?,
+        None => root.clone(),
+    };
+    let op = operations::manager().start(OperationKind::Search, &query.pattern, Some(window.label()), true);
+    tokio::task::spawn_blocking(move || op.track(search_files(&root, &dir, &query, Some(&op))))
+        .await
+        .map_err(|e| format!("Search failed: {}", e))?
+}
+
+/// Replace matches of `query` across the project. With `apply: false`
+/// (the default) nothing is written and a per-file diff is returned for
+/// review. With `apply: true` each file is rewritten atomically.
+/// `match_ids` limits the replacement to matches from an earlier
+/// `search_in_project` or preview; files whose content changed since are
+/// reported in `stale` and left untouched. For regex queries, `$1` /
+/// `${name}` in the replacement expand to capture groups.
+#[tauri::command]
+pub fn replace_in_project(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    query: SearchQuery,
+    replacement: String,
+    match_ids: Option<Vec<String>>,
+    apply: Option<bool>,
+) -> Result<ReplaceResult, String> {
+    let re = build_regex(&query)?;
+    let (root, dir) = search_root(query.path.as_deref(), window.label(), &state)?;
+    let apply = apply.unwrap_or(false);
+    let literal = !query.is_regex;
+
+    let selected = match_ids.map(group_ids_by_file);
+
+    let generated_dirs = generated::GeneratedDirs::for_project(&root);
+    let candidates: Vec<PathBuf> = match &selected {
+        Some(by_file) => by_file.keys().map(|rel| root.join(rel)).collect(),
+        None => project_files(&root, &dir)
+            .filter(|path| query.include_generated || !generated_dirs.contains(path))
+            .collect(),
+    };
+
+    let mut result = ReplaceResult { files: Vec::new(), applied: apply, stale: Vec::new(), failed: Vec::new() };
+    for path in candidates {
+        let rel = path.strip_prefix(&root).unwrap_or(&path).to_string_lossy().to_string();
+        // Ids come from the frontend; re-validate every path they name.
+        if validate_path(&path.to_string_lossy(), window.label(), &state).is_err() {
+            continue;
+        }
+        let Some(content) = read_text(&path) else { continue };
+        if selected.is_none() && !query.include_generated && generated::is_generated(&path, &content).is_some() {
+            continue;
+        }
+        let ids = match &selected {
+            Some(by_file) => {
+                let (hash, ids) = &by_file[&rel];
+                if *hash != content_hash(&content) {
+                    result.stale.push(rel);
+                    continue;
+                }
+                Some(ids)
+            }
+            None => None,
+        };
+        let (new_content, count) = replace_selected(&re, &rel, &content, &replacement, literal, ids);
+        if count == 0 || new_content == content {
+            continue;
+        }
+        if apply {
+            history::keep(&root, &path, new_content.as_bytes());
+            if let Err(e) = write_atomic(&path, new_content.as_bytes()) {
+                result.failed.push((rel, e));
+                continue;
+            }
+        }
+        result.files.push(FileReplacement { diff: unified_diff(&rel, &content, &new_content), path: rel, replacements: count });
+    }
+    result.files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(pattern: &str, is_regex: bool) -> SearchQuery {
+        SearchQuery { pattern: pattern.into(), is_regex, case_sensitive: true, ..Default::default() }
+    }
+
+    #[test]
+    fn literal_patterns_are_escaped() {
+        let re = build_regex(&query("a.b(", false)).unwrap();
+        assert!(re.is_match("x a.b( y"));
+        assert!(!re.is_match("axb("));
+    }
+
+    #[test]
+    fn whole_word_and_case_options() {
+        let mut q = query("foo", false);
+        q.whole_word = true;
+        q.case_sensitive = false;
+        let re = build_regex(&q).unwrap();
+        assert!(re.is_match("call FOO()"));
+        assert!(!re.is_match("foobar"));
+    }
+
+    #[test]
+    fn matches_report_line_and_char_column() {
+        let re = build_regex(&query("needle", false)).unwrap();
+        let mut out = Vec::new();
+        find_in_file(&re, "a.txt", "first\nélan needle\n", &mut out);
+        assert_eq!(out.len(), 1);
+        assert_eq!((out[0].line, out[0].column, out[0].length), (2, 5, 6));
+        assert_eq!(out[0].line_text, "élan needle");
+    }
+
+    #[test]
+    fn replace_only_selected_matches() {
+        let re = build_regex(&query("x", false)).unwrap();
+        let content = "x x x";
+        let mut matches = Vec::new();
+        find_in_file(&re, "f", content, &mut matches);
+        let keep: HashSet<String> = [matches[1].id.clone()].into_iter().collect();
+        let (out, n) = replace_selected(&re, "f", content, "y", true, Some(&keep));
+        assert_eq!((out.as_str(), n), ("x y x", 1));
+    }
+
+    #[test]
+    fn regex_replacement_expands_groups_but_literal_does_not() {
+        let re = build_regex(&query(r"(\w+)@(\w+)", true)).unwrap();
+        let (out, _) = replace_selected(&re, "f", "me@host", "$2 at $1", false, None);
+        assert_eq!(out, "host at me");
+
+        let re = build_regex(&query("cost", false)).unwrap();
+        let (out, _) = replace_selected(&re, "f", "cost", "$5", true, None);
+        assert_eq!(out, "$5");
+    }
+
+    #[test]
+    fn preview_diff_is_unified() {
+        let diff = unified_diff("a.rs", "let a = 1;\n", "let b = 1;\n");
+        assert!(diff.contains("--- a/a.rs"));
+        assert!(diff.contains("-let a = 1;\n+let b = 1;"));
+    }
+}
@@ -0,0 +1,173 @@
+//! Regex tester backend.
+//!
+//! Patterns are evaluated with the same engine and limits as project
+//! search, so what matches here matches there. The `regex` crate never
+//! backtracks, so matching is linear in the input; the remaining risks are
+//! patterns that compile to huge automata and inputs with huge numbers of
+//! matches, which are bounded by the compile size limit, a match cap and a
+//! time budget.
+
+use regex::RegexBuilder;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+use super::REGEX_SIZE_LIMIT;
+
+const MAX_SAMPLE_BYTES: usize = 1024 * 1024;
+const MAX_TEST_MATCHES: usize = 1000;
+const TIME_BUDGET: Duration = Duration::from_millis(250);
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct RegexGroup {
+    pub index: usize,
+    pub name: Option<String>,
+    /// UTF-16 offsets into the sample, so the frontend can slice directly.
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct RegexMatch {
+    /// UTF-16 offsets into the sample.
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+    /// Capture groups 1.., `None` where a group didn't participate.
+    pub groups: Vec<Option<RegexGroup>>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RegexTestResult {
+    pub matches: Vec<RegexMatch>,
+    /// Names of groups 1.., `None` for unnamed groups.
+    pub group_names: Vec<Option<String>>,
+    pub compile_micros: u64,
+    pub match_micros: u64,
+    /// Stopped at the match cap or time budget.
+    pub truncated: bool,
+}
+
+/// Flags as letters: `i` case-insensitive, `m` multi-line, `s` dot
+/// matches newline, `x` verbose, `U` swap greedy, `g` all matches
+/// (otherwise only the first).
+fn build(pattern: &str, flags: &str) -> Result<(regex::Regex, bool), String> {
+    let mut builder = RegexBuilder::new(pattern);
+    builder.size_limit(REGEX_SIZE_LIMIT).dfa_size_limit(REGEX_SIZE_LIMIT);
+    let mut global = false;
+    for flag in flags.chars() {
+        match flag {
+            'i' => builder.case_insensitive(true),
+            'm' => builder.multi_line(true),
+            's' => builder.dot_matches_new_line(true),
+            'x' => builder.ignore_whitespace(true),
+            'U' => builder.swap_greed(true),
+            'g' => {
+                global = true;
+                continue;
+            }
+            other => return Err(format!("Unknown regex flag: {}", other)),
+        };
+    }
+    let regex = builder.build().map_err(|e| format!("Invalid regex: {}", e))?;
+    Ok((regex, global))
+}
+
+/// Maps byte offsets to UTF-16 offsets relative to the last lookup, so
+/// walking through the matches in order stays linear.
+struct Utf16Offsets<'a> {
+    text: &'a str,
+    byte: usize,
+    utf16: usize,
+}
+
+impl Utf16Offsets<'_> {
+    fn at(&mut self, byte: usize) -> usize {
+        if byte >= self.byte {
+            self.utf16 += self.text[self.byte..byte].encode_utf16().count();
+        } else {
+            self.utf16 -= self.text[byte..self.byte].encode_utf16().count();
+        }
+        self.byte = byte;
+        self.utf16
+    }
+}
+
+fn evaluate(pattern: &str, flags: &str, sample: &str) -> Result<RegexTestResult, String> {
+    if sample.len() > MAX_SAMPLE_BYTES {
+        return Err(format!("CONTENT_TOO_LARGE: {} bytes; limit {}", sample.len(), MAX_SAMPLE_BYTES));
+    }
+    let started = Instant::now();
+    let (regex, global) = build(pattern, flags)?;
+    let compile_micros = started.elapsed().as_micros() as u64;
+    let group_names: Vec<Option<String>> = regex.capture_names().skip(1).map(|n| n.map(str::to_string)).collect();
+
+    let started = Instant::now();
+    let mut offsets = Utf16Offsets { text: sample, byte: 0, utf16: 0 };
+    let mut matches = Vec::new();
+    let mut truncated = false;
+    for caps in regex.captures_iter(sample) {
+        if matches.len() >= MAX_TEST_MATCHES || started.elapsed() > TIME_BUDGET {
+            truncated = true;
+            break;
+        }
+        let whole = caps.get(0).expect("group 0 always participates");
+        let (start, end) = (offsets.at(whole.start()), offsets.at(whole.end()));
+        let groups = (1..caps.len())
+            .map(|i| {
+                caps.get(i).map(|g| RegexGroup {
+                    index: i,
+                    name: group_names[i - 1].clone(),
+                    start: offsets.at(g.start()),
+                    end: offsets.at(g.end()),
+                    text: g.as_str().to_string(),
+                })
+            })
+            .collect();
+        matches.push(RegexMatch { start, end, text: whole.as_str().to_string(), groups });
+        if !global {
+            break;
+        }
+    }
+
+    Ok(RegexTestResult {
+        matches,
+        group_names,
+        compile_micros,
+        match_micros: started.elapsed().as_micros() as u64,
+        truncated,
+    })
+}
+
+/// Run `pattern` against `sample_text` for the regex tester panel.
+#[tauri::command]
+pub fn test_regex(pattern: String, flags: Option<String>, sample_text: String) -> Result<RegexTestResult, String> {
+    evaluate(&pattern, flags.as_deref().unwrap_or("g"), &sample_text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_groups_in_utf16_offsets() {
+        let r = evaluate(r"(?P<key>\w+)=(\d+)?", "g", "é a=1 b=").unwrap();
+        assert_eq!(r.group_names, vec![Some("key".to_string()), None]);
+        assert_eq!(r.matches.len(), 2);
+        let first = &r.matches[0];
+        assert_eq!((first.start, first.end, first.text.as_str()), (2, 5, "a=1"));
+        assert_eq!(first.groups[0].as_ref().map(|g| (g.start, g.name.as_deref())), Some((2, Some("key"))));
+        assert_eq!(r.matches[1].groups[1], None);
+    }
+
+    #[test]
+    fn flags_and_limits() {
+        assert_eq!(evaluate("a", "", "aaa").unwrap().matches.len(), 1);
+        assert_eq!(evaluate("A", "gi", "aaa").unwrap().matches.len(), 3);
+        assert!(evaluate("a", "q", "a").unwrap_err().contains("flag"));
+        assert!(evaluate(r"\w{1000}{1000}", "g", "a").unwrap_err().starts_with("Invalid regex"));
+        let many = evaluate("a", "g", &"a".repeat(5000)).unwrap();
+        assert!(many.truncated);
+        assert_eq!(many.matches.len(), MAX_TEST_MATCHES);
+    }
+}
@@ -0,0 +1,481 @@
+//! Structural (syntax-tree) search and replace.
+//!
+//! A pattern is a code snippet in the target language with metavariables:
+//! `$NAME` matches any single node, `$$$NAME` matches any run of sibling
+//! nodes (including none), and `$_` matches without capturing. A name used
+//! twice must match the same text both times. So `foo($A, $B)` finds every
+//! two-argument call to `foo` regardless of formatting or nesting, and the
+//! rewrite `bar($B, $A)` swaps the arguments.
+//!
+//! Matching compares tree-sitter trees node by node, ignoring comments and
+//! whitespace. Matches never overlap: once a node matches, its children
+//! are not searched. Generated files are skipped unless
+//! `include_generated` is set.
+
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tree_sitter::{Node, Parser, Tree};
+
+use super::{
+    content_hash, group_ids_by_file, match_id, project_files, read_text, search_root, unified_diff,
+    FileReplacement, ReplaceResult, MAX_MATCHES,
+};
+use crate::modules::fs::workspace_edit::{apply_edit, FileOperation, Position, Range, TextEdit, WorkspaceEdit};
+use crate::modules::fs::{generated, validate_path, ProjectRootState};
+use crate::modules::symbols::language_for_extension;
+
+const SINGLE_PREFIX: &str = "__SSR_";
+const MULTI_PREFIX: &str = "__SSRM_";
+
+#[derive(Serialize, Clone, Debug)]
+pub struct StructuralMatch {
+    pub id: String,
+    /// Path relative to the project root.
+    pub path: String,
+    /// 1-based line and 0-based byte column of the match start.
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub text: String,
+    pub captures: HashMap<String, String>,
+    /// The rewritten text, when a rewrite template was given.
+    pub replacement: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct StructuralResults {
+    pub matches: Vec<StructuralMatch>,
+    pub truncated: bool,
+}
+
+// ── Languages ──
+
+struct LanguageSpec {
+    /// Extension handed to the tree-sitter language lookup.
+    parser_ext: &'static str,
+    /// Files searched for this language.
+    extensions: &'static [&'static str],
+    /// Contexts tried, in order, until the pattern parses cleanly.
+    wrappers: &'static [(&'static str, &'static str)],
+}
+
+fn language_spec(language: &str) -> Result<LanguageSpec, String> {
+    let script_wrappers: &'static [(&str, &str)] = &[("", ""), ("(", ")"), ("function __ssr() {", "}"), ("class __Ssr {", "}")];
+    Ok(match language.to_lowercase().as_str() {
+        "javascript" | "js" | "jsx" => LanguageSpec {
+            parser_ext: "js",
+            extensions: &["js", "jsx", "mjs", "cjs"],
+            wrappers: script_wrappers,
+        },
+        "typescript" | "ts" | "tsx" => LanguageSpec {
+            parser_ext: "ts",
+            extensions: &["ts", "tsx", "mts", "cts"],
+            wrappers: script_wrappers,
+        },
+        "rust" | "rs" => LanguageSpec {
+            parser_ext: "rs",
+            extensions: &["rs"],
+            wrappers: &[("", ""), ("fn __ssr() {", "}"), ("fn __ssr() {", ";}"), ("impl __Ssr {", "}")],
+        },
+        "python" | "py" => LanguageSpec {
+            parser_ext: "py",
+            extensions: &["py", "pyi"],
+            wrappers: &[("", ""), ("class __Ssr:\n    ", "\n")],
+        },
+        _ => return Err(format!("Structural search is not supported for '{}'", language)),
+    })
+}
+
+fn parse(source: &str, ext: &str) -> Option<Tree> {
+    let mut parser = Parser::new();
+    parser.set_language(&language_for_extension(ext)?).ok()?;
+    parser.parse(source, None)
+}
+
+// ── Patterns ──
+
+fn metavar_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\$(\$\$)?([A-Z_][A-Z0-9_]*)").expect("valid regex"))
+}
+
+struct Pattern {
+    tree: Tree,
+    source: String,
+    start: usize,
+    end: usize,
+}
+
+impl Pattern {
+    fn compile(pattern: &str, spec: &LanguageSpec) -> Result<Self, String> {
+        let pattern = pattern.trim();
+        if pattern.is_empty() {
+            return Err("Search pattern is empty".to_string());
+        }
+        let body = metavar_regex().replace_all(pattern, |caps: &regex::Captures| {
+            let prefix = if caps.get(1).is_some() { MULTI_PREFIX } else { SINGLE_PREFIX };
+            format!("{}{}", prefix, &caps[2])
+        });
+        for (prefix, suffix) in spec.wrappers {
+            let source = format!("{}{}{}", prefix, body, suffix);
+            let Some(tree) = parse(&source, spec.parser_ext) else { continue };
+            if tree.root_node().has_error() {
+                continue;
+            }
+            let (start, end) = (prefix.len(), prefix.len() + body.len());
+            let Some(node) = tree.root_node().named_descendant_for_byte_range(start, end) else { continue };
+            if node.start_byte() == start && node.end_byte() == end {
+                return Ok(Self { tree, source, start, end });
+            }
+        }
+        Err("Pattern is not valid code in this language".to_string())
+    }
+
+    fn root(&self) -> Node<'_> {
+        self.tree
+            .root_node()
+            .named_descendant_for_byte_range(self.start, self.end)
+            .expect("checked in compile")
+    }
+}
+
+/// Validate that every metavariable in `rewrite` is bound by the pattern.
+fn check_rewrite(pattern: &str, rewrite: &str) -> Result<(), String> {
+    let bound: Vec<&str> = metavar_regex().captures_iter(pattern).map(|c| c.get(2).map_or("", |m| m.as_str())).collect();
+    for caps in metavar_regex().captures_iter(rewrite) {
+        let name = &caps[2];
+        if name != "_" && !bound.contains(&name) {
+            return Err(format!("${} is not defined in the pattern", name));
+        }
+    }
+    Ok(())
+}
+
+fn expand(rewrite: &str, captures: &HashMap<String, String>) -> String {
+    metavar_regex()
+        .replace_all(rewrite, |caps: &regex::Captures| captures.get(&caps[2]).cloned().unwrap_or_default())
+        .into_owned()
+}
+
+// ── Matching ──
+
+type Bindings = HashMap<String, String>;
+
+/// `(name, is_multi)` when `node` is a metavariable placeholder.
+fn metavar(node: Node, source: &str) -> Option<(String, bool)> {
+    let text = &source[node.byte_range()];
+    if let Some(name) = text.strip_prefix(MULTI_PREFIX) {
+        return Some((name.to_string(), true));
+    }
+    let name = text.strip_prefix(SINGLE_PREFIX)?;
+    name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_').then(|| (name.to_string(), false))
+}
+
+fn significant_children(node: Node) -> Vec<Node> {
+    let mut cursor = node.walk();
+    node.children(&mut cursor).filter(|c| !c.kind().contains("comment")).collect()
+}
+
+fn bind(bindings: &mut Bindings, name: &str, text: &str) -> bool {
+    if name == "_" {
+        return true;
+    }
+    match bindings.get(name) {
+        Some(existing) => existing == text,
+        None => {
+            bindings.insert(name.to_string(), text.to_string());
+            true
+        }
+    }
+}
+
+fn match_node(p: Node, psrc: &str, t: Node, tsrc: &str, bindings: &mut Bindings) -> bool {
+    if let Some((name, _)) = metavar(p, psrc) {
+        return bind(bindings, &name, &tsrc[t.byte_range()]);
+    }
+    if p.kind() != t.kind() {
+        return false;
+    }
+    let pc = significant_children(p);
+    let tc = significant_children(t);
+    if pc.is_empty() {
+        return tc.is_empty() && psrc[p.byte_range()] == tsrc[t.byte_range()];
+    }
+    match_seq(&pc, psrc, &tc, tsrc, bindings)
+}
+
+fn match_seq(pats: &[Node], psrc: &str, nodes: &[Node], tsrc: &str, bindings: &mut Bindings) -> bool {
+    let Some((&first, rest)) = pats.split_first() else { return nodes.is_empty() };
+    if let Some((name, true)) = metavar(first, psrc) {
+        for take in 0..=nodes.len() {
+            let text = match (nodes.first(), take) {
+                (Some(n), k) if k > 0 => &tsrc[n.start_byte()..nodes[k - 1].end_byte()],
+                _ => "",
+            };
+            let mut attempt = bindings.clone();
+            if bind(&mut attempt, &name, text) && match_seq(rest, psrc, &nodes[take..], tsrc, &mut attempt) {
+                *bindings = attempt;
+                return true;
+            }
+        }
+        return false;
+    }
+    let Some((&node, tail)) = nodes.split_first() else { return false };
+    let mut attempt = bindings.clone();
+    if match_node(first, psrc, node, tsrc, &mut attempt) && match_seq(rest, psrc, tail, tsrc, &mut attempt) {
+        *bindings = attempt;
+        return true;
+    }
+    false
+}
+
+struct FileMatch {
+    start: usize,
+    end: usize,
+    start_point: (usize, usize),
+    end_point: (usize, usize),
+    bindings: Bindings,
+}
+
+/// Non-overlapping matches in document order; children of a matched
+/// node are not searched.
+fn matches_in(pattern: &Pattern, source: &str, ext: &str) -> Vec<FileMatch> {
+    let Some(tree) = parse(source, ext) else { return Vec::new() };
+    let proot = pattern.root();
+    let mut out = Vec::new();
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        let mut bindings = Bindings::new();
+        if node.is_named() && match_node(proot, &pattern.source, node, source, &mut bindings) {
+            out.push(FileMatch {
+                start: node.start_byte(),
+                end: node.end_byte(),
+                start_point: (node.start_position().row, node.start_position().column),
+                end_point: (node.end_position().row, node.end_position().column),
+                bindings,
+            });
+            continue;
+        }
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.named_children(&mut cursor).collect();
+        stack.extend(children.into_iter().rev());
+    }
+    out
+}
+
+fn language_files(root: &Path, dir: &Path, spec: &LanguageSpec) -> impl Iterator<Item = PathBuf> {
+    let extensions = spec.extensions;
+    project_files(root, dir).filter(move |p| p.extension().and_then(|e| e.to_str()).is_some_and(|e| extensions.contains(&e)))
+}
+
+fn skip_generated(
+    path: &Path,
+    content: &str,
+    generated_dirs: &generated::GeneratedDirs,
+    include_generated: Option<bool>,
+) -> bool {
+    !include_generated.unwrap_or(false)
+        && (generated_dirs.contains(path) || generated::is_generated(path, content).is_some())
+}
+
+fn file_ext(path: &Path) -> &str {
+    path.extension().and_then(|e| e.to_str()).unwrap_or("")
+}
+
+// ── Commands ──
+
+/// Find code matching a structural `pattern` (see module docs). When
+/// `rewrite` is given each match also carries its rewritten text.
+#[tauri::command]
+pub fn structural_search(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    pattern: String,
+    language: String,
+    path: Option<String>,
+    rewrite: Option<String>,
+    include_generated: Option<bool>,
+) -> Result<StructuralResults, String> {
+    let spec = language_spec(&language)?;
+    let compiled = Pattern::compile(&pattern, &spec)?;
+    if let Some(rewrite) = &rewrite {
+        check_rewrite(&pattern, rewrite)?;
+    }
+    let (root, dir) = search_root(path.as_deref(), window.label(), &state)?;
+    let generated_dirs = generated::GeneratedDirs::for_project(&root);
+
+    let mut matches = Vec::new();
+    for file in language_files(&root, &dir, &spec) {
+        if matches.len() >= MAX_MATCHES {
+            break;
+        }
+        let Some(content) = read_text(&file) else { continue };
+        if skip_generated(&file, &content, &generated_dirs, include_generated) {
+            continue;
+        }
+        let rel = file.strip_prefix(&root).unwrap_or(&file).to_string_lossy().to_string();
+        let hash = content_hash(&content);
+        for m in matches_in(&compiled, &content, file_ext(&file)) {
+            matches.push(StructuralMatch {
+                id: match_id(&rel, m.start, &hash),
+                path: rel.clone(),
+                start_line: m.start_point.0 + 1,
+                start_column: m.start_point.1,
+                end_line: m.end_point.0 + 1,
+                end_column: m.end_point.1,
+                text: content[m.start..m.end].to_string(),
+                replacement: rewrite.as_deref().map(|r| expand(r, &m.bindings)),
+                captures: m.bindings,
+            });
+        }
+    }
+    matches.truncate(MAX_MATCHES);
+    Ok(StructuralResults { truncated: matches.len() >= MAX_MATCHES, matches })
+}
+
+/// Rewrite every match of `pattern` with `rewrite`, previewing by default
+/// and applying every file as one workspace edit with `apply: true`, so
+/// either all files change or none do. `match_ids` from
+/// `structural_search` restricts the change to those matches, with stale
+/// files skipped exactly as in `replace_in_project`.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub fn structural_replace(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    pattern: String,
+    language: String,
+    rewrite: String,
+    path: Option<String>,
+    match_ids: Option<Vec<String>>,
+    apply: Option<bool>,
+    include_generated: Option<bool>,
+) -> Result<ReplaceResult, String> {
+    let spec = language_spec(&language)?;
+    let compiled = Pattern::compile(&pattern, &spec)?;
+    check_rewrite(&pattern, &rewrite)?;
+    let (root, dir) = search_root(path.as_deref(), window.label(), &state)?;
+    let apply = apply.unwrap_or(false);
+
+    let selected = match_ids.map(group_ids_by_file);
+    let generated_dirs = generated::GeneratedDirs::for_project(&root);
+    let candidates: Vec<PathBuf> = match &selected {
+        Some(by_file) => by_file.keys().map(|rel| root.join(rel)).collect(),
+        None => language_files(&root, &dir, &spec).collect(),
+    };
+
+    let mut result = ReplaceResult { files: Vec::new(), applied: apply, stale: Vec::new(), failed: Vec::new() };
+    let mut edit = WorkspaceEdit::default();
+    for file in candidates {
+        let rel = file.strip_prefix(&root).unwrap_or(&file).to_string_lossy().to_string();
+        if validate_path(&file.to_string_lossy(), window.label(), &state).is_err() {
+            continue;
+        }
+        let Some(content) = read_text(&file) else { continue };
+        if selected.is_none() && skip_generated(&file, &content, &generated_dirs, include_generated) {
+            continue;
+        }
+        let hash = content_hash(&content);
+        let ids = match &selected {
+            Some(by_file) => {
+                let (expected, ids) = &by_file[&rel];
+                if *expected != hash {
+                    result.stale.push(rel);
+                    continue;
+                }
+                Some(ids)
+            }
+            None => None,
+        };
+
+        let mut new_content = String::with_capacity(content.len());
+        let mut last = 0;
+        let mut count = 0;
+        for m in matches_in(&compiled, &content, file_ext(&file)) {
+            if ids.is_some_and(|ids| !ids.contains(&match_id(&rel, m.start, &hash))) {
+                continue;
+            }
+            new_content.push_str(&content[last..m.start]);
+            new_content.push_str(&expand(&rewrite, &m.bindings));
+            last = m.end;
+            count += 1;
+        }
+        new_content.push_str(&content[last..]);
+        if count == 0 || new_content == content {
+            continue;
+        }
+        let diff = unified_diff(&rel, &content, &new_content);
+        let whole = Range { start: Position { line: 0, character: 0 }, end: Position::at(&content, content.len()) };
+        edit.operations.push(FileOperation::Edit {
+            path: file.to_string_lossy().into_owned(),
+            edits: vec![TextEdit { range: whole, new_text: new_content }],
+            expected_hash: None,
+        });
+        result.files.push(FileReplacement { diff, path: rel, replacements: count });
+    }
+    if apply && !edit.operations.is_empty() {
+        if let Err(e) = apply_edit(&root, window.label(), &edit, false) {
+            result.applied = false;
+            result.failed = result.files.drain(..).map(|f| (f.path, e.clone())).collect();
+        }
+    }
+    result.files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rewrite_all(pattern: &str, language: &str, rewrite: &str, source: &str, ext: &str) -> String {
+        let compiled = Pattern::compile(pattern, &language_spec(language).unwrap()).unwrap();
+        let mut out = String::new();
+        let mut last = 0;
+        for m in matches_in(&compiled, source, ext) {
+            out.push_str(&source[last..m.start]);
+            out.push_str(&expand(rewrite, &m.bindings));
+            last = m.end;
+        }
+        out.push_str(&source[last..]);
+        out
+    }
+
+    #[test]
+    fn swaps_call_arguments_regardless_of_formatting() {
+        let src = "const x = foo(a,   b + 1);\nfoo(c);\nbar(foo(d, e));\n";
+        let out = rewrite_all("foo($A, $B)", "javascript", "bar($B, $A)", src, "js");
+        assert_eq!(out, "const x = bar(b + 1, a);\nfoo(c);\nbar(bar(e, d));\n");
+    }
+
+    #[test]
+    fn repeated_metavariables_must_agree() {
+        let compiled = Pattern::compile("$A == $A", &language_spec("typescript").unwrap()).unwrap();
+        let found = matches_in(&compiled, "if (x == x) {}\nif (x == y) {}\n", "ts");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].bindings["A"], "x");
+    }
+
+    #[test]
+    fn multi_metavariables_capture_argument_runs() {
+        let out = rewrite_all("log($$$ARGS)", "rust", "tracing::info!($$$ARGS)", "fn f() { log(a, b, c); log(); }\n", "rs");
+        assert_eq!(out, "fn f() { tracing::info!(a, b, c); tracing::info!(); }\n");
+    }
+
+    #[test]
+    fn python_patterns_and_comments_are_ignored() {
+        let src = "x = open(path,  # the file\n  'r')\n";
+        let out = rewrite_all("open($P, 'r')", "python", "Path($P).open()", src, "py");
+        assert_eq!(out, "x = Path(path).open()\n");
+    }
+
+    #[test]
+    fn rewrite_must_use_bound_names() {
+        assert!(check_rewrite("foo($A)", "bar($A)").is_ok());
+        assert!(check_rewrite("foo($A)", "bar($B)").is_err());
+        assert!(Pattern::compile("foo(", &language_spec("js").unwrap()).is_err());
+    }
+}
@@ -0,0 +1,285 @@
+//! Session handoff between machines.
+//!
+//! A handoff bundle records where work on a project stopped — open tabs
+//! with their cursors, expanded folders, terminal directories and
+//! scratchpad notes — with every path relative to the project root, so
+//! it can be opened against a checkout of the same project anywhere else.
+//! File contents are never included; the other machine is expected to
+//! have the code already (pushed, synced or on a shared drive).
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use super::{SessionData, SessionFile};
+use crate::modules::fs::{write_atomic, ProjectRootState};
+use crate::modules::shell::{self, TerminalState};
+
+/// Bumped when the bundle changes in ways older builds can't read.
+const BUNDLE_VERSION: u32 = 1;
+const MAX_BUNDLE_BYTES: u64 = 8 * 1024 * 1024;
+
+/// A cursor, 1-based line and column.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct CursorPosition {
+    pub line: u32,
+    pub column: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Scratchpad {
+    pub name: String,
+    pub content: String,
+}
+
+/// The frontend's side of the session, with absolute paths.
+#[derive(Debug, Clone, Deserialize)]
+pub struct HandoffSnapshot {
+    pub session: SessionData,
+    /// Cursor of each open file, by path.
+    #[serde(default)]
+    pub cursors: HashMap<String, CursorPosition>,
+    #[serde(default)]
+    pub scratchpads: Vec<Scratchpad>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct BundleFile {
+    path: String,
+    pinned: bool,
+    #[serde(default)]
+    cursor: Option<CursorPosition>,
+}
+
+/// What goes in the file. Paths are relative to the project root with `/`
+/// separators; an empty path is the root itself.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct SessionBundle {
+    version: u32,
+    project_name: String,
+    exported_at: u64,
+    open_files: Vec<BundleFile>,
+    active_file: Option<String>,
+    expanded_dirs: Vec<String>,
+    terminal_visible: bool,
+    /// One entry per terminal, oldest first.
+    terminal_cwds: Vec<String>,
+    scratchpads: Vec<Scratchpad>,
+}
+
+/// A bundle mapped onto this machine's checkout, for the frontend to
+/// restore.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SessionHandoff {
+    /// Name of the project on the machine that exported it.
+    pub project_name: String,
+    pub exported_at: u64,
+    /// Tabs, folders and terminals, with absolute paths. Files missing
+    /// here are left out.
+    pub session: SessionData,
+    pub cursors: HashMap<String, CursorPosition>,
+    /// Directory of each terminal to open, absolute.
+    pub terminal_cwds: Vec<String>,
+    pub scratchpads: Vec<Scratchpad>,
+    /// Open files from the bundle that don't exist in this checkout.
+    pub missing_files: Vec<String>,
+}
+
+/// `path` relative to `root` in bundle form, or `None` if it is outside.
+fn relative(root: &Path, path: &Path) -> Option<String> {
+    let rel = path.strip_prefix(root).ok()?;
+    let parts: Option<Vec<&str>> = rel
+        .components()
+        .map(|c| match c {
+            Component::Normal(part) => part.to_str(),
+            _ => None,
+        })
+        .collect();
+    Some(parts?.join("/"))
+}
+
+/// A bundle path under `root`. Rejects anything that could climb out.
+fn absolute(root: &Path, rel: &str) -> Option<PathBuf> {
+    let mut path = root.to_path_buf();
+    for part in rel.split('/').filter(|p| !p.is_empty()) {
+        if part == "." || part == ".." || part.contains('\\') || Path::new(part).has_root() || part.contains(':') {
+            return None;
+        }
+        path.push(part);
+    }
+    Some(path)
+}
+
+fn to_bundle(root: &Path, snapshot: &HandoffSnapshot, terminal_cwds: &[Option<PathBuf>], now: u64) -> SessionBundle {
+    let session = &snapshot.session;
+    let rel = |p: &str| relative(root, Path::new(p));
+    let open_files = session
+        .open_files
+        .iter()
+        .filter_map(|f| {
+            Some(BundleFile { path: rel(&f.path)?, pinned: f.pinned, cursor: snapshot.cursors.get(&f.path).copied() })
+        })
+        .collect();
+    // Terminals the frontend shows but the backend can't place start in the root.
+    let count = (session.terminal_count as usize).max(terminal_cwds.len());
+    let terminal_cwds = (0..count)
+        .map(|i| terminal_cwds.get(i).cloned().flatten().and_then(|p| relative(root, &p)).unwrap_or_default())
+        .collect();
+    SessionBundle {
+        version: BUNDLE_VERSION,
+        project_name: root.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default(),
+        exported_at: now,
+        open_files,
+        active_file: session.active_file.as_deref().and_then(rel),
+        expanded_dirs: session.expanded_dirs.iter().filter_map(|d| rel(d)).filter(|d| !d.is_empty()).collect(),
+        terminal_visible: session.terminal_visible,
+        terminal_cwds,
+        scratchpads: snapshot.scratchpads.clone(),
+    }
+}
+
+fn from_bundle(root: &Path, bundle: SessionBundle) -> SessionHandoff {
+    let existing = |rel: &str| absolute(root, rel).filter(|p| p.exists()).map(|p| p.to_string_lossy().into_owned());
+    let (mut open_files, mut cursors, mut missing_files) = (Vec::new(), HashMap::new(), Vec::new());
+    for file in bundle.open_files {
+        let Some(path) = existing(&file.path).filter(|p| Path::new(p).is_file()) else {
+            missing_files.push(file.path);
+            continue;
+        };
+        if let Some(cursor) = file.cursor {
+            cursors.insert(path.clone(), cursor);
+        }
+        open_files.push(SessionFile { path, pinned: file.pinned });
+    }
+    let root_str = root.to_string_lossy().into_owned();
+    let terminal_cwds: Vec<String> = bundle
+        .terminal_cwds
+        .iter()
+        .map(|d| existing(d).filter(|p| Path::new(p).is_dir()).unwrap_or_else(|| root_str.clone()))
+        .collect();
+    SessionHandoff {
+        project_name: bundle.project_name,
+        exported_at: bundle.exported_at,
+        session: SessionData {
+            active_file: bundle
+                .active_file
+                .as_deref()
+                .and_then(existing)
+                .filter(|p| open_files.iter().any(|f| &f.path == p)),
+            open_files,
+            terminal_count: terminal_cwds.len() as u32,
+            terminal_visible: bundle.terminal_visible,
+            expanded_dirs: bundle.expanded_dirs.iter().filter_map(|d| existing(d)).collect(),
+        },
+        cursors,
+        terminal_cwds,
+        scratchpads: bundle.scratchpads,
+        missing_files,
+    }
+}
+
+fn window_root(window: &tauri::WebviewWindow, state: &ProjectRootState) -> Result<PathBuf, String> {
+    state
+        .blocking_read()
+        .get(window.label())
+        .and_then(|o| o.clone())
+        .ok_or_else(|| "No project is open".to_string())
+}
+
+/// Write a handoff bundle for `project`, the window's open project, to
+/// `path`.
+#[tauri::command]
+pub fn export_session_bundle(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    terminals: tauri::State<'_, TerminalState>,
+    project: String,
+    snapshot: HandoffSnapshot,
+    path: String,
+) -> Result<(), String> {
+    let root = window_root(&window, &state)?;
+    if std::fs::canonicalize(&project).ok().as_ref() != Some(&root) {
+        return Err("Only the project open in this window can be handed off".to_string());
+    }
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let bundle = to_bundle(&root, &snapshot, &shell::terminal_cwds(&terminals, window.label()), now);
+    let json = serde_json::to_string_pretty(&bundle).map_err(|e| e.to_string())?;
+    write_atomic(Path::new(&path), json.as_bytes()).map_err(|e| format!("Failed to write session bundle: {}", e))
+}
+
+/// Read a handoff bundle and map it onto the project open in this window.
+#[tauri::command]
+pub fn import_session_bundle(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: String,
+) -> Result<SessionHandoff, String> {
+    let root = window_root(&window, &state)?;
+    let size = std::fs::metadata(&path).map_err(|e| format!("Failed to read session bundle: {}", e))?.len();
+    if size > MAX_BUNDLE_BYTES {
+        return Err("Session bundle is too large".to_string());
+    }
+    let json = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read session bundle: {}", e))?;
+    let bundle: SessionBundle = serde_json::from_str(&json).map_err(|e| format!("Not a session bundle: {}", e))?;
+    if bundle.version > BUNDLE_VERSION {
+        return Err("This session bundle was made by a newer version of the app".to_string());
+    }
+    Ok(from_bundle(&root, bundle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundles_relative_paths_and_maps_them_onto_another_checkout() {
+        let desktop = tempfile::tempdir().unwrap();
+        let laptop = tempfile::tempdir().unwrap();
+        for root in [desktop.path(), laptop.path()] {
+            std::fs::create_dir_all(root.join("src/ui")).unwrap();
+            std::fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+        }
+        std::fs::write(desktop.path().join("notes.md"), "").unwrap();
+        let at = |p: &str| desktop.path().join(p).to_string_lossy().into_owned();
+        let snapshot = HandoffSnapshot {
+            session: SessionData {
+                open_files: vec![
+                    SessionFile { path: at("src/main.rs"), pinned: true },
+                    SessionFile { path: at("notes.md"), pinned: false },
+                    SessionFile { path: "/etc/hosts".into(), pinned: false },
+                ],
+                active_file: Some(at("notes.md")),
+                terminal_count: 3,
+                terminal_visible: true,
+                expanded_dirs: vec![at(""), at("src"), at("src/ui")],
+            },
+            cursors: HashMap::from([(at("src/main.rs"), CursorPosition { line: 1, column: 4 })]),
+            scratchpads: vec![Scratchpad { name: "todo".into(), content: "fix login".into() }],
+        };
+        let cwds = [Some(desktop.path().join("src/ui")), Some(PathBuf::from("/tmp")), None];
+
+        let bundle = to_bundle(desktop.path(), &snapshot, &cwds, 7);
+        assert_eq!(bundle.open_files.iter().map(|f| f.path.as_str()).collect::<Vec<_>>(), ["src/main.rs", "notes.md"]);
+        assert_eq!(bundle.expanded_dirs, ["src", "src/ui"]);
+        assert_eq!(bundle.terminal_cwds, ["src/ui", "", ""]);
+        let json = serde_json::to_string(&bundle).unwrap();
+        assert!(!json.contains(&desktop.path().to_string_lossy().into_owned()));
+
+        let handoff = from_bundle(laptop.path(), serde_json::from_str(&json).unwrap());
+        let here = |p: &str| laptop.path().join(p).to_string_lossy().into_owned();
+        assert_eq!(handoff.session.open_files, vec![SessionFile { path: here("src/main.rs"), pinned: true }]);
+        assert_eq!(handoff.session.active_file, None);
+        assert_eq!(handoff.missing_files, ["notes.md"]);
+        assert_eq!(handoff.cursors[&here("src/main.rs")], CursorPosition { line: 1, column: 4 });
+        let root = laptop.path().to_string_lossy().into_owned();
+        assert_eq!(handoff.terminal_cwds, [here("src/ui"), root.clone(), root]);
+        assert_eq!(handoff.session.terminal_count, 3);
+        assert_eq!(handoff.scratchpads, snapshot.scratchpads);
+
+        assert_eq!(absolute(laptop.path(), "../outside"), None);
+        assert_eq!(absolute(laptop.path(), "src/../../x"), None);
+    }
+}
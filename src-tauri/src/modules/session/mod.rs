@@ -1,14 +1,20 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Mutex;
 use tauri::{AppHandle, Manager};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+pub mod handoff;
+pub mod storage;
+
+use storage::SessionPolicy;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SessionFile {
     pub path: String,
     pub pinned: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct SessionData {
     pub open_files: Vec<SessionFile>,
     pub active_file: Option<String>,
@@ -29,11 +35,116 @@ pub struct RecentProject {
     pub name: String,
     pub last_opened: u64,
     pub session: SessionData,
+    /// ID of the `ProjectGroup` this project is filed under.
+    #[serde(default)]
+    pub group: Option<String>,
+}
+
+/// A user-defined heading on the welcome screen, e.g. one per client.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProjectGroup {
+    pub id: String,
+    pub name: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AppState {
     pub recent_projects: Vec<RecentProject>,
+    #[serde(default)]
+    pub groups: Vec<ProjectGroup>,
+    /// Paths of pinned projects, in the order they are shown.
+    #[serde(default)]
+    pub pinned: Vec<String>,
+    /// How sessions are pruned, unless a project has its own policy.
+    #[serde(default)]
+    pub session_policy: SessionPolicy,
+    /// Policies of individual projects, by path.
+    #[serde(default)]
+    pub project_policies: HashMap<String, SessionPolicy>,
+}
+
+/// Groups and pins for laying out the welcome screen.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectOrganization {
+    pub groups: Vec<ProjectGroup>,
+    pub pinned: Vec<String>,
+}
+
+impl AppState {
+    /// Cap the recent list at `max` entries. Pinned and grouped projects
+    /// don't count towards the cap and are never dropped.
+    fn trim_recent(&mut self, max: usize) {
+        let pinned = &self.pinned;
+        let mut kept = 0;
+        self.recent_projects.retain(|p| {
+            if p.group.is_some() || pinned.contains(&p.path) {
+                return true;
+            }
+            kept += 1;
+            kept <= max
+        });
+    }
+
+    fn project_mut(&mut self, path: &str) -> Result<&mut RecentProject, String> {
+        self.recent_projects
+            .iter_mut()
+            .find(|p| p.path == path)
+            .ok_or_else(|| format!("not a recent project: {path}"))
+    }
+
+    fn create_group(&mut self, name: &str) -> Result<ProjectGroup, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("group name must not be empty".into());
+        }
+        if self.groups.iter().any(|g| g.name.eq_ignore_ascii_case(name)) {
+            return Err(format!("a group named '{name}' already exists"));
+        }
+        let slug: String = name
+            .to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .collect::<Vec<_>>()
+            .join("-");
+        let slug = if slug.is_empty() { "group".to_string() } else { slug };
+        let id = std::iter::once(slug.clone())
+            .chain((2..).map(|n| format!("{slug}-{n}")))
+            .find(|id| !self.groups.iter().any(|g| &g.id == id))
+            .unwrap_or(slug);
+        let group = ProjectGroup { id, name: name.to_string() };
+        self.groups.push(group.clone());
+        Ok(group)
+    }
+
+    fn delete_group(&mut self, group_id: &str) {
+        self.groups.retain(|g| g.id != group_id);
+        for project in &mut self.recent_projects {
+            if project.group.as_deref() == Some(group_id) {
+                project.group = None;
+            }
+        }
+    }
+
+    fn assign_group(&mut self, path: &str, group_id: Option<String>) -> Result<(), String> {
+        if let Some(id) = &group_id {
+            if !self.groups.iter().any(|g| &g.id == id) {
+                return Err(format!("no such project group: {id}"));
+            }
+        }
+        self.project_mut(path)?.group = group_id;
+        Ok(())
+    }
+
+    /// Pin `path` at `index` (the end if `None`), or unpin it.
+    fn set_pinned(&mut self, path: &str, pinned: bool, index: Option<usize>) -> Result<(), String> {
+        self.pinned.retain(|p| p != path);
+        if pinned {
+            self.project_mut(path)?;
+            let index = index.unwrap_or(self.pinned.len()).min(self.pinned.len());
+            self.pinned.insert(index, path.to_string());
+        }
+        Ok(())
+    }
 }
 
 pub struct AppStateHandle(pub Mutex<AppState>);
@@ -63,22 +174,29 @@ fn validate_path(path: &str) -> Result<(), String> {
     Ok(())
 }
 
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Load the saved state, pruned. A corrupt file is moved to a backup and
+/// replaced by a fresh state.
 pub fn load_state_from_disk(app: &AppHandle) -> Result<AppState, String> {
-    let path = state_path(app)?;
-    match std::fs::read_to_string(&path) {
-        Ok(contents) => Ok(serde_json::from_str(&contents).unwrap_or_default()),
-        Err(_) => Ok(AppState::default()),
-    }
+    let mut state = storage::load(&state_path(app)?);
+    state.prune(now_secs());
+    Ok(state)
 }
 
 /// Atomic write: write to a temp file in the same directory, then rename.
+/// Written compactly; the file is only read back by us.
 fn save_state_to_disk(app: &AppHandle, state: &AppState) -> Result<(), String> {
     let path = state_path(app)?;
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent).map_err(|e| format!("failed to create state dir: {e}"))?;
     }
-    let json =
-        serde_json::to_string_pretty(state).map_err(|e| format!("failed to serialize: {e}"))?;
+    let json = serde_json::to_string(state).map_err(|e| format!("failed to serialize: {e}"))?;
 
     let tmp_path = path.with_extension("json.tmp");
     std::fs::write(&tmp_path, &json).map_err(|e| format!("failed to write temp state: {e}"))?;
@@ -87,6 +205,22 @@ fn save_state_to_disk(app: &AppHandle, state: &AppState) -> Result<(), String> {
     Ok(())
 }
 
+/// Apply `f` to the app state and persist the result.
+fn update_state<T>(app: &AppHandle, f: impl FnOnce(&mut AppState) -> Result<T, String>) -> Result<T, String> {
+    let (value, state_snapshot) = {
+        let handle = app.state::<AppStateHandle>();
+        let mut guard = handle
+            .0
+            .lock()
+            .map_err(|e| format!("state lock failed: {e}"))?;
+        let value = f(&mut guard)?;
+        guard.prune(now_secs());
+        (value, guard.clone())
+    }; // guard dropped here, mutex unlocked
+    save_state_to_disk(app, &state_snapshot)?;
+    Ok(value)
+}
+
 #[tauri::command]
 pub fn get_recent_projects(app: AppHandle) -> Result<Vec<RecentProject>, String> {
     let handle = app.state::<AppStateHandle>();
@@ -121,12 +255,10 @@ pub fn save_session(
             .map(|n| n.to_string_lossy().to_string())
             .unwrap_or_else(|| project_path.clone());
 
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs();
+        let now = now_secs();
 
-        // Upsert: remove existing entry for this path
+        // Upsert: remove existing entry for this path, keeping its group
+        let group = guard.recent_projects.iter().find(|p| p.path == project_path).and_then(|p| p.group.clone());
         guard.recent_projects.retain(|p| p.path != project_path);
 
         // Insert at front (most recent first)
@@ -137,11 +269,13 @@ pub fn save_session(
                 name,
                 last_opened: now,
                 session,
+                group,
             },
         );
 
         // Truncate to max (clamped to 0..=30)
-        guard.recent_projects.truncate(max_recent.min(30));
+        guard.trim_recent(max_recent.min(30));
+        guard.prune(now);
 
         guard.clone()
     }; // guard dropped here, mutex unlocked
@@ -160,8 +294,101 @@ pub fn remove_recent_project(app: AppHandle, project_path: String) -> Result<(),
             .lock()
             .map_err(|e| format!("state lock failed: {e}"))?;
         guard.recent_projects.retain(|p| p.path != project_path);
+        guard.pinned.retain(|p| p != &project_path);
         guard.clone()
     }; // guard dropped here, mutex unlocked
 
     save_state_to_disk(&app, &state_snapshot)
 }
+
+// ── Groups and pins ──
+
+#[tauri::command]
+pub fn get_project_groups(app: AppHandle) -> Result<ProjectOrganization, String> {
+    let handle = app.state::<AppStateHandle>();
+    let guard = handle
+        .0
+        .lock()
+        .map_err(|e| format!("state lock failed: {e}"))?;
+    Ok(ProjectOrganization { groups: guard.groups.clone(), pinned: guard.pinned.clone() })
+}
+
+#[tauri::command]
+pub fn create_project_group(app: AppHandle, name: String) -> Result<ProjectGroup, String> {
+    update_state(&app, |state| state.create_group(&name))
+}
+
+/// Delete a group. Its projects stay in the recent list, ungrouped.
+#[tauri::command]
+pub fn delete_project_group(app: AppHandle, group_id: String) -> Result<(), String> {
+    update_state(&app, |state| {
+        state.delete_group(&group_id);
+        Ok(())
+    })
+}
+
+/// File a recent project under `group_id`, or take it out of its group
+/// when `group_id` is `None`.
+#[tauri::command]
+pub fn assign_project_to_group(app: AppHandle, project_path: String, group_id: Option<String>) -> Result<(), String> {
+    validate_path(&project_path)?;
+    update_state(&app, |state| state.assign_group(&project_path, group_id))
+}
+
+/// Pin a recent project at `index` among the pinned ones (the end by
+/// default), or unpin it. Pinning an already pinned project moves it.
+#[tauri::command]
+pub fn set_pinned(app: AppHandle, project_path: String, pinned: bool, index: Option<usize>) -> Result<(), String> {
+    validate_path(&project_path)?;
+    update_state(&app, |state| state.set_pinned(&project_path, pinned, index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(path: &str) -> RecentProject {
+        RecentProject {
+            path: path.to_string(),
+            name: path.trim_start_matches('/').to_string(),
+            last_opened: 0,
+            session: SessionData {
+                open_files: Vec::new(),
+                active_file: None,
+                terminal_count: 0,
+                terminal_visible: false,
+                expanded_dirs: Vec::new(),
+            },
+            group: None,
+        }
+    }
+
+    #[test]
+    fn groups_and_pins_survive_trimming() {
+        let mut state = AppState {
+            recent_projects: ["/a", "/b", "/c", "/d"].into_iter().map(project).collect(),
+            ..Default::default()
+        };
+        let acme = state.create_group("Acme Corp").unwrap();
+        assert_eq!(acme.id, "acme-corp");
+        assert!(state.create_group("acme corp").is_err());
+        assert_eq!(state.create_group("Acme: Corp").unwrap().id, "acme-corp-2");
+
+        state.assign_group("/d", Some(acme.id.clone())).unwrap();
+        assert!(state.assign_group("/d", Some("nope".into())).is_err());
+        assert!(state.assign_group("/zzz", None).is_err());
+        state.set_pinned("/c", true, None).unwrap();
+        state.set_pinned("/b", true, Some(0)).unwrap();
+        assert_eq!(state.pinned, ["/b", "/c"]);
+        state.set_pinned("/b", true, Some(9)).unwrap();
+        assert_eq!(state.pinned, ["/c", "/b"]);
+
+        state.set_pinned("/b", false, None).unwrap();
+        state.trim_recent(1);
+        let kept: Vec<_> = state.recent_projects.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(kept, ["/a", "/c", "/d"]);
+
+        state.delete_group(&acme.id);
+        assert!(state.recent_projects.iter().all(|p| p.group.is_none()));
+    }
+}
@@ -0,0 +1,292 @@
+//! Keeping `state.json` small.
+//!
+//! Each recent project's saved session is pruned by a policy, the user's
+//! default or the project's own: a session not opened for `max_age_days`
+//! is emptied, and expanded folders are capped, deduplicated and limited
+//! to ones inside the project. Only the `max_sessions` most recently opened
+//! projects keep a session at all. Projects stay on the recent list either
+//! way; only their session payloads are dropped. Pruning runs on every
+//! change before the state is written, compactly.
+//!
+//! A `state.json` that can't be read back is moved aside to
+//! `state-<unix time>.json.bak` rather than being overwritten by the next
+//! save, so it can still be recovered by hand.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use super::{now_secs, state_path, update_state, validate_path, AppState, AppStateHandle, RecentProject, SessionData};
+
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SessionPolicy {
+    /// Recycle the session after this many days without opening the
+    /// project; 0 keeps it indefinitely.
+    pub max_age_days: u32,
+    /// Expanded folders remembered per session.
+    pub max_expanded_dirs: usize,
+    /// Sessions kept across all projects, most recently opened first; 0
+    /// keeps them all. Only the default policy's value applies.
+    pub max_sessions: usize,
+}
+
+impl Default for SessionPolicy {
+    fn default() -> Self {
+        Self { max_age_days: 90, max_expanded_dirs: 200, max_sessions: 20 }
+    }
+}
+
+impl AppState {
+    fn policy_for(&self, project_path: &str) -> SessionPolicy {
+        self.project_policies.get(project_path).copied().unwrap_or(self.session_policy)
+    }
+
+    /// Apply each project's policy as of `now` (seconds since the epoch),
+    /// and drop pins and policies of projects no longer listed. Projects
+    /// themselves are never removed here.
+    pub(super) fn prune(&mut self, now: u64) {
+        let policies: Vec<SessionPolicy> = self.recent_projects.iter().map(|p| self.policy_for(&p.path)).collect();
+        for (project, policy) in self.recent_projects.iter_mut().zip(policies) {
+            let max_age = u64::from(policy.max_age_days) * DAY_SECS;
+            if max_age > 0 && now.saturating_sub(project.last_opened) > max_age {
+                project.session = SessionData::default();
+                continue;
+            }
+            let root = Path::new(&project.path);
+            let mut seen = HashSet::new();
+            project.session.expanded_dirs.retain(|d| Path::new(d).starts_with(root) && seen.insert(d.clone()));
+            project.session.expanded_dirs.truncate(policy.max_expanded_dirs);
+        }
+        let max_sessions = self.session_policy.max_sessions;
+        if max_sessions > 0 {
+            let mut by_recency: Vec<&mut RecentProject> =
+                self.recent_projects.iter_mut().filter(|p| p.session != SessionData::default()).collect();
+            by_recency.sort_by_key(|p| std::cmp::Reverse(p.last_opened));
+            for project in by_recency.into_iter().skip(max_sessions) {
+                project.session = SessionData::default();
+            }
+        }
+        let listed: HashSet<&str> = self.recent_projects.iter().map(|p| p.path.as_str()).collect();
+        self.pinned.retain(|p| listed.contains(p.as_str()));
+        self.project_policies.retain(|p, _| listed.contains(p.as_str()));
+    }
+}
+
+/// Read the state at `path`. A missing file is a fresh start; one that
+/// doesn't parse is moved to a backup first.
+pub(super) fn load(path: &Path) -> AppState {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return AppState::default(),
+        Err(e) => {
+            log::warn!("could not read {}: {e}", path.display());
+            return AppState::default();
+        }
+    };
+    match serde_json::from_slice(&bytes) {
+        Ok(state) => state,
+        Err(e) => {
+            let backup = path.with_file_name(format!("state-{}.json.bak", now_secs()));
+            log::warn!("{} is corrupt ({e}); moving it to {}", path.display(), backup.display());
+            if let Err(e) = std::fs::rename(path, &backup) {
+                log::warn!("could not back up corrupt state file: {e}");
+            }
+            AppState::default()
+        }
+    }
+}
+
+/// Backups of corrupt state files next to `path`, oldest first.
+fn backups(path: &Path) -> Vec<PathBuf> {
+    let Some(dir) = path.parent() else { return Vec::new() };
+    let Ok(entries) = std::fs::read_dir(dir) else { return Vec::new() };
+    let mut found: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            let name = p.file_name().unwrap_or_default().to_string_lossy();
+            name.starts_with("state-") && name.ends_with(".json.bak")
+        })
+        .collect();
+    found.sort();
+    found
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ProjectStorage {
+    pub path: String,
+    /// Size of the project's entry in the saved JSON.
+    pub bytes: usize,
+    pub open_files: usize,
+    pub expanded_dirs: usize,
+    pub last_opened: u64,
+    pub policy: SessionPolicy,
+    /// The policy is the project's own rather than the default.
+    pub custom_policy: bool,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct StateStorageReport {
+    pub path: String,
+    /// Size of the file on disk.
+    pub file_bytes: u64,
+    /// Size of the state as it would be saved now.
+    pub state_bytes: usize,
+    /// Largest first.
+    pub projects: Vec<ProjectStorage>,
+    pub default_policy: SessionPolicy,
+    /// Corrupt state files that were moved aside.
+    pub backups: Vec<String>,
+}
+
+fn json_len<T: Serialize>(value: &T) -> usize {
+    serde_json::to_vec(value).map(|v| v.len()).unwrap_or(0)
+}
+
+fn report(state: &AppState, path: &Path) -> StateStorageReport {
+    let mut projects: Vec<ProjectStorage> = state
+        .recent_projects
+        .iter()
+        .map(|p| ProjectStorage {
+            path: p.path.clone(),
+            bytes: json_len(p),
+            open_files: p.session.open_files.len(),
+            expanded_dirs: p.session.expanded_dirs.len(),
+            last_opened: p.last_opened,
+            policy: state.policy_for(&p.path),
+            custom_policy: state.project_policies.contains_key(&p.path),
+        })
+        .collect();
+    projects.sort_by_key(|p| std::cmp::Reverse(p.bytes));
+    StateStorageReport {
+        path: path.to_string_lossy().into_owned(),
+        file_bytes: std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        state_bytes: json_len(state),
+        projects,
+        default_policy: state.session_policy,
+        backups: backups(path).iter().map(|p| p.to_string_lossy().into_owned()).collect(),
+    }
+}
+
+// ── Commands ──
+
+/// How big the saved state is, which projects take the space, and the
+/// policies that prune them.
+#[tauri::command]
+pub fn get_state_storage_report(app: AppHandle) -> Result<StateStorageReport, String> {
+    let path = state_path(&app)?;
+    let handle = app.state::<AppStateHandle>();
+    let guard = handle.0.lock().map_err(|e| format!("state lock failed: {e}"))?;
+    Ok(report(&guard, &path))
+}
+
+/// Set the default session policy, or `project_path`'s own. `None` for
+/// `policy` restores the built-in default, or makes the project follow the
+/// user's default again. The new policy is applied at once.
+#[tauri::command]
+pub fn set_session_policy(
+    app: AppHandle,
+    project_path: Option<String>,
+    policy: Option<SessionPolicy>,
+) -> Result<StateStorageReport, String> {
+    if let Some(path) = &project_path {
+        validate_path(path)?;
+    }
+    update_state(&app, |state| {
+        match (project_path, policy) {
+            (None, policy) => state.session_policy = policy.unwrap_or_default(),
+            (Some(path), Some(policy)) => {
+                state.project_mut(&path)?;
+                state.project_policies.insert(path, policy);
+            }
+            (Some(path), None) => {
+                state.project_policies.remove(&path);
+            }
+        }
+        Ok(())
+    })?;
+    get_state_storage_report(app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::SessionFile;
+    use super::*;
+
+    fn project(path: &str, last_opened: u64, dirs: &[&str]) -> RecentProject {
+        RecentProject {
+            path: path.to_string(),
+            name: path.trim_start_matches('/').to_string(),
+            last_opened,
+            session: SessionData {
+                open_files: vec![SessionFile { path: format!("{path}/main.rs"), pinned: false }],
+                expanded_dirs: dirs.iter().map(|d| d.to_string()).collect(),
+                ..Default::default()
+            },
+            group: None,
+        }
+    }
+
+    #[test]
+    fn prunes_by_policy_and_backs_up_corrupt_files() {
+        let now = 1000 * DAY_SECS;
+        let mut state = AppState {
+            recent_projects: vec![
+                project("/fresh", now - DAY_SECS, &["/fresh/src", "/fresh/src", "/elsewhere", "/fresh/a", "/fresh/b"]),
+                project("/stale", now - 100 * DAY_SECS, &[]),
+                project("/pinned", now - 100 * DAY_SECS, &["/pinned/src"]),
+                project("/kept", now - 100 * DAY_SECS, &[]),
+            ],
+            pinned: vec!["/pinned".into(), "/gone".into()],
+            ..Default::default()
+        };
+        state.project_policies.insert("/fresh".into(), SessionPolicy { max_expanded_dirs: 2, ..Default::default() });
+        state.project_policies.insert("/kept".into(), SessionPolicy { max_age_days: 0, ..Default::default() });
+        state.project_policies.insert("/gone".into(), SessionPolicy::default());
+        state.prune(now);
+
+        let paths: Vec<&str> = state.recent_projects.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(paths, ["/fresh", "/stale", "/pinned", "/kept"]);
+        assert_eq!(state.recent_projects[0].session.expanded_dirs, ["/fresh/src", "/fresh/a"]);
+        assert_eq!(state.recent_projects[1].session, SessionData::default());
+        assert_eq!(state.recent_projects[2].session, SessionData::default());
+        assert_eq!(state.recent_projects[3].session.open_files.len(), 1);
+        assert_eq!(state.pinned, ["/pinned"]);
+        assert!(!state.project_policies.contains_key("/gone"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        std::fs::write(&path, serde_json::to_vec(&state).unwrap()).unwrap();
+        let report = report(&load(&path), &path);
+        assert_eq!(report.projects[0].path, "/fresh");
+        assert!(report.projects[0].custom_policy);
+        assert_eq!(report.file_bytes as usize, report.state_bytes);
+
+        std::fs::write(&path, "{\"recent_projects\": [").unwrap();
+        assert!(load(&path).recent_projects.is_empty());
+        assert!(!path.exists());
+        assert_eq!(backups(&path).len(), 1);
+        assert_eq!(std::fs::read_to_string(&backups(&path)[0]).unwrap(), "{\"recent_projects\": [");
+    }
+
+    #[test]
+    fn only_the_most_recent_sessions_are_kept() {
+        let mut state = AppState {
+            recent_projects: vec![
+                project("/a", 10, &[]),
+                project("/b", 30, &[]),
+                project("/c", 20, &[]),
+            ],
+            session_policy: SessionPolicy { max_age_days: 0, max_sessions: 2, ..Default::default() },
+            ..Default::default()
+        };
+        state.prune(40);
+        let kept: Vec<(&str, bool)> =
+            state.recent_projects.iter().map(|p| (p.path.as_str(), p.session != SessionData::default())).collect();
+        assert_eq!(kept, [("/a", false), ("/b", true), ("/c", true)]);
+    }
+}
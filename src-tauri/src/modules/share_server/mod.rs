@@ -0,0 +1,408 @@
+//! Read-only sharing of a project over the local network.
+//!
+//! `share_readonly` serves the window's project on every interface at a
+//! free port, so a colleague can browse and search it from a browser
+//! without cloning anything. Every request must carry the share's random
+//! token (`?token=`); when the token expires the server stops. Only GETs
+//! are served: a page at `/` that drives `/api/list`, `/api/file` and
+//! `/api/search`. Hidden files and folders (`.git`, `.env`, …), paths the
+//! project excludes, binaries and files over `MAX_FILE_BYTES` are never
+//! sent.
+
+use qrcode::render::svg;
+use qrcode::QrCode;
+use rand::Rng;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, UdpSocket};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+
+use crate::modules::fs::exclude::Excludes;
+use crate::modules::fs::{project_root_async, ProjectRootState};
+use crate::modules::policy::Category;
+use crate::modules::preview_server::{percent_decode, read_head};
+use crate::modules::search::{search_files, SearchQuery};
+
+/// Policy categories of this module's commands.
+pub(crate) const COMMAND_CATEGORIES: &[(&str, &[Category])] = &[("share_readonly", &[Category::Network])];
+
+const PAGE: &str = include_str!("page.html");
+const PAGE_POLICY: &str =
+    "default-src 'none'; script-src 'unsafe-inline'; style-src 'unsafe-inline'; connect-src 'self'; img-src data:";
+const DEFAULT_MINUTES: u64 = 30;
+const MAX_MINUTES: u64 = 8 * 60;
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+const BINARY_SNIFF_BYTES: usize = 8192;
+const MAX_SEARCH_MATCHES: usize = 200;
+
+// ── State ──
+
+struct Share {
+    info: ShareInfo,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl Drop for Share {
+    fn drop(&mut self) {
+        if let Some(tx) = self.shutdown.take() {
+            let _ = tx.send(());
+        }
+    }
+}
+
+/// Running shares, keyed by window label.
+#[derive(Default)]
+pub struct ShareServerState(Mutex<HashMap<String, Share>>);
+
+impl ShareServerState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn remove_window(&self, label: &str) {
+        if let Ok(mut shares) = self.0.lock() {
+            shares.remove(label);
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ShareInfo {
+    /// The link to hand out, token included.
+    pub url: String,
+    pub token: String,
+    /// Seconds since the epoch.
+    pub expires_at: u64,
+    /// `url` as a QR code, for a phone or laptop camera.
+    pub qr_svg: String,
+    pub root: String,
+}
+
+/// What the request handlers need: the shared project and its token.
+struct Shared {
+    /// Canonical.
+    root: PathBuf,
+    token: String,
+    expires_at: u64,
+    excludes: Excludes,
+}
+
+impl Shared {
+    /// `rel` (relative to the root, `/`-separated) as a path a guest may
+    /// see.
+    fn resolve(&self, rel: &str) -> Option<PathBuf> {
+        let rel = Path::new(rel.trim_matches('/'));
+        if rel.components().any(|c| !matches!(c, Component::Normal(_))) {
+            return None;
+        }
+        // Canonical, so symlinks out of the project or into hidden folders
+        // are judged by where they lead.
+        let path = self.root.join(rel).canonicalize().ok()?;
+        self.visible(&path).then_some(path)
+    }
+
+    /// Inside the project, not hidden and not excluded.
+    fn visible(&self, path: &Path) -> bool {
+        let Ok(rel) = path.strip_prefix(&self.root) else { return false };
+        !rel.components().any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+            && !self.excludes.is_excluded(path, path.is_dir())
+    }
+
+    fn rel(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root).unwrap_or(path).to_string_lossy().replace('\\', "/")
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn new_token() -> String {
+    rand::thread_rng().gen::<[u8; 16]>().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Compares every byte, so the time taken doesn't tell how much of a
+/// guess was right.
+fn tokens_match(given: &str, token: &str) -> bool {
+    given.len() == token.len() && given.bytes().zip(token.bytes()).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// The address other machines on the network reach this one at: the local
+/// end of a route towards a public address (connecting a UDP socket sends
+/// nothing). Loopback when there is no network.
+fn lan_address() -> IpAddr {
+    UdpSocket::bind("0.0.0.0:0")
+        .and_then(|socket| {
+            socket.connect("192.0.2.1:80")?;
+            socket.local_addr()
+        })
+        .map(|addr| addr.ip())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+}
+
+fn qr_svg(url: &str) -> Result<String, String> {
+    let code = QrCode::new(url.as_bytes()).map_err(|e| format!("Failed to make QR code: {}", e))?;
+    Ok(code.render::<svg::Color>().min_dimensions(200, 200).build())
+}
+
+// ── HTTP ──
+
+type Response = (&'static str, &'static str, Vec<u8>);
+
+#[derive(Serialize)]
+struct Entry {
+    name: String,
+    dir: bool,
+    size: u64,
+}
+
+#[derive(Serialize)]
+struct Listing {
+    path: String,
+    /// Folders first, then by name.
+    entries: Vec<Entry>,
+}
+
+#[derive(Serialize)]
+struct FileView {
+    path: String,
+    content: String,
+}
+
+fn query_param(url: &str, name: &str) -> Option<String> {
+    let query = url.split_once('?')?.1.split('#').next().unwrap_or_default();
+    query.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        (key == name).then(|| percent_decode(&value.replace('+', " ")))
+    })
+}
+
+fn json<T: Serialize>(value: &T) -> Response {
+    ("200 OK", "application/json", serde_json::to_vec(value).unwrap_or_default())
+}
+
+fn error(status: &'static str, message: &str) -> Response {
+    (status, "text/plain; charset=utf-8", message.as_bytes().to_vec())
+}
+
+fn list(shared: &Shared, rel: &str) -> Response {
+    let Some(dir) = shared.resolve(rel).filter(|p| p.is_dir()) else {
+        return error("404 Not Found", "No such folder");
+    };
+    let Ok(read) = std::fs::read_dir(&dir) else {
+        return error("500 Internal Server Error", "Failed to read folder");
+    };
+    let mut entries: Vec<Entry> = read
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| shared.visible(p))
+        .filter_map(|p| {
+            let meta = p.metadata().ok()?;
+            let size = if meta.is_dir() { 0 } else { meta.len() };
+            Some(Entry { name: p.file_name()?.to_string_lossy().into_owned(), dir: meta.is_dir(), size })
+        })
+        .collect();
+    entries.sort_by(|a, b| b.dir.cmp(&a.dir).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase())));
+    json(&Listing { path: shared.rel(&dir), entries })
+}
+
+fn file(shared: &Shared, rel: &str) -> Response {
+    let Some(path) = shared.resolve(rel).filter(|p| p.is_file()) else {
+        return error("404 Not Found", "No such file");
+    };
+    if path.metadata().map(|m| m.len() > MAX_FILE_BYTES).unwrap_or(true) {
+        return error("413 Payload Too Large", "This file is too large to view");
+    }
+    let Ok(bytes) = std::fs::read(&path) else {
+        return error("500 Internal Server Error", "Failed to read file");
+    };
+    if bytes[..bytes.len().min(BINARY_SNIFF_BYTES)].contains(&0) {
+        return error("415 Unsupported Media Type", "Binary files can't be viewed");
+    }
+    json(&FileView { path: shared.rel(&path), content: String::from_utf8_lossy(&bytes).into_owned() })
+}
+
+fn search(shared: &Shared, pattern: &str) -> Response {
+    if pattern.trim().chars().count() < 2 {
+        return error("400 Bad Request", "Search for at least two characters");
+    }
+    let query = SearchQuery { pattern: pattern.to_string(), ..Default::default() };
+    match search_files(&shared.root, &shared.root, &query, None) {
+        Ok(mut results) => {
+            results.matches.retain(|m| shared.visible(&shared.root.join(&m.path)));
+            results.truncated |= results.matches.len() > MAX_SEARCH_MATCHES;
+            results.matches.truncate(MAX_SEARCH_MATCHES);
+            json(&results)
+        }
+        Err(e) => error("400 Bad Request", &e),
+    }
+}
+
+/// Status line, content type and body for a GET of `url` at `now`.
+fn respond(shared: &Shared, url: &str, now: u64) -> Response {
+    if now >= shared.expires_at {
+        return error("410 Gone", "This share link has expired");
+    }
+    if !tokens_match(&query_param(url, "token").unwrap_or_default(), &shared.token) {
+        return error("403 Forbidden", "This share link is not valid");
+    }
+    let path = query_param(url, "path").unwrap_or_default();
+    match url.split(['?', '#']).next().unwrap_or("/") {
+        "/" => ("200 OK", "text/html; charset=utf-8", PAGE.as_bytes().to_vec()),
+        "/api/list" => list(shared, &path),
+        "/api/file" => file(shared, &path),
+        "/api/search" => search(shared, &query_param(url, "q").unwrap_or_default()),
+        _ => error("404 Not Found", "Not found"),
+    }
+}
+
+async fn handle(mut stream: TcpStream, shared: Arc<Shared>) -> std::io::Result<()> {
+    let head = read_head(&mut stream).await?;
+    let mut parts = head.lines().next().unwrap_or_default().split_whitespace();
+    let (method, url) = (parts.next().unwrap_or_default(), parts.next().unwrap_or("/").to_string());
+    if method != "GET" && method != "HEAD" {
+        stream.write_all(b"HTTP/1.1 405 Method Not Allowed\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").await?;
+        return Ok(());
+    }
+    let (status, kind, body) = tokio::task::spawn_blocking(move || respond(&shared, &url, now_secs()))
+        .await
+        .unwrap_or_else(|_| error("500 Internal Server Error", "Request failed"));
+    // The token is in the URL; keep it out of Referer headers and caches.
+    let header = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nContent-Security-Policy: {}\r\nReferrer-Policy: no-referrer\r\nX-Content-Type-Options: nosniff\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        status,
+        kind,
+        body.len(),
+        PAGE_POLICY
+    );
+    stream.write_all(header.as_bytes()).await?;
+    if method == "GET" {
+        stream.write_all(&body).await?;
+    }
+    stream.shutdown().await
+}
+
+// ── Commands ──
+
+/// Share the window's project read-only on the local network for
+/// `minutes` (30 by default, at most 8 hours). Replaces the window's
+/// running share, so the old link stops working.
+#[tauri::command]
+pub async fn share_readonly(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    shares: tauri::State<'_, ShareServerState>,
+    minutes: Option<u64>,
+) -> Result<ShareInfo, String> {
+    let root = project_root_async(window.label(), &state).await?;
+    let root = root.canonicalize().map_err(|e| format!("Failed to share project: {}", e))?;
+    let minutes = minutes.unwrap_or(DEFAULT_MINUTES).clamp(1, MAX_MINUTES);
+
+    let listener = TcpListener::bind("0.0.0.0:0").await.map_err(|e| format!("Failed to start share server: {}", e))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+    let token = new_token();
+    let expires_at = now_secs() + minutes * 60;
+    let url = format!("http://{}:{}/?token={}", lan_address(), port, token);
+    let info = ShareInfo {
+        qr_svg: qr_svg(&url)?,
+        url,
+        token: token.clone(),
+        expires_at,
+        root: root.to_string_lossy().into_owned(),
+    };
+
+    let shared = Arc::new(Shared { excludes: Excludes::for_project(&root), root, token, expires_at });
+    let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(minutes * 60);
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = &mut shutdown_rx => break,
+                _ = tokio::time::sleep_until(deadline) => break,
+                accepted = listener.accept() => {
+                    let Ok((stream, _)) = accepted else { continue };
+                    let shared = shared.clone();
+                    tauri::async_runtime::spawn(async move {
+                        let _ = handle(stream, shared).await;
+                    });
+                }
+            }
+        }
+    });
+
+    let share = Share { info: info.clone(), shutdown: Some(shutdown_tx) };
+    shares.0.lock().map_err(|e| e.to_string())?.insert(window.label().to_string(), share);
+    Ok(info)
+}
+
+/// Stop sharing the window's project. Returns false if it wasn't shared.
+#[tauri::command]
+pub fn stop_share_readonly(window: tauri::WebviewWindow, shares: tauri::State<'_, ShareServerState>) -> bool {
+    shares.0.lock().map(|mut s| s.remove(window.label()).is_some()).unwrap_or(false)
+}
+
+/// The window's share, if one is running and hasn't expired.
+#[tauri::command]
+pub fn get_share_readonly(
+    window: tauri::WebviewWindow,
+    shares: tauri::State<'_, ShareServerState>,
+) -> Option<ShareInfo> {
+    let shares = shares.0.lock().ok()?;
+    shares.get(window.label()).map(|s| s.info.clone()).filter(|info| info.expires_at > now_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn body(response: Response) -> serde_json::Value {
+        assert_eq!(response.0, "200 OK", "{}", String::from_utf8_lossy(&response.2));
+        serde_json::from_slice(&response.2).unwrap()
+    }
+
+    #[test]
+    fn serves_visible_files_to_token_holders_until_expiry() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules/x")).unwrap();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() { let secret_word = 1; }\n").unwrap();
+        std::fs::write(root.join("README.md"), "# Demo\n").unwrap();
+        std::fs::write(root.join(".env"), "secret_word=hunter2\n").unwrap();
+        std::fs::write(root.join(".git/config"), "[core]\n").unwrap();
+        std::fs::write(root.join("node_modules/x/index.js"), "secret_word\n").unwrap();
+        std::fs::write(root.join("logo.bin"), [0u8, 1, 2]).unwrap();
+        let shared = Shared { excludes: Excludes::for_project(&root), root, token: "abc123".into(), expires_at: 100 };
+        let get = |url: &str| respond(&shared, url, 50);
+
+        assert_eq!(get("/api/list?path=").0, "403 Forbidden");
+        assert_eq!(get("/api/list?token=abc124").0, "403 Forbidden");
+        assert_eq!(respond(&shared, "/?token=abc123", 100).0, "410 Gone");
+        assert_eq!(get("/?token=abc123").1, "text/html; charset=utf-8");
+
+        let listing = body(get("/api/list?token=abc123&path="));
+        let names: Vec<&str> = listing["entries"].as_array().unwrap().iter().map(|e| e["name"].as_str().unwrap()).collect();
+        assert_eq!(names, ["src", "logo.bin", "README.md"]);
+        assert_eq!(body(get("/api/list?token=abc123&path=src"))["entries"][0]["name"], "main.rs");
+
+        assert_eq!(body(get("/api/file?token=abc123&path=src%2Fmain.rs"))["path"], "src/main.rs");
+        assert_eq!(get("/api/file?token=abc123&path=.env").0, "404 Not Found");
+        assert_eq!(get("/api/file?token=abc123&path=.git/config").0, "404 Not Found");
+        assert_eq!(get("/api/file?token=abc123&path=node_modules/x/index.js").0, "404 Not Found");
+        assert_eq!(get("/api/file?token=abc123&path=src/../../etc/passwd").0, "404 Not Found");
+        assert_eq!(get("/api/file?token=abc123&path=logo.bin").0, "415 Unsupported Media Type");
+
+        let results = body(get("/api/search?token=abc123&q=secret+word"));
+        assert_eq!(results["matches"].as_array().unwrap().len(), 0);
+        let results = body(get("/api/search?token=abc123&q=secret_word"));
+        let paths: Vec<&str> = results["matches"].as_array().unwrap().iter().map(|m| m["path"].as_str().unwrap()).collect();
+        assert_eq!(paths, ["src/main.rs"]);
+        assert_eq!(get("/api/search?token=abc123&q=x").0, "400 Bad Request");
+    }
+}
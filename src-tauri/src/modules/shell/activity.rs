@@ -0,0 +1,149 @@
+//! Bell and activity/silence detection for terminals.
+//!
+//! Each session gets an `ActivityTracker` fed by the PTY reader and ticked
+//! once a second. It reports three things on `terminal-activity-{id}`:
+//!
+//! - `bell`: a BEL outside an escape sequence (at most one per second);
+//! - `activity`: output after at least `activity_after_secs` of quiet;
+//! - `silence`: quiet for `silence_after_secs` after a stretch of output
+//!   that lasted at least `min_busy_secs`, i.e. a long build just ended.
+//!
+//! A threshold of 0 turns that notification off.
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use ts_rs::TS;
+
+const BELL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct ActivityConfig {
+    pub bell: bool,
+    pub activity_after_secs: u64,
+    pub silence_after_secs: u64,
+    pub min_busy_secs: u64,
+}
+
+impl Default for ActivityConfig {
+    fn default() -> Self {
+        Self { bell: true, activity_after_secs: 10, silence_after_secs: 5, min_busy_secs: 15 }
+    }
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum ActivityKind {
+    Bell,
+    Activity,
+    Silence,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, TS)]
+pub struct TerminalActivity {
+    pub kind: ActivityKind,
+    /// How long the terminal was quiet (`activity`) or busy (`silence`).
+    #[ts(type = "number | null")]
+    pub duration_ms: Option<u64>,
+}
+
+pub struct ActivityTracker {
+    config: ActivityConfig,
+    last_output: Option<Instant>,
+    busy_since: Option<Instant>,
+    last_bell: Option<Instant>,
+    closed: bool,
+}
+
+fn secs(n: u64) -> Option<Duration> {
+    (n > 0).then_some(Duration::from_secs(n))
+}
+
+impl ActivityTracker {
+    pub fn new(config: ActivityConfig) -> Self {
+        Self { config, last_output: None, busy_since: None, last_bell: None, closed: false }
+    }
+
+    pub fn set_config(&mut self, config: ActivityConfig) {
+        self.config = config;
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// The session's output ended; the ticker stops on its next tick.
+    pub fn close(&mut self) {
+        self.closed = true;
+    }
+
+    /// Record output at `now`; `bell` is whether it contained a bare BEL.
+    pub fn on_output(&mut self, now: Instant, bell: bool) -> Vec<TerminalActivity> {
+        let mut events = Vec::new();
+        let quiet = self.last_output.map(|t| now.saturating_duration_since(t));
+        if let (Some(quiet), Some(threshold)) = (quiet, secs(self.config.activity_after_secs)) {
+            if quiet >= threshold {
+                events.push(TerminalActivity { kind: ActivityKind::Activity, duration_ms: Some(quiet.as_millis() as u64) });
+            }
+        }
+        let stretch_ended = match (quiet, secs(self.config.silence_after_secs)) {
+            (Some(quiet), Some(threshold)) => quiet >= threshold,
+            _ => false,
+        };
+        if self.busy_since.is_none() || stretch_ended {
+            self.busy_since = Some(now);
+        }
+        self.last_output = Some(now);
+        if bell && self.config.bell && self.last_bell.map_or(true, |t| now.saturating_duration_since(t) >= BELL_INTERVAL) {
+            self.last_bell = Some(now);
+            events.push(TerminalActivity { kind: ActivityKind::Bell, duration_ms: None });
+        }
+        events
+    }
+
+    /// Check for silence at `now`.
+    pub fn on_tick(&mut self, now: Instant) -> Option<TerminalActivity> {
+        let threshold = secs(self.config.silence_after_secs)?;
+        let last = self.last_output?;
+        let busy_since = self.busy_since?;
+        if now.saturating_duration_since(last) < threshold {
+            return None;
+        }
+        self.busy_since = None;
+        let busy = last.saturating_duration_since(busy_since);
+        (busy >= Duration::from_secs(self.config.min_busy_secs))
+            .then_some(TerminalActivity { kind: ActivityKind::Silence, duration_ms: Some(busy.as_millis() as u64) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_bells_activity_and_silence() {
+        let t0 = Instant::now();
+        let at = |s: u64| t0 + Duration::from_secs(s);
+        let kinds = |events: Vec<TerminalActivity>| events.into_iter().map(|e| e.kind).collect::<Vec<_>>();
+        let mut tracker = ActivityTracker::new(ActivityConfig::default());
+
+        assert_eq!(kinds(tracker.on_output(at(0), true)), vec![ActivityKind::Bell]);
+        assert!(tracker.on_output(at(0), true).is_empty());
+        // A 20s build with steady output, then quiet.
+        for s in 1..=20 {
+            assert!(tracker.on_output(at(s), false).is_empty());
+        }
+        assert_eq!(tracker.on_tick(at(22)), None);
+        let silence = tracker.on_tick(at(25)).unwrap();
+        assert_eq!((silence.kind, silence.duration_ms), (ActivityKind::Silence, Some(20_000)));
+        assert_eq!(tracker.on_tick(at(26)), None);
+
+        let events = tracker.on_output(at(40), false);
+        assert_eq!(events, vec![TerminalActivity { kind: ActivityKind::Activity, duration_ms: Some(20_000) }]);
+        // A short burst is not worth a silence notification.
+        assert_eq!(tracker.on_tick(at(50)), None);
+
+        tracker.set_config(ActivityConfig { bell: false, activity_after_secs: 0, ..ActivityConfig::default() });
+        assert!(tracker.on_output(at(100), true).is_empty());
+    }
+}
@@ -1,10 +1,24 @@
+use crate::modules::events;
 use crate::modules::fs::ProjectRootState;
+use crate::modules::policy::Category;
 use portable_pty::{native_pty_system, CommandBuilder, MasterPty, PtySize};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::io::{Read, Write};
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
-use tauri::{AppHandle, Emitter};
+use tauri::AppHandle;
+
+pub mod activity;
+pub mod scrollback;
+pub mod title;
+
+/// Policy categories of this module's commands.
+pub(crate) const COMMAND_CATEGORIES: &[(&str, &[Category])] = &[
+    ("spawn_terminal", &[Category::Terminal]),
+    ("write_terminal", &[Category::Terminal]),
+    ("run_command_capture", &[Category::Terminal]),
+];
 
 const MAX_SESSIONS: usize = 10;
 
@@ -12,12 +26,16 @@ const MAX_SESSIONS: usize = 10;
 pub struct SpawnResult {
     pub id: u32,
     pub pid: Option<u32>,
+    /// xterm.js scrollback for the pane.
+    pub scrollback_lines: u32,
 }
 
 pub struct PtyInstance {
     writer: Box<dyn Write + Send>,
     master: Box<dyn MasterPty + Send>,
     child: Box<dyn portable_pty::Child + Send + Sync>,
+    activity: Arc<Mutex<activity::ActivityTracker>>,
+    scrollback: Arc<Mutex<scrollback::ScrollbackBuffer>>,
 }
 
 pub struct TerminalManager {
@@ -49,6 +67,37 @@ pub fn create_terminal_state() -> TerminalState {
     Arc::new(Mutex::new(HashMap::new()))
 }
 
+// ── Settings ──
+
+/// Terminal settings in `~/.leo-ide/terminal.json`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct TerminalSettings {
+    pub activity: activity::ActivityConfig,
+    pub scrollback: scrollback::ScrollbackConfig,
+}
+
+fn settings_path() -> PathBuf {
+    dirs::home_dir().unwrap_or_default().join(".leo-ide").join("terminal.json")
+}
+
+fn load_settings() -> TerminalSettings {
+    std::fs::read_to_string(settings_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_settings(settings: &TerminalSettings) -> Result<(), String> {
+    let path = settings_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to save terminal settings: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    crate::modules::fs::write_atomic(&path, json.as_bytes())
+        .map_err(|e| format!("Failed to save terminal settings: {}", e))
+}
+
 #[tauri::command]
 pub fn spawn_terminal(
     window: tauri::WebviewWindow,
@@ -116,6 +165,11 @@ pub fn spawn_terminal(
     let writer = pair.master.take_writer().map_err(|e| e.to_string())?;
     let mut reader = pair.master.try_clone_reader().map_err(|e| e.to_string())?;
 
+    let settings = load_settings();
+    let tracker = Arc::new(Mutex::new(activity::ActivityTracker::new(settings.activity)));
+    let history = Arc::new(Mutex::new(scrollback::ScrollbackBuffer::new(settings.scrollback)));
+    let scrollback_lines = settings.scrollback.clamped().lines;
+
     let mut managers = state.lock().map_err(|e| e.to_string())?;
     let manager = managers.entry(label).or_insert_with(TerminalManager::new);
     let id = NEXT_TERMINAL_ID.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
@@ -125,22 +179,68 @@ pub fn spawn_terminal(
             writer,
             master: pair.master,
             child,
+            activity: tracker.clone(),
+            scrollback: history.clone(),
         },
     );
     drop(managers);
 
     // Spawn reader thread — emits "terminal-output" events to the spawning window only
-    let event_name = format!("terminal-output-{}", id);
-    let exit_event_name = format!("terminal-exit-{}", id);
     let window_label = window.label().to_string();
+
+    // Silence detection needs a clock, not just reads
+    {
+        let tracker = tracker.clone();
+        let app = app.clone();
+        let label = window_label.clone();
+        std::thread::spawn(move || loop {
+            std::thread::sleep(std::time::Duration::from_secs(1));
+            let event = {
+                let Ok(mut tracker) = tracker.lock() else { return };
+                if tracker.is_closed() {
+                    return;
+                }
+                tracker.on_tick(std::time::Instant::now())
+            };
+            if let Some(event) = event {
+                events::TERMINAL_ACTIVITY.emit_to(&app, &label, id, event);
+            }
+        });
+    }
+
+    // Foreground process changes ("npm run dev", "vim src/main.rs")
+    #[cfg(unix)]
+    {
+        let state = state.inner().clone();
+        let app = app.clone();
+        let label = window_label.clone();
+        std::thread::spawn(move || {
+            title::watch_foreground(state, label.clone(), id, |t| {
+                events::TERMINAL_TITLE.emit_to(&app, &label, id, t);
+            });
+        });
+    }
+
     std::thread::spawn(move || {
         let mut buf = [0u8; 16384];
         let mut pending = Vec::new();
-        let target = tauri::EventTarget::WebviewWindow { label: window_label.clone() };
+        let mut titles = title::OscTitleParser::new();
         loop {
             match reader.read(&mut buf) {
                 Ok(0) => break,
                 Ok(n) => {
+                    if let Some(t) = titles.feed(&buf[..n]) {
+                        let title = title::TerminalTitle { source: title::TitleSource::Osc, title: t, pid: None };
+                        events::TERMINAL_TITLE.emit_to(&app, &window_label, id, title);
+                    }
+                    let bell = titles.take_bell();
+                    let events = tracker
+                        .lock()
+                        .map(|mut t| t.on_output(std::time::Instant::now(), bell))
+                        .unwrap_or_default();
+                    for event in events {
+                        events::TERMINAL_ACTIVITY.emit_to(&app, &window_label, id, event);
+                    }
                     pending.extend_from_slice(&buf[..n]);
                     // Find the last valid UTF-8 boundary to avoid splitting multi-byte chars
                     let valid_len = {
@@ -152,8 +252,11 @@ pub fn spawn_terminal(
                         len
                     };
                     if valid_len > 0 {
+                        if let Ok(mut history) = history.lock() {
+                            history.push(&pending[..valid_len]);
+                        }
                         let data = String::from_utf8_lossy(&pending[..valid_len]).to_string();
-                        let _ = app.emit_to(target.clone(), &event_name, data);
+                        events::TERMINAL_OUTPUT.emit_to(&app, &window_label, id, data);
                         pending.drain(..valid_len);
                     }
                 }
@@ -162,14 +265,20 @@ pub fn spawn_terminal(
         }
         // Flush any remaining bytes
         if !pending.is_empty() {
+            if let Ok(mut history) = history.lock() {
+                history.push(&pending);
+            }
             let data = String::from_utf8_lossy(&pending).to_string();
-            let _ = app.emit_to(target.clone(), &event_name, data);
+            events::TERMINAL_OUTPUT.emit_to(&app, &window_label, id, data);
+        }
+        if let Ok(mut tracker) = tracker.lock() {
+            tracker.close();
         }
         // Notify frontend that this terminal session has exited
-        let _ = app.emit_to(target, &exit_event_name, ());
+        events::TERMINAL_EXIT.emit_to(&app, &window_label, id, ());
     });
 
-    Ok(SpawnResult { id, pid })
+    Ok(SpawnResult { id, pid, scrollback_lines })
 }
 
 #[tauri::command]
@@ -251,6 +360,160 @@ pub struct CommandOutput {
 /// Run a shell command and capture its output (stdout, stderr, exit code).
 /// Used by the agent's run_command tool and self-verify loop.
 /// Enforces a timeout to prevent runaway processes.
+#[tauri::command]
+pub fn get_terminal_activity_config() -> activity::ActivityConfig {
+    load_settings().activity
+}
+
+/// Save the bell/activity/silence thresholds and apply them to every open
+/// terminal.
+#[tauri::command]
+pub fn set_terminal_activity_config(
+    state: tauri::State<'_, TerminalState>,
+    config: activity::ActivityConfig,
+) -> Result<(), String> {
+    let mut settings = load_settings();
+    settings.activity = config.clone();
+    save_settings(&settings)?;
+    let managers = state.lock().map_err(|e| e.to_string())?;
+    for session in managers.values().flat_map(|m| m.sessions.values()) {
+        if let Ok(mut tracker) = session.activity.lock() {
+            tracker.set_config(config.clone());
+        }
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+pub struct TerminalMemory {
+    pub window: String,
+    pub id: u32,
+    /// Output currently held for replay.
+    pub buffered_bytes: usize,
+    /// Heap reserved for the buffer.
+    pub allocated_bytes: usize,
+    pub limit_bytes: usize,
+    pub scrollback_lines: u32,
+    /// Everything the session has printed since it started.
+    pub total_output_bytes: u64,
+}
+
+#[derive(Serialize)]
+pub struct TerminalMemoryUsage {
+    pub terminals: Vec<TerminalMemory>,
+    pub total_allocated_bytes: usize,
+}
+
+#[tauri::command]
+pub fn get_terminal_scrollback_config() -> scrollback::ScrollbackConfig {
+    load_settings().scrollback.clamped()
+}
+
+/// Change the scrollback of terminal `id`, or with no `id` save the default
+/// and apply it to every open terminal. Returns the config after clamping.
+#[tauri::command]
+pub fn set_terminal_scrollback_config(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, TerminalState>,
+    config: scrollback::ScrollbackConfig,
+    id: Option<u32>,
+) -> Result<scrollback::ScrollbackConfig, String> {
+    let config = config.clamped();
+    let managers = state.lock().map_err(|e| e.to_string())?;
+    let sessions: Vec<&PtyInstance> = match id {
+        Some(id) => vec![managers
+            .get(window.label())
+            .and_then(|m| m.sessions.get(&id))
+            .ok_or("Terminal session not found")?],
+        None => {
+            let mut settings = load_settings();
+            settings.scrollback = config;
+            save_settings(&settings)?;
+            managers.values().flat_map(|m| m.sessions.values()).collect()
+        }
+    };
+    for session in sessions {
+        if let Ok(mut history) = session.scrollback.lock() {
+            history.set_config(config);
+        }
+    }
+    Ok(config)
+}
+
+/// Recent output of terminal `id`, for replaying into a fresh pane.
+#[tauri::command]
+pub fn read_terminal_scrollback(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, TerminalState>,
+    id: u32,
+) -> Result<String, String> {
+    let managers = state.lock().map_err(|e| e.to_string())?;
+    let session = managers
+        .get(window.label())
+        .and_then(|m| m.sessions.get(&id))
+        .ok_or("Terminal session not found")?;
+    let history = session.scrollback.lock().map_err(|e| e.to_string())?;
+    Ok(history.contents())
+}
+
+/// Scrollback memory held by every terminal in every window.
+#[tauri::command]
+pub fn get_terminal_memory_usage(state: tauri::State<'_, TerminalState>) -> Result<TerminalMemoryUsage, String> {
+    let managers = state.lock().map_err(|e| e.to_string())?;
+    let mut terminals = Vec::new();
+    for (label, manager) in managers.iter() {
+        for (id, session) in &manager.sessions {
+            let history = session.scrollback.lock().map_err(|e| e.to_string())?;
+            let config = history.config();
+            terminals.push(TerminalMemory {
+                window: label.clone(),
+                id: *id,
+                buffered_bytes: history.len(),
+                allocated_bytes: history.allocated(),
+                limit_bytes: config.bytes,
+                scrollback_lines: config.lines,
+                total_output_bytes: history.total_bytes(),
+            });
+        }
+    }
+    terminals.sort_by(|a, b| b.allocated_bytes.cmp(&a.allocated_bytes).then(a.id.cmp(&b.id)));
+    let total_allocated_bytes = terminals.iter().map(|t| t.allocated_bytes).sum();
+    Ok(TerminalMemoryUsage { terminals, total_allocated_bytes })
+}
+
+/// Where each of the window's shells currently is, oldest terminal first;
+/// `None` where the OS won't tell.
+pub(crate) fn terminal_cwds(state: &TerminalState, label: &str) -> Vec<Option<PathBuf>> {
+    let mut pids: Vec<(u32, Option<u32>)> = match state.lock() {
+        Ok(managers) => managers
+            .get(label)
+            .map(|m| m.sessions.iter().map(|(id, s)| (*id, s.child.process_id())).collect())
+            .unwrap_or_default(),
+        Err(_) => return Vec::new(),
+    };
+    pids.sort_unstable();
+    pids.into_iter().map(|(_, pid)| pid.and_then(process_cwd)).collect()
+}
+
+#[cfg(target_os = "linux")]
+fn process_cwd(pid: u32) -> Option<PathBuf> {
+    std::fs::read_link(format!("/proc/{}/cwd", pid)).ok()
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn process_cwd(pid: u32) -> Option<PathBuf> {
+    let out = crate::modules::process::ProcessCommand::new("lsof")
+        .args(["-a", "-p", &pid.to_string(), "-d", "cwd", "-Fn"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&out.stdout).lines().find_map(|l| l.strip_prefix('n')).map(PathBuf::from)
+}
+
+#[cfg(not(unix))]
+fn process_cwd(_pid: u32) -> Option<PathBuf> {
+    None
+}
+
 #[tauri::command]
 pub async fn run_command_capture(
     window: tauri::WebviewWindow,
@@ -0,0 +1,120 @@
+//! Bounded per-terminal output history.
+//!
+//! The reader thread appends every chunk it emits, so a pane can replay
+//! recent output after a reload. The buffer drops its oldest bytes once it
+//! reaches `bytes`; `lines` is the xterm.js scrollback the frontend keeps,
+//! and both are reported by `get_terminal_memory_usage`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+const MIN_BYTES: usize = 64 * 1024;
+const MAX_BYTES: usize = 64 * 1024 * 1024;
+const MIN_LINES: u32 = 100;
+const MAX_LINES: u32 = 100_000;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct ScrollbackConfig {
+    /// Lines of scrollback xterm.js keeps for the pane.
+    pub lines: u32,
+    /// Bytes of raw output the backend keeps for replay.
+    pub bytes: usize,
+}
+
+impl Default for ScrollbackConfig {
+    fn default() -> Self {
+        Self { lines: 1000, bytes: 1024 * 1024 }
+    }
+}
+
+impl ScrollbackConfig {
+    pub fn clamped(self) -> Self {
+        Self { lines: self.lines.clamp(MIN_LINES, MAX_LINES), bytes: self.bytes.clamp(MIN_BYTES, MAX_BYTES) }
+    }
+}
+
+pub struct ScrollbackBuffer {
+    config: ScrollbackConfig,
+    data: VecDeque<u8>,
+    /// Everything ever written, including what has since been dropped.
+    total_bytes: u64,
+}
+
+impl ScrollbackBuffer {
+    pub fn new(config: ScrollbackConfig) -> Self {
+        Self { config: config.clamped(), data: VecDeque::new(), total_bytes: 0 }
+    }
+
+    pub fn config(&self) -> ScrollbackConfig {
+        self.config
+    }
+
+    pub fn set_config(&mut self, config: ScrollbackConfig) {
+        self.config = config.clamped();
+        self.enforce();
+        self.data.shrink_to(self.config.bytes);
+    }
+
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.total_bytes += bytes.len() as u64;
+        // Only the tail of an oversized chunk can survive anyway.
+        let bytes = &bytes[bytes.len().saturating_sub(self.config.bytes)..];
+        self.data.extend(bytes);
+        self.enforce();
+    }
+
+    fn enforce(&mut self) {
+        let excess = self.data.len().saturating_sub(self.config.bytes);
+        self.data.drain(..excess);
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Heap bytes held, which can exceed `len` after a burst.
+    pub fn allocated(&self) -> usize {
+        self.data.capacity()
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.total_bytes
+    }
+
+    /// The buffered output, starting at the first complete character.
+    pub fn contents(&self) -> String {
+        let bytes: Vec<u8> = self.data.iter().copied().skip_while(|b| b & 0xC0 == 0x80).collect();
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_oldest_output_past_the_limit() {
+        let mut buf = ScrollbackBuffer::new(ScrollbackConfig { lines: 1, bytes: 1 });
+        assert_eq!(buf.config(), ScrollbackConfig { lines: MIN_LINES, bytes: MIN_BYTES });
+
+        buf.push(&vec![b'a'; MIN_BYTES - 1]);
+        buf.push("é".as_bytes());
+        assert_eq!(buf.len(), MIN_BYTES);
+        // Cutting through "é" must not leave a broken character at the start.
+        buf.push(&vec![b'b'; MIN_BYTES - 1]);
+        assert_eq!(buf.contents(), "b".repeat(MIN_BYTES - 1));
+        buf.push(&vec![b'c'; MIN_BYTES * 3]);
+        assert_eq!(buf.len(), MIN_BYTES);
+        assert_eq!(buf.total_bytes(), (MIN_BYTES * 5) as u64);
+
+        buf.set_config(ScrollbackConfig { lines: 500, bytes: MIN_BYTES * 2 });
+        buf.push(b"dd");
+        assert_eq!(buf.len(), MIN_BYTES + 2);
+        assert!(buf.contents().ends_with("cdd"));
+    }
+}
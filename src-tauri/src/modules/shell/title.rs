@@ -0,0 +1,223 @@
+//! Terminal titles for tab labels.
+//!
+//! Two sources feed `terminal-title-{id}`: OSC 0/2 sequences written by
+//! the shell or a full-screen program (`ESC ] 2 ; title BEL`), and, on
+//! unix, the command line of the PTY's foreground process group leader,
+//! polled while the session is alive. The frontend shows whichever
+//! arrived last.
+
+use serde::Serialize;
+use ts_rs::TS;
+
+/// Titles longer than this are cut; OSC bodies longer than this are dropped.
+const MAX_TITLE_CHARS: usize = 120;
+const MAX_OSC_BYTES: usize = 4096;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum TitleSource {
+    Osc,
+    Process,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, TS)]
+pub struct TerminalTitle {
+    pub source: TitleSource,
+    pub title: String,
+    /// Foreground process id, for `Process` titles.
+    pub pid: Option<u32>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum OscState {
+    Ground,
+    Escape,
+    Body,
+    /// Saw `ESC` inside the body; `\` completes the `ST` terminator.
+    BodyEscape,
+}
+
+/// Incremental OSC title scanner. Sequences may be split across reads, so
+/// state carries over between `feed` calls. It also notes bare BELs, which
+/// would otherwise be confused with the OSC terminator.
+pub(crate) struct OscTitleParser {
+    state: OscState,
+    body: Vec<u8>,
+    overflow: bool,
+    bell: bool,
+}
+
+impl OscTitleParser {
+    pub fn new() -> Self {
+        Self { state: OscState::Ground, body: Vec::new(), overflow: false, bell: false }
+    }
+
+    /// Whether a BEL outside any escape sequence was fed since the last call.
+    pub fn take_bell(&mut self) -> bool {
+        std::mem::take(&mut self.bell)
+    }
+
+    /// Scan `bytes` and return the last title set in them, if any.
+    pub fn feed(&mut self, bytes: &[u8]) -> Option<String> {
+        let mut title = None;
+        for &b in bytes {
+            self.state = match (self.state, b) {
+                (OscState::Ground, 0x1b) => OscState::Escape,
+                (OscState::Ground, 0x07) => {
+                    self.bell = true;
+                    OscState::Ground
+                }
+                (OscState::Ground, _) => OscState::Ground,
+                (OscState::Body, 0x07) | (OscState::BodyEscape, b'\\') => {
+                    if let Some(t) = self.finish() {
+                        title = Some(t);
+                    }
+                    OscState::Ground
+                }
+                (OscState::Body, 0x1b) => OscState::BodyEscape,
+                // Any other escape inside a body aborts it.
+                (OscState::Escape | OscState::BodyEscape, b']') => {
+                    self.body.clear();
+                    self.overflow = false;
+                    OscState::Body
+                }
+                (OscState::Escape | OscState::BodyEscape, 0x1b) => OscState::Escape,
+                (OscState::Escape | OscState::BodyEscape, _) => OscState::Ground,
+                (OscState::Body, _) => {
+                    if self.body.len() < MAX_OSC_BYTES {
+                        self.body.push(b);
+                    } else {
+                        self.overflow = true;
+                    }
+                    OscState::Body
+                }
+            };
+        }
+        title
+    }
+
+    fn finish(&mut self) -> Option<String> {
+        if self.overflow {
+            return None;
+        }
+        let body = String::from_utf8_lossy(&self.body);
+        let (code, text) = body.split_once(';')?;
+        // 0 sets icon name and title, 2 the title; 1 (icon name only) is ignored.
+        if code != "0" && code != "2" {
+            return None;
+        }
+        let text: String = text.chars().filter(|c| !c.is_control()).collect();
+        Some(truncate(text.trim()))
+    }
+}
+
+fn truncate(s: &str) -> String {
+    match s.char_indices().nth(MAX_TITLE_CHARS) {
+        Some((i, _)) => format!("{}…", &s[..i]),
+        None => s.to_string(),
+    }
+}
+
+/// `argv` as a tab label: the program's file name (without the `-` login
+/// shells get) followed by its arguments.
+#[cfg_attr(not(unix), allow(dead_code))]
+fn format_command(argv: &[String]) -> Option<String> {
+    let (program, args) = argv.split_first()?;
+    let program = program.trim_start_matches('-');
+    let name = program.rsplit('/').next().unwrap_or(program);
+    if name.is_empty() {
+        return None;
+    }
+    let mut out = name.to_string();
+    for arg in args {
+        out.push(' ');
+        out.push_str(arg);
+    }
+    Some(truncate(&out))
+}
+
+#[cfg(target_os = "linux")]
+fn process_command(pid: u32) -> Option<String> {
+    let raw = std::fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+    let argv: Vec<String> = raw
+        .split(|&b| b == 0)
+        .filter(|part| !part.is_empty())
+        .map(|part| String::from_utf8_lossy(part).into_owned())
+        .collect();
+    format_command(&argv)
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn process_command(pid: u32) -> Option<String> {
+    let out = crate::modules::process::ProcessCommand::new("ps")
+        .args(["-o", "command=", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    let line = String::from_utf8_lossy(&out.stdout);
+    let argv: Vec<String> = line.split_whitespace().map(str::to_string).collect();
+    format_command(&argv)
+}
+
+/// Poll the foreground process of session `id` until it goes away, calling
+/// `emit` whenever the process (or its command line) changes.
+#[cfg(unix)]
+pub(crate) fn watch_foreground(
+    state: super::TerminalState,
+    window_label: String,
+    id: u32,
+    emit: impl Fn(TerminalTitle),
+) {
+    let mut last: Option<(u32, String)> = None;
+    loop {
+        std::thread::sleep(std::time::Duration::from_secs(1));
+        let leader = {
+            let Ok(managers) = state.lock() else { return };
+            let Some(session) = managers.get(&window_label).and_then(|m| m.sessions.get(&id)) else {
+                return;
+            };
+            session.master.process_group_leader()
+        };
+        let Some(pid) = leader.and_then(|p| u32::try_from(p).ok()) else { continue };
+        let Some(title) = process_command(pid) else { continue };
+        if last.as_ref() != Some(&(pid, title.clone())) {
+            emit(TerminalTitle { source: TitleSource::Process, title: title.clone(), pid: Some(pid) });
+            last = Some((pid, title));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_osc_titles_across_chunks() {
+        let mut p = OscTitleParser::new();
+        assert_eq!(p.feed(b"plain output\x1b[0m"), None);
+        assert_eq!(p.feed(b"\x1b]2;vim \xe2\x80\x93 src/main.rs\x07$ "), Some("vim – src/main.rs".to_string()));
+        assert_eq!(p.feed(b"\x1b]0;user@host: ~/pro"), None);
+        assert_eq!(p.feed(b"ject\x1b"), None);
+        assert_eq!(p.feed(b"\\"), Some("user@host: ~/project".to_string()));
+        // Icon name only, and other OSC codes, are not titles.
+        assert_eq!(p.feed(b"\x1b]1;icon\x07\x1b]7;file:///tmp\x07"), None);
+        assert_eq!(p.feed(b"\x1b]2;first\x07\x1b]2;second\x07"), Some("second".to_string()));
+        let long = format!("\x1b]2;{}\x07", "x".repeat(MAX_OSC_BYTES + 1));
+        assert_eq!(p.feed(long.as_bytes()), None);
+        assert!(!p.take_bell());
+        p.feed(b"done\x07");
+        assert!(p.take_bell());
+        assert!(!p.take_bell());
+    }
+
+    #[test]
+    fn formats_command_lines() {
+        let argv = |s: &str| s.split(' ').map(str::to_string).collect::<Vec<_>>();
+        assert_eq!(format_command(&argv("/usr/bin/vim src/main.rs")).as_deref(), Some("vim src/main.rs"));
+        assert_eq!(format_command(&argv("-zsh")).as_deref(), Some("zsh"));
+        assert_eq!(format_command(&argv("npm run dev")).as_deref(), Some("npm run dev"));
+        assert_eq!(format_command(&[]), None);
+    }
+}
@@ -0,0 +1,227 @@
+//! Lines-of-code statistics for the project overview panel.
+//!
+//! Files are walked like project search does (gitignore plus the
+//! project's exclude patterns) and every file in a known language is
+//! split into code, comment and blank lines, tokei-style. A line with both
+//! code and a trailing comment counts as code. Files in other languages,
+//! binary files and files over 5 MB are only counted in `other_files`.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::modules::fs::{project_root_async, resolve_within_root, ProjectRootState};
+use crate::modules::search::{project_files, read_text};
+
+/// Stop after this many files so a huge tree can't stall the panel.
+const MAX_FILES: usize = 100_000;
+
+struct LanguageSpec {
+    name: &'static str,
+    extensions: &'static [&'static str],
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+}
+
+const C_BLOCK: Option<(&str, &str)> = Some(("/*", "*/"));
+
+const LANGUAGES: &[LanguageSpec] = &[
+    LanguageSpec { name: "Rust", extensions: &["rs"], line_comments: &["//"], block_comment: C_BLOCK },
+    LanguageSpec { name: "TypeScript", extensions: &["ts", "tsx", "mts", "cts"], line_comments: &["//"], block_comment: C_BLOCK },
+    LanguageSpec { name: "JavaScript", extensions: &["js", "jsx", "mjs", "cjs"], line_comments: &["//"], block_comment: C_BLOCK },
+    LanguageSpec { name: "Svelte", extensions: &["svelte"], line_comments: &["//"], block_comment: Some(("<!--", "-->")) },
+    LanguageSpec { name: "Vue", extensions: &["vue"], line_comments: &["//"], block_comment: Some(("<!--", "-->")) },
+    LanguageSpec { name: "HTML", extensions: &["html", "htm"], line_comments: &[], block_comment: Some(("<!--", "-->")) },
+    LanguageSpec { name: "CSS", extensions: &["css", "scss", "less"], line_comments: &[], block_comment: C_BLOCK },
+    LanguageSpec { name: "Python", extensions: &["py", "pyi"], line_comments: &["#"], block_comment: None },
+    LanguageSpec { name: "Go", extensions: &["go"], line_comments: &["//"], block_comment: C_BLOCK },
+    LanguageSpec { name: "Java", extensions: &["java"], line_comments: &["//"], block_comment: C_BLOCK },
+    LanguageSpec { name: "Kotlin", extensions: &["kt", "kts"], line_comments: &["//"], block_comment: C_BLOCK },
+    LanguageSpec { name: "C", extensions: &["c", "h"], line_comments: &["//"], block_comment: C_BLOCK },
+    LanguageSpec { name: "C++", extensions: &["cpp", "cc", "cxx", "hpp", "hh"], line_comments: &["//"], block_comment: C_BLOCK },
+    LanguageSpec { name: "C#", extensions: &["cs"], line_comments: &["//"], block_comment: C_BLOCK },
+    LanguageSpec { name: "Swift", extensions: &["swift"], line_comments: &["//"], block_comment: C_BLOCK },
+    LanguageSpec { name: "Ruby", extensions: &["rb"], line_comments: &["#"], block_comment: Some(("=begin", "=end")) },
+    LanguageSpec { name: "Shell", extensions: &["sh", "bash", "zsh"], line_comments: &["#"], block_comment: None },
+    LanguageSpec { name: "SQL", extensions: &["sql"], line_comments: &["--"], block_comment: C_BLOCK },
+    LanguageSpec { name: "Lua", extensions: &["lua"], line_comments: &["--"], block_comment: Some(("--[[", "]]")) },
+    LanguageSpec { name: "TOML", extensions: &["toml"], line_comments: &["#"], block_comment: None },
+    LanguageSpec { name: "YAML", extensions: &["yaml", "yml"], line_comments: &["#"], block_comment: None },
+    LanguageSpec { name: "JSON", extensions: &["json"], line_comments: &[], block_comment: None },
+    LanguageSpec { name: "Markdown", extensions: &["md", "markdown"], line_comments: &[], block_comment: Some(("<!--", "-->")) },
+];
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct LineCounts {
+    pub files: u64,
+    pub lines: u64,
+    pub code: u64,
+    pub comments: u64,
+    pub blanks: u64,
+}
+
+impl LineCounts {
+    fn add(&mut self, other: &LineCounts) {
+        self.files += other.files;
+        self.lines += other.lines;
+        self.code += other.code;
+        self.comments += other.comments;
+        self.blanks += other.blanks;
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct LanguageStats {
+    pub language: String,
+    #[serde(flatten)]
+    pub counts: LineCounts,
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct ProjectStats {
+    /// Totals over all recognized languages.
+    pub total: LineCounts,
+    /// Largest first, by lines of code.
+    pub languages: Vec<LanguageStats>,
+    pub other_files: u64,
+    /// Whether the walk stopped at `MAX_FILES`.
+    pub truncated: bool,
+}
+
+fn language_for(path: &Path) -> Option<&'static LanguageSpec> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    LANGUAGES.iter().find(|l| l.extensions.contains(&ext.as_str()))
+}
+
+/// Count the lines of one file's `content`.
+fn count_lines(content: &str, spec: &LanguageSpec) -> LineCounts {
+    let mut counts = LineCounts { files: 1, ..Default::default() };
+    let mut in_block = false;
+    for line in content.lines() {
+        counts.lines += 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            counts.blanks += 1;
+            continue;
+        }
+        if let (true, Some((_, end))) = (in_block, spec.block_comment) {
+            counts.comments += 1;
+            if let Some(at) = trimmed.find(end) {
+                in_block = false;
+                // Code after the comment closes makes it a code line.
+                if !trimmed[at + end.len()..].trim().is_empty() {
+                    counts.comments -= 1;
+                    counts.code += 1;
+                }
+            }
+            continue;
+        }
+        if spec.line_comments.iter().any(|c| trimmed.starts_with(c)) {
+            counts.comments += 1;
+            continue;
+        }
+        if let Some((start, end)) = spec.block_comment {
+            if let Some(rest) = trimmed.strip_prefix(start) {
+                match rest.find(end) {
+                    Some(at) if !rest[at + end.len()..].trim().is_empty() => counts.code += 1,
+                    Some(_) => counts.comments += 1,
+                    None => {
+                        counts.comments += 1;
+                        in_block = true;
+                    }
+                }
+                continue;
+            }
+        }
+        counts.code += 1;
+        // A block opened after code on this line runs on to the next.
+        if let Some((start, end)) = spec.block_comment {
+            if let Some(at) = trimmed.rfind(start) {
+                in_block = !trimmed[at + start.len()..].contains(end);
+            }
+        }
+    }
+    counts
+}
+
+pub(crate) fn collect_stats(root: &Path, dir: &Path) -> ProjectStats {
+    let mut by_language: HashMap<&'static str, LineCounts> = HashMap::new();
+    let mut stats = ProjectStats::default();
+    for (i, path) in project_files(root, dir).enumerate() {
+        if i == MAX_FILES {
+            stats.truncated = true;
+            break;
+        }
+        let counted = language_for(&path).and_then(|spec| Some((spec, read_text(&path)?)));
+        match counted {
+            Some((spec, content)) => by_language.entry(spec.name).or_default().add(&count_lines(&content, spec)),
+            None => stats.other_files += 1,
+        }
+    }
+    for counts in by_language.values() {
+        stats.total.add(counts);
+    }
+    stats.languages = by_language
+        .into_iter()
+        .map(|(language, counts)| LanguageStats { language: language.to_string(), counts })
+        .collect();
+    stats.languages.sort_by(|a, b| b.counts.code.cmp(&a.counts.code).then_with(|| a.language.cmp(&b.language)));
+    stats
+}
+
+/// File and line counts per language for the open project, or for `root`
+/// (a directory inside it) when given.
+#[tauri::command]
+pub async fn project_stats(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    root: Option<String>,
+) -> Result<ProjectStats, String> {
+    let project = project_root_async(window.label(), &state).await?;
+    let dir = match root {
+        Some(root) => resolve_within_root(&root, &project)?,
+        None => project.clone(),
+    };
+    tokio::task::spawn_blocking(move || collect_stats(&project, &dir))
+        .await
+        .map_err(|e| format!("Counting lines failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(name: &str) -> &'static LanguageSpec {
+        LANGUAGES.iter().find(|l| l.name == name).unwrap()
+    }
+
+    #[test]
+    fn splits_code_comments_and_blanks() {
+        let rust = "//! Crate docs\n\nfn main() {\n    /* one\n       two */\n    let x = 1; // trailing\n    /* a */ let y = 2;\n}\n";
+        let counts = count_lines(rust, spec("Rust"));
+        assert_eq!((counts.lines, counts.code, counts.comments, counts.blanks), (8, 4, 3, 1));
+
+        let python = "# comment\nimport os\n\n\ndef f():\n    return 1\n";
+        let counts = count_lines(python, spec("Python"));
+        assert_eq!((counts.code, counts.comments, counts.blanks), (3, 1, 2));
+    }
+
+    #[test]
+    fn totals_by_language_and_skips_excluded_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("target")).unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(root.join("src/lib.rs"), "// lib\npub fn f() {}\n\n").unwrap();
+        std::fs::write(root.join("src/app.ts"), "export const a = 1;\n").unwrap();
+        std::fs::write(root.join("target/out.rs"), "fn generated() {}\n").unwrap();
+        std::fs::write(root.join("logo.png"), b"\x89PNG\0\0").unwrap();
+
+        let stats = collect_stats(root, root);
+        let languages: Vec<_> = stats.languages.iter().map(|l| (l.language.as_str(), l.counts.files, l.counts.code)).collect();
+        assert_eq!(languages, [("Rust", 2, 2), ("TypeScript", 1, 1)]);
+        assert_eq!((stats.total.files, stats.total.lines, stats.total.comments), (3, 5, 1));
+        assert_eq!(stats.other_files, 1);
+    }
+}
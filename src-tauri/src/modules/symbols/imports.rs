@@ -0,0 +1,469 @@
+//! Import suggestions without a language server.
+//!
+//! Given a symbol name and the file that needs it, look up the files that
+//! export the symbol in the knowledge index and build the import statement
+//! each one would need, following the conventions of the language:
+//! relative specifiers for JS/TS, dotted package paths for Python, and
+//! `crate::`/crate-name paths for Rust. Only those candidates are read and
+//! parsed. The file watcher keeps the index current as files change; a
+//! project whose first full index run hasn't finished is walked instead.
+
+use serde::Serialize;
+use std::path::{Component, Path, PathBuf};
+
+use super::extract_symbols;
+use crate::modules::fs::{validate_path, ProjectRootState};
+use crate::modules::knowledge;
+
+const MAX_SCAN_FILES: usize = 20_000;
+const MAX_SCAN_BYTES: u64 = 512 * 1024;
+const MAX_SUGGESTIONS: usize = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum Lang {
+    Script,
+    Python,
+    Rust,
+}
+
+pub(super) fn lang_of(path: &Path) -> Option<Lang> {
+    match path.extension().and_then(|e| e.to_str())? {
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" => Some(Lang::Script),
+        "py" | "pyi" => Some(Lang::Python),
+        "rs" => Some(Lang::Rust),
+        _ => None,
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ImportSuggestion {
+    /// Exporting file, relative to the project root.
+    pub source_file: String,
+    /// Full statement including trailing newline, ready to insert.
+    pub statement: String,
+    /// 1-based line to insert the statement before.
+    pub insert_line: usize,
+    /// True when `from_file` already contains this exact statement.
+    pub already_imported: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ExportKind {
+    Named,
+    Default,
+    /// TS interface / type alias — imported with `import type`.
+    TypeOnly,
+}
+
+// ── Export detection ──
+
+fn ident_after<'a>(line: &'a str, keywords: &[&str]) -> Option<&'a str> {
+    for kw in keywords {
+        if let Some(rest) = line.strip_prefix(kw) {
+            let name = rest.trim_start().trim_start_matches('*').trim_start();
+            let end = name
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(name.len());
+            if end > 0 {
+                return Some(&name[..end]);
+            }
+        }
+    }
+    None
+}
+
+/// How `source` exports `symbol`, if it does. Declarations found by the
+/// tree-sitter symbol extractor are checked first; the line scan covers
+/// forms it doesn't model (`export const`, `export { a as b }`, Rust
+/// `pub const`, Python module-level assignments).
+fn export_kind(source: &str, ext: &str, lang: Lang, symbol: &str) -> Option<ExportKind> {
+    let lines: Vec<&str> = source.lines().collect();
+    for sym in extract_symbols(source, ext).iter().filter(|s| s.name == symbol) {
+        let decl = lines.get(sym.start_line - 1).copied().unwrap_or("");
+        match lang {
+            Lang::Script if sym.kind == "export" => {
+                let t = decl.trim_start();
+                return Some(if t.starts_with("export default") {
+                    ExportKind::Default
+                } else if t.starts_with("export interface") || t.starts_with("export type") {
+                    ExportKind::TypeOnly
+                } else {
+                    ExportKind::Named
+                });
+            }
+            Lang::Rust if decl.trim_start().starts_with("pub") => return Some(ExportKind::Named),
+            Lang::Python if !decl.starts_with(char::is_whitespace) && !symbol.starts_with('_') => {
+                return Some(ExportKind::Named)
+            }
+            _ => {}
+        }
+    }
+
+    for line in &lines {
+        let t = line.trim_start();
+        match lang {
+            Lang::Script => {
+                let Some(rest) = t.strip_prefix("export ") else { continue };
+                if ident_after(rest, &["const", "let", "var", "enum", "declare const", "abstract class"]) == Some(symbol) {
+                    return Some(ExportKind::Named);
+                }
+                if let Some(list) = rest.trim_start().strip_prefix('{') {
+                    let list = list.split('}').next().unwrap_or("");
+                    for item in list.split(',') {
+                        let exported = item.rsplit(" as ").next().unwrap_or("").trim();
+                        if exported == symbol {
+                            return Some(if exported == "default" { ExportKind::Default } else { ExportKind::Named });
+                        }
+                    }
+                }
+            }
+            Lang::Rust => {
+                let rest = t.strip_prefix("pub(crate) ").or_else(|| t.strip_prefix("pub "));
+                if rest.and_then(|r| ident_after(r, &["const", "static", "mod", "type"])) == Some(symbol) {
+                    return Some(ExportKind::Named);
+                }
+            }
+            Lang::Python => {
+                if line.starts_with(symbol)
+                    && !symbol.starts_with('_')
+                    && line[symbol.len()..].trim_start().starts_with(['=', ':'])
+                {
+                    return Some(ExportKind::Named);
+                }
+            }
+        }
+    }
+    None
+}
+
+// ── Module paths ──
+
+/// Relative path from `from_dir` to `to`, using `/` separators.
+fn relative_path(from_dir: &Path, to: &Path) -> String {
+    let from: Vec<Component> = from_dir.components().collect();
+    let target: Vec<Component> = to.components().collect();
+    let common = from.iter().zip(&target).take_while(|(a, b)| a == b).count();
+    let mut parts: Vec<String> = vec!["..".to_string(); from.len() - common];
+    parts.extend(target[common..].iter().map(|c| c.as_os_str().to_string_lossy().to_string()));
+    parts.join("/")
+}
+
+fn script_specifier(from_file: &Path, target: &Path) -> String {
+    let without_ext = target.with_extension("");
+    let without_index = if without_ext.file_name().is_some_and(|n| n == "index") {
+        without_ext.parent().unwrap_or(&without_ext).to_path_buf()
+    } else {
+        without_ext
+    };
+    let rel = relative_path(from_file.parent().unwrap_or(Path::new("")), &without_index);
+    if rel.is_empty() {
+        ".".to_string()
+    } else if rel.starts_with("..") {
+        rel
+    } else {
+        format!("./{}", rel)
+    }
+}
+
+/// Dotted module path relative to the project root. A top-level `src/`
+/// that isn't itself a package is treated as a source root (src layout).
+fn python_module(root: &Path, target: &Path) -> String {
+    let rel = target.strip_prefix(root).unwrap_or(target).with_extension("");
+    let mut parts: Vec<String> = rel.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+    if parts.last().is_some_and(|p| p == "__init__") {
+        parts.pop();
+    }
+    if parts.len() > 1 && parts[0] == "src" && !root.join("src/__init__.py").exists() {
+        parts.remove(0);
+    }
+    parts.join(".")
+}
+
+/// Nearest ancestor of `file` (inclusive of `root`) holding a Cargo.toml.
+fn crate_dir(root: &Path, file: &Path) -> Option<PathBuf> {
+    file.ancestors()
+        .skip(1)
+        .take_while(|d| d.starts_with(root))
+        .find(|d| d.join("Cargo.toml").is_file())
+        .map(Path::to_path_buf)
+}
+
+fn crate_name(crate_dir: &Path) -> Option<String> {
+    let manifest = std::fs::read_to_string(crate_dir.join("Cargo.toml")).ok()?;
+    let mut in_package = false;
+    for line in manifest.lines() {
+        let t = line.trim();
+        if t.starts_with('[') {
+            in_package = t == "[package]" || t == "[lib]";
+        } else if in_package {
+            if let Some(v) = t.strip_prefix("name").map(str::trim_start).and_then(|r| r.strip_prefix('=')) {
+                return Some(v.trim().trim_matches('"').replace('-', "_"));
+            }
+        }
+    }
+    None
+}
+
+fn rust_path(root: &Path, from_file: &Path, target: &Path, symbol: &str) -> Option<String> {
+    let target_crate = crate_dir(root, target)?;
+    let rel = target.strip_prefix(target_crate.join("src")).ok()?.with_extension("");
+    let mut modules: Vec<String> = rel.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+    if matches!(modules.last().map(String::as_str), Some("mod" | "lib" | "main")) {
+        modules.pop();
+    }
+    let prefix = if crate_dir(root, from_file).as_deref() == Some(target_crate.as_path()) {
+        "crate".to_string()
+    } else {
+        crate_name(&target_crate)?
+    };
+    let mut path = vec![prefix];
+    path.extend(modules);
+    path.push(symbol.to_string());
+    Some(path.join("::"))
+}
+
+// ── Statement formatting ──
+
+/// Quote and semicolon style of the first existing import, so new
+/// statements match the file.
+fn script_style(from_source: &str) -> (char, bool) {
+    from_source
+        .lines()
+        .map(str::trim)
+        .find(|l| l.starts_with("import "))
+        .map(|l| (if l.contains('"') { '"' } else { '\'' }, l.ends_with(';')))
+        .unwrap_or(('\'', true))
+}
+
+fn import_statement(
+    root: &Path,
+    from_file: &Path,
+    from_source: &str,
+    target: &Path,
+    lang: Lang,
+    kind: ExportKind,
+    symbol: &str,
+) -> Option<String> {
+    Some(match lang {
+        Lang::Script => {
+            let (q, semi) = script_style(from_source);
+            let spec = script_specifier(from_file, target);
+            let clause = match kind {
+                ExportKind::Default => symbol.to_string(),
+                ExportKind::TypeOnly => format!("type {{ {} }}", symbol),
+                ExportKind::Named => format!("{{ {} }}", symbol),
+            };
+            format!("import {} from {q}{}{q}{}\n", clause, spec, if semi { ";" } else { "" })
+        }
+        Lang::Python => format!("from {} import {}\n", python_module(root, target), symbol),
+        Lang::Rust => format!("use {};\n", rust_path(root, from_file, target, symbol)?),
+    })
+}
+
+/// 1-based line after the last top-of-file import, or after leading
+/// comments / docstrings / shebang when there are none.
+fn insertion_line(source: &str, lang: Lang) -> usize {
+    let is_import = |t: &str| match lang {
+        Lang::Script => t.starts_with("import ") || (t.starts_with("const ") && t.contains("require(")),
+        Lang::Python => t.starts_with("import ") || t.starts_with("from "),
+        Lang::Rust => t.starts_with("use ") || t.starts_with("pub use "),
+    };
+    let is_header = |t: &str| {
+        t.is_empty()
+            || t.starts_with("//")
+            || t.starts_with('#')
+            || t.starts_with("/*")
+            || t.starts_with('*')
+            || t.starts_with("\"use ")
+            || t.starts_with("'use ")
+    };
+    let closes_docstring = |t: &str| t.ends_with("\"\"\"") || t.ends_with("'''");
+
+    let mut last_import = None;
+    let mut header_end = 0;
+    let mut in_docstring = false;
+    for (i, line) in source.lines().enumerate() {
+        let t = line.trim();
+        if in_docstring {
+            header_end = i + 1;
+            in_docstring = !closes_docstring(t);
+        } else if is_import(t) {
+            last_import = Some(i + 1);
+        } else if last_import.is_some() {
+            // Blank lines and comments between imports don't end the block.
+            if !(t.is_empty() || t.starts_with("//") || t.starts_with('#')) {
+                break;
+            }
+        } else if is_header(t) {
+            header_end = i + 1;
+        } else if lang == Lang::Python && (t.starts_with("\"\"\"") || t.starts_with("'''")) {
+            header_end = i + 1;
+            in_docstring = t.len() < 6 || !closes_docstring(&t[3..]);
+        } else {
+            break;
+        }
+    }
+    last_import.unwrap_or(header_end) + 1
+}
+
+// ── Search ──
+
+/// Files that may export `symbol`: the knowledge index's entries for it
+/// once the project has been fully indexed, otherwise every file of the
+/// language found by a walk.
+fn candidates(root: &Path, lang: Lang, symbol: &str) -> Vec<PathBuf> {
+    if let Some(paths) = knowledge::files_exporting(&root.to_string_lossy(), symbol) {
+        return paths.into_iter().map(|p| root.join(p)).filter(|p| lang_of(p) == Some(lang)).collect();
+    }
+    ignore::WalkBuilder::new(root)
+        .hidden(true)
+        .build()
+        .flatten()
+        .take(MAX_SCAN_FILES)
+        .filter(|e| e.file_type().is_some_and(|t| t.is_file()) && lang_of(e.path()) == Some(lang))
+        .map(|e| e.into_path())
+        .collect()
+}
+
+fn suggest(root: &Path, from_file: &Path, symbol: &str) -> Result<Vec<ImportSuggestion>, String> {
+    let lang = lang_of(from_file).ok_or_else(|| "Import suggestions are not supported for this file type".to_string())?;
+    suggest_from(root, from_file, symbol, lang, candidates(root, lang, symbol))
+}
+
+fn suggest_from(
+    root: &Path,
+    from_file: &Path,
+    symbol: &str,
+    lang: Lang,
+    candidates: Vec<PathBuf>,
+) -> Result<Vec<ImportSuggestion>, String> {
+    let from_source = std::fs::read_to_string(from_file).unwrap_or_default();
+    let insert_line = insertion_line(&from_source, lang);
+
+    let mut found: Vec<(usize, ImportSuggestion)> = Vec::new();
+    for path in &candidates {
+        if path == from_file || std::fs::metadata(path).map(|m| m.len() > MAX_SCAN_BYTES).unwrap_or(true) {
+            continue;
+        }
+        let Ok(source) = std::fs::read_to_string(path) else { continue };
+        if !source.contains(symbol) {
+            continue;
+        }
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        let Some(kind) = export_kind(&source, ext, lang, symbol) else { continue };
+        let Some(statement) = import_statement(root, from_file, &from_source, path, lang, kind, symbol) else { continue };
+
+        let rel = path.strip_prefix(root).unwrap_or(path).to_string_lossy().to_string();
+        let is_test = rel.contains("test") || rel.contains("spec") || rel.contains("__mocks__");
+        let distance = relative_path(from_file.parent().unwrap_or(root), path).matches('/').count();
+        found.push((
+            distance + if is_test { 100 } else { 0 },
+            ImportSuggestion {
+                already_imported: from_source.contains(statement.trim_end()),
+                source_file: rel,
+                statement,
+                insert_line,
+            },
+        ));
+    }
+    found.sort_by(|(da, a), (db, b)| da.cmp(db).then(a.source_file.cmp(&b.source_file)));
+    Ok(found.into_iter().take(MAX_SUGGESTIONS).map(|(_, s)| s).collect())
+}
+
+/// Find files exporting `symbol` and return the import statement
+/// `from_file` would need for each, nearest files first.
+#[tauri::command]
+pub fn suggest_import(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    symbol: String,
+    from_file: String,
+) -> Result<Vec<ImportSuggestion>, String> {
+    let from = validate_path(&from_file, window.label(), &state)?;
+    let root = {
+        let map = state.blocking_read();
+        map.get(window.label()).and_then(|o| o.clone()).ok_or("No project is open")?
+    };
+    if symbol.is_empty() || !symbol.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$') {
+        return Err("Invalid symbol name".to_string());
+    }
+    suggest(&root, &from, &symbol)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, rel: &str, content: &str) {
+        let p = root.join(rel);
+        std::fs::create_dir_all(p.parent().unwrap()).unwrap();
+        std::fs::write(p, content).unwrap();
+    }
+
+    #[test]
+    fn script_imports_use_relative_specifiers_and_file_style() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "src/lib/api/index.ts", "export const fetchUser = async () => {};\n");
+        write(root, "src/lib/types.ts", "export interface User {\n  id: string;\n}\n");
+        write(root, "src/App.ts", "import { x } from \"./x\"\n\nfetchUser();\n");
+        let from = root.join("src/App.ts");
+
+        let s = suggest(root, &from, "fetchUser").unwrap();
+        assert_eq!(s[0].statement, "import { fetchUser } from \"./lib/api\"\n");
+        assert_eq!(s[0].insert_line, 2);
+
+        let s = suggest(root, &from, "User").unwrap();
+        assert_eq!(s[0].statement, "import type { User } from \"./lib/types\"\n");
+    }
+
+    #[test]
+    fn python_imports_use_package_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "src/app/__init__.py", "");
+        write(root, "src/app/util/__init__.py", "");
+        write(root, "src/app/util/retry.py", "MAX = 3\n\ndef retry_upload(f):\n    pass\n");
+        write(root, "src/app/main.py", "\"\"\"Entry point.\"\"\"\nimport os\n");
+        let from = root.join("src/app/main.py");
+        let s = suggest(root, &from, "retry_upload").unwrap();
+        assert_eq!(s[0].statement, "from app.util.retry import retry_upload\n");
+        assert_eq!(s[0].insert_line, 3);
+        assert_eq!(suggest(root, &from, "MAX").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn rust_imports_use_crate_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "Cargo.toml", "[package]\nname = \"my-app\"\n");
+        write(root, "src/modules/fs/mod.rs", "pub fn validate_path() {}\n");
+        write(root, "src/lib.rs", "//! Crate docs.\n\nmod modules;\n");
+        let s = suggest(root, &root.join("src/lib.rs"), "validate_path").unwrap();
+        assert_eq!(s[0].statement, "use crate::modules::fs::validate_path;\n");
+        assert_eq!(s[0].insert_line, 3);
+    }
+
+    #[test]
+    fn private_items_are_not_suggested() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "Cargo.toml", "[package]\nname = \"a\"\n");
+        write(root, "src/a.rs", "fn hidden() {}\n");
+        write(root, "src/lib.rs", "");
+        assert!(suggest(root, &root.join("src/lib.rs"), "hidden").unwrap().is_empty());
+    }
+
+    #[test]
+    fn only_indexed_candidates_are_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "src/a.ts", "export function load() {}\n");
+        write(root, "src/b.ts", "export function load() {}\n");
+        write(root, "src/main.ts", "load();\n");
+        let from = root.join("src/main.ts");
+        let s = suggest_from(root, &from, "load", Lang::Script, vec![root.join("src/b.ts")]).unwrap();
+        assert_eq!(s.len(), 1);
+        assert_eq!(s[0].statement, "import { load } from './b';\n");
+    }
+}
@@ -10,6 +10,9 @@ use tree_sitter::{Language, Parser, Node};
 
 use crate::modules::fs::ProjectRootState;
 
+pub mod imports;
+pub mod organize;
+
 // ── Types ──
 
 #[derive(Serialize, Clone, Debug)]
@@ -23,7 +26,7 @@ pub struct Symbol {
 
 // ── Language detection ──
 
-fn language_for_extension(ext: &str) -> Option<Language> {
+pub(crate) fn language_for_extension(ext: &str) -> Option<Language> {
     match ext {
         "js" | "jsx" | "mjs" | "cjs" => Some(tree_sitter_javascript::LANGUAGE.into()),
         "ts" | "tsx" | "mts" | "cts" => Some(tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()),
@@ -0,0 +1,476 @@
+//! Sort and dedupe the import block at the top of a file.
+//!
+//! The block is the first run of top-level import statements found by
+//! tree-sitter; anything else — a comment on its own line, an attribute,
+//! code — ends it, so only the statements that are safe to reorder move.
+//! Side-effect imports in JS/TS (`import './polyfill'`) stay where they
+//! are and the imports on either side are sorted independently.
+//!
+//! By default nothing is written: the command returns line edits and the
+//! editor applies them like any other change. With `apply`, the edits for
+//! every file go through one workspace edit instead, so a project-wide
+//! cleanup changes all files or none, each file's old content is kept in
+//! the local history, and the whole cleanup can be undone at once.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use tree_sitter::{Node, Parser};
+
+use super::imports::{lang_of, Lang};
+use super::language_for_extension;
+use crate::modules::fs::workspace_edit::{apply_edit, FileOperation, Position, Range, TextEdit, WorkspaceEdit};
+use crate::modules::fs::{validate_path, ProjectRootState};
+
+const MAX_SCAN_FILES: usize = 20_000;
+const MAX_FILE_BYTES: u64 = 512 * 1024;
+
+/// Top-level modules treated as the Python standard library when grouping.
+const PYTHON_STDLIB: &[&str] = &[
+    "abc", "argparse", "array", "ast", "asyncio", "base64", "bisect", "builtins", "calendar",
+    "collections", "concurrent", "contextlib", "copy", "csv", "ctypes", "dataclasses", "datetime",
+    "decimal", "difflib", "email", "enum", "errno", "fnmatch", "fractions", "functools", "gc",
+    "getpass", "glob", "gzip", "hashlib", "heapq", "hmac", "html", "http", "importlib", "inspect",
+    "io", "ipaddress", "itertools", "json", "logging", "math", "mimetypes", "multiprocessing",
+    "operator", "os", "pathlib", "pickle", "platform", "pprint", "queue", "random", "re",
+    "secrets", "select", "shlex", "shutil", "signal", "socket", "sqlite3", "ssl", "stat",
+    "statistics", "string", "struct", "subprocess", "sys", "tempfile", "textwrap", "threading",
+    "time", "timeit", "tomllib", "traceback", "types", "typing", "unittest", "urllib", "uuid",
+    "warnings", "weakref", "xml", "zipfile", "zlib",
+];
+
+// ── Types ──
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ImportStyle {
+    /// Regroup imports by origin (standard library, external, local),
+    /// separated by blank lines. When off, existing blank-line groups are
+    /// kept and sorted individually.
+    pub group: bool,
+    pub case_sensitive: bool,
+    /// Also sort names inside single-line `{ a, b }` / `import a, b` lists.
+    pub sort_specifiers: bool,
+}
+
+impl Default for ImportStyle {
+    fn default() -> Self {
+        Self { group: true, case_sensitive: false, sort_specifiers: true }
+    }
+}
+
+/// Replace lines `start_line..=end_line` (1-based) of `path` with `text`.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ImportEdit {
+    pub path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    /// Replacement text, without a trailing line break.
+    pub text: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct OrganizeResult {
+    /// One edit per file whose imports change.
+    pub edits: Vec<ImportEdit>,
+    pub files_scanned: usize,
+    /// Whether the edits were written.
+    pub applied: bool,
+}
+
+struct Entry {
+    text: String,
+    category: u8,
+    key: String,
+    /// Side-effect import that must keep its position.
+    barrier: bool,
+    /// Index of the blank-line separated group it came from.
+    group: usize,
+    blank_before: bool,
+}
+
+// ── Classification ──
+
+fn is_import(node: Node, lang: Lang) -> bool {
+    match lang {
+        Lang::Script => node.kind() == "import_statement",
+        Lang::Python => matches!(node.kind(), "import_statement" | "import_from_statement" | "future_import_statement"),
+        Lang::Rust => node.kind() == "use_declaration",
+    }
+}
+
+fn is_comment(node: Node) -> bool {
+    matches!(node.kind(), "comment" | "line_comment" | "block_comment")
+}
+
+fn node_text<'a>(node: Node, source: &'a str) -> &'a str {
+    &source[node.byte_range()]
+}
+
+/// `(category, sort key, barrier)` for an import node.
+fn classify(node: Node, source: &str, lang: Lang) -> (u8, String, bool) {
+    match lang {
+        Lang::Script => {
+            let Some(spec) = node.child_by_field_name("source") else {
+                // `import x = require(...)` and friends: leave in place.
+                return (0, String::new(), true);
+            };
+            let spec = node_text(spec, source).trim_matches(|c| c == '\'' || c == '"' || c == '`').to_string();
+            let mut cursor = node.walk();
+            let has_clause = node.named_children(&mut cursor).any(|c| c.kind() == "import_clause");
+            let category = if spec.starts_with("node:") {
+                0
+            } else if spec.starts_with('.') {
+                3
+            } else if ["$", "@/", "~/", "#"].iter().any(|p| spec.starts_with(p)) {
+                2
+            } else {
+                1
+            };
+            (category, spec, !has_clause)
+        }
+        Lang::Python => {
+            if node.kind() == "future_import_statement" {
+                return (0, "__future__".to_string(), false);
+            }
+            let module = match node.kind() {
+                "import_from_statement" => node.child_by_field_name("module_name").map(|n| node_text(n, source)),
+                _ => node.child_by_field_name("name").map(|n| node_text(n, source)),
+            }
+            .unwrap_or_default()
+            .to_string();
+            let top = module.split('.').next().unwrap_or_default();
+            let category = if module.starts_with('.') {
+                3
+            } else if PYTHON_STDLIB.contains(&top) {
+                1
+            } else {
+                2
+            };
+            // Plain `import x` sorts ahead of `from x import y`, as isort does.
+            let key = format!("{}{}", if node.kind() == "import_statement" { 0 } else { 1 }, module);
+            (category, key, false)
+        }
+        Lang::Rust => {
+            let path = node.child_by_field_name("argument").map(|n| node_text(n, source)).unwrap_or_default();
+            let path = path.trim_start_matches("::").to_string();
+            let first = path.split("::").next().unwrap_or_default();
+            let category = match first {
+                "std" | "core" | "alloc" => 0,
+                "crate" | "self" | "super" => 2,
+                _ => 1,
+            };
+            (category, path, false)
+        }
+    }
+}
+
+// ── Specifier lists ──
+
+fn sort_items(items: &str, lang: Lang) -> String {
+    let mut seen = HashSet::new();
+    let mut list: Vec<&str> = items.split(',').map(str::trim).filter(|s| !s.is_empty() && seen.insert(*s)).collect();
+    let key = |s: &str| -> (bool, String) {
+        let name = s.strip_prefix("type ").unwrap_or(s);
+        let name = name.split(" as ").next().unwrap_or(name).trim();
+        (!(lang == Lang::Rust && name == "self"), name.to_lowercase())
+    };
+    list.sort_by_key(|s| key(s));
+    list.join(", ")
+}
+
+/// Sort the names of a single-line import, leaving anything else alone.
+fn sort_specifiers(text: &str, lang: Lang) -> String {
+    if text.contains('\n') || text.contains("//") || text.contains('#') || text.contains("/*") {
+        return text.to_string();
+    }
+    match lang {
+        Lang::Script | Lang::Rust => {
+            if text.matches('{').count() != 1 || text.matches('}').count() != 1 {
+                return text.to_string();
+            }
+            let (Some(open), Some(close)) = (text.find('{'), text.find('}')) else { return text.to_string() };
+            if close < open {
+                return text.to_string();
+            }
+            let inner = &text[open + 1..close];
+            let sorted = sort_items(inner, lang);
+            let pad = if inner.starts_with(' ') { " " } else { "" };
+            format!("{}{{{}{}{}}}{}", &text[..open], pad, sorted, pad, &text[close + 1..])
+        }
+        Lang::Python => {
+            let Some(idx) = text.find(" import ") else { return text.to_string() };
+            let names = &text[idx + 8..];
+            if !text.starts_with("from ") || names.contains('(') || names.trim() == "*" {
+                return text.to_string();
+            }
+            format!("{} import {}", &text[..idx], sort_items(names, lang))
+        }
+    }
+}
+
+// ── Block detection and rendering ──
+
+/// Find the first import block: `(start_row, end_row, entries)`.
+fn import_block(root: Node, source: &str, lang: Lang, style: &ImportStyle) -> Option<(usize, usize, Vec<Entry>)> {
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut start_row = None;
+    let mut end_row = 0;
+    let mut end_byte = 0;
+    let mut group = 0;
+
+    let mut cursor = root.walk();
+    for node in root.named_children(&mut cursor) {
+        if is_import(node, lang) {
+            if node.has_error() {
+                return None;
+            }
+            let row = node.start_position().row;
+            let blank_before = start_row.is_some() && row > end_row + 1;
+            if start_row.is_none() {
+                if node.start_position().column != 0 {
+                    return None;
+                }
+                start_row = Some(row);
+            } else if blank_before {
+                group += 1;
+            }
+            let (category, key, barrier) = classify(node, source, lang);
+            let raw = node_text(node, source);
+            let text = if style.sort_specifiers { sort_specifiers(raw, lang) } else { raw.to_string() };
+            entries.push(Entry { text, category, key, barrier, group, blank_before });
+            end_row = node.end_position().row;
+            end_byte = node.end_byte();
+        } else if start_row.is_some() {
+            // A trailing comment on the import's own line travels with it.
+            if is_comment(node) && node.start_position().row == end_row && !node_text(node, source).contains('\n') {
+                let last = entries.last_mut()?;
+                last.text.push_str(&source[end_byte..node.end_byte()]);
+                end_byte = node.end_byte();
+                continue;
+            }
+            break;
+        }
+    }
+
+    let start_row = start_row?;
+    let rest_of_line = source[end_byte..].split('\n').next().unwrap_or_default();
+    if !rest_of_line.trim().is_empty() {
+        return None;
+    }
+    Some((start_row, end_row, entries))
+}
+
+fn render(entries: Vec<Entry>, style: &ImportStyle, line_break: &str) -> String {
+    let normalize = |s: &str| s.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut seen = HashSet::new();
+    let entries: Vec<Entry> = entries.into_iter().filter(|e| seen.insert(normalize(&e.text))).collect();
+
+    let sort_key = |e: &Entry| {
+        let key = if style.case_sensitive { e.key.clone() } else { e.key.to_lowercase() };
+        (e.category, key, e.text.clone())
+    };
+    let render_segment = |segment: Vec<&Entry>| -> String {
+        let mut buckets: BTreeMap<usize, Vec<&Entry>> = BTreeMap::new();
+        for e in segment {
+            let bucket = if style.group { e.category as usize } else { e.group };
+            buckets.entry(bucket).or_default().push(e);
+        }
+        buckets
+            .into_values()
+            .map(|mut list| {
+                list.sort_by_key(|e| sort_key(e));
+                list.iter().map(|e| e.text.as_str()).collect::<Vec<_>>().join(line_break)
+            })
+            .collect::<Vec<_>>()
+            .join(&format!("{0}{0}", line_break))
+    };
+
+    // (blank line before, text) for each piece between barriers.
+    let mut pieces: Vec<(bool, String)> = Vec::new();
+    let mut segment: Vec<&Entry> = Vec::new();
+    for e in &entries {
+        if e.barrier {
+            if let Some(first) = segment.first() {
+                pieces.push((first.blank_before, render_segment(std::mem::take(&mut segment))));
+            }
+            pieces.push((e.blank_before, e.text.clone()));
+        } else {
+            segment.push(e);
+        }
+    }
+    if let Some(first) = segment.first() {
+        pieces.push((first.blank_before, render_segment(segment)));
+    }
+
+    let mut out = String::new();
+    for (i, (blank_before, text)) in pieces.into_iter().enumerate() {
+        if i > 0 {
+            out.push_str(line_break);
+            if blank_before {
+                out.push_str(line_break);
+            }
+        }
+        out.push_str(&text);
+    }
+    out
+}
+
+/// Organize the imports of one file; `None` when nothing changes or the
+/// block can't be handled safely.
+fn organize_source(source: &str, ext: &str, lang: Lang, style: &ImportStyle) -> Option<(usize, usize, String)> {
+    let language = language_for_extension(ext)?;
+    let mut parser = Parser::new();
+    parser.set_language(&language).ok()?;
+    let tree = parser.parse(source, None)?;
+
+    let (start_row, end_row, entries) = import_block(tree.root_node(), source, lang, style)?;
+    let line_break = if source.contains("\r\n") { "\r\n" } else { "\n" };
+    let text = render(entries, style, line_break);
+    let old = source.lines().skip(start_row).take(end_row - start_row + 1).collect::<Vec<_>>().join(line_break);
+    (text != old).then_some((start_row + 1, end_row + 1, text))
+}
+
+/// Lines `start_line..=end_line` (1-based) of `source` replaced with
+/// `text`, as a workspace text edit. The last line's break stays.
+fn line_edit(source: &str, start_line: usize, end_line: usize, text: &str) -> TextEdit {
+    let last_start: usize = source.split_inclusive('\n').take(end_line - 1).map(str::len).sum();
+    let last_len = source[last_start..].lines().next().map_or(0, str::len);
+    TextEdit {
+        range: Range {
+            start: Position { line: start_line as u32 - 1, character: 0 },
+            end: Position::at(source, last_start + last_len),
+        },
+        new_text: text.to_string(),
+    }
+}
+
+fn organize_file(path: &Path, style: &ImportStyle) -> Option<(ImportEdit, TextEdit)> {
+    let lang = lang_of(path)?;
+    let ext = path.extension().and_then(|e| e.to_str())?;
+    let source = std::fs::read_to_string(path).ok()?;
+    let (start_line, end_line, text) = organize_source(&source, ext, lang, style)?;
+    let text_edit = line_edit(&source, start_line, end_line, &text);
+    Some((ImportEdit { path: path.to_string_lossy().to_string(), start_line, end_line, text }, text_edit))
+}
+
+/// Write `edits` as one workspace edit for `window_label`'s project.
+fn apply_all(root: &Path, window_label: &str, edits: Vec<(ImportEdit, TextEdit)>) -> Result<Vec<ImportEdit>, String> {
+    let mut operations = Vec::new();
+    let mut import_edits = Vec::new();
+    for (edit, text_edit) in edits {
+        operations.push(FileOperation::Edit { path: edit.path.clone(), edits: vec![text_edit], expected_hash: None });
+        import_edits.push(edit);
+    }
+    apply_edit(root, window_label, &WorkspaceEdit { operations }, false)?;
+    Ok(import_edits)
+}
+
+// ── Tauri command ──
+
+/// Sort and dedupe imports in `path` — a file, a directory, or the whole
+/// project when omitted — and return the edits, writing them too when
+/// `apply` is set. Directories are walked with gitignore rules applied.
+#[tauri::command]
+pub fn organize_imports(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, ProjectRootState>,
+    path: Option<String>,
+    style: Option<ImportStyle>,
+    apply: Option<bool>,
+) -> Result<OrganizeResult, String> {
+    let style = style.unwrap_or_default();
+    let apply = apply.unwrap_or(false);
+    let root = state.blocking_read().get(window.label()).and_then(|o| o.clone()).ok_or("No project is open")?;
+    let target = match path {
+        Some(p) => validate_path(&p, window.label(), &state)?,
+        None => root.clone(),
+    };
+    let finish = |edits: Vec<(ImportEdit, TextEdit)>, files_scanned: usize| -> Result<OrganizeResult, String> {
+        let edits = if apply { apply_all(&root, window.label(), edits)? } else { edits.into_iter().map(|(e, _)| e).collect() };
+        Ok(OrganizeResult { edits, files_scanned, applied: apply })
+    };
+
+    if target.is_file() {
+        lang_of(&target).ok_or_else(|| "Organizing imports is not supported for this file type".to_string())?;
+        return finish(organize_file(&target, &style).into_iter().collect(), 1);
+    }
+
+    let mut edits = Vec::new();
+    let mut files_scanned = 0;
+    let walker = ignore::WalkBuilder::new(&target).hidden(true).build();
+    for entry in walker.flatten().take(MAX_SCAN_FILES) {
+        let path = entry.path();
+        if lang_of(path).is_none() || !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        if entry.metadata().map(|m| m.len() > MAX_FILE_BYTES).unwrap_or(true) {
+            continue;
+        }
+        files_scanned += 1;
+        edits.extend(organize_file(path, &style));
+    }
+    edits.sort_by(|a, b| a.0.path.cmp(&b.0.path));
+    finish(edits, files_scanned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn organize(source: &str, ext: &str) -> Option<(usize, usize, String)> {
+        let lang = lang_of(Path::new(&format!("f.{}", ext))).unwrap();
+        organize_source(source, ext, lang, &ImportStyle::default())
+    }
+
+    #[test]
+    fn script_imports_are_grouped_sorted_and_deduped() {
+        let src = "import { b, a } from './local';\nimport fs from 'node:fs';\nimport React from 'react';\nimport { a, b } from './local';\n\nconsole.log(1);\n";
+        let (start, end, text) = organize(src, "ts").unwrap();
+        assert_eq!((start, end), (1, 4));
+        assert_eq!(text, "import fs from 'node:fs';\n\nimport React from 'react';\n\nimport { a, b } from './local';");
+    }
+
+    #[test]
+    fn line_edits_cover_whole_lines_but_the_last_break() {
+        let src = "// ü\r\nimport b from 'b';\r\nimport a from 'a';\r\n\r\nrun();\r\n";
+        let edit = line_edit(src, 2, 3, "import a from 'a';\r\nimport b from 'b';");
+        assert_eq!(edit.range.start, Position { line: 1, character: 0 });
+        assert_eq!(edit.range.end, Position { line: 2, character: 18 });
+    }
+
+    #[test]
+    fn side_effect_imports_keep_their_place() {
+        let src = "import z from 'z';\nimport './setup';\nimport b from 'b';\nimport a from 'a';\n";
+        let (_, _, text) = organize(src, "js").unwrap();
+        assert_eq!(text, "import z from 'z';\nimport './setup';\nimport a from 'a';\nimport b from 'b';");
+    }
+
+    #[test]
+    fn python_future_first_relative_last() {
+        let src = "\"\"\"Doc.\"\"\"\nfrom .util import x\nimport requests\nimport os\nfrom __future__ import annotations\nfrom typing import Optional, Any\n\nx = 1\n";
+        let (start, _, text) = organize(src, "py").unwrap();
+        assert_eq!(start, 2);
+        assert_eq!(
+            text,
+            "from __future__ import annotations\n\nimport os\nfrom typing import Any, Optional\n\nimport requests\n\nfrom .util import x"
+        );
+    }
+
+    #[test]
+    fn rust_uses_group_std_external_crate() {
+        let src = "use crate::modules::fs::{validate_path, ProjectRootState};\nuse serde::Serialize; // derive\nuse std::path::{PathBuf, Path};\n\nfn main() {}\n";
+        let (_, _, text) = organize(src, "rs").unwrap();
+        assert_eq!(
+            text,
+            "use std::path::{Path, PathBuf};\n\nuse serde::Serialize; // derive\n\nuse crate::modules::fs::{ProjectRootState, validate_path};"
+        );
+    }
+
+    #[test]
+    fn sorted_files_and_unsafe_blocks_are_left_alone() {
+        assert!(organize("use std::fs;\n\nuse serde::Serialize;\n", "rs").is_none());
+        // Code sharing the last import's line makes the block unsafe to rewrite.
+        assert!(organize("import b from 'b'; import a from 'a'; run();\n", "js").is_none());
+    }
+}
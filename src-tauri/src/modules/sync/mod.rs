@@ -0,0 +1,400 @@
+//! Settings sync between machines.
+//!
+//! Preferences (the frontend's `leo-*` settings, keybindings included) and
+//! the user-level files in `~/.leo-ide` that aren't tied to one machine —
+//! terminal, issue tracker and diff sharing settings, and the `snippets/`
+//! and `templates/` folders — travel as one bundle to a backend the user
+//! picks: a secret GitHub gist, an S3-compatible bucket or a file on a
+//! WebDAV server. The bundle is encrypted before it leaves the machine,
+//! with a key derived (Argon2id) from a passphrase set on every machine, so
+//! the backend only ever sees ciphertext. Credentials and the passphrase
+//! live in the same secure key storage as AI provider keys.
+//!
+//! `sync_now` merges three ways against the bundle of the last sync: an
+//! item changed on one side only takes that side's version, and one changed
+//! on both keeps this machine's. A machine that never synced takes the
+//! stored bundle's version of everything in it, so a new machine gets the
+//! same setup in one step.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Component, Path, PathBuf};
+
+use crate::modules::ai::{self, key_store};
+use crate::modules::fs::write_atomic;
+use crate::modules::policy::Category;
+
+mod backends;
+
+/// Policy categories of this module's commands.
+pub(crate) const COMMAND_CATEGORIES: &[(&str, &[Category])] = &[("sync_now", &[Category::Network])];
+
+const SECRET_NAME: &str = "sync-secret";
+const PASSPHRASE_NAME: &str = "sync-passphrase";
+/// Files in `~/.leo-ide` that are synced.
+const SYNCED_FILES: &[&str] = &["terminal.json", "issues.json", "diff-share.json"];
+/// Folders in `~/.leo-ide` synced with everything in them.
+const SYNCED_DIRS: &[&str] = &["snippets", "templates"];
+/// Preferences that only make sense on this machine.
+const LOCAL_PREFS: &[&str] = &["leo-api-key", "leo-project-root"];
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+const MAX_DEPTH: usize = 8;
+/// Start of an encrypted bundle; followed by the key derivation salt and
+/// a `key_store::encrypt` blob.
+const MAGIC: &[u8] = b"LEOSYNC1";
+const SALT_LEN: usize = 16;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum SyncTarget {
+    /// A secret gist, created on the first push. The token needs the
+    /// `gist` scope.
+    Gist {
+        #[serde(default)]
+        gist_id: Option<String>,
+    },
+    /// An object in a bucket on S3 or a compatible service (MinIO, R2, B2),
+    /// addressed path-style. The secret is the secret access key.
+    S3 {
+        /// e.g. `https://s3.eu-west-1.amazonaws.com`.
+        endpoint: String,
+        bucket: String,
+        region: String,
+        access_key_id: String,
+        /// Object key; defaults to `leo-ide-sync.bin`.
+        #[serde(default)]
+        key: Option<String>,
+    },
+    /// A file on a WebDAV server, e.g. `https://dav.example.com/leo/sync.bin`.
+    /// The secret is the password, or a bearer token without a username.
+    Webdav {
+        url: String,
+        #[serde(default)]
+        username: Option<String>,
+    },
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct SyncResult {
+    /// Preferences after the sync, for the frontend to apply.
+    pub prefs: BTreeMap<String, String>,
+    /// Items taken from the stored bundle (`pref:<key>` or `file:<path>`).
+    pub pulled: Vec<String>,
+    /// Whether this machine's changes were uploaded.
+    pub pushed: bool,
+    /// Items changed here and elsewhere since the last sync; this
+    /// machine's version was kept.
+    pub conflicts: Vec<String>,
+}
+
+/// Everything that is synced, keyed `pref:<key>` or `file:<path>` with
+/// `path` relative to `~/.leo-ide`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+struct Bundle {
+    items: BTreeMap<String, String>,
+}
+
+// ── Settings ──
+
+fn config_dir() -> PathBuf {
+    dirs::home_dir().unwrap_or_default().join(".leo-ide")
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Option<T> {
+    std::fs::read_to_string(path).ok().and_then(|s| serde_json::from_str(&s).ok())
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to save sync settings: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    write_atomic(path, json.as_bytes()).map_err(|e| format!("Failed to save sync settings: {}", e))
+}
+
+fn target_path() -> PathBuf {
+    config_dir().join("sync.json")
+}
+
+/// The bundle as of the last sync, the base of the next merge.
+fn base_path() -> PathBuf {
+    config_dir().join("sync-base.json")
+}
+
+fn check_url(url: &str, what: &str) -> Result<(), String> {
+    match reqwest::Url::parse(url) {
+        Ok(u) if matches!(u.scheme(), "http" | "https") => Ok(()),
+        _ => Err(format!("Invalid {}: {}", what, url)),
+    }
+}
+
+// ── Bundles ──
+
+/// Whether `rel` is a path sync may write: one of the synced files, or
+/// inside a synced folder, without `..`.
+fn is_synced_path(rel: &str) -> bool {
+    let path = Path::new(rel);
+    if !path.components().all(|c| matches!(c, Component::Normal(_))) {
+        return false;
+    }
+    SYNCED_FILES.contains(&rel) || SYNCED_DIRS.iter().any(|d| path.starts_with(d) && path != Path::new(d))
+}
+
+fn collect_dir(dir: &Path, rel: &str, depth: usize, items: &mut BTreeMap<String, String>) {
+    if depth > MAX_DEPTH {
+        return;
+    }
+    for entry in std::fs::read_dir(dir).into_iter().flatten().flatten() {
+        let Ok(file_type) = entry.file_type() else { continue };
+        let rel = format!("{}/{}", rel, entry.file_name().to_string_lossy());
+        if file_type.is_dir() {
+            collect_dir(&entry.path(), &rel, depth + 1, items);
+        } else if file_type.is_file() && entry.metadata().is_ok_and(|m| m.len() <= MAX_FILE_BYTES) {
+            if let Ok(content) = std::fs::read_to_string(entry.path()) {
+                items.insert(format!("file:{}", rel), content);
+            }
+        }
+    }
+}
+
+/// This machine's side: `prefs` from the frontend and the synced files
+/// under `dir`.
+fn local_bundle(dir: &Path, prefs: &BTreeMap<String, String>) -> Bundle {
+    let mut items: BTreeMap<String, String> = prefs
+        .iter()
+        .filter(|(k, _)| k.starts_with("leo-") && !LOCAL_PREFS.contains(&k.as_str()))
+        .map(|(k, v)| (format!("pref:{}", k), v.clone()))
+        .collect();
+    for file in SYNCED_FILES {
+        if let Ok(content) = std::fs::read_to_string(dir.join(file)) {
+            items.insert(format!("file:{}", file), content);
+        }
+    }
+    for sub in SYNCED_DIRS {
+        collect_dir(&dir.join(sub), sub, 0, &mut items);
+    }
+    Bundle { items }
+}
+
+struct Merge {
+    bundle: Bundle,
+    pulled: Vec<String>,
+    conflicts: Vec<String>,
+}
+
+/// Merge `local` and `remote` against `base`, the bundle both had at the
+/// last sync. Without a base this machine never synced, and whatever
+/// `remote` holds wins.
+fn merge(base: Option<&Bundle>, local: &Bundle, remote: Option<&Bundle>) -> Merge {
+    let Some(remote) = remote else {
+        return Merge { bundle: local.clone(), pulled: Vec::new(), conflicts: Vec::new() };
+    };
+    let (mut items, mut pulled, mut conflicts) = (BTreeMap::new(), Vec::new(), Vec::new());
+    let keys: BTreeSet<&String> = local.items.keys().chain(remote.items.keys()).collect();
+    for key in keys {
+        let (l, r) = (local.items.get(key), remote.items.get(key));
+        let take_remote = match base {
+            _ if l == r => false,
+            None => r.is_some(),
+            Some(base) => {
+                let b = base.items.get(key);
+                if l == b {
+                    true
+                } else {
+                    if r != b {
+                        conflicts.push(key.clone());
+                    }
+                    false
+                }
+            }
+        };
+        if take_remote {
+            pulled.push(key.clone());
+        }
+        if let Some(value) = if take_remote { r } else { l } {
+            items.insert(key.clone(), value.clone());
+        }
+    }
+    Merge { bundle: Bundle { items }, pulled, conflicts }
+}
+
+/// Bring the synced files under `dir` from `local` to `merged`.
+fn apply_files(dir: &Path, local: &Bundle, merged: &Bundle) -> Result<(), String> {
+    let files = |b: &Bundle| -> BTreeMap<String, String> {
+        b.items.iter().filter_map(|(k, v)| Some((k.strip_prefix("file:")?.to_string(), v.clone()))).collect()
+    };
+    let (before, after) = (files(local), files(merged));
+    for (rel, content) in &after {
+        if before.get(rel) == Some(content) {
+            continue;
+        }
+        if !is_synced_path(rel) {
+            log::warn!("settings sync skipped unexpected file {}", rel);
+            continue;
+        }
+        let path = dir.join(rel);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("Failed to write {}: {}", rel, e.kind()))?;
+        }
+        write_atomic(&path, content.as_bytes())?;
+    }
+    for rel in before.keys().filter(|rel| !after.contains_key(*rel)) {
+        let _ = std::fs::remove_file(dir.join(rel));
+    }
+    Ok(())
+}
+
+// ── Encryption ──
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; key_store::KEY_SIZE], String> {
+    let mut key = [0u8; key_store::KEY_SIZE];
+    argon2::Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Failed to derive the sync key: {}", e))?;
+    Ok(key)
+}
+
+fn seal(bundle: &Bundle, passphrase: &str) -> Result<Vec<u8>, String> {
+    use rand::RngCore;
+    let mut salt = [0u8; SALT_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    let json = serde_json::to_vec(bundle).map_err(|e| e.to_string())?;
+    let sealed = key_store::encrypt(&derive_key(passphrase, &salt)?, &json)?;
+    Ok([MAGIC, &salt, &sealed].concat())
+}
+
+fn unseal(bytes: &[u8], passphrase: &str) -> Result<Bundle, String> {
+    let rest = bytes.strip_prefix(MAGIC).filter(|r| r.len() > SALT_LEN).ok_or("The stored data isn't a settings sync bundle")?;
+    let (salt, sealed) = rest.split_at(SALT_LEN);
+    let json = key_store::decrypt(&derive_key(passphrase, salt)?, sealed)
+        .map_err(|_| "Couldn't decrypt the synced settings; check the sync passphrase".to_string())?;
+    serde_json::from_slice(&json).map_err(|e| format!("Damaged sync bundle: {}", e))
+}
+
+// ── Commands ──
+
+#[tauri::command]
+pub fn get_sync_target() -> Option<SyncTarget> {
+    read_json(&target_path())
+}
+
+/// Set where settings sync to, or turn sync off with no `target`. `secret`
+/// (the token, secret key or password) and `passphrase` replace the stored
+/// ones when given. Switching targets starts over as if this machine never
+/// synced.
+#[tauri::command]
+pub fn set_sync_target(target: Option<SyncTarget>, secret: Option<String>, passphrase: Option<String>) -> Result<(), String> {
+    let Some(target) = target else {
+        let _ = std::fs::remove_file(target_path());
+        let _ = std::fs::remove_file(base_path());
+        return Ok(());
+    };
+    match &target {
+        SyncTarget::Gist { .. } => {}
+        SyncTarget::S3 { endpoint, bucket, region, access_key_id, .. } => {
+            check_url(endpoint, "S3 endpoint")?;
+            if bucket.is_empty() || region.is_empty() || access_key_id.is_empty() {
+                return Err("S3 sync needs a bucket, a region and an access key id".to_string());
+            }
+        }
+        SyncTarget::Webdav { url, .. } => check_url(url, "WebDAV URL")?,
+    }
+    if let Some(secret) = secret {
+        ai::set_key(SECRET_NAME, secret.trim())?;
+    }
+    if let Some(passphrase) = passphrase {
+        ai::set_key(PASSPHRASE_NAME, &passphrase)?;
+    }
+    if get_sync_target().as_ref() != Some(&target) {
+        let _ = std::fs::remove_file(base_path());
+    }
+    write_json(&target_path(), &target)
+}
+
+/// Merge this machine's settings with the stored ones, write back the
+/// synced files that changed and upload the result if it differs from
+/// what is stored. `prefs` are the frontend's `leo-*` settings; the merged
+/// ones come back for it to apply.
+#[tauri::command]
+pub async fn sync_now(prefs: BTreeMap<String, String>) -> Result<SyncResult, String> {
+    let target = get_sync_target().ok_or("Settings sync is not set up")?;
+    let secret = ai::get_key(SECRET_NAME)?.ok_or("No sync credentials are set")?;
+    let passphrase = ai::get_key(PASSPHRASE_NAME)?.ok_or("No sync passphrase is set")?;
+    let dir = config_dir();
+
+    let local = local_bundle(&dir, &prefs);
+    let remote = match backends::fetch(&target, &secret).await? {
+        Some(bytes) => Some(unseal(&bytes, &passphrase)?),
+        None => None,
+    };
+    let base: Option<Bundle> = read_json(&base_path());
+    let Merge { bundle, pulled, conflicts } = merge(base.as_ref(), &local, remote.as_ref());
+    apply_files(&dir, &local, &bundle)?;
+
+    let pushed = remote.as_ref() != Some(&bundle);
+    if pushed {
+        let saved = backends::put(&target, &secret, &seal(&bundle, &passphrase)?).await?;
+        if saved != target {
+            write_json(&target_path(), &saved)?;
+        }
+    }
+    write_json(&base_path(), &bundle)?;
+    let prefs = bundle.items.iter().filter_map(|(k, v)| Some((k.strip_prefix("pref:")?.to_string(), v.clone()))).collect();
+    Ok(SyncResult { prefs, pulled, pushed, conflicts })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundle(items: &[(&str, &str)]) -> Bundle {
+        Bundle { items: items.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect() }
+    }
+
+    #[test]
+    fn merges_three_ways_and_round_trips_encrypted() {
+        let base = bundle(&[("pref:leo-theme", "dark"), ("pref:leo-tab", "2"), ("pref:leo-wrap", "on"), ("file:terminal.json", "{}")]);
+        let local = bundle(&[("pref:leo-theme", "light"), ("pref:leo-tab", "2"), ("pref:leo-wrap", "off"), ("pref:leo-vim", "true")]);
+        let remote = bundle(&[("pref:leo-theme", "dark"), ("pref:leo-tab", "4"), ("pref:leo-wrap", "auto"), ("file:terminal.json", "{}")]);
+        let merged = merge(Some(&base), &local, Some(&remote));
+        // Theme changed here, tab size there, wrap on both sides; vim was
+        // added here and terminal.json deleted here.
+        assert_eq!(merged.bundle, bundle(&[("pref:leo-theme", "light"), ("pref:leo-tab", "4"), ("pref:leo-wrap", "off"), ("pref:leo-vim", "true")]));
+        assert_eq!(merged.pulled, ["pref:leo-tab"]);
+        assert_eq!(merged.conflicts, ["pref:leo-wrap"]);
+
+        // A machine that never synced takes the stored setup but keeps
+        // what it alone has.
+        let fresh = merge(None, &bundle(&[("pref:leo-theme", "light"), ("pref:leo-font", "14")]), Some(&remote));
+        assert_eq!(fresh.bundle.items["pref:leo-theme"], "dark");
+        assert_eq!(fresh.bundle.items["pref:leo-font"], "14");
+        assert!(fresh.conflicts.is_empty());
+        assert_eq!(merge(Some(&base), &local, None).bundle, local);
+
+        let sealed = seal(&remote, "correct horse").unwrap();
+        assert!(!String::from_utf8_lossy(&sealed).contains("leo-theme"));
+        assert_eq!(unseal(&sealed, "correct horse").unwrap(), remote);
+        assert!(unseal(&sealed, "wrong horse").unwrap_err().contains("passphrase"));
+    }
+
+    #[test]
+    fn collects_and_writes_synced_files_only() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("snippets/rust")).unwrap();
+        std::fs::write(dir.path().join("snippets/rust/main.json"), "[]").unwrap();
+        std::fs::write(dir.path().join("terminal.json"), "{}").unwrap();
+        std::fs::write(dir.path().join("network.json"), "{\"proxy\":1}").unwrap();
+        let prefs = BTreeMap::from([("leo-editor-theme".to_string(), "nord".to_string()), ("leo-project-root".to_string(), "/x".to_string())]);
+
+        let local = local_bundle(dir.path(), &prefs);
+        assert_eq!(local.items.keys().collect::<Vec<_>>(), ["file:snippets/rust/main.json", "file:terminal.json", "pref:leo-editor-theme"]);
+
+        let merged = bundle(&[("file:snippets/go.json", "{}"), ("file:terminal.json", "{\"a\":1}"), ("file:../evil", "x"), ("file:network.json", "x")]);
+        apply_files(dir.path(), &local, &merged).unwrap();
+        assert!(!dir.path().join("snippets/rust/main.json").exists());
+        assert_eq!(std::fs::read_to_string(dir.path().join("snippets/go.json")).unwrap(), "{}");
+        assert_eq!(std::fs::read_to_string(dir.path().join("terminal.json")).unwrap(), "{\"a\":1}");
+        assert_eq!(std::fs::read_to_string(dir.path().join("network.json")).unwrap(), "{\"proxy\":1}");
+        assert!(!dir.path().parent().unwrap().join("evil").exists());
+    }
+}
@@ -0,0 +1,281 @@
+//! Watchdog for backend commands that block.
+//!
+//! Every IPC call passes through `wrap`, which records when it starts and
+//! finishes. Synchronous commands run on the main thread, so a slow one
+//! freezes the UI; a monitor thread notices while it is still running and
+//! writes it to the app log with its (sanitized) arguments. Async commands
+//! are measured up to the point they hand off to the runtime.
+//!
+//! `get_slow_command_report` returns the commands running over the
+//! threshold right now plus the most recent slow ones.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, Once, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::ipc::{Invoke, InvokeBody};
+use tauri::{Manager, Runtime};
+
+use crate::modules::log::{self as app_log, LogState};
+
+const THRESHOLD: Duration = Duration::from_millis(500);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+const MAX_RECENT: usize = 50;
+const MAX_STRING_CHARS: usize = 80;
+const MAX_ARRAY_ITEMS: usize = 10;
+const MAX_ARGS_CHARS: usize = 1000;
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct SlowCommand {
+    pub command: String,
+    pub window: String,
+    /// JSON arguments with secrets redacted and long values cut.
+    pub args: String,
+    /// Unix time in ms.
+    pub started_at: u64,
+    pub duration_ms: u64,
+    pub finished: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SlowCommandReport {
+    pub threshold_ms: u64,
+    pub running: Vec<SlowCommand>,
+    pub recent: Vec<SlowCommand>,
+}
+
+struct Running {
+    command: String,
+    window: String,
+    args: String,
+    started: Instant,
+    started_at: u64,
+    logged: bool,
+}
+
+impl Running {
+    fn snapshot(&self, now: Instant, finished: bool) -> SlowCommand {
+        SlowCommand {
+            command: self.command.clone(),
+            window: self.window.clone(),
+            args: self.args.clone(),
+            started_at: self.started_at,
+            duration_ms: now.saturating_duration_since(self.started).as_millis() as u64,
+            finished,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Watchdog {
+    next_id: u64,
+    running: HashMap<u64, Running>,
+    recent: VecDeque<SlowCommand>,
+}
+
+impl Watchdog {
+    fn begin(&mut self, command: String, window: String, args: String, now: Instant) -> u64 {
+        self.next_id += 1;
+        let started_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        self.running.insert(self.next_id, Running { command, window, args, started: now, started_at, logged: false });
+        self.next_id
+    }
+
+    fn finish(&mut self, id: u64, now: Instant) {
+        let Some(run) = self.running.remove(&id) else { return };
+        if now.saturating_duration_since(run.started) >= THRESHOLD {
+            if self.recent.len() == MAX_RECENT {
+                self.recent.pop_front();
+            }
+            self.recent.push_back(run.snapshot(now, true));
+        }
+    }
+
+    /// Commands that crossed the threshold since the last call.
+    fn newly_overdue(&mut self, now: Instant) -> Vec<SlowCommand> {
+        self.running
+            .values_mut()
+            .filter(|run| !run.logged && now.saturating_duration_since(run.started) >= THRESHOLD)
+            .map(|run| {
+                run.logged = true;
+                run.snapshot(now, false)
+            })
+            .collect()
+    }
+
+    fn report(&self, now: Instant) -> SlowCommandReport {
+        let mut running: Vec<SlowCommand> = self
+            .running
+            .values()
+            .filter(|run| now.saturating_duration_since(run.started) >= THRESHOLD)
+            .map(|run| run.snapshot(now, false))
+            .collect();
+        running.sort_by_key(|c| std::cmp::Reverse(c.duration_ms));
+        SlowCommandReport {
+            threshold_ms: THRESHOLD.as_millis() as u64,
+            running,
+            recent: self.recent.iter().rev().cloned().collect(),
+        }
+    }
+}
+
+fn watchdog() -> &'static Mutex<Watchdog> {
+    static WATCHDOG: OnceLock<Mutex<Watchdog>> = OnceLock::new();
+    WATCHDOG.get_or_init(|| Mutex::new(Watchdog::default()))
+}
+
+// ── Argument sanitizing ──
+
+fn is_secret_key(key: &str) -> bool {
+    let norm: String = key.to_lowercase().chars().filter(|c| *c != '_' && *c != '-').collect();
+    norm == "key" || app_log::SECRET_PATTERN.iter().any(|p| norm.contains(&p.replace(['_', '-'], "")))
+}
+
+fn sanitize_value(value: &Value) -> Value {
+    match value {
+        Value::String(s) => match s.char_indices().nth(MAX_STRING_CHARS) {
+            Some((i, _)) => Value::String(format!("{}… ({} chars)", &s[..i], s.chars().count())),
+            None => value.clone(),
+        },
+        Value::Array(items) => {
+            let mut out: Vec<Value> = items.iter().take(MAX_ARRAY_ITEMS).map(sanitize_value).collect();
+            if items.len() > MAX_ARRAY_ITEMS {
+                out.push(Value::String(format!("… {} more", items.len() - MAX_ARRAY_ITEMS)));
+            }
+            Value::Array(out)
+        }
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| {
+                    let v = if is_secret_key(k) { Value::String("[redacted]".to_string()) } else { sanitize_value(v) };
+                    (k.clone(), v)
+                })
+                .collect(),
+        ),
+        _ => value.clone(),
+    }
+}
+
+fn sanitize_args(body: &InvokeBody) -> String {
+    let text = match body {
+        InvokeBody::Json(value) => sanitize_value(value).to_string(),
+        InvokeBody::Raw(bytes) => format!("<{} bytes>", bytes.len()),
+    };
+    match text.char_indices().nth(MAX_ARGS_CHARS) {
+        Some((i, _)) => format!("{}…", &text[..i]),
+        None => text,
+    }
+}
+
+// ── Dispatch ──
+
+fn monitor<R: Runtime>(app: tauri::AppHandle<R>) {
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        let overdue = match watchdog().lock() {
+            Ok(mut w) => w.newly_overdue(Instant::now()),
+            Err(_) => return,
+        };
+        for slow in overdue {
+            log::warn!("command {} blocked for over {}ms: {}", slow.command, THRESHOLD.as_millis(), slow.args);
+            if let Some(state) = app.try_state::<LogState>() {
+                let entry = serde_json::json!({
+                    "level": "warn",
+                    "msg": format!("Slow command: {}", slow.command),
+                    "ts": slow.started_at,
+                    "data": serde_json::json!({ "window": slow.window, "args": slow.args }).to_string(),
+                    "err": null,
+                });
+                app_log::append(&state, &entry, true);
+            }
+        }
+    }
+}
+
+/// Wrap the app's invoke handler so every command is timed.
+pub fn wrap<R: Runtime>(
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    let started = Once::new();
+    move |invoke: Invoke<R>| {
+        let webview = invoke.message.webview_ref();
+        started.call_once(|| {
+            let app = webview.app_handle().clone();
+            std::thread::spawn(move || monitor(app));
+        });
+        let command = invoke.message.command().to_string();
+        let window = webview.label().to_string();
+        let args = sanitize_args(invoke.message.payload());
+        let id = watchdog().lock().map(|mut w| w.begin(command, window, args, Instant::now())).ok();
+        let handled = handler(invoke);
+        if let (Some(id), Ok(mut w)) = (id, watchdog().lock()) {
+            w.finish(id, Instant::now());
+        }
+        handled
+    }
+}
+
+/// Commands currently running past the threshold, then the latest slow
+/// ones, newest first.
+#[tauri::command]
+pub fn get_slow_command_report() -> Result<SlowCommandReport, String> {
+    let w = watchdog().lock().map_err(|e| e.to_string())?;
+    Ok(w.report(Instant::now()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_slow_commands() {
+        let mut w = Watchdog::default();
+        let t0 = Instant::now();
+        let fast = w.begin("read_file".into(), "main".into(), "{}".into(), t0);
+        let slow = w.begin("search_project".into(), "main".into(), "{}".into(), t0);
+        w.finish(fast, t0 + Duration::from_millis(20));
+        assert!(w.newly_overdue(t0 + Duration::from_millis(100)).is_empty());
+
+        let later = t0 + Duration::from_millis(800);
+        let overdue = w.newly_overdue(later);
+        assert_eq!(overdue.len(), 1);
+        assert_eq!((overdue[0].command.as_str(), overdue[0].finished), ("search_project", false));
+        assert!(w.newly_overdue(later).is_empty());
+        assert_eq!(w.report(later).running.len(), 1);
+
+        w.finish(slow, t0 + Duration::from_millis(1200));
+        let report = w.report(t0 + Duration::from_millis(1300));
+        assert!(report.running.is_empty());
+        assert_eq!(report.recent.len(), 1);
+        assert_eq!((report.recent[0].duration_ms, report.recent[0].finished), (1200, true));
+    }
+
+    #[test]
+    fn sanitizes_arguments() {
+        let body = InvokeBody::Json(serde_json::json!({
+            "path": "/src/main.rs",
+            "apiKey": "sk-123",
+            "token": "ghp_abc",
+            "passphrase": "correct horse",
+            "target": { "credentials": "user:pw" },
+            "config": { "api_key": "x", "model": "m" },
+            "content": "a".repeat(200),
+            "files": (0..15).collect::<Vec<_>>(),
+        }));
+        let args: Value = serde_json::from_str(&sanitize_args(&body)).unwrap();
+        assert_eq!(args["path"], "/src/main.rs");
+        assert_eq!(args["apiKey"], "[redacted]");
+        assert_eq!(args["token"], "[redacted]");
+        assert_eq!(args["passphrase"], "[redacted]");
+        assert_eq!(args["target"]["credentials"], "[redacted]");
+        assert_eq!(args["config"]["api_key"], "[redacted]");
+        assert_eq!(args["config"]["model"], "m");
+        assert!(args["content"].as_str().unwrap().ends_with("… (200 chars)"));
+        assert_eq!(args["files"].as_array().unwrap().len(), MAX_ARRAY_ITEMS + 1);
+        assert_eq!(sanitize_args(&InvokeBody::Raw(vec![0; 42])), "<42 bytes>");
+    }
+}
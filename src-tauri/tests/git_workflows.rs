@@ -0,0 +1,103 @@
+//! Git workflows run against a scripted `MockRunner`, so they need
+//! neither a repository nor a network.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use app_lib::modules::git;
+use app_lib::modules::process::{with_runner, MockResponse, MockRunner};
+
+const REPO: &str = "/work/repo";
+
+fn args(mock: &MockRunner) -> Vec<String> {
+    mock.calls().iter().map(|c| c.args.join(" ")).collect()
+}
+
+#[test]
+fn status_maps_porcelain_codes() {
+    let mock = Arc::new(MockRunner::new().on(
+        "git",
+        &["status", "--porcelain", "-uall", "-z"],
+        MockResponse::ok(&b" M src/main.rs\0A  new.rs\0?? notes.txt\0UU conflict.rs\0R  moved.rs\0old.rs\0"[..]),
+    ));
+    let status = with_runner(mock.clone(), || git::status_map(Path::new(REPO))).unwrap();
+    let code = |f: &str| status.get(&format!("{REPO}/{f}")).map(String::as_str);
+    assert_eq!(code("src/main.rs"), Some("M"));
+    assert_eq!(code("new.rs"), Some("A"));
+    assert_eq!(code("notes.txt"), Some("U"));
+    assert_eq!(code("conflict.rs"), Some("C"));
+    assert_eq!(code("moved.rs"), Some("A"));
+    assert_eq!(status.len(), 5);
+    assert_eq!(mock.calls()[0].cwd.as_deref(), Some(Path::new(REPO)));
+}
+
+#[test]
+fn status_outside_a_repository_is_empty() {
+    let mock = Arc::new(MockRunner::new().on(
+        "git",
+        &["status", "--porcelain", "-uall", "-z"],
+        MockResponse::fail(128, "fatal: not a git repository"),
+    ));
+    assert!(with_runner(mock, || git::status_map(Path::new(REPO))).unwrap().is_empty());
+}
+
+#[test]
+fn commit_returns_the_short_hash() {
+    let mock = Arc::new(MockRunner::new().on(
+        "git",
+        &["commit", "-m", "Fix parser"],
+        MockResponse::ok("[main 1a2b3c4] Fix parser\n 1 file changed\n"),
+    ));
+    assert_eq!(with_runner(mock, || git::commit(Path::new(REPO), "Fix parser")).unwrap(), "1a2b3c4");
+
+    let mock = Arc::new(MockRunner::new().on(
+        "git",
+        &["commit", "-m", "Empty"],
+        MockResponse::fail(1, "nothing to commit, working tree clean"),
+    ));
+    let err = with_runner(mock, || git::commit(Path::new(REPO), "Empty")).unwrap_err();
+    assert!(err.contains("nothing to commit"));
+}
+
+#[test]
+fn push_sets_upstream_on_first_push() {
+    let mock = Arc::new(
+        MockRunner::new()
+            .on(
+                "git",
+                &["push"],
+                MockResponse::fail(128, "fatal: The current branch feature has no upstream branch."),
+            )
+            .on("git", &["rev-parse", "--abbrev-ref", "HEAD"], MockResponse::ok("feature\n"))
+            .on(
+                "git",
+                &["push", "--set-upstream", "origin", "feature"],
+                MockResponse::ok("").with_stderr("branch 'feature' set up to track 'origin/feature'."),
+            ),
+    );
+    let out = with_runner(mock.clone(), || git::push(Path::new(REPO))).unwrap();
+    assert!(out.contains("set up to track"));
+    assert_eq!(args(&mock), ["push", "rev-parse --abbrev-ref HEAD", "push --set-upstream origin feature"]);
+}
+
+#[test]
+fn push_reports_other_failures_without_retrying() {
+    let mock = Arc::new(MockRunner::new().on("git", &["push"], MockResponse::fail(1, "! [rejected] main -> main (fetch first)")));
+    let err = with_runner(mock.clone(), || git::push(Path::new(REPO))).unwrap_err();
+    assert!(err.contains("rejected"));
+    assert_eq!(args(&mock), ["push"]);
+}
+
+#[test]
+fn delete_branch_refuses_the_current_branch() {
+    let mock = Arc::new(
+        MockRunner::new()
+            .on("git", &["rev-parse", "--abbrev-ref", "HEAD"], MockResponse::ok("main\n"))
+            .on("git", &["branch", "-d", "old"], MockResponse::ok("Deleted branch old (was 1a2b3c4).\n")),
+    );
+    let repo = Path::new(REPO);
+    assert!(with_runner(mock.clone(), || git::delete_branch(repo, "main", false)).is_err());
+    let out = with_runner(mock.clone(), || git::delete_branch(repo, "old", false)).unwrap();
+    assert!(out.starts_with("Deleted branch old"));
+    assert_eq!(args(&mock), ["rev-parse --abbrev-ref HEAD", "rev-parse --abbrev-ref HEAD", "branch -d old"]);
+}
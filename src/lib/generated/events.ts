@@ -0,0 +1,184 @@
+// Generated from src-tauri/src/modules/events/mod.rs; do not edit.
+// Regenerate with `UPDATE_IPC_BINDINGS=1 cargo test events`.
+
+export const IPC_VERSION = 1;
+
+export type ActivityKind = "bell" | "activity" | "silence";
+
+export type AgentStep = { run_id: number, 
+/**
+ * 1-based.
+ */
+step: number, kind: AgentStepKind, 
+/**
+ * The model's reason for the step, or what went wrong with it.
+ */
+summary: string, path: string | null, 
+/**
+ * Unified diff of an edit.
+ */
+diff: string | null, 
+/**
+ * The edit waits for `ai_agent_respond`.
+ */
+awaiting_approval: boolean, 
+/**
+ * Whether the edit was approved, once decided.
+ */
+approved: boolean | null, check: CheckResult | null, 
+/**
+ * The step has finished.
+ */
+finished: boolean, };
+
+export type AgentStepKind = "read" | "edit" | "check" | "done" | "invalid";
+
+export type CheckFailure = { path: string, line: number, column: number | null, message: string, };
+
+export type CheckResult = { command: string, 
+/**
+ * `None` when killed by a signal.
+ */
+exit_code: number | null, passed: boolean, failures: Array<CheckFailure>, 
+/**
+ * The last lines of stdout and stderr.
+ */
+output_tail: string, };
+
+export type Citation = { path: string, start_line: number, end_line: number, score: number, };
+
+export type CompressProgress = { output_path: string, files_done: number, files_total: number, bytes_done: number, bytes_total: number, 
+/**
+ * Archive name of the file being added.
+ */
+current: string, };
+
+export type DeleteProgress = { operation_id: string, 
+/**
+ * Files, links and directories removed so far.
+ */
+deleted: number, total: number, 
+/**
+ * The entry being removed when the event was sent.
+ */
+current: string | null, done: boolean, cancelled: boolean, error: string | null, };
+
+export type FileChunk = { request_id: string, index: number, 
+/**
+ * Byte offset of `text` in the file.
+ */
+start_byte: number, 
+/**
+ * Byte offset just past this chunk; pass to `read_file_window` to
+ * continue from here.
+ */
+next_byte: number, 
+/**
+ * 0-based line number of the first line in `text`, counted from the
+ * start of the read.
+ */
+start_line: number, text: string, 
+/**
+ * The chunk ends mid-line because the line exceeds the chunk size.
+ */
+partial_line: boolean, done: boolean, error: string | null, };
+
+export type GcProgress = { phase: string, percent: number | null, line: string, };
+
+export type IgnoredFilesChanged = { 
+/**
+ * The repo as passed to `watch_git_ignored`.
+ */
+repo_path: string, ignored: Array<string>, };
+
+export type IndexProgress = { done: number, total: number, };
+
+export type JsonValue = number | string | boolean | Array<JsonValue> | { [key in string]?: JsonValue } | null;
+
+export type LogLevel = "trace" | "debug" | "info" | "warn" | "error";
+
+export type LogLine = { 
+/**
+ * Byte offset of the line in the file.
+ */
+offset: number, text: string, level: LogLevel | null, timestamp: string | null, };
+
+export type OperationEvent = { state: OperationState, error: string | null, id: number, kind: OperationKind, 
+/**
+ * What is being worked on, e.g. the repository being cloned.
+ */
+title: string, 
+/**
+ * Label of the window that started it; `None` for app-wide work.
+ */
+window: string | null, 
+/**
+ * Units done so far (files, objects, bytes — whatever the kind counts).
+ */
+done: number, 
+/**
+ * Units in all, once known.
+ */
+total: number | null, 
+/**
+ * The current step, e.g. the file being copied.
+ */
+message: string | null, cancellable: boolean, 
+/**
+ * Unix milliseconds.
+ */
+started_at: number, };
+
+export type OperationKind = "clone" | "search" | "index" | "copy" | "archive" | "ai";
+
+export type OperationState = "running" | "done" | "failed" | "cancelled";
+
+export type StreamChunk = { session_id: string, delta: string, done: boolean, tool_calls?: JsonValue, 
+/**
+ * Retrieved chunks used for this turn; sent once, on the final chunk.
+ */
+citations?: Array<Citation>, };
+
+export type TailEvent = { tail_id: string, lines: Array<LogLine>, 
+/**
+ * The file shrank and is being read again from the start; the viewer
+ * should clear what it has.
+ */
+reset: boolean, done: boolean, error: string | null, };
+
+export type TerminalActivity = { kind: ActivityKind, 
+/**
+ * How long the terminal was quiet (`activity`) or busy (`silence`).
+ */
+duration_ms: number | null, };
+
+export type TerminalTitle = { source: TitleSource, title: string, 
+/**
+ * Foreground process id, for `Process` titles.
+ */
+pid: number | null, };
+
+export type TitleSource = "osc" | "process";
+
+/** Payload of each event, by name. */
+export interface EventPayloads {
+  'agent-step': AgentStep;
+  'ai-stream-chunk': StreamChunk;
+  'compress-progress': CompressProgress;
+  'delete-progress': DeleteProgress;
+  'file-chunk': FileChunk;
+  'git-gc-progress': GcProgress;
+  'ignored-files-changed': IgnoredFilesChanged;
+  'indexing-progress': IndexProgress;
+  'operation-progress': OperationEvent;
+  'semantic-indexing-progress': IndexProgress;
+  'tail-lines': TailEvent;
+}
+
+/** Payload of each scoped event, by the name before `-{id}`. */
+export interface ScopedEventPayloads {
+  'terminal-activity': TerminalActivity;
+  'terminal-exit': null;
+  'terminal-output': string;
+  'terminal-title': TerminalTitle;
+}
@@ -7,7 +7,6 @@
  */
 import { get, writable } from 'svelte/store';
 import { invoke } from '@tauri-apps/api/core';
-import { listen } from '@tauri-apps/api/event';
 import { chatMessages, aiProvider, aiModel, isStreaming, type ChatMessage, scheduleSaveConversation } from './ai';
 import { projectRoot } from '../git/git';
 import { buildProjectContext } from './contextBuilder';
@@ -19,6 +18,7 @@ import { currentPlan, createPlan, approvePlan, updateStepStatus, parsePlanSteps,
 import { createCheckpoint } from './checkpoints';
 import { runVerify, formatVerifyErrors } from './selfVerify';
 import { log } from '../logging';
+import { listenEvent } from '../ipc';
 
 // ── Agent state ──
 
@@ -354,12 +354,7 @@ async function streamAgentTurn(
   chatMessages.update(msgs => [...msgs, { role: 'assistant', content: '' }]);
 
   let unlisten: (() => void) | null = null;
-  unlisten = await listen<{
-    session_id: string;
-    delta: string;
-    done: boolean;
-    tool_calls?: ToolCall[];
-  }>('ai-stream-chunk', (event) => {
+  unlisten = await listenEvent('ai-stream-chunk', (event) => {
     if (event.payload.session_id !== sessionId) return;
 
     if (event.payload.done) {
@@ -370,7 +365,8 @@ async function streamAgentTurn(
 
     // Accumulate tool calls if present
     if (event.payload.tool_calls) {
-      for (const tc of event.payload.tool_calls) {
+      // Provider tool calls, already normalized to the OpenAI shape.
+      for (const tc of event.payload.tool_calls as unknown as ToolCall[]) {
         const existing = toolCalls.find(t => t.id === tc.id);
         if (existing) {
           existing.function.arguments += tc.function.arguments;
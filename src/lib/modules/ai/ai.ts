@@ -1,6 +1,5 @@
 import { writable, get } from 'svelte/store';
 import { invoke } from '@tauri-apps/api/core';
-import { listen } from '@tauri-apps/api/event';
 import { persistedString } from '../session/persisted';
 import { parseAiEdits, hasEdits } from './editParser';
 import { EDIT_SYSTEM_PROMPT } from './systemPrompts';
@@ -10,6 +9,7 @@ import { activeFilePath, getFileContent } from '../explorer/files';
 import { projectRoot } from '../git/git';
 import { log } from '../logging';
 import { isTerminalPath, isPreviewPath, isDiagramPath } from '../terminal/shell';
+import { listenEvent } from '../ipc';
 
 export interface ChatMessage {
   role: 'user' | 'assistant' | 'system' | 'tool';
@@ -156,7 +156,7 @@ export async function sendStreamingMessage(userContent: string, fileContexts?: {
 
   // Listen for stream chunks
   if (streamUnlisten) { streamUnlisten(); streamUnlisten = null; }
-  streamUnlisten = await listen<{ session_id: string; delta: string; done: boolean }>('ai-stream-chunk', (event) => {
+  streamUnlisten = await listenEvent('ai-stream-chunk', (event) => {
     if (event.payload.session_id !== sessionId) return;
 
     if (event.payload.done) {
@@ -1,5 +1,6 @@
 export * from './ai';
 export * from './agentLoop';
+export * from './workspaceAgent';
 export * from './chatRenderer';
 export * from './commandParser';
 export * from './editParser';
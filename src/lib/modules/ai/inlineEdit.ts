@@ -8,10 +8,10 @@
  */
 import { get, writable } from 'svelte/store';
 import { invoke } from '@tauri-apps/api/core';
-import { listen } from '@tauri-apps/api/event';
 import { aiProvider, aiModel } from './ai';
 import { editModel } from '../settings/settings';
 import { activeFilePath } from '../explorer/files';
+import { listenEvent } from '../ipc';
 
 // ── Types ──
 
@@ -79,7 +79,7 @@ export async function startInlineEdit(request: InlineEditRequest): Promise<Inlin
 
   if (streamUnlisten) { streamUnlisten(); streamUnlisten = null; }
 
-  streamUnlisten = await listen<{ session_id: string; delta: string; done: boolean }>(
+  streamUnlisten = await listenEvent(
     'ai-stream-chunk',
     (event) => {
       if (event.payload.session_id !== sessionId) return;
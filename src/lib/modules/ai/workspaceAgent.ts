@@ -0,0 +1,105 @@
+/**
+ * Workspace agent — drives the backend `ai_run_agent` loop.
+ *
+ * The backend does the work: it asks the model for one action at a time,
+ * proposes file edits, runs the project's check command and feeds the
+ * failures back. This module collects its `agent-step` events into a
+ * store for the panel, answers edit approvals, and cancels a run through
+ * the operations manager (the run id is the operation id).
+ */
+import { get, writable } from 'svelte/store';
+import { invoke } from '@tauri-apps/api/core';
+import { aiProvider, aiModel } from './ai';
+import { listenEvent } from '../ipc';
+import { cancelOperation } from '../operations';
+import type { AgentStep } from '../../generated/events';
+
+export type { AgentStep, AgentStepKind, CheckResult, CheckFailure } from '../../generated/events';
+
+// ── Types ──
+
+export type AgentOutcome = 'done' | 'step_budget' | 'time_budget' | 'cancelled';
+
+export interface AgentRun {
+  run_id: number;
+  outcome: AgentOutcome;
+  summary: string;
+  steps: number;
+  edits_applied: number;
+  last_check: AgentStep['check'];
+}
+
+export interface WorkspaceAgentState {
+  goal: string;
+  runId: number | null;
+  running: boolean;
+  /** Latest event for each step, in step order. */
+  steps: AgentStep[];
+  result: AgentRun | null;
+  error: string | null;
+}
+
+// ── Store ──
+
+export const workspaceAgent = writable<WorkspaceAgentState | null>(null);
+
+function recordStep(step: AgentStep) {
+  workspaceAgent.update((s) => {
+    if (!s || !s.running || (s.runId !== null && s.runId !== step.run_id)) return s;
+    const steps = s.steps.filter((x) => x.step !== step.step);
+    steps.push(step);
+    steps.sort((a, b) => a.step - b.step);
+    return { ...s, runId: step.run_id, steps };
+  });
+}
+
+// ── Actions ──
+
+/**
+ * Run the agent toward `goal` in the current project. Resolves with the
+ * backend's summary once the run ends; steps arrive in `workspaceAgent`
+ * as they happen.
+ */
+export async function runWorkspaceAgent(
+  goal: string,
+  options: { maxSteps?: number; maxSeconds?: number; checkCommand?: string } = {},
+): Promise<AgentRun | null> {
+  if (get(workspaceAgent)?.running) return null;
+  workspaceAgent.set({ goal, runId: null, running: true, steps: [], result: null, error: null });
+
+  // Listen before invoking so the first step isn't missed.
+  const unlisten = await listenEvent('agent-step', (e) => recordStep(e.payload));
+  try {
+    const result = await invoke<AgentRun>('ai_run_agent', {
+      goal,
+      maxSteps: options.maxSteps ?? null,
+      maxSeconds: options.maxSeconds ?? null,
+      checkCommand: options.checkCommand ?? null,
+      provider: get(aiProvider),
+      model: get(aiModel),
+    });
+    workspaceAgent.update((s) => s && { ...s, runId: result.run_id, running: false, result });
+    return result;
+  } catch (e) {
+    workspaceAgent.update((s) => s && { ...s, running: false, error: String(e) });
+    return null;
+  } finally {
+    unlisten();
+  }
+}
+
+/** Approve or reject the edit the run is waiting on. */
+export async function respondToAgentEdit(runId: number, approve: boolean): Promise<boolean> {
+  return await invoke<boolean>('ai_agent_respond', { runId, approve });
+}
+
+/** Stop the running agent; a check in progress is killed. */
+export async function stopWorkspaceAgent(): Promise<void> {
+  const runId = get(workspaceAgent)?.runId;
+  if (runId != null) await cancelOperation(runId);
+}
+
+/** Clear a finished run from the panel. */
+export function dismissWorkspaceAgent() {
+  workspaceAgent.update((s) => (s?.running ? s : null));
+}
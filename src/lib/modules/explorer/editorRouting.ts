@@ -0,0 +1,36 @@
+/**
+ * Editor routing — which editor or viewer opens a file. The backend owns
+ * the glob → editor rules (per user and per project) and applies them in
+ * `detect_file_type`; this module asks it and edits the rules.
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+export type EditorKind = 'text' | 'hex' | 'image' | 'table' | 'notebook' | 'markdown-preview';
+
+export interface RoutingRule {
+  /** Gitignore-style glob, relative to the project root. */
+  glob: string;
+  editor: EditorKind;
+}
+
+export interface EditorRouting {
+  project: RoutingRule[];
+  user: RoutingRule[];
+  /** Built in, tried after the project's and the user's rules. */
+  defaults: RoutingRule[];
+}
+
+/** The editor the routing rules (or, failing those, the file's type) pick for `path`. */
+export async function editorKindFor(path: string): Promise<EditorKind> {
+  const { editor } = await invoke<{ editor: EditorKind }>('detect_file_type', { path });
+  return editor;
+}
+
+export async function getEditorRouting(): Promise<EditorRouting> {
+  return await invoke<EditorRouting>('get_editor_routing');
+}
+
+/** Replace the project's rules, or the user's when `project` is false. */
+export async function setEditorRouting(rules: RoutingRule[], project: boolean): Promise<EditorRouting> {
+  return await invoke<EditorRouting>('set_editor_routing', { rules, project });
+}
@@ -28,6 +28,17 @@ export const maxTabs = persistedNumber('leo-max-tabs', 9);
  *  Synced by FileTree.svelte; read by session.ts for persistence. */
 export const expandedDirsStore = writable<Set<string>>(new Set());
 
+export interface CursorPosition {
+  /** 1-based. */
+  line: number;
+  /** 1-based. */
+  column: number;
+}
+
+/** Last cursor seen in each file, kept by the editor. Seeded by a session
+ * handoff so freshly opened files start where they were left. */
+export const cursorPositions = new Map<string, CursorPosition>();
+
 export const pinnedFiles = derived(openFiles, files => files.filter(f => f.pinned));
 export const unpinnedFiles = derived(openFiles, files => files.filter(f => !f.pinned));
 
@@ -0,0 +1,45 @@
+/**
+ * Suggested `.gitignore` for projects opened without one. The backend
+ * picks templates from the project's manifests; the file tree shows the
+ * result as a banner the user can preview, accept or dismiss. Dismissals
+ * are remembered per project.
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+export type GitignoreStack = 'rust' | 'node' | 'python' | 'macos' | 'ide';
+
+export interface GitignoreSuggestion {
+  stack: GitignoreStack[];
+  content: string;
+  /** The project already has a `.gitignore`. */
+  exists: boolean;
+}
+
+const DISMISSED_KEY = 'leo-gitignore-dismissed';
+
+function dismissedRoots(): string[] {
+  try {
+    return JSON.parse(localStorage.getItem(DISMISSED_KEY) ?? '[]');
+  } catch {
+    return [];
+  }
+}
+
+/** A `.gitignore` for the current project, or for `stack` when given. */
+export async function suggestGitignore(stack?: GitignoreStack[]): Promise<GitignoreSuggestion> {
+  return await invoke<GitignoreSuggestion>('suggest_gitignore', { detectedStack: stack ?? null });
+}
+
+/** Create the project's `.gitignore`; fails if one already exists. */
+export async function writeGitignore(content: string): Promise<void> {
+  await invoke('write_gitignore', { content });
+}
+
+export function isGitignoreDismissed(root: string): boolean {
+  return dismissedRoots().includes(root);
+}
+
+export function dismissGitignore(root: string) {
+  const roots = dismissedRoots().filter((r) => r !== root);
+  localStorage.setItem(DISMISSED_KEY, JSON.stringify([...roots, root].slice(-100)));
+}
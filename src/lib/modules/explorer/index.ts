@@ -1,2 +1,4 @@
 export * from './files';
 export * from './fileIcons';
+export * from './gitignore';
+export * from './editorRouting';
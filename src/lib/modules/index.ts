@@ -12,3 +12,5 @@ export * from './settingsSync';
 export * from './logging';
 export * from './layout';
 export * from './preview';
+export * from './operations';
+export * from './ipc';
@@ -0,0 +1,45 @@
+/**
+ * Typed access to the events the backend sends, as declared in the Rust
+ * `events` module. Names and payload types come from the generated
+ * `lib/generated/events.ts`, so listening for an event that no longer
+ * exists, or reading a field it no longer has, fails type checking
+ * instead of silently receiving nothing.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type EventCallback, type UnlistenFn } from '@tauri-apps/api/event';
+import { IPC_VERSION, type EventPayloads, type ScopedEventPayloads } from '../../generated/events';
+import { log } from '../logging';
+import { showToast } from '../ui/toast';
+
+/** Listen for a backend event by name. */
+export function listenEvent<K extends keyof EventPayloads>(
+  name: K,
+  handler: EventCallback<EventPayloads[K]>,
+): Promise<UnlistenFn> {
+  return listen<EventPayloads[K]>(name, handler);
+}
+
+/** Listen for one instance of a scoped event, e.g. `terminal-output` of session 3. */
+export function listenScoped<K extends keyof ScopedEventPayloads>(
+  name: K,
+  scope: string | number,
+  handler: EventCallback<ScopedEventPayloads[K]>,
+): Promise<UnlistenFn> {
+  return listen<ScopedEventPayloads[K]>(`${name}-${scope}`, handler);
+}
+
+/**
+ * Compare the frontend's `IPC_VERSION` with the backend's and warn when
+ * they differ, which means one side was rebuilt without the other.
+ */
+export async function checkIpcVersion(): Promise<boolean> {
+  const backend = await invoke<number>('ipc_version');
+  if (backend === IPC_VERSION) return true;
+  log.warn(`IPC version mismatch: frontend ${IPC_VERSION}, backend ${backend}`);
+  showToast({
+    level: 'warn',
+    message: 'The app\'s frontend and backend are out of sync; some features may not work. Rebuild or reinstall the app.',
+  });
+  return false;
+}
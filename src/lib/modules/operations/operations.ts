@@ -0,0 +1,44 @@
+/**
+ * Background operations: clones, searches, indexing, copies, archive
+ * exports and AI requests, as tracked by the Rust `operations` module.
+ *
+ * `operations` holds the ones still running that concern this window (its
+ * own plus app-wide ones). Call `watchOperations()` once at startup.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { UnlistenFn } from '@tauri-apps/api/event';
+import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow';
+import { writable } from 'svelte/store';
+import type { OperationInfo } from '../../generated/events';
+import { listenEvent } from '../ipc';
+
+export type { OperationKind, OperationState, OperationInfo, OperationEvent } from '../../generated/events';
+
+export const operations = writable<OperationInfo[]>([]);
+
+function concernsThisWindow(op: OperationInfo): boolean {
+  return op.window === null || op.window === getCurrentWebviewWindow().label;
+}
+
+let unlisten: UnlistenFn | null = null;
+
+/** Start following operations. Safe to call more than once. */
+export async function watchOperations(): Promise<void> {
+  if (unlisten) return;
+  unlisten = await listenEvent('operation-progress', ({ payload }) => {
+    if (!concernsThisWindow(payload)) return;
+    const { state, error, ...info } = payload;
+    operations.update(list => {
+      const rest = list.filter(op => op.id !== info.id);
+      return state === 'running' ? [...rest, info].sort((a, b) => a.id - b.id) : rest;
+    });
+  });
+  const current = await invoke<OperationInfo[]>('list_operations');
+  operations.set(current.filter(concernsThisWindow));
+}
+
+/** Ask an operation to stop. Resolves false if it already ended. */
+export async function cancelOperation(id: number): Promise<boolean> {
+  return await invoke<boolean>('cancel_operation', { id });
+}
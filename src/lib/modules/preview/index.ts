@@ -1 +1,2 @@
 export * from './allowList';
+export * from './share';
@@ -0,0 +1,30 @@
+/**
+ * Read-only sharing of the open project over the local network. The
+ * backend serves a browse/search page behind a short-lived token; the
+ * toolbar shows the link and its QR code to hand to a colleague.
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+export interface ShareInfo {
+  /** The link to hand out, token included. */
+  url: string;
+  token: string;
+  /** Seconds since the epoch. */
+  expires_at: number;
+  qr_svg: string;
+  root: string;
+}
+
+/** Share the project for `minutes` (30 by default); replaces any running share. */
+export async function shareReadonly(minutes?: number): Promise<ShareInfo> {
+  return await invoke<ShareInfo>('share_readonly', { minutes: minutes ?? null });
+}
+
+export async function stopShareReadonly(): Promise<boolean> {
+  return await invoke<boolean>('stop_share_readonly');
+}
+
+/** The window's running share, if it hasn't expired. */
+export async function getShareReadonly(): Promise<ShareInfo | null> {
+  return await invoke<ShareInfo | null>('get_share_readonly');
+}
@@ -1,8 +1,9 @@
 import { invoke } from '@tauri-apps/api/core';
 import { get } from 'svelte/store';
-import { openFiles, activeFilePath, maxRecentProjects, expandedDirsStore } from '../explorer/files';
+import { tick } from 'svelte';
+import { openFiles, activeFilePath, maxRecentProjects, expandedDirsStore, cursorPositions, addFile, togglePin, type CursorPosition } from '../explorer/files';
 import { projectRoot } from '../git/git';
-import { terminalTabs, showTerminal } from '../terminal/shell';
+import { terminalTabs, showTerminal, createTerminalSignal } from '../terminal/shell';
 import { log } from '../logging';
 
 export interface SessionFile {
@@ -23,6 +24,29 @@ export interface RecentProject {
   name: string;
   last_opened: number;
   session: SessionData;
+  group: string | null;
+}
+
+export interface ProjectGroup {
+  id: string;
+  name: string;
+}
+
+export interface ProjectOrganization {
+  groups: ProjectGroup[];
+  pinned: string[];
+}
+
+/** A handoff bundle mapped onto this machine's checkout of the project. */
+export interface SessionHandoff {
+  project_name: string;
+  exported_at: number;
+  session: SessionData;
+  cursors: Record<string, CursorPosition>;
+  terminal_cwds: string[];
+  scratchpads: { name: string; content: string }[];
+  /** Files open in the bundle that don't exist here. */
+  missing_files: string[];
 }
 
 export async function getRecentProjects(): Promise<RecentProject[]> {
@@ -38,6 +62,81 @@ export async function removeRecentProject(path: string): Promise<void> {
   return invoke('remove_recent_project', { projectPath: path });
 }
 
+export async function getProjectGroups(): Promise<ProjectOrganization> {
+  return invoke<ProjectOrganization>('get_project_groups');
+}
+
+export async function createProjectGroup(name: string): Promise<ProjectGroup> {
+  return invoke<ProjectGroup>('create_project_group', { name });
+}
+
+export async function deleteProjectGroup(groupId: string): Promise<void> {
+  return invoke('delete_project_group', { groupId });
+}
+
+export async function assignProjectToGroup(path: string, groupId: string | null): Promise<void> {
+  return invoke('assign_project_to_group', { projectPath: path, groupId });
+}
+
+export async function setPinned(path: string, pinned: boolean, index?: number): Promise<void> {
+  return invoke('set_pinned', { projectPath: path, pinned, index: index ?? null });
+}
+
+/**
+ * How saved sessions are pruned: after `max_age_days` unopened (0 = never),
+ * to `max_expanded_dirs` folders, and to the `max_sessions` most recently
+ * opened projects (0 = all; only the default policy's value applies).
+ * Projects stay on the recent list.
+ */
+export interface SessionPolicy {
+  max_age_days: number;
+  max_expanded_dirs: number;
+  max_sessions: number;
+}
+
+export interface ProjectStorage {
+  path: string;
+  bytes: number;
+  open_files: number;
+  expanded_dirs: number;
+  last_opened: number;
+  policy: SessionPolicy;
+  custom_policy: boolean;
+}
+
+export interface StateStorageReport {
+  path: string;
+  file_bytes: number;
+  state_bytes: number;
+  /** Largest first. */
+  projects: ProjectStorage[];
+  default_policy: SessionPolicy;
+  /** Corrupt state files that were moved aside. */
+  backups: string[];
+}
+
+export async function getStateStorageReport(): Promise<StateStorageReport> {
+  return invoke<StateStorageReport>('get_state_storage_report');
+}
+
+/**
+ * Set the default session policy, or one project's when `path` is given.
+ * `null` restores the built-in default, or makes the project follow the
+ * default again.
+ */
+export async function setSessionPolicy(path: string | null, policy: SessionPolicy | null): Promise<StateStorageReport> {
+  return invoke<StateStorageReport>('set_session_policy', { projectPath: path, policy });
+}
+
+/** Recent projects with pinned ones first, in their pinned order. */
+export function orderRecentProjects(projects: RecentProject[], pinned: string[]): RecentProject[] {
+  const rank = (p: RecentProject) => {
+    const i = pinned.indexOf(p.path);
+    return i === -1 ? pinned.length : i;
+  };
+  return [...projects].sort((a, b) => rank(a) - rank(b));
+}
+
 export function buildSessionData(): SessionData {
   const files = get(openFiles);
   const active = get(activeFilePath);
@@ -77,3 +176,31 @@ export async function saveSessionNow(projectPath: string): Promise<void> {
   const maxRecent = get(maxRecentProjects);
   await invoke('save_session', { projectPath, session, maxRecent });
 }
+
+/** Write a handoff bundle of the current session (tabs, cursors, terminal
+ * directories — not file contents) to `path`. */
+export async function exportSessionBundle(path: string): Promise<void> {
+  const project = get(projectRoot);
+  if (!project) throw new Error('No project is open');
+  const snapshot = { session: buildSessionData(), cursors: Object.fromEntries(cursorPositions) };
+  await invoke('export_session_bundle', { project, snapshot, path });
+}
+
+/** Reopen the tabs and terminals of a handoff bundle in the current project. */
+export async function importSessionBundle(path: string): Promise<SessionHandoff> {
+  const handoff = await invoke<SessionHandoff>('import_session_bundle', { path });
+  for (const [file, cursor] of Object.entries(handoff.cursors)) cursorPositions.set(file, cursor);
+  for (const file of handoff.session.open_files) {
+    addFile(file.path, file.path.split(/[/\\]/).pop() || file.path);
+    const open = get(openFiles).find(f => f.path === file.path);
+    if (open && open.pinned !== file.pinned) togglePin(file.path);
+  }
+  if (handoff.session.active_file) activeFilePath.set(handoff.session.active_file);
+  if (handoff.session.terminal_visible) showTerminal.set(true);
+  for (const cwd of handoff.terminal_cwds) {
+    // One signal per flush, or the terminal panel only sees the last.
+    createTerminalSignal.update(s => ({ count: s.count + 1, forceNew: true, cwd }));
+    await tick();
+  }
+  return handoff;
+}
@@ -25,6 +25,11 @@ hiddenPatterns.subscribe(patterns => {
   localStorage.setItem('leo-hidden-patterns', JSON.stringify(patterns));
 });
 
+/** Dotfiles (Unix) and hidden-attribute entries (Windows) in the explorer. */
+export const showHiddenFiles = persistedBoolDefaultTrue('leo-show-hidden-files');
+/** Expand, list and copy through symlinks that resolve inside the project. */
+export const followSymlinks = persistedBool('leo-follow-symlinks', false);
+
 // AI preferences
 export const ghostTextEnabled = persistedBool('leo-ghost-text-enabled', true);
 export const ghostTextDelay = persistedNumber('leo-ghost-text-delay', 450);
@@ -1,5 +1,6 @@
 // ── Cross-window settings sync ───────────────────────────────────
-import { autosaveEnabled, autosaveDelay, editorFontSize, editorTabSize, editorWordWrap, editorLineNumbers, editorShowErrorLens, editorVimMode, terminalFontSize, hiddenPatterns, ghostTextModel, editModel } from './settings/settings';
+import { invoke } from '@tauri-apps/api/core';
+import { autosaveEnabled, autosaveDelay, editorFontSize, editorTabSize, editorWordWrap, editorLineNumbers, editorShowErrorLens, editorVimMode, terminalFontSize, hiddenPatterns, showHiddenFiles, followSymlinks, ghostTextModel, editModel } from './settings/settings';
 import { appearanceMode, editorTheme, uiFontSize, uiDensity } from './ui/ui';
 import { maxRecentProjects, maxTabs } from './explorer/files';
 import { aiProvider, aiModel, type AiProvider } from './ai/ai';
@@ -23,6 +24,8 @@ const SETTINGS_SYNC: Record<string, { set: (v: string | null) => void }> = {
   'leo-ui-font-size':        { set: v => uiFontSize.set(parseInt(v || '13', 10)) },
   'leo-ui-density':          { set: v => uiDensity.set((v as 'compact' | 'comfortable') || 'comfortable') },
   'leo-hidden-patterns':     { set: v => { try { hiddenPatterns.set(JSON.parse(v || '[]')); } catch { /* ignore */ } } },
+  'leo-show-hidden-files':   { set: v => showHiddenFiles.set(v !== 'false') },
+  'leo-follow-symlinks':     { set: v => followSymlinks.set(v === 'true') },
   'leo-max-recent-projects': { set: v => maxRecentProjects.set(parseInt(v || '3', 10)) },
   'leo-max-tabs':            { set: v => maxTabs.set(parseInt(v || '9', 10)) },
   'leo-ai-provider':         { set: v => aiProvider.set((v as AiProvider) || 'openrouter') },
@@ -38,3 +41,38 @@ if (typeof window !== 'undefined') {
     if (entry) entry.set(e.newValue);
   });
 }
+
+// ── Sync between machines ────────────────────────────────────────
+
+export interface SettingsSyncResult {
+  prefs: Record<string, string>;
+  pulled: string[];
+  pushed: boolean;
+  conflicts: string[];
+}
+
+/** Merge this machine's `leo-*` preferences with the configured sync
+ * target and apply what came back. Reloads when a preference without a
+ * live setter (e.g. keybindings) changed. */
+export async function syncSettingsNow(): Promise<SettingsSyncResult> {
+  const prefs: Record<string, string> = {};
+  for (let i = 0; i < localStorage.length; i++) {
+    const k = localStorage.key(i);
+    if (k?.startsWith('leo-')) prefs[k] = localStorage.getItem(k) ?? '';
+  }
+  const result = await invoke<SettingsSyncResult>('sync_now', { prefs });
+  let needsReload = false;
+  const keys = new Set([...Object.keys(prefs), ...Object.keys(result.prefs)]);
+  for (const k of keys) {
+    if (k === 'leo-api-key' || k === 'leo-project-root') continue;
+    const v = result.prefs[k] ?? null;
+    if (v === (prefs[k] ?? null)) continue;
+    if (v === null) localStorage.removeItem(k);
+    else localStorage.setItem(k, v);
+    const entry = SETTINGS_SYNC[k];
+    if (entry) entry.set(v);
+    else needsReload = true;
+  }
+  if (needsReload) location.reload();
+  return result;
+}
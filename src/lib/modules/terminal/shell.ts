@@ -95,8 +95,15 @@ export const terminalSessions = writable<TerminalSessionInfo[]>([]);
 export interface TerminalTabInfo {
   id: number;
   name: string;
+  /** Latest `terminal-title-{id}` from one of the tab's panes, e.g. "npm run dev". */
+  title?: string;
 }
 
+/** Payloads of `terminal-activity-{id}` (a bell, output after quiet, or
+ *  quiet after a long busy stretch) and `terminal-title-{id}` (an OSC 0/2
+ *  title or the foreground process). */
+export type { TerminalActivity, TerminalTitle } from '../../generated/events';
+
 /** Ordered list of open terminal tabs. Each tab holds 1..N panes. */
 export const terminalTabs = writable<TerminalTabInfo[]>([]);
 
@@ -132,6 +139,8 @@ export const createTerminalSignal = writable<{
   count: number;
   /** When true, always create a NEW tab; when false, only create if none exist. */
   forceNew: boolean;
+  /** Directory for a new tab's shell; the project root by default. */
+  cwd?: string;
 }>({ count: 0, forceNew: false });
 
 /** Kill a specific pane by id, all panes in a tab, or everything. */
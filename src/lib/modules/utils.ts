@@ -4,3 +4,16 @@ import { twMerge } from 'tailwind-merge';
 export function cn(...inputs: ClassValue[]) {
   return twMerge(clsx(inputs));
 }
+
+/** A backend error localized by the `i18n` module: message ID, its arguments and the rendered text. */
+export interface LocalizedError {
+  id: string;
+  args: Record<string, string>;
+  message: string;
+}
+
+/** Text for an error rejected by `invoke`, whether the command returned a plain string or a `LocalizedError`. */
+export function errorMessage(e: unknown): string {
+  if (e && typeof e === 'object' && 'message' in e) return String((e as LocalizedError).message);
+  return String(e);
+}
@@ -0,0 +1,27 @@
+/**
+ * Rasterize an SVG (e.g. from `render_code_image`) into a PNG.
+ *
+ * The backend has no font rasterizer, so PNGs are drawn here: the SVG
+ * is loaded as an image and painted onto a canvas at `scale`× for
+ * sharp text on high-DPI screens.
+ */
+export async function svgToPng(svg: string, width: number, height: number, scale = 2): Promise<Blob> {
+  const url = URL.createObjectURL(new Blob([svg], { type: 'image/svg+xml' }));
+  try {
+    const img = new Image();
+    img.src = url;
+    await img.decode();
+    const canvas = document.createElement('canvas');
+    canvas.width = Math.round(width * scale);
+    canvas.height = Math.round(height * scale);
+    const ctx = canvas.getContext('2d');
+    if (!ctx) throw new Error('Canvas is not available');
+    ctx.scale(scale, scale);
+    ctx.drawImage(img, 0, 0, width, height);
+    return await new Promise((resolve, reject) => {
+      canvas.toBlob(blob => (blob ? resolve(blob) : reject(new Error('PNG encoding failed'))), 'image/png');
+    });
+  } finally {
+    URL.revokeObjectURL(url);
+  }
+}